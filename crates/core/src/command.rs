@@ -12,6 +12,21 @@ pub enum Error {
     #[error("{0}")]
     User(String),
 
+    /// Generation found nothing to pick from: the user has neither recipes of
+    /// their own nor saved favorites.
+    #[error("Add a recipe or save one from the community to plan your meals")]
+    NoFavorites,
+
+    /// An import matched one of the user's recipes, whose id it carries.
+    /// Importing again with `force` creates the copy anyway.
+    #[error("You already have this recipe")]
+    PossibleDuplicate(String),
+
+    /// Too many contact form submissions from the same address or IP, see
+    /// [`crate::contact::SUBMISSION_LIMIT_PER_EMAIL`].
+    #[error("Too many messages sent, please try again later")]
+    RateLimited,
+
     #[error("{0}")]
     Server(#[from] anyhow::Error),
 }
@@ -48,6 +63,12 @@ impl From<std::time::SystemTimeError> for Error {
     }
 }
 
+impl From<serde_json::Error> for Error {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Server(value.into())
+    }
+}
+
 impl From<std::num::TryFromIntError> for Error {
     fn from(value: std::num::TryFromIntError) -> Self {
         Self::Server(value.into())
@@ -14,7 +14,7 @@ use sqlx::prelude::FromRow;
 use strum::{AsRefStr, Display, EnumString, VariantArray};
 
 use imkitchen_types::contact::{
-    Contact, FormSubmitted, MarkedReadAndReply, Reopened, Resolved, Status, Subject,
+    Contact, FlaggedAsSpam, FormSubmitted, MarkedReadAndReply, Reopened, Resolved, Status, Subject,
 };
 
 #[evento::projection(Debug, FromRow, Cursor)]
@@ -43,6 +43,10 @@ impl AdminView {
         self.status.0 == Status::Resolved
     }
 
+    pub fn is_flagged(&self) -> bool {
+        self.status.0 == Status::Flagged
+    }
+
     pub fn short_name(&self) -> String {
         self.name
             .split(' ')
@@ -54,6 +58,7 @@ impl AdminView {
 }
 
 pub struct FilterQuery {
+    /// `None` lists everything but messages flagged as spam.
     pub status: Option<Status>,
     pub subject: Option<Subject>,
     pub search: Option<String>,
@@ -81,8 +86,14 @@ impl<E: Executor> crate::contact::Module<E> {
             statement.and_where(Expr::col(ContactAdmin::Subject).eq(subject.to_string()));
         }
 
-        if let Some(status) = input.status {
-            statement.and_where(Expr::col(ContactAdmin::Status).eq(status.to_string()));
+        match input.status {
+            Some(status) => {
+                statement.and_where(Expr::col(ContactAdmin::Status).eq(status.to_string()));
+            }
+            None => {
+                statement
+                    .and_where(Expr::col(ContactAdmin::Status).ne(Status::Flagged.to_string()));
+            }
         }
 
         if let Some(match_query) = input
@@ -175,6 +186,7 @@ pub fn create_projection<E: Executor>() -> Projection<E, AdminView> {
         .handler(handle_reopened())
         .handler(handle_marked_read_and_reply())
         .handler(handle_resolved())
+        .handler(handle_flagged_as_spam())
 }
 
 impl<E: Executor> Snapshot<E> for AdminView {
@@ -267,6 +279,16 @@ async fn handle_resolved(_event: Event<Resolved>, data: &mut AdminView) -> anyho
     Ok(())
 }
 
+#[evento::handler]
+async fn handle_flagged_as_spam(
+    _event: Event<FlaggedAsSpam>,
+    data: &mut AdminView,
+) -> anyhow::Result<()> {
+    data.status.0 = Status::Flagged;
+
+    Ok(())
+}
+
 #[evento::handler]
 async fn handle_reopened(_event: Event<Reopened>, data: &mut AdminView) -> anyhow::Result<()> {
     data.status.0 = Status::Read;
@@ -4,7 +4,7 @@ use evento::{
     subscription::{Context, SubscriptionBuilder},
 };
 use imkitchen_db::contact_global_stat::ContactGlobalStat;
-use imkitchen_types::contact::{FormSubmitted, MarkedReadAndReply, Resolved};
+use imkitchen_types::contact::{FlaggedAsSpam, FormSubmitted, MarkedReadAndReply, Resolved};
 use sea_query::{Expr, ExprTrait, OnConflict, Query, SqliteQueryBuilder};
 use sea_query_sqlx::SqlxBinder;
 use sqlx::{SqlitePool, prelude::FromRow};
@@ -50,6 +50,7 @@ impl<E: Executor> crate::contact::Module<E> {
 pub fn subscription<E: Executor>() -> SubscriptionBuilder<E> {
     SubscriptionBuilder::new("contact-global-stat-view")
         .handler(handle_contact_form_submitted())
+        .handler(handle_contact_flagged_as_spam())
         .handler(handle_contact_marked_read_and_reply())
         .handler(handle_contact_resolved())
 }
@@ -66,6 +67,20 @@ async fn handle_contact_form_submitted<E: Executor>(
     Ok(())
 }
 
+/// Spam stays in the total but is kept out of the unread count, as the
+/// inbox hides it.
+#[evento::subscription]
+async fn handle_contact_flagged_as_spam<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<FlaggedAsSpam>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<sqlx::SqlitePool>();
+    update(&pool, ContactGlobalStat::Unread, GLOBAL_TIMESTAMP, false).await?;
+    update(&pool, ContactGlobalStat::Unread, event.timestamp, false).await?;
+
+    Ok(())
+}
+
 #[evento::subscription]
 async fn handle_contact_marked_read_and_reply<E: Executor>(
     context: &Context<'_, E>,
@@ -1,16 +1,21 @@
 use bitcode::{Decode, Encode};
 use evento::{Executor, Projection, ProjectionAggregate, metadata::Event};
 use imkitchen_types::contact::{
-    self, FormSubmitted, MarkedReadAndReply, Reopened, Resolved, Status,
+    self, AcknowledgementRequested, FlaggedAsSpam, FormSubmitted, MarkedReadAndReply, Reopened,
+    Resolved, SenderIpRecorded, Status,
 };
 use std::ops::Deref;
 
 mod mark_read_and_reply;
 mod reopen;
 mod resolve;
+mod spam;
 mod submit_form;
 
-pub use submit_form::SubmitFormInput;
+pub use submit_form::{
+    ACKNOWLEDGEMENT_LIMIT, ACKNOWLEDGEMENT_WINDOW_SECS, SUBMISSION_LIMIT_PER_EMAIL,
+    SUBMISSION_LIMIT_PER_IP, SUBMISSION_WINDOW_SECS, SubmitFormInput,
+};
 
 #[derive(Clone)]
 pub struct Module<E: Executor>(crate::State<E>);
@@ -51,6 +56,9 @@ pub fn create_projection<E: Executor>() -> Projection<E, Contact> {
         .handler(handle_reopened())
         .handler(handle_resolved())
         .handler(handle_marked_read_and_reply())
+        .handler(handle_flagged_as_spam())
+        .skip::<AcknowledgementRequested>()
+        .skip::<SenderIpRecorded>()
         .strict()
 }
 
@@ -65,6 +73,16 @@ async fn handle_form_submitted(
     Ok(())
 }
 
+#[evento::handler]
+async fn handle_flagged_as_spam(
+    _event: Event<FlaggedAsSpam>,
+    row: &mut Contact,
+) -> anyhow::Result<()> {
+    row.status = Status::Flagged;
+
+    Ok(())
+}
+
 #[evento::handler]
 async fn handle_marked_read_and_reply(
     _event: Event<MarkedReadAndReply>,
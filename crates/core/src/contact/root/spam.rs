@@ -0,0 +1,42 @@
+/// Words that rarely show up in a genuine message about a cooking app.
+const SPAM_KEYWORDS: &[&str] = &[
+    "backlinks",
+    "betting",
+    "bitcoin",
+    "casino",
+    "cialis",
+    "crypto",
+    "forex",
+    "loan",
+    "loans",
+    "seo",
+    "viagra",
+];
+
+/// Links a message may carry before it counts as spam whatever its length.
+const MAX_LINKS: usize = 3;
+
+/// Rough check for the usual contact form spam: a spam keyword, more than
+/// [`MAX_LINKS`] links, or several links making up over a fifth of the
+/// words. A single link, like a bug report pointing at a page, is fine.
+pub(super) fn looks_like_spam(message: &str) -> bool {
+    let message = message.to_lowercase();
+    let words = message.split_whitespace().collect::<Vec<_>>();
+
+    let has_keyword = words.iter().any(|word| {
+        let word = word.trim_matches(|c: char| !c.is_alphanumeric());
+        SPAM_KEYWORDS.contains(&word)
+    });
+
+    if has_keyword {
+        return true;
+    }
+
+    let links = words.iter().filter(|word| is_link(word)).count();
+
+    links > MAX_LINKS || (links > 1 && links * 5 > words.len())
+}
+
+fn is_link(word: &str) -> bool {
+    word.starts_with("http://") || word.starts_with("https://") || word.starts_with("www.")
+}
@@ -1,7 +1,29 @@
 use evento::Executor;
-use imkitchen_types::contact::{FormSubmitted, Subject};
+use imkitchen_db::contact_submission::ContactSubmission;
+use imkitchen_types::contact::{
+    AcknowledgementRequested, FlaggedAsSpam, FormSubmitted, SenderIpRecorded, Subject,
+};
+use sea_query::{Expr, ExprTrait, Func, Query, SimpleExpr, SqliteQueryBuilder};
+use sea_query_sqlx::SqlxBinder;
+use time::OffsetDateTime;
 use validator::Validate;
 
+/// Acknowledgements sent to the same address within
+/// [`ACKNOWLEDGEMENT_WINDOW_SECS`]; later submissions are treated as spam and
+/// go unacknowledged.
+pub const ACKNOWLEDGEMENT_LIMIT: i64 = 3;
+pub const ACKNOWLEDGEMENT_WINDOW_SECS: i64 = 60 * 60;
+
+/// Submissions accepted from the same address, or the same IP, within
+/// [`SUBMISSION_WINDOW_SECS`]; later ones fail with
+/// [`crate::Error::RateLimited`].
+pub const SUBMISSION_LIMIT_PER_EMAIL: i64 = 5;
+pub const SUBMISSION_LIMIT_PER_IP: i64 = 10;
+pub const SUBMISSION_WINDOW_SECS: i64 = 60 * 60;
+
+/// Characters of the message quoted back in the acknowledgement.
+const SUMMARY_LEN: usize = 200;
+
 #[derive(Validate)]
 pub struct SubmitFormInput {
     #[validate(email)]
@@ -13,21 +35,156 @@ pub struct SubmitFormInput {
     pub subject: Subject,
     #[validate(length(min = 1, max = 2000))]
     pub message: String,
+    /// Language of the acknowledgement sent back to the submitter.
+    pub lang: String,
+    /// Sender's IP when known, to rate limit per IP as well as per address.
+    pub ip: Option<String>,
 }
 
 impl<E: Executor + Clone> super::Module<E> {
     pub async fn submit_form(&self, input: SubmitFormInput) -> crate::Result<String> {
         input.validate()?;
 
-        Ok(evento::create()
+        let email = input.email.to_lowercase();
+        let email_count = self
+            .count_recent(
+                SUBMISSION_WINDOW_SECS,
+                Expr::col(ContactSubmission::Email).eq(email.as_str()),
+            )
+            .await?;
+
+        if email_count >= SUBMISSION_LIMIT_PER_EMAIL {
+            return Err(crate::Error::RateLimited);
+        }
+
+        if let Some(ip) = input.ip.as_deref() {
+            let ip_count = self
+                .count_recent(
+                    SUBMISSION_WINDOW_SECS,
+                    Expr::col(ContactSubmission::Ip).eq(ip),
+                )
+                .await?;
+
+            if ip_count >= SUBMISSION_LIMIT_PER_IP {
+                return Err(crate::Error::RateLimited);
+            }
+        }
+
+        let is_spam = super::spam::looks_like_spam(&input.message);
+
+        let mut builder = evento::create()
             .event(&FormSubmitted {
                 to: input.to,
-                name: input.name,
+                name: input.name.to_owned(),
+                email: input.email.to_owned(),
+                subject: input.subject.to_owned(),
+                message: input.message.to_owned(),
+            })
+            .to_owned();
+
+        if let Some(ip) = input.ip.to_owned() {
+            builder.event(&SenderIpRecorded { ip });
+        }
+
+        if is_spam {
+            builder.event(&FlaggedAsSpam);
+        }
+
+        let acknowledgements = self
+            .count_recent(
+                ACKNOWLEDGEMENT_WINDOW_SECS,
+                Expr::col(ContactSubmission::Email)
+                    .eq(email.as_str())
+                    .and(Expr::col(ContactSubmission::Acknowledged).eq(true)),
+            )
+            .await?;
+
+        let acknowledged = !is_spam && acknowledgements < ACKNOWLEDGEMENT_LIMIT;
+        if acknowledged {
+            builder.event(&AcknowledgementRequested {
+                summary: summarize(&input.message),
                 email: input.email,
+                name: input.name,
                 subject: input.subject,
-                message: input.message,
-            })
-            .commit(&self.executor)
-            .await?)
+                lang: input.lang,
+            });
+        }
+
+        let id = builder.commit(&self.executor).await?;
+        self.record_submission(&id, email, input.ip, acknowledged)
+            .await?;
+
+        Ok(id)
+    }
+
+    /// Submissions matching `filter` within the last `window_secs`.
+    async fn count_recent(&self, window_secs: i64, filter: SimpleExpr) -> crate::Result<i64> {
+        let since = OffsetDateTime::now_utc().unix_timestamp() - window_secs;
+        let (sql, values) = Query::select()
+            .expr(Func::count(Expr::col(ContactSubmission::Id)))
+            .from(ContactSubmission::Table)
+            .and_where(filter)
+            .and_where(Expr::col(ContactSubmission::CreatedAt).gte(since))
+            .build_sqlx(SqliteQueryBuilder);
+
+        let (count,) = sqlx::query_as_with::<_, (i64,), _>(sqlx::AssertSqlSafe(sql), values)
+            .fetch_one(&self.read_db)
+            .await?;
+
+        Ok(count)
+    }
+
+    /// Kept in `contact_submission` for the limits above, written right away
+    /// so back-to-back submissions are counted. Rows past the longest window
+    /// are dropped.
+    async fn record_submission(
+        &self,
+        id: &str,
+        email: String,
+        ip: Option<String>,
+        acknowledged: bool,
+    ) -> crate::Result<()> {
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        let (sql, values) = Query::insert()
+            .into_table(ContactSubmission::Table)
+            .columns([
+                ContactSubmission::Id,
+                ContactSubmission::Email,
+                ContactSubmission::Ip,
+                ContactSubmission::Acknowledged,
+                ContactSubmission::CreatedAt,
+            ])
+            .values_panic([
+                id.into(),
+                email.into(),
+                ip.into(),
+                acknowledged.into(),
+                now.into(),
+            ])
+            .build_sqlx(SqliteQueryBuilder);
+
+        sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+            .execute(&self.write_db)
+            .await?;
+
+        let expired = now - SUBMISSION_WINDOW_SECS.max(ACKNOWLEDGEMENT_WINDOW_SECS);
+        let (sql, values) = Query::delete()
+            .from_table(ContactSubmission::Table)
+            .and_where(Expr::col(ContactSubmission::CreatedAt).lt(expired))
+            .build_sqlx(SqliteQueryBuilder);
+
+        sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+            .execute(&self.write_db)
+            .await?;
+
+        Ok(())
+    }
+}
+
+fn summarize(message: &str) -> String {
+    let message = message.trim();
+    match message.char_indices().nth(SUMMARY_LEN) {
+        Some((end, _)) => format!("{}…", message[..end].trim_end()),
+        None => message.to_owned(),
     }
 }
@@ -0,0 +1,70 @@
+use std::ops::AddAssign;
+
+/// How many of the recipes behind an estimate (nutrition, cost, ...) carried
+/// the data it is computed from, e.g. "based on 4 of 7 recipes".
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Coverage {
+    pub known: u32,
+    pub total: u32,
+}
+
+impl Coverage {
+    pub fn is_complete(&self) -> bool {
+        self.known == self.total
+    }
+
+    /// No recipe had the data, so any total is unknown rather than zero.
+    pub fn is_unknown(&self) -> bool {
+        self.known == 0
+    }
+
+    /// Sums the known values, counting how many were present. The total is
+    /// `None` when nothing was known, so a real zero stays distinguishable.
+    pub fn sum<T: Default + AddAssign>(
+        values: impl IntoIterator<Item = Option<T>>,
+    ) -> (Option<T>, Coverage) {
+        let mut coverage = Coverage::default();
+        let mut total = T::default();
+
+        for value in values {
+            coverage.total += 1;
+
+            if let Some(value) = value {
+                coverage.known += 1;
+                total += value;
+            }
+        }
+
+        let total = (!coverage.is_unknown()).then_some(total);
+
+        (total, coverage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Coverage;
+
+    #[test]
+    fn sum_partial() {
+        let (total, coverage) = Coverage::sum([Some(200), None, Some(350), None]);
+        assert_eq!(total, Some(550));
+        assert_eq!(coverage, Coverage { known: 2, total: 4 });
+        assert!(!coverage.is_complete());
+    }
+
+    #[test]
+    fn sum_known_zero_is_not_unknown() {
+        let (total, coverage) = Coverage::sum([Some(0), Some(0)]);
+        assert_eq!(total, Some(0));
+        assert!(coverage.is_complete());
+    }
+
+    #[test]
+    fn sum_nothing_known() {
+        let (total, coverage) = Coverage::sum::<u32>([None, None, None]);
+        assert_eq!(total, None);
+        assert_eq!(coverage, Coverage { known: 0, total: 3 });
+        assert!(coverage.is_unknown());
+    }
+}
@@ -1,11 +1,13 @@
 mod command;
 pub mod contact;
+mod coverage;
 mod date;
 pub mod mealplan;
 pub mod recipe;
 pub mod shopping;
 
 pub use command::*;
+pub use coverage::*;
 pub use date::*;
 
 use evento::Executor;
@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+
+use evento::Executor;
+use imkitchen_db::mealplan_recipe::MealPlanRecipe;
+use imkitchen_types::recipe::Equipment;
+use sea_query::{Expr, ExprTrait, Query, SqliteQueryBuilder};
+use sea_query_sqlx::SqlxBinder;
+use sqlx::types::Json;
+use strum::VariantArray;
+use time::OffsetDateTime;
+
+use super::slot::SlotRow;
+
+/// A planned day whose courses need the same appliance more times than the
+/// kitchen has it. Only a warning: the plan itself is left as is.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EquipmentConflict {
+    pub day: u64,
+    pub equipment: Equipment,
+    pub needed: u8,
+    pub available: u8,
+    /// Courses of the day using the appliance, main course first.
+    pub recipe_ids: Vec<String>,
+}
+
+impl<E: Executor> crate::mealplan::Module<E> {
+    /// Days between `start` and `end` that double-book an appliance.
+    /// `capacity` overrides [`Equipment::default_capacity`] per appliance.
+    pub async fn conflicts(
+        &self,
+        user_id: impl Into<String>,
+        start: OffsetDateTime,
+        end: OffsetDateTime,
+        capacity: &HashMap<Equipment, u8>,
+    ) -> anyhow::Result<Vec<EquipmentConflict>> {
+        let user_id = user_id.into();
+        let slots = self.range(&user_id, start, end).await?;
+
+        let mut ids = slots
+            .iter()
+            .flat_map(|slot| slot.recipes())
+            .map(|recipe| recipe.id.to_owned())
+            .collect::<Vec<_>>();
+        ids.sort();
+        ids.dedup();
+
+        if ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let statement = Query::select()
+            .columns([MealPlanRecipe::Id, MealPlanRecipe::Equipment])
+            .from(MealPlanRecipe::Table)
+            .and_where(Expr::col(MealPlanRecipe::UserId).eq(&user_id))
+            .and_where(Expr::col(MealPlanRecipe::Id).is_in(ids))
+            .to_owned();
+
+        let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+        let equipment = sqlx::query_as_with::<_, (String, Json<Vec<Equipment>>), _>(
+            sqlx::AssertSqlSafe(sql),
+            values,
+        )
+        .fetch_all(&self.read_db)
+        .await?
+        .into_iter()
+        .map(|(id, equipment)| (id, equipment.0))
+        .collect::<HashMap<_, _>>();
+
+        Ok(slots
+            .iter()
+            .flat_map(|slot| day_conflicts(slot, &equipment, capacity))
+            .collect())
+    }
+}
+
+fn day_conflicts(
+    slot: &SlotRow,
+    equipment: &HashMap<String, Vec<Equipment>>,
+    capacity: &HashMap<Equipment, u8>,
+) -> Vec<EquipmentConflict> {
+    let mut conflicts = vec![];
+
+    for item in Equipment::VARIANTS {
+        let mut recipe_ids: Vec<String> = vec![];
+        for recipe in slot.recipes() {
+            let uses = equipment
+                .get(&recipe.id)
+                .is_some_and(|list| list.contains(item));
+
+            if uses && !recipe_ids.contains(&recipe.id) {
+                recipe_ids.push(recipe.id.to_owned());
+            }
+        }
+
+        let available = capacity
+            .get(item)
+            .copied()
+            .unwrap_or_else(|| item.default_capacity());
+        let needed = recipe_ids.len() as u8;
+
+        if needed > available {
+            conflicts.push(EquipmentConflict {
+                day: slot.day,
+                equipment: *item,
+                needed,
+                available,
+                recipe_ids,
+            });
+        }
+    }
+
+    conflicts
+}
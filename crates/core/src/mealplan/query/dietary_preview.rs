@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+
+use evento::Executor;
+use imkitchen_db::mealplan_recipe::MealPlanRecipe;
+use imkitchen_types::recipe::{DietaryRestriction, RecipeType};
+use sea_query::{Alias, Expr, ExprTrait, Func, Query, SelectStatement, SqliteQueryBuilder};
+use sea_query_sqlx::SqlxBinder;
+use strum::VariantArray;
+
+use crate::mealplan::filter_by_dietary_restrictions;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DietaryPreviewRow {
+    pub recipe_type: RecipeType,
+    pub remaining: u32,
+    pub excluded: u32,
+}
+
+impl<E: Executor> crate::mealplan::Module<E> {
+    /// How many of the recipes meal plans pick from (own recipes and saved
+    /// favorites) would still be eligible under `dietary_restrictions`, per
+    /// course. Courses with no recipe at all are left out. Nothing is saved.
+    pub async fn dietary_preview(
+        &self,
+        user_id: impl Into<String>,
+        dietary_restrictions: &[DietaryRestriction],
+    ) -> anyhow::Result<Vec<DietaryPreviewRow>> {
+        let user_id = user_id.into();
+        let totals = self.count_by_recipe_type(&user_id, &[]).await?;
+        let remaining = self
+            .count_by_recipe_type(&user_id, dietary_restrictions)
+            .await?;
+
+        Ok(RecipeType::VARIANTS
+            .iter()
+            .filter_map(|recipe_type| {
+                let total = totals.get(recipe_type.as_ref()).copied()?;
+                let remaining = remaining.get(recipe_type.as_ref()).copied().unwrap_or(0);
+
+                Some(DietaryPreviewRow {
+                    recipe_type: recipe_type.clone(),
+                    remaining,
+                    excluded: total - remaining,
+                })
+            })
+            .collect())
+    }
+
+    async fn count_by_recipe_type(
+        &self,
+        user_id: &str,
+        dietary_restrictions: &[DietaryRestriction],
+    ) -> anyhow::Result<HashMap<String, u32>> {
+        let mut statement: SelectStatement = Query::select()
+            .column(MealPlanRecipe::RecipeType)
+            .expr_as(
+                Func::count(Expr::col(MealPlanRecipe::Id)),
+                Alias::new("total"),
+            )
+            .from(MealPlanRecipe::Table)
+            .and_where(Expr::col(MealPlanRecipe::UserId).eq(user_id))
+            .and_where(Expr::col(MealPlanRecipe::Name).not_equals(""))
+            .group_by_col(MealPlanRecipe::RecipeType)
+            .to_owned();
+
+        filter_by_dietary_restrictions(&mut statement, dietary_restrictions);
+
+        let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+
+        Ok(
+            sqlx::query_as_with::<_, (String, u32), _>(sqlx::AssertSqlSafe(sql), values)
+                .fetch_all(&self.read_db)
+                .await?
+                .into_iter()
+                .collect(),
+        )
+    }
+}
@@ -0,0 +1,127 @@
+use evento::Executor;
+use imkitchen_db::mealplan_recipe::MealPlanRecipe;
+use imkitchen_types::recipe::{Complexity, DietaryRestriction, Equipment, RecipeType};
+use sea_query::{Alias, Expr, ExprTrait, Query, SqliteQueryBuilder};
+use sea_query_sqlx::SqlxBinder;
+use sqlx::types::Text;
+use sqlx::{FromRow, Row};
+use time::Weekday;
+
+use crate::mealplan::{Generate, PoolFilter, RECIPE_COLUMNS, Recipe};
+
+/// Courses generation picks recipes for. Beverages and condiments are never
+/// planned.
+pub const PLANNED_RECIPE_TYPES: [RecipeType; 4] = [
+    RecipeType::Appetizer,
+    RecipeType::MainCourse,
+    RecipeType::Accompaniment,
+    RecipeType::Dessert,
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Exclusion {
+    /// Neither one of the user's recipes nor a saved favorite.
+    NotInPool,
+    /// Recipes without a name are still drafts.
+    Untitled,
+    /// The course is never planned.
+    RecipeType(RecipeType),
+    /// The recipe isn't tagged as meeting one of the user's restrictions.
+    DietaryRestriction(DietaryRestriction),
+    /// The recipe is meant for a bigger household than this one.
+    HouseholdSize(u16),
+    /// The recipe needs an appliance the kitchen doesn't have.
+    Equipment(Equipment),
+    /// The main course is harder than any day's cap allows.
+    Complexity(Complexity),
+    /// The main course takes longer, in minutes, than any day's time budget.
+    TimeBudget(u32),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanEligibility {
+    pub recipe_type: Option<RecipeType>,
+    pub exclusions: Vec<Exclusion>,
+}
+
+impl PlanEligibility {
+    pub fn is_eligible(&self) -> bool {
+        self.exclusions.is_empty()
+    }
+}
+
+impl<E: Executor> crate::mealplan::Module<E> {
+    /// Explains whether generating `input` could pick `recipe_id`. The pool
+    /// rules are the very conditions [`Self::generate`] queries with, and
+    /// the day caps the checks it plans main courses with. Every reason that
+    /// applies is listed, not just the first.
+    pub async fn plan_eligibility(
+        &self,
+        recipe_id: impl Into<String>,
+        input: &Generate,
+    ) -> anyhow::Result<PlanEligibility> {
+        let rules = PoolFilter::from(input).rules();
+        let mut statement = Query::select()
+            .columns(RECIPE_COLUMNS)
+            .column(MealPlanRecipe::RecipeType)
+            .from(MealPlanRecipe::Table)
+            .and_where(Expr::col(MealPlanRecipe::Id).eq(recipe_id.into()))
+            .and_where(Expr::col(MealPlanRecipe::UserId).eq(input.user_id.to_owned()))
+            .limit(1)
+            .to_owned();
+
+        for (index, (_, rule)) in rules.iter().enumerate() {
+            statement.expr_as(rule.clone(), Alias::new(format!("rule_{index}")));
+        }
+
+        let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+
+        let Some(row) = sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+            .fetch_optional(&self.read_db)
+            .await?
+        else {
+            return Ok(PlanEligibility {
+                recipe_type: None,
+                exclusions: vec![Exclusion::NotInPool],
+            });
+        };
+
+        let recipe = Recipe::from_row(&row)?;
+        let recipe_type = row.try_get::<Text<RecipeType>, _>("recipe_type")?.0;
+        let mut exclusions = vec![];
+
+        if recipe.name.is_empty() {
+            exclusions.push(Exclusion::Untitled);
+        }
+
+        if !PLANNED_RECIPE_TYPES.contains(&recipe_type) {
+            exclusions.push(Exclusion::RecipeType(recipe_type.clone()));
+        }
+
+        for (index, (exclusion, _)) in rules.into_iter().enumerate() {
+            if !row.try_get::<bool, _>(format!("rule_{index}").as_str())? {
+                exclusions.push(exclusion);
+            }
+        }
+
+        if recipe_type == RecipeType::MainCourse {
+            let week = || (0..7).map(|days| Weekday::Monday.nth_next(days));
+
+            if !week().any(|weekday| input.max_complexity.allows(&recipe, weekday)) {
+                exclusions.push(Exclusion::Complexity(recipe.complexity()));
+            }
+
+            if !week().any(|weekday| {
+                recipe
+                    .fits_time_budget(input.time_budget[weekday.number_days_from_monday() as usize])
+            }) {
+                exclusions.push(Exclusion::TimeBudget(recipe.total_time()));
+            }
+        }
+
+        Ok(PlanEligibility {
+            recipe_type: Some(recipe_type),
+            exclusions,
+        })
+    }
+}
@@ -0,0 +1,114 @@
+use evento::Executor;
+use time::{Duration, OffsetDateTime, macros::format_description};
+
+use super::slot::SlotRow;
+
+/// Media type of [`crate::mealplan::Module::ics`] output.
+pub const ICS_CONTENT_TYPE: &str = "text/calendar; charset=utf-8";
+
+/// Planned dinners start this long after the day begins and last
+/// [`DINNER_DURATION`].
+pub const DINNER_START: Duration = Duration::hours(19);
+pub const DINNER_DURATION: Duration = Duration::hours(1);
+
+impl<E: Executor> crate::mealplan::Module<E> {
+    /// The days planned between `start` and `end` as an RFC 5545 calendar,
+    /// one dinner event per day. A range with nothing planned still gives a
+    /// valid, empty calendar.
+    pub async fn ics(
+        &self,
+        user_id: impl Into<String>,
+        start: OffsetDateTime,
+        end: OffsetDateTime,
+    ) -> anyhow::Result<String> {
+        let user_id = user_id.into();
+        let slots = self.range(&user_id, start, end).await?;
+
+        to_ics(&user_id, &slots)
+    }
+}
+
+fn to_ics(user_id: &str, slots: &[SlotRow]) -> anyhow::Result<String> {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_owned(),
+        "VERSION:2.0".to_owned(),
+        "PRODID:-//imkitchen//Meal plan//EN".to_owned(),
+        "CALSCALE:GREGORIAN".to_owned(),
+    ];
+
+    for slot in slots {
+        let day = OffsetDateTime::from_unix_timestamp(slot.day as i64)?;
+        let start = day + DINNER_START;
+        let summary = slot
+            .recipes()
+            .map(|recipe| recipe.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let description = slot
+            .advance_prep_recipes()
+            .iter()
+            .map(|recipe| format!("{}: {}", recipe.name, recipe.advance_prep.trim()))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        lines.push("BEGIN:VEVENT".to_owned());
+        lines.push(format!(
+            "UID:{}-{}@imkitchen",
+            user_id,
+            crate::mealplan::date_to_u64(day)
+        ));
+        lines.push(format!(
+            "DTSTAMP:{}",
+            utc(OffsetDateTime::from_unix_timestamp(
+                slot.generated_at as i64
+            )?)?
+        ));
+        lines.push(format!("DTSTART:{}", utc(start)?));
+        lines.push(format!("DTEND:{}", utc(start + DINNER_DURATION)?));
+        lines.push(format!("SUMMARY:{}", escape(&summary)));
+        if !description.is_empty() {
+            lines.push(format!("DESCRIPTION:{}", escape(&description)));
+        }
+        lines.push("END:VEVENT".to_owned());
+    }
+
+    lines.push("END:VCALENDAR".to_owned());
+
+    Ok(lines.iter().map(|line| fold(line)).collect())
+}
+
+fn utc(date: OffsetDateTime) -> anyhow::Result<String> {
+    let format = format_description!("[year][month][day]T[hour][minute][second]Z");
+
+    Ok(date.to_offset(time::UtcOffset::UTC).format(&format)?)
+}
+
+/// TEXT value escaping (RFC 5545, 3.3.11).
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\r', "")
+        .replace('\n', "\\n")
+}
+
+/// Ends the line with CRLF, splitting it into continuation lines of at most
+/// 75 octets without cutting a character in half.
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut width = 0;
+
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+
+        folded.push(c);
+        width += c.len_utf8();
+    }
+
+    folded.push_str("\r\n");
+    folded
+}
@@ -1 +1,7 @@
+pub mod conflict;
+pub mod dietary_preview;
+pub mod eligibility;
+pub mod ics;
+pub mod nutrition;
 pub mod slot;
+pub mod week;
@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+
+use evento::Executor;
+use imkitchen_db::mealplan_recipe::MealPlanRecipe;
+use imkitchen_types::recipe::Nutrition;
+use sea_query::{Expr, ExprTrait, Query, SqliteQueryBuilder};
+use sea_query_sqlx::SqlxBinder;
+use sqlx::types::Json;
+use time::OffsetDateTime;
+
+use super::slot::SlotRow;
+use crate::Coverage;
+
+/// Nutrition for one person eating every planned course.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct NutritionTotals {
+    /// One entry per planned day, in order.
+    pub days: Vec<(u64, Nutrition)>,
+    pub total: Nutrition,
+    /// Planned courses whose recipe has nutrition data.
+    pub coverage: Coverage,
+}
+
+impl<E: Executor> crate::mealplan::Module<E> {
+    /// Per-serving nutrition of the days planned between `start` and `end`,
+    /// summed per day and over the whole range. Recipes or ingredients
+    /// without nutrition data count as zero; `coverage` tells how many
+    /// courses had some.
+    pub async fn nutrition(
+        &self,
+        user_id: impl Into<String>,
+        start: OffsetDateTime,
+        end: OffsetDateTime,
+    ) -> anyhow::Result<NutritionTotals> {
+        let user_id = user_id.into();
+        let slots = self.range(&user_id, start, end).await?;
+
+        let mut ids = slots
+            .iter()
+            .flat_map(|slot| slot.recipes())
+            .map(|recipe| recipe.id.to_owned())
+            .collect::<Vec<_>>();
+        ids.sort();
+        ids.dedup();
+
+        if ids.is_empty() {
+            return Ok(NutritionTotals::default());
+        }
+
+        let statement = Query::select()
+            .columns([
+                MealPlanRecipe::Id,
+                MealPlanRecipe::HouseholdSize,
+                MealPlanRecipe::Nutrition,
+            ])
+            .from(MealPlanRecipe::Table)
+            .and_where(Expr::col(MealPlanRecipe::UserId).eq(&user_id))
+            .and_where(Expr::col(MealPlanRecipe::Id).is_in(ids))
+            .to_owned();
+
+        let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+        let per_serving = sqlx::query_as_with::<_, (String, u16, Option<Json<Nutrition>>), _>(
+            sqlx::AssertSqlSafe(sql),
+            values,
+        )
+        .fetch_all(&self.read_db)
+        .await?
+        .into_iter()
+        .filter_map(|(id, household_size, nutrition)| {
+            Some((id, nutrition?.0.per_serving(household_size)))
+        })
+        .collect::<HashMap<_, _>>();
+
+        Ok(totals(&slots, &per_serving))
+    }
+}
+
+fn totals(slots: &[SlotRow], per_serving: &HashMap<String, Nutrition>) -> NutritionTotals {
+    let mut coverage = Coverage::default();
+    for recipe in slots.iter().flat_map(|slot| slot.recipes()) {
+        coverage.total += 1;
+        if per_serving.contains_key(&recipe.id) {
+            coverage.known += 1;
+        }
+    }
+
+    let days = slots
+        .iter()
+        .map(|slot| {
+            let day = slot
+                .recipes()
+                .filter_map(|recipe| per_serving.get(&recipe.id).copied())
+                .sum();
+
+            (slot.day, day)
+        })
+        .collect::<Vec<(u64, Nutrition)>>();
+
+    NutritionTotals {
+        total: days.iter().map(|(_, n)| *n).sum(),
+        days,
+        coverage,
+    }
+}
@@ -4,11 +4,17 @@ use evento::{
     subscription::{Context, SubscriptionBuilder},
 };
 use imkitchen_db::mealplan_recipe::MealPlanRecipe;
+use imkitchen_db::mealplan_skipped_week::MealPlanSkippedWeek;
 use imkitchen_db::mealplan_slot::MealPlanSlot;
-use imkitchen_types::mealplan::{DaySlotRecipe, DaysGenerated, SlotRecipeStatusChanged};
+use imkitchen_types::mealplan::{
+    AdvancePrepMarked, CommunityRecipesSuggested, DaySlotRecipe, DaysGenerated, LeftoversPlanned,
+    MealReplaced, MealsSwapped, SlotRecipeStatusChanged, WeekSkipped, WeekUnskipped,
+};
+use imkitchen_types::recipe::RecipeType;
 use sea_query::{Expr, ExprTrait, OnConflict, Query, SqliteQueryBuilder};
 use sea_query_sqlx::SqlxBinder;
 use sqlx::prelude::FromRow;
+use std::collections::HashMap;
 use time::OffsetDateTime;
 
 #[derive(Default, FromRow)]
@@ -29,10 +35,26 @@ impl From<&MealPlanRecipeRow> for DaySlotRecipe {
             cook_time: value.cook_time.to_owned(),
             advance_prep: value.advance_prep.to_owned(),
             status: Default::default(),
+            advance_prep_done: false,
         }
     }
 }
 
+/// Hours ahead of its day a course's advance prep is reminded of, when the
+/// recipe doesn't say.
+pub const DEFAULT_ADVANCE_PREP_HOURS: u16 = 24;
+
+/// A course whose advance prep should start at `remind_at` (unix time).
+pub struct PrepReminder {
+    pub user_id: String,
+    /// Day (YYYYMMDD) the course is planned on.
+    pub date: u64,
+    /// Start of that day (unix time), after which the reminder is useless.
+    pub day: u64,
+    pub remind_at: u64,
+    pub recipe: DaySlotRecipe,
+}
+
 #[derive(Default, Clone, FromRow)]
 pub struct SlotRow {
     pub day: u64,
@@ -44,6 +66,11 @@ pub struct SlotRow {
     pub beverage: Option<evento::sql_types::Bitcode<DaySlotRecipe>>,
     pub condiment: Option<evento::sql_types::Bitcode<DaySlotRecipe>>,
     pub generated_at: u64,
+    /// Date (YYYYMMDD) the main course was cooked on, when this day eats
+    /// its leftovers.
+    pub leftover_of: Option<u64>,
+    /// The main course is a community recipe planned as a suggestion.
+    pub suggested: bool,
 }
 
 impl SlotRow {
@@ -72,9 +99,201 @@ impl SlotRow {
 
         t
     }
+
+    /// Every course of the day, main course first.
+    pub fn recipes(&self) -> impl Iterator<Item = &DaySlotRecipe> {
+        [
+            Some(&self.main_course),
+            self.appetizer.as_ref(),
+            self.accompaniment.as_ref(),
+            self.dessert.as_ref(),
+            self.beverage.as_ref(),
+            self.condiment.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|recipe| &recipe.0)
+    }
+
+    /// Courses of the day that need some advance prep, done or not.
+    pub fn advance_prep_recipes(&self) -> Vec<&DaySlotRecipe> {
+        [
+            self.appetizer.as_ref(),
+            Some(&self.main_course),
+            self.accompaniment.as_ref(),
+            self.dessert.as_ref(),
+            self.beverage.as_ref(),
+            self.condiment.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|recipe| &recipe.0)
+        .filter(|recipe| !recipe.advance_prep.trim().is_empty())
+        .collect()
+    }
+}
+
+const COURSE_COLUMNS: [MealPlanSlot; 6] = [
+    MealPlanSlot::MainCourse,
+    MealPlanSlot::Appetizer,
+    MealPlanSlot::Accompaniment,
+    MealPlanSlot::Dessert,
+    MealPlanSlot::Beverage,
+    MealPlanSlot::Condiment,
+];
+
+type PrepSlotRow = (
+    String,
+    u64,
+    u64,
+    Option<evento::sql_types::Bitcode<DaySlotRecipe>>,
+    Option<evento::sql_types::Bitcode<DaySlotRecipe>>,
+    Option<evento::sql_types::Bitcode<DaySlotRecipe>>,
+    Option<evento::sql_types::Bitcode<DaySlotRecipe>>,
+    Option<evento::sql_types::Bitcode<DaySlotRecipe>>,
+    Option<evento::sql_types::Bitcode<DaySlotRecipe>>,
+);
+
+type CourseRow = (
+    Option<evento::sql_types::Bitcode<DaySlotRecipe>>,
+    Option<evento::sql_types::Bitcode<DaySlotRecipe>>,
+    Option<evento::sql_types::Bitcode<DaySlotRecipe>>,
+    Option<evento::sql_types::Bitcode<DaySlotRecipe>>,
+    Option<evento::sql_types::Bitcode<DaySlotRecipe>>,
+    Option<evento::sql_types::Bitcode<DaySlotRecipe>>,
+);
+
+/// Loads every course of a day, paired with the column it is stored in.
+async fn day_courses(
+    pool: &sqlx::SqlitePool,
+    user_id: &str,
+    date: u64,
+) -> anyhow::Result<Vec<(MealPlanSlot, DaySlotRecipe)>> {
+    let (sql, values) = Query::select()
+        .columns(COURSE_COLUMNS)
+        .from(MealPlanSlot::Table)
+        .and_where(Expr::col(MealPlanSlot::UserId).eq(user_id))
+        .and_where(Expr::col(MealPlanSlot::Date).eq(date))
+        .limit(1)
+        .build_sqlx(SqliteQueryBuilder);
+
+    let Some((main, appetizer, accompaniment, dessert, beverage, condiment)) =
+        sqlx::query_as_with::<_, CourseRow, _>(sqlx::AssertSqlSafe(sql), values)
+            .fetch_optional(pool)
+            .await?
+    else {
+        return Ok(vec![]);
+    };
+
+    Ok(COURSE_COLUMNS
+        .into_iter()
+        .zip([main, appetizer, accompaniment, dessert, beverage, condiment])
+        .filter_map(|(column, recipe)| Some((column, recipe?.0)))
+        .collect())
+}
+
+/// Dates (YYYYMMDD) each recipe is planned on for one course, across every
+/// generated week.
+pub(crate) async fn planned_dates(
+    pool: &sqlx::SqlitePool,
+    user_id: &str,
+    recipe_type: &RecipeType,
+) -> anyhow::Result<HashMap<String, Vec<u64>>> {
+    let (sql, values) = Query::select()
+        .columns([MealPlanSlot::Date, course_column(recipe_type)])
+        .from(MealPlanSlot::Table)
+        .and_where(Expr::col(MealPlanSlot::UserId).eq(user_id))
+        .build_sqlx(SqliteQueryBuilder);
+
+    let rows =
+        sqlx::query_as_with::<_, (u64, Option<evento::sql_types::Bitcode<DaySlotRecipe>>), _>(
+            sqlx::AssertSqlSafe(sql),
+            values,
+        )
+        .fetch_all(pool)
+        .await?;
+
+    let mut dates = HashMap::<String, Vec<u64>>::new();
+    for (date, recipe) in rows {
+        if let Some(recipe) = recipe {
+            dates.entry(recipe.0.id).or_default().push(date);
+        }
+    }
+
+    Ok(dates)
+}
+
+/// Slot column holding the course of the given recipe type.
+pub(crate) fn course_column(recipe_type: &RecipeType) -> MealPlanSlot {
+    match recipe_type {
+        RecipeType::Appetizer => MealPlanSlot::Appetizer,
+        RecipeType::MainCourse => MealPlanSlot::MainCourse,
+        RecipeType::Dessert => MealPlanSlot::Dessert,
+        RecipeType::Accompaniment => MealPlanSlot::Accompaniment,
+        RecipeType::Beverage => MealPlanSlot::Beverage,
+        RecipeType::Condiment => MealPlanSlot::Condiment,
+    }
 }
 
 impl<E: Executor> crate::mealplan::Module<E> {
+    /// Recipe planned for one course of a day, if any.
+    pub async fn slot_recipe(
+        &self,
+        user_id: impl Into<String>,
+        date: u64,
+        recipe_type: &RecipeType,
+    ) -> anyhow::Result<Option<DaySlotRecipe>> {
+        let user_id = user_id.into();
+        let (sql, values) = Query::select()
+            .column(course_column(recipe_type))
+            .from(MealPlanSlot::Table)
+            .and_where(Expr::col(MealPlanSlot::UserId).eq(&user_id))
+            .and_where(Expr::col(MealPlanSlot::Date).eq(date))
+            .limit(1)
+            .build_sqlx(SqliteQueryBuilder);
+
+        let recipe = sqlx::query_scalar_with::<
+            _,
+            Option<evento::sql_types::Bitcode<DaySlotRecipe>>,
+            _,
+        >(sqlx::AssertSqlSafe(sql), values)
+        .fetch_optional(&self.read_db)
+        .await?;
+
+        Ok(recipe.flatten().map(|r| r.0))
+    }
+
+    /// A planned recipe of a day, looked up by recipe id.
+    pub async fn day_slot_recipe(
+        &self,
+        user_id: impl Into<String>,
+        date: u64,
+        recipe_id: &str,
+    ) -> anyhow::Result<Option<DaySlotRecipe>> {
+        let user_id = user_id.into();
+
+        Ok(day_courses(&self.read_db, &user_id, date)
+            .await?
+            .into_iter()
+            .map(|(_, recipe)| recipe)
+            .find(|recipe| recipe.id == recipe_id))
+    }
+
+    /// Every course planned for a day, main course first.
+    pub async fn day_recipes(
+        &self,
+        user_id: impl Into<String>,
+        date: u64,
+    ) -> anyhow::Result<Vec<DaySlotRecipe>> {
+        let user_id = user_id.into();
+
+        Ok(day_courses(&self.read_db, &user_id, date)
+            .await?
+            .into_iter()
+            .map(|(_, recipe)| recipe)
+            .collect())
+    }
+
     pub async fn range(
         &self,
         user_id: impl Into<String>,
@@ -95,6 +314,8 @@ impl<E: Executor> crate::mealplan::Module<E> {
                 MealPlanSlot::Beverage,
                 MealPlanSlot::Condiment,
                 MealPlanSlot::GeneratedAt,
+                MealPlanSlot::LeftoverOf,
+                MealPlanSlot::Suggested,
             ])
             .from(MealPlanSlot::Table)
             .and_where(Expr::col(MealPlanSlot::UserId).eq(&user_id))
@@ -112,6 +333,35 @@ impl<E: Executor> crate::mealplan::Module<E> {
         )
     }
 
+    /// Skipped weeks (Monday, YYYYMMDD), earliest first.
+    pub async fn skipped_weeks(&self, user_id: impl Into<String>) -> anyhow::Result<Vec<u64>> {
+        let user_id = user_id.into();
+        let (sql, values) = Query::select()
+            .column(MealPlanSkippedWeek::Week)
+            .from(MealPlanSkippedWeek::Table)
+            .and_where(Expr::col(MealPlanSkippedWeek::UserId).eq(&user_id))
+            .order_by(MealPlanSkippedWeek::Week, sea_query::Order::Asc)
+            .build_sqlx(SqliteQueryBuilder);
+
+        Ok(
+            sqlx::query_scalar_with::<_, u64, _>(sqlx::AssertSqlSafe(sql), values)
+                .fetch_all(&self.read_db)
+                .await?,
+        )
+    }
+
+    /// Today's slot in the user's timezone, with each course's status, prep
+    /// times and advance-prep notes. `None` when nothing is planned today.
+    pub async fn today(
+        &self,
+        user_id: impl Into<String>,
+        tz: impl Into<String>,
+    ) -> anyhow::Result<Option<SlotRow>> {
+        let today = crate::mealplan::now(tz);
+
+        Ok(self.range(user_id, today, today).await?.into_iter().next())
+    }
+
     pub async fn next_slot_from(
         &self,
         day: OffsetDateTime,
@@ -130,6 +380,8 @@ impl<E: Executor> crate::mealplan::Module<E> {
                 MealPlanSlot::Beverage,
                 MealPlanSlot::Condiment,
                 MealPlanSlot::GeneratedAt,
+                MealPlanSlot::LeftoverOf,
+                MealPlanSlot::Suggested,
             ])
             .from(MealPlanSlot::Table)
             .and_where(Expr::col(MealPlanSlot::UserId).eq(&user_id))
@@ -160,12 +412,12 @@ impl<E: Executor> crate::mealplan::Module<E> {
 
         let mut remiders = vec![];
 
-        if !slot.main_course.advance_prep.is_empty() {
+        if slot.main_course.needs_advance_prep() {
             remiders.push(slot.main_course.0);
         }
 
         let recipe = slot.appetizer.and_then(|r| {
-            if !r.advance_prep.is_empty() {
+            if r.needs_advance_prep() {
                 Some(r.0)
             } else {
                 None
@@ -177,7 +429,7 @@ impl<E: Executor> crate::mealplan::Module<E> {
         }
 
         let recipe = slot.accompaniment.and_then(|r| {
-            if !r.advance_prep.is_empty() {
+            if r.needs_advance_prep() {
                 Some(r.0)
             } else {
                 None
@@ -189,7 +441,7 @@ impl<E: Executor> crate::mealplan::Module<E> {
         }
 
         let recipe = slot.dessert.and_then(|r| {
-            if !r.advance_prep.is_empty() {
+            if r.needs_advance_prep() {
                 Some(r.0)
             } else {
                 None
@@ -201,7 +453,7 @@ impl<E: Executor> crate::mealplan::Module<E> {
         }
 
         let recipe = slot.beverage.and_then(|r| {
-            if !r.advance_prep.is_empty() {
+            if r.needs_advance_prep() {
                 Some(r.0)
             } else {
                 None
@@ -213,7 +465,7 @@ impl<E: Executor> crate::mealplan::Module<E> {
         }
 
         let recipe = slot.condiment.and_then(|r| {
-            if !r.advance_prep.is_empty() {
+            if r.needs_advance_prep() {
                 Some(r.0)
             } else {
                 None
@@ -230,12 +482,109 @@ impl<E: Executor> crate::mealplan::Module<E> {
 
         Ok(Some(remiders))
     }
+
+    /// Advance prep reminders due in `[from, to)` (unix time), for every
+    /// user, so a job running every so often can send them. A course's prep
+    /// starts its recipe's `advance_prep_hours` before the day it is planned
+    /// on, which generation stores as midnight in the user's timezone. Prep
+    /// already marked done is left out.
+    pub async fn prep_reminders_between(
+        &self,
+        from: u64,
+        to: u64,
+    ) -> anyhow::Result<Vec<PrepReminder>> {
+        let max_ahead = crate::recipe::MAX_ADVANCE_PREP_HOURS as u64 * 3600;
+        let (sql, values) = Query::select()
+            .columns([MealPlanSlot::UserId, MealPlanSlot::Date, MealPlanSlot::Day])
+            .columns(COURSE_COLUMNS)
+            .from(MealPlanSlot::Table)
+            .and_where(Expr::col(MealPlanSlot::Day).gte(from))
+            .and_where(Expr::col(MealPlanSlot::Day).lt(to + max_ahead))
+            .order_by(MealPlanSlot::Day, sea_query::Order::Asc)
+            .build_sqlx(SqliteQueryBuilder);
+
+        let rows = sqlx::query_as_with::<_, PrepSlotRow, _>(sqlx::AssertSqlSafe(sql), values)
+            .fetch_all(&self.read_db)
+            .await?;
+
+        let mut reminders = vec![];
+        for (user_id, date, day, main, appetizer, accompaniment, dessert, beverage, condiment) in
+            rows
+        {
+            let recipes = [main, appetizer, accompaniment, dessert, beverage, condiment]
+                .into_iter()
+                .flatten()
+                .map(|recipe| recipe.0)
+                .filter(|recipe| recipe.needs_advance_prep())
+                .collect::<Vec<_>>();
+
+            if recipes.is_empty() {
+                continue;
+            }
+
+            let hours = self
+                .advance_prep_hours(&user_id, recipes.iter().map(|r| r.id.to_owned()))
+                .await?;
+
+            for recipe in recipes {
+                let hours = hours
+                    .get(&recipe.id)
+                    .copied()
+                    .unwrap_or(DEFAULT_ADVANCE_PREP_HOURS);
+                let remind_at = day.saturating_sub(hours as u64 * 3600);
+
+                if (from..to).contains(&remind_at) {
+                    reminders.push(PrepReminder {
+                        user_id: user_id.to_owned(),
+                        date,
+                        day,
+                        remind_at,
+                        recipe,
+                    });
+                }
+            }
+        }
+
+        reminders.sort_by_key(|reminder| reminder.remind_at);
+
+        Ok(reminders)
+    }
+
+    /// Hours of advance prep set on the user's copy of each recipe, when set.
+    async fn advance_prep_hours(
+        &self,
+        user_id: &str,
+        ids: impl IntoIterator<Item = String>,
+    ) -> anyhow::Result<HashMap<String, u16>> {
+        let (sql, values) = Query::select()
+            .columns([MealPlanRecipe::Id, MealPlanRecipe::AdvancePrepHours])
+            .from(MealPlanRecipe::Table)
+            .and_where(Expr::col(MealPlanRecipe::UserId).eq(user_id))
+            .and_where(Expr::col(MealPlanRecipe::Id).is_in(ids))
+            .and_where(Expr::col(MealPlanRecipe::AdvancePrepHours).is_not_null())
+            .build_sqlx(SqliteQueryBuilder);
+
+        Ok(
+            sqlx::query_as_with::<_, (String, u16), _>(sqlx::AssertSqlSafe(sql), values)
+                .fetch_all(&self.read_db)
+                .await?
+                .into_iter()
+                .collect(),
+        )
+    }
 }
 
 pub fn subscription<E: Executor>() -> SubscriptionBuilder<E> {
     SubscriptionBuilder::new("mealplan-slot")
         .handler(handle_days_generated())
         .handler(handle_slot_recipe_status_changed())
+        .handler(handle_meal_replaced())
+        .handler(handle_meals_swapped())
+        .handler(handle_advance_prep_marked())
+        .handler(handle_leftovers_planned())
+        .handler(handle_community_recipes_suggested())
+        .handler(handle_week_skipped())
+        .handler(handle_week_unskipped())
 }
 
 #[evento::subscription]
@@ -285,13 +634,43 @@ async fn handle_days_generated<E: Executor>(
         ])
         .from(MealPlanRecipe::Table)
         .and_where(Expr::col(MealPlanRecipe::UserId).eq(&event.aggregate_id))
-        .and_where(Expr::col(MealPlanRecipe::Id).is_in(recipe_ids))
+        .and_where(Expr::col(MealPlanRecipe::Id).is_in(recipe_ids.iter().cloned()))
         .to_owned();
 
     let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
-    let recipes = sqlx::query_as_with::<_, MealPlanRecipeRow, _>(sqlx::AssertSqlSafe(sql), values)
-        .fetch_all(&pool)
-        .await?;
+    let mut recipes =
+        sqlx::query_as_with::<_, MealPlanRecipeRow, _>(sqlx::AssertSqlSafe(sql), values)
+            .fetch_all(&pool)
+            .await?;
+
+    // Community suggestions aren't among the user's recipes, any copy of
+    // them will do.
+    let missing = recipe_ids
+        .iter()
+        .filter(|id| !recipes.iter().any(|r| &r.id == *id))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    if !missing.is_empty() {
+        let statement = Query::select()
+            .columns([
+                MealPlanRecipe::Id,
+                MealPlanRecipe::Name,
+                MealPlanRecipe::PrepTime,
+                MealPlanRecipe::CookTime,
+                MealPlanRecipe::AdvancePrep,
+            ])
+            .from(MealPlanRecipe::Table)
+            .and_where(Expr::col(MealPlanRecipe::Id).is_in(missing))
+            .to_owned();
+
+        let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+        recipes.extend(
+            sqlx::query_as_with::<_, MealPlanRecipeRow, _>(sqlx::AssertSqlSafe(sql), values)
+                .fetch_all(&pool)
+                .await?,
+        );
+    }
 
     let mut statement = Query::insert()
         .into_table(MealPlanSlot::Table)
@@ -307,6 +686,8 @@ async fn handle_days_generated<E: Executor>(
             MealPlanSlot::Beverage,
             MealPlanSlot::Condiment,
             MealPlanSlot::GeneratedAt,
+            MealPlanSlot::LeftoverOf,
+            MealPlanSlot::Suggested,
         ])
         .to_owned();
     let mut has_values = false;
@@ -370,6 +751,8 @@ async fn handle_days_generated<E: Executor>(
             beverage.into(),
             condiment.into(),
             timestamp.into(),
+            None::<u64>.into(),
+            false.into(),
         ]);
 
         has_values = true;
@@ -390,6 +773,8 @@ async fn handle_days_generated<E: Executor>(
                 MealPlanSlot::Beverage,
                 MealPlanSlot::Condiment,
                 MealPlanSlot::GeneratedAt,
+                MealPlanSlot::LeftoverOf,
+                MealPlanSlot::Suggested,
             ])
             .to_owned(),
     );
@@ -485,3 +870,250 @@ async fn handle_slot_recipe_status_changed<E: Executor>(
 
     Ok(())
 }
+
+#[evento::subscription]
+async fn handle_meal_replaced<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<MealReplaced>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<sqlx::SqlitePool>();
+
+    let (sql, values) = Query::select()
+        .columns([
+            MealPlanRecipe::Id,
+            MealPlanRecipe::Name,
+            MealPlanRecipe::PrepTime,
+            MealPlanRecipe::CookTime,
+            MealPlanRecipe::AdvancePrep,
+        ])
+        .from(MealPlanRecipe::Table)
+        .and_where(Expr::col(MealPlanRecipe::UserId).eq(&event.aggregate_id))
+        .and_where(Expr::col(MealPlanRecipe::Id).eq(&event.data.recipe.id))
+        .limit(1)
+        .build_sqlx(SqliteQueryBuilder);
+
+    // The replacement may have been deleted since; keep at least its name.
+    let recipe: DaySlotRecipe =
+        sqlx::query_as_with::<_, MealPlanRecipeRow, _>(sqlx::AssertSqlSafe(sql), values)
+            .fetch_optional(&pool)
+            .await?
+            .map(|r| (&r).into())
+            .unwrap_or_else(|| DaySlotRecipe {
+                id: event.data.recipe.id.to_owned(),
+                name: event.data.recipe.name.to_owned(),
+                ..Default::default()
+            });
+
+    let mut statement = Query::update()
+        .table(MealPlanSlot::Table)
+        .value(
+            course_column(&event.data.recipe_type),
+            bitcode::encode(&recipe),
+        )
+        .and_where(Expr::col(MealPlanSlot::UserId).eq(&event.aggregate_id))
+        .and_where(Expr::col(MealPlanSlot::Date).eq(event.data.date))
+        .to_owned();
+
+    // A new main course is cooked, not eaten from an earlier batch, and
+    // chosen by the user rather than suggested.
+    if event.data.recipe_type == RecipeType::MainCourse {
+        statement.value(MealPlanSlot::LeftoverOf, None::<u64>);
+        statement.value(MealPlanSlot::Suggested, false);
+    }
+
+    let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+
+    sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+        .execute(&pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Each day takes the other's recipe as it was, status and advance prep
+/// included.
+#[evento::subscription]
+async fn handle_meals_swapped<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<MealsSwapped>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<sqlx::SqlitePool>();
+    let column = course_column(&event.data.recipe_type);
+
+    let (sql, values) = Query::select()
+        .columns([MealPlanSlot::Date, column.clone()])
+        .from(MealPlanSlot::Table)
+        .and_where(Expr::col(MealPlanSlot::UserId).eq(&event.aggregate_id))
+        .and_where(Expr::col(MealPlanSlot::Date).is_in([event.data.date, event.data.other_date]))
+        .build_sqlx(SqliteQueryBuilder);
+
+    let rows =
+        sqlx::query_as_with::<_, (u64, Option<evento::sql_types::Bitcode<DaySlotRecipe>>), _>(
+            sqlx::AssertSqlSafe(sql),
+            values,
+        )
+        .fetch_all(&pool)
+        .await?;
+
+    for (date, recipe) in rows {
+        let target = if date == event.data.date {
+            event.data.other_date
+        } else {
+            event.data.date
+        };
+
+        let (sql, values) = Query::update()
+            .table(MealPlanSlot::Table)
+            .value(column.clone(), recipe.map(|r| bitcode::encode(&r.0)))
+            .and_where(Expr::col(MealPlanSlot::UserId).eq(&event.aggregate_id))
+            .and_where(Expr::col(MealPlanSlot::Date).eq(target))
+            .build_sqlx(SqliteQueryBuilder);
+
+        sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+            .execute(&pool)
+            .await?;
+    }
+
+    Ok(())
+}
+
+#[evento::subscription]
+async fn handle_advance_prep_marked<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<AdvancePrepMarked>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<sqlx::SqlitePool>();
+
+    let Some((column, mut recipe)) = day_courses(&pool, &event.aggregate_id, event.data.date)
+        .await?
+        .into_iter()
+        .find(|(_, recipe)| recipe.id == event.data.recipe_id)
+    else {
+        // The recipe was swapped out before the mark got processed.
+        return Ok(());
+    };
+
+    recipe.advance_prep_done = event.data.done;
+
+    let (sql, values) = Query::update()
+        .table(MealPlanSlot::Table)
+        .value(column, bitcode::encode(&recipe))
+        .and_where(Expr::col(MealPlanSlot::UserId).eq(&event.aggregate_id))
+        .and_where(Expr::col(MealPlanSlot::Date).eq(event.data.date))
+        .build_sqlx(SqliteQueryBuilder);
+
+    sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+        .execute(&pool)
+        .await?;
+
+    Ok(())
+}
+
+#[evento::subscription]
+async fn handle_leftovers_planned<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<LeftoversPlanned>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<sqlx::SqlitePool>();
+
+    for leftover in event.data.leftovers {
+        let (sql, values) = Query::update()
+            .table(MealPlanSlot::Table)
+            .value(MealPlanSlot::LeftoverOf, leftover.cooked_on)
+            .and_where(Expr::col(MealPlanSlot::UserId).eq(&event.aggregate_id))
+            .and_where(Expr::col(MealPlanSlot::Date).eq(leftover.date))
+            .build_sqlx(SqliteQueryBuilder);
+
+        sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+            .execute(&pool)
+            .await?;
+    }
+
+    Ok(())
+}
+
+#[evento::subscription]
+async fn handle_community_recipes_suggested<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<CommunityRecipesSuggested>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<sqlx::SqlitePool>();
+    let dates = event
+        .data
+        .suggestions
+        .iter()
+        .map(|suggestion| suggestion.date)
+        .collect::<Vec<_>>();
+
+    let (sql, values) = Query::update()
+        .table(MealPlanSlot::Table)
+        .value(MealPlanSlot::Suggested, true)
+        .and_where(Expr::col(MealPlanSlot::UserId).eq(&event.aggregate_id))
+        .and_where(Expr::col(MealPlanSlot::Date).is_in(dates))
+        .build_sqlx(SqliteQueryBuilder);
+
+    sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+        .execute(&pool)
+        .await?;
+
+    Ok(())
+}
+
+/// The week leaves the calendar, whatever was planned for it.
+#[evento::subscription]
+async fn handle_week_skipped<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<WeekSkipped>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<sqlx::SqlitePool>();
+    let Some(dates) = crate::mealplan::week_dates(event.data.week) else {
+        return Ok(());
+    };
+
+    let (sql, values) = Query::insert()
+        .into_table(MealPlanSkippedWeek::Table)
+        .columns([MealPlanSkippedWeek::UserId, MealPlanSkippedWeek::Week])
+        .values_panic([event.aggregate_id.to_owned().into(), dates[0].into()])
+        .on_conflict(
+            OnConflict::columns([MealPlanSkippedWeek::UserId, MealPlanSkippedWeek::Week])
+                .do_nothing()
+                .to_owned(),
+        )
+        .build_sqlx(SqliteQueryBuilder);
+
+    sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+        .execute(&pool)
+        .await?;
+
+    let (sql, values) = Query::delete()
+        .from_table(MealPlanSlot::Table)
+        .and_where(Expr::col(MealPlanSlot::UserId).eq(&event.aggregate_id))
+        .and_where(Expr::col(MealPlanSlot::Date).is_in(dates))
+        .build_sqlx(SqliteQueryBuilder);
+
+    sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+        .execute(&pool)
+        .await?;
+
+    Ok(())
+}
+
+#[evento::subscription]
+async fn handle_week_unskipped<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<WeekUnskipped>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<sqlx::SqlitePool>();
+
+    let (sql, values) = Query::delete()
+        .from_table(MealPlanSkippedWeek::Table)
+        .and_where(Expr::col(MealPlanSkippedWeek::UserId).eq(&event.aggregate_id))
+        .and_where(Expr::col(MealPlanSkippedWeek::Week).eq(event.data.week))
+        .build_sqlx(SqliteQueryBuilder);
+
+    sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+        .execute(&pool)
+        .await?;
+
+    Ok(())
+}
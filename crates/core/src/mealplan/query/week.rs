@@ -0,0 +1,99 @@
+use evento::Executor;
+use imkitchen_types::mealplan::DaySlotStatus;
+use imkitchen_types::recipe::RecipeType;
+use serde::Serialize;
+use time::{Duration, OffsetDateTime};
+
+use super::slot::SlotRow;
+
+/// A week of the plan as served by the JSON API.
+#[derive(Serialize, Debug)]
+pub struct Week {
+    /// First day of the week, as YYYYMMDD.
+    pub start: u64,
+    /// Planned days only, in order.
+    pub days: Vec<WeekDay>,
+    /// The week `start` falls in was skipped, nothing is planned for it.
+    pub skipped: bool,
+}
+
+#[derive(Serialize, Debug)]
+pub struct WeekDay {
+    /// YYYYMMDD.
+    pub date: u64,
+    pub household_size: u16,
+    pub courses: Vec<WeekCourse>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct WeekCourse {
+    pub recipe_type: RecipeType,
+    pub id: String,
+    pub name: String,
+    pub prep_time: u16,
+    pub cook_time: u16,
+    /// `Idle`, `Cooking` or `Completed`.
+    pub status: String,
+    /// Instruction being cooked, while `Cooking`.
+    pub step: Option<u8>,
+}
+
+impl<E: Executor> crate::mealplan::Module<E> {
+    /// The seven days starting at `start`.
+    pub async fn week(
+        &self,
+        user_id: impl Into<String>,
+        start: OffsetDateTime,
+    ) -> anyhow::Result<Week> {
+        let user_id = user_id.into();
+        let slots = self
+            .range(&user_id, start, start + Duration::days(6))
+            .await?;
+        let start = crate::mealplan::date_to_u64(start);
+        let skipped_weeks = self.skipped_weeks(&user_id).await?;
+        let skipped =
+            crate::mealplan::week_dates(start).is_some_and(|week| skipped_weeks.contains(&week[0]));
+
+        Ok(Week {
+            start,
+            skipped,
+            days: slots
+                .iter()
+                .map(to_week_day)
+                .collect::<anyhow::Result<_>>()?,
+        })
+    }
+}
+
+fn to_week_day(slot: &SlotRow) -> anyhow::Result<WeekDay> {
+    let courses = [
+        (RecipeType::Appetizer, slot.appetizer.as_ref()),
+        (RecipeType::MainCourse, Some(&slot.main_course)),
+        (RecipeType::Accompaniment, slot.accompaniment.as_ref()),
+        (RecipeType::Dessert, slot.dessert.as_ref()),
+        (RecipeType::Beverage, slot.beverage.as_ref()),
+        (RecipeType::Condiment, slot.condiment.as_ref()),
+    ]
+    .into_iter()
+    .filter_map(|(recipe_type, recipe)| {
+        recipe.map(|recipe| WeekCourse {
+            recipe_type,
+            id: recipe.id.to_owned(),
+            name: recipe.name.to_owned(),
+            prep_time: recipe.prep_time,
+            cook_time: recipe.cook_time,
+            status: recipe.status.to_string(),
+            step: match recipe.status {
+                DaySlotStatus::Cooking(step) => Some(step),
+                _ => None,
+            },
+        })
+    })
+    .collect();
+
+    Ok(WeekDay {
+        date: crate::mealplan::date_to_u64(OffsetDateTime::from_unix_timestamp(slot.day as i64)?),
+        household_size: slot.household_size,
+        courses,
+    })
+}
@@ -0,0 +1,61 @@
+use evento::Executor;
+use evento::cursor::Args;
+use evento::{Aggregate, EventFilter};
+use imkitchen_types::mealplan::{AdvancePrepMarked, MealPlan};
+
+pub struct MarkAdvancePrep {
+    pub user_id: String,
+    pub date: u64,
+    pub recipe_id: String,
+    pub done: bool,
+}
+
+impl<E: Executor> super::Module<E> {
+    /// Records whether the advance prep of a planned recipe (marinade,
+    /// soaking…) has been done. Cleared when the slot gets another recipe.
+    pub async fn mark_advance_prep(&self, input: MarkAdvancePrep) -> crate::Result<()> {
+        let Some(recipe) = self
+            .day_slot_recipe(&input.user_id, input.date, &input.recipe_id)
+            .await?
+        else {
+            crate::not_found!("slot recipe not found");
+        };
+
+        if recipe.advance_prep.trim().is_empty() {
+            crate::user!("This recipe has no advance prep");
+        }
+
+        if recipe.advance_prep_done == input.done {
+            return Ok(());
+        }
+
+        let last_event = self
+            .executor
+            .read(
+                Some(vec![EventFilter::by_id(
+                    MealPlan::aggregate_type(),
+                    &input.user_id,
+                )]),
+                None,
+                Args::backward(1, None),
+            )
+            .await?;
+
+        let Some(version) = last_event.edges.first().map(|e| e.node.version) else {
+            crate::not_found!("mealplan not found");
+        };
+
+        evento::append(&input.user_id)
+            .event(&AdvancePrepMarked {
+                date: input.date,
+                recipe_id: input.recipe_id,
+                done: input.done,
+            })
+            .original_version(version)
+            .requested_by(&input.user_id)
+            .commit(&self.executor)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,64 @@
+use evento::Executor;
+use evento::cursor::Args;
+use evento::{Aggregate, EventFilter};
+use imkitchen_types::mealplan::{DaySlotStatus, MealPlan, SlotRecipeStatusChanged};
+
+pub struct CompleteDay {
+    pub user_id: String,
+    pub date: u64,
+}
+
+impl<E: Executor> super::Module<E> {
+    /// Marks every course of a day that is still idle or being cooked as
+    /// completed, in a single commit. Courses already completed are left
+    /// alone, so completing a finished day does nothing.
+    pub async fn complete_day(&self, input: CompleteDay) -> crate::Result<()> {
+        let recipes = self.day_recipes(&input.user_id, input.date).await?;
+
+        if recipes.is_empty() {
+            crate::not_found!("slot not found");
+        }
+
+        let pending = recipes
+            .into_iter()
+            .filter(|recipe| !recipe.is_completed())
+            .collect::<Vec<_>>();
+
+        if pending.is_empty() {
+            return Ok(());
+        }
+
+        let last_event = self
+            .executor
+            .read(
+                Some(vec![EventFilter::by_id(
+                    MealPlan::aggregate_type(),
+                    &input.user_id,
+                )]),
+                None,
+                Args::backward(1, None),
+            )
+            .await?;
+
+        let Some(version) = last_event.edges.first().map(|e| e.node.version) else {
+            crate::not_found!("mealplan not found");
+        };
+
+        let mut builder = evento::append(&input.user_id)
+            .original_version(version)
+            .requested_by(&input.user_id)
+            .to_owned();
+
+        for recipe in pending {
+            builder.event(&SlotRecipeStatusChanged {
+                date: input.date,
+                recipe_id: recipe.id,
+                status: DaySlotStatus::Completed,
+            });
+        }
+
+        builder.commit(&self.executor).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+use evento::Executor;
+use imkitchen_types::mealplan::DaySlotStatus;
+use time::OffsetDateTime;
+
+pub struct AdvanceCookingStep {
+    pub user_id: String,
+    pub date: u64,
+    pub recipe_id: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CookingStep {
+    pub status: DaySlotStatus,
+    /// When the step just started is due to hand over to the next one, from
+    /// its `time_next` minutes. `None` when the step has no timer or the
+    /// recipe is completed.
+    pub next_step_at: Option<u64>,
+}
+
+impl<E: Executor> super::Module<E> {
+    /// Moves a planned recipe to its next instruction: an idle recipe starts
+    /// cooking at the first step, the last step completes it.
+    pub async fn advance_cooking_step(
+        &self,
+        input: AdvanceCookingStep,
+    ) -> crate::Result<CookingStep> {
+        let Some(recipe) = self
+            .day_recipes(&input.user_id, input.date)
+            .await?
+            .into_iter()
+            .find(|recipe| recipe.id == input.recipe_id)
+        else {
+            crate::not_found!("slot recipe in advance_cooking_step");
+        };
+
+        let instructions = crate::recipe::query::user::load(
+            &self.executor,
+            &self.read_db,
+            &self.write_db,
+            &input.recipe_id,
+        )
+        .await?
+        .map(|recipe| recipe.instructions.0)
+        .unwrap_or_default();
+
+        let Some(status) = recipe.status.next(instructions.len()) else {
+            crate::user!("This meal is already cooked");
+        };
+
+        self.change_slot_recipe_status(super::ChangeSlotRecipeStatus {
+            user_id: input.user_id,
+            date: input.date,
+            recipe_id: input.recipe_id,
+            status: status.to_owned(),
+        })
+        .await?;
+
+        let next_step_at = match status {
+            DaySlotStatus::Cooking(step) => instructions
+                .get(step as usize)
+                .filter(|instruction| instruction.time_next > 0)
+                .map(|instruction| {
+                    OffsetDateTime::now_utc().unix_timestamp() as u64
+                        + instruction.time_next as u64 * 60
+                }),
+            _ => None,
+        };
+
+        Ok(CookingStep {
+            status,
+            next_step_at,
+        })
+    }
+}
@@ -2,19 +2,458 @@ use evento::Executor;
 use evento::cursor::Args;
 use evento::{Aggregate, EventFilter};
 use imkitchen_db::mealplan_recipe::MealPlanRecipe;
-use imkitchen_types::mealplan::{DaysGenerated, MealPlan, Slot, SlotRecipe};
-use imkitchen_types::recipe::{DietaryRestriction, RecipeType};
+use imkitchen_db::recipe_user::RecipeUser;
+use imkitchen_db::shopping_recipe::ShoppingRecipe;
+use imkitchen_types::meal_preferences::UserConstraints;
+use imkitchen_types::mealplan::{
+    CommunityRecipesSuggested, DaysGenerated, Leftover, LeftoversPlanned, MealPlan, RecipeSnapshot,
+    RecipesSnapshotted, Seeded, Slot, SlotRecipe, Suggestion,
+};
+use imkitchen_types::recipe::{
+    AccompanimentType, Complexity, CuisineType, DietaryRestriction, Equipment, Ingredient,
+    RecipeType,
+};
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
-use sea_query::{Expr, ExprTrait, Func, IntoColumnRef, Query, SimpleExpr, SqliteQueryBuilder};
+use rand::{RngExt, SeedableRng};
+use sea_query::{
+    Alias, Expr, ExprTrait, IntoColumnRef, Query, SelectStatement, SqliteQueryBuilder,
+};
 use sea_query_sqlx::SqlxBinder;
+use sqlx::Row;
 use sqlx::prelude::FromRow;
-use time::{Duration, OffsetDateTime};
+use sqlx::types::Json;
+use std::collections::{HashMap, HashSet, VecDeque};
+use strum::VariantArray;
+use time::{Duration, OffsetDateTime, Weekday};
+
+use crate::mealplan::eligibility::Exclusion;
+
+use super::scorer::{
+    CuisineBalanceScorer, CuisineVarietyScorer, FreshnessScorer, RatingScorer, RecencyScorer,
+    Scorer, ScoringContext, combined_score, combined_weight,
+};
+
+pub(crate) const RECIPE_COLUMNS: [MealPlanRecipe; 15] = [
+    MealPlanRecipe::Id,
+    MealPlanRecipe::Name,
+    MealPlanRecipe::AcceptsAccompaniment,
+    MealPlanRecipe::AccompanimentType,
+    MealPlanRecipe::PreferredAccompanimentTypes,
+    MealPlanRecipe::DefaultAccompanimentIds,
+    MealPlanRecipe::ServingsYield,
+    MealPlanRecipe::Complexity,
+    MealPlanRecipe::IngredientCount,
+    MealPlanRecipe::InstructionCount,
+    MealPlanRecipe::PrepTime,
+    MealPlanRecipe::CookTime,
+    MealPlanRecipe::CuisineType,
+    MealPlanRecipe::Equipment,
+    MealPlanRecipe::Perishability,
+];
 
 #[derive(Clone, FromRow)]
 pub struct Recipe {
     pub id: String,
     pub name: String,
     pub accepts_accompaniment: bool,
+    pub accompaniment_type: Option<AccompanimentType>,
+    pub preferred_accompaniment_types: Json<Vec<AccompanimentType>>,
+    pub default_accompaniment_ids: Json<Vec<String>>,
+    pub servings_yield: u16,
+    /// The author's rating, see [`Recipe::complexity`].
+    #[sqlx(rename = "complexity")]
+    pub explicit_complexity: Option<Complexity>,
+    pub ingredient_count: u16,
+    pub instruction_count: u16,
+    pub prep_time: u16,
+    pub cook_time: u16,
+    pub cuisine_type: Option<CuisineType>,
+    pub equipment: Json<Vec<Equipment>>,
+    /// See [`imkitchen_types::recipe::IngredientCategory::max_perishability`].
+    pub perishability: u8,
+}
+
+impl Recipe {
+    /// The author's rating, or one estimated from the recipe's size.
+    pub fn complexity(&self) -> Complexity {
+        Complexity::resolve(
+            self.explicit_complexity,
+            self.ingredient_count.into(),
+            self.instruction_count.into(),
+            self.total_time(),
+        )
+    }
+
+    /// Prep and cook time, in minutes.
+    pub fn total_time(&self) -> u32 {
+        self.prep_time as u32 + self.cook_time as u32
+    }
+
+    /// Whether it can be cooked within a day's time `budget`, in minutes. A
+    /// day without a budget fits everything.
+    pub fn fits_time_budget(&self, budget: Option<u16>) -> bool {
+        budget.is_none_or(|budget| self.total_time() <= budget.into())
+    }
+}
+
+/// What is left to serve of each course before it starts over. Courses
+/// rotate independently, so three desserts come around again every three days
+/// while twenty main courses keep going.
+#[derive(Default)]
+struct RotationState<'a> {
+    queues: HashMap<RecipeType, VecDeque<&'a Recipe>>,
+}
+
+impl<'a> RotationState<'a> {
+    fn queue(&mut self, recipe_type: RecipeType) -> &mut VecDeque<&'a Recipe> {
+        self.queues.entry(recipe_type).or_default()
+    }
+
+    /// Next recipe of `recipe_type`, in the order of `recipes`. The course is
+    /// refilled from `recipes` once every one of them has been served. The
+    /// first one that `fits` wins; when none left does, the recipes already
+    /// served are added back behind them, and if still none fits the next
+    /// one is planned anyway.
+    fn select(
+        &mut self,
+        recipe_type: RecipeType,
+        recipes: &'a [Recipe],
+        fits: impl Fn(&Recipe) -> bool,
+    ) -> Option<&'a Recipe> {
+        let queue = self.queue(recipe_type);
+        if queue.is_empty() {
+            queue.extend(recipes);
+        }
+
+        if !queue.iter().any(|&recipe| fits(recipe)) {
+            for recipe in recipes {
+                if !queue.iter().any(|queued| queued.id == recipe.id) {
+                    queue.push_back(recipe);
+                }
+            }
+        }
+
+        let position = queue.iter().position(|&recipe| fits(recipe)).unwrap_or(0);
+        queue.remove(position)
+    }
+}
+
+/// Keeps a day's courses within the household's appliances: a recipe that
+/// would use an appliance more times than the kitchen has it that day is only
+/// planned when nothing else of its course fits. Recipes needing an appliance
+/// the kitchen doesn't have at all are left out by [`PoolFilter`].
+struct EquipmentConflictConstraint<'a> {
+    capacity: &'a HashMap<Equipment, u8>,
+    used: HashMap<Equipment, u8>,
+}
+
+impl<'a> EquipmentConflictConstraint<'a> {
+    fn new(capacity: &'a HashMap<Equipment, u8>) -> Self {
+        Self {
+            capacity,
+            used: HashMap::new(),
+        }
+    }
+
+    fn available(&self, item: &Equipment) -> u8 {
+        self.capacity
+            .get(item)
+            .copied()
+            .unwrap_or_else(|| item.default_capacity())
+    }
+
+    fn fits(&self, recipe: &Recipe) -> bool {
+        recipe
+            .equipment
+            .iter()
+            .all(|item| self.used.get(item).copied().unwrap_or_default() < self.available(item))
+    }
+
+    /// Counts the recipe once per appliance, like
+    /// [`crate::mealplan::conflict::EquipmentConflict`] does.
+    fn add(&mut self, recipe: &Recipe) {
+        for item in Equipment::VARIANTS {
+            if recipe.equipment.contains(item) {
+                *self.used.entry(*item).or_default() += 1;
+            }
+        }
+    }
+
+    /// Starts over for the next day.
+    fn clear(&mut self) {
+        self.used.clear();
+    }
+}
+
+/// Takes the next main course from `queue`. The first one `max_complexity`
+/// allows on `weekday` wins; skipped ones keep their place for a later day. When none fits,
+/// the recipes missing from the queue are added back behind them, and if
+/// still none fits the next one is planned anyway.
+///
+/// Among those that fit, the best of `ranking` wins: its `scorers` rank the
+/// recipes, its `tie_breakers` only order those they score the same, then
+/// the queue order decides. A recipe is never planned again only to score
+/// better.
+///
+/// Unlike the others, the day's time `budget` is never given up: recipes
+/// taking longer are skipped even when nothing else is left. The caller
+/// makes sure `recipes` has one that fits.
+///
+/// With `no_repeats`, nothing is added back before the queue runs out: the
+/// next recipe within budget is planned even if it is too hard, so every
+/// recipe comes around once per cycle.
+#[allow(clippy::too_many_arguments)]
+fn next_main_course<'a>(
+    queue: &mut VecDeque<&'a Recipe>,
+    recipes: &'a [Recipe],
+    max_complexity: &MaxComplexity,
+    ranking: &Ranking<'_>,
+    previous_cuisine: Option<&CuisineType>,
+    weekday: Weekday,
+    budget: Option<u16>,
+    no_repeats: bool,
+) -> Option<&'a Recipe> {
+    let in_time = |recipe: &Recipe| recipe.fits_time_budget(budget);
+    let (ratings, last_cooked) = (HashMap::new(), HashMap::new());
+
+    let fits = |queue: &VecDeque<&Recipe>| {
+        let context = ScoringContext {
+            recipe_type: RecipeType::MainCourse,
+            ratings: &ratings,
+            last_cooked: &last_cooked,
+            start: 0,
+            weekday: Some(weekday),
+            previous_cuisine,
+            queue,
+        };
+        let score = |recipe: &Recipe| {
+            (
+                combined_score(&ranking.scorers, recipe, &context),
+                combined_score(&ranking.tie_breakers, recipe, &context),
+            )
+        };
+
+        queue
+            .iter()
+            .enumerate()
+            .filter(|(_, recipe)| max_complexity.allows(recipe, weekday) && in_time(recipe))
+            .map(|(position, recipe)| (position, score(recipe)))
+            .max_by(|(a_position, a), (b_position, b)| {
+                a.0.total_cmp(&b.0)
+                    .then_with(|| a.1.total_cmp(&b.1))
+                    .then_with(|| b_position.cmp(a_position))
+            })
+            .map(|(position, _)| position)
+    };
+
+    let mut position = fits(queue);
+    if position.is_none() && no_repeats {
+        position = queue.iter().position(|recipe| in_time(recipe));
+    }
+
+    let position = match position {
+        Some(position) => position,
+        None => {
+            for recipe in recipes {
+                if !queue.iter().any(|queued| queued.id == recipe.id) {
+                    queue.push_back(recipe);
+                }
+            }
+            fits(queue)
+                .or_else(|| queue.iter().position(|recipe| in_time(recipe)))
+                .unwrap_or(0)
+        }
+    };
+
+    queue.remove(position)
+}
+
+/// Scorers ranking the main courses of a day, see [`next_main_course`].
+struct Ranking<'s> {
+    scorers: Vec<(&'s dyn Scorer, f32)>,
+    tie_breakers: Vec<(&'s dyn Scorer, f32)>,
+}
+
+impl Ranking<'static> {
+    /// Cuisines spread over the plan and, unless `cuisine_variety_weight`
+    /// lets it, never twice in a row; then the freshest recipes first.
+    fn main_course(cuisine_variety_weight: f32) -> Self {
+        Self {
+            scorers: vec![
+                (&CuisineBalanceScorer as &dyn Scorer, 1.0),
+                (&CuisineVarietyScorer, cuisine_variety_weight),
+            ],
+            tie_breakers: vec![(&FreshnessScorer as &dyn Scorer, 1.0)],
+        }
+    }
+}
+
+/// Picks the accompaniment served with `main`. The main's default
+/// accompaniments win when they are among the candidates (in the main's
+/// order), then candidates of a type the main prefers (in the main's order of
+/// preference), then candidates sharing the category of a preferred type,
+/// then whichever comes first.
+pub fn select_accompaniment<'a>(main: &Recipe, candidates: &'a [Recipe]) -> Option<&'a Recipe> {
+    let preferred = main.preferred_accompaniment_types.as_slice();
+
+    main.default_accompaniment_ids
+        .iter()
+        .find_map(|id| candidates.iter().find(|c| &c.id == id))
+        .or_else(|| {
+            preferred.iter().find_map(|t| {
+                candidates
+                    .iter()
+                    .find(|c| c.accompaniment_type.as_ref() == Some(t))
+            })
+        })
+        .or_else(|| {
+            preferred.iter().find_map(|t| {
+                candidates.iter().find(|c| {
+                    c.accompaniment_type
+                        .is_some_and(|kind| kind.category() == t.category())
+                })
+            })
+        })
+        .or_else(|| candidates.first())
+}
+
+/// Rules a recipe must meet to be planned at all. They are applied in SQL so
+/// that the candidate limit and the variety share are taken from recipes
+/// that can actually be planned.
+#[derive(Default, Clone)]
+pub struct PoolFilter {
+    pub dietary_restrictions: Vec<DietaryRestriction>,
+    /// Leaves out recipes meant for a bigger household, `None` keeps them.
+    pub household_size: Option<u16>,
+    /// Leaves out recipes needing an appliance the kitchen doesn't have,
+    /// see [`Equipment::default_capacity`].
+    pub equipment_capacity: HashMap<Equipment, u8>,
+}
+
+impl From<&Generate> for PoolFilter {
+    fn from(value: &Generate) -> Self {
+        Self {
+            dietary_restrictions: value
+                .randomize
+                .as_ref()
+                .map(|opts| opts.dietary_restrictions.to_vec())
+                .unwrap_or_default(),
+            household_size: (!value.scale_down).then_some(value.household_size),
+            equipment_capacity: value.equipment_capacity.clone(),
+        }
+    }
+}
+
+impl PoolFilter {
+    /// Every rule as the condition a `meal_plan_recipe` row must meet, with
+    /// the exclusion reported when it doesn't. Shared with
+    /// [`super::Module::plan_eligibility`].
+    pub(crate) fn rules(&self) -> Vec<(Exclusion, Expr)> {
+        let mut rules = self
+            .dietary_restrictions
+            .iter()
+            .map(|restriction| {
+                (
+                    Exclusion::DietaryRestriction(restriction.clone()),
+                    satisfies_dietary_restriction(restriction),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        if let Some(household_size) = self.household_size {
+            rules.push((
+                Exclusion::HouseholdSize(household_size),
+                Expr::col(MealPlanRecipe::MinHouseholdSize).lte(household_size),
+            ));
+        }
+
+        rules.extend(
+            self.missing_equipment()
+                .into_iter()
+                .map(|item| (Exclusion::Equipment(item), uses_equipment(&[item]).not())),
+        );
+
+        rules
+    }
+
+    /// Keeps the `meal_plan_recipe` rows meeting every rule.
+    pub(crate) fn apply(&self, statement: &mut SelectStatement) {
+        for (_, rule) in self.rules() {
+            statement.and_where(rule);
+        }
+    }
+
+    /// Appliances the kitchen doesn't have.
+    pub(crate) fn missing_equipment(&self) -> Vec<Equipment> {
+        Equipment::VARIANTS
+            .iter()
+            .filter(|item| {
+                self.equipment_capacity
+                    .get(item)
+                    .copied()
+                    .unwrap_or_else(|| item.default_capacity())
+                    == 0
+            })
+            .copied()
+            .collect()
+    }
+}
+
+/// Matches rows whose `equipment` includes one of `items`.
+pub(crate) fn uses_equipment(items: &[Equipment]) -> Expr {
+    Expr::cust_with_values(
+        format!(
+            "EXISTS (SELECT 1 FROM json_each(equipment) WHERE value IN ({}))",
+            vec!["?"; items.len()].join(", ")
+        ),
+        items
+            .iter()
+            .map(|item| sea_query::Value::String(Some(item.to_string())))
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Keeps the `meal_plan_recipe` rows compatible with every one of
+/// `dietary_restrictions`.
+pub(crate) fn filter_by_dietary_restrictions(
+    statement: &mut SelectStatement,
+    dietary_restrictions: &[DietaryRestriction],
+) {
+    for restriction in dietary_restrictions {
+        statement.and_where(satisfies_dietary_restriction(restriction));
+    }
+}
+
+/// Matches rows whose `dietary_restrictions` tags suit `restriction`, see
+/// [`DietaryRestriction::satisfied_by`].
+pub(crate) fn satisfies_dietary_restriction(restriction: &DietaryRestriction) -> Expr {
+    let tags = restriction.satisfied_by();
+
+    Expr::cust_with_values(
+        format!(
+            "EXISTS (SELECT 1 FROM json_each(dietary_restrictions) WHERE value IN ({}))",
+            vec!["?"; tags.len()].join(", ")
+        ),
+        tags.iter()
+            .map(|tag| sea_query::Value::String(Some(tag.to_string())))
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Orders `items` so that each one comes first with a probability proportional
+/// to its weight (Efraimidis–Spirakis: sort by `u^(1/w)`, highest first).
+fn weighted_shuffle<T>(rng: &mut StdRng, items: Vec<T>, weight: impl Fn(&T) -> f32) -> Vec<T> {
+    let mut keyed = items
+        .into_iter()
+        .map(|item| {
+            let key = rng.random::<f64>().powf(1.0 / weight(&item) as f64);
+            (key, item)
+        })
+        .collect::<Vec<_>>();
+
+    keyed.sort_by(|a, b| b.0.total_cmp(&a.0));
+    keyed.into_iter().map(|(_, item)| item).collect()
 }
 
 impl From<&Recipe> for SlotRecipe {
@@ -29,38 +468,282 @@ impl From<&Recipe> for SlotRecipe {
 pub struct Randomize {
     pub cuisine_variety_weight: f32,
     pub dietary_restrictions: Vec<imkitchen_types::recipe::DietaryRestriction>,
+    /// How strongly higher rated recipes are favored; 0 ignores ratings.
+    pub rating_weight: f32,
+    /// How strongly recipes cooked in the last few weeks are avoided, see
+    /// [`RecencyScorer`]; 0 ignores cooking history.
+    pub recency_weight: f32,
+    /// Fixed seed for reproducible plans; a random one is drawn when `None`.
+    pub seed: Option<u64>,
+    /// 0 always picks the best scored recipes, 1 draws in proportion to the
+    /// scores, in between leans more or less towards the best.
+    pub randomness: f32,
 }
 
+impl From<&UserConstraints> for Randomize {
+    fn from(value: &UserConstraints) -> Self {
+        Self {
+            cuisine_variety_weight: value.cuisine_variety_weight,
+            dietary_restrictions: value.dietary_restrictions.to_vec(),
+            rating_weight: value.rating_weight,
+            recency_weight: value.recency_weight,
+            seed: None,
+            randomness: value.randomness,
+        }
+    }
+}
+
+#[derive(Default)]
 pub struct Generate {
     pub user_id: String,
     pub start: u64,
     pub days: u8,
     pub randomize: Option<Randomize>,
     pub household_size: u16,
+    /// Date (YYYYMMDD) → number of people, for days cooked for more (or
+    /// fewer) than the household.
+    pub guests: HashMap<u64, u16>,
+    /// Keeps the planned recipes' ingredients as they are now, so editing a
+    /// recipe mid-week leaves this plan's shopping list alone.
+    pub snapshot_recipes: bool,
+    /// Also plan recipes meant for a bigger household than this one.
+    pub scale_down: bool,
+    /// (date YYYYMMDD, course) → recipe id planned there whatever the
+    /// selection would pick. The other slots are filled around them. Pins
+    /// are still held to the dietary restrictions and household size.
+    pub pinned: HashMap<(u64, RecipeType), String>,
+    /// Plans what's left of a big batch for the next days instead of cooking
+    /// something new.
+    pub allow_leftovers: bool,
+    /// Hardest main course planned each day while easier ones are left.
+    pub max_complexity: MaxComplexity,
+    /// Courses not planned unless pinned. Skipping the main course is
+    /// rejected, every day is built around one.
+    pub skipped_courses: Vec<RecipeType>,
+    /// Overrides [`Equipment::default_capacity`], see
+    /// [`UserConstraints::equipment_capacity`].
+    pub equipment_capacity: HashMap<Equipment, u8>,
+    /// Minutes available for each day's main course, Monday first, see
+    /// [`UserConstraints::time_budget`]. Pinned main courses and leftovers
+    /// aren't held to it.
+    pub time_budget: [Option<u16>; 7],
+    /// Plans every main course of the pool before any of them comes back,
+    /// however many weeks `days` covers, so repeats are spread evenly when
+    /// the pool is smaller than the plan. Only the time budget can bring a
+    /// recipe back early.
+    pub no_repeats: bool,
+    /// Main courses per week taken from the best rated community recipes
+    /// the user hasn't saved, see [`UserConstraints::community_suggestions`].
+    /// They are spread over the days the rotation would otherwise fill and
+    /// held to the same dietary restrictions, complexity and time budget.
+    pub community_suggestions: u8,
+}
+
+/// Complexity caps for busy weeknights and free weekends. Days over their cap
+/// still get a recipe when nothing easier is available.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MaxComplexity {
+    /// Monday to Friday.
+    pub weekday: Complexity,
+    /// Saturday and Sunday.
+    pub weekend: Complexity,
+}
+
+impl Default for MaxComplexity {
+    fn default() -> Self {
+        Self {
+            weekday: Complexity::Moderate,
+            weekend: Complexity::Advanced,
+        }
+    }
+}
+
+impl From<&UserConstraints> for MaxComplexity {
+    fn from(value: &UserConstraints) -> Self {
+        Self {
+            weekday: value.max_weekday_complexity,
+            weekend: value.max_weekend_complexity,
+        }
+    }
+}
+
+impl MaxComplexity {
+    pub fn on(&self, weekday: Weekday) -> Complexity {
+        match weekday {
+            Weekday::Saturday | Weekday::Sunday => self.weekend,
+            _ => self.weekday,
+        }
+    }
+
+    /// Whether `recipe` is no harder than the cap of `weekday`.
+    pub fn allows(&self, recipe: &Recipe, weekday: Weekday) -> bool {
+        recipe.complexity() <= self.on(weekday)
+    }
+}
+
+impl From<&UserConstraints> for Generate {
+    /// Everything the user's constraints decide; the user, the days and
+    /// `snapshot_recipes` are left to the caller.
+    fn from(value: &UserConstraints) -> Self {
+        Self {
+            randomize: Some(Randomize::from(value)),
+            household_size: value.household_size,
+            scale_down: value.scale_down,
+            allow_leftovers: value.allow_leftovers,
+            max_complexity: MaxComplexity::from(value),
+            skipped_courses: value.skipped_courses.to_vec(),
+            equipment_capacity: value.equipment_capacity.clone(),
+            time_budget: value.time_budget,
+            community_suggestions: value.community_suggestions,
+            ..Default::default()
+        }
+    }
 }
 
 impl<E: Executor> super::Module<E> {
     pub async fn generate(&self, input: Generate) -> crate::Result<()> {
-        let main_course_recipes = match input.randomize.as_ref() {
+        if input.guests.values().any(|guests| *guests == 0) {
+            crate::user!("Guest count must be at least 1");
+        }
+
+        if input.skipped_courses.contains(&RecipeType::MainCourse) {
+            crate::user!("Main course can't be skipped");
+        }
+
+        // Drawn up front rather than seeding from entropy so it can be
+        // recorded with the plan.
+        let seed = input
+            .randomize
+            .as_ref()
+            .and_then(|opts| opts.seed)
+            .unwrap_or_else(rand::random);
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        let filter = PoolFilter::from(&input);
+
+        // Skipped weeks take no slot and don't count towards `days`; the
+        // plan carries on after them.
+        let skipped_weeks = self
+            .skipped_weeks(&input.user_id)
+            .await?
+            .into_iter()
+            .collect::<HashSet<_>>();
+        let pinned = self
+            .pinned_recipes(
+                &input.user_id,
+                &input.pinned,
+                &crate::mealplan::plan_dates(
+                    OffsetDateTime::from_unix_timestamp(input.start as i64)?,
+                    input.days as usize,
+                    &skipped_weeks,
+                ),
+                &filter,
+            )
+            .await?;
+
+        let mut main_course_recipes = match input.randomize.as_ref() {
             Some(opts) => {
                 self.random(
+                    &mut rng,
                     &input.user_id,
                     RecipeType::MainCourse,
-                    opts.cuisine_variety_weight,
-                    opts.dietary_restrictions.to_vec(),
+                    // The whole pool is needed to rotate through all of it.
+                    if input.no_repeats {
+                        1.0
+                    } else {
+                        opts.cuisine_variety_weight
+                    },
+                    opts.rating_weight,
+                    opts.recency_weight,
+                    input.start,
+                    opts.randomness,
+                    &filter,
+                    (!input.no_repeats).then_some(7 * 5),
                 )
                 .await?
             }
             _ => {
-                self.first_week_recipes(&input.user_id, RecipeType::MainCourse)
+                self.first_week_recipes(&input.user_id, RecipeType::MainCourse, &filter)
                     .await?
             }
         };
 
+        let mut equipment = EquipmentConflictConstraint::new(&input.equipment_capacity);
+
+        // Best rated first, see `community_recipes`.
+        let community_recipes = if input.community_suggestions > 0 {
+            self.community_recipes(&input.user_id, &filter).await?
+        } else {
+            vec![]
+        };
+
+        // Pinned main courses come up on their own day, don't plan them twice
+        // unless there is nothing else.
+        if main_course_recipes
+            .iter()
+            .any(|r| !pinned.values().any(|p| p.id == r.id))
+        {
+            main_course_recipes.retain(|r| !pinned.values().any(|p| p.id == r.id));
+        }
+
         if main_course_recipes.is_empty() {
+            if !self.has_recipes(&input.user_id).await? {
+                return Err(crate::Error::NoFavorites);
+            }
+
             crate::user!("No main course found");
         }
 
+        // Drawn once for the whole plan and rotated through, see
+        // `RotationState`.
+        let appetizer_recipes = match input.randomize.as_ref() {
+            _ if input.skipped_courses.contains(&RecipeType::Appetizer) => vec![],
+            Some(opts) => {
+                self.random(
+                    &mut rng,
+                    &input.user_id,
+                    RecipeType::Appetizer,
+                    1.0,
+                    opts.rating_weight,
+                    opts.recency_weight,
+                    input.start,
+                    opts.randomness,
+                    &filter,
+                    Some(7 * 5),
+                )
+                .await?
+            }
+            _ => {
+                // self.first_week_recipes(&input.user_id, RecipeType::Appetizer)
+                //     .await?
+                vec![]
+            }
+        };
+
+        let dessert_recipes = match input.randomize.as_ref() {
+            _ if input.skipped_courses.contains(&RecipeType::Dessert) => vec![],
+            Some(opts) => {
+                self.random(
+                    &mut rng,
+                    &input.user_id,
+                    RecipeType::Dessert,
+                    1.0,
+                    opts.rating_weight,
+                    opts.recency_weight,
+                    input.start,
+                    opts.randomness,
+                    &filter,
+                    Some(7 * 5),
+                )
+                .await?
+            }
+            _ => {
+                // self.first_week_recipes(&input.user_id, RecipeType::Dessert)
+                //     .await?
+                vec![]
+            }
+        };
+
         let last_event = self
             .executor
             .read(
@@ -79,46 +762,148 @@ impl<E: Executor> super::Module<E> {
             .map(|e| e.node.version)
             .unwrap_or_default();
 
-        let mut main_course_recipes = main_course_recipes.iter().cycle().take(input.days as usize);
+        let mut rotation = RotationState::default();
         let mut builder = evento::append(&input.user_id)
             .original_version(version)
             .requested_by(&input.user_id)
             .to_owned();
 
         let mut slots = vec![];
+        let mut leftovers = vec![];
+        let mut suggestions: Vec<Suggestion> = vec![];
+        // Main course cooked earlier with servings still left: (recipe,
+        // cooked on, servings left).
+        let mut batch: Option<(&Recipe, u64, u16)> = None;
+        let mut previous_cuisine = None;
+        let ranking = Ranking::main_course(
+            input
+                .randomize
+                .as_ref()
+                .map_or(1.0, |opts| opts.cuisine_variety_weight),
+        );
+        let mut offset = 0;
+        // First and last day pushed past the requested range by a skipped
+        // week.
+        let mut spilled: Option<(OffsetDateTime, OffsetDateTime)> = None;
 
-        while let Some(recipe) = main_course_recipes.by_ref().next() {
-            let day = OffsetDateTime::from_unix_timestamp(input.start as i64)?
-                + Duration::days((slots.len()) as i64);
+        while slots.len() < input.days as usize {
+            let day =
+                OffsetDateTime::from_unix_timestamp(input.start as i64)? + Duration::days(offset);
+            offset += 1;
 
             let date = crate::mealplan::date_to_u64(day);
+            if crate::mealplan::week_dates(date)
+                .is_some_and(|week| skipped_weeks.contains(&week[0]))
+            {
+                // Nobody is home to eat a batch cooked before.
+                batch = None;
+                continue;
+            }
 
-            let appetizer_recipes = match input.randomize.as_ref() {
-                Some(opts) => {
-                    self.random(
-                        &input.user_id,
-                        RecipeType::Appetizer,
-                        1.0,
-                        opts.dietary_restrictions.to_vec(),
-                    )
-                    .await?
+            if offset > input.days as i64 {
+                spilled = Some((spilled.map_or(day, |(from, _)| from), day));
+            }
+
+            let household_size = input
+                .guests
+                .get(&date)
+                .copied()
+                .unwrap_or(input.household_size);
+            let pinned_main = pinned.get(&(date, RecipeType::MainCourse));
+
+            let leftover = match batch.take() {
+                Some((recipe, cooked_on, servings))
+                    if pinned_main.is_none() && servings >= household_size =>
+                {
+                    leftovers.push(Leftover {
+                        date,
+                        recipe_id: recipe.id.to_owned(),
+                        cooked_on,
+                    });
+                    batch = Some((recipe, cooked_on, servings - household_size))
+                        .filter(|(_, _, servings)| *servings > 0);
+
+                    Some(recipe)
                 }
-                _ => {
-                    // self.first_week_recipes(&input.user_id, RecipeType::Appetizer)
-                    //     .await?
-                    vec![]
+                _ => None,
+            };
+
+            let recipe = match pinned_main.or(leftover) {
+                Some(recipe) => recipe,
+                None => {
+                    let budget =
+                        input.time_budget[day.weekday().number_days_from_monday() as usize];
+
+                    // Spread over the plan; a day taken by a pin or leftovers
+                    // hands its suggestion on to the next one.
+                    let due =
+                        ((slots.len() + 1) * input.community_suggestions as usize).div_ceil(7);
+                    let suggestion = community_recipes.iter().find(|recipe| {
+                        suggestions.len() < due
+                            && input.max_complexity.allows(recipe, day.weekday())
+                            && recipe.fits_time_budget(budget)
+                            && !suggestions.iter().any(|s| s.recipe_id == recipe.id)
+                    });
+
+                    if let Some(recipe) = suggestion {
+                        suggestions.push(Suggestion {
+                            date,
+                            recipe_id: recipe.id.to_owned(),
+                        });
+
+                        recipe
+                    } else {
+                        if let Some(budget) = budget
+                            && !main_course_recipes
+                                .iter()
+                                .any(|recipe| recipe.fits_time_budget(Some(budget)))
+                        {
+                            crate::user!(
+                                "No main course fits the {budget} minutes available on {}",
+                                day.weekday()
+                            );
+                        }
+
+                        let Some(recipe) = next_main_course(
+                            rotation.queue(RecipeType::MainCourse),
+                            &main_course_recipes,
+                            &input.max_complexity,
+                            &ranking,
+                            previous_cuisine,
+                            day.weekday(),
+                            budget,
+                            input.no_repeats,
+                        ) else {
+                            break;
+                        };
+
+                        recipe
+                    }
                 }
             };
+            previous_cuisine = recipe.cuisine_type.as_ref();
+            equipment.clear();
+            equipment.add(recipe);
 
-            let mut appetizer_recipes = appetizer_recipes.iter();
+            if leftover.is_none() && input.allow_leftovers && recipe.servings_yield > household_size
+            {
+                batch = Some((recipe, date, recipe.servings_yield - household_size));
+            }
 
             let accompaniment_recipes = match input.randomize.as_ref() {
+                _ if input.skipped_courses.contains(&RecipeType::Accompaniment) => vec![],
                 Some(opts) => {
                     self.random(
+                        &mut rng,
                         &input.user_id,
                         RecipeType::Accompaniment,
                         1.0,
-                        opts.dietary_restrictions.to_vec(),
+                        opts.rating_weight,
+                        opts.recency_weight,
+                        input.start,
+                        opts.randomness,
+                        &filter,
+                        Some(7 * 5),
                     )
                     .await?
                 }
@@ -129,40 +914,48 @@ impl<E: Executor> super::Module<E> {
                 }
             };
 
-            let mut accompaniment_recipes = accompaniment_recipes.iter();
+            // Accompaniments the kitchen can still cook alongside the main
+            // course go first.
+            let fitting = accompaniment_recipes
+                .iter()
+                .filter(|r| equipment.fits(r))
+                .cloned()
+                .collect::<Vec<_>>();
+            let accompaniment = if let Some(pinned) = pinned.get(&(date, RecipeType::Accompaniment))
+            {
+                Some(pinned)
+            } else if recipe.accepts_accompaniment && input.randomize.is_some() {
+                select_accompaniment(recipe, &fitting)
+                    .or_else(|| select_accompaniment(recipe, &accompaniment_recipes))
+            } else {
+                None
+            };
+            if let Some(accompaniment) = accompaniment {
+                equipment.add(accompaniment);
+            }
 
-            let dessert_recipes = match input.randomize.as_ref() {
-                Some(opts) => {
-                    self.random(
-                        &input.user_id,
-                        RecipeType::Dessert,
-                        1.0,
-                        opts.dietary_restrictions.to_vec(),
-                    )
-                    .await?
+            let mut pinned_or_next = |recipe_type: RecipeType, recipes| {
+                let recipe = match pinned.get(&(date, recipe_type.clone())) {
+                    Some(pinned) => Some(pinned),
+                    None => rotation.select(recipe_type, recipes, |r| equipment.fits(r)),
+                };
+                if let Some(recipe) = recipe {
+                    equipment.add(recipe);
                 }
-                _ => {
-                    // self.first_week_recipes(&input.user_id, RecipeType::Dessert)
-                    //     .await?
-                    vec![]
-                }
-            };
-            let mut dessert_recipes = dessert_recipes.iter();
 
-            let accompaniment = if recipe.accepts_accompaniment && input.randomize.is_some() {
-                accompaniment_recipes.next().map(|r| r.into())
-            } else {
-                None
+                recipe.map(SlotRecipe::from)
             };
+            let appetizer = pinned_or_next(RecipeType::Appetizer, appetizer_recipes.as_slice());
+            let dessert = pinned_or_next(RecipeType::Dessert, dessert_recipes.as_slice());
 
             slots.push(Slot {
                 day: day.unix_timestamp() as u64,
                 date,
-                household_size: input.household_size,
-                appetizer: appetizer_recipes.next().map(|r| r.into()),
+                household_size,
+                appetizer,
                 main_course: recipe.into(),
-                dessert: dessert_recipes.next().map(|r| r.into()),
-                accompaniment,
+                dessert,
+                accompaniment: accompaniment.map(SlotRecipe::from),
                 beverage: None,
                 condiment: None,
             });
@@ -172,37 +965,270 @@ impl<E: Executor> super::Module<E> {
             crate::user!("No slots generated");
         }
 
+        if let Some((from, to)) = spilled
+            && !self.range(&input.user_id, from, to).await?.is_empty()
+        {
+            crate::user!("The days after the skipped week are already planned");
+        }
+
+        let dates = slots.iter().map(|slot| slot.date).collect::<Vec<_>>();
+        crate::mealplan::check_contiguous_dates(
+            OffsetDateTime::from_unix_timestamp(input.start as i64)?,
+            input.days as usize,
+            &dates,
+            &skipped_weeks,
+        )?;
+
+        let recipes = if input.snapshot_recipes {
+            self.snapshot_recipes(&slots).await?
+        } else {
+            vec![]
+        };
+
         builder.event(&DaysGenerated {
             slots,
             start: input.start,
             household_size: input.household_size,
         });
 
+        if input.snapshot_recipes {
+            builder.event(&RecipesSnapshotted { recipes });
+        }
+
+        if !leftovers.is_empty() {
+            builder.event(&LeftoversPlanned { leftovers });
+        }
+
+        if !suggestions.is_empty() {
+            builder.event(&CommunityRecipesSuggested { suggestions });
+        }
+
+        builder.event(&Seeded { seed });
+
         builder.commit(&self.executor).await?;
 
         Ok(())
     }
 
+    async fn snapshot_recipes(&self, slots: &[Slot]) -> crate::Result<Vec<RecipeSnapshot>> {
+        let ids = slots
+            .iter()
+            .flat_map(|slot| {
+                [
+                    slot.appetizer.as_ref(),
+                    Some(&slot.main_course),
+                    slot.accompaniment.as_ref(),
+                    slot.dessert.as_ref(),
+                    slot.beverage.as_ref(),
+                    slot.condiment.as_ref(),
+                ]
+            })
+            .flatten()
+            .map(|recipe| recipe.id.to_owned())
+            .collect::<HashSet<_>>();
+
+        let statement = Query::select()
+            .columns([
+                ShoppingRecipe::Id,
+                ShoppingRecipe::HouseholdSize,
+                ShoppingRecipe::Ingredients,
+            ])
+            .from(ShoppingRecipe::Table)
+            .and_where(Expr::col(ShoppingRecipe::Id).is_in(ids))
+            .order_by(ShoppingRecipe::Id, sea_query::Order::Asc)
+            .to_owned();
+
+        let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+
+        Ok(sqlx::query_as_with::<
+            _,
+            (String, u16, evento::sql_types::Bitcode<Vec<Ingredient>>),
+            _,
+        >(sqlx::AssertSqlSafe(sql), values)
+        .fetch_all(&self.read_db)
+        .await?
+        .into_iter()
+        .map(|(id, household_size, ingredients)| RecipeSnapshot {
+            id,
+            household_size,
+            ingredients: ingredients.0,
+        })
+        .collect())
+    }
+
+    /// Loads every pinned recipe, before anything is planned. Fails on the
+    /// first pin outside the plan's `dates`, that isn't one of the user's
+    /// recipes of that course, or that the dietary restrictions or household
+    /// size of `filter` rule out.
+    async fn pinned_recipes(
+        &self,
+        user_id: &str,
+        pinned: &HashMap<(u64, RecipeType), String>,
+        dates: &[u64],
+        filter: &PoolFilter,
+    ) -> crate::Result<HashMap<(u64, RecipeType), Recipe>> {
+        // The kitchen's equipment is only balanced around pins, see
+        // `EquipmentConflictConstraint`.
+        let rules = filter
+            .rules()
+            .into_iter()
+            .filter(|(exclusion, _)| !matches!(exclusion, Exclusion::Equipment(_)))
+            .collect::<Vec<_>>();
+
+        let mut recipes = HashMap::new();
+        for ((date, recipe_type), id) in pinned {
+            if !dates.contains(date) {
+                crate::user!("Pinned day {date} is outside the plan");
+            }
+
+            let mut statement = Query::select()
+                .columns(RECIPE_COLUMNS)
+                .from(MealPlanRecipe::Table)
+                .and_where(Expr::col(MealPlanRecipe::UserId).eq(user_id))
+                .and_where(Expr::col(MealPlanRecipe::Id).eq(id))
+                .and_where(Expr::col(MealPlanRecipe::RecipeType).eq(recipe_type.to_string()))
+                .and_where(Expr::col(MealPlanRecipe::Name).not_equals(""))
+                .limit(1)
+                .to_owned();
+
+            for (index, (_, rule)) in rules.iter().enumerate() {
+                statement.expr_as(rule.clone(), Alias::new(format!("rule_{index}")));
+            }
+
+            let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+            let Some(row) = sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+                .fetch_optional(&self.read_db)
+                .await?
+            else {
+                crate::user!("Pinned {recipe_type} on {date} is not one of your recipes");
+            };
+
+            for (index, (exclusion, _)) in rules.iter().enumerate() {
+                if row.try_get::<bool, _>(format!("rule_{index}").as_str())? {
+                    continue;
+                }
+
+                match exclusion {
+                    Exclusion::DietaryRestriction(restriction) => {
+                        crate::user!("Pinned {recipe_type} on {date} is not {restriction}")
+                    }
+                    Exclusion::HouseholdSize(household_size) => crate::user!(
+                        "Pinned {recipe_type} on {date} is meant for more than {household_size} people"
+                    ),
+                    _ => {}
+                }
+            }
+
+            recipes.insert((*date, recipe_type.clone()), Recipe::from_row(&row)?);
+        }
+
+        Ok(recipes)
+    }
+
+    /// Main courses other users share with the community that `user_id`
+    /// hasn't saved and `filter` keeps, highest average rating first.
+    async fn community_recipes(
+        &self,
+        user_id: &str,
+        filter: &PoolFilter,
+    ) -> crate::Result<Vec<Recipe>> {
+        // The owner's row, favorites of other users copy it.
+        let shared = Query::select()
+            .columns([RecipeUser::Id, RecipeUser::OwnerId])
+            .from(RecipeUser::Table)
+            .and_where(Expr::col(RecipeUser::IsShared).eq(true))
+            .and_where(Expr::col(RecipeUser::OwnerId).ne(user_id))
+            .to_owned();
+
+        let saved = Query::select()
+            .column(MealPlanRecipe::Id)
+            .from(MealPlanRecipe::Table)
+            .and_where(Expr::col(MealPlanRecipe::UserId).eq(user_id))
+            .to_owned();
+
+        let mut statement = Query::select()
+            .columns(RECIPE_COLUMNS)
+            .from(MealPlanRecipe::Table)
+            .and_where(
+                Expr::tuple([
+                    Expr::col(MealPlanRecipe::Id),
+                    Expr::col(MealPlanRecipe::UserId),
+                ])
+                .in_subquery(shared),
+            )
+            .and_where(Expr::col(MealPlanRecipe::Id).not_in_subquery(saved))
+            .and_where(Expr::col(MealPlanRecipe::RecipeType).eq(RecipeType::MainCourse.to_string()))
+            .and_where(Expr::col(MealPlanRecipe::Name).not_equals(""))
+            .order_by_expr(
+                Expr::cust(
+                    "COALESCE((SELECT average_rating FROM recipe_rating_stat \
+                     WHERE recipe_rating_stat.id = meal_plan_recipe.id), 0.0)",
+                ),
+                sea_query::Order::Desc,
+            )
+            .order_by_expr(
+                Expr::cust(
+                    "COALESCE((SELECT rating_count FROM recipe_rating_stat \
+                     WHERE recipe_rating_stat.id = meal_plan_recipe.id), 0)",
+                ),
+                sea_query::Order::Desc,
+            )
+            .order_by(MealPlanRecipe::Id, sea_query::Order::Asc)
+            .limit(7 * 5)
+            .to_owned();
+
+        filter.apply(&mut statement);
+
+        let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+
+        Ok(
+            sqlx::query_as_with::<_, Recipe, _>(sqlx::AssertSqlSafe(sql), values)
+                .fetch_all(&self.read_db)
+                .await?,
+        )
+    }
+
+    /// Whether the user has any recipe generation could pick from, of any
+    /// course and whatever their restrictions.
+    async fn has_recipes(&self, user_id: &str) -> crate::Result<bool> {
+        let statement = Query::select()
+            .expr(Expr::val(1))
+            .from(MealPlanRecipe::Table)
+            .and_where(Expr::col(MealPlanRecipe::UserId).eq(user_id))
+            .and_where(Expr::col(MealPlanRecipe::Name).not_equals(""))
+            .limit(1)
+            .to_owned();
+
+        let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+
+        Ok(sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+            .fetch_optional(&self.read_db)
+            .await?
+            .is_some())
+    }
+
+    /// Up to a week of the user's recipes of `recipe_type` that `filter`
+    /// keeps, shuffled.
     pub async fn first_week_recipes(
         &self,
         id: impl Into<String>,
         recipe_type: RecipeType,
+        filter: &PoolFilter,
     ) -> crate::Result<Vec<Recipe>> {
         let id = id.into();
 
-        let statement = Query::select()
-            .columns([
-                MealPlanRecipe::Id,
-                MealPlanRecipe::Name,
-                MealPlanRecipe::AcceptsAccompaniment,
-            ])
+        let mut statement = Query::select()
+            .columns(RECIPE_COLUMNS)
             .from(MealPlanRecipe::Table)
             .and_where(Expr::col(MealPlanRecipe::UserId).eq(id))
             .and_where(Expr::col(MealPlanRecipe::RecipeType).eq(recipe_type.to_string()))
             .and_where(Expr::col(MealPlanRecipe::Name).not_equals(""))
+            .order_by(MealPlanRecipe::Id, sea_query::Order::Asc)
             .limit(7)
             .to_owned();
 
+        filter.apply(&mut statement);
+
         let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
 
         let mut recipes = sqlx::query_as_with::<_, Recipe, _>(sqlx::AssertSqlSafe(sql), values)
@@ -215,12 +1241,28 @@ impl<E: Executor> super::Module<E> {
         Ok(recipes)
     }
 
+    /// Shuffles the candidates with `rng`. They are fetched in id order first,
+    /// so the same seed yields the same pick whatever order SQLite returns
+    /// rows in. A positive `rating_weight` biases the shuffle towards recipes
+    /// with a higher average rating, a positive `recency_weight` away from
+    /// those cooked shortly before `start`, and so does every scorer
+    /// registered with [`super::Module::with_scorer`]. A `randomness` below 1 sharpens that
+    /// bias; at 0 candidates are simply ranked by score. Only recipes
+    /// `filter` keeps are candidates; at most `limit` of them are kept, then
+    /// only the `weight` share of those.
+    #[allow(clippy::too_many_arguments)]
     async fn random(
         &self,
+        rng: &mut StdRng,
         id: impl Into<String>,
         recipe_type: RecipeType,
         weight: f32,
-        dietary_restrictions: Vec<DietaryRestriction>,
+        rating_weight: f32,
+        recency_weight: f32,
+        start: u64,
+        randomness: f32,
+        filter: &PoolFilter,
+        limit: Option<usize>,
     ) -> crate::Result<Vec<Recipe>> {
         if weight < 0.1 {
             crate::user!("weight must be greater than or equal to 0.1");
@@ -230,52 +1272,22 @@ impl<E: Executor> super::Module<E> {
         let mut sub_statement = Query::select()
             .columns([MealPlanRecipe::Id])
             .from(MealPlanRecipe::Table)
-            .and_where(Expr::col(MealPlanRecipe::UserId).eq(id))
+            .and_where(Expr::col(MealPlanRecipe::UserId).eq(id.to_owned()))
             .and_where(Expr::col(MealPlanRecipe::RecipeType).eq(recipe_type.to_string()))
             .and_where(Expr::col(MealPlanRecipe::Name).not_equals(""))
             .to_owned();
 
-        if !dietary_restrictions.is_empty() {
-            let in_clause = dietary_restrictions
-                .iter()
-                .map(|_| "?")
-                .collect::<Vec<_>>()
-                .join(", ");
-
-            sub_statement.and_where(Expr::cust_with_values(
-            format!(
-                "(SELECT COUNT(*) FROM json_each(dietary_restrictions) WHERE value IN ({})) = ?",
-                in_clause
-            ),
-            dietary_restrictions
-                .iter()
-                .map(|t| sea_query::Value::String(Some(*Box::new(t.to_string()))))
-                .chain(std::iter::once(sea_query::Value::Int(Some(
-                    dietary_restrictions.len() as i32,
-                ))))
-                .collect::<Vec<_>>(),
-        ));
-        }
-
-        sub_statement
-            .order_by_expr(
-                SimpleExpr::FunctionCall(Func::random()),
-                sea_query::Order::Asc,
-            )
-            .limit(7 * 5);
+        filter.apply(&mut sub_statement);
 
         let statement = Query::select()
-            .columns([
-                MealPlanRecipe::Id,
-                MealPlanRecipe::Name,
-                MealPlanRecipe::AcceptsAccompaniment,
-            ])
+            .columns(RECIPE_COLUMNS)
             .from(MealPlanRecipe::Table)
             .and_where(
                 MealPlanRecipe::Id
                     .into_column_ref()
                     .in_subquery(sub_statement),
             )
+            .order_by(MealPlanRecipe::Id, sea_query::Order::Asc)
             .to_owned();
 
         let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
@@ -284,8 +1296,68 @@ impl<E: Executor> super::Module<E> {
             .fetch_all(&self.read_db)
             .await?;
 
-        let mut rng = rand::rng();
-        recipes.shuffle(&mut rng);
+        let scorers = [
+            (&RatingScorer as &dyn Scorer, rating_weight),
+            (&RecencyScorer, recency_weight),
+        ]
+        .into_iter()
+        .chain(self.scorers.iter().map(|s| (s.scorer.as_ref(), s.weight)))
+        .filter(|(_, weight)| *weight > 0.0)
+        .collect::<Vec<_>>();
+
+        if scorers.is_empty() && randomness >= 1.0 {
+            recipes.shuffle(rng);
+        } else {
+            let ratings = if rating_weight > 0.0 {
+                crate::recipe::query::rating::average_ratings(
+                    &self.read_db,
+                    recipes.iter().map(|r| r.id.to_owned()),
+                )
+                .await?
+            } else {
+                HashMap::new()
+            };
+            let last_cooked = if recency_weight > 0.0 {
+                crate::recipe::query::cook_count::last_cooked(
+                    &self.read_db,
+                    &id,
+                    recipes.iter().map(|r| r.id.to_owned()),
+                )
+                .await?
+            } else {
+                HashMap::new()
+            };
+
+            let queue = VecDeque::new();
+            let context = ScoringContext {
+                recipe_type,
+                ratings: &ratings,
+                last_cooked: &last_cooked,
+                start,
+                weekday: None,
+                previous_cuisine: None,
+                queue: &queue,
+            };
+
+            recipes = if randomness <= 0.0 {
+                let mut weighted = recipes
+                    .into_iter()
+                    .map(|r| (combined_weight(&scorers, &r, &context), r))
+                    .collect::<Vec<_>>();
+                weighted.sort_by(|a, b| b.0.total_cmp(&a.0));
+                weighted.into_iter().map(|(_, r)| r).collect()
+            } else {
+                // Raising the weights to 1/randomness widens the gap between
+                // good and bad candidates as randomness goes down.
+                weighted_shuffle(rng, recipes, |r| {
+                    combined_weight(&scorers, r, &context).powf(1.0 / randomness.min(1.0))
+                })
+            };
+        }
+
+        if let Some(limit) = limit {
+            recipes.truncate(limit);
+        }
         recipes.truncate((recipes.len() as f32 * weight).ceil() as usize);
 
         Ok(recipes)
@@ -1,28 +1,60 @@
+mod advance_prep;
 mod change_slot_recipe_status;
+mod complete_day;
+mod cooking_step;
 mod generate;
+mod replace_meal;
+mod scorer;
+mod skip_week;
+mod slot_servings;
+mod swap_meals;
 
-use bitcode::{Decode, Encode};
 use evento::{
-    Executor, Projection, ProjectionAggregate,
+    Executor, Projection, ProjectionAggregate, Snapshot,
     metadata::Event,
     subscription::{Context, SubscriptionBuilder},
 };
-use imkitchen_db::mealplan_recipe::MealPlanRecipe;
+use imkitchen_db::{mealplan_recipe::MealPlanRecipe, mealplan_snapshot::MealPlanSnapshot};
 use imkitchen_types::{
-    mealplan::{self, SlotRecipeStatusChanged},
-    recipe::RecipeType,
+    mealplan::{
+        self, AdvancePrepMarked, CommunityRecipesSuggested, LeftoversPlanned, MealReplaced,
+        MealsSwapped, RecipesSnapshotted, Seeded, SlotRecipeStatusChanged, SlotServingsChanged,
+        WeekSkipped, WeekUnskipped,
+    },
+    recipe::{IngredientCategory, Nutrition, RecipeType},
 };
-use sea_query::{Expr, ExprTrait, Query, SqliteQueryBuilder};
+use sea_query::{Expr, ExprTrait, OnConflict, Query, SqliteQueryBuilder};
 use sea_query_sqlx::SqlxBinder;
 use sqlx::SqlitePool;
+use sqlx::prelude::FromRow;
 use std::ops::Deref;
+use std::sync::Arc;
 
+pub use advance_prep::MarkAdvancePrep;
 pub use change_slot_recipe_status::ChangeSlotRecipeStatus;
+pub use complete_day::CompleteDay;
+pub use cooking_step::{AdvanceCookingStep, CookingStep};
 pub use generate::*;
+pub use replace_meal::ReplaceMeal;
+pub use scorer::{
+    CuisineBalanceScorer, CuisineVarietyScorer, EffortScorer, FreshnessScorer, RECENCY_WINDOW_DAYS,
+    RatingScorer, RecencyScorer, Scorer, ScoringContext,
+};
+pub use skip_week::{SkipWeek, UnskipWeek};
+pub use slot_servings::{ChangeSlotServings, MAX_SERVINGS_MULTIPLIER, MIN_SERVINGS_MULTIPLIER};
+pub use swap_meals::SwapMeals;
+
+/// Events applied on top of the last snapshot before `load` stores a fresh
+/// one. Plans regenerated every week otherwise replay their whole history.
+pub const DEFAULT_SNAPSHOT_THRESHOLD: u32 = 20;
 
 #[derive(Clone)]
 pub struct Module<E: Executor> {
     state: crate::State<E>,
+    snapshot_threshold: u32,
+    /// Extra heuristics applied on top of the rating weight when picking
+    /// recipes at random.
+    scorers: Vec<scorer::WeightedScorer>,
 }
 
 impl<E: Executor> Deref for Module<E> {
@@ -35,18 +67,55 @@ impl<E: Executor> Deref for Module<E> {
 
 impl<E: Executor> Module<E> {
     pub fn new(state: crate::State<E>) -> Self {
-        Self { state }
+        Self {
+            state,
+            snapshot_threshold: DEFAULT_SNAPSHOT_THRESHOLD,
+            scorers: vec![],
+        }
+    }
+
+    pub fn with_snapshot_threshold(mut self, threshold: u32) -> Self {
+        self.snapshot_threshold = threshold;
+        self
+    }
+
+    /// Registers a scorer used by randomized generation. `weight` scales how
+    /// far its scores move a recipe's odds; a weight of 0 disables it.
+    pub fn with_scorer(mut self, scorer: impl Scorer + 'static, weight: f32) -> Self {
+        self.scorers.push(scorer::WeightedScorer {
+            scorer: Arc::new(scorer),
+            weight,
+        });
+        self
     }
 
     pub async fn load(&self, id: impl Into<String>) -> anyhow::Result<Option<MealPlan>> {
-        create_projection().load(id).execute(&self.executor).await
+        create_projection()
+            .data(SnapshotContext {
+                read_db: self.read_db.clone(),
+                write_db: self.write_db.clone(),
+                threshold: self.snapshot_threshold,
+            })
+            .load(id)
+            .execute(&self.executor)
+            .await
     }
 }
 
-#[evento::projection(Encode, Decode)]
+#[derive(Clone)]
+struct SnapshotContext {
+    read_db: SqlitePool,
+    write_db: SqlitePool,
+    threshold: u32,
+}
+
+#[evento::projection(FromRow)]
 pub struct MealPlan {
     pub user_id: String,
     pub generated_at: u64,
+    /// Events applied since the snapshot this projection was restored from.
+    #[sqlx(default)]
+    pub pending_events: u32,
 }
 
 impl ProjectionAggregate for MealPlan {
@@ -58,10 +127,78 @@ impl ProjectionAggregate for MealPlan {
 pub fn create_projection<E: Executor>() -> Projection<E, MealPlan> {
     Projection::new::<mealplan::MealPlan>()
         .handler(handle_generated())
-        .skip::<SlotRecipeStatusChanged>()
+        .handler(handle_slot_recipe_status_changed())
+        .handler(handle_meal_replaced())
+        .handler(handle_meals_swapped())
+        .handler(handle_advance_prep_marked())
+        .handler(handle_week_skipped())
+        .handler(handle_week_unskipped())
+        .handler(handle_slot_servings_changed())
+        .skip::<RecipesSnapshotted>()
+        .skip::<LeftoversPlanned>()
+        .skip::<Seeded>()
+        .skip::<CommunityRecipesSuggested>()
         .strict()
 }
 
+impl<E: Executor> Snapshot<E> for MealPlan {
+    async fn restore(context: &evento::projection::Context<'_, E>) -> anyhow::Result<Option<Self>> {
+        let snapshot = context.extract::<SnapshotContext>();
+        let statement = Query::select()
+            .columns([
+                MealPlanSnapshot::UserId,
+                MealPlanSnapshot::Cursor,
+                MealPlanSnapshot::GeneratedAt,
+            ])
+            .from(MealPlanSnapshot::Table)
+            .and_where(Expr::col(MealPlanSnapshot::UserId).eq(&context.id))
+            .limit(1)
+            .to_owned();
+
+        let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+
+        Ok(sqlx::query_as_with(sqlx::AssertSqlSafe(sql), values)
+            .fetch_optional(&snapshot.read_db)
+            .await?)
+    }
+
+    async fn take_snapshot(
+        &self,
+        context: &evento::projection::Context<'_, E>,
+    ) -> anyhow::Result<()> {
+        let snapshot = context.extract::<SnapshotContext>();
+        if self.pending_events < snapshot.threshold {
+            return Ok(());
+        }
+
+        let statement = Query::insert()
+            .into_table(MealPlanSnapshot::Table)
+            .columns([
+                MealPlanSnapshot::UserId,
+                MealPlanSnapshot::Cursor,
+                MealPlanSnapshot::GeneratedAt,
+            ])
+            .values([
+                self.user_id.to_owned().into(),
+                self.cursor.to_owned().into(),
+                self.generated_at.into(),
+            ])?
+            .on_conflict(
+                OnConflict::column(MealPlanSnapshot::UserId)
+                    .update_columns([MealPlanSnapshot::Cursor, MealPlanSnapshot::GeneratedAt])
+                    .to_owned(),
+            )
+            .to_owned();
+
+        let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+        sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+            .execute(&snapshot.write_db)
+            .await?;
+
+        Ok(())
+    }
+}
+
 #[evento::handler]
 async fn handle_generated(
     event: Event<mealplan::DaysGenerated>,
@@ -69,6 +206,76 @@ async fn handle_generated(
 ) -> anyhow::Result<()> {
     data.user_id = event.metadata.requested_by()?;
     data.generated_at = event.timestamp;
+    data.pending_events += 1;
+
+    Ok(())
+}
+
+#[evento::handler]
+async fn handle_slot_recipe_status_changed(
+    _event: Event<SlotRecipeStatusChanged>,
+    data: &mut MealPlan,
+) -> anyhow::Result<()> {
+    data.pending_events += 1;
+
+    Ok(())
+}
+
+#[evento::handler]
+async fn handle_meal_replaced(
+    _event: Event<MealReplaced>,
+    data: &mut MealPlan,
+) -> anyhow::Result<()> {
+    data.pending_events += 1;
+
+    Ok(())
+}
+
+#[evento::handler]
+async fn handle_meals_swapped(
+    _event: Event<MealsSwapped>,
+    data: &mut MealPlan,
+) -> anyhow::Result<()> {
+    data.pending_events += 1;
+
+    Ok(())
+}
+
+#[evento::handler]
+async fn handle_advance_prep_marked(
+    _event: Event<AdvancePrepMarked>,
+    data: &mut MealPlan,
+) -> anyhow::Result<()> {
+    data.pending_events += 1;
+
+    Ok(())
+}
+
+#[evento::handler]
+async fn handle_week_skipped(event: Event<WeekSkipped>, data: &mut MealPlan) -> anyhow::Result<()> {
+    // May come before anything was ever generated.
+    data.user_id = event.metadata.requested_by()?;
+    data.pending_events += 1;
+
+    Ok(())
+}
+
+#[evento::handler]
+async fn handle_week_unskipped(
+    _event: Event<WeekUnskipped>,
+    data: &mut MealPlan,
+) -> anyhow::Result<()> {
+    data.pending_events += 1;
+
+    Ok(())
+}
+
+#[evento::handler]
+async fn handle_slot_servings_changed(
+    _event: Event<SlotServingsChanged>,
+    data: &mut MealPlan,
+) -> anyhow::Result<()> {
+    data.pending_events += 1;
 
     Ok(())
 }
@@ -78,11 +285,23 @@ pub fn subscription<E: Executor>() -> SubscriptionBuilder<E> {
         .handler(handle_recipe_created())
         .handler(handle_recipe_imported())
         .handler(handle_recipe_deleted())
+        .handler(handle_recipe_hidden())
         .handler(handle_recipe_type_changed())
         .handler(handle_recipe_basic_information_changed())
         .handler(handle_recipe_dietary_restrictions_changed())
         .handler(handle_recipe_main_course_changed())
+        .handler(handle_recipe_accompaniment_types_changed())
+        .handler(handle_recipe_default_accompaniments_changed())
+        .handler(handle_recipe_equipment_changed())
+        .handler(handle_recipe_min_household_size_changed())
+        .handler(handle_recipe_servings_yield_changed())
+        .handler(handle_recipe_nutrition_changed())
+        .handler(handle_recipe_ingredients_changed())
+        .handler(handle_recipe_instructions_changed())
+        .handler(handle_recipe_complexity_changed())
+        .handler(handle_recipe_cuisine_type_changed())
         .handler(handle_recipe_advance_prep_changed())
+        .handler(handle_recipe_advance_prep_hours_changed())
         .handler(handle_favorite_saved())
         .handler(handle_favorite_unsaved())
 }
@@ -144,6 +363,10 @@ async fn handle_recipe_imported<E: Executor>(
             MealPlanRecipe::CookTime,
             MealPlanRecipe::PrepTime,
             MealPlanRecipe::AcceptsAccompaniment,
+            MealPlanRecipe::HouseholdSize,
+            MealPlanRecipe::IngredientCount,
+            MealPlanRecipe::InstructionCount,
+            MealPlanRecipe::Perishability,
         ])
         .values_panic([
             event.aggregate_id.to_owned().into(),
@@ -155,6 +378,10 @@ async fn handle_recipe_imported<E: Executor>(
             event.data.cook_time.into(),
             event.data.prep_time.into(),
             event.data.accepts_accompaniment.into(),
+            event.data.household_size.into(),
+            (event.data.ingredients.len() as u32).into(),
+            (event.data.instructions.len() as u32).into(),
+            IngredientCategory::max_perishability(&event.data.ingredients).into(),
         ])
         .to_owned();
     let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
@@ -201,6 +428,28 @@ async fn handle_recipe_deleted<E: Executor>(
     Ok(())
 }
 
+/// Only the owner's row stays, so the recipe drops out of the meal plans of
+/// everyone who saved it.
+#[evento::subscription]
+async fn handle_recipe_hidden<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<imkitchen_types::recipe::Hidden>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<sqlx::SqlitePool>();
+    let statement = Query::delete()
+        .from_table(MealPlanRecipe::Table)
+        .and_where(Expr::col(MealPlanRecipe::Id).eq(&event.aggregate_id))
+        .and_where(Expr::col(MealPlanRecipe::UserId).ne(&event.data.owner_id))
+        .to_owned();
+
+    let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+    sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+        .execute(&pool)
+        .await?;
+
+    Ok(())
+}
+
 #[evento::subscription]
 async fn handle_recipe_basic_information_changed<E: Executor>(
     context: &Context<'_, E>,
@@ -212,6 +461,7 @@ async fn handle_recipe_basic_information_changed<E: Executor>(
         .value(MealPlanRecipe::Name, &event.data.name)
         .value(MealPlanRecipe::PrepTime, event.data.prep_time)
         .value(MealPlanRecipe::CookTime, event.data.cook_time)
+        .value(MealPlanRecipe::HouseholdSize, event.data.household_size)
         .and_where(Expr::col(MealPlanRecipe::Id).eq(&event.aggregate_id))
         .to_owned();
 
@@ -264,6 +514,225 @@ async fn handle_recipe_main_course_changed<E: Executor>(
     Ok(())
 }
 
+#[evento::subscription]
+async fn handle_recipe_accompaniment_types_changed<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<imkitchen_types::recipe::AccompanimentTypesChanged>,
+) -> anyhow::Result<()> {
+    let preferred_accompaniment_types = event
+        .data
+        .preferred_accompaniment_types
+        .iter()
+        .map(|t| serde_json::Value::String(t.to_string()))
+        .collect::<Vec<_>>();
+
+    let pool = context.extract::<sqlx::SqlitePool>();
+    let statement = Query::update()
+        .table(MealPlanRecipe::Table)
+        .value(
+            MealPlanRecipe::AccompanimentType,
+            event.data.accompaniment_type.map(|t| t.to_string()),
+        )
+        .value(
+            MealPlanRecipe::PreferredAccompanimentTypes,
+            serde_json::Value::Array(preferred_accompaniment_types),
+        )
+        .and_where(Expr::col(MealPlanRecipe::Id).eq(&event.aggregate_id))
+        .to_owned();
+
+    let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+    sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+        .execute(&pool)
+        .await?;
+
+    Ok(())
+}
+
+#[evento::subscription]
+async fn handle_recipe_default_accompaniments_changed<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<imkitchen_types::recipe::DefaultAccompanimentsChanged>,
+) -> anyhow::Result<()> {
+    let accompaniment_ids = event
+        .data
+        .accompaniment_ids
+        .iter()
+        .map(|id| serde_json::Value::String(id.to_owned()))
+        .collect::<Vec<_>>();
+
+    let pool = context.extract::<sqlx::SqlitePool>();
+    update_col(
+        &pool,
+        &event.aggregate_id,
+        MealPlanRecipe::DefaultAccompanimentIds,
+        serde_json::Value::Array(accompaniment_ids),
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[evento::subscription]
+async fn handle_recipe_equipment_changed<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<imkitchen_types::recipe::EquipmentChanged>,
+) -> anyhow::Result<()> {
+    let equipment = event
+        .data
+        .equipment
+        .iter()
+        .map(|e| serde_json::Value::String(e.to_string()))
+        .collect::<Vec<_>>();
+
+    let pool = context.extract::<sqlx::SqlitePool>();
+    update_col(
+        &pool,
+        &event.aggregate_id,
+        MealPlanRecipe::Equipment,
+        serde_json::Value::Array(equipment),
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[evento::subscription]
+async fn handle_recipe_min_household_size_changed<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<imkitchen_types::recipe::MinHouseholdSizeChanged>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<sqlx::SqlitePool>();
+    update_col(
+        &pool,
+        &event.aggregate_id,
+        MealPlanRecipe::MinHouseholdSize,
+        event.data.min_household_size,
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[evento::subscription]
+async fn handle_recipe_servings_yield_changed<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<imkitchen_types::recipe::ServingsYieldChanged>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<sqlx::SqlitePool>();
+    update_col(
+        &pool,
+        &event.aggregate_id,
+        MealPlanRecipe::ServingsYield,
+        event.data.servings_yield,
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[evento::subscription]
+async fn handle_recipe_nutrition_changed<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<imkitchen_types::recipe::NutritionChanged>,
+) -> anyhow::Result<()> {
+    // No data at all is unknown, not zero.
+    let total = if event.data.nutrition.iter().any(Option::is_some) {
+        Some(serde_json::to_value(Nutrition::total(
+            &event.data.nutrition,
+        ))?)
+    } else {
+        None
+    };
+
+    let pool = context.extract::<sqlx::SqlitePool>();
+    update_col(&pool, &event.aggregate_id, MealPlanRecipe::Nutrition, total).await?;
+
+    Ok(())
+}
+
+#[evento::subscription]
+async fn handle_recipe_ingredients_changed<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<imkitchen_types::recipe::IngredientsChanged>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<sqlx::SqlitePool>();
+    // Nutrition is per ingredient position; the recipe root drops it too.
+    update_col(
+        &pool,
+        &event.aggregate_id,
+        MealPlanRecipe::Nutrition,
+        Option::<serde_json::Value>::None,
+    )
+    .await?;
+    update_col(
+        &pool,
+        &event.aggregate_id,
+        MealPlanRecipe::IngredientCount,
+        event.data.ingredients.len() as u32,
+    )
+    .await?;
+    update_col(
+        &pool,
+        &event.aggregate_id,
+        MealPlanRecipe::Perishability,
+        IngredientCategory::max_perishability(&event.data.ingredients),
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[evento::subscription]
+async fn handle_recipe_instructions_changed<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<imkitchen_types::recipe::InstructionsChanged>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<sqlx::SqlitePool>();
+    update_col(
+        &pool,
+        &event.aggregate_id,
+        MealPlanRecipe::InstructionCount,
+        event.data.instructions.len() as u32,
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[evento::subscription]
+async fn handle_recipe_complexity_changed<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<imkitchen_types::recipe::ComplexityChanged>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<sqlx::SqlitePool>();
+    update_col(
+        &pool,
+        &event.aggregate_id,
+        MealPlanRecipe::Complexity,
+        event.data.complexity.map(|c| c.to_string()),
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[evento::subscription]
+async fn handle_recipe_cuisine_type_changed<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<imkitchen_types::recipe::CuisineTypeChanged>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<sqlx::SqlitePool>();
+    update_col(
+        &pool,
+        &event.aggregate_id,
+        MealPlanRecipe::CuisineType,
+        event.data.cuisine_type.to_string(),
+    )
+    .await?;
+
+    Ok(())
+}
+
 #[evento::subscription]
 async fn handle_recipe_advance_prep_changed<E: Executor>(
     context: &Context<'_, E>,
@@ -281,6 +750,23 @@ async fn handle_recipe_advance_prep_changed<E: Executor>(
     Ok(())
 }
 
+#[evento::subscription]
+async fn handle_recipe_advance_prep_hours_changed<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<imkitchen_types::recipe::AdvancePrepHoursChanged>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<sqlx::SqlitePool>();
+    update_col(
+        &pool,
+        &event.aggregate_id,
+        MealPlanRecipe::AdvancePrepHours,
+        event.data.advance_prep_hours,
+    )
+    .await?;
+
+    Ok(())
+}
+
 #[evento::subscription]
 async fn handle_favorite_saved<E: Executor>(
     context: &Context<'_, E>,
@@ -298,6 +784,20 @@ async fn handle_favorite_saved<E: Executor>(
             MealPlanRecipe::CookTime,
             MealPlanRecipe::PrepTime,
             MealPlanRecipe::AcceptsAccompaniment,
+            MealPlanRecipe::AccompanimentType,
+            MealPlanRecipe::PreferredAccompanimentTypes,
+            MealPlanRecipe::DefaultAccompanimentIds,
+            MealPlanRecipe::Equipment,
+            MealPlanRecipe::MinHouseholdSize,
+            MealPlanRecipe::ServingsYield,
+            MealPlanRecipe::HouseholdSize,
+            MealPlanRecipe::Nutrition,
+            MealPlanRecipe::Complexity,
+            MealPlanRecipe::IngredientCount,
+            MealPlanRecipe::InstructionCount,
+            MealPlanRecipe::CuisineType,
+            MealPlanRecipe::AdvancePrepHours,
+            MealPlanRecipe::Perishability,
         ])
         .expr(Expr::value(event.metadata.requested_by()?))
         .and_where(Expr::col(MealPlanRecipe::Id).eq(&event.data.recipe_id))
@@ -315,6 +815,20 @@ async fn handle_favorite_saved<E: Executor>(
             MealPlanRecipe::CookTime,
             MealPlanRecipe::PrepTime,
             MealPlanRecipe::AcceptsAccompaniment,
+            MealPlanRecipe::AccompanimentType,
+            MealPlanRecipe::PreferredAccompanimentTypes,
+            MealPlanRecipe::DefaultAccompanimentIds,
+            MealPlanRecipe::Equipment,
+            MealPlanRecipe::MinHouseholdSize,
+            MealPlanRecipe::ServingsYield,
+            MealPlanRecipe::HouseholdSize,
+            MealPlanRecipe::Nutrition,
+            MealPlanRecipe::Complexity,
+            MealPlanRecipe::IngredientCount,
+            MealPlanRecipe::InstructionCount,
+            MealPlanRecipe::CuisineType,
+            MealPlanRecipe::AdvancePrepHours,
+            MealPlanRecipe::Perishability,
             MealPlanRecipe::UserId,
         ])
         .select_from(select)?
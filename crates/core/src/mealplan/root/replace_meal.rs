@@ -0,0 +1,133 @@
+use evento::Executor;
+use evento::cursor::Args;
+use evento::{Aggregate, EventFilter};
+use imkitchen_db::mealplan_recipe::MealPlanRecipe;
+use imkitchen_types::mealplan::{MealPlan, MealReplaced};
+use imkitchen_types::recipe::RecipeType;
+use sea_query::{Expr, ExprTrait, Func, Query, SimpleExpr, SqliteQueryBuilder};
+use sea_query_sqlx::SqlxBinder;
+
+use super::{RECIPE_COLUMNS, Recipe};
+
+pub struct ReplaceMeal {
+    pub user_id: String,
+    pub date: u64,
+    pub recipe_type: RecipeType,
+    /// Only consider recipes whose prep and cook time fit in this many
+    /// minutes. Advance prep is done ahead and doesn't count.
+    pub available_minutes: Option<u16>,
+    /// When every recipe of the course is already planned, one planned no
+    /// closer than this many days to `date` comes back first, see
+    /// `UserConstraints::min_days_since_use`.
+    pub min_days_since_use: u16,
+}
+
+impl<E: Executor> super::Module<E> {
+    /// Swaps the recipe planned for one course of a day with another recipe of
+    /// the same type, e.g. when the planned recipe has been deleted. Recipes
+    /// planned in no week go first, then any planned at least
+    /// `min_days_since_use` days away. Failing that, the one planned the
+    /// furthest from the day is reused rather than leaving the course as is.
+    pub async fn replace_meal(&self, input: ReplaceMeal) -> crate::Result<()> {
+        let Some(current) = self
+            .slot_recipe(&input.user_id, input.date, &input.recipe_type)
+            .await?
+        else {
+            crate::not_found!("slot recipe not found");
+        };
+
+        let mut statement = Query::select()
+            .columns(RECIPE_COLUMNS)
+            .from(MealPlanRecipe::Table)
+            .and_where(Expr::col(MealPlanRecipe::UserId).eq(&input.user_id))
+            .and_where(Expr::col(MealPlanRecipe::RecipeType).eq(input.recipe_type.to_string()))
+            .and_where(Expr::col(MealPlanRecipe::Name).not_equals(""))
+            .and_where(Expr::col(MealPlanRecipe::Id).ne(&current.id))
+            .order_by_expr(
+                SimpleExpr::FunctionCall(Func::random()),
+                sea_query::Order::Asc,
+            )
+            .to_owned();
+
+        if let Some(minutes) = input.available_minutes {
+            statement.and_where(
+                Expr::col(MealPlanRecipe::PrepTime)
+                    .add(Expr::col(MealPlanRecipe::CookTime))
+                    .lte(minutes),
+            );
+        }
+
+        let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+
+        let candidates = sqlx::query_as_with::<_, Recipe, _>(sqlx::AssertSqlSafe(sql), values)
+            .fetch_all(&self.read_db)
+            .await?;
+
+        let planned =
+            crate::mealplan::slot::planned_dates(&self.read_db, &input.user_id, &input.recipe_type)
+                .await?;
+
+        let day = julian_day(input.date);
+        let gap = |recipe: &Recipe| {
+            planned
+                .get(&recipe.id)?
+                .iter()
+                .filter_map(|date| Some(julian_day(*date)?.abs_diff(day?)))
+                .min()
+        };
+
+        // Candidates come in random order, so the first far enough away is a
+        // random one of them.
+        let unused = candidates.iter().find(|r| !planned.contains_key(&r.id));
+        let spaced = || {
+            candidates
+                .iter()
+                .find(|r| gap(r).is_some_and(|gap| gap >= input.min_days_since_use.into()))
+        };
+        let least_recently_used = || candidates.iter().max_by_key(|r| gap(r));
+
+        let Some(recipe) = unused.or_else(spaced).or_else(least_recently_used) else {
+            crate::user!("No replacement recipe found");
+        };
+
+        let last_event = self
+            .executor
+            .read(
+                Some(vec![EventFilter::by_id(
+                    MealPlan::aggregate_type(),
+                    &input.user_id,
+                )]),
+                None,
+                Args::backward(1, None),
+            )
+            .await?;
+
+        let Some(version) = last_event.edges.first().map(|e| e.node.version) else {
+            crate::not_found!("mealplan not found");
+        };
+
+        evento::append(&input.user_id)
+            .event(&MealReplaced {
+                date: input.date,
+                recipe_type: input.recipe_type,
+                previous_recipe_id: current.id,
+                recipe: recipe.into(),
+            })
+            .original_version(version)
+            .requested_by(&input.user_id)
+            .commit(&self.executor)
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Days since a fixed origin of a YYYYMMDD date, to count the days between
+/// two of them.
+fn julian_day(date: u64) -> Option<i32> {
+    let month = time::Month::try_from(((date % 10000) / 100) as u8).ok()?;
+    let date =
+        time::Date::from_calendar_date((date / 10000) as i32, month, (date % 100) as u8).ok()?;
+
+    Some(date.to_julian_day())
+}
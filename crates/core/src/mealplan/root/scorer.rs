@@ -0,0 +1,172 @@
+use imkitchen_types::recipe::{Complexity, CuisineType, IngredientCategory, RecipeType};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use time::Weekday;
+
+use super::Recipe;
+
+/// What scorers can look at besides the candidate itself.
+pub struct ScoringContext<'a> {
+    pub recipe_type: RecipeType,
+    /// Average stars by recipe id. Only loaded when ratings are weighted.
+    pub ratings: &'a HashMap<String, f32>,
+    /// When the user last cooked each recipe (unix time). Only loaded when
+    /// recency is weighted.
+    pub last_cooked: &'a HashMap<String, u64>,
+    /// Start of the plan being generated (unix time).
+    pub start: u64,
+    /// Day the recipe would be planned on. `None` while the pool is
+    /// shuffled, before any day is planned.
+    pub weekday: Option<Weekday>,
+    /// Cuisine of the main course planned the day before.
+    pub previous_cuisine: Option<&'a CuisineType>,
+    /// Recipes of the course still waiting for a day, the candidate
+    /// included. Empty while the pool is shuffled.
+    pub queue: &'a VecDeque<&'a Recipe>,
+}
+
+/// A selection heuristic. Scores range from -1.0 (avoid) to 1.0 (favor),
+/// 0.0 leaves the candidate's odds untouched.
+pub trait Scorer: Send + Sync {
+    fn score(&self, recipe: &Recipe, context: &ScoringContext<'_>) -> f32;
+}
+
+/// Favors recipes rated above 3 stars and penalizes those below. Unrated
+/// recipes stay neutral.
+pub struct RatingScorer;
+
+impl Scorer for RatingScorer {
+    fn score(&self, recipe: &Recipe, context: &ScoringContext<'_>) -> f32 {
+        context
+            .ratings
+            .get(&recipe.id)
+            .map(|average| (average - 3.0) / 2.0)
+            .unwrap_or(0.0)
+    }
+}
+
+/// Days after which a cooked recipe is as likely as one never cooked.
+pub const RECENCY_WINDOW_DAYS: u64 = 28;
+
+/// Penalizes recipes cooked shortly before the plan starts, the most for the
+/// day before and fading out over [`RECENCY_WINDOW_DAYS`]. Recipes never
+/// cooked stay neutral.
+pub struct RecencyScorer;
+
+impl Scorer for RecencyScorer {
+    fn score(&self, recipe: &Recipe, context: &ScoringContext<'_>) -> f32 {
+        let Some(cooked_at) = context.last_cooked.get(&recipe.id) else {
+            return 0.0;
+        };
+
+        let days = context.start.saturating_sub(*cooked_at) / 86400;
+        if days >= RECENCY_WINDOW_DAYS {
+            return 0.0;
+        }
+
+        -(1.0 - days as f32 / RECENCY_WINDOW_DAYS as f32)
+    }
+}
+
+/// Favors recipes whose cuisine makes up the largest share of what is left
+/// to plan, so that a cuisine covering most of the queue is spread over
+/// every other day instead of piling up at the end. Recipes without a
+/// cuisine stay neutral.
+pub struct CuisineBalanceScorer;
+
+impl Scorer for CuisineBalanceScorer {
+    fn score(&self, recipe: &Recipe, context: &ScoringContext<'_>) -> f32 {
+        let Some(cuisine) = recipe.cuisine_type.as_ref() else {
+            return 0.0;
+        };
+
+        if context.queue.is_empty() {
+            return 0.0;
+        }
+
+        let left = context
+            .queue
+            .iter()
+            .filter(|queued| queued.cuisine_type.as_ref() == Some(cuisine))
+            .count();
+
+        left as f32 / context.queue.len() as f32
+    }
+}
+
+/// Penalizes recipes of the same cuisine as the day before. Weighted at 1.0
+/// alongside [`CuisineBalanceScorer`], another cuisine always wins when one
+/// is left; lower weights let a dominant cuisine come back the next day.
+pub struct CuisineVarietyScorer;
+
+impl Scorer for CuisineVarietyScorer {
+    fn score(&self, recipe: &Recipe, context: &ScoringContext<'_>) -> f32 {
+        match (context.previous_cuisine, recipe.cuisine_type.as_ref()) {
+            (Some(previous), Some(cuisine)) if previous == cuisine => -1.0,
+            _ => 0.0,
+        }
+    }
+}
+
+/// Groceries are bought for the week ahead: favors the most perishable
+/// recipes from Monday to Wednesday and the least perishable ones later.
+pub struct FreshnessScorer;
+
+impl Scorer for FreshnessScorer {
+    fn score(&self, recipe: &Recipe, context: &ScoringContext<'_>) -> f32 {
+        let freshness = match context.weekday {
+            None => return 0.0,
+            Some(Weekday::Monday | Weekday::Tuesday | Weekday::Wednesday) => recipe.perishability,
+            Some(_) => IngredientCategory::MAX_PERISHABILITY.saturating_sub(recipe.perishability),
+        };
+
+        freshness as f32 / IngredientCategory::MAX_PERISHABILITY as f32
+    }
+}
+
+/// Favors simple recipes and penalizes advanced ones. Not part of the
+/// default set, register it with [`super::Module::with_scorer`].
+pub struct EffortScorer;
+
+impl Scorer for EffortScorer {
+    fn score(&self, recipe: &Recipe, _context: &ScoringContext<'_>) -> f32 {
+        match recipe.complexity() {
+            Complexity::Simple => 1.0,
+            Complexity::Moderate => 0.0,
+            Complexity::Advanced => -1.0,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct WeightedScorer {
+    pub scorer: Arc<dyn Scorer>,
+    pub weight: f32,
+}
+
+/// Selection weight of `recipe`: each scorer moves the neutral 1.0 by its
+/// weight times its score, and the results are multiplied together. Every
+/// factor is floored so that no candidate is ruled out entirely.
+pub(crate) fn combined_weight(
+    scorers: &[(&dyn Scorer, f32)],
+    recipe: &Recipe,
+    context: &ScoringContext<'_>,
+) -> f32 {
+    scorers
+        .iter()
+        .map(|(scorer, weight)| (1.0 + weight * scorer.score(recipe, context)).max(0.05))
+        .product()
+}
+
+/// Sum of every scorer's score times its weight, for ranking candidates
+/// against each other rather than drawing them.
+pub(crate) fn combined_score(
+    scorers: &[(&dyn Scorer, f32)],
+    recipe: &Recipe,
+    context: &ScoringContext<'_>,
+) -> f32 {
+    scorers
+        .iter()
+        .map(|(scorer, weight)| weight * scorer.score(recipe, context))
+        .sum()
+}
@@ -0,0 +1,97 @@
+use evento::Executor;
+use evento::cursor::Args;
+use evento::{Aggregate, EventFilter};
+use imkitchen_types::mealplan::{MealPlan, WeekSkipped, WeekUnskipped};
+
+pub struct SkipWeek {
+    pub user_id: String,
+    /// Any day (YYYYMMDD) of the week.
+    pub date: u64,
+}
+
+pub struct UnskipWeek {
+    pub user_id: String,
+    /// Any day (YYYYMMDD) of the week.
+    pub date: u64,
+}
+
+impl<E: Executor> super::Module<E> {
+    /// Sets a week aside, e.g. while traveling: whatever was planned for it
+    /// is cleared, it gets no shopping list and generation plans the days
+    /// around it without moving the recipe rotation forward.
+    pub async fn skip_week(&self, input: SkipWeek) -> crate::Result<()> {
+        let Some(week) = crate::mealplan::week_dates(input.date).map(|dates| dates[0]) else {
+            crate::user!("Invalid date");
+        };
+
+        if self.skipped_weeks(&input.user_id).await?.contains(&week) {
+            return Ok(());
+        }
+
+        let last_event = self
+            .executor
+            .read(
+                Some(vec![EventFilter::by_id(
+                    MealPlan::aggregate_type(),
+                    &input.user_id,
+                )]),
+                None,
+                Args::backward(1, None),
+            )
+            .await?;
+
+        let version = last_event
+            .edges
+            .first()
+            .map(|e| e.node.version)
+            .unwrap_or_default();
+
+        evento::append(&input.user_id)
+            .event(&WeekSkipped { week })
+            .original_version(version)
+            .requested_by(&input.user_id)
+            .commit(&self.executor)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Puts a skipped week back on the calendar. Its days stay empty until
+    /// the next generation.
+    pub async fn unskip_week(&self, input: UnskipWeek) -> crate::Result<()> {
+        let Some(week) = crate::mealplan::week_dates(input.date).map(|dates| dates[0]) else {
+            crate::user!("Invalid date");
+        };
+
+        if !self.skipped_weeks(&input.user_id).await?.contains(&week) {
+            return Ok(());
+        }
+
+        let last_event = self
+            .executor
+            .read(
+                Some(vec![EventFilter::by_id(
+                    MealPlan::aggregate_type(),
+                    &input.user_id,
+                )]),
+                None,
+                Args::backward(1, None),
+            )
+            .await?;
+
+        let version = last_event
+            .edges
+            .first()
+            .map(|e| e.node.version)
+            .unwrap_or_default();
+
+        evento::append(&input.user_id)
+            .event(&WeekUnskipped { week })
+            .original_version(version)
+            .requested_by(&input.user_id)
+            .commit(&self.executor)
+            .await?;
+
+        Ok(())
+    }
+}
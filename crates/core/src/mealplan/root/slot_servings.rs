@@ -0,0 +1,67 @@
+use evento::Executor;
+use evento::cursor::Args;
+use evento::{Aggregate, EventFilter};
+use imkitchen_types::mealplan::{MealPlan, SlotServingsChanged};
+use imkitchen_types::recipe::RecipeType;
+
+/// Smallest and largest amount a slot can be cooked for, relative to usual.
+pub const MIN_SERVINGS_MULTIPLIER: f32 = 0.25;
+pub const MAX_SERVINGS_MULTIPLIER: f32 = 10.0;
+
+pub struct ChangeSlotServings {
+    pub user_id: String,
+    pub date: u64,
+    pub recipe_type: RecipeType,
+    /// 1.0 goes back to the household size.
+    pub multiplier: f32,
+}
+
+impl<E: Executor> super::Module<E> {
+    /// Cooks one course of one day for more or fewer people than usual, e.g.
+    /// guests coming on Saturday. Only that slot's ingredients change on the
+    /// shopping list.
+    pub async fn change_slot_servings(&self, input: ChangeSlotServings) -> crate::Result<()> {
+        if !(MIN_SERVINGS_MULTIPLIER..=MAX_SERVINGS_MULTIPLIER).contains(&input.multiplier) {
+            crate::user!(
+                "Servings multiplier must be between {MIN_SERVINGS_MULTIPLIER} and {MAX_SERVINGS_MULTIPLIER}"
+            );
+        }
+
+        let Some(recipe) = self
+            .slot_recipe(&input.user_id, input.date, &input.recipe_type)
+            .await?
+        else {
+            crate::not_found!("slot recipe not found");
+        };
+
+        let last_event = self
+            .executor
+            .read(
+                Some(vec![EventFilter::by_id(
+                    MealPlan::aggregate_type(),
+                    &input.user_id,
+                )]),
+                None,
+                Args::backward(1, None),
+            )
+            .await?;
+
+        let Some(version) = last_event.edges.first().map(|e| e.node.version) else {
+            crate::not_found!("mealplan not found");
+        };
+
+        evento::append(&input.user_id)
+            .event(&SlotServingsChanged {
+                date: input.date,
+                recipe_type: input.recipe_type,
+                recipe_id: recipe.id,
+                multiplier: input.multiplier,
+            })
+            .original_version(version)
+            .requested_by(&input.user_id)
+            .commit(&self.executor)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+use evento::Executor;
+use evento::cursor::Args;
+use evento::{Aggregate, EventFilter};
+use imkitchen_db::mealplan_slot::MealPlanSlot;
+use imkitchen_types::mealplan::{MealPlan, MealsSwapped};
+use imkitchen_types::recipe::RecipeType;
+use sea_query::{Cond, Expr, ExprTrait, Query, SqliteQueryBuilder};
+use sea_query_sqlx::SqlxBinder;
+
+pub struct SwapMeals {
+    pub user_id: String,
+    pub date: u64,
+    pub recipe_type: RecipeType,
+    pub other_date: u64,
+}
+
+impl<E: Executor> super::Module<E> {
+    /// Exchanges the recipes planned for the same course on two days. Unlike
+    /// [`Self::replace_meal`] no other recipe is needed, and the week's
+    /// recipes, hence its shopping list, stay the same.
+    pub async fn swap_meals(&self, input: SwapMeals) -> crate::Result<()> {
+        if input.date == input.other_date {
+            crate::user!("Pick two different days to swap");
+        }
+
+        let Some(recipe) = self
+            .slot_recipe(&input.user_id, input.date, &input.recipe_type)
+            .await?
+        else {
+            crate::not_found!("slot recipe not found");
+        };
+
+        let Some(other) = self
+            .slot_recipe(&input.user_id, input.other_date, &input.recipe_type)
+            .await?
+        else {
+            crate::not_found!("slot recipe not found");
+        };
+
+        if input.recipe_type == RecipeType::MainCourse
+            && self
+                .has_leftovers(&input.user_id, [input.date, input.other_date])
+                .await?
+        {
+            crate::user!("Meals planned around leftovers can't be swapped");
+        }
+
+        let last_event = self
+            .executor
+            .read(
+                Some(vec![EventFilter::by_id(
+                    MealPlan::aggregate_type(),
+                    &input.user_id,
+                )]),
+                None,
+                Args::backward(1, None),
+            )
+            .await?;
+
+        let Some(version) = last_event.edges.first().map(|e| e.node.version) else {
+            crate::not_found!("mealplan not found");
+        };
+
+        evento::append(&input.user_id)
+            .event(&MealsSwapped {
+                recipe_type: input.recipe_type,
+                date: input.date,
+                recipe_id: recipe.id,
+                other_date: input.other_date,
+                other_recipe_id: other.id,
+            })
+            .original_version(version)
+            .requested_by(&input.user_id)
+            .commit(&self.executor)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Whether the main course of one of `dates` is eaten as leftovers, or
+    /// cooked in a batch eaten on a later day.
+    async fn has_leftovers(&self, user_id: &str, dates: [u64; 2]) -> crate::Result<bool> {
+        let (sql, values) = Query::select()
+            .column(MealPlanSlot::Date)
+            .from(MealPlanSlot::Table)
+            .and_where(Expr::col(MealPlanSlot::UserId).eq(user_id))
+            .cond_where(
+                Cond::any()
+                    .add(
+                        Cond::all()
+                            .add(Expr::col(MealPlanSlot::Date).is_in(dates))
+                            .add(Expr::col(MealPlanSlot::LeftoverOf).is_not_null()),
+                    )
+                    .add(Expr::col(MealPlanSlot::LeftoverOf).is_in(dates)),
+            )
+            .limit(1)
+            .build_sqlx(SqliteQueryBuilder);
+
+        Ok(
+            sqlx::query_scalar_with::<_, u64, _>(sqlx::AssertSqlSafe(sql), values)
+                .fetch_optional(&self.read_db)
+                .await?
+                .is_some(),
+        )
+    }
+}
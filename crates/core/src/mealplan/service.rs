@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use time::macros::format_description;
 use time::{Date, Duration, Month, OffsetDateTime, PrimitiveDateTime, Weekday};
 use time_tz::{PrimitiveDateTimeExt, ToTimezone, timezones};
@@ -141,6 +142,14 @@ pub fn month_bounds_from_now(tz: impl Into<String>) -> anyhow::Result<MonthBound
     month_bounds(now, tz)
 }
 
+/// Converts the date to the timezone, left unchanged when it is unknown.
+pub fn to_timezone(date: OffsetDateTime, tz: &str) -> OffsetDateTime {
+    match timezones::get_by_name(tz) {
+        Some(tz) => date.to_timezone(tz),
+        None => date,
+    }
+}
+
 pub fn now(tz: impl Into<String>) -> OffsetDateTime {
     let tz = tz.into();
     let mut now = OffsetDateTime::now_utc();
@@ -161,6 +170,52 @@ pub fn date_to_u64(date: OffsetDateTime) -> u64 {
     year * 10000 + month * 100 + day
 }
 
+/// The seven days (YYYYMMDD) of the week the YYYYMMDD `date` falls in,
+/// Monday first. `None` when `date` isn't a valid date.
+pub fn week_dates(date: u64) -> Option<[u64; 7]> {
+    let month = Month::try_from(((date % 10000) / 100) as u8).ok()?;
+    let date = Date::from_calendar_date((date / 10000) as i32, month, (date % 100) as u8).ok()?;
+    let monday = date - Duration::days(date.weekday().number_days_from_monday().into());
+
+    Some(std::array::from_fn(|index| {
+        let day = monday + Duration::days(index as i64);
+        day.year() as u64 * 10000 + day.month() as u64 * 100 + day.day() as u64
+    }))
+}
+
+/// The `days` dates (YYYYMMDD) a plan starting on `start` covers, days of the
+/// `skipped_weeks` (Monday, YYYYMMDD) jumped over.
+pub fn plan_dates(start: OffsetDateTime, days: usize, skipped_weeks: &HashSet<u64>) -> Vec<u64> {
+    (0..)
+        .map(|index| date_to_u64(start + Duration::days(index)))
+        .filter(|date| !week_dates(*date).is_some_and(|week| skipped_weeks.contains(&week[0])))
+        .take(days)
+        .collect()
+}
+
+/// Safety net for generation: `dates` (YYYYMMDD) must be exactly the
+/// [`plan_dates`] of `start`, with no gap or duplicate.
+pub fn check_contiguous_dates(
+    start: OffsetDateTime,
+    days: usize,
+    dates: &[u64],
+    skipped_weeks: &HashSet<u64>,
+) -> anyhow::Result<()> {
+    if dates.len() != days {
+        anyhow::bail!("expected {days} planned days, got {}", dates.len());
+    }
+
+    let expected_dates = plan_dates(start, days, skipped_weeks);
+
+    for (index, (date, expected)) in dates.iter().zip(expected_dates).enumerate() {
+        if *date != expected {
+            anyhow::bail!("planned day {index} is {date}, expected {expected}");
+        }
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -364,4 +419,54 @@ mod tests {
         let date = datetime!(2025-01-05 00:00:00 UTC);
         assert_eq!(date_to_u64(date), 20250105);
     }
+
+    #[test]
+    fn test_check_contiguous_dates() {
+        let start = datetime!(2025-01-30 12:00:00 UTC);
+        let dates = [20250130, 20250131, 20250201, 20250202];
+
+        assert!(check_contiguous_dates(start, 4, &dates, &HashSet::new()).is_ok());
+        assert!(check_contiguous_dates(start, 5, &dates, &HashSet::new()).is_err());
+    }
+
+    #[test]
+    fn test_check_contiguous_dates_corrupted() {
+        let start = datetime!(2025-01-20 12:00:00 UTC);
+
+        let gap = [20250120, 20250121, 20250123];
+        assert_eq!(
+            check_contiguous_dates(start, 3, &gap, &HashSet::new())
+                .unwrap_err()
+                .to_string(),
+            "planned day 2 is 20250123, expected 20250122"
+        );
+
+        let duplicate = [20250120, 20250121, 20250121];
+        assert!(check_contiguous_dates(start, 3, &duplicate, &HashSet::new()).is_err());
+
+        let shifted = [20250121, 20250122, 20250123];
+        assert!(check_contiguous_dates(start, 3, &shifted, &HashSet::new()).is_err());
+    }
+
+    #[test]
+    fn test_check_contiguous_dates_skipped_week() {
+        let start = datetime!(2025-01-24 12:00:00 UTC);
+        let skipped = HashSet::from([20250127]);
+        let dates = [20250124, 20250125, 20250126, 20250203];
+
+        assert!(check_contiguous_dates(start, 4, &dates, &skipped).is_ok());
+        assert!(check_contiguous_dates(start, 4, &dates, &HashSet::new()).is_err());
+    }
+
+    #[test]
+    fn test_week_dates() {
+        let week = [
+            20241230, 20241231, 20250101, 20250102, 20250103, 20250104, 20250105,
+        ];
+
+        assert_eq!(week_dates(20250101), Some(week));
+        assert_eq!(week_dates(20241230), Some(week));
+        assert_eq!(week_dates(20250105), Some(week));
+        assert_eq!(week_dates(20250230), None);
+    }
 }
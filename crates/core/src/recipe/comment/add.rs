@@ -0,0 +1,52 @@
+use evento::Executor;
+use imkitchen_types::comment::Added;
+
+/// Longest comment body, in characters.
+pub const MAX_COMMENT_LENGTH: usize = 2000;
+
+impl<E: Executor> super::Module<E> {
+    /// Comments on a recipe shared with the community, or on one of the
+    /// user's own. Returns the comment id.
+    pub async fn add(
+        &self,
+        recipe_id: impl Into<String>,
+        user_id: impl Into<String>,
+        body: impl Into<String>,
+    ) -> crate::Result<String> {
+        let recipe_id = recipe_id.into();
+        let user_id = user_id.into();
+        let body = validate_body(body.into())?;
+
+        let Some(recipe) = crate::recipe::create_projection::<E>()
+            .load(&recipe_id)
+            .execute(&self.executor)
+            .await?
+        else {
+            crate::not_found!("recipe");
+        };
+
+        if !recipe.is_shared && recipe.owner_id != user_id {
+            crate::not_found!("recipe");
+        }
+
+        Ok(evento::create()
+            .event(&Added { recipe_id, body })
+            .requested_by(user_id)
+            .commit(&self.executor)
+            .await?)
+    }
+}
+
+pub(super) fn validate_body(body: String) -> crate::Result<String> {
+    let body = body.trim().to_owned();
+
+    if body.is_empty() {
+        crate::user!("Comment can't be empty");
+    }
+
+    if body.chars().count() > MAX_COMMENT_LENGTH {
+        crate::user!("Comment must be at most {MAX_COMMENT_LENGTH} characters");
+    }
+
+    Ok(body)
+}
@@ -0,0 +1,45 @@
+use evento::{Executor, ProjectionAggregate};
+use imkitchen_types::comment::{FlagReason, Flagged, Hidden};
+
+/// Distinct users that need to flag a comment before it is hidden.
+pub const FLAG_THRESHOLD: u16 = 3;
+
+impl<E: Executor> super::Module<E> {
+    /// Reports a comment. Each user counts once, flagging again is a no-op,
+    /// and the flag that reaches [`FLAG_THRESHOLD`] hides the comment until
+    /// an admin reviews it.
+    pub async fn flag(
+        &self,
+        comment_id: impl Into<String>,
+        user_id: impl Into<String>,
+        reason: FlagReason,
+    ) -> crate::Result<()> {
+        let user_id = user_id.into();
+
+        let Some(comment) = self.load(comment_id).await? else {
+            crate::not_found!("comment");
+        };
+
+        if comment.user_id == user_id {
+            crate::user!("You can't flag your own comment");
+        }
+
+        if comment.flagged_by.contains(&user_id) {
+            return Ok(());
+        }
+
+        let flag_count = comment.flagged_by.len() as u16 + 1;
+        let hide = !comment.is_hidden && flag_count >= FLAG_THRESHOLD;
+
+        let mut builder = comment.write()?;
+        builder.event(&Flagged { reason });
+
+        if hide {
+            builder.event(&Hidden { flag_count });
+        }
+
+        builder.requested_by(user_id).commit(&self.executor).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,105 @@
+mod add;
+mod flag;
+mod reply;
+mod resolve;
+
+pub use add::MAX_COMMENT_LENGTH;
+pub use flag::FLAG_THRESHOLD;
+pub use resolve::ResolveReportsInput;
+
+use bitcode::{Decode, Encode};
+use evento::{Executor, Projection, ProjectionAggregate, metadata::Event};
+use imkitchen_types::comment;
+use std::ops::Deref;
+
+#[derive(Clone)]
+pub struct Module<E: Executor>(pub(crate) crate::State<E>);
+
+impl<E: Executor> Deref for Module<E> {
+    type Target = crate::State<E>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<E: Executor> Module<E> {
+    pub async fn load(&self, id: impl Into<String>) -> anyhow::Result<Option<Comment>> {
+        create_projection::<E>()
+            .load(id)
+            .execute(&self.executor)
+            .await
+    }
+}
+
+#[evento::projection(Encode, Decode)]
+pub struct Comment {
+    pub id: String,
+    pub recipe_id: String,
+    /// Top level comment of the thread, `None` on top level comments.
+    pub parent_id: Option<String>,
+    pub user_id: String,
+    pub flagged_by: Vec<String>,
+    pub is_hidden: bool,
+}
+
+pub fn create_projection<E: Executor>() -> Projection<E, Comment> {
+    Projection::new::<comment::Comment>()
+        .revision(2)
+        .handler(handle_added())
+        .handler(handle_replied())
+        .handler(handle_flagged())
+        .handler(handle_hidden())
+        .handler(handle_reports_resolved())
+        .strict()
+}
+
+impl ProjectionAggregate for Comment {
+    fn aggregate_id(&self) -> String {
+        self.id.to_owned()
+    }
+}
+
+#[evento::handler]
+async fn handle_added(event: Event<comment::Added>, data: &mut Comment) -> anyhow::Result<()> {
+    data.id = event.aggregate_id.to_owned();
+    data.recipe_id = event.data.recipe_id;
+    data.user_id = event.metadata.requested_by()?;
+
+    Ok(())
+}
+
+#[evento::handler]
+async fn handle_replied(event: Event<comment::Replied>, data: &mut Comment) -> anyhow::Result<()> {
+    data.id = event.aggregate_id.to_owned();
+    data.recipe_id = event.data.recipe_id;
+    data.parent_id = Some(event.data.parent_id);
+    data.user_id = event.metadata.requested_by()?;
+
+    Ok(())
+}
+
+#[evento::handler]
+async fn handle_flagged(event: Event<comment::Flagged>, data: &mut Comment) -> anyhow::Result<()> {
+    data.flagged_by.push(event.metadata.requested_by()?);
+
+    Ok(())
+}
+
+#[evento::handler]
+async fn handle_hidden(_event: Event<comment::Hidden>, data: &mut Comment) -> anyhow::Result<()> {
+    data.is_hidden = true;
+
+    Ok(())
+}
+
+#[evento::handler]
+async fn handle_reports_resolved(
+    event: Event<comment::ReportsResolved>,
+    data: &mut Comment,
+) -> anyhow::Result<()> {
+    data.flagged_by.clear();
+    data.is_hidden = event.data.resolution == comment::ReportResolution::Removed;
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use evento::Executor;
+use imkitchen_types::comment::Replied;
+
+impl<E: Executor> super::Module<E> {
+    /// Replies to a comment. Replying to a reply adds to the same thread, so
+    /// threads never go deeper than one level. Returns the reply id.
+    pub async fn reply(
+        &self,
+        comment_id: impl Into<String>,
+        user_id: impl Into<String>,
+        body: impl Into<String>,
+    ) -> crate::Result<String> {
+        let body = super::add::validate_body(body.into())?;
+
+        let Some(comment) = self.load(comment_id).await? else {
+            crate::not_found!("comment");
+        };
+
+        let parent_id = comment.parent_id.unwrap_or(comment.id);
+
+        Ok(evento::create()
+            .event(&Replied {
+                recipe_id: comment.recipe_id,
+                parent_id,
+                body,
+            })
+            .requested_by(user_id)
+            .commit(&self.executor)
+            .await?)
+    }
+}
@@ -0,0 +1,44 @@
+use evento::{Executor, ProjectionAggregate};
+use imkitchen_types::comment::{ReportResolution, ReportsResolved};
+
+pub struct ResolveReportsInput {
+    pub ids: Vec<String>,
+    pub resolution: ReportResolution,
+}
+
+impl<E: Executor> super::Module<E> {
+    /// Settles the reports on many comments at once. Callers are responsible
+    /// for checking `admin_id` is an admin. Returns how many comments were
+    /// resolved; unknown ids and comments nobody flagged are skipped.
+    pub async fn resolve_reports(
+        &self,
+        input: ResolveReportsInput,
+        admin_id: impl Into<String>,
+    ) -> crate::Result<usize> {
+        let admin_id = admin_id.into();
+        let mut resolved = 0;
+
+        for id in input.ids {
+            let Some(comment) = self.load(&id).await? else {
+                continue;
+            };
+
+            if comment.flagged_by.is_empty() {
+                continue;
+            }
+
+            comment
+                .write()?
+                .event(&ReportsResolved {
+                    resolution: input.resolution,
+                })
+                .requested_by(&admin_id)
+                .commit(&self.executor)
+                .await?;
+
+            resolved += 1;
+        }
+
+        Ok(resolved)
+    }
+}
@@ -7,23 +7,68 @@ impl<E: Executor + Clone> super::Module<E> {
         id: impl Into<String>,
         owner_id: impl Into<String>,
         user_id: impl Into<String>,
+        max: u32,
     ) -> crate::Result<()> {
         let id = id.into();
         let user_id = user_id.into();
         let favorite = self.load(&id, &user_id).await?;
 
-        if !favorite.saved {
-            favorite
-                .write()?
-                .event(&Saved {
-                    recipe_id: id,
-                    recipe_owner: owner_id.into(),
-                })
-                .requested_by(user_id)
-                .commit(&self.executor)
-                .await?;
+        if favorite.saved {
+            return Ok(());
+        }
+
+        // Read from the stat view, so a burst of saves may briefly exceed
+        // the cap before the subscription catches up.
+        let count =
+            crate::recipe::query::user_stat::favorite_count(&self.read_db, &user_id).await?;
+
+        if count >= max {
+            crate::user!("You can save up to {max} favorite recipes");
+        }
+
+        let committed = favorite
+            .write()?
+            .event(&Saved {
+                recipe_id: id.to_owned(),
+                recipe_owner: owner_id.into(),
+            })
+            .requested_by(user_id.to_owned())
+            .commit(&self.executor)
+            .await;
+
+        // A concurrent save moved the aggregate past the version we loaded.
+        // If it left the recipe saved there is nothing left to do.
+        if let Err(err) = committed {
+            if self.load(&id, &user_id).await?.saved {
+                return Ok(());
+            }
+
+            return Err(err.into());
         }
 
         Ok(())
     }
+
+    /// Saves the recipe if it isn't saved yet, unsaves it otherwise, and
+    /// returns whether it ends up saved.
+    pub async fn toggle(
+        &self,
+        id: impl Into<String>,
+        owner_id: impl Into<String>,
+        user_id: impl Into<String>,
+        max: u32,
+    ) -> crate::Result<bool> {
+        let id = id.into();
+        let user_id = user_id.into();
+
+        if self.load(&id, &user_id).await?.saved {
+            self.unsave(&id, &user_id).await?;
+
+            return Ok(false);
+        }
+
+        self.save(&id, owner_id, &user_id, max).await?;
+
+        Ok(true)
+    }
 }
@@ -10,13 +10,27 @@ impl<E: Executor + Clone> super::Module<E> {
         let id = id.into();
         let user_id = user_id.into();
         let favorite = self.load(&id, &user_id).await?;
-        if favorite.saved {
-            favorite
-                .write()?
-                .event(&Unsaved { recipe_id: id })
-                .requested_by(user_id)
-                .commit(&self.executor)
-                .await?;
+
+        if !favorite.saved {
+            return Ok(());
+        }
+
+        let committed = favorite
+            .write()?
+            .event(&Unsaved {
+                recipe_id: id.to_owned(),
+            })
+            .requested_by(user_id.to_owned())
+            .commit(&self.executor)
+            .await;
+
+        // Same as `save`: losing the race to another unsave is fine.
+        if let Err(err) = committed {
+            if !self.load(&id, &user_id).await?.saved {
+                return Ok(());
+            }
+
+            return Err(err.into());
         }
 
         Ok(())
@@ -1,5 +1,7 @@
+pub mod comment;
 pub mod favorite;
 pub mod query;
+pub mod rating;
 pub mod saga;
 
 mod root;
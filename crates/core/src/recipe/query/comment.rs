@@ -0,0 +1,472 @@
+use evento::{
+    Cursor, Executor,
+    cursor::{Args, Edge, ReadResult, Value},
+    metadata::Event,
+    sql::Reader,
+    subscription::{Context, SubscriptionBuilder},
+};
+use imkitchen_db::recipe_comment::{RecipeComment, RecipeCommentFlag};
+use imkitchen_db::recipe_user::RecipeUser;
+use imkitchen_types::comment::{
+    Added, FlagReason, Flagged, Hidden, Replied, ReportResolution, ReportsResolved,
+};
+use imkitchen_types::recipe::Deleted;
+use sea_query::{
+    Alias, Expr, ExprTrait, Func, OnConflict, Order, Query, SelectStatement, SqliteQueryBuilder,
+};
+use sea_query_sqlx::SqlxBinder;
+use sqlx::{SqlitePool, prelude::FromRow};
+
+/// Replies shown under each comment when the caller doesn't say.
+pub const DEFAULT_REPLY_LIMIT: u16 = 3;
+
+#[derive(Debug, Default, Clone, FromRow, Cursor)]
+pub struct CommentView {
+    #[cursor(RecipeComment::Id, 1)]
+    pub id: String,
+    pub recipe_id: String,
+    pub parent_id: Option<String>,
+    pub user_id: String,
+    pub body: String,
+    #[cursor(RecipeComment::Position, 2)]
+    pub position: u64,
+    pub created_at: u64,
+    pub flag_count: u16,
+    pub is_hidden: bool,
+}
+
+#[derive(Debug, Default, Clone, FromRow)]
+pub struct FlaggedRecipeView {
+    pub id: String,
+    pub name: String,
+    pub owner_id: String,
+    pub owner_name: Option<String>,
+    pub flag_count: u32,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct CommentFlagView {
+    pub user_id: String,
+    pub reason: sqlx::types::Text<FlagReason>,
+    pub created_at: u64,
+}
+
+/// A top level comment with the first of its replies.
+#[derive(Debug, Clone)]
+pub struct CommentThread {
+    pub comment: CommentView,
+    /// Oldest first.
+    pub replies: Vec<CommentView>,
+    /// Where to pick up with [`crate::recipe::Module::comment_replies`] to
+    /// view more replies, `None` when they are all shown.
+    pub more_replies: Option<Value>,
+}
+
+pub struct CommentThreadsQuery {
+    pub recipe_id: String,
+    /// Replies shown per thread, see [`DEFAULT_REPLY_LIMIT`].
+    pub reply_limit: u16,
+    /// Pages over top level comments.
+    pub args: Args,
+}
+
+fn select_comments() -> SelectStatement {
+    Query::select()
+        .columns([
+            RecipeComment::Id,
+            RecipeComment::RecipeId,
+            RecipeComment::ParentId,
+            RecipeComment::UserId,
+            RecipeComment::Body,
+            RecipeComment::Position,
+            RecipeComment::CreatedAt,
+            RecipeComment::FlagCount,
+            RecipeComment::IsHidden,
+        ])
+        .from(RecipeComment::Table)
+        .to_owned()
+}
+
+impl<E: Executor> crate::recipe::Module<E> {
+    /// Top level comments of a recipe, newest first, each with its oldest
+    /// `reply_limit` replies. Hidden comments are left out, along with the
+    /// replies under them.
+    pub async fn comment_threads(
+        &self,
+        query: CommentThreadsQuery,
+    ) -> anyhow::Result<ReadResult<CommentThread>> {
+        let statement = select_comments()
+            .and_where(Expr::col(RecipeComment::RecipeId).eq(query.recipe_id))
+            .and_where(Expr::col(RecipeComment::ParentId).is_null())
+            .and_where(Expr::col(RecipeComment::IsHidden).eq(false))
+            .to_owned();
+
+        let comments = Reader::new(statement)
+            .desc()
+            .args(query.args)
+            .execute::<_, CommentView, _>(&self.read_db)
+            .await?;
+
+        let mut threads = vec![];
+        for edge in comments.edges {
+            let replies = self
+                .comment_replies(&edge.node.id, Args::forward(query.reply_limit, None))
+                .await?;
+
+            let has_more = replies.page_info.has_next_page;
+
+            threads.push(Edge {
+                cursor: edge.cursor,
+                node: CommentThread {
+                    more_replies: replies.page_info.end_cursor.filter(|_| has_more),
+                    replies: replies.edges.into_iter().map(|edge| edge.node).collect(),
+                    comment: edge.node,
+                },
+            });
+        }
+
+        Ok(ReadResult {
+            edges: threads,
+            page_info: comments.page_info,
+        })
+    }
+
+    /// Replies to a top level comment, oldest first.
+    pub async fn comment_replies(
+        &self,
+        comment_id: impl Into<String>,
+        args: Args,
+    ) -> anyhow::Result<ReadResult<CommentView>> {
+        let statement = select_comments()
+            .and_where(Expr::col(RecipeComment::ParentId).eq(comment_id.into()))
+            .and_where(Expr::col(RecipeComment::IsHidden).eq(false))
+            .to_owned();
+
+        Reader::new(statement)
+            .args(args)
+            .execute(&self.read_db)
+            .await
+    }
+
+    /// Comments with at least one flag, hidden or not, newest first. For the
+    /// admin review queue; resolved comments leave it until flagged again.
+    pub async fn filter_flagged_comments(
+        &self,
+        args: Args,
+    ) -> anyhow::Result<ReadResult<CommentView>> {
+        let statement = select_comments()
+            .and_where(Expr::col(RecipeComment::FlagCount).gt(0))
+            .to_owned();
+
+        Reader::new(statement)
+            .desc()
+            .args(args)
+            .execute(&self.read_db)
+            .await
+    }
+
+    /// Community recipes with flagged comments, most flags first. For the
+    /// admin moderation queue; recipes made private or deleted leave it.
+    pub async fn filter_flagged_recipes(
+        &self,
+        limit: u64,
+    ) -> anyhow::Result<Vec<FlaggedRecipeView>> {
+        let (sql, values) = Query::select()
+            .column((RecipeUser::Table, RecipeUser::Id))
+            .column((RecipeUser::Table, RecipeUser::Name))
+            .column((RecipeUser::Table, RecipeUser::OwnerId))
+            .column((RecipeUser::Table, RecipeUser::OwnerName))
+            .expr_as(
+                Func::sum(Expr::col((RecipeComment::Table, RecipeComment::FlagCount))),
+                Alias::new("flag_count"),
+            )
+            .from(RecipeComment::Table)
+            .inner_join(
+                RecipeUser::Table,
+                Expr::col((RecipeUser::Table, RecipeUser::Id))
+                    .equals((RecipeComment::Table, RecipeComment::RecipeId)),
+            )
+            .and_where(Expr::col((RecipeUser::Table, RecipeUser::IsShared)).eq(true))
+            .and_where(Expr::col((RecipeComment::Table, RecipeComment::FlagCount)).gt(0))
+            .group_by_col((RecipeUser::Table, RecipeUser::Id))
+            .order_by_expr(Expr::cust("flag_count"), Order::Desc)
+            .order_by((RecipeUser::Table, RecipeUser::Name), Order::Asc)
+            .limit(limit)
+            .build_sqlx(SqliteQueryBuilder);
+
+        Ok(
+            sqlx::query_as_with::<_, FlaggedRecipeView, _>(sqlx::AssertSqlSafe(sql), values)
+                .fetch_all(&self.read_db)
+                .await?,
+        )
+    }
+
+    /// A comment whether or not it is hidden.
+    pub async fn find_comment(&self, id: impl Into<String>) -> anyhow::Result<Option<CommentView>> {
+        let (sql, values) = select_comments()
+            .and_where(Expr::col(RecipeComment::Id).eq(id.into()))
+            .build_sqlx(SqliteQueryBuilder);
+
+        Ok(
+            sqlx::query_as_with::<_, CommentView, _>(sqlx::AssertSqlSafe(sql), values)
+                .fetch_optional(&self.read_db)
+                .await?,
+        )
+    }
+
+    /// Who flagged a comment and why, oldest first.
+    pub async fn comment_flags(
+        &self,
+        comment_id: impl Into<String>,
+    ) -> anyhow::Result<Vec<CommentFlagView>> {
+        let (sql, values) = Query::select()
+            .columns([
+                RecipeCommentFlag::UserId,
+                RecipeCommentFlag::Reason,
+                RecipeCommentFlag::CreatedAt,
+            ])
+            .from(RecipeCommentFlag::Table)
+            .and_where(Expr::col(RecipeCommentFlag::CommentId).eq(comment_id.into()))
+            .order_by(RecipeCommentFlag::CreatedAt, Order::Asc)
+            .build_sqlx(SqliteQueryBuilder);
+
+        Ok(
+            sqlx::query_as_with::<_, CommentFlagView, _>(sqlx::AssertSqlSafe(sql), values)
+                .fetch_all(&self.read_db)
+                .await?,
+        )
+    }
+}
+
+pub fn subscription<E: Executor>() -> SubscriptionBuilder<E> {
+    SubscriptionBuilder::new("recipe-comment")
+        .handler(handle_added())
+        .handler(handle_replied())
+        .handler(handle_flagged())
+        .handler(handle_hidden())
+        .handler(handle_reports_resolved())
+        .handler(handle_deleted())
+}
+
+async fn insert_comment(
+    pool: &SqlitePool,
+    id: &str,
+    recipe_id: String,
+    parent_id: Option<String>,
+    user_id: String,
+    body: String,
+    created_at: u64,
+) -> anyhow::Result<()> {
+    let (sql, values) = Query::insert()
+        .into_table(RecipeComment::Table)
+        .columns([
+            RecipeComment::Id,
+            RecipeComment::RecipeId,
+            RecipeComment::ParentId,
+            RecipeComment::UserId,
+            RecipeComment::Body,
+            RecipeComment::Position,
+            RecipeComment::CreatedAt,
+        ])
+        .values_panic([
+            id.into(),
+            recipe_id.into(),
+            parent_id.into(),
+            user_id.into(),
+            body.into(),
+            Expr::cust("(SELECT COALESCE(MAX(position), 0) + 1 FROM recipe_comment)"),
+            created_at.into(),
+        ])
+        .on_conflict(
+            OnConflict::column(RecipeComment::Id)
+                .do_nothing()
+                .to_owned(),
+        )
+        .build_sqlx(SqliteQueryBuilder);
+
+    sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+#[evento::subscription]
+async fn handle_added<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<Added>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<SqlitePool>();
+
+    insert_comment(
+        &pool,
+        &event.aggregate_id,
+        event.data.recipe_id.to_owned(),
+        None,
+        event.metadata.requested_by()?,
+        event.data.body.to_owned(),
+        event.timestamp,
+    )
+    .await
+}
+
+#[evento::subscription]
+async fn handle_replied<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<Replied>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<SqlitePool>();
+
+    insert_comment(
+        &pool,
+        &event.aggregate_id,
+        event.data.recipe_id.to_owned(),
+        Some(event.data.parent_id.to_owned()),
+        event.metadata.requested_by()?,
+        event.data.body.to_owned(),
+        event.timestamp,
+    )
+    .await
+}
+
+#[evento::subscription]
+async fn handle_flagged<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<Flagged>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<SqlitePool>();
+
+    let (sql, values) = Query::insert()
+        .into_table(RecipeCommentFlag::Table)
+        .columns([
+            RecipeCommentFlag::CommentId,
+            RecipeCommentFlag::UserId,
+            RecipeCommentFlag::Reason,
+            RecipeCommentFlag::CreatedAt,
+        ])
+        .values_panic([
+            event.aggregate_id.to_owned().into(),
+            event.metadata.requested_by()?.into(),
+            event.data.reason.to_string().into(),
+            event.timestamp.into(),
+        ])
+        .on_conflict(
+            OnConflict::columns([RecipeCommentFlag::CommentId, RecipeCommentFlag::UserId])
+                .do_nothing()
+                .to_owned(),
+        )
+        .build_sqlx(SqliteQueryBuilder);
+
+    let inserted = sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+        .execute(&pool)
+        .await?
+        .rows_affected();
+
+    if inserted == 0 {
+        return Ok(());
+    }
+
+    let (sql, values) = Query::update()
+        .table(RecipeComment::Table)
+        .value(
+            RecipeComment::FlagCount,
+            Expr::col(RecipeComment::FlagCount).add(1),
+        )
+        .and_where(Expr::col(RecipeComment::Id).eq(&event.aggregate_id))
+        .build_sqlx(SqliteQueryBuilder);
+
+    sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+        .execute(&pool)
+        .await?;
+
+    Ok(())
+}
+
+#[evento::subscription]
+async fn handle_hidden<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<Hidden>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<SqlitePool>();
+
+    let (sql, values) = Query::update()
+        .table(RecipeComment::Table)
+        .value(RecipeComment::IsHidden, true)
+        .and_where(Expr::col(RecipeComment::Id).eq(&event.aggregate_id))
+        .build_sqlx(SqliteQueryBuilder);
+
+    sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+        .execute(&pool)
+        .await?;
+
+    Ok(())
+}
+
+#[evento::subscription]
+async fn handle_reports_resolved<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<ReportsResolved>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<SqlitePool>();
+
+    let (sql, values) = Query::delete()
+        .from_table(RecipeCommentFlag::Table)
+        .and_where(Expr::col(RecipeCommentFlag::CommentId).eq(&event.aggregate_id))
+        .build_sqlx(SqliteQueryBuilder);
+
+    sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+        .execute(&pool)
+        .await?;
+
+    let (sql, values) = Query::update()
+        .table(RecipeComment::Table)
+        .value(RecipeComment::FlagCount, 0)
+        .value(
+            RecipeComment::IsHidden,
+            event.data.resolution == ReportResolution::Removed,
+        )
+        .and_where(Expr::col(RecipeComment::Id).eq(&event.aggregate_id))
+        .build_sqlx(SqliteQueryBuilder);
+
+    sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+        .execute(&pool)
+        .await?;
+
+    Ok(())
+}
+
+#[evento::subscription]
+async fn handle_deleted<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<Deleted>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<SqlitePool>();
+
+    let (sql, values) = Query::delete()
+        .from_table(RecipeCommentFlag::Table)
+        .and_where(
+            Expr::col(RecipeCommentFlag::CommentId).in_subquery(
+                Query::select()
+                    .column(RecipeComment::Id)
+                    .from(RecipeComment::Table)
+                    .and_where(Expr::col(RecipeComment::RecipeId).eq(&event.aggregate_id))
+                    .to_owned(),
+            ),
+        )
+        .build_sqlx(SqliteQueryBuilder);
+
+    sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+        .execute(&pool)
+        .await?;
+
+    let (sql, values) = Query::delete()
+        .from_table(RecipeComment::Table)
+        .and_where(Expr::col(RecipeComment::RecipeId).eq(&event.aggregate_id))
+        .build_sqlx(SqliteQueryBuilder);
+
+    sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+        .execute(&pool)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,147 @@
+use evento::{
+    Executor,
+    metadata::Event,
+    subscription::{Context, SubscriptionBuilder},
+};
+use imkitchen_db::{recipe_cooked::RecipeCooked, recipe_user::RecipeUser};
+use imkitchen_types::mealplan::{DaySlotStatus, SlotRecipeStatusChanged};
+use sea_query::{Alias, Expr, ExprTrait, Func, OnConflict, Order, Query, SqliteQueryBuilder};
+use sea_query_sqlx::SqlxBinder;
+use sqlx::prelude::FromRow;
+use std::collections::HashMap;
+
+#[derive(Default, FromRow)]
+pub struct MostCookedRow {
+    pub id: String,
+    pub name: String,
+    pub cook_count: u32,
+}
+
+impl<E: Executor> crate::recipe::Module<E> {
+    /// Recipes the user cooked the most, counting each completed meal-plan
+    /// slot once. Deleted recipes are left out.
+    pub async fn most_cooked(
+        &self,
+        owner_id: impl Into<String>,
+        limit: u64,
+    ) -> anyhow::Result<Vec<MostCookedRow>> {
+        let owner_id = owner_id.into();
+        let statement = Query::select()
+            .column((RecipeUser::Table, RecipeUser::Id))
+            .column((RecipeUser::Table, RecipeUser::Name))
+            .expr_as(
+                Func::count(Expr::col((RecipeCooked::Table, RecipeCooked::RecipeId))),
+                Alias::new("cook_count"),
+            )
+            .from(RecipeCooked::Table)
+            .inner_join(
+                RecipeUser::Table,
+                Expr::col((RecipeUser::Table, RecipeUser::Id))
+                    .equals((RecipeCooked::Table, RecipeCooked::RecipeId)),
+            )
+            .and_where(Expr::col((RecipeCooked::Table, RecipeCooked::UserId)).eq(owner_id))
+            .group_by_col((RecipeUser::Table, RecipeUser::Id))
+            .order_by_expr(Expr::cust("cook_count"), Order::Desc)
+            .order_by((RecipeUser::Table, RecipeUser::Name), Order::Asc)
+            .limit(limit)
+            .to_owned();
+
+        let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+
+        Ok(sqlx::query_as_with(sqlx::AssertSqlSafe(sql), values)
+            .fetch_all(&self.read_db)
+            .await?)
+    }
+}
+
+#[derive(FromRow)]
+struct LastCookedRow {
+    recipe_id: String,
+    cooked_at: u64,
+}
+
+/// When `user_id` last completed each of the given recipes (unix time);
+/// recipes never cooked are absent.
+pub(crate) async fn last_cooked(
+    pool: &sqlx::SqlitePool,
+    user_id: impl Into<String>,
+    ids: impl IntoIterator<Item = String>,
+) -> anyhow::Result<HashMap<String, u64>> {
+    let statement = Query::select()
+        .column(RecipeCooked::RecipeId)
+        .expr_as(
+            Func::max(Expr::col(RecipeCooked::CookedAt)),
+            Alias::new("cooked_at"),
+        )
+        .from(RecipeCooked::Table)
+        .and_where(Expr::col(RecipeCooked::UserId).eq(user_id.into()))
+        .and_where(Expr::col(RecipeCooked::RecipeId).is_in(ids))
+        .group_by_col(RecipeCooked::RecipeId)
+        .to_owned();
+
+    let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+    let rows = sqlx::query_as_with::<_, LastCookedRow, _>(sqlx::AssertSqlSafe(sql), values)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| (r.recipe_id, r.cooked_at))
+        .collect())
+}
+
+pub fn subscription<E: Executor>() -> SubscriptionBuilder<E> {
+    SubscriptionBuilder::new("recipe-cook-count").handler(handle_slot_recipe_status_changed())
+}
+
+/// A slot counts as cooked while its status is `Completed`; moving it back to
+/// idle or cooking un-counts it, so repeated transitions on the same slot
+/// never inflate the count.
+#[evento::subscription]
+async fn handle_slot_recipe_status_changed<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<SlotRecipeStatusChanged>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<sqlx::SqlitePool>();
+
+    let statement = if event.data.status == DaySlotStatus::Completed {
+        Query::insert()
+            .into_table(RecipeCooked::Table)
+            .columns([
+                RecipeCooked::UserId,
+                RecipeCooked::RecipeId,
+                RecipeCooked::Date,
+                RecipeCooked::CookedAt,
+            ])
+            .values_panic([
+                event.aggregate_id.to_owned().into(),
+                event.data.recipe_id.to_owned().into(),
+                event.data.date.into(),
+                event.timestamp.into(),
+            ])
+            .on_conflict(
+                OnConflict::columns([
+                    RecipeCooked::UserId,
+                    RecipeCooked::Date,
+                    RecipeCooked::RecipeId,
+                ])
+                .do_nothing()
+                .to_owned(),
+            )
+            .build_sqlx(SqliteQueryBuilder)
+    } else {
+        Query::delete()
+            .from_table(RecipeCooked::Table)
+            .and_where(Expr::col(RecipeCooked::UserId).eq(&event.aggregate_id))
+            .and_where(Expr::col(RecipeCooked::Date).eq(event.data.date))
+            .and_where(Expr::col(RecipeCooked::RecipeId).eq(&event.data.recipe_id))
+            .build_sqlx(SqliteQueryBuilder)
+    };
+
+    let (sql, values) = statement;
+    sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+        .execute(&pool)
+        .await?;
+
+    Ok(())
+}
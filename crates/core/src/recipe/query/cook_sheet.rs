@@ -0,0 +1,34 @@
+use imkitchen_types::recipe::{Ingredient, IngredientCategory};
+
+use crate::recipe::query::user::UserView;
+
+/// Everything needed at the stove for one recipe: its ingredients scaled and
+/// grouped by aisle, followed by the numbered steps.
+#[derive(Debug, Clone)]
+pub struct CookSheet {
+    pub servings: u16,
+    pub aisles: Vec<(Option<IngredientCategory>, Vec<Ingredient>)>,
+    pub steps: Vec<(usize, String)>,
+}
+
+impl UserView {
+    /// The cook sheet for `servings` people. Ingredients are scaled with
+    /// [`UserView::scaled_ingredients`] and grouped in the same aisle order
+    /// as the shopping list.
+    pub fn cook_sheet(&self, servings: u16) -> CookSheet {
+        let order = IngredientCategory::aisle_order(&[]);
+        let ingredients = self.scaled_ingredients(servings);
+
+        CookSheet {
+            servings,
+            aisles: Ingredient::group_by_aisle(&ingredients, &order),
+            steps: self
+                .instructions
+                .0
+                .iter()
+                .enumerate()
+                .map(|(i, instruction)| (i + 1, instruction.description.to_owned()))
+                .collect(),
+        }
+    }
+}
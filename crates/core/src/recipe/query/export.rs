@@ -0,0 +1,96 @@
+use evento::Executor;
+use imkitchen_db::recipe_user::RecipeUser;
+use imkitchen_types::recipe::{DietaryRestriction, Ingredient, Instruction, RecipeType};
+use sea_query::{Expr, ExprTrait, Order, Query, SqliteQueryBuilder};
+use sea_query_sqlx::SqlxBinder;
+use sqlx::prelude::FromRow;
+
+use crate::recipe::ImportInput;
+
+/// Recipes read per [`crate::recipe::Module::export_page`] call.
+pub const EXPORT_PAGE_SIZE: u64 = 50;
+
+#[derive(FromRow)]
+struct ExportRow {
+    id: String,
+    recipe_type: sqlx::types::Text<RecipeType>,
+    name: String,
+    origin: Option<String>,
+    description: String,
+    household_size: u16,
+    prep_time: u16,
+    cook_time: u16,
+    ingredients: evento::sql_types::Bitcode<Vec<Ingredient>>,
+    instructions: evento::sql_types::Bitcode<Vec<Instruction>>,
+    dietary_restrictions: sqlx::types::Json<Vec<DietaryRestriction>>,
+    accepts_accompaniment: bool,
+    advance_prep: String,
+}
+
+impl<E: Executor> crate::recipe::Module<E> {
+    /// A page of the recipes `owner_id` wrote, ordered by id, as the input
+    /// [`crate::recipe::Module::import`] takes back. Drafts and saved
+    /// community recipes are left out. Pass the last id of the previous page
+    /// as `after` to continue.
+    pub async fn export_page(
+        &self,
+        owner_id: impl Into<String>,
+        after: Option<String>,
+    ) -> anyhow::Result<Vec<(String, ImportInput)>> {
+        let mut statement = Query::select()
+            .columns([
+                RecipeUser::Id,
+                RecipeUser::RecipeType,
+                RecipeUser::Name,
+                RecipeUser::Origin,
+                RecipeUser::Description,
+                RecipeUser::HouseholdSize,
+                RecipeUser::PrepTime,
+                RecipeUser::CookTime,
+                RecipeUser::Ingredients,
+                RecipeUser::Instructions,
+                RecipeUser::DietaryRestrictions,
+                RecipeUser::AcceptsAccompaniment,
+                RecipeUser::AdvancePrep,
+            ])
+            .from(RecipeUser::Table)
+            .and_where(Expr::col(RecipeUser::OwnerId).eq(owner_id.into()))
+            .and_where(Expr::col(RecipeUser::Name).not_equals(""))
+            .order_by(RecipeUser::Id, Order::Asc)
+            .limit(EXPORT_PAGE_SIZE)
+            .to_owned();
+
+        if let Some(after) = after {
+            statement.and_where(Expr::col(RecipeUser::Id).gt(after));
+        }
+
+        let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+        let rows = sqlx::query_as_with::<_, ExportRow, _>(sqlx::AssertSqlSafe(sql), values)
+            .fetch_all(&self.read_db)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                (
+                    row.id,
+                    ImportInput {
+                        recipe_type: row.recipe_type.0,
+                        name: row.name,
+                        origin: row.origin,
+                        description: row.description,
+                        household_size: row.household_size,
+                        prep_time: row.prep_time,
+                        cook_time: row.cook_time,
+                        ingredients: row.ingredients.0,
+                        instructions: row.instructions.0,
+                        advance_prep: row.advance_prep,
+                        accepts_accompaniment: row.accepts_accompaniment,
+                        dietary_restrictions: row.dietary_restrictions.0,
+                        force: false,
+                    },
+                )
+            })
+            .collect())
+    }
+}
@@ -1,4 +1,11 @@
+pub mod comment;
+pub mod cook_count;
+pub mod cook_sheet;
 pub mod embeddable;
+pub mod export;
+pub mod pantry;
+pub mod rating;
+pub mod similar;
 pub mod thumbnail;
 pub mod user;
 pub mod user_fts;
@@ -0,0 +1,73 @@
+use evento::Executor;
+use imkitchen_db::recipe_user::RecipeUser;
+use imkitchen_types::recipe::Ingredient;
+use sea_query::{Expr, ExprTrait, Query, SqliteQueryBuilder};
+use sea_query_sqlx::SqlxBinder;
+use sqlx::prelude::FromRow;
+use std::collections::HashSet;
+
+#[derive(FromRow)]
+struct PantryRecipeRow {
+    id: String,
+    name: String,
+    ingredients: evento::sql_types::Bitcode<Vec<Ingredient>>,
+}
+
+pub struct CookableRecipe {
+    pub id: String,
+    pub name: String,
+    /// Ingredients of the recipe not found in the pantry, in recipe order.
+    pub missing: Vec<Ingredient>,
+}
+
+impl<E: Executor> crate::recipe::Module<E> {
+    /// The owner's recipes ranked by how few ingredients are missing from
+    /// `available`, fully cookable ones first. Names match case-insensitively;
+    /// recipes without ingredients are left out.
+    pub async fn cookable_from(
+        &self,
+        owner_id: impl Into<String>,
+        available: &[String],
+    ) -> anyhow::Result<Vec<CookableRecipe>> {
+        let owner_id = owner_id.into();
+        let statement = Query::select()
+            .columns([RecipeUser::Id, RecipeUser::Name, RecipeUser::Ingredients])
+            .from(RecipeUser::Table)
+            .and_where(Expr::col(RecipeUser::OwnerId).eq(owner_id))
+            .to_owned();
+
+        let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+        let rows = sqlx::query_as_with::<_, PantryRecipeRow, _>(sqlx::AssertSqlSafe(sql), values)
+            .fetch_all(&self.read_db)
+            .await?;
+
+        let pantry = available
+            .iter()
+            .map(|name| name.trim().to_lowercase())
+            .collect::<HashSet<_>>();
+
+        let mut recipes = rows
+            .into_iter()
+            .filter(|row| !row.ingredients.0.is_empty())
+            .map(|row| CookableRecipe {
+                missing: row
+                    .ingredients
+                    .0
+                    .into_iter()
+                    .filter(|i| !pantry.contains(&i.name.trim().to_lowercase()))
+                    .collect(),
+                id: row.id,
+                name: row.name,
+            })
+            .collect::<Vec<_>>();
+
+        recipes.sort_by(|a, b| {
+            a.missing
+                .len()
+                .cmp(&b.missing.len())
+                .then_with(|| a.name.cmp(&b.name))
+        });
+
+        Ok(recipes)
+    }
+}
@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+
+use evento::{
+    Executor,
+    metadata::Event,
+    subscription::{Context, SubscriptionBuilder},
+};
+use imkitchen_db::recipe_rating::RecipeRating;
+use imkitchen_db::recipe_rating_stat::RecipeRatingStat;
+use imkitchen_types::{rating::Rated, recipe::Deleted};
+use sea_query::{Alias, Expr, ExprTrait, Func, OnConflict, Query, SqliteQueryBuilder};
+use sea_query_sqlx::SqlxBinder;
+use sqlx::{SqlitePool, prelude::FromRow};
+
+#[derive(FromRow)]
+struct AverageRow {
+    recipe_id: String,
+    average: f32,
+}
+
+/// Average stars of each of the given recipes; unrated recipes are absent.
+pub(crate) async fn average_ratings(
+    pool: &SqlitePool,
+    ids: impl IntoIterator<Item = String>,
+) -> anyhow::Result<HashMap<String, f32>> {
+    let statement = Query::select()
+        .column(RecipeRating::RecipeId)
+        .expr_as(
+            Func::avg(Expr::col(RecipeRating::Stars)),
+            Alias::new("average"),
+        )
+        .from(RecipeRating::Table)
+        .and_where(Expr::col(RecipeRating::RecipeId).is_in(ids))
+        .group_by_col(RecipeRating::RecipeId)
+        .to_owned();
+
+    let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+    let rows = sqlx::query_as_with::<_, AverageRow, _>(sqlx::AssertSqlSafe(sql), values)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(rows.into_iter().map(|r| (r.recipe_id, r.average)).collect())
+}
+
+/// How a recipe is rated overall.
+#[derive(Debug, Default, Clone, PartialEq, FromRow)]
+pub struct RatingStat {
+    pub rating_count: u32,
+    /// Average stars, 0.0 while nobody rated the recipe.
+    pub average_rating: f64,
+}
+
+impl<E: Executor> crate::recipe::Module<E> {
+    pub async fn find_rating_stat(&self, id: impl Into<String>) -> anyhow::Result<RatingStat> {
+        let (sql, values) = Query::select()
+            .columns([
+                RecipeRatingStat::RatingCount,
+                RecipeRatingStat::AverageRating,
+            ])
+            .from(RecipeRatingStat::Table)
+            .and_where(Expr::col(RecipeRatingStat::Id).eq(id.into()))
+            .build_sqlx(SqliteQueryBuilder);
+
+        Ok(
+            sqlx::query_as_with::<_, RatingStat, _>(sqlx::AssertSqlSafe(sql), values)
+                .fetch_optional(&self.read_db)
+                .await?
+                .unwrap_or_default(),
+        )
+    }
+}
+
+/// Recomputes the recipe's row in `recipe_rating_stat` from its ratings.
+async fn update_rating_stat(pool: &SqlitePool, recipe_id: &str) -> anyhow::Result<()> {
+    let (sql, values) = Query::select()
+        .expr(Func::count(Expr::col(RecipeRating::Stars)))
+        .expr(Func::avg(Expr::col(RecipeRating::Stars)))
+        .from(RecipeRating::Table)
+        .and_where(Expr::col(RecipeRating::RecipeId).eq(recipe_id))
+        .build_sqlx(SqliteQueryBuilder);
+
+    let (rating_count, average_rating) =
+        sqlx::query_as_with::<_, (u32, Option<f64>), _>(sqlx::AssertSqlSafe(sql), values)
+            .fetch_one(pool)
+            .await?;
+
+    let (sql, values) = Query::insert()
+        .into_table(RecipeRatingStat::Table)
+        .columns([
+            RecipeRatingStat::Id,
+            RecipeRatingStat::RatingCount,
+            RecipeRatingStat::AverageRating,
+        ])
+        .values_panic([
+            recipe_id.into(),
+            rating_count.into(),
+            average_rating.unwrap_or_default().into(),
+        ])
+        .on_conflict(
+            OnConflict::column(RecipeRatingStat::Id)
+                .update_columns([
+                    RecipeRatingStat::RatingCount,
+                    RecipeRatingStat::AverageRating,
+                ])
+                .to_owned(),
+        )
+        .build_sqlx(SqliteQueryBuilder);
+
+    sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+pub fn subscription<E: Executor>() -> SubscriptionBuilder<E> {
+    SubscriptionBuilder::new("recipe-rating")
+        .handler(handle_rated())
+        .handler(handle_deleted())
+}
+
+#[evento::subscription]
+async fn handle_rated<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<Rated>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<SqlitePool>();
+    let user_id = event.metadata.requested_by()?;
+
+    let (sql, values) = Query::insert()
+        .into_table(RecipeRating::Table)
+        .columns([
+            RecipeRating::UserId,
+            RecipeRating::RecipeId,
+            RecipeRating::Stars,
+        ])
+        .values_panic([
+            user_id.into(),
+            event.data.recipe_id.to_owned().into(),
+            event.data.stars.into(),
+        ])
+        .on_conflict(
+            OnConflict::columns([RecipeRating::UserId, RecipeRating::RecipeId])
+                .update_column(RecipeRating::Stars)
+                .to_owned(),
+        )
+        .build_sqlx(SqliteQueryBuilder);
+
+    sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+        .execute(&pool)
+        .await?;
+
+    update_rating_stat(&pool, &event.data.recipe_id).await?;
+
+    Ok(())
+}
+
+#[evento::subscription]
+async fn handle_deleted<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<Deleted>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<SqlitePool>();
+
+    let (sql, values) = Query::delete()
+        .from_table(RecipeRating::Table)
+        .and_where(Expr::col(RecipeRating::RecipeId).eq(&event.aggregate_id))
+        .build_sqlx(SqliteQueryBuilder);
+
+    sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+        .execute(&pool)
+        .await?;
+
+    let (sql, values) = Query::delete()
+        .from_table(RecipeRatingStat::Table)
+        .and_where(Expr::col(RecipeRatingStat::Id).eq(&event.aggregate_id))
+        .build_sqlx(SqliteQueryBuilder);
+
+    sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+        .execute(&pool)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,167 @@
+use std::collections::HashSet;
+
+use evento::Executor;
+use imkitchen_db::recipe_user::RecipeUser;
+use imkitchen_types::recipe::{CuisineType, DietaryRestriction, Ingredient};
+use sea_query::{Cond, Expr, ExprTrait, Order, Query, SqliteQueryBuilder};
+use sea_query_sqlx::SqlxBinder;
+use sqlx::prelude::FromRow;
+
+use super::user::UserViewList;
+
+/// Most recent accessible recipes compared against by
+/// [`crate::recipe::Module::similar`].
+pub const SIMILAR_CANDIDATES: u64 = 500;
+
+#[derive(FromRow)]
+struct SimilarRow {
+    #[sqlx(flatten)]
+    recipe: UserViewList,
+    cuisine_type: Option<CuisineType>,
+    tags: sqlx::types::Json<Vec<String>>,
+    ingredients: evento::sql_types::Bitcode<Vec<Ingredient>>,
+}
+
+struct Profile<'a> {
+    cuisine_type: Option<&'a CuisineType>,
+    dietary_restrictions: &'a [DietaryRestriction],
+    tags: &'a [String],
+    ingredients: HashSet<String>,
+}
+
+impl<'a> Profile<'a> {
+    fn new(
+        cuisine_type: Option<&'a CuisineType>,
+        dietary_restrictions: &'a [DietaryRestriction],
+        tags: &'a [String],
+        ingredients: &[Ingredient],
+    ) -> Self {
+        Self {
+            cuisine_type,
+            dietary_restrictions,
+            tags,
+            ingredients: ingredients
+                .iter()
+                .map(|i| i.name.trim().to_lowercase())
+                .filter(|name| !name.is_empty())
+                .collect(),
+        }
+    }
+
+    /// Same cuisine counts 3, each shared dietary tag or tag 1, and the
+    /// Jaccard overlap of ingredient names up to 2.
+    fn score(&self, other: &Profile<'_>) -> f32 {
+        let mut score = 0.0;
+
+        if self.cuisine_type.is_some() && self.cuisine_type == other.cuisine_type {
+            score += 3.0;
+        }
+
+        score += self
+            .dietary_restrictions
+            .iter()
+            .filter(|d| d.exists_in(other.dietary_restrictions))
+            .count() as f32;
+
+        score += self
+            .tags
+            .iter()
+            .filter(|tag| other.tags.contains(tag))
+            .count() as f32;
+
+        let union = self.ingredients.union(&other.ingredients).count();
+        if union > 0 {
+            let shared = self.ingredients.intersection(&other.ingredients).count();
+            score += 2.0 * shared as f32 / union as f32;
+        }
+
+        score
+    }
+}
+
+impl<E: Executor> crate::recipe::Module<E> {
+    /// Recipes of the same course as `recipe_id`, most alike first, among
+    /// the community recipes and `viewer_id`'s own. Ties, including recipes
+    /// with nothing else in common, go to the most recent.
+    pub async fn similar(
+        &self,
+        recipe_id: impl Into<String>,
+        viewer_id: impl Into<String>,
+        limit: usize,
+    ) -> anyhow::Result<Vec<UserViewList>> {
+        let Some(recipe) = self.find_user(recipe_id).await? else {
+            return Ok(vec![]);
+        };
+
+        let statement = Query::select()
+            .columns([
+                RecipeUser::Id,
+                RecipeUser::OwnerId,
+                RecipeUser::OwnerName,
+                RecipeUser::RecipeType,
+                RecipeUser::Name,
+                RecipeUser::Slug,
+                RecipeUser::Description,
+                RecipeUser::PrepTime,
+                RecipeUser::CookTime,
+                RecipeUser::DietaryRestrictions,
+                RecipeUser::AcceptsAccompaniment,
+                RecipeUser::IsShared,
+                RecipeUser::DifficultyScore,
+                RecipeUser::CreatedAt,
+                RecipeUser::ThumbnailVersion,
+                RecipeUser::BlurPlaceholder,
+                RecipeUser::CuisineType,
+                RecipeUser::Tags,
+                RecipeUser::Ingredients,
+            ])
+            .from(RecipeUser::Table)
+            .and_where(Expr::col(RecipeUser::Id).ne(&recipe.id))
+            .and_where(Expr::col(RecipeUser::RecipeType).eq(recipe.recipe_type.to_string()))
+            .and_where(Expr::col(RecipeUser::Name).not_equals(""))
+            .cond_where(
+                Cond::any()
+                    .add(Expr::col(RecipeUser::IsShared).eq(true))
+                    .add(Expr::col(RecipeUser::OwnerId).eq(viewer_id.into())),
+            )
+            .order_by(RecipeUser::CreatedAt, Order::Desc)
+            .limit(SIMILAR_CANDIDATES)
+            .to_owned();
+
+        let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+        let rows = sqlx::query_as_with::<_, SimilarRow, _>(sqlx::AssertSqlSafe(sql), values)
+            .fetch_all(&self.read_db)
+            .await?;
+
+        let profile = Profile::new(
+            recipe.cuisine_type.as_ref(),
+            &recipe.dietary_restrictions.0,
+            &recipe.tags.0,
+            &recipe.ingredients.0,
+        );
+
+        let mut scored = rows
+            .into_iter()
+            .map(|row| {
+                let score = profile.score(&Profile::new(
+                    row.cuisine_type.as_ref(),
+                    &row.recipe.dietary_restrictions.0,
+                    &row.tags.0,
+                    &row.ingredients.0,
+                ));
+
+                (score, row.recipe)
+            })
+            .collect::<Vec<_>>();
+
+        // Candidates come newest first and the sort is stable, so ties keep
+        // favoring recent recipes.
+        scored.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+
+        Ok(scored
+            .into_iter()
+            .take(limit)
+            .map(|(_, recipe)| recipe)
+            .collect())
+    }
+}
@@ -7,16 +7,17 @@ use evento::{
 };
 use image::imageops::FilterType;
 use imkitchen_db::mealplan_recipe::MealPlanRecipe;
+use imkitchen_db::recipe_rating_stat::RecipeRatingStat;
 use imkitchen_db::recipe_thumbnail::RecipeThumbnail;
 use imkitchen_db::recipe_user::{RecipeUser, RecipeUserFts};
 use imkitchen_types::recipe::{
-    AdvancePrepChanged, BasicInformationChanged, Created, Deleted, DietaryRestriction,
-    DietaryRestrictionsChanged, Imported, Ingredient, IngredientsChanged, Instruction,
-    InstructionsChanged, MadePrivate, MainCourseOptionsChanged, Recipe, RecipeType,
-    RecipeTypeChanged, SharedToCommunity, ThumbnailResized,
+    AdvancePrepChanged, BasicInformationChanged, Created, CuisineType, CuisineTypeChanged, Deleted,
+    DietaryRestriction, DietaryRestrictionsChanged, Imported, Ingredient, IngredientsChanged,
+    Instruction, InstructionsChanged, MadePrivate, MainCourseOptionsChanged, Recipe, RecipeType,
+    RecipeTypeChanged, SharedToCommunity, TagsChanged, ThumbnailResized,
 };
 use sea_query::{
-    Alias, Asterisk, Expr, ExprTrait, Func, OnConflict, Query, SimpleExpr, SqliteQueryBuilder,
+    Alias, Asterisk, Cond, Expr, ExprTrait, Func, OnConflict, Query, SimpleExpr, SqliteQueryBuilder,
 };
 use sea_query_sqlx::SqlxBinder;
 use serde::Deserialize;
@@ -30,6 +31,8 @@ pub enum SortBy {
     RecentlyAdded,
     Easiest,
     Hardest,
+    /// Best average stars first.
+    TopRated,
     Random,
 }
 
@@ -39,6 +42,7 @@ pub struct UserView {
     pub owner_id: String,
     pub owner_name: Option<String>,
     pub recipe_type: sqlx::types::Text<RecipeType>,
+    pub cuisine_type: Option<CuisineType>,
     pub name: String,
     pub slug: String,
     pub origin: Option<String>,
@@ -49,6 +53,7 @@ pub struct UserView {
     pub ingredients: evento::sql_types::Bitcode<Vec<Ingredient>>,
     pub instructions: evento::sql_types::Bitcode<Vec<Instruction>>,
     pub dietary_restrictions: sqlx::types::Json<Vec<DietaryRestriction>>,
+    pub tags: sqlx::types::Json<Vec<String>>,
     pub accepts_accompaniment: bool,
     pub advance_prep: String,
     pub is_shared: bool,
@@ -58,6 +63,18 @@ pub struct UserView {
     pub blur_placeholder: Option<String>,
 }
 
+impl UserView {
+    /// Ingredients for `target_household` people rather than the household
+    /// size the recipe was written for. See [`Ingredient::scaled`].
+    pub fn scaled_ingredients(&self, target_household: u16) -> Vec<Ingredient> {
+        self.ingredients
+            .0
+            .iter()
+            .map(|ingredient| ingredient.scaled(self.household_size, target_household))
+            .collect()
+    }
+}
+
 #[derive(Debug, Default, Clone, FromRow, Cursor)]
 pub struct UserViewList {
     #[cursor(RecipeUser::Id, 1)]
@@ -66,6 +83,8 @@ pub struct UserViewList {
     // `RecipeUser::Id`) only so both `by_relevance` columns share one enum, as
     // the cursor derive requires; the outer query resolves it to `sub.id`.
     #[cursor(by_relevance, RecipeUserFts::Id, 1)]
+    // Same for `by_rating`, resolved to `sub.id` by the rating sort.
+    #[cursor(by_rating, RecipeRatingStat::Id, 1)]
     pub id: String,
     pub owner_id: String,
     pub owner_name: Option<String>,
@@ -91,6 +110,19 @@ pub struct UserViewList {
     #[cursor(by_relevance, RecipeUserFts::Rank, 2)]
     #[sqlx(default)]
     pub rank: f64,
+    /// From `recipe_rating_stat`, 0 while nobody rated the recipe.
+    #[sqlx(default)]
+    pub rating_count: u32,
+    #[cursor(by_rating, RecipeRatingStat::AverageRating, 2)]
+    #[sqlx(default)]
+    pub average_rating: f64,
+}
+
+impl UserViewList {
+    /// Average stars with one decimal, e.g. "4.5"; `None` while unrated.
+    pub fn average_stars(&self) -> Option<String> {
+        (self.rating_count > 0).then(|| format!("{:.1}", self.average_rating))
+    }
 }
 
 /// Compact recipe view for listing recipes by id (e.g. the shopping list's
@@ -113,6 +145,8 @@ pub struct RecipesQuery {
     pub has_thumbnail: Option<bool>,
     pub dietary_restrictions: Vec<DietaryRestriction>,
     pub dietary_where_any: bool,
+    /// Only recipes carrying every one of these tags.
+    pub tags: Vec<String>,
     pub in_meal_plan: Option<(String, bool)>,
     pub sort_by: SortBy,
     pub search: Option<String>,
@@ -147,6 +181,20 @@ impl<E: Executor> crate::recipe::Module<E> {
                 (RecipeUser::Table, RecipeUser::ThumbnailVersion),
                 (RecipeUser::Table, RecipeUser::BlurPlaceholder),
             ])
+            .expr_as(
+                Expr::cust(
+                    "COALESCE((SELECT rating_count FROM recipe_rating_stat \
+                     WHERE recipe_rating_stat.id = recipe_user.id), 0)",
+                ),
+                Alias::new("rating_count"),
+            )
+            .expr_as(
+                Expr::cust(
+                    "COALESCE((SELECT average_rating FROM recipe_rating_stat \
+                     WHERE recipe_rating_stat.id = recipe_user.id), 0.0)",
+                ),
+                Alias::new("average_rating"),
+            )
             .from(RecipeUser::Table)
             .to_owned();
 
@@ -200,35 +248,25 @@ impl<E: Executor> crate::recipe::Module<E> {
         }
 
         if !query.dietary_restrictions.is_empty() {
-            let in_clause = query
-                .dietary_restrictions
-                .iter()
-                .map(|_| "?")
-                .collect::<Vec<_>>()
-                .join(", ");
-
-            let mut values: Vec<sea_query::Value> = query
-                .dietary_restrictions
-                .iter()
-                .map(|t| sea_query::Value::String(Some(t.to_string())))
-                .collect();
-
-            let sql = if query.dietary_where_any {
-                format!(
-                    "(SELECT COUNT(*) FROM json_each(dietary_restrictions) WHERE value IN ({})) > 0",
-                    in_clause
-                )
+            let mut condition = if query.dietary_where_any {
+                Cond::any()
             } else {
-                values.push(sea_query::Value::Int(Some(
-                    query.dietary_restrictions.len() as i32,
-                )));
-                format!(
-                    "(SELECT COUNT(*) FROM json_each(dietary_restrictions) WHERE value IN ({})) = ?",
-                    in_clause
-                )
+                Cond::all()
             };
 
-            statement.and_where(Expr::cust_with_values(sql, values));
+            for restriction in query.dietary_restrictions.iter() {
+                condition =
+                    condition.add(crate::mealplan::satisfies_dietary_restriction(restriction));
+            }
+
+            statement.cond_where(condition);
+        }
+
+        for tag in query.tags.iter() {
+            statement.and_where(Expr::cust_with_values(
+                "EXISTS (SELECT 1 FROM json_each(tags) WHERE value = ?)",
+                [tag.trim().to_lowercase()],
+            ));
         }
 
         if let Some((meal_plan_user_id, in_plan)) = query.in_meal_plan {
@@ -307,6 +345,22 @@ impl<E: Executor> crate::recipe::Module<E> {
 
                 Ok(result.map(|item| item.0))
             }
+            // `average_rating` is a computed column, so like the search path
+            // the query is wrapped for the cursor to order and keyset on it.
+            SortBy::TopRated => {
+                let outer = Query::select()
+                    .column(Asterisk)
+                    .from_subquery(statement, Alias::new("sub"))
+                    .to_owned();
+
+                let result = Reader::new(outer)
+                    .desc()
+                    .args(query.args)
+                    .execute::<_, UserViewListByRating, _>(&self.read_db)
+                    .await?;
+
+                Ok(result.map(|item| item.0))
+            }
             // Random ordering is incompatible with cursor pagination, so bypass
             // `Reader` and run a one-shot `ORDER BY RANDOM()` query (same pattern
             // as meal-plan generation). The limit comes from the caller's `Args`.
@@ -449,6 +503,30 @@ impl<E: Executor> crate::recipe::Module<E> {
         )
     }
 
+    /// The user's most recent recipe with the same basic information,
+    /// ingredients and instructions, looked up by content hash.
+    pub async fn find_user_duplicate(
+        &self,
+        user_id: impl Into<String>,
+        content_hash: &[u8],
+    ) -> anyhow::Result<Option<String>> {
+        let (sql, values) = sea_query::Query::select()
+            .columns([RecipeUser::Id])
+            .from(RecipeUser::Table)
+            .and_where(Expr::col(RecipeUser::OwnerId).eq(user_id.into()))
+            .and_where(Expr::col(RecipeUser::ContentHash).eq(content_hash.to_vec()))
+            .order_by(RecipeUser::CreatedAt, sea_query::Order::Desc)
+            .limit(1)
+            .build_sqlx(SqliteQueryBuilder);
+
+        Ok(
+            sqlx::query_as_with::<_, (String,), _>(sqlx::AssertSqlSafe(sql), values)
+                .fetch_optional(&self.read_db)
+                .await?
+                .map(|(id,)| id),
+        )
+    }
+
     /// Maps a batch of recipe ids to their current slugs. Ids without a row are
     /// simply absent from the result, so callers should fall back to the id.
     pub async fn slugs(
@@ -514,6 +592,7 @@ async fn find_user(pool: &SqlitePool, id: impl Into<String>) -> anyhow::Result<O
             RecipeUser::OwnerId,
             RecipeUser::OwnerName,
             RecipeUser::RecipeType,
+            RecipeUser::CuisineType,
             RecipeUser::Name,
             RecipeUser::Slug,
             RecipeUser::Origin,
@@ -524,6 +603,7 @@ async fn find_user(pool: &SqlitePool, id: impl Into<String>) -> anyhow::Result<O
             RecipeUser::Ingredients,
             RecipeUser::Instructions,
             RecipeUser::DietaryRestrictions,
+            RecipeUser::Tags,
             RecipeUser::AcceptsAccompaniment,
             RecipeUser::AdvancePrep,
             RecipeUser::IsShared,
@@ -599,10 +679,12 @@ pub fn create_projection<E: Executor>() -> Projection<E, UserView> {
         .handler(handle_created())
         .handler(handle_imported())
         .handler(handle_recipe_type_changed())
+        .handler(handle_cuisine_type_changed())
         .handler(handle_basic_information_changed())
         .handler(handle_ingredients_changed())
         .handler(handle_instructions_changed())
         .handler(handle_dietary_restrictions_changed())
+        .handler(handle_tags_changed())
         .handler(handle_main_course_options_changed())
         .handler(handle_advance_prep_changed())
         .handler(handle_shared_to_community())
@@ -665,6 +747,23 @@ impl<E: Executor> Snapshot<E> for UserView {
             .iter()
             .map(|d| serde_json::Value::String(d.to_string()))
             .collect::<Vec<_>>();
+        let tags = self
+            .tags
+            .iter()
+            .map(|tag| serde_json::Value::String(tag.to_owned()))
+            .collect::<Vec<_>>();
+        let content_hash = crate::recipe::content_hash(
+            &crate::recipe::basic_information_hash(
+                &self.name,
+                self.origin.as_deref(),
+                &self.description,
+                self.household_size,
+                self.prep_time,
+                self.cook_time,
+            ),
+            &crate::recipe::ingredients_hash(&self.ingredients.0),
+            &crate::recipe::instructions_hash(&self.instructions.0),
+        );
 
         let statement = sea_query::Query::insert()
             .into_table(RecipeUser::Table)
@@ -674,6 +773,7 @@ impl<E: Executor> Snapshot<E> for UserView {
                 RecipeUser::OwnerId,
                 RecipeUser::OwnerName,
                 RecipeUser::RecipeType,
+                RecipeUser::CuisineType,
                 RecipeUser::Name,
                 RecipeUser::Slug,
                 RecipeUser::Origin,
@@ -684,6 +784,7 @@ impl<E: Executor> Snapshot<E> for UserView {
                 RecipeUser::Ingredients,
                 RecipeUser::Instructions,
                 RecipeUser::DietaryRestrictions,
+                RecipeUser::Tags,
                 RecipeUser::AcceptsAccompaniment,
                 RecipeUser::AdvancePrep,
                 RecipeUser::IsShared,
@@ -691,6 +792,7 @@ impl<E: Executor> Snapshot<E> for UserView {
                 RecipeUser::CreatedAt,
                 RecipeUser::ThumbnailVersion,
                 RecipeUser::BlurPlaceholder,
+                RecipeUser::ContentHash,
             ])
             .values([
                 self.id.to_owned().into(),
@@ -698,6 +800,7 @@ impl<E: Executor> Snapshot<E> for UserView {
                 self.owner_id.to_owned().into(),
                 self.owner_name.to_owned().into(),
                 self.recipe_type.to_string().into(),
+                self.cuisine_type.as_ref().map(|c| c.to_string()).into(),
                 self.name.to_owned().into(),
                 slug.to_owned().into(),
                 self.origin.to_owned().into(),
@@ -708,6 +811,7 @@ impl<E: Executor> Snapshot<E> for UserView {
                 ingredients.into(),
                 instructions.into(),
                 serde_json::Value::Array(dietary_restrictions).into(),
+                serde_json::Value::Array(tags).into(),
                 self.accepts_accompaniment.into(),
                 self.advance_prep.to_owned().into(),
                 self.is_shared.into(),
@@ -715,6 +819,7 @@ impl<E: Executor> Snapshot<E> for UserView {
                 self.created_at.into(),
                 self.thumbnail_version.to_owned().into(),
                 blur_placeholder.into(),
+                content_hash.into(),
             ])?
             .on_conflict(
                 OnConflict::column(RecipeUser::Id)
@@ -723,6 +828,7 @@ impl<E: Executor> Snapshot<E> for UserView {
                         RecipeUser::OwnerId,
                         RecipeUser::OwnerName,
                         RecipeUser::RecipeType,
+                        RecipeUser::CuisineType,
                         RecipeUser::Name,
                         RecipeUser::Slug,
                         RecipeUser::Origin,
@@ -733,6 +839,7 @@ impl<E: Executor> Snapshot<E> for UserView {
                         RecipeUser::Ingredients,
                         RecipeUser::Instructions,
                         RecipeUser::DietaryRestrictions,
+                        RecipeUser::Tags,
                         RecipeUser::AcceptsAccompaniment,
                         RecipeUser::AdvancePrep,
                         RecipeUser::IsShared,
@@ -740,6 +847,7 @@ impl<E: Executor> Snapshot<E> for UserView {
                         RecipeUser::CreatedAt,
                         RecipeUser::ThumbnailVersion,
                         RecipeUser::BlurPlaceholder,
+                        RecipeUser::ContentHash,
                     ])
                     .to_owned(),
             )
@@ -810,6 +918,16 @@ async fn handle_recipe_type_changed(
     Ok(())
 }
 
+#[evento::handler]
+async fn handle_cuisine_type_changed(
+    event: Event<CuisineTypeChanged>,
+    data: &mut UserView,
+) -> anyhow::Result<()> {
+    data.cuisine_type = Some(event.data.cuisine_type);
+
+    Ok(())
+}
+
 #[evento::handler]
 async fn handle_basic_information_changed(
     event: Event<BasicInformationChanged>,
@@ -855,6 +973,13 @@ async fn handle_dietary_restrictions_changed(
     Ok(())
 }
 
+#[evento::handler]
+async fn handle_tags_changed(event: Event<TagsChanged>, data: &mut UserView) -> anyhow::Result<()> {
+    data.tags.0 = event.data.tags;
+
+    Ok(())
+}
+
 #[evento::handler]
 async fn handle_main_course_options_changed(
     event: Event<MainCourseOptionsChanged>,
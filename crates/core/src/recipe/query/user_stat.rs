@@ -1,10 +1,12 @@
+use bitcode::{Decode, Encode};
 use evento::{
-    Executor,
+    Executor, Projection, ProjectionAggregate,
     metadata::Event,
     subscription::{Context, SubscriptionBuilder},
 };
 use imkitchen_db::recipe_user_stat::RecipeUserStat;
-use imkitchen_types::recipe::{Created, Deleted, Imported, MadePrivate, SharedToCommunity};
+use imkitchen_types::favorite::{Saved, Unsaved};
+use imkitchen_types::recipe::{self, Created, Deleted, Imported, MadePrivate, SharedToCommunity};
 use sea_query::{Expr, ExprTrait, OnConflict, Query, SqliteQueryBuilder};
 use sea_query_sqlx::SqlxBinder;
 use sqlx::prelude::FromRow;
@@ -42,6 +44,82 @@ impl<E: Executor> crate::recipe::Module<E> {
     }
 }
 
+/// Number of recipes the user currently has saved as favorites.
+pub(crate) async fn favorite_count(
+    read_db: &sqlx::SqlitePool,
+    user_id: &str,
+) -> anyhow::Result<u32> {
+    let (sql, values) = Query::select()
+        .column(RecipeUserStat::Favorite)
+        .from(RecipeUserStat::Table)
+        .and_where(Expr::col(RecipeUserStat::UserId).eq(user_id))
+        .build_sqlx(SqliteQueryBuilder);
+
+    Ok(
+        sqlx::query_scalar_with::<_, u32, _>(sqlx::AssertSqlSafe(sql), values)
+            .fetch_optional(read_db)
+            .await?
+            .unwrap_or_default(),
+    )
+}
+
+/// Who a recipe's stats are counted against. Admins moderating a recipe
+/// request its `MadePrivate` and `Deleted` events, so the owner is read from
+/// the recipe's own history instead of the event metadata.
+#[evento::projection(Encode, Decode)]
+pub struct RecipeOwner {
+    pub id: String,
+    pub owner_id: String,
+}
+
+impl ProjectionAggregate for RecipeOwner {
+    fn aggregate_id(&self) -> String {
+        self.id.to_owned()
+    }
+}
+
+// No tombstone: the owner of a deleted recipe is still needed for its
+// `Deleted` event.
+fn create_owner_projection<E: Executor>() -> Projection<E, RecipeOwner> {
+    Projection::new::<recipe::Recipe>()
+        .handler(handle_owner_created())
+        .handler(handle_owner_imported())
+}
+
+#[evento::handler]
+async fn handle_owner_created(event: Event<Created>, data: &mut RecipeOwner) -> anyhow::Result<()> {
+    data.id = event.aggregate_id.to_owned();
+    data.owner_id = event.metadata.requested_by()?;
+
+    Ok(())
+}
+
+#[evento::handler]
+async fn handle_owner_imported(
+    event: Event<Imported>,
+    data: &mut RecipeOwner,
+) -> anyhow::Result<()> {
+    data.id = event.aggregate_id.to_owned();
+    data.owner_id = event.metadata.requested_by()?;
+
+    Ok(())
+}
+
+async fn owner_id<E: Executor>(
+    context: &Context<'_, E>,
+    recipe_id: &str,
+) -> anyhow::Result<String> {
+    let Some(owner) = create_owner_projection()
+        .load(recipe_id)
+        .execute(context.executor)
+        .await?
+    else {
+        anyhow::bail!("recipe {recipe_id} not found");
+    };
+
+    Ok(owner.owner_id)
+}
+
 pub fn subscription<E: Executor>() -> SubscriptionBuilder<E> {
     SubscriptionBuilder::new("recipe-user-stat-view")
         .handler(handle_created())
@@ -49,6 +127,8 @@ pub fn subscription<E: Executor>() -> SubscriptionBuilder<E> {
         .handler(handle_deleted())
         .handler(handle_shared_to_community())
         .handler(handle_made_private())
+        .handler(handle_favorite_saved())
+        .handler(handle_favorite_unsaved())
 }
 
 #[evento::subscription]
@@ -119,7 +199,7 @@ async fn handle_deleted<E: Executor>(
     event: Event<Deleted>,
 ) -> anyhow::Result<()> {
     let pool = context.extract::<sqlx::SqlitePool>();
-    let user_id = event.metadata.requested_by()?;
+    let user_id = owner_id(context, &event.aggregate_id).await?;
 
     let statement = Query::insert()
         .into_table(RecipeUserStat::Table)
@@ -181,7 +261,7 @@ async fn handle_made_private<E: Executor>(
     event: Event<MadePrivate>,
 ) -> anyhow::Result<()> {
     let pool = context.extract::<sqlx::SqlitePool>();
-    let user_id = event.metadata.requested_by()?;
+    let user_id = owner_id(context, &event.aggregate_id).await?;
 
     let statement = Query::insert()
         .into_table(RecipeUserStat::Table)
@@ -205,3 +285,62 @@ async fn handle_made_private<E: Executor>(
 
     Ok(())
 }
+
+#[evento::subscription]
+async fn handle_favorite_saved<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<Saved>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<sqlx::SqlitePool>();
+    let user_id = event.metadata.requested_by()?;
+
+    let statement = Query::insert()
+        .into_table(RecipeUserStat::Table)
+        .columns([RecipeUserStat::UserId, RecipeUserStat::Favorite])
+        .values_panic([user_id.into(), 1.into()])
+        .on_conflict(
+            OnConflict::column(RecipeUserStat::UserId)
+                .value(
+                    RecipeUserStat::Favorite,
+                    Expr::col(RecipeUserStat::Favorite).add(1),
+                )
+                .to_owned(),
+        )
+        .to_owned();
+
+    let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+
+    sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+        .execute(&pool)
+        .await?;
+
+    Ok(())
+}
+
+#[evento::subscription]
+async fn handle_favorite_unsaved<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<Unsaved>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<sqlx::SqlitePool>();
+    let user_id = event.metadata.requested_by()?;
+
+    let statement = Query::insert()
+        .into_table(RecipeUserStat::Table)
+        .columns([RecipeUserStat::UserId, RecipeUserStat::Favorite])
+        .values_panic([user_id.into(), 0.into()])
+        .on_conflict(
+            OnConflict::column(RecipeUserStat::UserId)
+                .value(RecipeUserStat::Favorite, Expr::cust("MAX(favorite - 1, 0)"))
+                .to_owned(),
+        )
+        .to_owned();
+
+    let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+
+    sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+        .execute(&pool)
+        .await?;
+
+    Ok(())
+}
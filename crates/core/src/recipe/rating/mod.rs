@@ -0,0 +1,67 @@
+mod rate;
+
+use bitcode::{Decode, Encode};
+use evento::{Executor, Projection, ProjectionAggregate, metadata::Event};
+use imkitchen_types::rating;
+use std::ops::Deref;
+
+#[derive(Clone)]
+pub struct Module<E: Executor>(pub(crate) crate::State<E>);
+
+impl<E: Executor> Deref for Module<E> {
+    type Target = crate::State<E>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<E: Executor> Module<E> {
+    pub async fn load(
+        &self,
+        id: impl Into<String>,
+        user_id: impl Into<String>,
+    ) -> anyhow::Result<Rating> {
+        let id = id.into();
+        let user_id = user_id.into();
+
+        create_projection::<E>()
+            .load_ids(vec![id.clone(), user_id.clone()])
+            .execute(&self.executor)
+            .await
+            .map(|r| {
+                r.unwrap_or_else(|| Rating {
+                    id: evento::hash_ids(vec![id, user_id]),
+                    stars: 0,
+                    cursor: Default::default(),
+                })
+            })
+    }
+}
+
+#[evento::projection(Encode, Decode)]
+pub struct Rating {
+    pub id: String,
+    /// 1 to 5, or 0 while the user hasn't rated the recipe.
+    pub stars: u8,
+}
+
+pub fn create_projection<E: Executor>() -> Projection<E, Rating> {
+    Projection::new::<rating::Rating>()
+        .handler(handle_rated())
+        .strict()
+}
+
+impl ProjectionAggregate for Rating {
+    fn aggregate_id(&self) -> String {
+        self.id.to_owned()
+    }
+}
+
+#[evento::handler]
+async fn handle_rated(event: Event<rating::Rated>, data: &mut Rating) -> anyhow::Result<()> {
+    data.id = event.aggregate_id.to_owned();
+    data.stars = event.data.stars;
+
+    Ok(())
+}
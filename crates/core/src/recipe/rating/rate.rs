@@ -0,0 +1,37 @@
+use evento::{Executor, ProjectionAggregate};
+use imkitchen_types::rating::Rated;
+
+impl<E: Executor> super::Module<E> {
+    /// Rate a recipe from 1 to 5 stars; rating again replaces the previous
+    /// score.
+    pub async fn rate(
+        &self,
+        id: impl Into<String>,
+        user_id: impl Into<String>,
+        stars: u8,
+    ) -> crate::Result<()> {
+        if !(1..=5).contains(&stars) {
+            crate::user!("Rating must be between 1 and 5 stars");
+        }
+
+        let id = id.into();
+        let user_id = user_id.into();
+        let rating = self.load(&id, &user_id).await?;
+
+        if rating.stars == stars {
+            return Ok(());
+        }
+
+        rating
+            .write()?
+            .event(&Rated {
+                recipe_id: id,
+                stars,
+            })
+            .requested_by(user_id)
+            .commit(&self.executor)
+            .await?;
+
+        Ok(())
+    }
+}
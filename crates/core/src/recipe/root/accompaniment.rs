@@ -0,0 +1,118 @@
+use evento::{Executor, ProjectionAggregate};
+use imkitchen_types::recipe::{
+    AccompanimentType, AccompanimentTypesChanged, DefaultAccompanimentsChanged, RecipeType,
+};
+
+/// Most default accompaniments a main course can list.
+pub const MAX_DEFAULT_ACCOMPANIMENTS: usize = 5;
+
+pub struct AccompanimentTypesInput {
+    pub id: String,
+    /// Set on accompaniment recipes.
+    pub accompaniment_type: Option<AccompanimentType>,
+    /// Set on main courses, most preferred first.
+    pub preferred_accompaniment_types: Vec<AccompanimentType>,
+}
+
+pub struct DefaultAccompanimentsInput {
+    pub id: String,
+    /// Accompaniment recipe ids, most preferred first.
+    pub accompaniment_ids: Vec<String>,
+}
+
+impl<E: Executor + Clone> super::Module<E> {
+    pub async fn set_accompaniment_types(
+        &self,
+        input: AccompanimentTypesInput,
+        request_by: impl Into<String>,
+    ) -> crate::Result<()> {
+        let Some(recipe) = self.load(&input.id).await? else {
+            crate::not_found!("recipe");
+        };
+
+        let request_by = request_by.into();
+        if recipe.owner_id != request_by {
+            crate::forbidden!("not owner of recipe");
+        }
+
+        let mut preferred_accompaniment_types = vec![];
+        for accompaniment_type in input.preferred_accompaniment_types {
+            if !preferred_accompaniment_types.contains(&accompaniment_type) {
+                preferred_accompaniment_types.push(accompaniment_type);
+            }
+        }
+
+        if recipe.accompaniment_type == input.accompaniment_type
+            && recipe.preferred_accompaniment_types == preferred_accompaniment_types
+        {
+            return Ok(());
+        }
+
+        recipe
+            .write()?
+            .event(&AccompanimentTypesChanged {
+                accompaniment_type: input.accompaniment_type,
+                preferred_accompaniment_types,
+            })
+            .requested_by(request_by)
+            .commit(&self.executor)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Accompaniments are checked to be accompaniment recipes of the
+    /// requester or shared with the community.
+    pub async fn set_default_accompaniments(
+        &self,
+        input: DefaultAccompanimentsInput,
+        request_by: impl Into<String>,
+    ) -> crate::Result<()> {
+        let Some(recipe) = self.load(&input.id).await? else {
+            crate::not_found!("recipe");
+        };
+
+        let request_by = request_by.into();
+        if recipe.owner_id != request_by {
+            crate::forbidden!("not owner of recipe");
+        }
+
+        let mut accompaniment_ids = vec![];
+        for id in input.accompaniment_ids {
+            if id != recipe.id && !accompaniment_ids.contains(&id) {
+                accompaniment_ids.push(id);
+            }
+        }
+
+        if accompaniment_ids.len() > MAX_DEFAULT_ACCOMPANIMENTS {
+            crate::user!(
+                "A recipe can have up to {MAX_DEFAULT_ACCOMPANIMENTS} default accompaniments"
+            );
+        }
+
+        for id in accompaniment_ids.iter() {
+            let visible = self.load(id).await?.is_some_and(|accompaniment| {
+                accompaniment.recipe_type == RecipeType::Accompaniment
+                    && !accompaniment.is_hidden
+                    && (accompaniment.owner_id == request_by || accompaniment.is_shared)
+            });
+
+            if !visible {
+                crate::user!("Default accompaniments must be accompaniment recipes");
+            }
+        }
+
+        if recipe.default_accompaniment_ids == accompaniment_ids {
+            return Ok(());
+        }
+
+        recipe
+            .write()?
+            .event(&DefaultAccompanimentsChanged { accompaniment_ids })
+            .requested_by(request_by)
+            .commit(&self.executor)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+use evento::{Executor, ProjectionAggregate};
+use imkitchen_types::recipe::AdvancePrepHoursChanged;
+
+use super::rules::MAX_ADVANCE_PREP_HOURS;
+
+pub struct AdvancePrepHoursInput {
+    pub id: String,
+    /// `None` reminds the day before, see
+    /// [`crate::mealplan::slot::DEFAULT_ADVANCE_PREP_HOURS`].
+    pub advance_prep_hours: Option<u16>,
+}
+
+impl<E: Executor + Clone> super::Module<E> {
+    pub async fn set_advance_prep_hours(
+        &self,
+        input: AdvancePrepHoursInput,
+        request_by: impl Into<String>,
+    ) -> crate::Result<()> {
+        if input
+            .advance_prep_hours
+            .is_some_and(|hours| hours > MAX_ADVANCE_PREP_HOURS)
+        {
+            crate::user!("Advance prep can't start more than {MAX_ADVANCE_PREP_HOURS} hours ahead");
+        }
+
+        let Some(recipe) = self.load(&input.id).await? else {
+            crate::not_found!("recipe");
+        };
+
+        let request_by = request_by.into();
+        if recipe.owner_id != request_by {
+            crate::forbidden!("not owner of recipe");
+        }
+
+        if recipe.advance_prep_hours == input.advance_prep_hours {
+            return Ok(());
+        }
+
+        recipe
+            .write()?
+            .event(&AdvancePrepHoursChanged {
+                advance_prep_hours: input.advance_prep_hours,
+            })
+            .requested_by(request_by)
+            .commit(&self.executor)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+use evento::{Executor, ProjectionAggregate};
+use imkitchen_types::recipe::{Complexity, ComplexityChanged};
+
+pub struct ComplexityInput {
+    pub id: String,
+    /// `None` lets the planner estimate it from the recipe's size.
+    pub complexity: Option<Complexity>,
+}
+
+impl<E: Executor + Clone> super::Module<E> {
+    pub async fn set_complexity(
+        &self,
+        input: ComplexityInput,
+        request_by: impl Into<String>,
+    ) -> crate::Result<()> {
+        let Some(recipe) = self.load(&input.id).await? else {
+            crate::not_found!("recipe");
+        };
+
+        let request_by = request_by.into();
+        if recipe.owner_id != request_by {
+            crate::forbidden!("not owner of recipe");
+        }
+
+        if recipe.complexity == input.complexity {
+            return Ok(());
+        }
+
+        recipe
+            .write()?
+            .event(&ComplexityChanged {
+                complexity: input.complexity,
+            })
+            .requested_by(request_by)
+            .commit(&self.executor)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -1,19 +1,27 @@
 use evento::Executor;
-use imkitchen_types::recipe::Created;
+use imkitchen_types::recipe::{Created, RecipeType, RecipeTypeChanged};
 
 impl<E: Executor> super::Module<E> {
     pub async fn create(
         &self,
         request_by: impl Into<String>,
         owner_name: impl Into<Option<String>>,
+        recipe_type: RecipeType,
     ) -> crate::Result<String> {
-        Ok(evento::create()
+        let mut builder = evento::create()
             .event(&Created {
                 name: "".to_owned(),
                 owner_name: owner_name.into(),
             })
             .requested_by(request_by)
-            .commit(&self.executor)
-            .await?)
+            .to_owned();
+
+        // `Created` predates the type choice; projections start from the
+        // default type, so only record the choice when it differs.
+        if recipe_type != RecipeType::default() {
+            builder.event(&RecipeTypeChanged { recipe_type });
+        }
+
+        Ok(builder.commit(&self.executor).await?)
     }
 }
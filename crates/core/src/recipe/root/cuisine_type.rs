@@ -0,0 +1,39 @@
+use evento::{Executor, ProjectionAggregate};
+use imkitchen_types::recipe::{CuisineType, CuisineTypeChanged};
+
+pub struct CuisineTypeInput {
+    pub id: String,
+    pub cuisine_type: CuisineType,
+}
+
+impl<E: Executor + Clone> super::Module<E> {
+    pub async fn set_cuisine_type(
+        &self,
+        input: CuisineTypeInput,
+        request_by: impl Into<String>,
+    ) -> crate::Result<()> {
+        let Some(recipe) = self.load(&input.id).await? else {
+            crate::not_found!("recipe");
+        };
+
+        let request_by = request_by.into();
+        if recipe.owner_id != request_by {
+            crate::forbidden!("not owner of recipe");
+        }
+
+        if recipe.cuisine_type.as_ref() == Some(&input.cuisine_type) {
+            return Ok(());
+        }
+
+        recipe
+            .write()?
+            .event(&CuisineTypeChanged {
+                cuisine_type: input.cuisine_type,
+            })
+            .requested_by(request_by)
+            .commit(&self.executor)
+            .await?;
+
+        Ok(())
+    }
+}
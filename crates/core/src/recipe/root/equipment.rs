@@ -0,0 +1,44 @@
+use evento::{Executor, ProjectionAggregate};
+use imkitchen_types::recipe::{Equipment, EquipmentChanged};
+
+pub struct EquipmentInput {
+    pub id: String,
+    pub equipment: Vec<Equipment>,
+}
+
+impl<E: Executor + Clone> super::Module<E> {
+    pub async fn set_equipment(
+        &self,
+        input: EquipmentInput,
+        request_by: impl Into<String>,
+    ) -> crate::Result<()> {
+        let Some(recipe) = self.load(&input.id).await? else {
+            crate::not_found!("recipe");
+        };
+
+        let request_by = request_by.into();
+        if recipe.owner_id != request_by {
+            crate::forbidden!("not owner of recipe");
+        }
+
+        let mut equipment = vec![];
+        for item in input.equipment {
+            if !equipment.contains(&item) {
+                equipment.push(item);
+            }
+        }
+
+        if recipe.equipment == equipment {
+            return Ok(());
+        }
+
+        recipe
+            .write()?
+            .event(&EquipmentChanged { equipment })
+            .requested_by(request_by)
+            .commit(&self.executor)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -6,7 +6,7 @@ use validator::Validate;
 
 use super::UpdateInput;
 
-#[derive(Validate, Clone)]
+#[derive(Validate, Clone, Debug, Default, PartialEq)]
 pub struct ImportInput {
     pub recipe_type: RecipeType,
     #[validate(length(min = 3, max = 100))]
@@ -19,27 +19,54 @@ pub struct ImportInput {
     pub household_size: u16,
     pub prep_time: u16,
     pub cook_time: u16,
+    #[validate(custom(function = "super::rules::validate_ingredients"))]
     pub ingredients: Vec<Ingredient>,
+    #[validate(custom(function = "super::rules::validate_instructions"))]
     pub instructions: Vec<Instruction>,
     #[validate(length(max = 2000))]
     pub advance_prep: String,
     pub accepts_accompaniment: bool,
     pub dietary_restrictions: Vec<DietaryRestriction>,
+    /// Always creates a new recipe: skips the duplicate check (see
+    /// [`crate::Error::PossibleDuplicate`]) and doesn't update the recipe
+    /// previously imported from the same origin.
+    pub force: bool,
 }
 
 impl<E: Executor + Clone> super::Module<E> {
     pub async fn import(
         &self,
-        input: ImportInput,
+        mut input: ImportInput,
         request_by: impl Into<String>,
         owner_name: impl Into<Option<String>>,
     ) -> crate::Result<String> {
+        input.instructions = normalize_instructions(input.instructions);
         input.validate()?;
         let request_by = request_by.into();
 
-        if let Some(existing_id) = self
-            .find_user_to_upsert(&request_by, input.origin.as_deref(), &input.name)
-            .await?
+        if !input.force {
+            let content_hash = super::content_hash(
+                &super::basic_information_hash(
+                    &input.name,
+                    input.origin.as_deref(),
+                    &input.description,
+                    input.household_size,
+                    input.prep_time,
+                    input.cook_time,
+                ),
+                &super::ingredients_hash(&input.ingredients),
+                &super::instructions_hash(&input.instructions),
+            );
+
+            if let Some(existing_id) = self.find_user_duplicate(&request_by, &content_hash).await? {
+                return Err(crate::Error::PossibleDuplicate(existing_id));
+            }
+        }
+
+        if !input.force
+            && let Some(existing_id) = self
+                .find_user_to_upsert(&request_by, input.origin.as_deref(), &input.name)
+                .await?
             && let Some(existing) = self.load(&existing_id).await?
             && existing.owner_id == request_by
         {
@@ -88,3 +115,119 @@ impl<E: Executor + Clone> super::Module<E> {
             .await?)
     }
 }
+
+/// Cleans up imported instruction text: whitespace is collapsed and leading
+/// step numbers ("1.", "2)", "Step 3:") are stripped, since steps are numbered
+/// when displayed. Steps left empty are dropped. Idempotent.
+pub(crate) fn normalize_instructions(instructions: Vec<Instruction>) -> Vec<Instruction> {
+    instructions
+        .into_iter()
+        .map(|instruction| Instruction {
+            description: normalize_instruction(&instruction.description),
+            ..instruction
+        })
+        .filter(|instruction| !instruction.description.is_empty())
+        .collect()
+}
+
+fn normalize_instruction(text: &str) -> String {
+    let mut text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    while let Some(rest) = strip_step_number(&text) {
+        text = rest.to_owned();
+    }
+
+    text
+}
+
+fn strip_step_number(text: &str) -> Option<&str> {
+    let (rest, keyword) = match text.get(..5) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("step ") => (&text[5..], true),
+        _ => (text, false),
+    };
+
+    let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits == 0 {
+        return None;
+    }
+
+    let rest = &rest[digits..];
+    let rest = match rest.trim_start().chars().next() {
+        Some('.' | ')' | ':' | '-') => &rest.trim_start()[1..],
+        _ if keyword => rest,
+        _ => return None,
+    };
+
+    // "1.5 cups" or "2 eggs" are part of the step, not its number.
+    if !rest.is_empty() && !rest.starts_with(' ') {
+        return None;
+    }
+
+    Some(rest.trim_start())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::normalize_instructions;
+    use imkitchen_types::recipe::Instruction;
+
+    fn normalize(descriptions: &[&str]) -> Vec<String> {
+        normalize_instructions(
+            descriptions
+                .iter()
+                .map(|description| Instruction {
+                    description: description.to_string(),
+                    time_next: 0,
+                })
+                .collect(),
+        )
+        .into_iter()
+        .map(|instruction| instruction.description)
+        .collect()
+    }
+
+    #[test]
+    fn strips_step_numbers() {
+        assert_eq!(
+            normalize(&[
+                "1. Preheat the oven",
+                "2) Mix flour",
+                "Step 3: Bake",
+                "step 4 Rest",
+                "5 - Serve",
+                "6. 6. Enjoy",
+            ]),
+            vec![
+                "Preheat the oven",
+                "Mix flour",
+                "Bake",
+                "Rest",
+                "Serve",
+                "Enjoy"
+            ]
+        );
+    }
+
+    #[test]
+    fn collapses_whitespace_and_drops_empty_steps() {
+        assert_eq!(
+            normalize(&["  Chop   the\n onions\t finely ", "   ", "7."]),
+            vec!["Chop the onions finely"]
+        );
+    }
+
+    #[test]
+    fn keeps_leading_quantities() {
+        assert_eq!(
+            normalize(&["2 eggs, beaten", "1.5 cups of milk", "350F oven"]),
+            vec!["2 eggs, beaten", "1.5 cups of milk", "350F oven"]
+        );
+    }
+
+    #[test]
+    fn is_idempotent() {
+        let once = normalize(&[" 1.  Whisk   eggs ", "Step 2: Fold in"]);
+        let refs = once.iter().map(String::as_str).collect::<Vec<_>>();
+        assert_eq!(normalize(&refs), once);
+    }
+}
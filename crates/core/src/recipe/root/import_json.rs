@@ -0,0 +1,190 @@
+use evento::Executor;
+use imkitchen_types::recipe::{
+    CuisineType, DietaryRestriction, Ingredient, Instruction, RecipeType,
+};
+use serde::Deserialize;
+use std::str::FromStr;
+
+use super::{CuisineTypeInput, ImportInput};
+
+/// One recipe in the interchange format:
+///
+/// ```json
+/// {
+///   "name": "Chili",
+///   "description": "Slow cooked beef and beans",
+///   "recipe_type": "MainCourse",
+///   "cuisine_type": "Mexican",
+///   "origin": "https://example.com/chili",
+///   "household_size": 4,
+///   "prep_time": 15,
+///   "cook_time": 90,
+///   "ingredients": [
+///     { "name": "beef", "quantity": 500, "unit": "G", "category": "Butcher" }
+///   ],
+///   "instructions": [{ "description": "Brown the beef", "time_next": 10 }],
+///   "advance_prep": "Soak the beans overnight",
+///   "dietary_restrictions": ["GlutenFree"],
+///   "accepts_accompaniment": true,
+///   "force": false
+/// }
+/// ```
+///
+/// Only `name` is required. Enum values use the variant names, units are
+/// case insensitive. Times are minutes. `force` imports a recipe even when it
+/// looks like one the user already has.
+#[derive(Deserialize)]
+struct RecipeJson {
+    name: Option<String>,
+    description: Option<String>,
+    recipe_type: Option<String>,
+    cuisine_type: Option<String>,
+    origin: Option<String>,
+    household_size: Option<i64>,
+    prep_time: Option<i64>,
+    cook_time: Option<i64>,
+    #[serde(default)]
+    ingredients: Vec<IngredientJson>,
+    #[serde(default)]
+    instructions: Vec<InstructionJson>,
+    advance_prep: Option<String>,
+    #[serde(default)]
+    dietary_restrictions: Vec<String>,
+    #[serde(default)]
+    accepts_accompaniment: bool,
+    #[serde(default)]
+    force: bool,
+}
+
+#[derive(Deserialize)]
+struct IngredientJson {
+    name: String,
+    quantity: Option<i64>,
+    unit: Option<String>,
+    category: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct InstructionJson {
+    description: String,
+    time_next: Option<i64>,
+}
+
+fn number<T: TryFrom<i64>>(value: Option<i64>, field: &str, default: T) -> crate::Result<T> {
+    let Some(value) = value else {
+        return Ok(default);
+    };
+
+    match T::try_from(value) {
+        Ok(value) => Ok(value),
+        Err(_) => crate::user!("Invalid {field}: {value}"),
+    }
+}
+
+fn variant<T: FromStr>(value: &str, field: &str) -> crate::Result<T> {
+    match T::from_str(value.trim()) {
+        Ok(value) => Ok(value),
+        Err(_) => crate::user!("Unknown {field}: {value}"),
+    }
+}
+
+/// Reads a recipe in the interchange format described on `RecipeJson`. Field
+/// level problems come back as user errors naming the field, the remaining
+/// rules are checked by [`ImportInput`] validation on import.
+pub fn parse_recipe_json(json: &str) -> crate::Result<(ImportInput, Option<CuisineType>)> {
+    let recipe: RecipeJson = match serde_json::from_str(json) {
+        Ok(recipe) => recipe,
+        Err(err) => crate::user!("Invalid recipe JSON: {err}"),
+    };
+
+    let name = recipe.name.unwrap_or_default().trim().to_owned();
+    if name.is_empty() {
+        crate::user!("Name is required");
+    }
+
+    let mut ingredients = vec![];
+    for ingredient in recipe.ingredients {
+        let field = format!("quantity for {}", ingredient.name);
+        ingredients.push(Ingredient {
+            quantity: number(ingredient.quantity, &field, 0)?,
+            unit: ingredient
+                .unit
+                .map(|unit| variant(&unit, &format!("unit for {}", ingredient.name)))
+                .transpose()?,
+            category: ingredient
+                .category
+                .map(|category| variant(&category, &format!("category for {}", ingredient.name)))
+                .transpose()?,
+            name: ingredient.name,
+        });
+    }
+
+    let mut instructions = vec![];
+    for (pos, instruction) in recipe.instructions.into_iter().enumerate() {
+        instructions.push(Instruction {
+            time_next: number(
+                instruction.time_next,
+                &format!("time for step {}", pos + 1),
+                0,
+            )?,
+            description: instruction.description,
+        });
+    }
+
+    let input = ImportInput {
+        recipe_type: match recipe.recipe_type {
+            Some(value) => variant::<RecipeType>(&value, "recipe type")?,
+            None => RecipeType::default(),
+        },
+        description: recipe.description.unwrap_or_else(|| name.to_owned()),
+        origin: recipe.origin,
+        household_size: number(recipe.household_size, "household size", 4)?,
+        prep_time: number(recipe.prep_time, "prep time", 0)?,
+        cook_time: number(recipe.cook_time, "cook time", 0)?,
+        ingredients,
+        instructions,
+        advance_prep: recipe.advance_prep.unwrap_or_default(),
+        accepts_accompaniment: recipe.accepts_accompaniment,
+        dietary_restrictions: recipe
+            .dietary_restrictions
+            .iter()
+            .map(|value| variant::<DietaryRestriction>(value, "dietary restriction"))
+            .collect::<crate::Result<_>>()?,
+        name,
+        force: recipe.force,
+    };
+
+    let cuisine_type = recipe
+        .cuisine_type
+        .map(|value| variant(&value, "cuisine type"))
+        .transpose()?;
+
+    Ok((input, cuisine_type))
+}
+
+impl<E: Executor + Clone> super::Module<E> {
+    /// Imports one recipe written in the interchange format.
+    pub async fn import_json(
+        &self,
+        json: &str,
+        request_by: impl Into<String>,
+        owner_name: impl Into<Option<String>>,
+    ) -> crate::Result<String> {
+        let (input, cuisine_type) = parse_recipe_json(json)?;
+        let request_by = request_by.into();
+        let id = self.import(input, &request_by, owner_name).await?;
+
+        if let Some(cuisine_type) = cuisine_type {
+            self.set_cuisine_type(
+                CuisineTypeInput {
+                    id: id.to_owned(),
+                    cuisine_type,
+                },
+                request_by,
+            )
+            .await?;
+        }
+
+        Ok(id)
+    }
+}
@@ -0,0 +1,308 @@
+use evento::Executor;
+use imkitchen_types::recipe::{Ingredient, IngredientUnit, Instruction, RecipeType};
+use serde::Deserialize;
+use std::str::FromStr;
+
+use super::ImportInput;
+
+/// Which CSV column (by header name) feeds which recipe field. Only `name` is
+/// required; unmapped fields fall back to the same defaults as a blank recipe.
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct ColumnMapping {
+    pub delimiter: char,
+    /// Separates ingredients (and instructions) listed in a single cell.
+    pub list_delimiter: char,
+    pub name: String,
+    pub description: Option<String>,
+    pub recipe_type: Option<String>,
+    pub origin: Option<String>,
+    pub household_size: Option<String>,
+    pub prep_time: Option<String>,
+    pub cook_time: Option<String>,
+    /// Each entry reads `[quantity] [g|ml] name`, e.g. `200 g flour`.
+    pub ingredients: Option<String>,
+    pub instructions: Option<String>,
+    pub advance_prep: Option<String>,
+}
+
+impl Default for ColumnMapping {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            list_delimiter: ';',
+            name: "name".to_owned(),
+            description: None,
+            recipe_type: None,
+            origin: None,
+            household_size: None,
+            prep_time: None,
+            cook_time: None,
+            ingredients: None,
+            instructions: None,
+            advance_prep: None,
+        }
+    }
+}
+
+pub struct MappedRowError {
+    /// 1-based data row, not counting the header.
+    pub row: usize,
+    pub name: String,
+    pub error: crate::Error,
+}
+
+#[derive(Default)]
+pub struct MappedImport {
+    pub ids: Vec<String>,
+    pub errors: Vec<MappedRowError>,
+}
+
+struct Columns {
+    name: usize,
+    description: Option<usize>,
+    recipe_type: Option<usize>,
+    origin: Option<usize>,
+    household_size: Option<usize>,
+    prep_time: Option<usize>,
+    cook_time: Option<usize>,
+    ingredients: Option<usize>,
+    instructions: Option<usize>,
+    advance_prep: Option<usize>,
+}
+
+impl Columns {
+    fn resolve(header: &[String], mapping: &ColumnMapping) -> crate::Result<Self> {
+        let position = |column: &str| -> crate::Result<usize> {
+            match header.iter().position(|h| h.trim() == column) {
+                Some(index) => Ok(index),
+                None => crate::user!("Column \"{column}\" not found"),
+            }
+        };
+        let optional = |column: &Option<String>| column.as_deref().map(&position).transpose();
+
+        Ok(Self {
+            name: position(mapping.name.as_str())?,
+            description: optional(&mapping.description)?,
+            recipe_type: optional(&mapping.recipe_type)?,
+            origin: optional(&mapping.origin)?,
+            household_size: optional(&mapping.household_size)?,
+            prep_time: optional(&mapping.prep_time)?,
+            cook_time: optional(&mapping.cook_time)?,
+            ingredients: optional(&mapping.ingredients)?,
+            instructions: optional(&mapping.instructions)?,
+            advance_prep: optional(&mapping.advance_prep)?,
+        })
+    }
+
+    fn to_input(&self, row: &[String], list_delimiter: char) -> crate::Result<ImportInput> {
+        let cell = |index: Option<usize>| {
+            index
+                .and_then(|i| row.get(i))
+                .map(|v| v.trim())
+                .filter(|v| !v.is_empty())
+        };
+        let number = |index: Option<usize>, field: &str, default: u16| -> crate::Result<u16> {
+            match cell(index) {
+                Some(value) => match value.parse() {
+                    Ok(value) => Ok(value),
+                    Err(_) => crate::user!("Invalid {field}: {value}"),
+                },
+                None => Ok(default),
+            }
+        };
+        let list = |index: Option<usize>| {
+            cell(index)
+                .map(|v| {
+                    v.split(list_delimiter)
+                        .map(str::trim)
+                        .filter(|v| !v.is_empty())
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default()
+        };
+
+        let name = cell(Some(self.name)).unwrap_or_default().to_owned();
+        let recipe_type = match cell(self.recipe_type) {
+            Some(value) => match RecipeType::from_str(value) {
+                Ok(recipe_type) => recipe_type,
+                Err(_) => crate::user!("Invalid recipe type: {value}"),
+            },
+            None => RecipeType::default(),
+        };
+
+        Ok(ImportInput {
+            recipe_type,
+            description: cell(self.description).unwrap_or(name.as_str()).to_owned(),
+            origin: cell(self.origin).map(str::to_owned),
+            household_size: number(self.household_size, "household size", 4)?,
+            prep_time: number(self.prep_time, "prep time", 0)?,
+            cook_time: number(self.cook_time, "cook time", 0)?,
+            ingredients: list(self.ingredients)
+                .into_iter()
+                .map(parse_ingredient)
+                .collect(),
+            instructions: list(self.instructions)
+                .into_iter()
+                .map(|description| Instruction {
+                    description: description.to_owned(),
+                    time_next: 0,
+                })
+                .collect(),
+            advance_prep: cell(self.advance_prep).unwrap_or_default().to_owned(),
+            accepts_accompaniment: false,
+            dietary_restrictions: vec![],
+            name,
+            force: false,
+        })
+    }
+}
+
+/// Parses `[quantity] [unit] name`; a missing quantity means "to taste" (0).
+fn parse_ingredient(value: &str) -> Ingredient {
+    let mut rest = value;
+    let mut quantity = 0;
+    let mut unit = None;
+
+    if let Some((first, tail)) = rest.split_once(' ')
+        && let Ok(value) = first.parse::<u32>()
+    {
+        quantity = value;
+        rest = tail.trim_start();
+
+        if let Some((first, tail)) = rest.split_once(' ')
+            && let Ok(value) = IngredientUnit::from_str(first)
+        {
+            unit = Some(value);
+            rest = tail.trim_start();
+        }
+    }
+
+    Ingredient {
+        name: rest.to_owned(),
+        quantity,
+        unit,
+        category: None,
+    }
+}
+
+/// Splits CSV text into rows of cells. Cells may be wrapped in double quotes
+/// to contain the delimiter or line breaks; `""` inside quotes is a literal
+/// quote. Blank lines are skipped.
+pub(crate) fn parse_csv(input: &str, delimiter: char) -> Vec<Vec<String>> {
+    let mut rows = vec![];
+    let mut row = vec![];
+    let mut cell = String::new();
+    let mut quoted = false;
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                cell.push('"');
+                chars.next();
+            }
+            '"' if quoted => quoted = false,
+            '"' if cell.is_empty() => quoted = true,
+            c if quoted => cell.push(c),
+            c if c == delimiter => row.push(std::mem::take(&mut cell)),
+            '\r' => {}
+            '\n' => {
+                row.push(std::mem::take(&mut cell));
+                let row = std::mem::take(&mut row);
+                if row.iter().any(|c| !c.is_empty()) {
+                    rows.push(row);
+                }
+            }
+            c => cell.push(c),
+        }
+    }
+
+    row.push(cell);
+    if row.iter().any(|c| !c.is_empty()) {
+        rows.push(row);
+    }
+
+    rows
+}
+
+impl<E: Executor + Clone> super::Module<E> {
+    /// Imports every row of a CSV export, mapping columns to recipe fields.
+    /// Rows that fail to parse or import are reported and don't stop the rest.
+    pub async fn import_mapped(
+        &self,
+        csv: &str,
+        mapping: &ColumnMapping,
+        request_by: impl Into<String>,
+        owner_name: impl Into<Option<String>>,
+    ) -> crate::Result<MappedImport> {
+        let request_by = request_by.into();
+        let owner_name = owner_name.into();
+        let mut rows = parse_csv(csv, mapping.delimiter).into_iter();
+
+        let Some(header) = rows.next() else {
+            crate::user!("CSV file is empty");
+        };
+
+        let columns = Columns::resolve(&header, mapping)?;
+        let mut imported = MappedImport::default();
+
+        for (index, row) in rows.enumerate() {
+            let name = row.get(columns.name).cloned().unwrap_or_default();
+            let result = match columns.to_input(&row, mapping.list_delimiter) {
+                Ok(input) => self.import(input, &request_by, owner_name.clone()).await,
+                Err(err) => Err(err),
+            };
+
+            match result {
+                Ok(id) => imported.ids.push(id),
+                Err(error) => imported.errors.push(MappedRowError {
+                    row: index + 1,
+                    name,
+                    error,
+                }),
+            }
+        }
+
+        Ok(imported)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_csv, parse_ingredient};
+    use imkitchen_types::recipe::IngredientUnit;
+
+    #[test]
+    fn parse_csv_handles_quotes() {
+        let rows = parse_csv("a;b\n\"x;y\";\"say \"\"hi\"\"\"\r\n\n", ';');
+        assert_eq!(
+            rows,
+            vec![
+                vec!["a".to_owned(), "b".to_owned()],
+                vec!["x;y".to_owned(), "say \"hi\"".to_owned()],
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_ingredient_quantity_and_unit() {
+        let flour = parse_ingredient("200 g flour");
+        assert_eq!(flour.quantity, 200);
+        assert_eq!(flour.unit, Some(IngredientUnit::G));
+        assert_eq!(flour.name, "flour");
+
+        let sugar = parse_ingredient("2 tbsp sugar");
+        assert_eq!(sugar.unit, Some(IngredientUnit::Tbsp));
+        assert_eq!(sugar.name, "sugar");
+
+        let eggs = parse_ingredient("3 eggs");
+        assert_eq!(eggs.quantity, 3);
+        assert_eq!(eggs.unit, None);
+        assert_eq!(eggs.name, "eggs");
+
+        let salt = parse_ingredient("salt");
+        assert_eq!(salt.quantity, 0);
+        assert_eq!(salt.name, "salt");
+    }
+}
@@ -0,0 +1,360 @@
+use evento::Executor;
+use imkitchen_types::recipe::{Ingredient, IngredientUnit, Instruction, RecipeType};
+use serde_json::Value;
+
+use super::ImportInput;
+
+/// A recipe read from schema.org markup, with what couldn't be read cleanly.
+pub struct ClippedRecipe {
+    pub input: ImportInput,
+    /// One line per ingredient, duration or yield that had to be guessed or
+    /// left out.
+    pub warnings: Vec<String>,
+}
+
+/// Reads the first `Recipe` out of schema.org JSON-LD, given either the
+/// JSON-LD itself or a whole HTML page embedding it in
+/// `<script type="application/ld+json">` tags.
+pub fn parse_schema_org(source: &str) -> crate::Result<ClippedRecipe> {
+    let source = source.trim();
+    let blocks = if source.starts_with('<') {
+        json_ld_blocks(source)
+    } else {
+        vec![source]
+    };
+
+    let Some(recipe) = blocks
+        .into_iter()
+        .filter_map(|block| serde_json::from_str::<Value>(block).ok())
+        .find_map(|value| find_recipe(&value).cloned())
+    else {
+        crate::user!("No schema.org Recipe found");
+    };
+
+    let mut warnings = vec![];
+    let name = text(&recipe["name"]).unwrap_or_default();
+
+    let ingredients = list(&recipe["recipeIngredient"])
+        .into_iter()
+        .filter_map(|line| {
+            let (ingredient, warning) = parse_ingredient_line(&line)?;
+            warnings.extend(warning);
+            Some(ingredient)
+        })
+        .collect();
+
+    let mut instructions = vec![];
+    collect_instructions(&recipe["recipeInstructions"], &mut instructions);
+
+    let mut minutes = |field: &str| match text(&recipe[field]) {
+        Some(value) => parse_duration(&value).unwrap_or_else(|| {
+            warnings.push(format!("Couldn't read {field}: {value}"));
+            0
+        }),
+        None => 0,
+    };
+    let prep_time = minutes("prepTime");
+    let cook_time = minutes("cookTime");
+
+    let household_size = match recipe_yield(&recipe["recipeYield"]) {
+        Some(size) => size,
+        None => {
+            if !recipe["recipeYield"].is_null() {
+                warnings.push(format!(
+                    "Couldn't read recipeYield: {}",
+                    recipe["recipeYield"]
+                ));
+            }
+            4
+        }
+    };
+
+    Ok(ClippedRecipe {
+        input: ImportInput {
+            recipe_type: RecipeType::MainCourse,
+            description: text(&recipe["description"]).unwrap_or_else(|| name.to_owned()),
+            origin: text(&recipe["url"]),
+            household_size,
+            prep_time,
+            cook_time,
+            ingredients,
+            instructions: instructions
+                .into_iter()
+                .map(|description| Instruction {
+                    description,
+                    time_next: 0,
+                })
+                .collect(),
+            advance_prep: "".to_owned(),
+            accepts_accompaniment: false,
+            dietary_restrictions: vec![],
+            name,
+            force: false,
+        },
+        warnings,
+    })
+}
+
+impl<E: Executor + Clone> super::Module<E> {
+    /// Imports a recipe clipped from a web page, see [`parse_schema_org`].
+    /// Returns the recipe id and the parse warnings.
+    pub async fn import_schema_org(
+        &self,
+        source: &str,
+        request_by: impl Into<String>,
+        owner_name: impl Into<Option<String>>,
+    ) -> crate::Result<(String, Vec<String>)> {
+        let clipped = parse_schema_org(source)?;
+        let id = self.import(clipped.input, request_by, owner_name).await?;
+
+        Ok((id, clipped.warnings))
+    }
+}
+
+fn json_ld_blocks(html: &str) -> Vec<&str> {
+    let lower = html.to_ascii_lowercase();
+    let mut blocks = vec![];
+    let mut from = 0;
+
+    while let Some(start) = lower[from..].find("<script").map(|i| i + from) {
+        let Some(tag_end) = lower[start..].find('>').map(|i| i + start + 1) else {
+            break;
+        };
+        let Some(end) = lower[tag_end..].find("</script").map(|i| i + tag_end) else {
+            break;
+        };
+
+        if lower[start..tag_end].contains("application/ld+json") {
+            blocks.push(html[tag_end..end].trim());
+        }
+
+        from = end;
+    }
+
+    blocks
+}
+
+fn is_recipe(value: &Value) -> bool {
+    match &value["@type"] {
+        Value::String(kind) => kind == "Recipe",
+        Value::Array(kinds) => kinds.iter().any(|kind| kind == "Recipe"),
+        _ => false,
+    }
+}
+
+fn find_recipe(value: &Value) -> Option<&Value> {
+    match value {
+        Value::Array(values) => values.iter().find_map(find_recipe),
+        Value::Object(_) if is_recipe(value) => Some(value),
+        Value::Object(_) => find_recipe(&value["@graph"]),
+        _ => None,
+    }
+}
+
+fn text(value: &Value) -> Option<String> {
+    let value = match value {
+        Value::String(value) => value.trim().to_owned(),
+        Value::Number(value) => value.to_string(),
+        Value::Array(values) => return values.iter().find_map(text),
+        _ => return None,
+    };
+
+    (!value.is_empty()).then_some(value)
+}
+
+fn list(value: &Value) -> Vec<String> {
+    match value {
+        Value::Array(values) => values.iter().filter_map(text).collect(),
+        value => text(value).into_iter().collect(),
+    }
+}
+
+/// Steps come as plain strings, `HowToStep`s or `HowToSection`s of steps.
+fn collect_instructions(value: &Value, instructions: &mut Vec<String>) {
+    match value {
+        Value::Array(values) => {
+            for value in values {
+                collect_instructions(value, instructions);
+            }
+        }
+        Value::Object(_) if !value["itemListElement"].is_null() => {
+            collect_instructions(&value["itemListElement"], instructions);
+        }
+        Value::Object(_) => collect_instructions(&value["text"], instructions),
+        Value::String(value) => instructions.extend(
+            value
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(str::to_owned),
+        ),
+        _ => {}
+    }
+}
+
+/// Minutes in an ISO 8601 duration such as `PT1H30M` or `P0DT45M`.
+fn parse_duration(value: &str) -> Option<u16> {
+    let rest = value.trim().strip_prefix('P')?;
+    let mut seconds = 0u64;
+    let mut number = String::new();
+    let mut in_time = false;
+
+    for c in rest.chars() {
+        match c {
+            'T' if number.is_empty() => in_time = true,
+            '0'..='9' | '.' => number.push(c),
+            _ => {
+                let amount = number.parse::<f64>().ok()?;
+                number.clear();
+                let unit = match (in_time, c) {
+                    (false, 'D') => 86400.0,
+                    (false, 'W') => 604800.0,
+                    (true, 'H') => 3600.0,
+                    (true, 'M') => 60.0,
+                    (true, 'S') => 1.0,
+                    _ => return None,
+                };
+                seconds += (amount * unit) as u64;
+            }
+        }
+    }
+
+    if !number.is_empty() {
+        return None;
+    }
+
+    seconds.div_ceil(60).try_into().ok()
+}
+
+/// Servings out of `4`, `"4 servings"` or `["4", "4 bowls"]`.
+fn recipe_yield(value: &Value) -> Option<u16> {
+    list(value).iter().find_map(|value| {
+        value
+            .split_whitespace()
+            .find_map(|word| word.parse::<u16>().ok())
+            .filter(|size| *size > 0)
+    })
+}
+
+/// Amount at the start of `line`: `2`, `1.5`, `1/2`, `1 1/2` or `½`.
+fn parse_amount(line: &str) -> Option<(f64, &str)> {
+    let vulgar = |c: char| match c {
+        '¼' => Some(0.25),
+        '½' => Some(0.5),
+        '¾' => Some(0.75),
+        '⅓' => Some(1.0 / 3.0),
+        '⅔' => Some(2.0 / 3.0),
+        _ => None,
+    };
+    let number = |word: &str| -> Option<f64> {
+        if let Some((top, bottom)) = word.split_once('/') {
+            let bottom = bottom.parse::<f64>().ok().filter(|b| *b > 0.0)?;
+            return Some(top.parse::<f64>().ok()? / bottom);
+        }
+
+        let mut chars = word.chars();
+        match (chars.next_back().and_then(vulgar), chars.as_str()) {
+            (Some(fraction), "") => Some(fraction),
+            (Some(fraction), whole) => Some(whole.parse::<f64>().ok()? + fraction),
+            (None, _) => word.replace(',', ".").parse().ok(),
+        }
+    };
+
+    let (first, rest) = line.split_once(' ').unwrap_or((line, ""));
+    let amount = number(first)?;
+
+    if let Some((second, tail)) = rest.split_once(' ')
+        && second.contains('/')
+        && let Some(fraction) = number(second)
+    {
+        return Some((amount + fraction, tail.trim_start()));
+    }
+
+    Some((amount, rest.trim_start()))
+}
+
+/// Unit named by `word` and how many of it one of `word` makes.
+fn parse_unit(word: &str) -> Option<(IngredientUnit, f64)> {
+    let word = word.trim_end_matches('.').to_lowercase();
+    let unit = match word.as_str() {
+        "g" | "gr" | "gram" | "grams" => (IngredientUnit::G, 1.0),
+        "kg" | "kilogram" | "kilograms" => (IngredientUnit::G, 1000.0),
+        "ml" | "milliliter" | "milliliters" | "millilitre" | "millilitres" => {
+            (IngredientUnit::ML, 1.0)
+        }
+        "cl" => (IngredientUnit::ML, 10.0),
+        "l" | "liter" | "liters" | "litre" | "litres" => (IngredientUnit::ML, 1000.0),
+        "tsp" | "teaspoon" | "teaspoons" => (IngredientUnit::Tsp, 1.0),
+        "tbsp" | "tablespoon" | "tablespoons" => (IngredientUnit::Tbsp, 1.0),
+        "cup" | "cups" => (IngredientUnit::Cup, 1.0),
+        "pinch" | "pinches" => (IngredientUnit::Pinch, 1.0),
+        "piece" | "pieces" => (IngredientUnit::Piece, 1.0),
+        _ => return None,
+    };
+
+    Some(unit)
+}
+
+/// Best effort `quantity unit name` reading of a free text ingredient line.
+/// Amounts that aren't whole in their unit are converted to a smaller unit
+/// when there is one, rounded otherwise. Lines that can't be structured are
+/// kept whole as the name, with a warning.
+fn parse_ingredient_line(line: &str) -> Option<(Ingredient, Option<String>)> {
+    let line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+    if line.is_empty() {
+        return None;
+    }
+
+    let Some((amount, rest)) = parse_amount(&line) else {
+        let ingredient = Ingredient {
+            name: line.to_owned(),
+            quantity: 0,
+            unit: None,
+            category: None,
+        };
+        return Some((
+            ingredient,
+            Some(format!("Couldn't read a quantity: {line}")),
+        ));
+    };
+
+    let (first, tail) = rest.split_once(' ').unwrap_or((rest, ""));
+    let (unit, amount, name) = match parse_unit(first) {
+        Some((unit, factor)) if !tail.is_empty() => (Some(unit), amount * factor, tail),
+        _ => (None, amount, rest),
+    };
+
+    let (unit, amount) = match unit {
+        Some(unit) if amount.fract() != 0.0 && unit.base().1 > 1 => {
+            let (base, factor) = unit.base();
+            (Some(base), amount * factor as f64)
+        }
+        unit => (unit, amount),
+    };
+
+    let name = name.trim_start_matches("of ").trim();
+    if name.is_empty() {
+        return Some((
+            Ingredient {
+                name: line.to_owned(),
+                quantity: 0,
+                unit: None,
+                category: None,
+            },
+            Some(format!("Couldn't read an ingredient name: {line}")),
+        ));
+    }
+
+    let quantity = amount.round().max(1.0) as u32;
+    let warning = (quantity as f64 != amount).then(|| format!("Rounded quantity: {line}"));
+
+    Some((
+        Ingredient {
+            name: name.to_owned(),
+            quantity,
+            unit,
+            category: None,
+        },
+        warning,
+    ))
+}
@@ -0,0 +1,43 @@
+use evento::{Executor, ProjectionAggregate};
+use imkitchen_types::recipe::InstructionOverlapsChanged;
+
+impl<E: Executor + Clone> super::Module<E> {
+    /// Marks which instructions can start alongside the previous one on the
+    /// cook-along timeline. Steps without a flag stay serial.
+    pub async fn set_instruction_overlaps(
+        &self,
+        id: impl Into<String>,
+        mut can_overlap: Vec<bool>,
+        request_by: impl Into<String>,
+    ) -> crate::Result<()> {
+        let Some(recipe) = self.load(id).await? else {
+            crate::not_found!("recipe");
+        };
+
+        let request_by = request_by.into();
+        if recipe.owner_id != request_by {
+            crate::forbidden!("not owner of recipe");
+        }
+
+        if can_overlap.len() > super::MAX_INSTRUCTIONS {
+            crate::user!("Too many instructions");
+        }
+
+        while can_overlap.last() == Some(&false) {
+            can_overlap.pop();
+        }
+
+        if recipe.can_overlap == can_overlap {
+            return Ok(());
+        }
+
+        recipe
+            .write()?
+            .event(&InstructionOverlapsChanged { can_overlap })
+            .requested_by(request_by)
+            .commit(&self.executor)
+            .await?;
+
+        Ok(())
+    }
+}
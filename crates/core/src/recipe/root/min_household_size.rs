@@ -0,0 +1,40 @@
+use evento::{Executor, ProjectionAggregate};
+use imkitchen_types::recipe::MinHouseholdSizeChanged;
+
+pub struct MinHouseholdSizeInput {
+    pub id: String,
+    /// 0 lets the recipe be planned for any household.
+    pub min_household_size: u16,
+}
+
+impl<E: Executor + Clone> super::Module<E> {
+    pub async fn set_min_household_size(
+        &self,
+        input: MinHouseholdSizeInput,
+        request_by: impl Into<String>,
+    ) -> crate::Result<()> {
+        let Some(recipe) = self.load(&input.id).await? else {
+            crate::not_found!("recipe");
+        };
+
+        let request_by = request_by.into();
+        if recipe.owner_id != request_by {
+            crate::forbidden!("not owner of recipe");
+        }
+
+        if recipe.min_household_size == input.min_household_size {
+            return Ok(());
+        }
+
+        recipe
+            .write()?
+            .event(&MinHouseholdSizeChanged {
+                min_household_size: input.min_household_size,
+            })
+            .requested_by(request_by)
+            .commit(&self.executor)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -7,9 +7,13 @@ use evento::{
 use image::imageops::FilterType;
 use imkitchen_db::recipe_thumbnail::RecipeThumbnail;
 use imkitchen_types::recipe::{
-    self, AdvancePrepChanged, BasicInformationChanged, Created, CuisineTypeChanged, Deleted,
-    DietaryRestrictionsChanged, Imported, IngredientsChanged, InstructionsChanged, MadePrivate,
-    MainCourseOptionsChanged, RecipeType, RecipeTypeChanged, SharedToCommunity, ThumbnailResized,
+    self, AccompanimentType, AccompanimentTypesChanged, AdvancePrepChanged,
+    AdvancePrepHoursChanged, BasicInformationChanged, Complexity, ComplexityChanged, Created,
+    CuisineType, CuisineTypeChanged, DefaultAccompanimentsChanged, Deleted,
+    DietaryRestrictionsChanged, Equipment, EquipmentChanged, Hidden, Imported, Ingredient,
+    IngredientsChanged, Instruction, InstructionOverlapsChanged, InstructionsChanged, MadePrivate,
+    MainCourseOptionsChanged, MinHouseholdSizeChanged, Nutrition, NutritionChanged, RecipeType,
+    RecipeTypeChanged, ServingsYieldChanged, SharedToCommunity, TagsChanged, ThumbnailResized,
     ThumbnailUploaded,
 };
 use imkitchen_types::recipe_share::{self, AllMadePrivate, AllSharedToCommunity};
@@ -19,23 +23,56 @@ use sha3::{Digest, Sha3_224};
 use std::ops::Deref;
 use webp::Encoder;
 
+mod accompaniment;
+mod advance_prep_hours;
+mod complexity;
 mod create;
+mod cuisine_type;
 mod delete;
+mod equipment;
 mod import;
+mod import_json;
+mod import_mapped;
+mod import_schema_org;
+mod instruction_overlaps;
 mod make_all_private;
 mod make_private;
+mod min_household_size;
+mod moderate;
+mod nutrition;
+mod rules;
+mod servings_yield;
 mod share_all_to_community;
 mod share_to_community;
+mod tags;
 mod update;
 mod upload_thumbnail;
 
+pub use accompaniment::{
+    AccompanimentTypesInput, DefaultAccompanimentsInput, MAX_DEFAULT_ACCOMPANIMENTS,
+};
+pub use advance_prep_hours::AdvancePrepHoursInput;
+pub use complexity::ComplexityInput;
+pub use cuisine_type::CuisineTypeInput;
+pub use equipment::EquipmentInput;
 pub use import::ImportInput;
+pub use import_json::parse_recipe_json;
+pub use import_mapped::{ColumnMapping, MappedImport, MappedRowError};
+pub use import_schema_org::{ClippedRecipe, parse_schema_org};
+pub use min_household_size::MinHouseholdSizeInput;
+pub use moderate::ModerateInput;
+pub use nutrition::NutritionInput;
+pub use rules::{MAX_ADVANCE_PREP_HOURS, MAX_INGREDIENTS, MAX_INSTRUCTIONS, MAX_QUANTITY};
+pub use servings_yield::ServingsYieldInput;
+pub use tags::TagsInput;
 pub use update::UpdateInput;
 
 #[derive(Clone)]
 pub struct Module<E: Executor> {
     state: crate::State<E>,
+    pub comment: crate::recipe::comment::Module<E>,
     pub favorite: crate::recipe::favorite::Module<E>,
+    pub rating: crate::recipe::rating::Module<E>,
 }
 
 impl<E: Executor> Deref for Module<E> {
@@ -52,7 +89,9 @@ impl<E: Executor> Module<E> {
         crate::State<E>: Clone,
     {
         Self {
+            comment: crate::recipe::comment::Module(state.clone()),
             favorite: crate::recipe::favorite::Module(state.clone()),
+            rating: crate::recipe::rating::Module(state.clone()),
             state,
         }
     }
@@ -84,8 +123,27 @@ pub struct Recipe {
     pub instructions_hash: Vec<u8>,
     pub dietary_restrictions_hash: Vec<u8>,
     pub advance_prep_hash: Vec<u8>,
+    pub advance_prep_hours: Option<u16>,
     pub accepts_accompaniment: bool,
+    pub accompaniment_type: Option<AccompanimentType>,
+    pub preferred_accompaniment_types: Vec<AccompanimentType>,
+    pub default_accompaniment_ids: Vec<String>,
+    pub equipment: Vec<Equipment>,
+    pub min_household_size: u16,
+    pub servings_yield: u16,
+    /// Per-instruction flags for the cook-along timeline.
+    pub can_overlap: Vec<bool>,
+    /// Per-ingredient nutrition, see [`NutritionChanged`].
+    pub nutrition: Vec<Option<Nutrition>>,
+    /// The author's own rating, see [`ComplexityChanged`].
+    pub complexity: Option<Complexity>,
+    pub tags: Vec<String>,
+    /// `None` until the author picks one, imports don't know it.
+    pub cuisine_type: Option<CuisineType>,
     pub is_shared: bool,
+    pub moderated_by: Option<String>,
+    /// Hidden by an admin, see [`Hidden`].
+    pub is_hidden: bool,
 }
 
 #[evento::projection(Encode, Decode)]
@@ -126,7 +184,7 @@ async fn handle_all_made_private(
 
 pub fn create_projection<E: Executor>() -> Projection<E, Recipe> {
     Projection::new::<recipe::Recipe>()
-        .revision(2)
+        .revision(17)
         .tombstone::<Deleted>()
         .handler(handle_created())
         .handler(handle_imported())
@@ -135,13 +193,25 @@ pub fn create_projection<E: Executor>() -> Projection<E, Recipe> {
         .handler(handle_recipe_type_changed())
         .handler(handle_shared_to_community())
         .handler(handle_advance_prep_changed())
+        .handler(handle_advance_prep_hours_changed())
         .handler(handle_instructions_changed())
+        .handler(handle_instruction_overlaps_changed())
         .handler(handle_basic_information_changed())
         .handler(handle_main_course_options_changed())
+        .handler(handle_accompaniment_types_changed())
+        .handler(handle_default_accompaniments_changed())
+        .handler(handle_equipment_changed())
+        .handler(handle_min_household_size_changed())
+        .handler(handle_servings_yield_changed())
+        .handler(handle_nutrition_changed())
+        .handler(handle_complexity_changed())
+        .handler(handle_tags_changed())
+        .handler(handle_cuisine_type_changed())
         .handler(handle_dietary_restrictions_changed())
+        .handler(handle_moderated())
+        .handler(handle_hidden())
         .skip::<ThumbnailUploaded>()
         .skip::<ThumbnailResized>()
-        .skip::<CuisineTypeChanged>()
         .strict()
 }
 
@@ -151,55 +221,97 @@ impl ProjectionAggregate for Recipe {
     }
 }
 
-#[evento::handler]
-async fn handle_created(event: Event<Created>, data: &mut Recipe) -> anyhow::Result<()> {
-    data.id = event.aggregate_id.to_owned();
-    data.owner_id = event.metadata.requested_by()?;
-
-    Ok(())
-}
-
-#[evento::handler]
-async fn handle_imported(event: Event<Imported>, data: &mut Recipe) -> anyhow::Result<()> {
-    data.id = event.aggregate_id.to_owned();
-    data.owner_id = event.metadata.requested_by()?;
-    data.recipe_type = event.data.recipe_type;
-
+pub(crate) fn basic_information_hash(
+    name: &str,
+    origin: Option<&str>,
+    description: &str,
+    household_size: u16,
+    prep_time: u16,
+    cook_time: u16,
+) -> Vec<u8> {
     let mut hasher = Sha3_224::default();
-    hasher.update(event.data.name);
-    hasher.update(event.data.origin.unwrap_or_default());
-    hasher.update(event.data.description);
-    hasher.update(event.data.household_size.to_string());
-    hasher.update(event.data.prep_time.to_string());
-    hasher.update(event.data.cook_time.to_string());
-
-    data.basic_information_hash = hasher.finalize()[..].to_vec();
+    hasher.update(name);
+    hasher.update(origin.unwrap_or_default());
+    hasher.update(description);
+    hasher.update(household_size.to_string());
+    hasher.update(prep_time.to_string());
+    hasher.update(cook_time.to_string());
+
+    hasher.finalize()[..].to_vec()
+}
 
+pub(crate) fn instructions_hash(instructions: &[Instruction]) -> Vec<u8> {
     let mut hasher = Sha3_224::default();
 
-    for instruction in event.data.instructions {
-        hasher.update(instruction.description);
+    for instruction in instructions {
+        hasher.update(&instruction.description);
         hasher.update(instruction.time_next.to_string());
     }
 
-    data.instructions_hash = hasher.finalize()[..].to_vec();
+    hasher.finalize()[..].to_vec()
+}
 
+pub(crate) fn ingredients_hash(ingredients: &[Ingredient]) -> Vec<u8> {
     let mut hasher = Sha3_224::default();
 
-    for ingredient in event.data.ingredients {
-        hasher.update(ingredient.name);
+    for ingredient in ingredients {
+        hasher.update(&ingredient.name);
         hasher.update(ingredient.quantity.to_string());
 
-        if let Some(unit) = ingredient.unit {
+        if let Some(unit) = &ingredient.unit {
             hasher.update(unit.to_string());
         }
 
-        if let Some(category) = ingredient.category {
+        if let Some(category) = &ingredient.category {
             hasher.update(category.to_string());
         }
     }
 
-    data.ingredients_hash = hasher.finalize()[..].to_vec();
+    hasher.finalize()[..].to_vec()
+}
+
+/// Fingerprint of what makes two recipes the same dish: the basic
+/// information, ingredient and instruction hashes combined. Dietary
+/// restrictions and advance prep are left out, they don't change the dish.
+pub(crate) fn content_hash(
+    basic_information: &[u8],
+    ingredients: &[u8],
+    instructions: &[u8],
+) -> Vec<u8> {
+    let mut hasher = Sha3_224::default();
+    hasher.update(basic_information);
+    hasher.update(ingredients);
+    hasher.update(instructions);
+
+    hasher.finalize()[..].to_vec()
+}
+
+#[evento::handler]
+async fn handle_created(event: Event<Created>, data: &mut Recipe) -> anyhow::Result<()> {
+    data.id = event.aggregate_id.to_owned();
+    data.owner_id = event.metadata.requested_by()?;
+
+    Ok(())
+}
+
+#[evento::handler]
+async fn handle_imported(event: Event<Imported>, data: &mut Recipe) -> anyhow::Result<()> {
+    data.id = event.aggregate_id.to_owned();
+    data.owner_id = event.metadata.requested_by()?;
+    data.recipe_type = event.data.recipe_type;
+
+    data.basic_information_hash = basic_information_hash(
+        &event.data.name,
+        event.data.origin.as_deref(),
+        &event.data.description,
+        event.data.household_size,
+        event.data.prep_time,
+        event.data.cook_time,
+    );
+
+    data.instructions_hash = instructions_hash(&event.data.instructions);
+
+    data.ingredients_hash = ingredients_hash(&event.data.ingredients);
 
     let mut hasher = Sha3_224::default();
     hasher.update(event.data.advance_prep);
@@ -232,15 +344,14 @@ async fn handle_basic_information_changed(
     event: Event<BasicInformationChanged>,
     data: &mut Recipe,
 ) -> anyhow::Result<()> {
-    let mut hasher = Sha3_224::default();
-    hasher.update(event.data.name);
-    hasher.update(event.data.origin.unwrap_or_default());
-    hasher.update(event.data.description);
-    hasher.update(event.data.household_size.to_string());
-    hasher.update(event.data.prep_time.to_string());
-    hasher.update(event.data.cook_time.to_string());
-
-    data.basic_information_hash = hasher.finalize()[..].to_vec();
+    data.basic_information_hash = basic_information_hash(
+        &event.data.name,
+        event.data.origin.as_deref(),
+        &event.data.description,
+        event.data.household_size,
+        event.data.prep_time,
+        event.data.cook_time,
+    );
 
     Ok(())
 }
@@ -250,14 +361,9 @@ async fn handle_instructions_changed(
     event: Event<InstructionsChanged>,
     data: &mut Recipe,
 ) -> anyhow::Result<()> {
-    let mut hasher = Sha3_224::default();
-
-    for instruction in event.data.instructions {
-        hasher.update(instruction.description);
-        hasher.update(instruction.time_next.to_string());
-    }
-
-    data.instructions_hash = hasher.finalize()[..].to_vec();
+    data.instructions_hash = instructions_hash(&event.data.instructions);
+    // The flags are positional; they don't survive the steps being replaced.
+    data.can_overlap = vec![];
 
     Ok(())
 }
@@ -267,22 +373,9 @@ async fn handle_ingredients_changed(
     event: Event<IngredientsChanged>,
     data: &mut Recipe,
 ) -> anyhow::Result<()> {
-    let mut hasher = Sha3_224::default();
-
-    for ingredient in event.data.ingredients {
-        hasher.update(ingredient.name);
-        hasher.update(ingredient.quantity.to_string());
-
-        if let Some(unit) = ingredient.unit {
-            hasher.update(unit.to_string());
-        }
-
-        if let Some(category) = ingredient.category {
-            hasher.update(category.to_string());
-        }
-    }
-
-    data.ingredients_hash = hasher.finalize()[..].to_vec();
+    data.ingredients_hash = ingredients_hash(&event.data.ingredients);
+    // Positional like the overlaps, see `handle_instructions_changed`.
+    data.nutrition = vec![];
 
     Ok(())
 }
@@ -313,6 +406,121 @@ async fn handle_main_course_options_changed(
     Ok(())
 }
 
+#[evento::handler]
+async fn handle_accompaniment_types_changed(
+    event: Event<AccompanimentTypesChanged>,
+    data: &mut Recipe,
+) -> anyhow::Result<()> {
+    data.accompaniment_type = event.data.accompaniment_type;
+    data.preferred_accompaniment_types = event.data.preferred_accompaniment_types;
+
+    Ok(())
+}
+
+#[evento::handler]
+async fn handle_default_accompaniments_changed(
+    event: Event<DefaultAccompanimentsChanged>,
+    data: &mut Recipe,
+) -> anyhow::Result<()> {
+    data.default_accompaniment_ids = event.data.accompaniment_ids;
+
+    Ok(())
+}
+
+#[evento::handler]
+async fn handle_equipment_changed(
+    event: Event<EquipmentChanged>,
+    data: &mut Recipe,
+) -> anyhow::Result<()> {
+    data.equipment = event.data.equipment;
+
+    Ok(())
+}
+
+#[evento::handler]
+async fn handle_min_household_size_changed(
+    event: Event<MinHouseholdSizeChanged>,
+    data: &mut Recipe,
+) -> anyhow::Result<()> {
+    data.min_household_size = event.data.min_household_size;
+
+    Ok(())
+}
+
+#[evento::handler]
+async fn handle_servings_yield_changed(
+    event: Event<ServingsYieldChanged>,
+    data: &mut Recipe,
+) -> anyhow::Result<()> {
+    data.servings_yield = event.data.servings_yield;
+
+    Ok(())
+}
+
+#[evento::handler]
+async fn handle_nutrition_changed(
+    event: Event<NutritionChanged>,
+    data: &mut Recipe,
+) -> anyhow::Result<()> {
+    data.nutrition = event.data.nutrition;
+
+    Ok(())
+}
+
+#[evento::handler]
+async fn handle_complexity_changed(
+    event: Event<ComplexityChanged>,
+    data: &mut Recipe,
+) -> anyhow::Result<()> {
+    data.complexity = event.data.complexity;
+
+    Ok(())
+}
+
+#[evento::handler]
+async fn handle_tags_changed(event: Event<TagsChanged>, data: &mut Recipe) -> anyhow::Result<()> {
+    data.tags = event.data.tags;
+
+    Ok(())
+}
+
+#[evento::handler]
+async fn handle_cuisine_type_changed(
+    event: Event<CuisineTypeChanged>,
+    data: &mut Recipe,
+) -> anyhow::Result<()> {
+    data.cuisine_type = Some(event.data.cuisine_type);
+
+    Ok(())
+}
+
+#[evento::handler]
+async fn handle_instruction_overlaps_changed(
+    event: Event<InstructionOverlapsChanged>,
+    data: &mut Recipe,
+) -> anyhow::Result<()> {
+    data.can_overlap = event.data.can_overlap;
+
+    Ok(())
+}
+
+#[evento::handler]
+async fn handle_moderated(
+    event: Event<recipe::Moderated>,
+    data: &mut Recipe,
+) -> anyhow::Result<()> {
+    data.moderated_by = Some(event.data.admin_id);
+
+    Ok(())
+}
+
+#[evento::handler]
+async fn handle_hidden(_event: Event<Hidden>, data: &mut Recipe) -> anyhow::Result<()> {
+    data.is_hidden = true;
+
+    Ok(())
+}
+
 #[evento::handler]
 async fn handle_advance_prep_changed(
     event: Event<AdvancePrepChanged>,
@@ -326,6 +534,16 @@ async fn handle_advance_prep_changed(
     Ok(())
 }
 
+#[evento::handler]
+async fn handle_advance_prep_hours_changed(
+    event: Event<AdvancePrepHoursChanged>,
+    data: &mut Recipe,
+) -> anyhow::Result<()> {
+    data.advance_prep_hours = event.data.advance_prep_hours;
+
+    Ok(())
+}
+
 #[evento::handler]
 async fn handle_shared_to_community(
     _event: Event<SharedToCommunity>,
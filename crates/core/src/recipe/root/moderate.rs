@@ -0,0 +1,75 @@
+use evento::{Aggregate, Executor, ProjectionAggregate};
+use imkitchen_types::recipe::{self, Deleted, Hidden, MadePrivate, Moderated, ModerationAction};
+
+pub struct ModerateInput {
+    pub ids: Vec<String>,
+    pub action: ModerationAction,
+}
+
+impl<E: Executor> super::Module<E> {
+    /// Forces recipes private, hides them or deletes them on behalf of an
+    /// admin, whoever owns them. The events are requested by `admin_id`;
+    /// callers are responsible for checking it is an admin. Returns how many
+    /// recipes were changed; unknown ids and recipes already private or
+    /// hidden are skipped.
+    pub async fn moderate(
+        &self,
+        input: ModerateInput,
+        admin_id: impl Into<String>,
+    ) -> crate::Result<usize> {
+        let admin_id = admin_id.into();
+        let mut changed = 0;
+
+        for id in input.ids {
+            let Some(recipe) = self.load(&id).await? else {
+                continue;
+            };
+
+            if input.action == ModerationAction::MadePrivate && !recipe.is_shared {
+                continue;
+            }
+
+            if input.action == ModerationAction::Hidden && recipe.is_hidden {
+                continue;
+            }
+
+            let mut builder = recipe.write()?;
+            builder
+                .event(&Moderated {
+                    action: input.action,
+                    admin_id: admin_id.to_owned(),
+                })
+                .requested_by(&admin_id);
+
+            match input.action {
+                ModerationAction::MadePrivate => {
+                    builder.event(&MadePrivate);
+                }
+                ModerationAction::Hidden => {
+                    if recipe.is_shared {
+                        builder.event(&MadePrivate);
+                    }
+
+                    builder.event(&Hidden {
+                        owner_id: recipe.owner_id.to_owned(),
+                    });
+                }
+                ModerationAction::Deleted => {
+                    builder.event(&Deleted);
+                }
+            };
+
+            builder.commit(&self.executor).await?;
+
+            if input.action == ModerationAction::Deleted {
+                self.executor
+                    .delete_snapshot(recipe::Recipe::aggregate_type().to_owned(), id)
+                    .await?;
+            }
+
+            changed += 1;
+        }
+
+        Ok(changed)
+    }
+}
@@ -0,0 +1,49 @@
+use evento::{Executor, ProjectionAggregate};
+use imkitchen_types::recipe::{Nutrition, NutritionChanged};
+
+pub struct NutritionInput {
+    pub id: String,
+    /// One entry per ingredient, in order. `None` for ingredients without
+    /// nutrition data.
+    pub nutrition: Vec<Option<Nutrition>>,
+}
+
+impl<E: Executor + Clone> super::Module<E> {
+    pub async fn set_nutrition(
+        &self,
+        mut input: NutritionInput,
+        request_by: impl Into<String>,
+    ) -> crate::Result<()> {
+        let Some(recipe) = self.load(&input.id).await? else {
+            crate::not_found!("recipe");
+        };
+
+        let request_by = request_by.into();
+        if recipe.owner_id != request_by {
+            crate::forbidden!("not owner of recipe");
+        }
+
+        if input.nutrition.len() > super::MAX_INGREDIENTS {
+            crate::user!("Too many ingredients");
+        }
+
+        while input.nutrition.last() == Some(&None) {
+            input.nutrition.pop();
+        }
+
+        if recipe.nutrition == input.nutrition {
+            return Ok(());
+        }
+
+        recipe
+            .write()?
+            .event(&NutritionChanged {
+                nutrition: input.nutrition,
+            })
+            .requested_by(request_by)
+            .commit(&self.executor)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,120 @@
+use imkitchen_types::recipe::{Ingredient, Instruction};
+use validator::ValidationError;
+
+pub const MAX_INGREDIENTS: usize = 100;
+pub const MAX_INSTRUCTIONS: usize = 100;
+/// Largest quantity an ingredient can hold, in its unit (100 kg / 100 L).
+pub const MAX_QUANTITY: u32 = 100_000;
+/// Advance prep can't start more than a week ahead.
+pub const MAX_ADVANCE_PREP_HOURS: u16 = 7 * 24;
+
+/// Shared by [`super::ImportInput`] and [`super::UpdateInput`]. Every problem
+/// found in the list is reported in a single error, so a form can show them
+/// all at once.
+pub(crate) fn validate_ingredients(ingredients: &[Ingredient]) -> Result<(), ValidationError> {
+    let mut problems = vec![];
+
+    if ingredients.is_empty() {
+        problems.push("at least one ingredient".to_owned());
+    }
+
+    if ingredients.len() > MAX_INGREDIENTS {
+        problems.push(format!("no more than {MAX_INGREDIENTS} ingredients"));
+    }
+
+    for (index, ingredient) in ingredients.iter().enumerate() {
+        if ingredient.name.trim().is_empty() {
+            problems.push(format!("ingredient {} has no name", index + 1));
+        }
+
+        if ingredient.quantity > MAX_QUANTITY {
+            problems.push(format!(
+                "ingredient {} quantity is over {MAX_QUANTITY}",
+                index + 1
+            ));
+        }
+    }
+
+    to_result("ingredients", problems)
+}
+
+pub(crate) fn validate_instructions(instructions: &[Instruction]) -> Result<(), ValidationError> {
+    let mut problems = vec![];
+
+    if instructions.is_empty() {
+        problems.push("at least one step".to_owned());
+    }
+
+    if instructions.len() > MAX_INSTRUCTIONS {
+        problems.push(format!("no more than {MAX_INSTRUCTIONS} instructions"));
+    }
+
+    for (index, instruction) in instructions.iter().enumerate() {
+        if instruction.description.trim().is_empty() {
+            problems.push(format!("step {} is empty", index + 1));
+        }
+    }
+
+    to_result("instructions", problems)
+}
+
+fn to_result(code: &'static str, problems: Vec<String>) -> Result<(), ValidationError> {
+    if problems.is_empty() {
+        return Ok(());
+    }
+
+    Err(ValidationError::new(code).with_message(problems.join(", ").into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MAX_QUANTITY, validate_ingredients, validate_instructions};
+    use imkitchen_types::recipe::{Ingredient, Instruction};
+
+    fn ingredient(name: &str, quantity: u32) -> Ingredient {
+        Ingredient {
+            name: name.to_owned(),
+            quantity,
+            unit: None,
+            category: None,
+        }
+    }
+
+    #[test]
+    fn ingredients_report_every_problem() {
+        let err = validate_ingredients(&[
+            ingredient("flour", 200),
+            ingredient(" ", 3),
+            ingredient("sugar", MAX_QUANTITY + 1),
+        ])
+        .unwrap_err();
+
+        assert_eq!(
+            err.message.unwrap(),
+            "ingredient 2 has no name, ingredient 3 quantity is over 100000"
+        );
+    }
+
+    #[test]
+    fn instructions_reject_empty_steps() {
+        let step = |description: &str| Instruction {
+            description: description.to_owned(),
+            time_next: 0,
+        };
+
+        assert!(validate_instructions(&[step("Mix")]).is_ok());
+        assert!(validate_instructions(&[step("Mix"), step("")]).is_err());
+    }
+
+    #[test]
+    fn empty_lists_are_rejected() {
+        assert_eq!(
+            validate_ingredients(&[]).unwrap_err().message.unwrap(),
+            "at least one ingredient"
+        );
+        assert_eq!(
+            validate_instructions(&[]).unwrap_err().message.unwrap(),
+            "at least one step"
+        );
+    }
+}
@@ -0,0 +1,40 @@
+use evento::{Executor, ProjectionAggregate};
+use imkitchen_types::recipe::ServingsYieldChanged;
+
+pub struct ServingsYieldInput {
+    pub id: String,
+    /// 0 means one batch serves the recipe's household size.
+    pub servings_yield: u16,
+}
+
+impl<E: Executor + Clone> super::Module<E> {
+    pub async fn set_servings_yield(
+        &self,
+        input: ServingsYieldInput,
+        request_by: impl Into<String>,
+    ) -> crate::Result<()> {
+        let Some(recipe) = self.load(&input.id).await? else {
+            crate::not_found!("recipe");
+        };
+
+        let request_by = request_by.into();
+        if recipe.owner_id != request_by {
+            crate::forbidden!("not owner of recipe");
+        }
+
+        if recipe.servings_yield == input.servings_yield {
+            return Ok(());
+        }
+
+        recipe
+            .write()?
+            .event(&ServingsYieldChanged {
+                servings_yield: input.servings_yield,
+            })
+            .requested_by(request_by)
+            .commit(&self.executor)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -17,6 +17,10 @@ impl<E: Executor + Clone> super::Module<E> {
             crate::forbidden!("not owner of recipe");
         }
 
+        if recipe.is_hidden {
+            crate::forbidden!("recipe hidden by an admin");
+        }
+
         if !recipe.is_shared {
             recipe
                 .write()?
@@ -0,0 +1,45 @@
+use evento::{Executor, ProjectionAggregate};
+use imkitchen_types::recipe::TagsChanged;
+
+pub struct TagsInput {
+    pub id: String,
+    pub tags: Vec<String>,
+}
+
+impl<E: Executor + Clone> super::Module<E> {
+    pub async fn set_tags(
+        &self,
+        input: TagsInput,
+        request_by: impl Into<String>,
+    ) -> crate::Result<()> {
+        let Some(recipe) = self.load(&input.id).await? else {
+            crate::not_found!("recipe");
+        };
+
+        let request_by = request_by.into();
+        if recipe.owner_id != request_by {
+            crate::forbidden!("not owner of recipe");
+        }
+
+        let mut tags = vec![];
+        for tag in input.tags {
+            let tag = tag.trim().to_lowercase();
+            if !tag.is_empty() && !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+
+        if recipe.tags == tags {
+            return Ok(());
+        }
+
+        recipe
+            .write()?
+            .event(&TagsChanged { tags })
+            .requested_by(request_by)
+            .commit(&self.executor)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -22,7 +22,9 @@ pub struct UpdateInput {
     pub household_size: u16,
     pub prep_time: u16,
     pub cook_time: u16,
+    #[validate(custom(function = "super::rules::validate_ingredients"))]
     pub ingredients: Vec<Ingredient>,
+    #[validate(custom(function = "super::rules::validate_instructions"))]
     pub instructions: Vec<Instruction>,
     pub dietary_restrictions: Vec<DietaryRestriction>,
     pub accepts_accompaniment: bool,
@@ -0,0 +1,295 @@
+use evento::{
+    Cursor, Executor,
+    cursor::{Args, ReadResult},
+    metadata::Event,
+    sql::Reader,
+    subscription::{Context, SubscriptionBuilder},
+};
+use imkitchen_db::shopping_history::ShoppingHistory;
+use imkitchen_types::shopping::{
+    Checked, ChecklistReset, Generated, GeneratedV2, RecipeAdded, RecipeRemoved, Unchecked,
+};
+use sea_query::{Expr, ExprTrait, OnConflict, Order, Query, SqliteQueryBuilder};
+use sea_query_sqlx::SqlxBinder;
+use sqlx::{SqlitePool, prelude::FromRow};
+
+/// Number of most recent weeks kept per user; older lists are evicted when a
+/// new one is generated.
+pub const RETENTION_WEEKS: u64 = 12;
+
+#[derive(Debug, Default, Clone, FromRow, Cursor)]
+pub struct ShoppingHistoryRow {
+    #[cursor(ShoppingHistory::Id, 1)]
+    pub id: String,
+    #[cursor(ShoppingHistory::FromDate, 2)]
+    pub from_date: u64,
+    pub days: u8,
+    pub total: u32,
+    pub checked: u32,
+    pub generated_at: u64,
+}
+
+impl ShoppingHistoryRow {
+    /// Share of the list's items that were checked off, 0–100.
+    pub fn completion_pct(&self) -> u32 {
+        (self.checked.min(self.total) * 100)
+            .checked_div(self.total)
+            .unwrap_or(0)
+    }
+}
+
+impl<E: Executor> crate::shopping::Module<E> {
+    /// Past shopping lists of the user, newest week first.
+    pub async fn history(
+        &self,
+        user_id: impl Into<String>,
+        args: Args,
+    ) -> anyhow::Result<ReadResult<ShoppingHistoryRow>> {
+        let statement = Query::select()
+            .columns([
+                ShoppingHistory::Id,
+                ShoppingHistory::FromDate,
+                ShoppingHistory::Days,
+                ShoppingHistory::Total,
+                ShoppingHistory::Checked,
+                ShoppingHistory::GeneratedAt,
+            ])
+            .from(ShoppingHistory::Table)
+            .and_where(Expr::col(ShoppingHistory::UserId).eq(user_id.into()))
+            .to_owned();
+
+        Reader::new(statement)
+            .desc()
+            .args(args)
+            .execute(&self.read_db)
+            .await
+    }
+}
+
+pub fn subscription<E: Executor>() -> SubscriptionBuilder<E> {
+    SubscriptionBuilder::new("shopping-history")
+        .handler(handle_generated())
+        .handler(handle_generated_v2())
+        .handler(handle_checked())
+        .handler(handle_unchecked())
+        .handler(handle_checklist_reset())
+        .handler(handle_recipe_added())
+        .handler(handle_recipe_removed())
+}
+
+#[evento::subscription]
+async fn handle_generated<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<Generated>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<SqlitePool>();
+    insert_week(
+        &pool,
+        &event.aggregate_id,
+        event.data.from_date,
+        event.data.days,
+        event.data.ingredients.len(),
+        event.timestamp,
+    )
+    .await
+}
+
+#[evento::subscription]
+async fn handle_generated_v2<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<GeneratedV2>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<SqlitePool>();
+    insert_week(
+        &pool,
+        &event.aggregate_id,
+        event.data.from_date,
+        event.data.days,
+        event.data.ingredients.len(),
+        event.timestamp,
+    )
+    .await
+}
+
+/// Regenerating a week resets its row; then everything past the retention
+/// window is dropped.
+async fn insert_week(
+    pool: &SqlitePool,
+    user_id: &str,
+    from_date: u64,
+    days: u8,
+    total: usize,
+    timestamp: u64,
+) -> anyhow::Result<()> {
+    let (sql, values) = Query::insert()
+        .into_table(ShoppingHistory::Table)
+        .columns([
+            ShoppingHistory::Id,
+            ShoppingHistory::UserId,
+            ShoppingHistory::FromDate,
+            ShoppingHistory::Days,
+            ShoppingHistory::Total,
+            ShoppingHistory::Checked,
+            ShoppingHistory::GeneratedAt,
+        ])
+        .values_panic([
+            format!("{user_id}-{from_date}").into(),
+            user_id.into(),
+            from_date.into(),
+            (days as i32).into(),
+            (total as u32).into(),
+            0.into(),
+            timestamp.into(),
+        ])
+        .on_conflict(
+            OnConflict::column(ShoppingHistory::Id)
+                .update_columns([
+                    ShoppingHistory::Days,
+                    ShoppingHistory::Total,
+                    ShoppingHistory::Checked,
+                    ShoppingHistory::GeneratedAt,
+                ])
+                .to_owned(),
+        )
+        .build_sqlx(SqliteQueryBuilder);
+
+    sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+        .execute(pool)
+        .await?;
+
+    let kept = Query::select()
+        .column(ShoppingHistory::Id)
+        .from(ShoppingHistory::Table)
+        .and_where(Expr::col(ShoppingHistory::UserId).eq(user_id))
+        .order_by(ShoppingHistory::FromDate, Order::Desc)
+        .limit(RETENTION_WEEKS)
+        .to_owned();
+
+    let (sql, values) = Query::delete()
+        .from_table(ShoppingHistory::Table)
+        .and_where(Expr::col(ShoppingHistory::UserId).eq(user_id))
+        .and_where(Expr::col(ShoppingHistory::Id).not_in_subquery(kept))
+        .build_sqlx(SqliteQueryBuilder);
+
+    sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Checks and recipe changes always apply to the list generated last. Weeks
+/// generated within the same second resolve to the latest week.
+fn latest_week(user_id: &str) -> sea_query::SelectStatement {
+    Query::select()
+        .column(ShoppingHistory::Id)
+        .from(ShoppingHistory::Table)
+        .and_where(Expr::col(ShoppingHistory::UserId).eq(user_id))
+        .order_by(ShoppingHistory::GeneratedAt, Order::Desc)
+        .order_by(ShoppingHistory::FromDate, Order::Desc)
+        .limit(1)
+        .to_owned()
+}
+
+async fn update_latest(
+    pool: &SqlitePool,
+    user_id: &str,
+    column: ShoppingHistory,
+    value: Expr,
+) -> anyhow::Result<()> {
+    let (sql, values) = Query::update()
+        .table(ShoppingHistory::Table)
+        .value(column, value)
+        .and_where(Expr::col(ShoppingHistory::Id).in_subquery(latest_week(user_id)))
+        .build_sqlx(SqliteQueryBuilder);
+
+    sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+#[evento::subscription]
+async fn handle_checked<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<Checked>,
+) -> anyhow::Result<()> {
+    // Manual items aren't counted in the list's total.
+    if crate::shopping::is_manual_item_key(&event.data.ingredient) {
+        return Ok(());
+    }
+
+    let pool = context.extract::<SqlitePool>();
+    update_latest(
+        &pool,
+        &event.aggregate_id,
+        ShoppingHistory::Checked,
+        Expr::col(ShoppingHistory::Checked).add(1),
+    )
+    .await
+}
+
+#[evento::subscription]
+async fn handle_unchecked<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<Unchecked>,
+) -> anyhow::Result<()> {
+    if crate::shopping::is_manual_item_key(&event.data.ingredient) {
+        return Ok(());
+    }
+
+    let pool = context.extract::<SqlitePool>();
+    update_latest(
+        &pool,
+        &event.aggregate_id,
+        ShoppingHistory::Checked,
+        Expr::cust("MAX(checked - 1, 0)"),
+    )
+    .await
+}
+
+#[evento::subscription]
+async fn handle_checklist_reset<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<ChecklistReset>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<SqlitePool>();
+    update_latest(
+        &pool,
+        &event.aggregate_id,
+        ShoppingHistory::Checked,
+        Expr::val(0),
+    )
+    .await
+}
+
+#[evento::subscription]
+async fn handle_recipe_added<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<RecipeAdded>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<SqlitePool>();
+    update_latest(
+        &pool,
+        &event.aggregate_id,
+        ShoppingHistory::Total,
+        Expr::val(event.data.ingredients.len() as u32),
+    )
+    .await
+}
+
+#[evento::subscription]
+async fn handle_recipe_removed<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<RecipeRemoved>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<SqlitePool>();
+    update_latest(
+        &pool,
+        &event.aggregate_id,
+        ShoppingHistory::Total,
+        Expr::val(event.data.ingredients.len() as u32),
+    )
+    .await
+}
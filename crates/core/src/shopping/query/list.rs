@@ -6,7 +6,7 @@ use evento::{
 use imkitchen_db::shopping_list::ShoppingList;
 use imkitchen_types::{
     recipe::Ingredient,
-    shopping::{Generated, RecipeAdded, RecipeRemoved, RecipeSetGenerated},
+    shopping::{Generated, GeneratedV2, RecipeAdded, RecipeRemoved, RecipeSetGenerated},
 };
 use sea_query::{Expr, ExprTrait, OnConflict, Query, SqliteQueryBuilder};
 use sea_query_sqlx::SqlxBinder;
@@ -23,6 +23,19 @@ pub struct ShoppingListRow {
     pub recipes: Option<evento::sql_types::Bitcode<Vec<String>>>,
 }
 
+impl ShoppingListRow {
+    /// Last day (YYYYMMDD) the list covers.
+    pub fn valid_until(&self) -> Option<u64> {
+        crate::shopping::valid_until(self.from_date, self.days)
+    }
+
+    /// Whether the week the list was generated for has passed as of `today`
+    /// (YYYYMMDD).
+    pub fn is_expired(&self, today: u64) -> bool {
+        self.valid_until().is_some_and(|last| last < today)
+    }
+}
+
 impl<E: Executor> crate::shopping::Module<E> {
     pub async fn find(
         &self,
@@ -55,6 +68,7 @@ impl<E: Executor> crate::shopping::Module<E> {
 pub fn subscription<E: Executor>() -> SubscriptionBuilder<E> {
     SubscriptionBuilder::new("shopping-list")
         .handler(handle_generated())
+        .handler(handle_generated_v2())
         .handler(handle_recipe_set_generated())
         .handler(handle_recipe_added())
         .handler(handle_recipe_removed())
@@ -66,7 +80,43 @@ async fn handle_generated<E: Executor>(
     event: Event<Generated>,
 ) -> anyhow::Result<()> {
     let pool = context.extract::<sqlx::SqlitePool>();
-    let ingredients = bitcode::encode(&event.data.ingredients);
+    upsert_generated(
+        &pool,
+        &event.aggregate_id,
+        &event.data.ingredients,
+        event.data.from_date,
+        event.data.days,
+        event.timestamp,
+    )
+    .await
+}
+
+#[evento::subscription]
+async fn handle_generated_v2<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<GeneratedV2>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<sqlx::SqlitePool>();
+    upsert_generated(
+        &pool,
+        &event.aggregate_id,
+        &event.data.ingredients,
+        event.data.from_date,
+        event.data.days,
+        event.timestamp,
+    )
+    .await
+}
+
+async fn upsert_generated(
+    pool: &sqlx::SqlitePool,
+    user_id: &str,
+    ingredients: &[Ingredient],
+    from_date: u64,
+    days: u8,
+    timestamp: u64,
+) -> anyhow::Result<()> {
+    let ingredients = bitcode::encode(&ingredients.to_vec());
 
     let statement = Query::insert()
         .into_table(ShoppingList::Table)
@@ -78,11 +128,11 @@ async fn handle_generated<E: Executor>(
             ShoppingList::GeneratedAt,
         ])
         .values_panic([
-            event.aggregate_id.to_owned().into(),
+            user_id.to_owned().into(),
             ingredients.into(),
-            event.data.from_date.into(),
-            (event.data.days as i32).into(),
-            event.timestamp.into(),
+            from_date.into(),
+            (days as i32).into(),
+            timestamp.into(),
         ])
         .on_conflict(
             OnConflict::column(ShoppingList::UserId)
@@ -98,7 +148,7 @@ async fn handle_generated<E: Executor>(
 
     let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
     sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
-        .execute(&pool)
+        .execute(pool)
         .await?;
 
     Ok(())
@@ -35,6 +35,12 @@ impl<E: Executor> super::Module<E> {
                 from_date: 0,
                 days: 0,
                 generated_at: 0,
+                household_size: 0,
+                package_sizes: Default::default(),
+                rounding_strategy: Default::default(),
+                manual_items: Default::default(),
+                aisle_order: Default::default(),
+                owned: Default::default(),
             });
 
         if shopping.recipes.contains(&recipe_id) {
@@ -45,9 +51,21 @@ impl<E: Executor> super::Module<E> {
         recipe_ids.push(recipe_id.to_owned());
 
         let recipe_ingredients = self
-            .filter_recipe_ingredients_by_ids(recipe_ids.clone())
+            .filter_recipe_ingredients_by_ids(&request_by, recipe_ids.clone())
             .await?;
-        let ingredients = merge_ingredients(recipe_ingredients, household_size);
+        let guests = self
+            .filter_guests(&request_by, shopping.from_date, shopping.days)
+            .await?;
+        let servings = self
+            .filter_servings(&request_by, shopping.from_date, shopping.days)
+            .await?;
+        let ingredients = merge_ingredients(
+            recipe_ingredients,
+            household_size,
+            &guests,
+            &servings,
+            shopping.rounding_strategy,
+        );
 
         shopping
             .write()?
@@ -0,0 +1,55 @@
+use evento::{Executor, ProjectionAggregate};
+use imkitchen_types::recipe::IngredientCategory;
+use imkitchen_types::shopping::AisleOrderChanged;
+
+impl<E: Executor> super::Module<E> {
+    /// Sort the list's aisles the way the user walks their store. Categories
+    /// left out follow in the default walk order; an empty list resets it.
+    pub async fn set_aisle_order(
+        &self,
+        aisle_order: Vec<IngredientCategory>,
+        request_by: impl Into<String>,
+    ) -> crate::Result<()> {
+        let request_by = request_by.into();
+        let shopping = self
+            .load(&request_by)
+            .await?
+            .unwrap_or_else(|| super::Shopping {
+                user_id: request_by.to_owned(),
+                checked: Default::default(),
+                ingredients: Default::default(),
+                recipes: Default::default(),
+                cursor: Default::default(),
+                from_date: 0,
+                days: 0,
+                generated_at: 0,
+                household_size: 0,
+                package_sizes: Default::default(),
+                rounding_strategy: Default::default(),
+                manual_items: Default::default(),
+                aisle_order: Default::default(),
+                owned: Default::default(),
+            });
+
+        let mut names: Vec<String> = vec![];
+        for category in aisle_order {
+            let name = category.to_string();
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+
+        if shopping.aisle_order == names {
+            return Ok(());
+        }
+
+        shopping
+            .write()?
+            .event(&AisleOrderChanged { aisle_order: names })
+            .requested_by(request_by)
+            .commit(&self.executor)
+            .await?;
+
+        Ok(())
+    }
+}
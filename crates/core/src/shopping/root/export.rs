@@ -0,0 +1,76 @@
+use imkitchen_types::recipe::{Ingredient, IngredientCategory};
+
+use super::{ShoppingState, manual_item_key};
+
+impl ShoppingState {
+    /// Ingredients grouped by aisle in the user's aisle order, as the
+    /// groceries page lists them.
+    pub fn aisles(&self) -> Vec<(Option<IngredientCategory>, Vec<Ingredient>)> {
+        Ingredient::group_by_aisle(&self.ingredients, &self.aisle_order)
+    }
+
+    /// The list as CSV with a `category,item,quantity,unit,checked` header,
+    /// one row per ingredient then one per manual item. Quantities are what
+    /// to buy, rounded up to whole packages where one is set. Ingredients the
+    /// user always has aren't exported.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("category,item,quantity,unit,checked\r\n");
+
+        for (category, ingredients) in self.aisles() {
+            let category = category.map(|c| c.to_string()).unwrap_or_default();
+
+            for ingredient in ingredients {
+                if self.owned.contains(&ingredient.key()) {
+                    continue;
+                }
+
+                let quantity = self.to_buy(&ingredient).unwrap_or(ingredient.quantity);
+                let unit = ingredient
+                    .unit
+                    .as_ref()
+                    .map(|u| u.to_string())
+                    .unwrap_or_default();
+
+                csv.push_str(&csv_row(&[
+                    &category,
+                    &ingredient.name,
+                    &quantity.to_string(),
+                    &unit,
+                    &self.checked.contains(&ingredient.key()).to_string(),
+                ]));
+            }
+        }
+
+        for name in self.manual_items.iter() {
+            let checked = self.checked.contains(&manual_item_key(name));
+            csv.push_str(&csv_row(&["", name, "", "", &checked.to_string()]));
+        }
+
+        csv
+    }
+}
+
+/// Cells spreadsheets would read as a formula, e.g. an ingredient named
+/// `=HYPERLINK(...)` in a community recipe, are prefixed with `'` so they
+/// stay plain text.
+fn csv_row(cells: &[&str]) -> String {
+    let mut row = cells
+        .iter()
+        .map(|cell| {
+            let cell = if cell.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+                format!("'{cell}")
+            } else {
+                cell.to_string()
+            };
+
+            if cell.contains([',', '"', '\r', '\n']) {
+                format!("\"{}\"", cell.replace('"', "\"\""))
+            } else {
+                cell
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    row.push_str("\r\n");
+    row
+}
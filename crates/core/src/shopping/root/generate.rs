@@ -1,6 +1,6 @@
 use evento::{Executor, ProjectionAggregate};
 use imkitchen_db::shopping_slot::ShoppingSlot;
-use imkitchen_types::shopping::{Generated, RecipeSetGenerated};
+use imkitchen_types::shopping::{GeneratedV2, RecipeSetGenerated};
 use sea_query::{Expr, ExprTrait, SqliteQueryBuilder};
 use sea_query_sqlx::SqlxBinder;
 use std::collections::HashSet;
@@ -36,6 +36,12 @@ impl<E: Executor> super::Module<E> {
                 from_date: 0,
                 days: 0,
                 generated_at: 0,
+                household_size: 0,
+                package_sizes: Default::default(),
+                rounding_strategy: Default::default(),
+                manual_items: Default::default(),
+                aisle_order: Default::default(),
+                owned: Default::default(),
             });
 
         let slots_recipe_ids = self
@@ -43,17 +49,30 @@ impl<E: Executor> super::Module<E> {
             .await?;
 
         let recipe_ingredients = self
-            .filter_recipe_ingredients_by_ids(slots_recipe_ids.clone())
+            .filter_recipe_ingredients_by_ids(&request_by, slots_recipe_ids.clone())
             .await?;
 
-        let ingredients = merge_ingredients(recipe_ingredients, input.household_size);
+        let guests = self
+            .filter_guests(&request_by, input.date, input.days)
+            .await?;
+        let servings = self
+            .filter_servings(&request_by, input.date, input.days)
+            .await?;
+        let ingredients = merge_ingredients(
+            recipe_ingredients,
+            input.household_size,
+            &guests,
+            &servings,
+            shopping.rounding_strategy,
+        );
 
         shopping
             .write()?
-            .event(&Generated {
+            .event(&GeneratedV2 {
                 ingredients,
                 from_date: input.date,
                 days: input.days,
+                household_size: input.household_size,
             })
             .event(&RecipeSetGenerated {
                 recipe_ids: slots_recipe_ids,
@@ -72,7 +91,7 @@ impl<E: Executor> super::Module<E> {
         limit: u64,
     ) -> anyhow::Result<Vec<String>> {
         let user_id = user_id.into();
-        let statement = sea_query::Query::select()
+        let mut statement = sea_query::Query::select()
             .column(ShoppingSlot::RecipeIds)
             .from(ShoppingSlot::Table)
             .and_where(Expr::col(ShoppingSlot::UserId).eq(&user_id))
@@ -80,6 +99,12 @@ impl<E: Executor> super::Module<E> {
             .limit(limit)
             .to_owned();
 
+        // Days missing from the plan, such as a skipped week, must not pull
+        // in the days after them.
+        if let Some(until) = super::valid_until(date, limit as u8) {
+            statement.and_where(Expr::col(ShoppingSlot::Date).lte(until));
+        }
+
         let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
 
         Ok(
@@ -0,0 +1,82 @@
+use evento::{Executor, ProjectionAggregate};
+use imkitchen_types::shopping::{ChecklistReset, ManualItemAdded};
+
+const MAX_MANUAL_ITEM_LEN: usize = 100;
+
+pub struct AddManualItemInput {
+    pub name: String,
+}
+
+/// Key a manual item is checked under. Prefixed so it never collides with an
+/// `Ingredient::key`.
+pub fn manual_item_key(name: &str) -> String {
+    format!("manual:{name}")
+}
+
+/// Whether `key` was built by [`manual_item_key`].
+pub fn is_manual_item_key(key: &str) -> bool {
+    key.starts_with("manual:")
+}
+
+impl<E: Executor> super::Module<E> {
+    /// Append a non-recipe item to the current list. Adding an item already
+    /// on the list (ignoring case) does nothing.
+    pub async fn add_manual_item(
+        &self,
+        input: AddManualItemInput,
+        request_by: impl Into<String>,
+    ) -> crate::Result<()> {
+        let request_by = request_by.into();
+        let Some(shopping) = self.load(&request_by).await? else {
+            crate::not_found!("shopping in add_manual_item");
+        };
+
+        let name = input.name.trim().to_owned();
+        if name.is_empty() {
+            crate::user!("item name is required");
+        }
+
+        if name.chars().count() > MAX_MANUAL_ITEM_LEN {
+            crate::user!("item name is too long");
+        }
+
+        if shopping
+            .manual_items
+            .iter()
+            .any(|item| item.to_lowercase() == name.to_lowercase())
+        {
+            return Ok(());
+        }
+
+        shopping
+            .write()?
+            .event(&ManualItemAdded { name })
+            .requested_by(request_by)
+            .commit(&self.executor)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Uncheck every item to start a new run through the same list. Manual
+    /// items stay on the list.
+    pub async fn reset_checklist(&self, request_by: impl Into<String>) -> crate::Result<()> {
+        let request_by = request_by.into();
+        let Some(shopping) = self.load(&request_by).await? else {
+            crate::not_found!("shopping in reset_checklist");
+        };
+
+        if shopping.checked.is_empty() {
+            return Ok(());
+        }
+
+        shopping
+            .write()?
+            .event(&ChecklistReset)
+            .requested_by(request_by)
+            .commit(&self.executor)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -1,42 +1,212 @@
 use evento::Executor;
 use imkitchen_db::shopping_recipe::ShoppingRecipe;
+use imkitchen_db::shopping_recipe_snapshot::ShoppingRecipeSnapshot;
+use imkitchen_db::shopping_slot::ShoppingSlot;
 use imkitchen_types::recipe::Ingredient;
-use sea_query::{Expr, ExprTrait, Query, SqliteQueryBuilder};
+use imkitchen_types::shopping::RoundingStrategy;
+use sea_query::{Expr, ExprTrait, Query, SelectStatement, SqliteQueryBuilder};
 use sea_query_sqlx::SqlxBinder;
 use std::collections::HashMap;
 
+use super::IngredientSource;
+
 impl<E: Executor> super::Module<E> {
-    /// Fetch each recipe's authored household size and ingredient list from the
-    /// `shopping_recipe` projection, for a set of recipe ids.
+    /// Fetch each recipe's authored household size and ingredient list for a
+    /// set of recipe ids. Recipes the user's plan snapshotted come from
+    /// `shopping_recipe_snapshot`, the rest from the `shopping_recipe`
+    /// projection.
     pub(crate) async fn filter_recipe_ingredients_by_ids(
         &self,
+        user_id: &str,
         ids: Vec<String>,
-    ) -> anyhow::Result<Vec<(u16, Vec<Ingredient>)>> {
+    ) -> anyhow::Result<Vec<(String, u16, Vec<Ingredient>)>> {
         if ids.is_empty() {
             return Ok(vec![]);
         }
 
         let statement = Query::select()
+            .column(ShoppingRecipeSnapshot::RecipeId)
+            .column(ShoppingRecipeSnapshot::HouseholdSize)
+            .column(ShoppingRecipeSnapshot::Ingredients)
+            .from(ShoppingRecipeSnapshot::Table)
+            .and_where(Expr::col(ShoppingRecipeSnapshot::UserId).eq(user_id))
+            .and_where(Expr::col(ShoppingRecipeSnapshot::RecipeId).is_in(ids.clone()))
+            .to_owned();
+
+        let mut recipes = self.fetch_recipe_ingredients(statement).await?;
+        let live_ids = ids
+            .into_iter()
+            .filter(|id| !recipes.iter().any(|(snapshot_id, _, _)| snapshot_id == id))
+            .collect::<Vec<_>>();
+
+        if live_ids.is_empty() {
+            return Ok(recipes);
+        }
+
+        let statement = Query::select()
+            .column(ShoppingRecipe::Id)
             .column(ShoppingRecipe::HouseholdSize)
             .column(ShoppingRecipe::Ingredients)
             .from(ShoppingRecipe::Table)
-            .and_where(Expr::col(ShoppingRecipe::Id).is_in(ids))
+            .and_where(Expr::col(ShoppingRecipe::Id).is_in(live_ids))
             .to_owned();
 
+        recipes.extend(self.fetch_recipe_ingredients(statement).await?);
+
+        Ok(recipes)
+    }
+
+    async fn fetch_recipe_ingredients(
+        &self,
+        statement: SelectStatement,
+    ) -> anyhow::Result<Vec<(String, u16, Vec<Ingredient>)>> {
         let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
         Ok(
-            sqlx::query_as_with::<_, (u16, evento::sql_types::Bitcode<Vec<Ingredient>>), _>(
-                sqlx::AssertSqlSafe(sql),
-                values,
-            )
+            sqlx::query_as_with::<
+                _,
+                (String, u16, evento::sql_types::Bitcode<Vec<Ingredient>>),
+                _,
+            >(sqlx::AssertSqlSafe(sql), values)
             .fetch_all(&self.read_db)
             .await?
             .into_iter()
-            .map(|(household_size, ingredients)| (household_size, ingredients.0))
+            .map(|(id, household_size, ingredients)| (id, household_size, ingredients.0))
             .collect(),
         )
     }
 
+    /// Recipe id → number of people, for recipes planned on a day with a
+    /// guest count between `from_date` and the list's last day. A recipe
+    /// served on several such days takes the largest count.
+    pub(crate) async fn filter_guests(
+        &self,
+        user_id: &str,
+        from_date: u64,
+        days: u8,
+    ) -> anyhow::Result<HashMap<String, u16>> {
+        let Some(until) = super::valid_until(from_date, days) else {
+            return Ok(HashMap::new());
+        };
+
+        let statement = Query::select()
+            .column(ShoppingSlot::RecipeIds)
+            .column(ShoppingSlot::Guests)
+            .from(ShoppingSlot::Table)
+            .and_where(Expr::col(ShoppingSlot::UserId).eq(user_id))
+            .and_where(Expr::col(ShoppingSlot::Date).gte(from_date))
+            .and_where(Expr::col(ShoppingSlot::Date).lte(until))
+            .and_where(Expr::col(ShoppingSlot::Guests).is_not_null())
+            .to_owned();
+
+        let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+        let rows = sqlx::query_as_with::<_, (evento::sql_types::Bitcode<Vec<String>>, u16), _>(
+            sqlx::AssertSqlSafe(sql),
+            values,
+        )
+        .fetch_all(&self.read_db)
+        .await?;
+
+        let mut guests = HashMap::new();
+        for (ids, count) in rows {
+            for id in ids.0 {
+                let entry = guests.entry(id).or_insert(count);
+                *entry = Ord::max(*entry, count);
+            }
+        }
+
+        Ok(guests)
+    }
+
+    /// Recipe id → servings multiplier, for recipes with a slot cooked for
+    /// more or fewer people than usual between `from_date` and the list's
+    /// last day.
+    ///
+    /// A recipe is bought once however many of its days the list covers, so
+    /// each of its slots adds or takes away its own share: 2× on Saturday
+    /// alone is 2×, 2× on both Tuesday and Saturday is 3×. It never goes
+    /// below the smallest multiplier of its slots.
+    pub(crate) async fn filter_servings(
+        &self,
+        user_id: &str,
+        from_date: u64,
+        days: u8,
+    ) -> anyhow::Result<HashMap<String, f32>> {
+        let Some(until) = super::valid_until(from_date, days) else {
+            return Ok(HashMap::new());
+        };
+
+        let statement = Query::select()
+            .column(ShoppingSlot::Date)
+            .column(ShoppingSlot::Servings)
+            .from(ShoppingSlot::Table)
+            .and_where(Expr::col(ShoppingSlot::UserId).eq(user_id))
+            .and_where(Expr::col(ShoppingSlot::Date).gte(from_date))
+            .and_where(Expr::col(ShoppingSlot::Date).lte(until))
+            .and_where(Expr::col(ShoppingSlot::Servings).is_not_null())
+            .to_owned();
+
+        let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+        let rows =
+            sqlx::query_as_with::<_, (u64, evento::sql_types::Bitcode<Vec<(String, f32)>>), _>(
+                sqlx::AssertSqlSafe(sql),
+                values,
+            )
+            .fetch_all(&self.read_db)
+            .await?;
+
+        // Date and recipe → multiplier, one per slot.
+        let slots = rows
+            .into_iter()
+            .flat_map(|(date, servings)| {
+                servings
+                    .0
+                    .into_iter()
+                    .map(move |(id, multiplier)| ((date, id), multiplier))
+            })
+            .collect::<HashMap<_, _>>();
+
+        let mut servings: HashMap<String, (f32, f32)> = HashMap::new();
+        for ((_, id), multiplier) in slots {
+            let (total, smallest) = servings.entry(id).or_insert((1.0, multiplier));
+            *total += multiplier - 1.0;
+            *smallest = f32::min(*smallest, multiplier);
+        }
+
+        Ok(servings
+            .into_iter()
+            .map(|(id, (total, smallest))| (id, f32::max(total, smallest)))
+            .collect())
+    }
+
+    /// Recipe id → servings multiplier of one day's slots, only for those
+    /// cooked for more or fewer people than usual.
+    pub async fn day_servings(
+        &self,
+        user_id: &str,
+        date: u64,
+    ) -> anyhow::Result<HashMap<String, f32>> {
+        let statement = Query::select()
+            .column(ShoppingSlot::Servings)
+            .from(ShoppingSlot::Table)
+            .and_where(Expr::col(ShoppingSlot::UserId).eq(user_id))
+            .and_where(Expr::col(ShoppingSlot::Date).eq(date))
+            .and_where(Expr::col(ShoppingSlot::Servings).is_not_null())
+            .to_owned();
+
+        let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+        let servings = sqlx::query_scalar_with::<
+            _,
+            evento::sql_types::Bitcode<Vec<(String, f32)>>,
+            _,
+        >(sqlx::AssertSqlSafe(sql), values)
+        .fetch_optional(&self.read_db)
+        .await?;
+
+        Ok(servings
+            .map(|servings| servings.0.into_iter().collect())
+            .unwrap_or_default())
+    }
+
     /// Whether a `shopping_recipe` row exists for the given recipe id. Ownership
     /// is intentionally NOT checked here: a user may add a shared recipe they do
     /// not own (viewability is enforced in the web layer, like `save()`).
@@ -60,22 +230,64 @@ impl<E: Executor> super::Module<E> {
 
 /// Merge and scale a set of recipes' ingredients into a single shopping list.
 ///
-/// Duplicate ingredients (same `key()`) are summed. Each recipe's quantities are
+/// Quantities are first converted to their unit's base (1 tbsp → 15 ml), then
+/// duplicate ingredients (same `key()`) are summed. Each recipe's quantities are
 /// scaled from its authored household size to the user's household size via
-/// [`scale_quantity`].
+/// [`scale_quantity`], or to its day's guest count when it has one. A slot's
+/// servings multiplier then applies to that recipe alone.
 pub(crate) fn merge_ingredients(
-    recipe_ingredients: Vec<(u16, Vec<Ingredient>)>,
+    recipe_ingredients: Vec<(String, u16, Vec<Ingredient>)>,
     user_household_size: u16,
+    guests: &HashMap<String, u16>,
+    servings: &HashMap<String, f32>,
+    rounding: RoundingStrategy,
 ) -> Vec<Ingredient> {
+    merge_ingredients_with_sources(
+        recipe_ingredients,
+        user_household_size,
+        guests,
+        servings,
+        rounding,
+    )
+    .0
+}
+
+/// [`merge_ingredients`], along with what each recipe adds to every merged
+/// ingredient, keyed by ingredient key. A merged quantity is always the sum
+/// of its sources.
+pub(crate) fn merge_ingredients_with_sources(
+    recipe_ingredients: Vec<(String, u16, Vec<Ingredient>)>,
+    user_household_size: u16,
+    guests: &HashMap<String, u16>,
+    servings: &HashMap<String, f32>,
+    rounding: RoundingStrategy,
+) -> (Vec<Ingredient>, HashMap<String, Vec<IngredientSource>>) {
     let mut ingredients: HashMap<String, Ingredient> = HashMap::new();
-    for (recipe_household_size, list) in recipe_ingredients {
+    let mut sources: HashMap<String, Vec<IngredientSource>> = HashMap::new();
+    for (id, recipe_household_size, list) in recipe_ingredients {
+        let serving_size = guests.get(&id).copied().unwrap_or(user_household_size);
+        let multiplier = servings.get(&id).copied().unwrap_or(1.0);
+
         for ingredient in list {
+            let ingredient = ingredient.to_base_unit();
             let scaled = scale_quantity(
-                ingredient.quantity,
+                multiply_quantity(ingredient.quantity, multiplier),
                 recipe_household_size,
-                user_household_size,
+                serving_size,
+                rounding,
             );
-            let entry = ingredients.entry(ingredient.key()).or_insert(Ingredient {
+            let key = ingredient.key();
+
+            let recipe_sources = sources.entry(key.to_owned()).or_default();
+            match recipe_sources.iter_mut().find(|s| s.recipe_id == id) {
+                Some(source) => source.quantity += scaled,
+                None => recipe_sources.push(IngredientSource {
+                    recipe_id: id.to_owned(),
+                    quantity: scaled,
+                }),
+            }
+
+            let entry = ingredients.entry(key).or_insert(Ingredient {
                 name: ingredient.name,
                 quantity: 0,
                 unit: ingredient.unit,
@@ -86,7 +298,35 @@ pub(crate) fn merge_ingredients(
         }
     }
 
-    ingredients.into_values().collect()
+    (ingredients.into_values().collect(), sources)
+}
+
+/// Names of merged ingredients left on several lines because their units
+/// don't convert into each other, e.g. flour in grams and in milliliters.
+pub(crate) fn mixed_units(ingredients: &[Ingredient]) -> Vec<String> {
+    let mut units: HashMap<&str, usize> = HashMap::new();
+    for ingredient in ingredients {
+        *units.entry(ingredient.name.as_str()).or_default() += 1;
+    }
+
+    let mut names = units
+        .into_iter()
+        .filter(|(_, count)| *count > 1)
+        .map(|(name, _)| name.to_owned())
+        .collect::<Vec<_>>();
+    names.sort();
+
+    names
+}
+
+/// Quantity for `multiplier` times the usual amount, in the base unit. Never
+/// brings a non-zero quantity down to nothing.
+fn multiply_quantity(quantity: u32, multiplier: f32) -> u32 {
+    if multiplier == 1.0 || quantity == 0 {
+        return quantity;
+    }
+
+    Ord::max((quantity as f64 * multiplier as f64).round() as u32, 1)
 }
 
 /// Scale one recipe's ingredient quantity to the user's household size.
@@ -96,51 +336,188 @@ pub(crate) fn merge_ingredients(
 /// for (e.g. a whole chicken serves 4 — you can't halve it for 2). So the
 /// serving target is `max(recipe_household_size, user_household_size)` — we scale
 /// up when the household is larger, but never down below the recipe's own size.
+/// Quantities that scale to a fraction are rounded per `rounding`.
 pub(crate) fn scale_quantity(
     quantity: u32,
     recipe_household_size: u16,
     user_household_size: u16,
+    rounding: RoundingStrategy,
 ) -> u32 {
     let recipe_household_size = Ord::max(recipe_household_size, 1);
     let serving_target = Ord::max(recipe_household_size, user_household_size);
-    (quantity as f64 * serving_target as f64 / recipe_household_size as f64).ceil() as u32
+    let scaled = quantity as f64 * serving_target as f64 / recipe_household_size as f64;
+
+    match rounding {
+        RoundingStrategy::Nearest => scaled.round() as u32,
+        RoundingStrategy::Up => scaled.ceil() as u32,
+        RoundingStrategy::PracticalSteps => round_to_practical_step(scaled),
+    }
+}
+
+/// Closest multiple of the step a quantity of that size is usually measured
+/// in: 333 g → 325 g, 1 240 ml → 1 200 ml. Never rounds a non-zero quantity
+/// down to nothing.
+fn round_to_practical_step(quantity: f64) -> u32 {
+    let step = match quantity {
+        q if q < 20.0 => 1.0,
+        q if q < 100.0 => 5.0,
+        q if q < 500.0 => 25.0,
+        q if q < 1000.0 => 50.0,
+        _ => 100.0,
+    };
+
+    let rounded = (quantity / step).round() * step;
+    if rounded == 0.0 && quantity > 0.0 {
+        return step as u32;
+    }
+
+    rounded as u32
+}
+
+/// Round a merged quantity up to whole packages, e.g. 3 eggs sold by 6 → 6,
+/// 1100 g of flour sold by the kilo → 2000 g.
+pub(crate) fn round_to_package(quantity: u32, package_size: u32) -> u32 {
+    if package_size == 0 {
+        return quantity;
+    }
+
+    quantity.div_ceil(package_size) * package_size
 }
 
 #[cfg(test)]
 mod tests {
-    use super::scale_quantity;
+    use super::{merge_ingredients, mixed_units, round_to_package, scale_quantity};
+    use imkitchen_types::recipe::{Ingredient, IngredientUnit};
+    use imkitchen_types::shopping::RoundingStrategy::{self, Nearest, PracticalSteps, Up};
+    use std::collections::HashMap;
+
+    fn ingredient(name: &str, quantity: u32, unit: IngredientUnit) -> Ingredient {
+        Ingredient {
+            name: name.to_owned(),
+            quantity,
+            unit: Some(unit),
+            category: None,
+        }
+    }
 
     #[test]
     fn scales_up_when_household_exceeds_recipe() {
         // Recipe authored for 4, household of 8 → double.
-        assert_eq!(scale_quantity(800, 4, 8), 1600);
+        assert_eq!(scale_quantity(800, 4, 8, Up), 1600);
     }
 
     #[test]
     fn respects_recipe_minimum_when_household_is_smaller() {
         // Household of 2 is below the recipe's authored 4 — do NOT scale down;
         // use the recipe's own quantities (the minimum). This is the #602 case.
-        assert_eq!(scale_quantity(800, 4, 2), 800);
-        assert_eq!(scale_quantity(150, 4, 1), 150);
+        assert_eq!(scale_quantity(800, 4, 2, Up), 800);
+        assert_eq!(scale_quantity(150, 4, 1, Up), 150);
     }
 
     #[test]
     fn keeps_quantity_when_household_matches_recipe() {
-        assert_eq!(scale_quantity(800, 4, 4), 800);
+        assert_eq!(scale_quantity(800, 4, 4, Up), 800);
     }
 
     #[test]
     fn rounds_up_fractional_results() {
         // 150 * 6 / 4 = 225 exactly.
-        assert_eq!(scale_quantity(150, 4, 6), 225);
+        assert_eq!(scale_quantity(150, 4, 6, Up), 225);
         // 100 * 3 / 2 = 150; 10 * 3 / 4 = 7.5 → 8 (ceil, never under-order).
-        assert_eq!(scale_quantity(10, 4, 3), 10); // household 3 < recipe 4 → unchanged
-        assert_eq!(scale_quantity(10, 2, 3), 15); // 10 * 3 / 2
+        assert_eq!(scale_quantity(10, 4, 3, Up), 10); // household 3 < recipe 4 → unchanged
+        assert_eq!(scale_quantity(10, 2, 3, Up), 15); // 10 * 3 / 2
     }
 
     #[test]
     fn guards_against_zero_recipe_size() {
         // A malformed 0-serving recipe must not divide by zero.
-        assert_eq!(scale_quantity(100, 0, 4), 400);
+        assert_eq!(scale_quantity(100, 0, 4, Up), 400);
+    }
+
+    #[test]
+    fn rounds_up_to_whole_packages() {
+        assert_eq!(round_to_package(3, 6), 6);
+        assert_eq!(round_to_package(1100, 1000), 2000);
+        assert_eq!(round_to_package(1000, 1000), 1000);
+        assert_eq!(round_to_package(5, 0), 5);
+    }
+
+    #[test]
+    fn rounding_strategies() {
+        // 500 g for 3, scaled to 4 → 666.67.
+        let scale = |rounding: RoundingStrategy| scale_quantity(500, 3, 4, rounding);
+        assert_eq!(scale(Nearest), 667);
+        assert_eq!(scale(Up), 667);
+        assert_eq!(scale(PracticalSteps), 650);
+
+        // 250 g for 3, scaled to 4 → 333.33.
+        let scale = |rounding: RoundingStrategy| scale_quantity(250, 3, 4, rounding);
+        assert_eq!(scale(Nearest), 333);
+        assert_eq!(scale(Up), 334);
+        assert_eq!(scale(PracticalSteps), 325);
+
+        // 1 egg for 3, scaled to 4 → 1.33.
+        let scale = |rounding: RoundingStrategy| scale_quantity(1, 3, 4, rounding);
+        assert_eq!(scale(Nearest), 1);
+        assert_eq!(scale(Up), 2);
+        assert_eq!(scale(PracticalSteps), 1);
+    }
+
+    #[test]
+    fn merges_compatible_units() {
+        let recipes = vec![
+            (
+                "dressing".to_owned(),
+                4,
+                vec![
+                    ingredient("oil", 2, IngredientUnit::Tbsp),
+                    ingredient("sugar", 1, IngredientUnit::Tsp),
+                ],
+            ),
+            (
+                "stir-fry".to_owned(),
+                4,
+                vec![
+                    ingredient("oil", 1, IngredientUnit::Cup),
+                    ingredient("sugar", 20, IngredientUnit::ML),
+                ],
+            ),
+        ];
+
+        let mut merged = merge_ingredients(recipes, 4, &HashMap::new(), &HashMap::new(), Up);
+        merged.sort_by(|a, b| a.name.cmp(&b.name));
+
+        assert_eq!(
+            merged,
+            vec![
+                ingredient("oil", 270, IngredientUnit::ML),
+                ingredient("sugar", 25, IngredientUnit::ML),
+            ]
+        );
+        assert!(mixed_units(&merged).is_empty());
+    }
+
+    #[test]
+    fn keeps_mass_and_volume_apart() {
+        let recipes = vec![
+            (
+                "bread".to_owned(),
+                4,
+                vec![ingredient("flour", 500, IngredientUnit::G)],
+            ),
+            (
+                "batter".to_owned(),
+                4,
+                vec![
+                    ingredient("flour", 1, IngredientUnit::Cup),
+                    ingredient("eggs", 2, IngredientUnit::Piece),
+                ],
+            ),
+        ];
+
+        let merged = merge_ingredients(recipes, 4, &HashMap::new(), &HashMap::new(), Up);
+
+        assert_eq!(merged.len(), 3);
+        assert_eq!(mixed_units(&merged), vec!["flour".to_owned()]);
     }
 }
@@ -1,20 +1,38 @@
 mod add;
+mod aisle_order;
+mod export;
 mod generate;
+mod manual;
 mod merge;
+mod owned;
+mod package;
 mod remove;
+mod repair;
+mod rounding;
 mod state;
 mod toogle;
+mod weeks;
 
 use bitcode::{Decode, Encode};
 pub use generate::Generate;
-pub use state::ShoppingState;
+pub use manual::{AddManualItemInput, is_manual_item_key, manual_item_key};
+pub use package::PackageSizeInput;
+pub use state::{IngredientSource, ShoppingState, valid_until};
 pub use toogle::*;
+pub use weeks::{CombinedItem, MAX_COMBINED_WEEKS, WeekQuantity};
 
 use evento::{Executor, Projection, ProjectionAggregate, metadata::Event};
+use imkitchen_types::recipe::IngredientCategory;
 use imkitchen_types::shopping::{
-    self, Checked, Generated, RecipeAdded, RecipeRemoved, RecipeSetGenerated, Unchecked,
+    self, AisleOrderChanged, Checked, ChecklistReset, Generated, GeneratedV2, ManualItemAdded,
+    MarkedOwned, PackageSizeChanged, RecipeAdded, RecipeRemoved, RecipeSetGenerated,
+    RoundingStrategy, RoundingStrategyChanged, Unchecked, UnmarkedOwned,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Deref,
+    str::FromStr,
 };
-use std::{collections::HashSet, ops::Deref};
 
 #[derive(Clone)]
 pub struct Module<E: Executor> {
@@ -51,6 +69,37 @@ pub struct Shopping {
     pub from_date: u64,
     pub days: u8,
     pub generated_at: u64,
+    /// Household size the list was generated for; 0 when unknown (lists
+    /// generated before it was recorded).
+    pub household_size: u16,
+    /// Ingredient key → package size the user buys it in, in the ingredient's
+    /// unit. Kept across regenerations.
+    pub package_sizes: HashMap<String, u32>,
+    /// How scaled quantities are rounded; kept across regenerations.
+    pub rounding_strategy: RoundingStrategy,
+    /// Items added by hand, in the order they were added. Cleared when a new
+    /// list is generated.
+    pub manual_items: Vec<String>,
+    /// `IngredientCategory` names in the user's store order, see
+    /// [`Shopping::aisle_order`].
+    pub aisle_order: Vec<String>,
+    /// Ingredient keys the user always has at home. Kept across
+    /// regenerations, unlike `checked`.
+    pub owned: HashSet<String>,
+}
+
+impl Shopping {
+    /// Full aisle order: the user's own first, skipping names that are no
+    /// longer a category, then the rest in store walk order.
+    pub fn aisle_order(&self) -> Vec<IngredientCategory> {
+        let custom = self
+            .aisle_order
+            .iter()
+            .filter_map(|name| IngredientCategory::from_str(name).ok())
+            .collect::<Vec<_>>();
+
+        IngredientCategory::aisle_order(&custom)
+    }
 }
 
 impl ProjectionAggregate for Shopping {
@@ -64,13 +113,24 @@ pub fn create_projection<E: Executor>() -> Projection<E, Shopping> {
         // Bumped from the implicit 0 → 1 when the `recipes` field was added to
         // `Shopping`: invalidates old snapshots so they rebuild from events
         // rather than failing to bitcode-decode into the new struct shape.
-        .revision(1)
+        // Bumped again to 2 for `household_size`, to 3 for `package_sizes`,
+        // to 4 for `rounding_strategy`, to 5 for `manual_items`, to 6 for
+        // `aisle_order`, and to 7 for `owned`.
+        .revision(7)
         .handler(handle_checked())
         .handler(handle_generated())
+        .handler(handle_generated_v2())
         .handler(handle_unchecked())
         .handler(handle_recipe_set_generated())
         .handler(handle_recipe_added())
         .handler(handle_recipe_removed())
+        .handler(handle_package_size_changed())
+        .handler(handle_rounding_strategy_changed())
+        .handler(handle_manual_item_added())
+        .handler(handle_checklist_reset())
+        .handler(handle_aisle_order_changed())
+        .handler(handle_marked_owned())
+        .handler(handle_unmarked_owned())
         .strict()
 }
 
@@ -82,6 +142,22 @@ async fn handle_generated(event: Event<Generated>, data: &mut Shopping) -> anyho
     data.from_date = event.data.from_date;
     data.days = event.data.days;
     data.generated_at = event.timestamp;
+    data.household_size = 0;
+    data.manual_items = vec![];
+
+    Ok(())
+}
+
+#[evento::handler]
+async fn handle_generated_v2(event: Event<GeneratedV2>, data: &mut Shopping) -> anyhow::Result<()> {
+    data.user_id = event.metadata.requested_by()?;
+    data.ingredients = event.data.ingredients.iter().map(|i| i.key()).collect();
+    data.checked = HashSet::new();
+    data.from_date = event.data.from_date;
+    data.days = event.data.days;
+    data.generated_at = event.timestamp;
+    data.household_size = event.data.household_size;
+    data.manual_items = vec![];
 
     Ok(())
 }
@@ -129,3 +205,76 @@ async fn handle_recipe_removed(
 
     Ok(())
 }
+
+#[evento::handler]
+async fn handle_package_size_changed(
+    event: Event<PackageSizeChanged>,
+    data: &mut Shopping,
+) -> anyhow::Result<()> {
+    match event.data.package_size {
+        Some(size) => data.package_sizes.insert(event.data.ingredient, size),
+        None => data.package_sizes.remove(&event.data.ingredient),
+    };
+
+    Ok(())
+}
+
+#[evento::handler]
+async fn handle_rounding_strategy_changed(
+    event: Event<RoundingStrategyChanged>,
+    data: &mut Shopping,
+) -> anyhow::Result<()> {
+    data.user_id = event.metadata.requested_by()?;
+    data.rounding_strategy = event.data.rounding_strategy;
+
+    Ok(())
+}
+
+#[evento::handler]
+async fn handle_manual_item_added(
+    event: Event<ManualItemAdded>,
+    data: &mut Shopping,
+) -> anyhow::Result<()> {
+    data.user_id = event.metadata.requested_by()?;
+    data.manual_items.push(event.data.name);
+
+    Ok(())
+}
+
+#[evento::handler]
+async fn handle_checklist_reset(
+    _event: Event<ChecklistReset>,
+    data: &mut Shopping,
+) -> anyhow::Result<()> {
+    data.checked = HashSet::new();
+
+    Ok(())
+}
+
+#[evento::handler]
+async fn handle_aisle_order_changed(
+    event: Event<AisleOrderChanged>,
+    data: &mut Shopping,
+) -> anyhow::Result<()> {
+    data.user_id = event.metadata.requested_by()?;
+    data.aisle_order = event.data.aisle_order;
+
+    Ok(())
+}
+
+#[evento::handler]
+async fn handle_marked_owned(event: Event<MarkedOwned>, data: &mut Shopping) -> anyhow::Result<()> {
+    data.owned.insert(event.data.ingredient);
+
+    Ok(())
+}
+
+#[evento::handler]
+async fn handle_unmarked_owned(
+    event: Event<UnmarkedOwned>,
+    data: &mut Shopping,
+) -> anyhow::Result<()> {
+    data.owned.remove(&event.data.ingredient);
+
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use evento::{Executor, ProjectionAggregate};
+use imkitchen_types::shopping::{MarkedOwned, UnmarkedOwned};
+
+use super::ToggleInput;
+
+impl<E: Executor> super::Module<E> {
+    /// Flip the "always have it" flag of an ingredient. Owned ingredients stay
+    /// on the list but are left out of what remains to buy, and the flag
+    /// survives regenerations.
+    pub async fn toggle_owned(
+        &self,
+        input: ToggleInput,
+        request_by: impl Into<String>,
+    ) -> crate::Result<()> {
+        let request_by = request_by.into();
+        let Some(shopping) = self.load(&request_by).await? else {
+            crate::not_found!("shopping in toggle_owned");
+        };
+
+        if shopping.owned.contains(&input.name) {
+            shopping
+                .write()?
+                .event(&UnmarkedOwned {
+                    ingredient: input.name,
+                })
+                .requested_by(request_by)
+                .commit(&self.executor)
+                .await?;
+
+            return Ok(());
+        }
+
+        if !shopping.ingredients.contains(&input.name) {
+            crate::user!("ingredient not found");
+        }
+
+        shopping
+            .write()?
+            .event(&MarkedOwned {
+                ingredient: input.name,
+            })
+            .requested_by(request_by)
+            .commit(&self.executor)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,44 @@
+use evento::{Executor, ProjectionAggregate};
+use imkitchen_types::shopping::PackageSizeChanged;
+
+pub struct PackageSizeInput {
+    /// Ingredient key, as returned by `Ingredient::key`.
+    pub name: String,
+    /// Package size in the ingredient's unit; `None` clears it.
+    pub package_size: Option<u32>,
+}
+
+impl<E: Executor> super::Module<E> {
+    /// Record the package size an ingredient is sold in, so the list can show
+    /// how much to buy in whole packages.
+    pub async fn set_package_size(
+        &self,
+        input: PackageSizeInput,
+        request_by: impl Into<String>,
+    ) -> crate::Result<()> {
+        let request_by = request_by.into();
+        let Some(shopping) = self.load(&request_by).await? else {
+            crate::not_found!("shopping in set_package_size");
+        };
+
+        if input.package_size == Some(0) {
+            crate::user!("package size must be greater than 0");
+        }
+
+        if shopping.package_sizes.get(&input.name) == input.package_size.as_ref() {
+            return Ok(());
+        }
+
+        shopping
+            .write()?
+            .event(&PackageSizeChanged {
+                ingredient: input.name,
+                package_size: input.package_size,
+            })
+            .requested_by(request_by)
+            .commit(&self.executor)
+            .await?;
+
+        Ok(())
+    }
+}
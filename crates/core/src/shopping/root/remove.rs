@@ -29,9 +29,21 @@ impl<E: Executor> super::Module<E> {
             .collect();
 
         let recipe_ingredients = self
-            .filter_recipe_ingredients_by_ids(recipe_ids.clone())
+            .filter_recipe_ingredients_by_ids(&request_by, recipe_ids.clone())
             .await?;
-        let ingredients = merge_ingredients(recipe_ingredients, household_size);
+        let guests = self
+            .filter_guests(&request_by, shopping.from_date, shopping.days)
+            .await?;
+        let servings = self
+            .filter_servings(&request_by, shopping.from_date, shopping.days)
+            .await?;
+        let ingredients = merge_ingredients(
+            recipe_ingredients,
+            household_size,
+            &guests,
+            &servings,
+            shopping.rounding_strategy,
+        );
 
         shopping
             .write()?
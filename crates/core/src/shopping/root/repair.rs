@@ -0,0 +1,94 @@
+use evento::{Executor, ProjectionAggregate};
+use imkitchen_types::shopping::{Checked, GeneratedV2, ManualItemAdded, RecipeSetGenerated};
+
+use super::merge::merge_ingredients;
+
+impl<E: Executor> super::Module<E> {
+    /// Rebuilds the list from the meal plan slots it was generated for, for
+    /// lists that drifted away from their plan. Checked items whose
+    /// ingredient is still on the rebuilt list stay checked, and manual items
+    /// are kept.
+    pub async fn repair(
+        &self,
+        household_size: u16,
+        request_by: impl Into<String>,
+    ) -> crate::Result<()> {
+        let request_by = request_by.into();
+        let Some(shopping) = self.load(&request_by).await? else {
+            crate::not_found!("shopping in repair");
+        };
+
+        if shopping.from_date == 0 || shopping.days == 0 {
+            crate::user!("This shopping list wasn't generated from a meal plan");
+        }
+
+        let household_size = if shopping.household_size > 0 {
+            shopping.household_size
+        } else {
+            household_size
+        };
+
+        let slots_recipe_ids = self
+            .filter_slot_recipe_ids(shopping.from_date, &request_by, shopping.days.into())
+            .await?;
+
+        let recipe_ingredients = self
+            .filter_recipe_ingredients_by_ids(&request_by, slots_recipe_ids.clone())
+            .await?;
+
+        let guests = self
+            .filter_guests(&request_by, shopping.from_date, shopping.days)
+            .await?;
+        let servings = self
+            .filter_servings(&request_by, shopping.from_date, shopping.days)
+            .await?;
+        let ingredients = merge_ingredients(
+            recipe_ingredients,
+            household_size,
+            &guests,
+            &servings,
+            shopping.rounding_strategy,
+        );
+
+        let mut checked = ingredients
+            .iter()
+            .map(|i| i.key())
+            .filter(|key| shopping.checked.contains(key))
+            .collect::<Vec<_>>();
+
+        checked.extend(
+            shopping
+                .manual_items
+                .iter()
+                .map(|name| super::manual_item_key(name))
+                .filter(|key| shopping.checked.contains(key)),
+        );
+
+        let mut builder = shopping.write()?;
+        builder
+            .event(&GeneratedV2 {
+                ingredients,
+                from_date: shopping.from_date,
+                days: shopping.days,
+                household_size,
+            })
+            .event(&RecipeSetGenerated {
+                recipe_ids: slots_recipe_ids,
+            })
+            .requested_by(&request_by);
+
+        for name in shopping.manual_items.iter() {
+            builder.event(&ManualItemAdded {
+                name: name.to_owned(),
+            });
+        }
+
+        for ingredient in checked {
+            builder.event(&Checked { ingredient });
+        }
+
+        builder.commit(&self.executor).await?;
+
+        Ok(())
+    }
+}
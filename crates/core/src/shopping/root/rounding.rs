@@ -0,0 +1,47 @@
+use evento::{Executor, ProjectionAggregate};
+use imkitchen_types::shopping::{RoundingStrategy, RoundingStrategyChanged};
+
+impl<E: Executor> super::Module<E> {
+    /// Choose how quantities scaled to the household size are rounded, from
+    /// exact amounts to convenient ones. Applies from the next computation of
+    /// the list.
+    pub async fn set_rounding_strategy(
+        &self,
+        rounding_strategy: RoundingStrategy,
+        request_by: impl Into<String>,
+    ) -> crate::Result<()> {
+        let request_by = request_by.into();
+        let shopping = self
+            .load(&request_by)
+            .await?
+            .unwrap_or_else(|| super::Shopping {
+                user_id: request_by.to_owned(),
+                checked: Default::default(),
+                ingredients: Default::default(),
+                recipes: Default::default(),
+                cursor: Default::default(),
+                from_date: 0,
+                days: 0,
+                generated_at: 0,
+                household_size: 0,
+                package_sizes: Default::default(),
+                rounding_strategy: Default::default(),
+                manual_items: Default::default(),
+                aisle_order: Default::default(),
+                owned: Default::default(),
+            });
+
+        if shopping.rounding_strategy == rounding_strategy {
+            return Ok(());
+        }
+
+        shopping
+            .write()?
+            .event(&RoundingStrategyChanged { rounding_strategy })
+            .requested_by(request_by)
+            .commit(&self.executor)
+            .await?;
+
+        Ok(())
+    }
+}
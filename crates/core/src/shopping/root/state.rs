@@ -1,8 +1,8 @@
 use evento::Executor;
-use imkitchen_types::recipe::Ingredient;
-use std::collections::HashSet;
+use imkitchen_types::recipe::{Ingredient, IngredientCategory};
+use std::collections::{HashMap, HashSet};
 
-use super::merge::merge_ingredients;
+use super::merge::{merge_ingredients_with_sources, mixed_units, round_to_package};
 
 /// Current shopping-list state, computed straight from the aggregate so it is
 /// immediately consistent after a command (unlike the `shopping_list` read
@@ -14,6 +14,72 @@ pub struct ShoppingState {
     pub checked: HashSet<String>,
     pub from_date: u64,
     pub days: u8,
+    /// Unix timestamp of the last generation; 0 when the list was never
+    /// generated.
+    pub generated_at: u64,
+    /// Household size the list was generated for, when it no longer matches
+    /// the one passed to [`super::Module::state`]. Lets the page warn that the
+    /// list may be outdated.
+    pub outdated_household_size: Option<u16>,
+    /// Ingredient key → package size it is bought in.
+    pub package_sizes: HashMap<String, u32>,
+    /// Items added by hand; checked under [`super::manual_item_key`].
+    pub manual_items: Vec<String>,
+    /// Ingredients listed more than once because their units can't be added
+    /// up, e.g. grams and milliliters.
+    pub mixed_units: Vec<String>,
+    /// Every category in the order the user's aisles are listed.
+    pub aisle_order: Vec<IngredientCategory>,
+    /// Ingredient keys the user always has; still listed, but not to buy.
+    pub owned: HashSet<String>,
+    /// Ingredient key → the recipes it is bought for, e.g. onions for the
+    /// soup and the curry.
+    pub sources: HashMap<String, Vec<IngredientSource>>,
+}
+
+/// What one recipe adds to a merged ingredient, in the ingredient's unit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IngredientSource {
+    pub recipe_id: String,
+    pub quantity: u32,
+}
+
+/// Last day (YYYYMMDD) a list generated for `days` days starting at
+/// `from_date` is meant to be shopped for.
+pub fn valid_until(from_date: u64, days: u8) -> Option<u64> {
+    if from_date == 0 || days == 0 {
+        return None;
+    }
+
+    let month = time::Month::try_from(((from_date % 10000) / 100) as u8).ok()?;
+    let date =
+        time::Date::from_calendar_date((from_date / 10000) as i32, month, (from_date % 100) as u8)
+            .ok()?;
+    let last = date.checked_add(time::Duration::days(days as i64 - 1))?;
+
+    Some(last.year() as u64 * 10000 + last.month() as u64 * 100 + last.day() as u64)
+}
+
+impl ShoppingState {
+    /// Last day (YYYYMMDD) the list covers.
+    pub fn valid_until(&self) -> Option<u64> {
+        valid_until(self.from_date, self.days)
+    }
+
+    /// Whether every day the list was generated for is before `today`
+    /// (YYYYMMDD), so it shouldn't be shopped from anymore.
+    pub fn is_expired(&self, today: u64) -> bool {
+        self.valid_until().is_some_and(|last| last < today)
+    }
+
+    /// Quantity to buy for a merged ingredient, when it is sold in packages
+    /// and the needed amount doesn't fill whole ones.
+    pub fn to_buy(&self, ingredient: &Ingredient) -> Option<u32> {
+        let package_size = self.package_sizes.get(&ingredient.key())?;
+
+        Some(round_to_package(ingredient.quantity, *package_size))
+            .filter(|quantity| *quantity != ingredient.quantity)
+    }
 }
 
 impl<E: Executor> super::Module<E> {
@@ -24,20 +90,63 @@ impl<E: Executor> super::Module<E> {
         user_id: impl Into<String>,
         household_size: u16,
     ) -> anyhow::Result<ShoppingState> {
-        let (recipe_ids, checked, from_date, days) = match self.load(user_id).await? {
+        let user_id = user_id.into();
+        let shopping = self.load(&user_id).await?;
+        let aisle_order = shopping
+            .as_ref()
+            .map(|s| s.aisle_order())
+            .unwrap_or_else(|| IngredientCategory::aisle_order(&[]));
+        let (
+            recipe_ids,
+            checked,
+            from_date,
+            days,
+            generated_at,
+            planned_household_size,
+            package_sizes,
+            rounding_strategy,
+            manual_items,
+            owned,
+        ) = match shopping {
             Some(s) => (
                 s.recipes.into_iter().collect::<Vec<_>>(),
                 s.checked,
                 s.from_date,
                 s.days,
+                s.generated_at,
+                s.household_size,
+                s.package_sizes,
+                s.rounding_strategy,
+                s.manual_items,
+                s.owned,
+            ),
+            None => (
+                vec![],
+                HashSet::new(),
+                0,
+                0,
+                0,
+                0,
+                HashMap::new(),
+                Default::default(),
+                vec![],
+                HashSet::new(),
             ),
-            None => (vec![], HashSet::new(), 0, 0),
         };
 
         let recipe_ingredients = self
-            .filter_recipe_ingredients_by_ids(recipe_ids.clone())
+            .filter_recipe_ingredients_by_ids(&user_id, recipe_ids.clone())
             .await?;
-        let ingredients = merge_ingredients(recipe_ingredients, household_size);
+        let guests = self.filter_guests(&user_id, from_date, days).await?;
+        let servings = self.filter_servings(&user_id, from_date, days).await?;
+        let (ingredients, sources) = merge_ingredients_with_sources(
+            recipe_ingredients,
+            household_size,
+            &guests,
+            &servings,
+            rounding_strategy,
+        );
+        let mixed_units = mixed_units(&ingredients);
 
         Ok(ShoppingState {
             recipe_ids,
@@ -45,6 +154,15 @@ impl<E: Executor> super::Module<E> {
             checked,
             from_date,
             days,
+            generated_at,
+            outdated_household_size: Some(planned_household_size)
+                .filter(|size| *size > 0 && *size != household_size),
+            package_sizes,
+            manual_items,
+            mixed_units,
+            aisle_order,
+            owned,
+            sources,
         })
     }
 }
@@ -16,7 +16,12 @@ impl<E: Executor> super::Module<E> {
             crate::not_found!("shopping in toogle");
         };
 
-        if !shopping.ingredients.contains(&input.name) {
+        let is_manual_item = shopping
+            .manual_items
+            .iter()
+            .any(|item| super::manual_item_key(item) == input.name);
+
+        if !shopping.ingredients.contains(&input.name) && !is_manual_item {
             crate::user!("ingredient not found");
         }
 
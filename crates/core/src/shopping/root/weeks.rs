@@ -0,0 +1,137 @@
+use evento::Executor;
+use imkitchen_db::shopping_slot::ShoppingSlot;
+use imkitchen_types::recipe::Ingredient;
+use sea_query::{Expr, ExprTrait, Query, SqliteQueryBuilder};
+use sea_query_sqlx::SqlxBinder;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+use super::merge::merge_ingredients;
+use super::valid_until;
+
+/// Most weeks [`super::Module::combined_weeks`] merges at once.
+pub const MAX_COMBINED_WEEKS: u8 = 4;
+
+/// How much of a combined item one week needs.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WeekQuantity {
+    /// First day (YYYYMMDD) of the week.
+    pub from_date: u64,
+    pub quantity: u32,
+}
+
+/// An ingredient of a multi-week list with its total quantity, and the
+/// weeks that total is made of, earliest first.
+#[derive(Debug, Clone, Serialize)]
+pub struct CombinedItem {
+    pub ingredient: Ingredient,
+    pub weeks: Vec<WeekQuantity>,
+}
+
+impl<E: Executor> super::Module<E> {
+    /// One shopping list for `weeks` consecutive planned weeks starting on
+    /// `from_date` (YYYYMMDD). Each week is merged the way a regular list is,
+    /// then items with the same key are summed across weeks.
+    pub async fn combined_weeks(
+        &self,
+        user_id: impl Into<String>,
+        from_date: u64,
+        weeks: u8,
+        household_size: u16,
+    ) -> crate::Result<Vec<CombinedItem>> {
+        if weeks == 0 || weeks > MAX_COMBINED_WEEKS {
+            crate::user!("Combine between 1 and {MAX_COMBINED_WEEKS} weeks");
+        }
+
+        let user_id = user_id.into();
+        let rounding = self
+            .load(&user_id)
+            .await?
+            .map(|shopping| shopping.rounding_strategy)
+            .unwrap_or_default();
+
+        let mut items: HashMap<String, CombinedItem> = HashMap::new();
+        let mut week_start = from_date;
+        for _ in 0..weeks {
+            let Some(week_end) = valid_until(week_start, 7) else {
+                crate::user!("Invalid date");
+            };
+
+            let recipe_ids = self.week_recipe_ids(&user_id, week_start, week_end).await?;
+            let recipe_ingredients = self
+                .filter_recipe_ingredients_by_ids(&user_id, recipe_ids)
+                .await?;
+            let guests = self.filter_guests(&user_id, week_start, 7).await?;
+            let servings = self.filter_servings(&user_id, week_start, 7).await?;
+
+            for ingredient in merge_ingredients(
+                recipe_ingredients,
+                household_size,
+                &guests,
+                &servings,
+                rounding,
+            ) {
+                let week = WeekQuantity {
+                    from_date: week_start,
+                    quantity: ingredient.quantity,
+                };
+                let item = items
+                    .entry(ingredient.key())
+                    .or_insert_with(|| CombinedItem {
+                        ingredient: Ingredient {
+                            quantity: 0,
+                            ..ingredient
+                        },
+                        weeks: vec![],
+                    });
+
+                item.ingredient.quantity += week.quantity;
+                item.weeks.push(week);
+            }
+
+            // The day after `week_end`.
+            let Some(next) = valid_until(week_end, 2) else {
+                crate::user!("Invalid date");
+            };
+            week_start = next;
+        }
+
+        let mut items = items.into_values().collect::<Vec<_>>();
+        items.sort_by(|a, b| a.ingredient.key().cmp(&b.ingredient.key()));
+
+        Ok(items)
+    }
+
+    /// Recipes planned between `from_date` and `until` (YYYYMMDD, inclusive).
+    async fn week_recipe_ids(
+        &self,
+        user_id: &str,
+        from_date: u64,
+        until: u64,
+    ) -> anyhow::Result<Vec<String>> {
+        let statement = Query::select()
+            .column(ShoppingSlot::RecipeIds)
+            .from(ShoppingSlot::Table)
+            .and_where(Expr::col(ShoppingSlot::UserId).eq(user_id))
+            .and_where(Expr::col(ShoppingSlot::Date).gte(from_date))
+            .and_where(Expr::col(ShoppingSlot::Date).lte(until))
+            .to_owned();
+
+        let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+
+        let mut ids = sqlx::query_scalar_with::<_, evento::sql_types::Bitcode<Vec<String>>, _>(
+            sqlx::AssertSqlSafe(sql),
+            values,
+        )
+        .fetch_all(&self.read_db)
+        .await?
+        .into_iter()
+        .flat_map(|ids| ids.0)
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+        ids.sort();
+
+        Ok(ids)
+    }
+}
@@ -4,6 +4,7 @@ use evento::{
     subscription::{Context, SubscriptionBuilder},
 };
 use imkitchen_db::shopping_recipe::ShoppingRecipe;
+use imkitchen_db::shopping_recipe_snapshot::ShoppingRecipeSnapshot;
 use imkitchen_db::shopping_slot::ShoppingSlot;
 use imkitchen_types::recipe::Ingredient;
 use sea_query::{Expr, ExprTrait, OnConflict, Query, SqliteQueryBuilder};
@@ -16,6 +17,12 @@ pub fn subscription<E: Executor>() -> SubscriptionBuilder<E> {
         .handler(handle_recipe_imported())
         .handler(handle_recipe_deleted())
         .handler(handle_mealplan_days_generated())
+        .handler(handle_mealplan_meal_replaced())
+        .handler(handle_mealplan_recipes_snapshotted())
+        .handler(handle_mealplan_leftovers_planned())
+        .handler(handle_mealplan_meals_swapped())
+        .handler(handle_mealplan_week_skipped())
+        .handler(handle_mealplan_slot_servings_changed())
         .handler(handle_recipe_ingredients_changed())
         .handler(handle_recipe_basic_information_changed())
 }
@@ -37,6 +44,8 @@ async fn handle_mealplan_days_generated<E: Executor>(
             ShoppingSlot::UserId,
             ShoppingSlot::Date,
             ShoppingSlot::RecipeIds,
+            ShoppingSlot::Guests,
+            ShoppingSlot::Servings,
         ])
         .to_owned();
 
@@ -56,17 +65,26 @@ async fn handle_mealplan_days_generated<E: Executor>(
         }
 
         let ids = bitcode::encode(&ids);
+        // Only days planned for a different number of people keep a count;
+        // the rest follow whatever household size the list is built for.
+        let guests = Some(slot.household_size).filter(|size| *size != event.data.household_size);
 
         statement.values_panic([
             event.metadata.requested_by()?.into(),
             slot.date.into(),
             ids.into(),
+            guests.into(),
+            Option::<Vec<u8>>::None.into(),
         ]);
     }
 
     statement.on_conflict(
         OnConflict::columns([ShoppingSlot::UserId, ShoppingSlot::Date])
-            .update_column(ShoppingSlot::RecipeIds)
+            .update_columns([
+                ShoppingSlot::RecipeIds,
+                ShoppingSlot::Guests,
+                ShoppingSlot::Servings,
+            ])
             .to_owned(),
     );
 
@@ -75,9 +93,329 @@ async fn handle_mealplan_days_generated<E: Executor>(
         .execute(&pool)
         .await?;
 
+    // A new plan starts from the recipes as they are now; when it keeps them
+    // as planned, `RecipesSnapshotted` follows and fills this back in.
+    let statement = Query::delete()
+        .from_table(ShoppingRecipeSnapshot::Table)
+        .and_where(Expr::col(ShoppingRecipeSnapshot::UserId).eq(&event.aggregate_id))
+        .to_owned();
+
+    let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+    sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+        .execute(&pool)
+        .await?;
+
+    Ok(())
+}
+
+/// The day buys its new recipe instead of the one it replaces.
+#[evento::subscription]
+async fn handle_mealplan_meal_replaced<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<imkitchen_types::mealplan::MealReplaced>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<sqlx::SqlitePool>();
+
+    let statement = Query::select()
+        .column(ShoppingSlot::RecipeIds)
+        .from(ShoppingSlot::Table)
+        .and_where(Expr::col(ShoppingSlot::UserId).eq(&event.aggregate_id))
+        .and_where(Expr::col(ShoppingSlot::Date).eq(event.data.date))
+        .to_owned();
+
+    let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+    let Some(mut ids) = sqlx::query_scalar_with::<_, evento::sql_types::Bitcode<Vec<String>>, _>(
+        sqlx::AssertSqlSafe(sql),
+        values,
+    )
+    .fetch_optional(&pool)
+    .await?
+    else {
+        return Ok(());
+    };
+
+    ids.0.retain(|id| id != &event.data.previous_recipe_id);
+    ids.0.push(event.data.recipe.id.to_owned());
+
+    let statement = Query::update()
+        .table(ShoppingSlot::Table)
+        .value(ShoppingSlot::RecipeIds, bitcode::encode(&ids.0))
+        .and_where(Expr::col(ShoppingSlot::UserId).eq(&event.aggregate_id))
+        .and_where(Expr::col(ShoppingSlot::Date).eq(event.data.date))
+        .to_owned();
+
+    let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+    sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+        .execute(&pool)
+        .await?;
+
+    Ok(())
+}
+
+#[evento::subscription]
+async fn handle_mealplan_recipes_snapshotted<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<imkitchen_types::mealplan::RecipesSnapshotted>,
+) -> anyhow::Result<()> {
+    if event.data.recipes.is_empty() {
+        return Ok(());
+    }
+
+    let pool = context.extract::<sqlx::SqlitePool>();
+    let mut statement = Query::insert()
+        .into_table(ShoppingRecipeSnapshot::Table)
+        .columns([
+            ShoppingRecipeSnapshot::UserId,
+            ShoppingRecipeSnapshot::RecipeId,
+            ShoppingRecipeSnapshot::Ingredients,
+            ShoppingRecipeSnapshot::HouseholdSize,
+        ])
+        .to_owned();
+
+    for recipe in event.data.recipes.iter() {
+        statement.values_panic([
+            event.aggregate_id.to_owned().into(),
+            recipe.id.to_owned().into(),
+            bitcode::encode(&recipe.ingredients).into(),
+            recipe.household_size.into(),
+        ]);
+    }
+
+    statement.on_conflict(
+        OnConflict::columns([
+            ShoppingRecipeSnapshot::UserId,
+            ShoppingRecipeSnapshot::RecipeId,
+        ])
+        .update_columns([
+            ShoppingRecipeSnapshot::Ingredients,
+            ShoppingRecipeSnapshot::HouseholdSize,
+        ])
+        .to_owned(),
+    );
+
+    let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+    sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+        .execute(&pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Leftover days eat from a batch already bought for, so their main course
+/// is dropped from the day's recipes.
+#[evento::subscription]
+async fn handle_mealplan_leftovers_planned<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<imkitchen_types::mealplan::LeftoversPlanned>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<sqlx::SqlitePool>();
+
+    for leftover in event.data.leftovers {
+        let statement = Query::select()
+            .column(ShoppingSlot::RecipeIds)
+            .from(ShoppingSlot::Table)
+            .and_where(Expr::col(ShoppingSlot::UserId).eq(&event.aggregate_id))
+            .and_where(Expr::col(ShoppingSlot::Date).eq(leftover.date))
+            .to_owned();
+
+        let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+        let Some(mut ids) =
+            sqlx::query_scalar_with::<_, evento::sql_types::Bitcode<Vec<String>>, _>(
+                sqlx::AssertSqlSafe(sql),
+                values,
+            )
+            .fetch_optional(&pool)
+            .await?
+        else {
+            continue;
+        };
+
+        ids.0.retain(|id| id != &leftover.recipe_id);
+
+        let statement = Query::update()
+            .table(ShoppingSlot::Table)
+            .value(ShoppingSlot::RecipeIds, bitcode::encode(&ids.0))
+            .and_where(Expr::col(ShoppingSlot::UserId).eq(&event.aggregate_id))
+            .and_where(Expr::col(ShoppingSlot::Date).eq(leftover.date))
+            .to_owned();
+
+        let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+        sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+            .execute(&pool)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// The week buys the same recipes, only the days change; keeps lists built
+/// for part of the week right.
+#[evento::subscription]
+async fn handle_mealplan_meals_swapped<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<imkitchen_types::mealplan::MealsSwapped>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<sqlx::SqlitePool>();
+    let data = &event.data;
+
+    for (date, from, to) in [
+        (data.date, &data.recipe_id, &data.other_recipe_id),
+        (data.other_date, &data.other_recipe_id, &data.recipe_id),
+    ] {
+        let statement = Query::select()
+            .column(ShoppingSlot::RecipeIds)
+            .from(ShoppingSlot::Table)
+            .and_where(Expr::col(ShoppingSlot::UserId).eq(&event.aggregate_id))
+            .and_where(Expr::col(ShoppingSlot::Date).eq(date))
+            .to_owned();
+
+        let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+        let Some(mut ids) =
+            sqlx::query_scalar_with::<_, evento::sql_types::Bitcode<Vec<String>>, _>(
+                sqlx::AssertSqlSafe(sql),
+                values,
+            )
+            .fetch_optional(&pool)
+            .await?
+        else {
+            continue;
+        };
+
+        let Some(id) = ids.0.iter_mut().find(|id| id.as_str() == from.as_str()) else {
+            continue;
+        };
+        *id = to.to_owned();
+
+        let statement = Query::update()
+            .table(ShoppingSlot::Table)
+            .value(ShoppingSlot::RecipeIds, bitcode::encode(&ids.0))
+            .and_where(Expr::col(ShoppingSlot::UserId).eq(&event.aggregate_id))
+            .and_where(Expr::col(ShoppingSlot::Date).eq(date))
+            .to_owned();
+
+        let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+        sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+            .execute(&pool)
+            .await?;
+
+        rename_slot_servings(&pool, &event.aggregate_id, date, from, to).await?;
+    }
+
+    Ok(())
+}
+
+/// Nothing is bought for a skipped week.
+#[evento::subscription]
+async fn handle_mealplan_week_skipped<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<imkitchen_types::mealplan::WeekSkipped>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<sqlx::SqlitePool>();
+    let Some(dates) = crate::mealplan::week_dates(event.data.week) else {
+        return Ok(());
+    };
+
+    let statement = Query::delete()
+        .from_table(ShoppingSlot::Table)
+        .and_where(Expr::col(ShoppingSlot::UserId).eq(&event.aggregate_id))
+        .and_where(Expr::col(ShoppingSlot::Date).is_in(dates))
+        .to_owned();
+
+    let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+    sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+        .execute(&pool)
+        .await?;
+
+    Ok(())
+}
+
+#[evento::subscription]
+async fn handle_mealplan_slot_servings_changed<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<imkitchen_types::mealplan::SlotServingsChanged>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<sqlx::SqlitePool>();
+    let Some(mut servings) = slot_servings(&pool, &event.aggregate_id, event.data.date).await?
+    else {
+        return Ok(());
+    };
+
+    servings.retain(|(id, _)| id != &event.data.recipe_id);
+    if event.data.multiplier != 1.0 {
+        servings.push((event.data.recipe_id.to_owned(), event.data.multiplier));
+    }
+
+    set_slot_servings(&pool, &event.aggregate_id, event.data.date, servings).await
+}
+
+/// Recipe id → servings multiplier of a day's slots, `None` when nothing is
+/// planned that day.
+async fn slot_servings(
+    pool: &SqlitePool,
+    user_id: &str,
+    date: u64,
+) -> anyhow::Result<Option<Vec<(String, f32)>>> {
+    let statement = Query::select()
+        .column(ShoppingSlot::Servings)
+        .from(ShoppingSlot::Table)
+        .and_where(Expr::col(ShoppingSlot::UserId).eq(user_id))
+        .and_where(Expr::col(ShoppingSlot::Date).eq(date))
+        .to_owned();
+
+    let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+    let servings = sqlx::query_scalar_with::<
+        _,
+        Option<evento::sql_types::Bitcode<Vec<(String, f32)>>>,
+        _,
+    >(sqlx::AssertSqlSafe(sql), values)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(servings.map(|servings| servings.map(|s| s.0).unwrap_or_default()))
+}
+
+async fn set_slot_servings(
+    pool: &SqlitePool,
+    user_id: &str,
+    date: u64,
+    servings: Vec<(String, f32)>,
+) -> anyhow::Result<()> {
+    let servings = (!servings.is_empty()).then(|| bitcode::encode(&servings));
+    let statement = Query::update()
+        .table(ShoppingSlot::Table)
+        .value(ShoppingSlot::Servings, servings)
+        .and_where(Expr::col(ShoppingSlot::UserId).eq(user_id))
+        .and_where(Expr::col(ShoppingSlot::Date).eq(date))
+        .to_owned();
+
+    let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+    sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+        .execute(pool)
+        .await?;
+
     Ok(())
 }
 
+/// A slot keeps its multiplier when it gets another recipe.
+async fn rename_slot_servings(
+    pool: &SqlitePool,
+    user_id: &str,
+    date: u64,
+    from: &str,
+    to: &str,
+) -> anyhow::Result<()> {
+    let Some(mut servings) = slot_servings(pool, user_id, date).await? else {
+        return Ok(());
+    };
+
+    let Some(entry) = servings.iter_mut().find(|(id, _)| id == from) else {
+        return Ok(());
+    };
+    entry.0 = to.to_owned();
+
+    set_slot_servings(pool, user_id, date, servings).await
+}
+
 #[evento::subscription]
 async fn handle_recipe_created<E: Executor>(
     context: &Context<'_, E>,
@@ -1,10 +1,16 @@
+#[path = "contact/acknowledgement.rs"]
+mod acknowledgement;
 #[path = "contact/helpers/mod.rs"]
 mod helpers;
 #[path = "contact/mark_read_and_reply.rs"]
 mod mark_read_and_reply;
+#[path = "contact/rate_limit.rs"]
+mod rate_limit;
 #[path = "contact/reopen.rs"]
 mod reopen;
 #[path = "contact/resolved.rs"]
 mod resolved;
 #[path = "contact/safety_check.rs"]
 mod safety_check;
+#[path = "contact/spam.rs"]
+mod spam;
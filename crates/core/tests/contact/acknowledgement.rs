@@ -0,0 +1,134 @@
+use evento::{AggregateEvent, EventFilter, Executor, cursor::Args};
+use imkitchen_core::contact::SubmitFormInput;
+use imkitchen_types::contact::{AcknowledgementRequested, Subject};
+use temp_dir::TempDir;
+
+async fn acknowledgements(
+    state: &imkitchen_core::State<evento::Sqlite>,
+) -> anyhow::Result<Vec<AcknowledgementRequested>> {
+    let result = state
+        .executor
+        .read(
+            Some(vec![EventFilter::by_event(
+                AcknowledgementRequested::aggregate_type(),
+                AcknowledgementRequested::event_name(),
+            )]),
+            None,
+            Args::forward(500, None),
+        )
+        .await?;
+
+    Ok(result
+        .edges
+        .iter()
+        .map(|e| bitcode::decode(&e.node.data))
+        .collect::<Result<_, _>>()?)
+}
+
+#[tokio::test]
+async fn test_submission_queues_acknowledgement() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::contact::Module::new(state.clone());
+    crate::helpers::create_submit(&cmd, "john.doe").await?;
+
+    let acks = acknowledgements(&state).await?;
+    assert_eq!(acks.len(), 1);
+    assert_eq!(acks[0].email, "john.doe@imkitchen.localhost");
+    assert_eq!(acks[0].summary, "my message");
+    assert_eq!(acks[0].lang, "en");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_repeated_submissions_stop_being_acknowledged() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::contact::Module::new(state.clone());
+    let limit = imkitchen_core::contact::ACKNOWLEDGEMENT_LIMIT as usize;
+    crate::helpers::create_submit_all(&cmd, vec!["spammer"; limit + 2]).await?;
+    crate::helpers::create_submit(&cmd, "john.doe").await?;
+
+    let acks = acknowledgements(&state).await?;
+    let spammer = acks
+        .iter()
+        .filter(|a| a.email == "spammer@imkitchen.localhost")
+        .count();
+    assert_eq!(spammer, limit);
+    assert!(
+        acks.iter()
+            .any(|a| a.email == "john.doe@imkitchen.localhost")
+    );
+
+    Ok(())
+}
+
+/// The limit holds however many other messages came in since, whatever the
+/// address's case.
+#[tokio::test]
+async fn test_acknowledgement_limit_ignores_other_senders() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::contact::Module::new(state.clone());
+    let limit = imkitchen_core::contact::ACKNOWLEDGEMENT_LIMIT as usize;
+    crate::helpers::create_submit_all(&cmd, vec!["spammer"; limit]).await?;
+    crate::helpers::create_submit_all(&cmd, (0..150).map(|i| format!("user{i}"))).await?;
+    crate::helpers::create_submit(&cmd, "Spammer").await?;
+
+    let acks = acknowledgements(&state).await?;
+    let spammer = acks
+        .iter()
+        .filter(|a| a.email.eq_ignore_ascii_case("spammer@imkitchen.localhost"))
+        .count();
+    assert_eq!(spammer, limit);
+    assert_eq!(acks.len(), limit + 150);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_each_submission_acknowledged_once_in_its_language() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::contact::Module::new(state.clone());
+
+    for (name, lang, subject) in [
+        ("john.doe", "en", Subject::GeneralInquiry),
+        ("marie", "fr", Subject::BillingQuestion),
+    ] {
+        cmd.submit_form(SubmitFormInput {
+            to: "contact@imkitchen.localhost".to_owned(),
+            email: format!("{name}@imkitchen.localhost"),
+            name: name.to_owned(),
+            subject,
+            message: "my message".to_owned(),
+            lang: lang.to_owned(),
+            ip: None,
+        })
+        .await?;
+    }
+
+    let acks = acknowledgements(&state).await?;
+    let acks = acks
+        .iter()
+        .map(|a| (a.email.as_str(), a.lang.as_str(), a.subject.to_owned()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        acks,
+        vec![
+            (
+                "john.doe@imkitchen.localhost",
+                "en",
+                Subject::GeneralInquiry
+            ),
+            ("marie@imkitchen.localhost", "fr", Subject::BillingQuestion),
+        ]
+    );
+
+    Ok(())
+}
@@ -50,6 +50,8 @@ pub async fn create_submit_all(
                 name: "my name".to_owned(),
                 subject: Subject::Other,
                 message: "my message".to_owned(),
+                lang: "en".to_owned(),
+                ip: None,
             })
             .await?;
         ids.push(id);
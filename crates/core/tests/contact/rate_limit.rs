@@ -0,0 +1,91 @@
+use evento::Sqlite;
+use imkitchen_core::contact::{
+    SUBMISSION_LIMIT_PER_EMAIL, SUBMISSION_LIMIT_PER_IP, SubmitFormInput,
+};
+use imkitchen_types::contact::Subject;
+use temp_dir::TempDir;
+
+async fn submit(
+    cmd: &imkitchen_core::contact::Module<Sqlite>,
+    email: &str,
+    ip: Option<&str>,
+) -> imkitchen_core::Result<String> {
+    cmd.submit_form(SubmitFormInput {
+        to: "contact@imkitchen.localhost".to_owned(),
+        email: email.to_owned(),
+        name: "my name".to_owned(),
+        subject: Subject::Other,
+        message: "my message".to_owned(),
+        lang: "en".to_owned(),
+        ip: ip.map(ToOwned::to_owned),
+    })
+    .await
+}
+
+#[tokio::test]
+async fn test_rate_limit_per_email() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::contact::Module::new(state);
+
+    for _ in 0..SUBMISSION_LIMIT_PER_EMAIL {
+        submit(&cmd, "john.doe@imkitchen.localhost", None).await?;
+    }
+
+    let err = submit(&cmd, "John.Doe@imkitchen.localhost", None)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, imkitchen_core::Error::RateLimited));
+
+    submit(&cmd, "albert@imkitchen.localhost", None).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rate_limit_per_ip() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::contact::Module::new(state);
+
+    for i in 0..SUBMISSION_LIMIT_PER_IP {
+        let email = format!("user{i}@imkitchen.localhost");
+        submit(&cmd, &email, Some("203.0.113.7")).await?;
+    }
+
+    let err = submit(&cmd, "john.doe@imkitchen.localhost", Some("203.0.113.7"))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, imkitchen_core::Error::RateLimited));
+
+    submit(&cmd, "john.doe@imkitchen.localhost", Some("198.51.100.2")).await?;
+
+    Ok(())
+}
+
+/// The limit holds however many other messages came in since.
+#[tokio::test]
+async fn test_rate_limit_ignores_other_senders() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::contact::Module::new(state);
+
+    for _ in 0..SUBMISSION_LIMIT_PER_EMAIL {
+        submit(&cmd, "john.doe@imkitchen.localhost", Some("203.0.113.7")).await?;
+    }
+
+    for i in 0..150 {
+        let email = format!("user{i}@imkitchen.localhost");
+        submit(&cmd, &email, Some(&format!("198.51.100.{}", i % 100))).await?;
+    }
+
+    let err = submit(&cmd, "john.doe@imkitchen.localhost", Some("192.0.2.1"))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, imkitchen_core::Error::RateLimited));
+
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use imkitchen_core::contact::SubmitFormInput;
+use imkitchen_types::contact::{Status, Subject};
+use temp_dir::TempDir;
+
+/// A link-stuffed message lands in the flagged bucket and goes
+/// unacknowledged, while a message with a single link stays in the inbox.
+#[tokio::test]
+async fn test_spammy_message_is_flagged() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::contact::Module::new(state);
+
+    let input = |message: &str| SubmitFormInput {
+        to: "contact@imkitchen.localhost".to_owned(),
+        email: "visitor@imkitchen.localhost".to_owned(),
+        name: "my name".to_owned(),
+        subject: Subject::Other,
+        message: message.to_owned(),
+        lang: "en".to_owned(),
+        ip: None,
+    };
+
+    let spam = cmd
+        .submit_form(input(
+            "Great deals https://a.example https://b.example https://c.example",
+        ))
+        .await?;
+    let keyword = cmd
+        .submit_form(input("Best casino bonus of the year, click now"))
+        .await?;
+    let genuine = cmd
+        .submit_form(input(
+            "The shopping list page crashes: https://imkitchen.localhost/groceries",
+        ))
+        .await?;
+
+    for id in [&spam, &keyword] {
+        let contact = cmd.load(id).await?.expect("contact");
+        assert_eq!(contact.status, Status::Flagged);
+
+        let admin = cmd.admin(id).await?.expect("admin view");
+        assert!(admin.is_flagged());
+    }
+
+    let contact = cmd.load(&genuine).await?.expect("contact");
+    assert_eq!(contact.status, Status::Unread);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_spam_is_not_counted_as_unread() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::contact::Module::new(state.clone());
+
+    let input = |message: &str| SubmitFormInput {
+        to: "contact@imkitchen.localhost".to_owned(),
+        email: "visitor@imkitchen.localhost".to_owned(),
+        name: "my name".to_owned(),
+        subject: Subject::Other,
+        message: message.to_owned(),
+        lang: "en".to_owned(),
+        ip: None,
+    };
+
+    cmd.submit_form(input("Best casino bonus of the year, click now"))
+        .await?;
+    cmd.submit_form(input("How do I share a recipe?")).await?;
+
+    imkitchen_core::contact::global_stat::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let stat = cmd.find_global_stat_global().await?.expect("global stat");
+    assert_eq!(stat.total, 2);
+    assert_eq!(stat.unread, 1);
+
+    Ok(())
+}
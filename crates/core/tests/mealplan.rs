@@ -1,4 +1,58 @@
+#[path = "mealplan/accompaniment.rs"]
+mod accompaniment;
+#[path = "mealplan/advance_prep.rs"]
+mod advance_prep;
+#[path = "mealplan/community.rs"]
+mod community;
+#[path = "mealplan/complete_day.rs"]
+mod complete_day;
+#[path = "mealplan/complexity.rs"]
+mod complexity;
+#[path = "mealplan/conflict.rs"]
+mod conflict;
+#[path = "mealplan/cooking_step.rs"]
+mod cooking_step;
+#[path = "mealplan/courses.rs"]
+mod courses;
+#[path = "mealplan/cuisine.rs"]
+mod cuisine;
+#[path = "mealplan/dietary_preview.rs"]
+mod dietary_preview;
+#[path = "mealplan/eligibility.rs"]
+mod eligibility;
+#[path = "mealplan/freshness.rs"]
+mod freshness;
 #[path = "mealplan/generate.rs"]
 mod generate;
 #[path = "mealplan/helpers/mod.rs"]
 mod helpers;
+#[path = "mealplan/ics.rs"]
+mod ics;
+#[path = "mealplan/min_household.rs"]
+mod min_household;
+#[path = "mealplan/nutrition.rs"]
+mod nutrition;
+#[path = "mealplan/pin.rs"]
+mod pin;
+#[path = "mealplan/rating.rs"]
+mod rating;
+#[path = "mealplan/recency.rs"]
+mod recency;
+#[path = "mealplan/replace_meal.rs"]
+mod replace_meal;
+#[path = "mealplan/rotation.rs"]
+mod rotation;
+#[path = "mealplan/scorer.rs"]
+mod scorer;
+#[path = "mealplan/skip_week.rs"]
+mod skip_week;
+#[path = "mealplan/snapshot.rs"]
+mod snapshot;
+#[path = "mealplan/swap_meals.rs"]
+mod swap_meals;
+#[path = "mealplan/time_budget.rs"]
+mod time_budget;
+#[path = "mealplan/today.rs"]
+mod today;
+#[path = "mealplan/week.rs"]
+mod week;
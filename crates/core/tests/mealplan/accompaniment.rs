@@ -0,0 +1,305 @@
+use evento::Sqlite;
+use imkitchen_core::recipe::{AccompanimentTypesInput, DefaultAccompanimentsInput, ImportInput};
+use imkitchen_types::recipe::{AccompanimentType, RecipeType};
+use temp_dir::TempDir;
+use time::OffsetDateTime;
+
+#[tokio::test]
+async fn test_preferred_accompaniment_type() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    let steak = import_recipe(&recipe_cmd, "Steak", RecipeType::MainCourse, true).await?;
+    recipe_cmd
+        .set_accompaniment_types(
+            AccompanimentTypesInput {
+                id: steak,
+                accompaniment_type: None,
+                preferred_accompaniment_types: vec![AccompanimentType::Fries],
+            },
+            "john",
+        )
+        .await?;
+
+    let mut accompaniments = vec![];
+    for (name, accompaniment_type) in [
+        ("Rice", AccompanimentType::Rice),
+        ("Mash", AccompanimentType::Potatoes),
+        ("Salad", AccompanimentType::Salad),
+    ] {
+        let id = import_recipe(&recipe_cmd, name, RecipeType::Accompaniment, false).await?;
+        recipe_cmd
+            .set_accompaniment_types(
+                AccompanimentTypesInput {
+                    id: id.to_owned(),
+                    accompaniment_type: Some(accompaniment_type),
+                    preferred_accompaniment_types: vec![],
+                },
+                "john",
+            )
+            .await?;
+        accompaniments.push(id);
+    }
+
+    let today = OffsetDateTime::now_utc();
+    let planned = async || {
+        imkitchen_core::mealplan::subscription()
+            .data(state.write_db.clone())
+            .no_retry()
+            .run_once(&state.executor)
+            .await?;
+
+        cmd.generate(imkitchen_core::mealplan::Generate {
+            user_id: "john".to_owned(),
+            days: 7,
+            start: today.unix_timestamp() as u64,
+            randomize: Some(imkitchen_core::mealplan::Randomize {
+                cuisine_variety_weight: 1.0,
+                dietary_restrictions: vec![],
+                rating_weight: 0.0,
+                recency_weight: 0.0,
+                seed: None,
+                randomness: 1.0,
+            }),
+            household_size: 2,
+            ..Default::default()
+        })
+        .await?;
+
+        imkitchen_core::mealplan::slot::subscription()
+            .data(state.write_db.clone())
+            .no_retry()
+            .run_once(&state.executor)
+            .await?;
+
+        let slots = cmd
+            .range("john", today, today + time::Duration::days(6))
+            .await?;
+
+        anyhow::Ok(
+            slots
+                .into_iter()
+                .map(|slot| slot.accompaniment.map(|r| r.id.to_owned()))
+                .collect::<Vec<_>>(),
+        )
+    };
+
+    // No fries yet: mash shares the potato category.
+    let mash = Some(accompaniments[1].to_owned());
+    assert_eq!(planned().await?, vec![mash; 7]);
+
+    let fries = import_recipe(&recipe_cmd, "Fries", RecipeType::Accompaniment, false).await?;
+    recipe_cmd
+        .set_accompaniment_types(
+            AccompanimentTypesInput {
+                id: fries.to_owned(),
+                accompaniment_type: Some(AccompanimentType::Fries),
+                preferred_accompaniment_types: vec![],
+            },
+            "john",
+        )
+        .await?;
+
+    assert_eq!(planned().await?, vec![Some(fries); 7]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_default_accompaniment() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    let schnitzel = import_recipe(&recipe_cmd, "Schnitzel", RecipeType::MainCourse, true).await?;
+    recipe_cmd
+        .set_accompaniment_types(
+            AccompanimentTypesInput {
+                id: schnitzel.to_owned(),
+                accompaniment_type: None,
+                preferred_accompaniment_types: vec![AccompanimentType::Salad],
+            },
+            "john",
+        )
+        .await?;
+
+    let mut accompaniments = vec![];
+    for (name, accompaniment_type) in [
+        ("Fries", AccompanimentType::Fries),
+        ("Salad", AccompanimentType::Salad),
+    ] {
+        let id = import_recipe(&recipe_cmd, name, RecipeType::Accompaniment, false).await?;
+        recipe_cmd
+            .set_accompaniment_types(
+                AccompanimentTypesInput {
+                    id: id.to_owned(),
+                    accompaniment_type: Some(accompaniment_type),
+                    preferred_accompaniment_types: vec![],
+                },
+                "john",
+            )
+            .await?;
+        accompaniments.push(id);
+    }
+
+    recipe_cmd
+        .set_default_accompaniments(
+            DefaultAccompanimentsInput {
+                id: schnitzel,
+                accompaniment_ids: vec![accompaniments[0].to_owned()],
+            },
+            "john",
+        )
+        .await?;
+
+    let today = OffsetDateTime::now_utc();
+    let planned = async || {
+        imkitchen_core::mealplan::subscription()
+            .data(state.write_db.clone())
+            .no_retry()
+            .run_once(&state.executor)
+            .await?;
+
+        cmd.generate(imkitchen_core::mealplan::Generate {
+            user_id: "john".to_owned(),
+            days: 7,
+            start: today.unix_timestamp() as u64,
+            randomize: Some(imkitchen_core::mealplan::Randomize {
+                cuisine_variety_weight: 1.0,
+                dietary_restrictions: vec![],
+                rating_weight: 0.0,
+                recency_weight: 0.0,
+                seed: None,
+                randomness: 1.0,
+            }),
+            household_size: 2,
+            ..Default::default()
+        })
+        .await?;
+
+        imkitchen_core::mealplan::slot::subscription()
+            .data(state.write_db.clone())
+            .no_retry()
+            .run_once(&state.executor)
+            .await?;
+
+        let slots = cmd
+            .range("john", today, today + time::Duration::days(6))
+            .await?;
+
+        anyhow::Ok(
+            slots
+                .into_iter()
+                .map(|slot| slot.accompaniment.map(|r| r.id.to_owned()))
+                .collect::<Vec<_>>(),
+        )
+    };
+
+    // The default wins over the preferred salad.
+    assert_eq!(
+        planned().await?,
+        vec![Some(accompaniments[0].to_owned()); 7]
+    );
+
+    // Once the fries are gone, the preferred type takes over again.
+    recipe_cmd.delete(&accompaniments[0], "john").await?;
+    assert_eq!(
+        planned().await?,
+        vec![Some(accompaniments[1].to_owned()); 7]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_default_accompaniments_must_be_visible_accompaniments() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    let schnitzel = import_recipe(&recipe_cmd, "Schnitzel", RecipeType::MainCourse, true).await?;
+    let steak = import_recipe(&recipe_cmd, "Steak", RecipeType::MainCourse, true).await?;
+    let fries = import_recipe(&recipe_cmd, "Fries", RecipeType::Accompaniment, false).await?;
+    let private = recipe_cmd
+        .import(
+            ImportInput {
+                name: "Secret salad".to_owned(),
+                description: "my description".to_owned(),
+                ingredients: crate::helpers::ingredients(),
+                instructions: crate::helpers::instructions(),
+                household_size: 4,
+                cook_time: 5,
+                prep_time: 10,
+                recipe_type: RecipeType::Accompaniment,
+                ..Default::default()
+            },
+            "jane",
+            None,
+        )
+        .await?;
+
+    for accompaniment_ids in [
+        vec![steak],
+        vec![private.to_owned()],
+        vec!["unknown".to_owned()],
+        vec![fries.to_owned(), private],
+    ] {
+        let err = recipe_cmd
+            .set_default_accompaniments(
+                DefaultAccompanimentsInput {
+                    id: schnitzel.to_owned(),
+                    accompaniment_ids,
+                },
+                "john",
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Default accompaniments must be accompaniment recipes"
+        );
+    }
+
+    recipe_cmd
+        .set_default_accompaniments(
+            DefaultAccompanimentsInput {
+                id: schnitzel.to_owned(),
+                accompaniment_ids: vec![fries.to_owned()],
+            },
+            "john",
+        )
+        .await?;
+
+    let schnitzel = recipe_cmd.load(&schnitzel).await?.unwrap();
+    assert_eq!(schnitzel.default_accompaniment_ids, vec![fries]);
+
+    Ok(())
+}
+
+async fn import_recipe(
+    cmd: &imkitchen_core::recipe::Module<Sqlite>,
+    name: &str,
+    recipe_type: RecipeType,
+    accepts_accompaniment: bool,
+) -> anyhow::Result<String> {
+    let input = ImportInput {
+        name: name.to_owned(),
+        description: "my description".to_owned(),
+        ingredients: crate::helpers::ingredients(),
+        instructions: crate::helpers::instructions(),
+        household_size: 4,
+        cook_time: 25,
+        prep_time: 10,
+        recipe_type,
+        accepts_accompaniment,
+        ..Default::default()
+    };
+
+    Ok(cmd.import(input, "john", None).await?)
+}
@@ -0,0 +1,217 @@
+use evento::Sqlite;
+use imkitchen_core::mealplan::slot::DEFAULT_ADVANCE_PREP_HOURS;
+use imkitchen_core::mealplan::{Generate, MarkAdvancePrep, ReplaceMeal};
+use imkitchen_core::recipe::{AdvancePrepHoursInput, ImportInput, MAX_ADVANCE_PREP_HOURS};
+use imkitchen_types::recipe::RecipeType;
+use temp_dir::TempDir;
+use time::{Duration, OffsetDateTime};
+
+async fn import_recipe(
+    cmd: &imkitchen_core::recipe::Module<Sqlite>,
+    name: &str,
+) -> anyhow::Result<String> {
+    Ok(cmd
+        .import(
+            ImportInput {
+                name: name.to_owned(),
+                description: "my description".to_owned(),
+                ingredients: crate::helpers::ingredients(),
+                instructions: crate::helpers::instructions(),
+                advance_prep: "Marinate overnight".to_owned(),
+                household_size: 4,
+                cook_time: 25,
+                prep_time: 10,
+                recipe_type: RecipeType::MainCourse,
+                ..Default::default()
+            },
+            "john",
+            None,
+        )
+        .await?)
+}
+
+async fn run_slot_subscription(state: &imkitchen_core::State<Sqlite>) -> anyhow::Result<()> {
+    imkitchen_core::mealplan::slot::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_advance_prep_done_suppresses_reminder() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    import_recipe(&recipe_cmd, "Chicken tikka").await?;
+    import_recipe(&recipe_cmd, "Pulled pork").await?;
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let today = OffsetDateTime::now_utc();
+    cmd.generate(Generate {
+        user_id: "john".to_owned(),
+        start: today.unix_timestamp() as u64,
+        days: 1,
+        household_size: 4,
+        ..Default::default()
+    })
+    .await?;
+    run_slot_subscription(&state).await?;
+
+    // Reminders are looked up for the day after the given one.
+    let yesterday = (today - Duration::days(1)).unix_timestamp() as u64;
+    let reminders = cmd.next_prep_remiders_from(yesterday, "john").await?;
+    let planned = reminders.expect("reminders")[0].id.to_owned();
+
+    let date = imkitchen_core::mealplan::date_to_u64(today);
+    cmd.mark_advance_prep(MarkAdvancePrep {
+        user_id: "john".to_owned(),
+        date,
+        recipe_id: planned.to_owned(),
+        done: true,
+    })
+    .await?;
+    run_slot_subscription(&state).await?;
+
+    let recipe = cmd.day_slot_recipe("john", date, &planned).await?.unwrap();
+    assert!(recipe.advance_prep_done);
+    assert!(
+        cmd.next_prep_remiders_from(yesterday, "john")
+            .await?
+            .is_none()
+    );
+
+    // A new recipe in the slot starts with its prep not done.
+    cmd.replace_meal(ReplaceMeal {
+        user_id: "john".to_owned(),
+        date,
+        recipe_type: RecipeType::MainCourse,
+        available_minutes: None,
+        min_days_since_use: 0,
+    })
+    .await?;
+    run_slot_subscription(&state).await?;
+
+    let reminders = cmd.next_prep_remiders_from(yesterday, "john").await?;
+    let reminders = reminders.expect("reminders");
+    assert_eq!(reminders.len(), 1);
+    assert_ne!(reminders[0].id, planned);
+    assert!(!reminders[0].advance_prep_done);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_prep_reminder_is_due_advance_prep_hours_before_the_day() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    let id = import_recipe(&recipe_cmd, "Chickpea stew").await?;
+    recipe_cmd
+        .set_advance_prep_hours(
+            AdvancePrepHoursInput {
+                id: id.to_owned(),
+                advance_prep_hours: Some(36),
+            },
+            "john",
+        )
+        .await?;
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let day = (OffsetDateTime::now_utc() + Duration::days(3)).unix_timestamp() as u64;
+    cmd.generate(Generate {
+        user_id: "john".to_owned(),
+        start: day,
+        days: 1,
+        household_size: 4,
+        ..Default::default()
+    })
+    .await?;
+    run_slot_subscription(&state).await?;
+
+    let remind_at = day - 36 * 3600;
+    let reminders = cmd
+        .prep_reminders_between(remind_at - 60, remind_at + 60)
+        .await?;
+    assert_eq!(reminders.len(), 1);
+    assert_eq!(reminders[0].user_id, "john");
+    assert_eq!(reminders[0].remind_at, remind_at);
+    assert_eq!(reminders[0].recipe.id, id);
+    assert_eq!(reminders[0].recipe.advance_prep, "Marinate overnight");
+
+    // Not due yet a day before the meal.
+    let reminders = cmd
+        .prep_reminders_between(day - 24 * 3600, day - 24 * 3600 + 60)
+        .await?;
+    assert!(reminders.is_empty());
+
+    // Without hours set, the reminder goes out the day before.
+    recipe_cmd
+        .set_advance_prep_hours(
+            AdvancePrepHoursInput {
+                id: id.to_owned(),
+                advance_prep_hours: None,
+            },
+            "john",
+        )
+        .await?;
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let remind_at = day - DEFAULT_ADVANCE_PREP_HOURS as u64 * 3600;
+    let reminders = cmd.prep_reminders_between(remind_at, remind_at + 1).await?;
+    assert_eq!(reminders.len(), 1);
+
+    // Marking the prep done takes it off the schedule.
+    cmd.mark_advance_prep(MarkAdvancePrep {
+        user_id: "john".to_owned(),
+        date: imkitchen_core::mealplan::date_to_u64(OffsetDateTime::from_unix_timestamp(
+            day as i64,
+        )?),
+        recipe_id: id.to_owned(),
+        done: true,
+    })
+    .await?;
+    run_slot_subscription(&state).await?;
+    assert!(
+        cmd.prep_reminders_between(remind_at, remind_at + 1)
+            .await?
+            .is_empty()
+    );
+
+    assert!(
+        recipe_cmd
+            .set_advance_prep_hours(
+                AdvancePrepHoursInput {
+                    id,
+                    advance_prep_hours: Some(MAX_ADVANCE_PREP_HOURS + 1),
+                },
+                "john",
+            )
+            .await
+            .is_err()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,206 @@
+use evento::Sqlite;
+use imkitchen_core::recipe::ImportInput;
+use imkitchen_types::recipe::{DietaryRestriction, RecipeType};
+use temp_dir::TempDir;
+use time::{Duration, OffsetDateTime};
+
+async fn import_recipe(
+    cmd: &imkitchen_core::recipe::Module<Sqlite>,
+    name: &str,
+    dietary_restrictions: Vec<DietaryRestriction>,
+    user_id: &str,
+) -> anyhow::Result<String> {
+    Ok(cmd
+        .import(
+            ImportInput {
+                name: name.to_owned(),
+                description: "my description".to_owned(),
+                ingredients: crate::helpers::ingredients(),
+                instructions: crate::helpers::instructions(),
+                household_size: 4,
+                cook_time: 25,
+                prep_time: 10,
+                recipe_type: RecipeType::MainCourse,
+                dietary_restrictions,
+                ..Default::default()
+            },
+            user_id,
+            None,
+        )
+        .await?)
+}
+
+/// The best rated shared recipes fill the requested days, never one that is
+/// unshared, outside the user's restrictions or already theirs.
+#[tokio::test]
+async fn test_community_suggestions() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+    let vegetarian = vec![DietaryRestriction::Vegetarian];
+
+    let mut own = vec![];
+    for index in 0..7 {
+        own.push(
+            import_recipe(
+                &recipe_cmd,
+                &format!("Main {index}"),
+                vegetarian.clone(),
+                "john",
+            )
+            .await?,
+        );
+    }
+
+    let top = import_recipe(&recipe_cmd, "Top", vegetarian.clone(), "jane").await?;
+    let second = import_recipe(&recipe_cmd, "Second", vegetarian.clone(), "jane").await?;
+    let third = import_recipe(&recipe_cmd, "Third", vegetarian.clone(), "jane").await?;
+    let meat = import_recipe(&recipe_cmd, "Meat", vec![], "jane").await?;
+    let private = import_recipe(&recipe_cmd, "Private", vegetarian.clone(), "jane").await?;
+    let shared_by_john = own[0].to_owned();
+
+    for id in [&top, &second, &third, &meat] {
+        recipe_cmd.share_to_community(id, "jane", "Jane").await?;
+    }
+    recipe_cmd
+        .share_to_community(&shared_by_john, "john", "John")
+        .await?;
+
+    for (id, stars) in [
+        (&top, 5),
+        (&second, 4),
+        (&third, 1),
+        (&meat, 5),
+        (&private, 5),
+        (&shared_by_john, 5),
+    ] {
+        recipe_cmd.rating.rate(id, "alice", stars).await?;
+    }
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    imkitchen_core::recipe::query::user::create_projection()
+        .data((state.read_db.clone(), state.write_db.clone()))
+        .subscription("recipe-query")
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    imkitchen_core::recipe::query::rating::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let today = OffsetDateTime::now_utc();
+    cmd.generate(imkitchen_core::mealplan::Generate {
+        user_id: "john".to_owned(),
+        days: 7,
+        start: today.unix_timestamp() as u64,
+        randomize: Some(imkitchen_core::mealplan::Randomize {
+            cuisine_variety_weight: 1.0,
+            dietary_restrictions: vegetarian.clone(),
+            rating_weight: 0.0,
+            recency_weight: 0.0,
+            seed: Some(1),
+            randomness: 1.0,
+        }),
+        household_size: 4,
+        community_suggestions: 2,
+        ..Default::default()
+    })
+    .await?;
+
+    imkitchen_core::mealplan::slot::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let slots = cmd.range("john", today, today + Duration::days(6)).await?;
+    assert_eq!(slots.len(), 7);
+
+    let suggested = slots
+        .iter()
+        .filter(|slot| slot.suggested)
+        .map(|slot| slot.main_course.id.to_owned())
+        .collect::<Vec<_>>();
+    assert_eq!(suggested, vec![top.to_owned(), second.to_owned()]);
+    assert_eq!(slots[0].main_course.name, "Top");
+
+    for slot in slots.iter().filter(|slot| !slot.suggested) {
+        assert!(own.contains(&slot.main_course.id));
+    }
+
+    Ok(())
+}
+
+/// Without the option only the user's recipes are planned.
+#[tokio::test]
+async fn test_community_suggestions_off() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    let mut own = vec![];
+    for index in 0..7 {
+        own.push(import_recipe(&recipe_cmd, &format!("Main {index}"), vec![], "john").await?);
+    }
+
+    let shared = import_recipe(&recipe_cmd, "Shared", vec![], "jane").await?;
+    recipe_cmd
+        .share_to_community(&shared, "jane", "Jane")
+        .await?;
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    imkitchen_core::recipe::query::user::create_projection()
+        .data((state.read_db.clone(), state.write_db.clone()))
+        .subscription("recipe-query")
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let today = OffsetDateTime::now_utc();
+    cmd.generate(imkitchen_core::mealplan::Generate {
+        user_id: "john".to_owned(),
+        days: 7,
+        start: today.unix_timestamp() as u64,
+        randomize: Some(imkitchen_core::mealplan::Randomize {
+            cuisine_variety_weight: 1.0,
+            dietary_restrictions: vec![],
+            rating_weight: 0.0,
+            recency_weight: 0.0,
+            seed: Some(1),
+            randomness: 1.0,
+        }),
+        household_size: 4,
+        ..Default::default()
+    })
+    .await?;
+
+    imkitchen_core::mealplan::slot::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let slots = cmd.range("john", today, today + Duration::days(6)).await?;
+    assert_eq!(slots.len(), 7);
+    assert!(slots.iter().all(|slot| !slot.suggested));
+    assert!(slots.iter().all(|slot| own.contains(&slot.main_course.id)));
+
+    Ok(())
+}
@@ -0,0 +1,154 @@
+use evento::Sqlite;
+use imkitchen_core::mealplan::{ChangeSlotRecipeStatus, CompleteDay, Generate};
+use imkitchen_core::recipe::ImportInput;
+use imkitchen_types::mealplan::DaySlotStatus;
+use imkitchen_types::recipe::RecipeType;
+use temp_dir::TempDir;
+use time::OffsetDateTime;
+
+async fn import_recipe(
+    cmd: &imkitchen_core::recipe::Module<Sqlite>,
+    db: &sqlx::SqlitePool,
+    name: &str,
+    recipe_type: RecipeType,
+) -> anyhow::Result<String> {
+    let id = cmd
+        .import(
+            ImportInput {
+                name: name.to_owned(),
+                description: "my description".to_owned(),
+                ingredients: crate::helpers::ingredients(),
+                instructions: crate::helpers::instructions(),
+                household_size: 4,
+                cook_time: 25,
+                prep_time: 10,
+                recipe_type: recipe_type.clone(),
+                ..Default::default()
+            },
+            "john",
+            None,
+        )
+        .await?;
+
+    // Mirror what the `recipe-query` projection writes at runtime.
+    sqlx::query(
+        "INSERT INTO recipe_user \
+         (id, cursor, owner_id, recipe_type, slug, name, description, ingredients, \
+          instructions, dietary_restrictions, is_shared, created_at, difficulty_score) \
+         VALUES (?, ?, 'john', ?, ?, ?, '', X'', X'', '[]', 0, 0, 0)",
+    )
+    .bind(&id)
+    .bind(&id)
+    .bind(recipe_type.to_string())
+    .bind(&id)
+    .bind(name)
+    .execute(db)
+    .await?;
+
+    Ok(id)
+}
+
+async fn run_subscriptions(state: &imkitchen_core::State<Sqlite>) -> anyhow::Result<()> {
+    imkitchen_core::mealplan::slot::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    imkitchen_core::recipe::query::cook_count::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_complete_day_marks_every_course() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    let curry = import_recipe(
+        &recipe_cmd,
+        &state.write_db,
+        "Curry",
+        RecipeType::MainCourse,
+    )
+    .await?;
+    let samosa = import_recipe(
+        &recipe_cmd,
+        &state.write_db,
+        "Samosa",
+        RecipeType::Appetizer,
+    )
+    .await?;
+    let kulfi = import_recipe(&recipe_cmd, &state.write_db, "Kulfi", RecipeType::Dessert).await?;
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let today = OffsetDateTime::now_utc();
+    cmd.generate(Generate {
+        user_id: "john".to_owned(),
+        start: today.unix_timestamp() as u64,
+        days: 1,
+        household_size: 4,
+        ..Default::default()
+    })
+    .await?;
+    run_subscriptions(&state).await?;
+
+    let date = imkitchen_core::mealplan::date_to_u64(today);
+
+    // One course is already under way, another already done.
+    cmd.change_slot_recipe_status(ChangeSlotRecipeStatus {
+        user_id: "john".to_owned(),
+        date,
+        recipe_id: curry.to_owned(),
+        status: DaySlotStatus::Cooking(1),
+    })
+    .await?;
+    cmd.change_slot_recipe_status(ChangeSlotRecipeStatus {
+        user_id: "john".to_owned(),
+        date,
+        recipe_id: kulfi.to_owned(),
+        status: DaySlotStatus::Completed,
+    })
+    .await?;
+    run_subscriptions(&state).await?;
+
+    let input = || CompleteDay {
+        user_id: "john".to_owned(),
+        date,
+    };
+    cmd.complete_day(input()).await?;
+    run_subscriptions(&state).await?;
+
+    let recipes = cmd.day_recipes("john", date).await?;
+    assert_eq!(recipes.len(), 3);
+    assert!(recipes.iter().all(|r| r.is_completed()));
+
+    // Completing again is a no-op.
+    cmd.complete_day(input()).await?;
+    run_subscriptions(&state).await?;
+
+    let mut cooked = recipe_cmd
+        .most_cooked("john", 10)
+        .await?
+        .into_iter()
+        .map(|r| (r.id, r.cook_count))
+        .collect::<Vec<_>>();
+    cooked.sort();
+    let mut expected = vec![(curry, 1), (samosa, 1), (kulfi, 1)];
+    expected.sort();
+    assert_eq!(cooked, expected);
+
+    Ok(())
+}
@@ -0,0 +1,192 @@
+use evento::Sqlite;
+use imkitchen_core::State;
+use imkitchen_core::mealplan::{Generate, MaxComplexity, Module};
+use imkitchen_core::recipe::{ComplexityInput, ImportInput};
+use imkitchen_types::recipe::{
+    Complexity, Ingredient, IngredientCategory, IngredientUnit, RecipeType,
+};
+use temp_dir::TempDir;
+use time::{Duration, OffsetDateTime, Weekday};
+
+/// Imports a main course; its size alone decides its estimated complexity.
+async fn import_main_course(
+    cmd: &imkitchen_core::recipe::Module<Sqlite>,
+    name: &str,
+    ingredient_count: usize,
+) -> anyhow::Result<String> {
+    let ingredients = (0..ingredient_count)
+        .map(|i| Ingredient {
+            name: format!("ingredient {i}"),
+            quantity: 100,
+            unit: Some(IngredientUnit::G),
+            category: Some(IngredientCategory::Grocery),
+        })
+        .collect();
+
+    Ok(cmd
+        .import(
+            ImportInput {
+                name: name.to_owned(),
+                description: "my description".to_owned(),
+                instructions: crate::helpers::instructions(),
+                ingredients,
+                household_size: 2,
+                cook_time: 15,
+                prep_time: 10,
+                recipe_type: RecipeType::MainCourse,
+                ..Default::default()
+            },
+            "john",
+            None,
+        )
+        .await?)
+}
+
+async fn run_subscriptions(state: &State<Sqlite>) -> anyhow::Result<()> {
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+    imkitchen_core::mealplan::slot::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    Ok(())
+}
+
+fn next_monday() -> OffsetDateTime {
+    let today = OffsetDateTime::now_utc();
+
+    today + Duration::days(7 - today.weekday().number_days_from_monday() as i64)
+}
+
+fn week(monday: OffsetDateTime, max_complexity: MaxComplexity) -> Generate {
+    Generate {
+        user_id: "john".to_owned(),
+        start: monday.unix_timestamp() as u64,
+        days: 7,
+        household_size: 2,
+        max_complexity,
+        ..Default::default()
+    }
+}
+
+/// Weekdays of the week starting on `monday` that `recipe_id` is planned on.
+async fn planned_on(
+    cmd: &Module<Sqlite>,
+    monday: OffsetDateTime,
+    recipe_id: &str,
+) -> anyhow::Result<Vec<Weekday>> {
+    let mut days = vec![];
+    for slot in cmd
+        .range("john", monday, monday + Duration::days(6))
+        .await?
+    {
+        if slot.main_course.id == recipe_id {
+            days.push(OffsetDateTime::from_unix_timestamp(slot.day as i64)?.weekday());
+        }
+    }
+
+    Ok(days)
+}
+
+fn is_weekend(day: &Weekday) -> bool {
+    matches!(day, Weekday::Saturday | Weekday::Sunday)
+}
+
+#[tokio::test]
+async fn test_complex_main_courses_wait_for_the_weekend() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    import_main_course(&recipe_cmd, "Salad", 4).await?;
+    import_main_course(&recipe_cmd, "Pasta", 5).await?;
+    // 15 ingredients are enough to estimate it as advanced.
+    let roast = import_main_course(&recipe_cmd, "Roast", 15).await?;
+    run_subscriptions(&state).await?;
+
+    let monday = next_monday();
+    cmd.generate(week(monday, Default::default())).await?;
+    run_subscriptions(&state).await?;
+    assert_eq!(planned_on(&cmd, monday, &roast).await?, [Weekday::Saturday]);
+
+    // The author's rating wins over the estimate.
+    recipe_cmd
+        .set_complexity(
+            ComplexityInput {
+                id: roast.to_owned(),
+                complexity: Some(Complexity::Simple),
+            },
+            "john",
+        )
+        .await?;
+    run_subscriptions(&state).await?;
+
+    cmd.generate(week(monday, Default::default())).await?;
+    run_subscriptions(&state).await?;
+    let days = planned_on(&cmd, monday, &roast).await?;
+    assert!(days.iter().any(|day| !is_weekend(day)));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_strict_weekday_cap() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    let salad = import_main_course(&recipe_cmd, "Salad", 4).await?;
+    let stew = import_main_course(&recipe_cmd, "Stew", 9).await?;
+    let roast = import_main_course(&recipe_cmd, "Roast", 15).await?;
+    run_subscriptions(&state).await?;
+
+    let monday = next_monday();
+    let max_complexity = MaxComplexity {
+        weekday: Complexity::Simple,
+        weekend: Complexity::Advanced,
+    };
+    cmd.generate(week(monday, max_complexity)).await?;
+    run_subscriptions(&state).await?;
+
+    assert_eq!(planned_on(&cmd, monday, &salad).await?.len(), 5);
+    for id in [stew, roast] {
+        let days = planned_on(&cmd, monday, &id).await?;
+        assert_eq!(days.len(), 1);
+        assert!(days.iter().all(is_weekend));
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_weekday_cap_falls_back_when_nothing_fits() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    let roast = import_main_course(&recipe_cmd, "Roast", 15).await?;
+    run_subscriptions(&state).await?;
+
+    let monday = next_monday();
+    let max_complexity = MaxComplexity {
+        weekday: Complexity::Simple,
+        weekend: Complexity::Simple,
+    };
+    cmd.generate(week(monday, max_complexity)).await?;
+    run_subscriptions(&state).await?;
+
+    assert_eq!(planned_on(&cmd, monday, &roast).await?.len(), 7);
+
+    Ok(())
+}
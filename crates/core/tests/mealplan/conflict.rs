@@ -0,0 +1,233 @@
+use evento::Sqlite;
+use imkitchen_core::State;
+use imkitchen_core::recipe::{EquipmentInput, ImportInput};
+use imkitchen_db::mealplan_slot::MealPlanSlot;
+use imkitchen_types::mealplan::DaySlotRecipe;
+use imkitchen_types::recipe::{Equipment, RecipeType};
+use sea_query::{Query, SqliteQueryBuilder};
+use sea_query_sqlx::SqlxBinder;
+use std::collections::HashMap;
+use temp_dir::TempDir;
+use time::{Duration, OffsetDateTime};
+
+#[tokio::test]
+async fn test_equipment_conflicts() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    let roast = import_recipe(&recipe_cmd, "Roast", RecipeType::MainCourse).await?;
+    let gratin = import_recipe(&recipe_cmd, "Gratin", RecipeType::Accompaniment).await?;
+    let soup = import_recipe(&recipe_cmd, "Soup", RecipeType::Appetizer).await?;
+
+    for (id, equipment) in [
+        (&roast, vec![Equipment::Oven]),
+        (&gratin, vec![Equipment::Oven, Equipment::Oven]),
+        (&soup, vec![Equipment::Stovetop]),
+    ] {
+        recipe_cmd
+            .set_equipment(
+                EquipmentInput {
+                    id: id.to_owned(),
+                    equipment,
+                },
+                "john",
+            )
+            .await?;
+    }
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    // Roast and gratin both want the only oven on the same evening.
+    let today = OffsetDateTime::now_utc();
+    let course = |id: &str| {
+        bitcode::encode(&DaySlotRecipe {
+            id: id.to_owned(),
+            name: id.to_owned(),
+            ..Default::default()
+        })
+    };
+    let statement = Query::insert()
+        .into_table(MealPlanSlot::Table)
+        .columns([
+            MealPlanSlot::UserId,
+            MealPlanSlot::Day,
+            MealPlanSlot::Date,
+            MealPlanSlot::HouseholdSize,
+            MealPlanSlot::MainCourse,
+            MealPlanSlot::Appetizer,
+            MealPlanSlot::Accompaniment,
+            MealPlanSlot::GeneratedAt,
+        ])
+        .values_panic([
+            "john".into(),
+            today.unix_timestamp().into(),
+            imkitchen_core::mealplan::date_to_u64(today).into(),
+            2.into(),
+            course(&roast).into(),
+            course(&soup).into(),
+            course(&gratin).into(),
+            today.unix_timestamp().into(),
+        ])
+        .to_owned();
+    let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+    sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+        .execute(&state.write_db)
+        .await?;
+
+    let conflicts = cmd.conflicts("john", today, today, &HashMap::new()).await?;
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].day, today.unix_timestamp() as u64);
+    assert_eq!(conflicts[0].equipment, Equipment::Oven);
+    assert_eq!(conflicts[0].needed, 2);
+    assert_eq!(conflicts[0].available, 1);
+    assert_eq!(conflicts[0].recipe_ids, vec![roast, gratin]);
+
+    // A second oven clears the warning.
+    let conflicts = cmd
+        .conflicts("john", today, today, &HashMap::from([(Equipment::Oven, 2)]))
+        .await?;
+    assert!(conflicts.is_empty());
+
+    assert!(
+        cmd.conflicts("albert", today, today, &HashMap::new())
+            .await?
+            .is_empty()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_generate_keeps_one_oven_recipe_per_day() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let (_, tart, mousse) = import_oven_week(&state).await?;
+
+    let desserts = plan_desserts(&state, HashMap::new()).await?;
+    assert_eq!(desserts.len(), 7);
+    assert!(desserts.iter().all(|id| id == &mousse));
+
+    // With a second oven the tart comes around again.
+    let desserts = plan_desserts(&state, HashMap::from([(Equipment::Oven, 2)])).await?;
+    assert!(desserts.contains(&tart));
+    assert!(desserts.contains(&mousse));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_generate_skips_missing_equipment() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    import_oven_week(&state).await?;
+
+    // Every main course needs the oven the kitchen doesn't have.
+    let resp = plan_desserts(&state, HashMap::from([(Equipment::Oven, 0)])).await;
+    assert_eq!(
+        resp.err().map(|e| e.to_string()),
+        Some("No main course found".to_owned())
+    );
+
+    Ok(())
+}
+
+/// An oven roast as the only main course, an oven tart and a mousse for
+/// dessert. Returns their ids in that order.
+async fn import_oven_week(state: &State<Sqlite>) -> anyhow::Result<(String, String, String)> {
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    let roast = import_recipe(&recipe_cmd, "Roast", RecipeType::MainCourse).await?;
+    let tart = import_recipe(&recipe_cmd, "Tart", RecipeType::Dessert).await?;
+    let mousse = import_recipe(&recipe_cmd, "Mousse", RecipeType::Dessert).await?;
+
+    for id in [&roast, &tart] {
+        recipe_cmd
+            .set_equipment(
+                EquipmentInput {
+                    id: id.to_owned(),
+                    equipment: vec![Equipment::Oven],
+                },
+                "john",
+            )
+            .await?;
+    }
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    Ok((roast, tart, mousse))
+}
+
+/// Generates the week and returns the dessert planned each day.
+async fn plan_desserts(
+    state: &State<Sqlite>,
+    equipment_capacity: HashMap<Equipment, u8>,
+) -> anyhow::Result<Vec<String>> {
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+    let today = OffsetDateTime::now_utc();
+
+    cmd.generate(imkitchen_core::mealplan::Generate {
+        user_id: "john".to_owned(),
+        days: 7,
+        start: today.unix_timestamp() as u64,
+        randomize: Some(imkitchen_core::mealplan::Randomize {
+            cuisine_variety_weight: 1.0,
+            dietary_restrictions: vec![],
+            rating_weight: 0.0,
+            recency_weight: 0.0,
+            seed: Some(1),
+            randomness: 1.0,
+        }),
+        household_size: 2,
+        skipped_courses: vec![RecipeType::Appetizer, RecipeType::Accompaniment],
+        equipment_capacity,
+        ..Default::default()
+    })
+    .await?;
+
+    imkitchen_core::mealplan::slot::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    Ok(cmd
+        .range("john", today, today + Duration::days(6))
+        .await?
+        .into_iter()
+        .filter_map(|slot| slot.dessert.map(|dessert| dessert.id))
+        .collect())
+}
+
+async fn import_recipe(
+    cmd: &imkitchen_core::recipe::Module<Sqlite>,
+    name: &str,
+    recipe_type: RecipeType,
+) -> anyhow::Result<String> {
+    let input = ImportInput {
+        name: name.to_owned(),
+        description: "my description".to_owned(),
+        ingredients: crate::helpers::ingredients(),
+        instructions: crate::helpers::instructions(),
+        household_size: 4,
+        cook_time: 25,
+        prep_time: 10,
+        recipe_type,
+        accepts_accompaniment: true,
+        ..Default::default()
+    };
+
+    Ok(cmd.import(input, "john", None).await?)
+}
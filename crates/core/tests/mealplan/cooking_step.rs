@@ -0,0 +1,117 @@
+use evento::Sqlite;
+use imkitchen_core::mealplan::{AdvanceCookingStep, Generate};
+use imkitchen_core::recipe::ImportInput;
+use imkitchen_types::mealplan::DaySlotStatus;
+use imkitchen_types::recipe::{Instruction, RecipeType};
+use temp_dir::TempDir;
+use time::OffsetDateTime;
+
+async fn run_subscriptions(state: &imkitchen_core::State<Sqlite>) -> anyhow::Result<()> {
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    imkitchen_core::mealplan::slot::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    imkitchen_core::recipe::query::user::create_projection()
+        .data((state.read_db.clone(), state.write_db.clone()))
+        .subscription("recipe-query")
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_advance_cooking_steps_until_completed() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    let step = |description: &str, time_next| Instruction {
+        description: description.to_owned(),
+        time_next,
+    };
+    let curry = recipe_cmd
+        .import(
+            ImportInput {
+                name: "Curry".to_owned(),
+                description: "my description".to_owned(),
+                ingredients: crate::helpers::ingredients(),
+                instructions: vec![
+                    step("Fry the onions", 10),
+                    step("Add the spices", 0),
+                    step("Simmer", 30),
+                ],
+                household_size: 4,
+                cook_time: 40,
+                prep_time: 10,
+                recipe_type: RecipeType::MainCourse,
+                ..Default::default()
+            },
+            "john",
+            None,
+        )
+        .await?;
+    run_subscriptions(&state).await?;
+
+    let today = OffsetDateTime::now_utc();
+    cmd.generate(Generate {
+        user_id: "john".to_owned(),
+        start: today.unix_timestamp() as u64,
+        days: 1,
+        household_size: 4,
+        ..Default::default()
+    })
+    .await?;
+    run_subscriptions(&state).await?;
+
+    let date = imkitchen_core::mealplan::date_to_u64(today);
+    let input = || AdvanceCookingStep {
+        user_id: "john".to_owned(),
+        date,
+        recipe_id: curry.to_owned(),
+    };
+    let now = OffsetDateTime::now_utc().unix_timestamp() as u64;
+
+    let cooking = cmd.advance_cooking_step(input()).await?;
+    run_subscriptions(&state).await?;
+    assert_eq!(cooking.status, DaySlotStatus::Cooking(0));
+    let next_step_at = cooking.next_step_at.unwrap();
+    assert!(next_step_at >= now + 10 * 60 && next_step_at <= now + 11 * 60);
+    assert_eq!(
+        cmd.day_recipes("john", date).await?[0].status,
+        DaySlotStatus::Cooking(0)
+    );
+
+    // A step without a timer waits for the cook.
+    let cooking = cmd.advance_cooking_step(input()).await?;
+    run_subscriptions(&state).await?;
+    assert_eq!(cooking.status, DaySlotStatus::Cooking(1));
+    assert_eq!(cooking.next_step_at, None);
+
+    let cooking = cmd.advance_cooking_step(input()).await?;
+    run_subscriptions(&state).await?;
+    assert_eq!(cooking.status, DaySlotStatus::Cooking(2));
+    assert!(cooking.next_step_at.unwrap() >= now + 30 * 60);
+
+    let cooking = cmd.advance_cooking_step(input()).await?;
+    run_subscriptions(&state).await?;
+    assert_eq!(cooking.status, DaySlotStatus::Completed);
+    assert_eq!(cooking.next_step_at, None);
+    assert!(cmd.day_recipes("john", date).await?[0].is_completed());
+
+    let resp = cmd.advance_cooking_step(input()).await;
+    assert_eq!(resp.unwrap_err().to_string(), "This meal is already cooked");
+
+    Ok(())
+}
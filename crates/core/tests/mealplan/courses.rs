@@ -0,0 +1,198 @@
+use evento::Sqlite;
+use imkitchen_core::State;
+use imkitchen_core::recipe::ImportInput;
+use imkitchen_types::recipe::{Ingredient, IngredientCategory, IngredientUnit, RecipeType};
+use temp_dir::TempDir;
+use time::{Duration, OffsetDateTime};
+
+#[tokio::test]
+async fn test_dinner_only() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    import_courses(&state).await?;
+
+    let planned = plan(
+        &state,
+        vec![
+            RecipeType::Appetizer,
+            RecipeType::Accompaniment,
+            RecipeType::Dessert,
+        ],
+    )
+    .await?;
+
+    assert_eq!(planned, 7);
+    assert_eq!(shopping_ingredients(&state).await?, 7);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_dinner_and_dessert() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    import_courses(&state).await?;
+
+    let planned = plan(
+        &state,
+        vec![RecipeType::Appetizer, RecipeType::Accompaniment],
+    )
+    .await?;
+
+    assert_eq!(planned, 14);
+    // Every dessert uses sugar.
+    assert_eq!(shopping_ingredients(&state).await?, 8);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_main_course_cannot_be_skipped() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    import_courses(&state).await?;
+
+    assert!(plan(&state, vec![RecipeType::MainCourse]).await.is_err());
+
+    Ok(())
+}
+
+/// Mains each with their own ingredient, sides sharing one per course.
+async fn import_courses(state: &State<Sqlite>) -> anyhow::Result<()> {
+    let cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    for i in 0..7 {
+        let ingredient = format!("ingredient {i}");
+        import_recipe(
+            &cmd,
+            &format!("Main {i}"),
+            RecipeType::MainCourse,
+            &ingredient,
+        )
+        .await?;
+    }
+
+    for i in 0..3 {
+        import_recipe(
+            &cmd,
+            &format!("Starter {i}"),
+            RecipeType::Appetizer,
+            "lettuce",
+        )
+        .await?;
+        import_recipe(
+            &cmd,
+            &format!("Side {i}"),
+            RecipeType::Accompaniment,
+            "rice",
+        )
+        .await?;
+        import_recipe(&cmd, &format!("Dessert {i}"), RecipeType::Dessert, "sugar").await?;
+    }
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    Ok(())
+}
+
+/// Generates the week and returns how many recipes were planned in it.
+async fn plan(state: &State<Sqlite>, skipped_courses: Vec<RecipeType>) -> anyhow::Result<usize> {
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+    let today = OffsetDateTime::now_utc();
+
+    cmd.generate(imkitchen_core::mealplan::Generate {
+        user_id: "john".to_owned(),
+        days: 7,
+        start: today.unix_timestamp() as u64,
+        randomize: Some(imkitchen_core::mealplan::Randomize {
+            cuisine_variety_weight: 1.0,
+            dietary_restrictions: vec![],
+            rating_weight: 0.0,
+            recency_weight: 0.0,
+            seed: None,
+            randomness: 1.0,
+        }),
+        household_size: 2,
+        skipped_courses,
+        ..Default::default()
+    })
+    .await?;
+
+    imkitchen_core::mealplan::slot::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let slots = cmd.range("john", today, today + Duration::days(6)).await?;
+    assert_eq!(slots.len(), 7);
+
+    Ok(slots
+        .iter()
+        .map(|slot| {
+            1 + [&slot.appetizer, &slot.accompaniment, &slot.dessert]
+                .into_iter()
+                .filter(|recipe| recipe.is_some())
+                .count()
+        })
+        .sum())
+}
+
+/// Generates the week's shopping list and returns how many ingredients it
+/// has.
+async fn shopping_ingredients(state: &State<Sqlite>) -> anyhow::Result<usize> {
+    let shopping = imkitchen_core::shopping::Module::new(state.clone());
+
+    imkitchen_core::shopping::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    shopping
+        .generate(
+            imkitchen_core::shopping::Generate {
+                date: imkitchen_core::mealplan::date_to_u64(OffsetDateTime::now_utc()),
+                days: 7,
+                household_size: 2,
+            },
+            "john",
+        )
+        .await?;
+
+    Ok(shopping.state("john", 2).await?.ingredients.len())
+}
+
+async fn import_recipe(
+    cmd: &imkitchen_core::recipe::Module<Sqlite>,
+    name: &str,
+    recipe_type: RecipeType,
+    ingredient: &str,
+) -> anyhow::Result<String> {
+    let input = ImportInput {
+        name: name.to_owned(),
+        description: "my description".to_owned(),
+        instructions: crate::helpers::instructions(),
+        ingredients: vec![Ingredient {
+            name: ingredient.to_owned(),
+            quantity: 100,
+            unit: Some(IngredientUnit::G),
+            category: Some(IngredientCategory::Grocery),
+        }],
+        household_size: 2,
+        cook_time: 25,
+        prep_time: 10,
+        recipe_type,
+        accepts_accompaniment: true,
+        ..Default::default()
+    };
+
+    Ok(cmd.import(input, "john", None).await?)
+}
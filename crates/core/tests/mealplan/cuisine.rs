@@ -0,0 +1,180 @@
+use evento::Sqlite;
+use imkitchen_core::State;
+use imkitchen_core::recipe::{CuisineTypeInput, ImportInput};
+use imkitchen_types::recipe::{CuisineType, RecipeType};
+use std::collections::{HashMap, HashSet};
+use temp_dir::TempDir;
+use time::{Duration, OffsetDateTime};
+
+/// Imports one main course per entry and returns the cuisine of each id.
+async fn import_main_courses(
+    state: &State<Sqlite>,
+    cuisines: &[CuisineType],
+) -> anyhow::Result<HashMap<String, CuisineType>> {
+    let cmd = imkitchen_core::recipe::Module::new(state.clone());
+    let mut recipes = HashMap::new();
+
+    for (i, cuisine_type) in cuisines.iter().enumerate() {
+        let id = cmd
+            .import(
+                ImportInput {
+                    name: format!("{cuisine_type} {i}"),
+                    description: "my description".to_owned(),
+                    ingredients: crate::helpers::ingredients(),
+                    instructions: crate::helpers::instructions(),
+                    household_size: 2,
+                    cook_time: 15,
+                    prep_time: 10,
+                    recipe_type: RecipeType::MainCourse,
+                    ..Default::default()
+                },
+                "john",
+                None,
+            )
+            .await?;
+
+        cmd.set_cuisine_type(
+            CuisineTypeInput {
+                id: id.to_owned(),
+                cuisine_type: cuisine_type.clone(),
+            },
+            "john",
+        )
+        .await?;
+
+        recipes.insert(id, cuisine_type.clone());
+    }
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    Ok(recipes)
+}
+
+/// Main course ids of the week generated with `seed`, day by day.
+async fn plan(
+    state: &State<Sqlite>,
+    seed: u64,
+    cuisine_variety_weight: f32,
+) -> anyhow::Result<Vec<String>> {
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+    let today = OffsetDateTime::now_utc();
+
+    cmd.generate(imkitchen_core::mealplan::Generate {
+        user_id: "john".to_owned(),
+        days: 7,
+        start: today.unix_timestamp() as u64,
+        randomize: Some(imkitchen_core::mealplan::Randomize {
+            cuisine_variety_weight,
+            dietary_restrictions: vec![],
+            rating_weight: 0.0,
+            recency_weight: 0.0,
+            seed: Some(seed),
+            randomness: 1.0,
+        }),
+        household_size: 2,
+        ..Default::default()
+    })
+    .await?;
+
+    imkitchen_core::mealplan::slot::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    Ok(cmd
+        .range("john", today, today + Duration::days(6))
+        .await?
+        .into_iter()
+        .map(|slot| slot.main_course.id.to_owned())
+        .collect())
+}
+
+#[tokio::test]
+async fn test_same_cuisine_is_not_planned_two_days_in_a_row() -> anyhow::Result<()> {
+    use CuisineType::*;
+
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let recipes = import_main_courses(
+        &state,
+        &[Italian, Italian, Italian, Italian, French, Thai, Mexican],
+    )
+    .await?;
+
+    for seed in 0..10 {
+        let planned = plan(&state, seed, 1.0).await?;
+        assert_eq!(planned.len(), 7);
+
+        for days in planned.windows(2) {
+            assert_ne!(recipes[&days[0]], recipes[&days[1]], "seed {seed}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Too many Italian recipes to keep them apart: some land next to each
+/// other, but the others still split them up and nothing is planned twice.
+#[tokio::test]
+async fn test_dominant_cuisine_is_spread_as_much_as_possible() -> anyhow::Result<()> {
+    use CuisineType::*;
+
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let recipes = import_main_courses(
+        &state,
+        &[Italian, Italian, Italian, Italian, Italian, French, Thai],
+    )
+    .await?;
+
+    for seed in 0..10 {
+        let planned = plan(&state, seed, 1.0).await?;
+        assert_eq!(planned.len(), 7);
+        assert_eq!(planned.iter().collect::<HashSet<_>>().len(), 7);
+
+        for days in planned.windows(2) {
+            if recipes[&days[0]] != Italian {
+                assert_eq!(recipes[&days[1]], Italian, "seed {seed}");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// A low weight only softens the spacing: the Italian recipes making up most
+/// of the queue come back the next day even though other cuisines fit.
+#[tokio::test]
+async fn test_low_cuisine_variety_weight_allows_same_cuisine_in_a_row() -> anyhow::Result<()> {
+    use CuisineType::*;
+
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let recipes = import_main_courses(
+        &state,
+        &[Italian, Italian, Italian, Italian, French, Thai, Mexican],
+    )
+    .await?;
+
+    for seed in 0..10 {
+        let planned = plan(&state, seed, 0.1).await?;
+        assert_eq!(planned.len(), 7);
+        assert_eq!(planned.iter().collect::<HashSet<_>>().len(), 7);
+        assert!(
+            planned
+                .windows(2)
+                .any(|days| recipes[&days[0]] == Italian && recipes[&days[1]] == Italian),
+            "seed {seed}"
+        );
+    }
+
+    Ok(())
+}
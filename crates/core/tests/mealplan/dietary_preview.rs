@@ -0,0 +1,248 @@
+use evento::Sqlite;
+use imkitchen_core::mealplan::dietary_preview::DietaryPreviewRow;
+use imkitchen_core::recipe::{ImportInput, ModerateInput};
+use imkitchen_types::recipe::{DietaryRestriction, ModerationAction, RecipeType};
+use std::collections::HashSet;
+use temp_dir::TempDir;
+use time::OffsetDateTime;
+
+#[tokio::test]
+async fn test_dietary_preview_matches_generation() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    use DietaryRestriction::{GlutenFree, Vegan, Vegetarian};
+    for (name, recipe_type, restrictions) in [
+        ("Curry", RecipeType::MainCourse, vec![Vegan, Vegetarian]),
+        ("Tofu bowl", RecipeType::MainCourse, vec![Vegan, GlutenFree]),
+        ("Steak", RecipeType::MainCourse, vec![GlutenFree]),
+        ("Lasagna", RecipeType::MainCourse, vec![Vegetarian]),
+        ("Sorbet", RecipeType::Dessert, vec![Vegan]),
+        ("Cheesecake", RecipeType::Dessert, vec![Vegetarian]),
+    ] {
+        import_recipe(&recipe_cmd, name, recipe_type, restrictions).await?;
+    }
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let preview = cmd.dietary_preview("john", &[Vegan]).await?;
+    assert_eq!(
+        preview,
+        vec![
+            DietaryPreviewRow {
+                recipe_type: RecipeType::MainCourse,
+                remaining: 2,
+                excluded: 2,
+            },
+            DietaryPreviewRow {
+                recipe_type: RecipeType::Dessert,
+                remaining: 1,
+                excluded: 1,
+            },
+        ]
+    );
+
+    let preview = cmd.dietary_preview("john", &[Vegan, GlutenFree]).await?;
+    assert_eq!(preview[0].remaining, 1);
+    assert_eq!(preview[1].remaining, 0);
+
+    // Nothing was saved, and a plan under the same restrictions draws from
+    // exactly the remaining main courses.
+    let today = OffsetDateTime::now_utc();
+    cmd.generate(imkitchen_core::mealplan::Generate {
+        user_id: "john".to_owned(),
+        days: 7,
+        start: today.unix_timestamp() as u64,
+        randomize: Some(imkitchen_core::mealplan::Randomize {
+            cuisine_variety_weight: 1.0,
+            dietary_restrictions: vec![Vegan],
+            rating_weight: 0.0,
+            recency_weight: 0.0,
+            seed: Some(1),
+            randomness: 1.0,
+        }),
+        household_size: 2,
+        ..Default::default()
+    })
+    .await?;
+
+    imkitchen_core::mealplan::slot::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let planned = cmd
+        .range("john", today, today + time::Duration::days(6))
+        .await?
+        .into_iter()
+        .map(|slot| slot.main_course.name.to_owned())
+        .collect::<HashSet<_>>();
+    assert_eq!(
+        planned,
+        HashSet::from(["Curry".to_owned(), "Tofu bowl".to_owned()])
+    );
+
+    Ok(())
+}
+
+/// Every active restriction must be met at once; vegetarian dishes suit a
+/// pescatarian too.
+#[tokio::test]
+async fn test_pescatarian_and_gluten_free_combine() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    use DietaryRestriction::{GlutenFree, Pescatarian, Vegetarian};
+    for (name, restrictions) in [
+        ("Grilled salmon", vec![Pescatarian, GlutenFree]),
+        ("Fish pie", vec![Pescatarian]),
+        ("Risotto", vec![Vegetarian, GlutenFree]),
+        ("Steak", vec![GlutenFree]),
+        ("Burger", vec![]),
+    ] {
+        import_recipe(&recipe_cmd, name, RecipeType::MainCourse, restrictions).await?;
+    }
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let preview = cmd
+        .dietary_preview("john", &[Pescatarian, GlutenFree])
+        .await?;
+    assert_eq!(
+        preview,
+        vec![DietaryPreviewRow {
+            recipe_type: RecipeType::MainCourse,
+            remaining: 2,
+            excluded: 3,
+        }]
+    );
+
+    let today = OffsetDateTime::now_utc();
+    cmd.generate(imkitchen_core::mealplan::Generate {
+        user_id: "john".to_owned(),
+        days: 7,
+        start: today.unix_timestamp() as u64,
+        randomize: Some(imkitchen_core::mealplan::Randomize {
+            cuisine_variety_weight: 1.0,
+            dietary_restrictions: vec![Pescatarian, GlutenFree],
+            rating_weight: 0.0,
+            recency_weight: 0.0,
+            seed: Some(1),
+            randomness: 1.0,
+        }),
+        household_size: 2,
+        ..Default::default()
+    })
+    .await?;
+
+    imkitchen_core::mealplan::slot::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let planned = cmd
+        .range("john", today, today + time::Duration::days(6))
+        .await?
+        .into_iter()
+        .map(|slot| slot.main_course.name.to_owned())
+        .collect::<HashSet<_>>();
+    assert_eq!(
+        planned,
+        HashSet::from(["Grilled salmon".to_owned(), "Risotto".to_owned()])
+    );
+
+    Ok(())
+}
+
+/// A recipe an admin hid is gone from the pool of users who saved it, but
+/// still counts for its owner.
+#[tokio::test]
+async fn test_dietary_preview_skips_hidden_recipes() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    use DietaryRestriction::Vegan;
+    let curry = import_recipe(&recipe_cmd, "Curry", RecipeType::MainCourse, vec![Vegan]).await?;
+    recipe_cmd
+        .share_to_community(&curry, "john", "John")
+        .await?;
+    recipe_cmd
+        .favorite
+        .save(&curry, "john", "albert", 10)
+        .await?;
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let expected = vec![DietaryPreviewRow {
+        recipe_type: RecipeType::MainCourse,
+        remaining: 1,
+        excluded: 0,
+    }];
+    assert_eq!(cmd.dietary_preview("albert", &[Vegan]).await?, expected);
+
+    recipe_cmd
+        .moderate(
+            ModerateInput {
+                ids: vec![curry.to_owned()],
+                action: ModerationAction::Hidden,
+            },
+            "admin",
+        )
+        .await?;
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    assert_eq!(cmd.dietary_preview("albert", &[Vegan]).await?, vec![]);
+    assert_eq!(cmd.dietary_preview("john", &[Vegan]).await?, expected);
+
+    Ok(())
+}
+
+async fn import_recipe(
+    cmd: &imkitchen_core::recipe::Module<Sqlite>,
+    name: &str,
+    recipe_type: RecipeType,
+    dietary_restrictions: Vec<DietaryRestriction>,
+) -> anyhow::Result<String> {
+    let input = ImportInput {
+        name: name.to_owned(),
+        description: "my description".to_owned(),
+        ingredients: crate::helpers::ingredients(),
+        instructions: crate::helpers::instructions(),
+        household_size: 4,
+        cook_time: 25,
+        prep_time: 10,
+        recipe_type,
+        dietary_restrictions,
+        ..Default::default()
+    };
+
+    Ok(cmd.import(input, "john", None).await?)
+}
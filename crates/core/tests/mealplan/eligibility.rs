@@ -0,0 +1,190 @@
+use imkitchen_core::mealplan::Generate;
+use imkitchen_core::mealplan::eligibility::Exclusion;
+use imkitchen_core::recipe::{EquipmentInput, ImportInput, MinHouseholdSizeInput};
+use imkitchen_types::meal_preferences::UserConstraints;
+use imkitchen_types::recipe::{Complexity, DietaryRestriction, Equipment, RecipeType};
+use temp_dir::TempDir;
+
+fn generate(user_id: &str, constraints: UserConstraints) -> Generate {
+    Generate {
+        user_id: user_id.to_owned(),
+        ..Generate::from(&constraints)
+    }
+}
+
+#[tokio::test]
+async fn test_plan_eligibility() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    let mut ids = vec![];
+    for (name, recipe_type, restrictions) in [
+        ("Gratin", RecipeType::MainCourse, vec![]),
+        (
+            "Curry",
+            RecipeType::MainCourse,
+            vec![DietaryRestriction::DairyFree],
+        ),
+        ("Lemonade", RecipeType::Beverage, vec![]),
+    ] {
+        let input = ImportInput {
+            name: name.to_owned(),
+            description: "my description".to_owned(),
+            ingredients: crate::helpers::ingredients(),
+            instructions: crate::helpers::instructions(),
+            household_size: 4,
+            cook_time: 25,
+            prep_time: 10,
+            recipe_type,
+            dietary_restrictions: restrictions,
+            ..Default::default()
+        };
+        ids.push(recipe_cmd.import(input, "john", None).await?);
+    }
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let dairy_free = generate(
+        "john",
+        UserConstraints {
+            dietary_restrictions: vec![DietaryRestriction::DairyFree],
+            ..Default::default()
+        },
+    );
+
+    let gratin = cmd.plan_eligibility(&ids[0], &dairy_free).await?;
+    assert!(!gratin.is_eligible());
+    assert_eq!(gratin.recipe_type, Some(RecipeType::MainCourse));
+    assert_eq!(
+        gratin.exclusions,
+        vec![Exclusion::DietaryRestriction(DietaryRestriction::DairyFree)]
+    );
+    assert!(
+        cmd.plan_eligibility(&ids[0], &generate("john", UserConstraints::default()))
+            .await?
+            .is_eligible()
+    );
+
+    let curry = cmd.plan_eligibility(&ids[1], &dairy_free).await?;
+    assert!(curry.is_eligible());
+
+    let lemonade = cmd.plan_eligibility(&ids[2], &dairy_free).await?;
+    assert_eq!(
+        lemonade.exclusions,
+        vec![
+            Exclusion::RecipeType(RecipeType::Beverage),
+            Exclusion::DietaryRestriction(DietaryRestriction::DairyFree),
+        ]
+    );
+
+    let other = cmd
+        .plan_eligibility(&ids[1], &generate("albert", UserConstraints::default()))
+        .await?;
+    assert_eq!(other.exclusions, vec![Exclusion::NotInPool]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_plan_eligibility_follows_household_equipment_and_day_caps() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    // Over an hour and a half in total makes it advanced.
+    let roast = recipe_cmd
+        .import(
+            ImportInput {
+                name: "Roast".to_owned(),
+                description: "my description".to_owned(),
+                ingredients: crate::helpers::ingredients(),
+                instructions: crate::helpers::instructions(),
+                household_size: 8,
+                cook_time: 100,
+                prep_time: 20,
+                recipe_type: RecipeType::MainCourse,
+                ..Default::default()
+            },
+            "john",
+            None,
+        )
+        .await?;
+    recipe_cmd
+        .set_min_household_size(
+            MinHouseholdSizeInput {
+                id: roast.to_owned(),
+                min_household_size: 6,
+            },
+            "john",
+        )
+        .await?;
+    recipe_cmd
+        .set_equipment(
+            EquipmentInput {
+                id: roast.to_owned(),
+                equipment: vec![Equipment::Oven],
+            },
+            "john",
+        )
+        .await?;
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let constraints = UserConstraints {
+        household_size: 2,
+        max_weekday_complexity: Complexity::Moderate,
+        max_weekend_complexity: Complexity::Moderate,
+        equipment_capacity: [(Equipment::Oven, 0)].into_iter().collect(),
+        time_budget: [Some(60); 7],
+        ..Default::default()
+    };
+
+    let eligibility = cmd
+        .plan_eligibility(&roast, &generate("john", constraints.clone()))
+        .await?;
+    assert_eq!(
+        eligibility.exclusions,
+        vec![
+            Exclusion::HouseholdSize(2),
+            Exclusion::Equipment(Equipment::Oven),
+            Exclusion::Complexity(Complexity::Advanced),
+            Exclusion::TimeBudget(120),
+        ]
+    );
+
+    // One free evening is enough for the caps, scaling down for the
+    // household size.
+    let mut time_budget = [Some(60); 7];
+    time_budget[5] = None;
+    let eligibility = cmd
+        .plan_eligibility(
+            &roast,
+            &generate(
+                "john",
+                UserConstraints {
+                    scale_down: true,
+                    max_weekend_complexity: Complexity::Advanced,
+                    equipment_capacity: Default::default(),
+                    time_budget,
+                    ..constraints
+                },
+            ),
+        )
+        .await?;
+    assert!(eligibility.is_eligible());
+
+    Ok(())
+}
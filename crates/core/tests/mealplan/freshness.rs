@@ -0,0 +1,105 @@
+use evento::Sqlite;
+use imkitchen_core::State;
+use imkitchen_core::mealplan::{Generate, Module};
+use imkitchen_core::recipe::ImportInput;
+use imkitchen_types::recipe::{Ingredient, IngredientCategory, IngredientUnit, RecipeType};
+use temp_dir::TempDir;
+use time::{Duration, OffsetDateTime, Weekday};
+
+async fn import_main_course(
+    cmd: &imkitchen_core::recipe::Module<Sqlite>,
+    name: &str,
+    category: IngredientCategory,
+) -> anyhow::Result<String> {
+    Ok(cmd
+        .import(
+            ImportInput {
+                name: name.to_owned(),
+                description: "my description".to_owned(),
+                instructions: crate::helpers::instructions(),
+                ingredients: vec![Ingredient {
+                    name: "main ingredient".to_owned(),
+                    quantity: 100,
+                    unit: Some(IngredientUnit::G),
+                    category: Some(category),
+                }],
+                household_size: 2,
+                cook_time: 15,
+                prep_time: 10,
+                recipe_type: RecipeType::MainCourse,
+                ..Default::default()
+            },
+            "john",
+            None,
+        )
+        .await?)
+}
+
+async fn run_subscriptions(state: &State<Sqlite>) -> anyhow::Result<()> {
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+    imkitchen_core::mealplan::slot::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    Ok(())
+}
+
+fn next_monday() -> OffsetDateTime {
+    let today = OffsetDateTime::now_utc();
+
+    today + Duration::days(7 - today.weekday().number_days_from_monday() as i64)
+}
+
+#[tokio::test]
+async fn test_perishable_main_course_is_planned_early_in_the_week() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = Module::new(state.clone());
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    for i in 0..6 {
+        import_main_course(
+            &recipe_cmd,
+            &format!("Pantry {i}"),
+            IngredientCategory::Grocery,
+        )
+        .await?;
+    }
+    // Imported last, it would be planned on Sunday without freshness.
+    let salmon = import_main_course(&recipe_cmd, "Salmon", IngredientCategory::Seafood).await?;
+    run_subscriptions(&state).await?;
+
+    let monday = next_monday();
+    cmd.generate(Generate {
+        user_id: "john".to_owned(),
+        start: monday.unix_timestamp() as u64,
+        days: 7,
+        household_size: 2,
+        ..Default::default()
+    })
+    .await?;
+    run_subscriptions(&state).await?;
+
+    let slots = cmd
+        .range("john", monday, monday + Duration::days(6))
+        .await?;
+    let slot = slots
+        .iter()
+        .find(|slot| slot.main_course.id == salmon)
+        .expect("salmon is planned");
+    let weekday = OffsetDateTime::from_unix_timestamp(slot.day as i64)?.weekday();
+
+    assert!(matches!(
+        weekday,
+        Weekday::Monday | Weekday::Tuesday | Weekday::Wednesday
+    ));
+
+    Ok(())
+}
@@ -1,5 +1,6 @@
-use evento::Sqlite;
+use evento::{AggregateEvent, EventFilter, Executor, Sqlite, cursor::Args};
 use imkitchen_core::recipe::ImportInput;
+use imkitchen_types::mealplan::Seeded;
 use imkitchen_types::recipe::RecipeType;
 use temp_dir::TempDir;
 use time::OffsetDateTime;
@@ -37,14 +38,221 @@ async fn test_random() -> anyhow::Result<()> {
         randomize: Some(imkitchen_core::mealplan::Randomize {
             cuisine_variety_weight: 1.0,
             dietary_restrictions: vec![],
+            rating_weight: 0.0,
+            recency_weight: 0.0,
+            seed: None,
+            randomness: 1.0,
         }),
         household_size: 2,
+        ..Default::default()
     })
     .await?;
 
     Ok(())
 }
 
+#[tokio::test]
+async fn test_seeded_generation_is_reproducible() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    for i in 0..20 {
+        import_recipe(&recipe_cmd, i.to_string(), RecipeType::MainCourse, "john").await?;
+    }
+
+    for i in 0..10 {
+        import_recipe(&recipe_cmd, i.to_string(), RecipeType::Dessert, "john").await?;
+    }
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let today = OffsetDateTime::now_utc();
+    let plan = |seed: u64| {
+        let cmd = cmd.clone();
+        let state = state.clone();
+
+        async move {
+            cmd.generate(imkitchen_core::mealplan::Generate {
+                user_id: "john".to_owned(),
+                days: 7,
+                start: today.unix_timestamp() as u64,
+                randomize: Some(imkitchen_core::mealplan::Randomize {
+                    cuisine_variety_weight: 1.0,
+                    dietary_restrictions: vec![],
+                    rating_weight: 0.0,
+                    recency_weight: 0.0,
+                    seed: Some(seed),
+                    randomness: 1.0,
+                }),
+                household_size: 2,
+                ..Default::default()
+            })
+            .await?;
+
+            imkitchen_core::mealplan::slot::subscription()
+                .data(state.write_db.clone())
+                .no_retry()
+                .run_once(&state.executor)
+                .await?;
+
+            let slots = cmd
+                .range("john", today, today + time::Duration::days(6))
+                .await?;
+
+            anyhow::Ok(
+                slots
+                    .into_iter()
+                    .map(|slot| {
+                        (
+                            slot.main_course.id.to_owned(),
+                            slot.dessert.map(|dessert| dessert.id.to_owned()),
+                        )
+                    })
+                    .collect::<Vec<_>>(),
+            )
+        }
+    };
+
+    let first = plan(42).await?;
+    assert_eq!(first.len(), 7);
+    assert_eq!(plan(42).await?, first);
+    assert_eq!(plan(42).await?, first);
+    assert_ne!(plan(7).await?, first);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_generation_records_its_seed() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    for i in 0..20 {
+        import_recipe(&recipe_cmd, i.to_string(), RecipeType::MainCourse, "john").await?;
+    }
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let today = OffsetDateTime::now_utc();
+    let plan = |seed: Option<u64>| {
+        let cmd = cmd.clone();
+        let state = state.clone();
+
+        async move {
+            cmd.generate(imkitchen_core::mealplan::Generate {
+                user_id: "john".to_owned(),
+                days: 7,
+                start: today.unix_timestamp() as u64,
+                randomize: Some(imkitchen_core::mealplan::Randomize {
+                    cuisine_variety_weight: 1.0,
+                    dietary_restrictions: vec![],
+                    rating_weight: 0.0,
+                    recency_weight: 0.0,
+                    seed,
+                    randomness: 1.0,
+                }),
+                household_size: 2,
+                ..Default::default()
+            })
+            .await?;
+
+            imkitchen_core::mealplan::slot::subscription()
+                .data(state.write_db.clone())
+                .no_retry()
+                .run_once(&state.executor)
+                .await?;
+
+            let slots = cmd
+                .range("john", today, today + time::Duration::days(6))
+                .await?;
+
+            anyhow::Ok(
+                slots
+                    .into_iter()
+                    .map(|slot| slot.main_course.id.to_owned())
+                    .collect::<Vec<_>>(),
+            )
+        }
+    };
+
+    let first = plan(None).await?;
+    assert_eq!(first.len(), 7);
+
+    let result = state
+        .executor
+        .read(
+            Some(vec![EventFilter::by_event(
+                Seeded::aggregate_type(),
+                Seeded::event_name(),
+            )]),
+            None,
+            Args::forward(100, None),
+        )
+        .await?;
+    assert_eq!(result.edges.len(), 1);
+
+    let seeded: Seeded = bitcode::decode(&result.edges[0].node.data)?;
+    assert_eq!(plan(Some(seeded.seed)).await?, first);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_no_favorites() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+    let generate = |user_id: &str| imkitchen_core::mealplan::Generate {
+        user_id: user_id.to_owned(),
+        days: 7,
+        start: OffsetDateTime::now_utc().unix_timestamp() as u64,
+        randomize: Some(imkitchen_core::mealplan::Randomize {
+            cuisine_variety_weight: 1.0,
+            dietary_restrictions: vec![],
+            rating_weight: 0.0,
+            recency_weight: 0.0,
+            seed: Some(1),
+            randomness: 1.0,
+        }),
+        household_size: 2,
+        ..Default::default()
+    };
+
+    import_recipe(&recipe_cmd, "1", RecipeType::Dessert, "albert").await?;
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let err = cmd.generate(generate("john")).await.unwrap_err();
+    assert!(matches!(err, imkitchen_core::Error::NoFavorites));
+
+    // Having recipes, just no main course, keeps the generic error.
+    let err = cmd.generate(generate("albert")).await.unwrap_err();
+    assert!(matches!(err, imkitchen_core::Error::User(_)));
+    assert_eq!(err.to_string(), "No main course found");
+
+    Ok(())
+}
+
 async fn import_recipe(
     cmd: &imkitchen_core::recipe::Module<Sqlite>,
     id: impl Into<String>,
@@ -54,17 +262,14 @@ async fn import_recipe(
     let id = id.into();
     let input = ImportInput {
         name: format!("recipe {id}"),
-        origin: None,
         description: "my description".to_owned(),
-        advance_prep: "".to_owned(),
-        ingredients: vec![],
-        instructions: vec![],
+        ingredients: crate::helpers::ingredients(),
+        instructions: crate::helpers::instructions(),
         household_size: 4,
         cook_time: 25,
         prep_time: 10,
         recipe_type,
-        accepts_accompaniment: false,
-        dietary_restrictions: vec![],
+        ..Default::default()
     };
 
     cmd.import(input, user_id, None).await?;
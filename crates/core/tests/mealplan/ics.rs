@@ -0,0 +1,102 @@
+use imkitchen_core::mealplan::Generate;
+use imkitchen_core::mealplan::ics::ICS_CONTENT_TYPE;
+use imkitchen_core::recipe::ImportInput;
+use imkitchen_types::recipe::RecipeType;
+use temp_dir::TempDir;
+use time::{Duration, OffsetDateTime};
+
+#[tokio::test]
+async fn test_ics_has_one_event_per_planned_day() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    for (name, advance_prep) in [
+        ("Lasagna", "Make the sauce, then chill it"),
+        ("Risotto", ""),
+        ("Curry", ""),
+    ] {
+        recipe_cmd
+            .import(
+                ImportInput {
+                    name: name.to_owned(),
+                    description: "my description".to_owned(),
+                    ingredients: crate::helpers::ingredients(),
+                    instructions: crate::helpers::instructions(),
+                    advance_prep: advance_prep.to_owned(),
+                    household_size: 2,
+                    cook_time: 25,
+                    prep_time: 10,
+                    recipe_type: RecipeType::MainCourse,
+                    ..Default::default()
+                },
+                "john",
+                None,
+            )
+            .await?;
+    }
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let today = OffsetDateTime::now_utc().replace_time(time::Time::MIDNIGHT);
+    let last = today + Duration::days(2);
+
+    let empty = cmd.ics("john", today, last).await?;
+    assert_eq!(
+        empty,
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//imkitchen//Meal plan//EN\r\n\
+         CALSCALE:GREGORIAN\r\nEND:VCALENDAR\r\n"
+    );
+
+    cmd.generate(Generate {
+        user_id: "john".to_owned(),
+        start: today.unix_timestamp() as u64,
+        days: 3,
+        household_size: 2,
+        ..Default::default()
+    })
+    .await?;
+
+    imkitchen_core::mealplan::slot::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    assert!(ICS_CONTENT_TYPE.starts_with("text/calendar"));
+    let ics = cmd.ics("john", today, last).await?;
+    let lines = ics.split("\r\n").collect::<Vec<_>>();
+
+    assert_eq!(lines.first(), Some(&"BEGIN:VCALENDAR"));
+    assert_eq!(lines[lines.len() - 2], "END:VCALENDAR");
+    assert_eq!(lines.iter().filter(|l| **l == "BEGIN:VEVENT").count(), 3);
+    assert_eq!(lines.iter().filter(|l| **l == "END:VEVENT").count(), 3);
+    assert!(lines.iter().all(|l| l.len() <= 75));
+
+    let summaries = lines
+        .iter()
+        .filter_map(|l| l.strip_prefix("SUMMARY:"))
+        .collect::<Vec<_>>();
+    assert_eq!(summaries.len(), 3);
+    for name in ["Lasagna", "Risotto", "Curry"] {
+        assert!(summaries.contains(&name));
+    }
+    assert!(lines.contains(&"DESCRIPTION:Lasagna: Make the sauce\\, then chill it"));
+
+    let day = today + Duration::days(1);
+    let start = format!(
+        "DTSTART:{}{:02}{:02}T190000Z",
+        day.year(),
+        day.month() as u8,
+        day.day()
+    );
+    assert!(lines.contains(&start.as_str()));
+
+    Ok(())
+}
@@ -0,0 +1,186 @@
+use imkitchen_core::mealplan::Generate;
+use imkitchen_core::recipe::{ImportInput, MinHouseholdSizeInput};
+use imkitchen_types::recipe::RecipeType;
+use temp_dir::TempDir;
+use time::{Duration, OffsetDateTime};
+
+#[tokio::test]
+async fn test_bulk_only_recipe_skipped_for_small_households() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    let mut ids = vec![];
+    for name in ["Whole roast chicken", "Omelette"] {
+        let id = recipe_cmd
+            .import(
+                ImportInput {
+                    name: name.to_owned(),
+                    description: "my description".to_owned(),
+                    ingredients: crate::helpers::ingredients(),
+                    instructions: crate::helpers::instructions(),
+                    household_size: 1,
+                    cook_time: 25,
+                    prep_time: 10,
+                    recipe_type: RecipeType::MainCourse,
+                    ..Default::default()
+                },
+                "john",
+                None,
+            )
+            .await?;
+        ids.push(id);
+    }
+    let roast = ids[0].to_owned();
+
+    recipe_cmd
+        .set_min_household_size(
+            MinHouseholdSizeInput {
+                id: roast.to_owned(),
+                min_household_size: 3,
+            },
+            "john",
+        )
+        .await?;
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let today = OffsetDateTime::now_utc();
+    let planned = |household_size: u16, scale_down: bool| {
+        let cmd = cmd.clone();
+        let state = state.clone();
+
+        async move {
+            cmd.generate(Generate {
+                user_id: "john".to_owned(),
+                start: today.unix_timestamp() as u64,
+                days: 7,
+                household_size,
+                scale_down,
+                ..Default::default()
+            })
+            .await?;
+
+            imkitchen_core::mealplan::slot::subscription()
+                .data(state.write_db.clone())
+                .no_retry()
+                .run_once(&state.executor)
+                .await?;
+
+            anyhow::Ok(
+                cmd.range("john", today, today + Duration::days(6))
+                    .await?
+                    .into_iter()
+                    .map(|slot| slot.main_course.id.to_owned())
+                    .collect::<Vec<_>>(),
+            )
+        }
+    };
+
+    let single = planned(1, false).await?;
+    assert_eq!(single.len(), 7);
+    assert!(!single.contains(&roast));
+
+    assert!(planned(4, false).await?.contains(&roast));
+    assert!(planned(1, true).await?.contains(&roast));
+
+    Ok(())
+}
+
+/// Recipes meant for a bigger household don't take the places of those that
+/// fit in the candidates drawn for the first week.
+#[tokio::test]
+async fn test_bulk_only_recipes_filtered_before_candidate_limit() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    // Imported first, so they sort before the omelette.
+    for pos in 0..7 {
+        let id = recipe_cmd
+            .import(
+                ImportInput {
+                    name: format!("Whole roast chicken {pos}"),
+                    description: "my description".to_owned(),
+                    ingredients: crate::helpers::ingredients(),
+                    instructions: crate::helpers::instructions(),
+                    household_size: 6,
+                    cook_time: 25,
+                    prep_time: 10,
+                    recipe_type: RecipeType::MainCourse,
+                    ..Default::default()
+                },
+                "john",
+                None,
+            )
+            .await?;
+
+        recipe_cmd
+            .set_min_household_size(
+                MinHouseholdSizeInput {
+                    id,
+                    min_household_size: 3,
+                },
+                "john",
+            )
+            .await?;
+    }
+
+    let omelette = recipe_cmd
+        .import(
+            ImportInput {
+                name: "Omelette".to_owned(),
+                description: "my description".to_owned(),
+                ingredients: crate::helpers::ingredients(),
+                instructions: crate::helpers::instructions(),
+                household_size: 1,
+                cook_time: 25,
+                prep_time: 10,
+                recipe_type: RecipeType::MainCourse,
+                ..Default::default()
+            },
+            "john",
+            None,
+        )
+        .await?;
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let today = OffsetDateTime::now_utc();
+    cmd.generate(Generate {
+        user_id: "john".to_owned(),
+        start: today.unix_timestamp() as u64,
+        days: 7,
+        household_size: 1,
+        ..Default::default()
+    })
+    .await?;
+
+    imkitchen_core::mealplan::slot::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let planned = cmd
+        .range("john", today, today + Duration::days(6))
+        .await?
+        .into_iter()
+        .map(|slot| slot.main_course.id.to_owned())
+        .collect::<Vec<_>>();
+    assert_eq!(planned, vec![omelette; 7]);
+
+    Ok(())
+}
@@ -0,0 +1,268 @@
+use std::collections::HashMap;
+
+use imkitchen_core::Coverage;
+use imkitchen_core::mealplan::{Generate, date_to_u64};
+use imkitchen_core::recipe::{ImportInput, NutritionInput, UpdateInput};
+use imkitchen_types::recipe::{Ingredient, Nutrition, RecipeType};
+use temp_dir::TempDir;
+use time::{Duration, OffsetDateTime};
+
+fn nutrition(calories: u32, protein: u32, carbs: u32, fat: u32) -> Nutrition {
+    Nutrition {
+        calories,
+        protein,
+        carbs,
+        fat,
+    }
+}
+
+#[tokio::test]
+async fn test_nutrition_per_day_and_week() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    let recipes = [
+        (
+            "Chili",
+            RecipeType::MainCourse,
+            2,
+            vec![
+                Some(nutrition(600, 40, 100, 4)),
+                Some(nutrition(800, 80, 0, 50)),
+                None,
+            ],
+        ),
+        (
+            "Salad",
+            RecipeType::MainCourse,
+            1,
+            vec![Some(nutrition(300, 10, 20, 15)), None],
+        ),
+        (
+            "Soup",
+            RecipeType::Appetizer,
+            4,
+            vec![None, Some(nutrition(400, 8, 60, 12))],
+        ),
+    ];
+
+    let mut ids = vec![];
+    for (name, recipe_type, household_size, values) in recipes {
+        let id = recipe_cmd
+            .import(
+                ImportInput {
+                    name: name.to_owned(),
+                    description: "my description".to_owned(),
+                    instructions: crate::helpers::instructions(),
+                    ingredients: values
+                        .iter()
+                        .enumerate()
+                        .map(|(i, _)| Ingredient {
+                            name: format!("ingredient {i}"),
+                            quantity: 100,
+                            unit: None,
+                            category: None,
+                        })
+                        .collect(),
+                    household_size,
+                    cook_time: 25,
+                    prep_time: 10,
+                    recipe_type,
+                    ..Default::default()
+                },
+                "john",
+                None,
+            )
+            .await?;
+        recipe_cmd
+            .set_nutrition(
+                NutritionInput {
+                    id: id.to_owned(),
+                    nutrition: values,
+                },
+                "john",
+            )
+            .await?;
+        ids.push(id);
+    }
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let today = OffsetDateTime::now_utc();
+    let tomorrow = today + Duration::days(1);
+    cmd.generate(Generate {
+        user_id: "john".to_owned(),
+        start: today.unix_timestamp() as u64,
+        days: 2,
+        household_size: 2,
+        pinned: HashMap::from([
+            (
+                (date_to_u64(today), RecipeType::MainCourse),
+                ids[0].to_owned(),
+            ),
+            (
+                (date_to_u64(today), RecipeType::Appetizer),
+                ids[2].to_owned(),
+            ),
+            (
+                (date_to_u64(tomorrow), RecipeType::MainCourse),
+                ids[1].to_owned(),
+            ),
+        ]),
+        ..Default::default()
+    })
+    .await?;
+
+    imkitchen_core::mealplan::slot::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let totals = cmd.nutrition("john", today, tomorrow).await?;
+    let days = totals.days.iter().map(|(_, n)| *n).collect::<Vec<_>>();
+
+    // One serving of chili and one of soup, then one of salad. Ingredients
+    // without data add nothing.
+    assert_eq!(
+        days,
+        vec![nutrition(800, 62, 65, 30), nutrition(300, 10, 20, 15)]
+    );
+    assert_eq!(totals.total, nutrition(1100, 72, 85, 45));
+    assert_eq!(totals.coverage, Coverage { known: 3, total: 3 });
+
+    Ok(())
+}
+
+/// Nutrition is per ingredient position, so new ingredients drop it instead
+/// of summing stale values, and the week reports the course as unknown.
+#[tokio::test]
+async fn test_nutrition_cleared_when_ingredients_change() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    let ingredient = |name: &str| Ingredient {
+        name: name.to_owned(),
+        quantity: 100,
+        unit: None,
+        category: None,
+    };
+
+    let mut ids = vec![];
+    for (name, recipe_type) in [
+        ("Chili", RecipeType::MainCourse),
+        ("Soup", RecipeType::Appetizer),
+    ] {
+        let id = recipe_cmd
+            .import(
+                ImportInput {
+                    name: name.to_owned(),
+                    description: "my description".to_owned(),
+                    instructions: crate::helpers::instructions(),
+                    ingredients: vec![ingredient("beans")],
+                    household_size: 1,
+                    cook_time: 25,
+                    prep_time: 10,
+                    recipe_type,
+                    ..Default::default()
+                },
+                "john",
+                None,
+            )
+            .await?;
+        ids.push(id);
+    }
+
+    recipe_cmd
+        .set_nutrition(
+            NutritionInput {
+                id: ids[0].to_owned(),
+                nutrition: vec![Some(nutrition(600, 40, 100, 4))],
+            },
+            "john",
+        )
+        .await?;
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let today = OffsetDateTime::now_utc();
+    cmd.generate(Generate {
+        user_id: "john".to_owned(),
+        start: today.unix_timestamp() as u64,
+        days: 1,
+        household_size: 1,
+        pinned: HashMap::from([
+            (
+                (date_to_u64(today), RecipeType::MainCourse),
+                ids[0].to_owned(),
+            ),
+            (
+                (date_to_u64(today), RecipeType::Appetizer),
+                ids[1].to_owned(),
+            ),
+        ]),
+        ..Default::default()
+    })
+    .await?;
+
+    imkitchen_core::mealplan::slot::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let totals = cmd.nutrition("john", today, today).await?;
+    assert_eq!(totals.total, nutrition(600, 40, 100, 4));
+    assert_eq!(totals.coverage, Coverage { known: 1, total: 2 });
+
+    recipe_cmd
+        .update(
+            UpdateInput {
+                id: ids[0].to_owned(),
+                recipe_type: RecipeType::MainCourse,
+                name: "Chili".to_owned(),
+                origin: None,
+                description: "my description".to_owned(),
+                household_size: 1,
+                prep_time: 10,
+                cook_time: 25,
+                ingredients: vec![ingredient("rice"), ingredient("beans")],
+                instructions: crate::helpers::instructions(),
+                dietary_restrictions: vec![],
+                accepts_accompaniment: false,
+                advance_prep: String::new(),
+            },
+            "john",
+        )
+        .await?;
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let recipe = recipe_cmd.load(&ids[0]).await?.expect("recipe");
+    assert!(recipe.nutrition.is_empty());
+
+    let totals = cmd.nutrition("john", today, today).await?;
+    assert_eq!(totals.total, Nutrition::default());
+    assert_eq!(totals.coverage, Coverage { known: 0, total: 2 });
+    assert!(totals.coverage.is_unknown());
+
+    Ok(())
+}
@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+
+use imkitchen_core::mealplan::{Generate, date_to_u64};
+use imkitchen_core::recipe::{ImportInput, MinHouseholdSizeInput};
+use imkitchen_types::meal_preferences::UserConstraints;
+use imkitchen_types::recipe::{DietaryRestriction, RecipeType};
+use temp_dir::TempDir;
+use time::{Duration, OffsetDateTime};
+
+#[tokio::test]
+async fn test_pinned_main_course_lands_on_its_day() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    let mut ids = vec![];
+    for name in ["Lasagna", "Risotto", "Curry", "Tacos"] {
+        let id = recipe_cmd
+            .import(
+                ImportInput {
+                    name: name.to_owned(),
+                    description: "my description".to_owned(),
+                    ingredients: crate::helpers::ingredients(),
+                    instructions: crate::helpers::instructions(),
+                    household_size: 2,
+                    cook_time: 25,
+                    prep_time: 10,
+                    recipe_type: RecipeType::MainCourse,
+                    ..Default::default()
+                },
+                "john",
+                None,
+            )
+            .await?;
+        ids.push(id);
+    }
+    let lasagna = ids[0].to_owned();
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let today = OffsetDateTime::now_utc();
+    let day_2 = today + Duration::days(1);
+    let generate = |pinned: HashMap<(u64, RecipeType), String>| Generate {
+        user_id: "john".to_owned(),
+        start: today.unix_timestamp() as u64,
+        days: 7,
+        household_size: 2,
+        pinned,
+        ..Default::default()
+    };
+
+    let unknown = HashMap::from([(
+        (date_to_u64(day_2), RecipeType::MainCourse),
+        "unknown".to_owned(),
+    )]);
+    assert!(cmd.generate(generate(unknown)).await.is_err());
+
+    let outside = HashMap::from([(
+        (
+            date_to_u64(today + Duration::days(30)),
+            RecipeType::MainCourse,
+        ),
+        lasagna.to_owned(),
+    )]);
+    assert!(cmd.generate(generate(outside)).await.is_err());
+
+    let pinned = HashMap::from([(
+        (date_to_u64(day_2), RecipeType::MainCourse),
+        lasagna.to_owned(),
+    )]);
+    cmd.generate(generate(pinned)).await?;
+
+    imkitchen_core::mealplan::slot::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let slots = cmd.range("john", day_2, day_2).await?;
+    assert_eq!(slots.len(), 1);
+    assert_eq!(slots[0].main_course.id, lasagna);
+
+    let planned = cmd
+        .range("john", today, today + Duration::days(6))
+        .await?
+        .into_iter()
+        .filter(|slot| slot.main_course.id == lasagna)
+        .count();
+    assert_eq!(planned, 1);
+
+    Ok(())
+}
+
+fn user_error(result: Result<(), imkitchen_core::Error>) -> String {
+    match result {
+        Err(imkitchen_core::Error::User(message)) => message,
+        other => panic!("expected a user error, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_pins_are_checked_before_planning() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    let mut ids = vec![];
+    for (name, dietary_restrictions) in [
+        ("Lasagna", vec![]),
+        ("Roast", vec![DietaryRestriction::DairyFree]),
+        ("Curry", vec![DietaryRestriction::DairyFree]),
+    ] {
+        let id = recipe_cmd
+            .import(
+                ImportInput {
+                    name: name.to_owned(),
+                    description: "my description".to_owned(),
+                    ingredients: crate::helpers::ingredients(),
+                    instructions: crate::helpers::instructions(),
+                    household_size: 2,
+                    cook_time: 25,
+                    prep_time: 10,
+                    recipe_type: RecipeType::MainCourse,
+                    dietary_restrictions,
+                    ..Default::default()
+                },
+                "john",
+                None,
+            )
+            .await?;
+        ids.push(id);
+    }
+    let (lasagna, roast) = (ids[0].to_owned(), ids[1].to_owned());
+    recipe_cmd
+        .set_min_household_size(
+            MinHouseholdSizeInput {
+                id: roast.to_owned(),
+                min_household_size: 6,
+            },
+            "john",
+        )
+        .await?;
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let today = OffsetDateTime::now_utc();
+    let day_2 = date_to_u64(today + Duration::days(1));
+    let generate = |pinned: HashMap<(u64, RecipeType), String>, scale_down| Generate {
+        user_id: "john".to_owned(),
+        start: today.unix_timestamp() as u64,
+        days: 7,
+        pinned,
+        ..Generate::from(&UserConstraints {
+            household_size: 2,
+            scale_down,
+            dietary_restrictions: vec![DietaryRestriction::DairyFree],
+            ..Default::default()
+        })
+    };
+
+    let outside = date_to_u64(today + Duration::days(30));
+    assert_eq!(
+        user_error(
+            cmd.generate(generate(
+                HashMap::from([((outside, RecipeType::MainCourse), roast.to_owned())]),
+                true,
+            ))
+            .await
+        ),
+        format!("Pinned day {outside} is outside the plan")
+    );
+
+    assert_eq!(
+        user_error(
+            cmd.generate(generate(
+                HashMap::from([((day_2, RecipeType::MainCourse), lasagna.to_owned())]),
+                true,
+            ))
+            .await
+        ),
+        format!("Pinned MainCourse on {day_2} is not DairyFree")
+    );
+
+    let pinned = HashMap::from([((day_2, RecipeType::MainCourse), roast.to_owned())]);
+    assert_eq!(
+        user_error(cmd.generate(generate(pinned.clone(), false)).await),
+        format!("Pinned MainCourse on {day_2} is meant for more than 2 people")
+    );
+    assert!(cmd.load("john").await?.is_none());
+
+    // Scaling down lets the household cook it anyway.
+    cmd.generate(generate(pinned, true)).await?;
+
+    Ok(())
+}
@@ -0,0 +1,102 @@
+use imkitchen_core::recipe::ImportInput;
+use imkitchen_types::recipe::RecipeType;
+use temp_dir::TempDir;
+use time::{Duration, OffsetDateTime};
+
+#[tokio::test]
+async fn test_recently_cooked_recipes_are_avoided() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    let mut recipes = vec![];
+    for index in 0..2 {
+        let id = recipe_cmd
+            .import(
+                ImportInput {
+                    name: format!("Main {index}"),
+                    description: "my description".to_owned(),
+                    ingredients: crate::helpers::ingredients(),
+                    instructions: crate::helpers::instructions(),
+                    household_size: 4,
+                    cook_time: 25,
+                    prep_time: 10,
+                    recipe_type: RecipeType::MainCourse,
+                    ..Default::default()
+                },
+                "john",
+                None,
+            )
+            .await?;
+        recipes.push(id);
+    }
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let today = OffsetDateTime::now_utc();
+
+    // Mirror what the `recipe-cook-count` subscription writes when a slot is
+    // completed: the first recipe yesterday, the second over a month ago.
+    for (id, cooked) in [(&recipes[0], 1), (&recipes[1], 35)] {
+        let cooked_at = today - Duration::days(cooked);
+        sqlx::query(
+            "INSERT INTO recipe_cooked (user_id, recipe_id, date, cooked_at) \
+             VALUES ('john', ?, ?, ?)",
+        )
+        .bind(id)
+        .bind(imkitchen_core::mealplan::date_to_u64(cooked_at) as i64)
+        .bind(cooked_at.unix_timestamp())
+        .execute(&state.write_db)
+        .await?;
+    }
+
+    let recent_picks = async |recency_weight: f32| {
+        let mut picks = 0;
+        for seed in 0..40 {
+            cmd.generate(imkitchen_core::mealplan::Generate {
+                user_id: "john".to_owned(),
+                days: 1,
+                start: today.unix_timestamp() as u64,
+                randomize: Some(imkitchen_core::mealplan::Randomize {
+                    cuisine_variety_weight: 1.0,
+                    dietary_restrictions: vec![],
+                    rating_weight: 0.0,
+                    recency_weight,
+                    seed: Some(seed),
+                    randomness: 1.0,
+                }),
+                household_size: 2,
+                ..Default::default()
+            })
+            .await?;
+
+            imkitchen_core::mealplan::slot::subscription()
+                .data(state.write_db.clone())
+                .no_retry()
+                .run_once(&state.executor)
+                .await?;
+
+            let slots = cmd.range("john", today, today).await?;
+            if slots[0].main_course.id == recipes[0] {
+                picks += 1;
+            }
+        }
+
+        anyhow::Ok(picks)
+    };
+
+    let unweighted = recent_picks(0.0).await?;
+    let weighted = recent_picks(1.0).await?;
+    assert!(
+        weighted * 2 < unweighted,
+        "cooked yesterday picked {weighted} times weighted, {unweighted} unweighted"
+    );
+
+    Ok(())
+}
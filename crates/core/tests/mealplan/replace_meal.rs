@@ -0,0 +1,331 @@
+use evento::Sqlite;
+use imkitchen_core::mealplan::{Generate, ReplaceMeal};
+use imkitchen_core::recipe::ImportInput;
+use imkitchen_types::recipe::RecipeType;
+use temp_dir::TempDir;
+use time::{Duration, OffsetDateTime};
+
+async fn import_recipe(
+    cmd: &imkitchen_core::recipe::Module<Sqlite>,
+    db: &sqlx::SqlitePool,
+    name: &str,
+) -> anyhow::Result<String> {
+    let id = cmd
+        .import(
+            ImportInput {
+                name: name.to_owned(),
+                description: "my description".to_owned(),
+                ingredients: crate::helpers::ingredients(),
+                instructions: crate::helpers::instructions(),
+                household_size: 4,
+                cook_time: 25,
+                prep_time: 10,
+                recipe_type: RecipeType::MainCourse,
+                ..Default::default()
+            },
+            "john",
+            None,
+        )
+        .await?;
+
+    // Mirror what the `recipe-query` projection writes at runtime.
+    sqlx::query(
+        "INSERT INTO recipe_user \
+         (id, cursor, owner_id, recipe_type, slug, name, description, ingredients, \
+          instructions, dietary_restrictions, is_shared, created_at, difficulty_score) \
+         VALUES (?, ?, 'john', 'MainCourse', ?, ?, '', X'', X'', '[]', 0, 0, 0)",
+    )
+    .bind(&id)
+    .bind(&id)
+    .bind(&id)
+    .bind(name)
+    .execute(db)
+    .await?;
+
+    Ok(id)
+}
+
+#[tokio::test]
+async fn test_replace_deleted_recipe() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    let curry = import_recipe(&recipe_cmd, &state.write_db, "Curry").await?;
+    let pasta = import_recipe(&recipe_cmd, &state.write_db, "Pasta").await?;
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let today = OffsetDateTime::now_utc();
+    cmd.generate(Generate {
+        user_id: "john".to_owned(),
+        start: today.unix_timestamp() as u64,
+        days: 1,
+        household_size: 4,
+        ..Default::default()
+    })
+    .await?;
+
+    imkitchen_core::mealplan::slot::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let slots = cmd.range("john", today, today).await?;
+    let planned = slots[0].main_course.id.to_owned();
+    let other = if planned == curry { &pasta } else { &curry };
+
+    recipe_cmd.delete(&planned, "john").await?;
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    // Mirror the `recipe-query` projection dropping its row on `Deleted`.
+    sqlx::query("DELETE FROM recipe_user WHERE id = ?")
+        .bind(&planned)
+        .execute(&state.write_db)
+        .await?;
+
+    // The calendar still loads the slot, and the missing slug flags it as removed.
+    let slots = cmd.range("john", today, today).await?;
+    assert_eq!(slots.len(), 1);
+    assert_eq!(slots[0].main_course.id, planned);
+    let slugs = recipe_cmd.slugs(vec![planned.to_owned()]).await?;
+    assert!(!slugs.contains_key(&planned));
+
+    let date = imkitchen_core::mealplan::date_to_u64(today);
+    cmd.replace_meal(ReplaceMeal {
+        user_id: "john".to_owned(),
+        date,
+        recipe_type: RecipeType::MainCourse,
+        available_minutes: None,
+        min_days_since_use: 0,
+    })
+    .await?;
+
+    imkitchen_core::mealplan::slot::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let slots = cmd.range("john", today, today).await?;
+    assert_eq!(&slots[0].main_course.id, other);
+    assert_eq!(slots[0].main_course.prep_time, 10);
+
+    // Nothing left to swap the last main course with.
+    let resp = cmd
+        .replace_meal(ReplaceMeal {
+            user_id: "john".to_owned(),
+            date,
+            recipe_type: RecipeType::MainCourse,
+            available_minutes: None,
+            min_days_since_use: 0,
+        })
+        .await;
+    assert_eq!(resp.unwrap_err().to_string(), "No replacement recipe found");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_replace_within_available_minutes() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    let mut ids = vec![];
+    for (name, prep_time, cook_time) in
+        [("Quick", 10, 20), ("Borderline", 15, 30), ("Long", 20, 40)]
+    {
+        let id = recipe_cmd
+            .import(
+                ImportInput {
+                    name: name.to_owned(),
+                    description: "my description".to_owned(),
+                    ingredients: crate::helpers::ingredients(),
+                    instructions: crate::helpers::instructions(),
+                    household_size: 4,
+                    cook_time,
+                    prep_time,
+                    recipe_type: RecipeType::MainCourse,
+                    ..Default::default()
+                },
+                "john",
+                None,
+            )
+            .await?;
+        ids.push(id);
+    }
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let today = OffsetDateTime::now_utc();
+    cmd.generate(Generate {
+        user_id: "john".to_owned(),
+        start: today.unix_timestamp() as u64,
+        days: 1,
+        household_size: 4,
+        ..Default::default()
+    })
+    .await?;
+
+    let date = imkitchen_core::mealplan::date_to_u64(today);
+    let mut picked = vec![];
+    for _ in 0..4 {
+        cmd.replace_meal(ReplaceMeal {
+            user_id: "john".to_owned(),
+            date,
+            recipe_type: RecipeType::MainCourse,
+            available_minutes: Some(45),
+            min_days_since_use: 0,
+        })
+        .await?;
+
+        imkitchen_core::mealplan::slot::subscription()
+            .data(state.write_db.clone())
+            .no_retry()
+            .run_once(&state.executor)
+            .await?;
+
+        let slots = cmd.range("john", today, today).await?;
+        picked.push(slots[0].main_course.id.to_owned());
+    }
+
+    // 60 minutes never fits; 45 exactly does, and the two fitting recipes
+    // alternate since the planned one is never its own replacement.
+    assert!(!picked.contains(&ids[2]));
+    assert!(picked.contains(&ids[0]));
+    assert!(picked.contains(&ids[1]));
+
+    let resp = cmd
+        .replace_meal(ReplaceMeal {
+            user_id: "john".to_owned(),
+            date,
+            recipe_type: RecipeType::MainCourse,
+            available_minutes: Some(25),
+            min_days_since_use: 0,
+        })
+        .await;
+    assert_eq!(resp.unwrap_err().to_string(), "No replacement recipe found");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_replace_reuses_recipes_from_other_weeks() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    for index in 0..7 {
+        import_recipe(&recipe_cmd, &state.write_db, &format!("Main {index}")).await?;
+    }
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    // Two weeks, each planning every recipe once.
+    let today = OffsetDateTime::now_utc();
+    cmd.generate(Generate {
+        user_id: "john".to_owned(),
+        start: today.unix_timestamp() as u64,
+        days: 14,
+        household_size: 4,
+        ..Default::default()
+    })
+    .await?;
+    run_subscriptions(&state).await?;
+
+    let planned = cmd
+        .range("john", today, today + Duration::days(13))
+        .await?
+        .into_iter()
+        .map(|slot| slot.main_course.id.to_owned())
+        .collect::<Vec<_>>();
+    assert_eq!(planned[..7], planned[7..]);
+
+    let date = imkitchen_core::mealplan::date_to_u64(today);
+    let replace = async |min_days_since_use: u16| {
+        cmd.replace_meal(ReplaceMeal {
+            user_id: "john".to_owned(),
+            date,
+            recipe_type: RecipeType::MainCourse,
+            available_minutes: None,
+            min_days_since_use,
+        })
+        .await?;
+        run_subscriptions(&state).await?;
+
+        let slots = cmd.range("john", today, today).await?;
+        anyhow::Ok(slots[0].main_course.id.to_owned())
+    };
+
+    // Nothing is unused, only the last recipe is planned 6 days away.
+    assert_eq!(replace(6).await?, planned[6]);
+    // Today's first recipe is now only planned a week later.
+    assert_eq!(replace(7).await?, planned[0]);
+    // None is 8 days away, the one planned the furthest comes back anyway.
+    assert_eq!(replace(8).await?, planned[6]);
+
+    // A recipe planned in no week beats any reused one.
+    let unused = import_recipe(&recipe_cmd, &state.write_db, "Main 7").await?;
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+    assert_eq!(replace(0).await?, unused);
+
+    // Only the replaced day's shopping changes.
+    let shopping = imkitchen_core::shopping::Module::new(state.clone());
+    shopping
+        .generate(
+            imkitchen_core::shopping::Generate {
+                date,
+                days: 1,
+                household_size: 4,
+            },
+            "john",
+        )
+        .await?;
+    assert_eq!(shopping.state("john", 4).await?.recipe_ids, vec![unused]);
+
+    Ok(())
+}
+
+async fn run_subscriptions(state: &imkitchen_core::State<Sqlite>) -> anyhow::Result<()> {
+    imkitchen_core::mealplan::slot::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    imkitchen_core::shopping::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,212 @@
+use evento::Sqlite;
+use imkitchen_core::State;
+use imkitchen_core::recipe::ImportInput;
+use imkitchen_types::recipe::RecipeType;
+use std::collections::{HashMap, HashSet};
+use temp_dir::TempDir;
+use time::{Duration, OffsetDateTime};
+
+/// Three desserts start over every three days while twenty main courses keep
+/// going without a repeat.
+#[tokio::test]
+async fn test_courses_rotate_independently() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    for index in 0..20 {
+        import_recipe(
+            &recipe_cmd,
+            &format!("Main {index}"),
+            RecipeType::MainCourse,
+        )
+        .await?;
+    }
+
+    for index in 0..3 {
+        import_recipe(
+            &recipe_cmd,
+            &format!("Dessert {index}"),
+            RecipeType::Dessert,
+        )
+        .await?;
+    }
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let today = OffsetDateTime::now_utc();
+
+    for seed in 0..5 {
+        cmd.generate(imkitchen_core::mealplan::Generate {
+            user_id: "john".to_owned(),
+            days: 14,
+            start: today.unix_timestamp() as u64,
+            randomize: Some(imkitchen_core::mealplan::Randomize {
+                cuisine_variety_weight: 1.0,
+                dietary_restrictions: vec![],
+                rating_weight: 0.0,
+                recency_weight: 0.0,
+                seed: Some(seed),
+                randomness: 1.0,
+            }),
+            household_size: 2,
+            ..Default::default()
+        })
+        .await?;
+
+        imkitchen_core::mealplan::slot::subscription()
+            .data(state.write_db.clone())
+            .no_retry()
+            .run_once(&state.executor)
+            .await?;
+
+        let slots = cmd.range("john", today, today + Duration::days(13)).await?;
+        assert_eq!(slots.len(), 14);
+
+        let main_courses = slots
+            .iter()
+            .map(|slot| slot.main_course.id.to_owned())
+            .collect::<HashSet<_>>();
+        assert_eq!(main_courses.len(), 14, "seed {seed}");
+
+        let desserts = slots
+            .iter()
+            .map(|slot| slot.dessert.as_ref().map(|dessert| dessert.id.to_owned()))
+            .collect::<Option<Vec<_>>>()
+            .expect("a dessert every day");
+
+        for cycle in desserts.chunks(3) {
+            assert_eq!(
+                cycle.iter().collect::<HashSet<_>>().len(),
+                cycle.len(),
+                "seed {seed}"
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// No main course comes back within four weeks when there are enough of
+/// them, even with a cuisine variety weight that would otherwise only keep
+/// part of the pool.
+#[tokio::test]
+async fn test_no_repeats_across_weeks() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    for index in 0..30 {
+        import_recipe(
+            &recipe_cmd,
+            &format!("Main {index}"),
+            RecipeType::MainCourse,
+        )
+        .await?;
+    }
+
+    let main_courses = plan_four_weeks(&state).await?;
+    assert_eq!(main_courses.len(), 28);
+    assert_eq!(main_courses.iter().collect::<HashSet<_>>().len(), 28);
+
+    Ok(())
+}
+
+/// With fewer main courses than days, each one is planned two or three times
+/// over four weeks.
+#[tokio::test]
+async fn test_no_repeats_spreads_repeats_evenly() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    for index in 0..10 {
+        import_recipe(
+            &recipe_cmd,
+            &format!("Main {index}"),
+            RecipeType::MainCourse,
+        )
+        .await?;
+    }
+
+    let mut counts = HashMap::new();
+    for id in plan_four_weeks(&state).await? {
+        *counts.entry(id).or_insert(0) += 1;
+    }
+
+    assert_eq!(counts.len(), 10);
+    assert!(counts.values().all(|count| (2..=3).contains(count)));
+
+    Ok(())
+}
+
+/// Generates 28 days without repeats and returns each day's main course.
+async fn plan_four_weeks(state: &State<Sqlite>) -> anyhow::Result<Vec<String>> {
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let today = OffsetDateTime::now_utc();
+    cmd.generate(imkitchen_core::mealplan::Generate {
+        user_id: "john".to_owned(),
+        days: 28,
+        start: today.unix_timestamp() as u64,
+        randomize: Some(imkitchen_core::mealplan::Randomize {
+            cuisine_variety_weight: 0.5,
+            dietary_restrictions: vec![],
+            rating_weight: 0.0,
+            recency_weight: 0.0,
+            seed: Some(7),
+            randomness: 1.0,
+        }),
+        household_size: 2,
+        no_repeats: true,
+        ..Default::default()
+    })
+    .await?;
+
+    imkitchen_core::mealplan::slot::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    Ok(cmd
+        .range("john", today, today + Duration::days(27))
+        .await?
+        .into_iter()
+        .map(|slot| slot.main_course.id)
+        .collect())
+}
+
+async fn import_recipe(
+    cmd: &imkitchen_core::recipe::Module<Sqlite>,
+    name: &str,
+    recipe_type: RecipeType,
+) -> anyhow::Result<String> {
+    let input = ImportInput {
+        name: name.to_owned(),
+        description: "my description".to_owned(),
+        ingredients: crate::helpers::ingredients(),
+        instructions: crate::helpers::instructions(),
+        household_size: 2,
+        cook_time: 25,
+        prep_time: 10,
+        recipe_type,
+        ..Default::default()
+    };
+
+    Ok(cmd.import(input, "john", None).await?)
+}
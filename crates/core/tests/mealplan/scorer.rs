@@ -0,0 +1,244 @@
+use imkitchen_core::mealplan::{EffortScorer, Recipe, Scorer, ScoringContext};
+use imkitchen_core::recipe::ImportInput;
+use imkitchen_types::recipe::RecipeType;
+use temp_dir::TempDir;
+use time::OffsetDateTime;
+
+struct FavorName(&'static str);
+
+impl Scorer for FavorName {
+    fn score(&self, recipe: &Recipe, _context: &ScoringContext<'_>) -> f32 {
+        if recipe.name == self.0 { 1.0 } else { -1.0 }
+    }
+}
+
+#[tokio::test]
+async fn test_custom_scorer_influences_selection() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone())
+        .with_scorer(FavorName("Main 3"), 100.0);
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    let mut recipes = vec![];
+    for index in 0..10 {
+        let id = recipe_cmd
+            .import(
+                ImportInput {
+                    name: format!("Main {index}"),
+                    description: "my description".to_owned(),
+                    ingredients: crate::helpers::ingredients(),
+                    instructions: crate::helpers::instructions(),
+                    household_size: 4,
+                    cook_time: 25,
+                    prep_time: 10,
+                    recipe_type: RecipeType::MainCourse,
+                    ..Default::default()
+                },
+                "john",
+                None,
+            )
+            .await?;
+        recipes.push(id);
+    }
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let today = OffsetDateTime::now_utc();
+    let mut picks = 0;
+    for seed in 0..20 {
+        cmd.generate(imkitchen_core::mealplan::Generate {
+            user_id: "john".to_owned(),
+            days: 1,
+            start: today.unix_timestamp() as u64,
+            randomize: Some(imkitchen_core::mealplan::Randomize {
+                cuisine_variety_weight: 1.0,
+                dietary_restrictions: vec![],
+                rating_weight: 0.0,
+                recency_weight: 0.0,
+                seed: Some(seed),
+                randomness: 1.0,
+            }),
+            household_size: 2,
+            ..Default::default()
+        })
+        .await?;
+
+        imkitchen_core::mealplan::slot::subscription()
+            .data(state.write_db.clone())
+            .no_retry()
+            .run_once(&state.executor)
+            .await?;
+
+        let slots = cmd.range("john", today, today).await?;
+        if slots[0].main_course.id == recipes[3] {
+            picks += 1;
+        }
+    }
+
+    assert!(picks >= 18, "favored recipe picked {picks} times out of 20");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_randomness_controls_variety() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd =
+        imkitchen_core::mealplan::Module::new(state.clone()).with_scorer(FavorName("Main 3"), 0.5);
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    let mut recipes = vec![];
+    for index in 0..10 {
+        let id = recipe_cmd
+            .import(
+                ImportInput {
+                    name: format!("Main {index}"),
+                    description: "my description".to_owned(),
+                    ingredients: crate::helpers::ingredients(),
+                    instructions: crate::helpers::instructions(),
+                    household_size: 4,
+                    cook_time: 25,
+                    prep_time: 10,
+                    recipe_type: RecipeType::MainCourse,
+                    ..Default::default()
+                },
+                "john",
+                None,
+            )
+            .await?;
+        recipes.push(id);
+    }
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let today = OffsetDateTime::now_utc();
+    let picks = |randomness: f32| {
+        let cmd = cmd.clone();
+        let state = state.clone();
+
+        async move {
+            let mut picks = std::collections::HashSet::new();
+            for seed in 0..20 {
+                cmd.generate(imkitchen_core::mealplan::Generate {
+                    user_id: "john".to_owned(),
+                    days: 1,
+                    start: today.unix_timestamp() as u64,
+                    randomize: Some(imkitchen_core::mealplan::Randomize {
+                        cuisine_variety_weight: 1.0,
+                        dietary_restrictions: vec![],
+                        rating_weight: 0.0,
+                        recency_weight: 0.0,
+                        seed: Some(seed),
+                        randomness,
+                    }),
+                    household_size: 2,
+                    ..Default::default()
+                })
+                .await?;
+
+                imkitchen_core::mealplan::slot::subscription()
+                    .data(state.write_db.clone())
+                    .no_retry()
+                    .run_once(&state.executor)
+                    .await?;
+
+                let slots = cmd.range("john", today, today).await?;
+                picks.insert(slots[0].main_course.id.to_owned());
+            }
+
+            anyhow::Ok(picks)
+        }
+    };
+
+    // Whatever the seed, no randomness plans the top scored recipe.
+    let picked = picks(0.0).await?;
+    assert_eq!(picked.len(), 1);
+    assert!(picked.contains(&recipes[3]));
+
+    assert!(picks(1.0).await?.len() > 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_effort_scorer_favors_simple_recipes() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone()).with_scorer(EffortScorer, 1.0);
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    let mut simple = String::new();
+    for index in 0..10 {
+        let id = recipe_cmd
+            .import(
+                ImportInput {
+                    name: format!("Main {index}"),
+                    description: "my description".to_owned(),
+                    ingredients: crate::helpers::ingredients(),
+                    instructions: crate::helpers::instructions(),
+                    household_size: 4,
+                    // An hour in total makes the others moderate.
+                    cook_time: if index == 7 { 25 } else { 50 },
+                    prep_time: 10,
+                    recipe_type: RecipeType::MainCourse,
+                    ..Default::default()
+                },
+                "john",
+                None,
+            )
+            .await?;
+        if index == 7 {
+            simple = id;
+        }
+    }
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let today = OffsetDateTime::now_utc();
+    for seed in 0..5 {
+        cmd.generate(imkitchen_core::mealplan::Generate {
+            user_id: "john".to_owned(),
+            days: 1,
+            start: today.unix_timestamp() as u64,
+            randomize: Some(imkitchen_core::mealplan::Randomize {
+                cuisine_variety_weight: 1.0,
+                dietary_restrictions: vec![],
+                rating_weight: 0.0,
+                recency_weight: 0.0,
+                seed: Some(seed),
+                randomness: 0.0,
+            }),
+            household_size: 2,
+            ..Default::default()
+        })
+        .await?;
+
+        imkitchen_core::mealplan::slot::subscription()
+            .data(state.write_db.clone())
+            .no_retry()
+            .run_once(&state.executor)
+            .await?;
+
+        let slots = cmd.range("john", today, today).await?;
+        assert_eq!(slots[0].main_course.id, simple);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,211 @@
+use evento::Sqlite;
+use imkitchen_core::State;
+use imkitchen_core::mealplan::{Generate, Module, Randomize, SkipWeek, UnskipWeek};
+use imkitchen_core::recipe::ImportInput;
+use imkitchen_types::recipe::{Ingredient, IngredientCategory, IngredientUnit, RecipeType};
+use temp_dir::TempDir;
+use time::{Duration, OffsetDateTime};
+
+async fn setup(dir: &TempDir) -> anyhow::Result<State<Sqlite>> {
+    let state = crate::helpers::setup_test_state(dir.child("db.sqlite3")).await?;
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    for index in 0..10 {
+        recipe_cmd
+            .import(
+                ImportInput {
+                    name: format!("Main {index}"),
+                    description: "my description".to_owned(),
+                    instructions: crate::helpers::instructions(),
+                    ingredients: vec![Ingredient {
+                        name: "rice".to_owned(),
+                        quantity: 100,
+                        unit: Some(IngredientUnit::G),
+                        category: Some(IngredientCategory::Grocery),
+                    }],
+                    household_size: 2,
+                    cook_time: 15,
+                    prep_time: 10,
+                    recipe_type: RecipeType::MainCourse,
+                    ..Default::default()
+                },
+                "john",
+                None,
+            )
+            .await?;
+    }
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    Ok(state)
+}
+
+async fn run_subscriptions(state: &State<Sqlite>) -> anyhow::Result<()> {
+    imkitchen_core::mealplan::slot::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+    imkitchen_core::shopping::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    Ok(())
+}
+
+fn week(monday: OffsetDateTime) -> Generate {
+    Generate {
+        user_id: "john".to_owned(),
+        start: monday.unix_timestamp() as u64,
+        days: 7,
+        randomize: Some(Randomize {
+            cuisine_variety_weight: 1.0,
+            dietary_restrictions: vec![],
+            rating_weight: 0.0,
+            recency_weight: 0.0,
+            seed: Some(42),
+            randomness: 1.0,
+        }),
+        household_size: 2,
+        ..Default::default()
+    }
+}
+
+async fn main_courses(cmd: &Module<Sqlite>, monday: OffsetDateTime) -> anyhow::Result<Vec<String>> {
+    Ok(cmd
+        .range("john", monday, monday + Duration::days(6))
+        .await?
+        .into_iter()
+        .map(|slot| slot.main_course.name.to_owned())
+        .collect())
+}
+
+fn next_monday() -> OffsetDateTime {
+    let today = OffsetDateTime::now_utc();
+
+    today + Duration::days(7 - today.weekday().number_days_from_monday() as i64)
+}
+
+#[tokio::test]
+async fn test_skipped_week_is_planned_around() -> anyhow::Result<()> {
+    let monday = next_monday();
+    let week_1 = imkitchen_core::mealplan::date_to_u64(monday);
+    let week_2 = imkitchen_core::mealplan::date_to_u64(monday + Duration::days(7));
+
+    // The same week without skipping, for reference.
+    let dir = TempDir::new()?;
+    let state = setup(&dir).await?;
+    let cmd = Module::new(state.clone());
+    cmd.generate(week(monday)).await?;
+    run_subscriptions(&state).await?;
+    let expected = main_courses(&cmd, monday).await?;
+    assert_eq!(expected.len(), 7);
+
+    let dir = TempDir::new()?;
+    let state = setup(&dir).await?;
+    let cmd = Module::new(state.clone());
+    let shopping = imkitchen_core::shopping::Module::new(state.clone());
+
+    // A day in the middle of the week skips all of it.
+    cmd.skip_week(SkipWeek {
+        user_id: "john".to_owned(),
+        date: imkitchen_core::mealplan::date_to_u64(monday + Duration::days(2)),
+    })
+    .await?;
+    run_subscriptions(&state).await?;
+    assert_eq!(cmd.skipped_weeks("john").await?, [week_1]);
+
+    cmd.generate(week(monday)).await?;
+    run_subscriptions(&state).await?;
+
+    assert!(main_courses(&cmd, monday).await?.is_empty());
+    assert!(cmd.week("john", monday).await?.skipped);
+    assert!(
+        shopping
+            .combined_weeks("john", week_1, 1, 2)
+            .await?
+            .is_empty()
+    );
+
+    // The rotation starts where it would have without the skipped week.
+    let following = monday + Duration::days(7);
+    assert_eq!(main_courses(&cmd, following).await?, expected);
+    assert!(!cmd.week("john", following).await?.skipped);
+    assert!(
+        !shopping
+            .combined_weeks("john", week_2, 1, 2)
+            .await?
+            .is_empty()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_unskipped_week_can_be_planned_again() -> anyhow::Result<()> {
+    let monday = next_monday();
+    let dir = TempDir::new()?;
+    let state = setup(&dir).await?;
+    let cmd = Module::new(state.clone());
+
+    cmd.skip_week(SkipWeek {
+        user_id: "john".to_owned(),
+        date: imkitchen_core::mealplan::date_to_u64(monday),
+    })
+    .await?;
+    run_subscriptions(&state).await?;
+
+    cmd.unskip_week(UnskipWeek {
+        user_id: "john".to_owned(),
+        date: imkitchen_core::mealplan::date_to_u64(monday + Duration::days(6)),
+    })
+    .await?;
+    run_subscriptions(&state).await?;
+    assert!(cmd.skipped_weeks("john").await?.is_empty());
+
+    cmd.generate(week(monday)).await?;
+    run_subscriptions(&state).await?;
+
+    assert_eq!(main_courses(&cmd, monday).await?.len(), 7);
+    assert!(!cmd.week("john", monday).await?.skipped);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_skipped_week_does_not_overwrite_the_following_one() -> anyhow::Result<()> {
+    let monday = next_monday();
+    let following = monday + Duration::days(7);
+    let dir = TempDir::new()?;
+    let state = setup(&dir).await?;
+    let cmd = Module::new(state.clone());
+
+    cmd.generate(week(following)).await?;
+    run_subscriptions(&state).await?;
+    let planned = main_courses(&cmd, following).await?;
+    assert_eq!(planned.len(), 7);
+
+    cmd.skip_week(SkipWeek {
+        user_id: "john".to_owned(),
+        date: imkitchen_core::mealplan::date_to_u64(monday),
+    })
+    .await?;
+    run_subscriptions(&state).await?;
+
+    let err = cmd.generate(week(monday)).await.unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "The days after the skipped week are already planned"
+    );
+
+    run_subscriptions(&state).await?;
+    assert_eq!(main_courses(&cmd, following).await?, planned);
+
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use imkitchen_core::recipe::ImportInput;
+use imkitchen_db::mealplan_snapshot::MealPlanSnapshot;
+use imkitchen_types::recipe::RecipeType;
+use sea_query::{Expr, ExprTrait, Query, SqliteQueryBuilder};
+use sea_query_sqlx::SqlxBinder;
+use temp_dir::TempDir;
+use time::OffsetDateTime;
+
+async fn snapshot_generated_at(pool: &sqlx::SqlitePool, id: &str) -> anyhow::Result<Option<u64>> {
+    let statement = Query::select()
+        .column(MealPlanSnapshot::GeneratedAt)
+        .from(MealPlanSnapshot::Table)
+        .and_where(Expr::col(MealPlanSnapshot::UserId).eq(id))
+        .to_owned();
+    let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+
+    Ok(sqlx::query_scalar_with(sqlx::AssertSqlSafe(sql), values)
+        .fetch_optional(pool)
+        .await?)
+}
+
+#[tokio::test]
+async fn test_snapshot_after_threshold() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone()).with_snapshot_threshold(3);
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    recipe_cmd
+        .import(
+            ImportInput {
+                name: "Main".to_owned(),
+                description: "my description".to_owned(),
+                ingredients: crate::helpers::ingredients(),
+                instructions: crate::helpers::instructions(),
+                household_size: 4,
+                cook_time: 25,
+                prep_time: 10,
+                recipe_type: RecipeType::MainCourse,
+                ..Default::default()
+            },
+            "john",
+            None,
+        )
+        .await?;
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let today = OffsetDateTime::now_utc().unix_timestamp() as u64;
+    let mut snapshots = vec![];
+    for _ in 0..7 {
+        cmd.generate(imkitchen_core::mealplan::Generate {
+            user_id: "john".to_owned(),
+            days: 1,
+            start: today,
+            household_size: 2,
+            ..Default::default()
+        })
+        .await?;
+
+        let plan = cmd.load("john").await?.expect("meal plan");
+        snapshots.push(snapshot_generated_at(&state.read_db, "john").await?);
+        assert_eq!(plan.user_id, "john");
+    }
+
+    assert_eq!(snapshots[0], None);
+    assert_eq!(snapshots[1], None);
+    assert!(snapshots[2].is_some());
+    assert_eq!(snapshots[3], snapshots[2]);
+    assert_eq!(snapshots[4], snapshots[2]);
+    assert!(snapshots[5].is_some());
+
+    assert_eq!(snapshots[6], snapshots[5]);
+
+    // Restored from the sixth generation's snapshot, replaying only the last.
+    let plan = cmd.load("john").await?.expect("meal plan");
+    assert_eq!(plan.pending_events, 1);
+    assert!(Some(plan.generated_at) >= snapshots[5]);
+
+    Ok(())
+}
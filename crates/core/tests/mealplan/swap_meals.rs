@@ -0,0 +1,158 @@
+use evento::Sqlite;
+use imkitchen_core::State;
+use imkitchen_core::mealplan::{Generate, SwapMeals, date_to_u64};
+use imkitchen_core::recipe::ImportInput;
+use imkitchen_types::recipe::{Ingredient, IngredientCategory, IngredientUnit, RecipeType};
+use temp_dir::TempDir;
+use time::{Duration, OffsetDateTime};
+
+#[tokio::test]
+async fn test_swap_main_courses() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    for (name, ingredient) in [("Curry", "rice"), ("Pasta", "spaghetti"), ("Soup", "leek")] {
+        recipe_cmd
+            .import(
+                ImportInput {
+                    name: name.to_owned(),
+                    description: "my description".to_owned(),
+                    instructions: crate::helpers::instructions(),
+                    ingredients: vec![Ingredient {
+                        name: ingredient.to_owned(),
+                        quantity: 100,
+                        unit: Some(IngredientUnit::G),
+                        category: Some(IngredientCategory::Grocery),
+                    }],
+                    household_size: 2,
+                    cook_time: 25,
+                    prep_time: 10,
+                    recipe_type: RecipeType::MainCourse,
+                    ..Default::default()
+                },
+                "john",
+                None,
+            )
+            .await?;
+    }
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let today = OffsetDateTime::now_utc();
+    let last_day = today + Duration::days(2);
+    cmd.generate(Generate {
+        user_id: "john".to_owned(),
+        start: today.unix_timestamp() as u64,
+        days: 3,
+        household_size: 2,
+        ..Default::default()
+    })
+    .await?;
+
+    run_subscriptions(&state).await?;
+
+    let before = cmd.range("john", today, last_day).await?;
+    let shopping_before = shopping_list(&state).await?;
+
+    cmd.swap_meals(SwapMeals {
+        user_id: "john".to_owned(),
+        date: date_to_u64(today),
+        recipe_type: RecipeType::MainCourse,
+        other_date: date_to_u64(last_day),
+    })
+    .await?;
+
+    run_subscriptions(&state).await?;
+
+    let after = cmd.range("john", today, last_day).await?;
+    assert_eq!(after[0].main_course.id, before[2].main_course.id);
+    assert_eq!(after[1].main_course.id, before[1].main_course.id);
+    assert_eq!(after[2].main_course.id, before[0].main_course.id);
+    assert_eq!(shopping_list(&state).await?, shopping_before);
+
+    // Only the last day's list now buys what was planned for today.
+    let shopping = imkitchen_core::shopping::Module::new(state.clone());
+    shopping
+        .generate(
+            imkitchen_core::shopping::Generate {
+                date: date_to_u64(last_day),
+                days: 1,
+                household_size: 2,
+            },
+            "john",
+        )
+        .await?;
+    assert_eq!(
+        shopping.state("john", 2).await?.recipe_ids,
+        vec![before[0].main_course.id.to_owned()]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_swap_rejects_the_same_day() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+    let today = date_to_u64(OffsetDateTime::now_utc());
+
+    let resp = cmd
+        .swap_meals(SwapMeals {
+            user_id: "john".to_owned(),
+            date: today,
+            recipe_type: RecipeType::MainCourse,
+            other_date: today,
+        })
+        .await;
+    assert_eq!(
+        resp.unwrap_err().to_string(),
+        "Pick two different days to swap"
+    );
+
+    Ok(())
+}
+
+async fn run_subscriptions(state: &State<Sqlite>) -> anyhow::Result<()> {
+    imkitchen_core::mealplan::slot::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    imkitchen_core::shopping::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    Ok(())
+}
+
+/// The week's shopping list, ingredients sorted by name.
+async fn shopping_list(state: &State<Sqlite>) -> anyhow::Result<Vec<Ingredient>> {
+    let shopping = imkitchen_core::shopping::Module::new(state.clone());
+    shopping
+        .generate(
+            imkitchen_core::shopping::Generate {
+                date: date_to_u64(OffsetDateTime::now_utc()),
+                days: 3,
+                household_size: 2,
+            },
+            "john",
+        )
+        .await?;
+
+    let mut ingredients = shopping.state("john", 2).await?.ingredients;
+    ingredients.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(ingredients)
+}
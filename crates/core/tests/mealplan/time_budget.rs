@@ -0,0 +1,124 @@
+use evento::Sqlite;
+use imkitchen_core::State;
+use imkitchen_core::mealplan::{Generate, Module};
+use imkitchen_core::recipe::ImportInput;
+use imkitchen_types::recipe::{Ingredient, IngredientCategory, IngredientUnit, RecipeType};
+use temp_dir::TempDir;
+use time::{Duration, OffsetDateTime, Weekday};
+
+async fn import_main_course(
+    cmd: &imkitchen_core::recipe::Module<Sqlite>,
+    name: &str,
+    prep_time: u16,
+    cook_time: u16,
+) -> anyhow::Result<String> {
+    Ok(cmd
+        .import(
+            ImportInput {
+                name: name.to_owned(),
+                description: "my description".to_owned(),
+                instructions: crate::helpers::instructions(),
+                ingredients: vec![Ingredient {
+                    name: "main ingredient".to_owned(),
+                    quantity: 100,
+                    unit: Some(IngredientUnit::G),
+                    category: Some(IngredientCategory::Grocery),
+                }],
+                household_size: 2,
+                cook_time,
+                prep_time,
+                recipe_type: RecipeType::MainCourse,
+                ..Default::default()
+            },
+            "john",
+            None,
+        )
+        .await?)
+}
+
+async fn run_subscriptions(state: &State<Sqlite>) -> anyhow::Result<()> {
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+    imkitchen_core::mealplan::slot::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    Ok(())
+}
+
+fn next_monday() -> OffsetDateTime {
+    let today = OffsetDateTime::now_utc();
+
+    today + Duration::days(7 - today.weekday().number_days_from_monday() as i64)
+}
+
+fn week(monday: OffsetDateTime) -> Generate {
+    Generate {
+        user_id: "john".to_owned(),
+        start: monday.unix_timestamp() as u64,
+        days: 7,
+        household_size: 2,
+        // 20 minutes on Tuesday.
+        time_budget: [None, Some(20), None, None, None, None, None],
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_generate_keeps_to_the_day_time_budget() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = Module::new(state.clone());
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    for i in 0..5 {
+        import_main_course(&recipe_cmd, &format!("Stew {i}"), 20, 40).await?;
+    }
+    let omelette = import_main_course(&recipe_cmd, "Omelette", 5, 10).await?;
+    run_subscriptions(&state).await?;
+
+    let monday = next_monday();
+    cmd.generate(week(monday)).await?;
+    run_subscriptions(&state).await?;
+
+    let tuesday = cmd
+        .range("john", monday, monday + Duration::days(6))
+        .await?
+        .into_iter()
+        .find(|slot| {
+            OffsetDateTime::from_unix_timestamp(slot.day as i64)
+                .is_ok_and(|day| day.weekday() == Weekday::Tuesday)
+        })
+        .expect("tuesday is planned");
+    assert_eq!(tuesday.main_course.id, omelette);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_generate_reports_day_over_its_time_budget() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = Module::new(state.clone());
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    for i in 0..3 {
+        import_main_course(&recipe_cmd, &format!("Stew {i}"), 20, 40).await?;
+    }
+    run_subscriptions(&state).await?;
+
+    let resp = cmd.generate(week(next_monday())).await;
+    assert_eq!(
+        resp.err().map(|e| e.to_string()),
+        Some("No main course fits the 20 minutes available on Tuesday".to_owned())
+    );
+
+    Ok(())
+}
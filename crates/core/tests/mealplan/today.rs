@@ -0,0 +1,75 @@
+use imkitchen_core::mealplan::Generate;
+use imkitchen_core::recipe::ImportInput;
+use imkitchen_types::mealplan::DaySlotStatus;
+use imkitchen_types::recipe::RecipeType;
+use temp_dir::TempDir;
+use time::macros::time;
+
+/// UTC+14 and UTC-11: their local dates always differ.
+const EAST: &str = "Pacific/Kiritimati";
+const WEST: &str = "Pacific/Pago_Pago";
+
+#[tokio::test]
+async fn test_today_in_user_timezone() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    recipe_cmd
+        .import(
+            ImportInput {
+                name: "Chicken tikka".to_owned(),
+                description: "my description".to_owned(),
+                ingredients: crate::helpers::ingredients(),
+                instructions: crate::helpers::instructions(),
+                advance_prep: "Marinate overnight".to_owned(),
+                household_size: 4,
+                cook_time: 25,
+                prep_time: 10,
+                recipe_type: RecipeType::MainCourse,
+                ..Default::default()
+            },
+            "john",
+            None,
+        )
+        .await?;
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    assert!(cmd.today("john", EAST).await?.is_none());
+
+    // Plan only the day it currently is in the east.
+    let east_today = imkitchen_core::mealplan::now(EAST);
+    let start = east_today.date().with_time(time!(12:00)).assume_utc();
+    cmd.generate(Generate {
+        user_id: "john".to_owned(),
+        start: start.unix_timestamp() as u64,
+        days: 1,
+        household_size: 4,
+        ..Default::default()
+    })
+    .await?;
+
+    imkitchen_core::mealplan::slot::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let slot = cmd.today("john", EAST).await?.expect("planned today");
+    assert_eq!(slot.main_course.name, "Chicken tikka");
+    assert_eq!(slot.main_course.status, DaySlotStatus::Idle);
+    assert_eq!(slot.main_course.advance_prep, "Marinate overnight");
+    assert_eq!(slot.prep_time(), slot.main_course.total_prep_time());
+
+    // Same instant, but it is a different day in the west.
+    assert!(cmd.today("john", WEST).await?.is_none());
+
+    Ok(())
+}
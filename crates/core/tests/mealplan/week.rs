@@ -0,0 +1,82 @@
+use imkitchen_core::mealplan::Generate;
+use imkitchen_core::recipe::ImportInput;
+use imkitchen_types::recipe::RecipeType;
+use temp_dir::TempDir;
+use time::OffsetDateTime;
+
+#[tokio::test]
+async fn test_week_as_json() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::mealplan::Module::new(state.clone());
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    let curry = recipe_cmd
+        .import(
+            ImportInput {
+                name: "Curry".to_owned(),
+                description: "my description".to_owned(),
+                ingredients: crate::helpers::ingredients(),
+                instructions: crate::helpers::instructions(),
+                household_size: 2,
+                cook_time: 25,
+                prep_time: 10,
+                recipe_type: RecipeType::MainCourse,
+                ..Default::default()
+            },
+            "john",
+            None,
+        )
+        .await?;
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let today = OffsetDateTime::now_utc().replace_time(time::Time::MIDNIGHT);
+    cmd.generate(Generate {
+        user_id: "john".to_owned(),
+        start: today.unix_timestamp() as u64,
+        days: 1,
+        household_size: 2,
+        ..Default::default()
+    })
+    .await?;
+
+    imkitchen_core::mealplan::slot::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let date = imkitchen_core::mealplan::date_to_u64(today);
+    let week = serde_json::to_value(cmd.week("john", today).await?)?;
+    assert_eq!(
+        week,
+        serde_json::json!({
+            "start": date,
+            "days": [{
+                "date": date,
+                "household_size": 2,
+                "courses": [{
+                    "recipe_type": "MainCourse",
+                    "id": curry,
+                    "name": "Curry",
+                    "prep_time": 10,
+                    "cook_time": 25,
+                    "status": "Idle",
+                    "step": null,
+                }],
+            }],
+        })
+    );
+
+    // Another user only ever sees their own plan.
+    let week = cmd.week("albert", today).await?;
+    assert!(week.days.is_empty());
+
+    Ok(())
+}
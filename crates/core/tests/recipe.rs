@@ -1,8 +1,44 @@
+#[path = "recipe/comment.rs"]
+mod comment;
+#[path = "recipe/cook_sheet.rs"]
+mod cook_sheet;
+#[path = "recipe/cookable.rs"]
+mod cookable;
+#[path = "recipe/create.rs"]
+mod create;
 #[path = "recipe/delete.rs"]
 mod delete;
+#[path = "recipe/display_order.rs"]
+mod display_order;
+#[path = "recipe/duplicate.rs"]
+mod duplicate;
+#[path = "recipe/export.rs"]
+mod export;
+#[path = "recipe/favorite.rs"]
+mod favorite;
 #[path = "recipe/helpers/mod.rs"]
 mod helpers;
+#[path = "recipe/import_json.rs"]
+mod import_json;
+#[path = "recipe/import_mapped.rs"]
+mod import_mapped;
+#[path = "recipe/import_schema_org.rs"]
+mod import_schema_org;
+#[path = "recipe/moderate.rs"]
+mod moderate;
+#[path = "recipe/most_cooked.rs"]
+mod most_cooked;
+#[path = "recipe/rating.rs"]
+mod rating;
 #[path = "recipe/relevance.rs"]
 mod relevance;
+#[path = "recipe/search.rs"]
+mod search;
+#[path = "recipe/similar.rs"]
+mod similar;
+#[path = "recipe/tags.rs"]
+mod tags;
 #[path = "recipe/update.rs"]
 mod update;
+#[path = "recipe/validate.rs"]
+mod validate;
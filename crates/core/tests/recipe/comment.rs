@@ -0,0 +1,354 @@
+use evento::Sqlite;
+use evento::cursor::{Args, ReadResult, Value};
+use imkitchen_core::recipe::comment::ResolveReportsInput;
+use imkitchen_core::recipe::query::comment::{CommentThread, CommentThreadsQuery, CommentView};
+use imkitchen_core::recipe::{ImportInput, Module};
+use imkitchen_types::comment::{FlagReason, ReportResolution};
+use imkitchen_types::recipe::RecipeType;
+use temp_dir::TempDir;
+
+async fn run_subscription(state: &imkitchen_core::State<Sqlite>) -> anyhow::Result<()> {
+    imkitchen_core::recipe::query::comment::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    Ok(())
+}
+
+async fn import_recipe(cmd: &Module<Sqlite>, name: &str) -> anyhow::Result<String> {
+    let input = ImportInput {
+        name: name.to_owned(),
+        description: "my description".to_owned(),
+        ingredients: crate::helpers::ingredients(),
+        instructions: crate::helpers::instructions(),
+        household_size: 4,
+        cook_time: 25,
+        prep_time: 10,
+        recipe_type: RecipeType::MainCourse,
+        ..Default::default()
+    };
+
+    Ok(cmd.import(input, "john", None).await?)
+}
+
+fn bodies(comments: &[CommentView]) -> Vec<&str> {
+    comments.iter().map(|c| c.body.as_str()).collect()
+}
+
+fn top_level(threads: ReadResult<CommentThread>) -> Vec<String> {
+    threads
+        .edges
+        .into_iter()
+        .map(|edge| edge.node.comment.body)
+        .collect()
+}
+
+/// Replies nest under their top level comment in the order they were
+/// written, replies to replies included, and only the first few show.
+#[tokio::test]
+async fn test_comment_threads() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = Module::new(state.clone());
+
+    let curry = import_recipe(&cmd, "Curry").await?;
+    cmd.share_to_community(&curry, "john", "John").await?;
+
+    let first = cmd.comment.add(&curry, "alice", "Lovely").await?;
+    let second = cmd.comment.add(&curry, "bob", "Too spicy").await?;
+    let reply = cmd.comment.reply(&first, "john", "Thanks!").await?;
+    cmd.comment.reply(&reply, "alice", "You're welcome").await?;
+    cmd.comment.reply(&first, "bob", "Agreed").await?;
+    cmd.comment.reply(&second, "john", "Use less chili").await?;
+    run_subscription(&state).await?;
+
+    let threads = cmd
+        .comment_threads(CommentThreadsQuery {
+            recipe_id: curry.to_owned(),
+            reply_limit: 2,
+            args: Args::forward(10, None),
+        })
+        .await?;
+
+    let threads = threads
+        .edges
+        .into_iter()
+        .map(|edge| edge.node)
+        .collect::<Vec<_>>();
+    assert_eq!(threads.len(), 2);
+
+    assert_eq!(threads[0].comment.body, "Too spicy");
+    assert_eq!(bodies(&threads[0].replies), vec!["Use less chili"]);
+    assert!(threads[0].more_replies.is_none());
+
+    assert_eq!(threads[1].comment.body, "Lovely");
+    assert_eq!(
+        bodies(&threads[1].replies),
+        vec!["Thanks!", "You're welcome"]
+    );
+    assert!(
+        threads[1]
+            .replies
+            .iter()
+            .all(|r| r.parent_id.as_deref() == Some(first.as_str()))
+    );
+
+    let more = cmd
+        .comment_replies(&first, Args::forward(10, threads[1].more_replies.clone()))
+        .await?;
+    let more = more.edges.into_iter().map(|e| e.node).collect::<Vec<_>>();
+    assert_eq!(bodies(&more), vec!["Agreed"]);
+
+    Ok(())
+}
+
+/// Walking the pages of top level comments sees each one once.
+#[tokio::test]
+async fn test_comment_threads_pagination() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = Module::new(state.clone());
+
+    let curry = import_recipe(&cmd, "Curry").await?;
+    cmd.share_to_community(&curry, "john", "John").await?;
+
+    for index in 0..5 {
+        let id = cmd
+            .comment
+            .add(&curry, "alice", format!("Comment {index}"))
+            .await?;
+        cmd.comment.reply(&id, "bob", "Reply").await?;
+    }
+    run_subscription(&state).await?;
+
+    let mut seen = vec![];
+    let mut after: Option<Value> = None;
+    loop {
+        let page = cmd
+            .comment_threads(CommentThreadsQuery {
+                recipe_id: curry.to_owned(),
+                reply_limit: 1,
+                args: Args::forward(2, after.clone()),
+            })
+            .await?;
+
+        assert!(page.edges.len() <= 2);
+        seen.extend(page.edges.into_iter().map(|edge| edge.node.comment.body));
+
+        if !page.page_info.has_next_page {
+            break;
+        }
+        after = page.page_info.end_cursor;
+    }
+
+    assert_eq!(
+        seen,
+        (0..5)
+            .rev()
+            .map(|index| format!("Comment {index}"))
+            .collect::<Vec<_>>()
+    );
+
+    Ok(())
+}
+
+/// A comment stays up until enough distinct users flag it, then drops out
+/// of the thread but is still there for admins to review.
+#[tokio::test]
+async fn test_comment_flagging() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = Module::new(state.clone());
+
+    let curry = import_recipe(&cmd, "Curry").await?;
+    cmd.share_to_community(&curry, "john", "John").await?;
+
+    let spam = cmd.comment.add(&curry, "alice", "Buy cheap pans").await?;
+    cmd.comment.add(&curry, "bob", "Lovely").await?;
+
+    let resp = cmd.comment.flag(&spam, "alice", FlagReason::Other).await;
+    assert_eq!(
+        resp.err().map(|e| e.to_string()),
+        Some("You can't flag your own comment".to_owned())
+    );
+
+    cmd.comment.flag(&spam, "bob", FlagReason::Spam).await?;
+    cmd.comment
+        .flag(&spam, "bob", FlagReason::Offensive)
+        .await?;
+    cmd.comment.flag(&spam, "carol", FlagReason::Spam).await?;
+    run_subscription(&state).await?;
+
+    let threads = cmd
+        .comment_threads(CommentThreadsQuery {
+            recipe_id: curry.to_owned(),
+            reply_limit: 3,
+            args: Args::forward(10, None),
+        })
+        .await?;
+    assert_eq!(top_level(threads), vec!["Lovely", "Buy cheap pans"]);
+
+    let comment = cmd.comment.load(&spam).await?.unwrap();
+    assert!(!comment.is_hidden);
+
+    cmd.comment
+        .flag(&spam, "dave", FlagReason::OffTopic)
+        .await?;
+    run_subscription(&state).await?;
+
+    let threads = cmd
+        .comment_threads(CommentThreadsQuery {
+            recipe_id: curry.to_owned(),
+            reply_limit: 3,
+            args: Args::forward(10, None),
+        })
+        .await?;
+    assert_eq!(top_level(threads), vec!["Lovely"]);
+
+    let comment = cmd.find_comment(&spam).await?.unwrap();
+    assert_eq!(comment.body, "Buy cheap pans");
+    assert_eq!(comment.flag_count, 3);
+    assert!(comment.is_hidden);
+
+    let flagged = cmd.filter_flagged_comments(Args::forward(10, None)).await?;
+    let flagged = flagged
+        .edges
+        .into_iter()
+        .map(|edge| edge.node.id)
+        .collect::<Vec<_>>();
+    assert_eq!(flagged, vec![spam.to_owned()]);
+
+    let flags = cmd.comment_flags(&spam).await?;
+    let mut flags = flags
+        .iter()
+        .map(|flag| (flag.user_id.as_str(), flag.reason.0))
+        .collect::<Vec<_>>();
+    flags.sort_by_key(|(user_id, _)| *user_id);
+    assert_eq!(
+        flags,
+        vec![
+            ("bob", FlagReason::Spam),
+            ("carol", FlagReason::Spam),
+            ("dave", FlagReason::OffTopic),
+        ]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_comment_validation() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = Module::new(state.clone());
+
+    let curry = import_recipe(&cmd, "Curry").await?;
+
+    let resp = cmd.comment.add(&curry, "alice", "Lovely").await;
+    assert_eq!(
+        resp.err().map(|e| e.to_string()),
+        Some("recipe not found".to_owned())
+    );
+
+    cmd.share_to_community(&curry, "john", "John").await?;
+
+    let resp = cmd.comment.add(&curry, "alice", "  ").await;
+    assert_eq!(
+        resp.err().map(|e| e.to_string()),
+        Some("Comment can't be empty".to_owned())
+    );
+
+    let resp = cmd.comment.reply("unknown", "alice", "Hi").await;
+    assert_eq!(
+        resp.err().map(|e| e.to_string()),
+        Some("comment not found".to_owned())
+    );
+
+    Ok(())
+}
+
+/// Dismissed reports bring a hidden comment back, removed ones keep it
+/// hidden; both leave the review queue.
+#[tokio::test]
+async fn test_resolve_comment_reports() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = Module::new(state.clone());
+
+    let curry = import_recipe(&cmd, "Curry").await?;
+    cmd.share_to_community(&curry, "john", "John").await?;
+
+    let harsh = cmd.comment.add(&curry, "alice", "Too spicy").await?;
+    let spam = cmd.comment.add(&curry, "bob", "Buy cheap pans").await?;
+    let clean = cmd.comment.add(&curry, "carol", "Lovely").await?;
+
+    for user in ["carol", "dave", "erin"] {
+        cmd.comment
+            .flag(&harsh, user, FlagReason::Offensive)
+            .await?;
+    }
+    cmd.comment.flag(&spam, "carol", FlagReason::Spam).await?;
+    run_subscription(&state).await?;
+
+    let flagged = cmd.filter_flagged_comments(Args::forward(10, None)).await?;
+    assert_eq!(flagged.edges.len(), 2);
+
+    let resolved = cmd
+        .comment
+        .resolve_reports(
+            ResolveReportsInput {
+                ids: vec![harsh.to_owned(), clean.to_owned(), "unknown".to_owned()],
+                resolution: ReportResolution::Dismissed,
+            },
+            "admin",
+        )
+        .await?;
+    assert_eq!(resolved, 1);
+
+    let resolved = cmd
+        .comment
+        .resolve_reports(
+            ResolveReportsInput {
+                ids: vec![spam.to_owned()],
+                resolution: ReportResolution::Removed,
+            },
+            "admin",
+        )
+        .await?;
+    assert_eq!(resolved, 1);
+    run_subscription(&state).await?;
+
+    let flagged = cmd.filter_flagged_comments(Args::forward(10, None)).await?;
+    assert!(flagged.edges.is_empty());
+    assert!(cmd.comment_flags(&harsh).await?.is_empty());
+
+    let threads = cmd
+        .comment_threads(CommentThreadsQuery {
+            recipe_id: curry.to_owned(),
+            reply_limit: 3,
+            args: Args::forward(10, None),
+        })
+        .await?;
+    assert_eq!(top_level(threads), vec!["Lovely", "Too spicy"]);
+
+    let comment = cmd.comment.load(&spam).await?.unwrap();
+    assert!(comment.is_hidden);
+    assert!(comment.flagged_by.is_empty());
+
+    // Users that flagged before the dismissal can report the comment again.
+    cmd.comment.flag(&harsh, "carol", FlagReason::Spam).await?;
+    run_subscription(&state).await?;
+
+    let comment = cmd.find_comment(&harsh).await?.unwrap();
+    assert_eq!(comment.flag_count, 1);
+    assert!(!comment.is_hidden);
+
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use imkitchen_core::recipe::ImportInput;
+use imkitchen_types::recipe::{
+    Ingredient, IngredientCategory, IngredientUnit, Instruction, RecipeType,
+};
+use temp_dir::TempDir;
+
+fn ingredient(name: &str, quantity: u32, category: Option<IngredientCategory>) -> Ingredient {
+    Ingredient {
+        name: name.to_owned(),
+        quantity,
+        unit: Some(IngredientUnit::G),
+        category,
+    }
+}
+
+#[tokio::test]
+async fn test_cook_sheet_scales_and_groups_ingredients() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    let id = cmd
+        .import(
+            ImportInput {
+                recipe_type: RecipeType::MainCourse,
+                name: "Fish pie".to_owned(),
+                description: "my description".to_owned(),
+                household_size: 2,
+                prep_time: 10,
+                cook_time: 25,
+                ingredients: vec![
+                    ingredient("butter", 50, Some(IngredientCategory::DairyAndEggs)),
+                    ingredient("salt", 5, None),
+                    ingredient("cod", 300, Some(IngredientCategory::Seafood)),
+                    ingredient(
+                        "potatoes",
+                        500,
+                        Some(IngredientCategory::FruitsAndVegetables),
+                    ),
+                    ingredient("leeks", 200, Some(IngredientCategory::FruitsAndVegetables)),
+                ],
+                instructions: vec![
+                    Instruction {
+                        time_next: 0,
+                        description: "Boil the potatoes".to_owned(),
+                    },
+                    Instruction {
+                        time_next: 0,
+                        description: "Poach the cod".to_owned(),
+                    },
+                    Instruction {
+                        time_next: 0,
+                        description: "Bake".to_owned(),
+                    },
+                ],
+                ..Default::default()
+            },
+            "john",
+            None,
+        )
+        .await?;
+
+    imkitchen_core::recipe::query::user::create_projection()
+        .data((state.read_db.clone(), state.write_db.clone()))
+        .subscription("recipe-query")
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let recipe = cmd.user(&id).await?.expect("recipe to be projected");
+    let sheet = recipe.cook_sheet(4);
+    assert_eq!(sheet.servings, 4);
+
+    let aisles = sheet
+        .aisles
+        .iter()
+        .map(|(category, ingredients)| {
+            (
+                category.clone(),
+                ingredients
+                    .iter()
+                    .map(|i| (i.name.as_str(), i.quantity))
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        aisles,
+        vec![
+            (
+                Some(IngredientCategory::FruitsAndVegetables),
+                vec![("leeks", 400), ("potatoes", 1000)]
+            ),
+            (Some(IngredientCategory::Seafood), vec![("cod", 600)]),
+            (
+                Some(IngredientCategory::DairyAndEggs),
+                vec![("butter", 100)]
+            ),
+            (None, vec![("salt", 10)]),
+        ]
+    );
+
+    assert_eq!(
+        sheet.steps,
+        vec![
+            (1, "Boil the potatoes".to_owned()),
+            (2, "Poach the cod".to_owned()),
+            (3, "Bake".to_owned()),
+        ]
+    );
+
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use imkitchen_types::recipe::Ingredient;
+use temp_dir::TempDir;
+
+/// Mirrors the `recipe-query` projection row, with the ingredients the
+/// pantry lookup reads.
+async fn seed(
+    db: &sqlx::SqlitePool,
+    id: &str,
+    owner_id: &str,
+    ingredients: &[&str],
+) -> anyhow::Result<()> {
+    let ingredients = ingredients
+        .iter()
+        .map(|name| Ingredient {
+            name: name.to_string(),
+            quantity: 1,
+            unit: None,
+            category: None,
+        })
+        .collect::<Vec<_>>();
+
+    sqlx::query(
+        "INSERT INTO recipe_user \
+         (id, cursor, owner_id, recipe_type, slug, name, description, ingredients, \
+          instructions, dietary_restrictions, is_shared, created_at, difficulty_score) \
+         VALUES (?, ?, ?, 'MainCourse', ?, ?, '', ?, X'', '[]', 0, 0, 0)",
+    )
+    .bind(id)
+    .bind(id)
+    .bind(owner_id)
+    .bind(id)
+    .bind(id)
+    .bind(bitcode::encode(&ingredients))
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cookable_from_pantry() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    seed(
+        &state.write_db,
+        "curry",
+        "john",
+        &["rice", "chicken", "coconut milk"],
+    )
+    .await?;
+    seed(&state.write_db, "omelette", "john", &["Eggs", "butter"]).await?;
+    seed(
+        &state.write_db,
+        "pancakes",
+        "john",
+        &["eggs", "flour", "milk"],
+    )
+    .await?;
+    seed(&state.write_db, "salad", "albert", &["eggs"]).await?;
+
+    let pantry = ["eggs", "Butter ", "flour", "rice"].map(str::to_owned);
+    let recipes = cmd.cookable_from("john", &pantry).await?;
+
+    let ranked = recipes
+        .iter()
+        .map(|r| {
+            (
+                r.id.as_str(),
+                r.missing
+                    .iter()
+                    .map(|i| i.name.as_str())
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        ranked,
+        vec![
+            ("omelette", vec![]),
+            ("pancakes", vec!["milk"]),
+            ("curry", vec!["chicken", "coconut milk"]),
+        ]
+    );
+
+    Ok(())
+}
@@ -0,0 +1,24 @@
+use imkitchen_types::recipe::RecipeType;
+use temp_dir::TempDir;
+
+#[tokio::test]
+async fn test_create_with_recipe_type() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::recipe::Module::new(state);
+
+    let appetizer = cmd
+        .create("john", "john_doe".to_owned(), RecipeType::Appetizer)
+        .await?;
+    let recipe = cmd.load(&appetizer).await?.unwrap();
+    assert_eq!(recipe.recipe_type, RecipeType::Appetizer);
+
+    let main_course = cmd
+        .create("john", "john_doe".to_owned(), RecipeType::MainCourse)
+        .await?;
+    let recipe = cmd.load(&main_course).await?.unwrap();
+    assert_eq!(recipe.recipe_type, RecipeType::MainCourse);
+
+    Ok(())
+}
@@ -1,3 +1,4 @@
+use imkitchen_types::recipe::RecipeType;
 use temp_dir::TempDir;
 
 #[tokio::test]
@@ -7,7 +8,9 @@ async fn test_delete() -> anyhow::Result<()> {
     let state = crate::helpers::setup_test_state(path).await?;
     let cmd = imkitchen_core::recipe::Module::new(state);
 
-    let recipe_id = cmd.create("john", "john_doe".to_owned()).await?;
+    let recipe_id = cmd
+        .create("john", "john_doe".to_owned(), RecipeType::MainCourse)
+        .await?;
     assert!(cmd.load(&recipe_id).await?.is_some());
 
     cmd.delete(&recipe_id, "john").await?;
@@ -0,0 +1,71 @@
+use imkitchen_core::recipe::UpdateInput;
+use imkitchen_types::recipe::{Ingredient, IngredientCategory, RecipeType};
+use temp_dir::TempDir;
+
+#[tokio::test]
+async fn test_recipe_keeps_author_order_shopping_walks_aisles() -> anyhow::Result<()> {
+    use IngredientCategory::*;
+
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    let recipe_id = cmd
+        .create("john", "john_doe".to_owned(), RecipeType::MainCourse)
+        .await?;
+
+    let ingredient = |name: &str, category| Ingredient {
+        name: name.to_owned(),
+        quantity: 1,
+        unit: None,
+        category: Some(category),
+    };
+    cmd.update(
+        UpdateInput {
+            name: "Crumble".to_owned(),
+            origin: None,
+            description: "my description".to_owned(),
+            advance_prep: "".to_owned(),
+            dietary_restrictions: vec![],
+            accepts_accompaniment: false,
+            ingredients: vec![
+                ingredient("flour", Grocery),
+                ingredient("butter", DairyAndEggs),
+                ingredient("sugar", Grocery),
+                ingredient("apple", FruitsAndVegetables),
+            ],
+            instructions: crate::helpers::instructions(),
+            household_size: 4,
+            cook_time: 25,
+            prep_time: 10,
+            recipe_type: RecipeType::MainCourse,
+            id: recipe_id.to_owned(),
+        },
+        "john",
+    )
+    .await?;
+
+    imkitchen_core::recipe::query::user::create_projection()
+        .data((state.read_db.clone(), state.write_db.clone()))
+        .subscription("recipe-query")
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let recipe = cmd.find_user(&recipe_id).await?.expect("recipe");
+    let names: Vec<_> = recipe
+        .ingredients
+        .0
+        .iter()
+        .map(|i| i.name.as_str())
+        .collect();
+    assert_eq!(names, ["flour", "butter", "sugar", "apple"]);
+
+    let mut shopping = recipe.ingredients.0.to_vec();
+    Ingredient::sort_for_shopping(&mut shopping, &IngredientCategory::aisle_order(&[]));
+    let names: Vec<_> = shopping.iter().map(|i| i.name.as_str()).collect();
+    assert_eq!(names, ["apple", "butter", "flour", "sugar"]);
+
+    Ok(())
+}
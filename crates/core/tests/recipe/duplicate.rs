@@ -0,0 +1,84 @@
+use evento::Sqlite;
+use imkitchen_core::State;
+use imkitchen_core::recipe::{ImportInput, Module};
+use imkitchen_types::recipe::{Ingredient, IngredientUnit, Instruction, RecipeType};
+use temp_dir::TempDir;
+
+async fn run_recipe_query(state: &State<Sqlite>) -> anyhow::Result<()> {
+    imkitchen_core::recipe::query::user::create_projection()
+        .data((state.read_db.clone(), state.write_db.clone()))
+        .subscription("recipe-query")
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    Ok(())
+}
+
+fn input(force: bool) -> ImportInput {
+    ImportInput {
+        recipe_type: RecipeType::MainCourse,
+        name: "Chili".to_owned(),
+        origin: Some("https://example.com/chili".to_owned()),
+        description: "Slow cooked beef and beans".to_owned(),
+        household_size: 4,
+        prep_time: 15,
+        cook_time: 90,
+        ingredients: vec![Ingredient {
+            name: "beef".to_owned(),
+            quantity: 500,
+            unit: Some(IngredientUnit::G),
+            category: None,
+        }],
+        instructions: vec![Instruction {
+            description: "Brown the beef".to_owned(),
+            time_next: 10,
+        }],
+        force,
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_import_flags_duplicate() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = Module::new(state.clone());
+
+    let id = cmd.import(input(false), "john", None).await?;
+    run_recipe_query(&state).await?;
+
+    let err = cmd.import(input(false), "john", None).await.unwrap_err();
+    assert!(matches!(err, imkitchen_core::Error::PossibleDuplicate(existing) if existing == id));
+
+    // Other users' recipes aren't duplicates.
+    cmd.import(input(false), "albert", None).await?;
+
+    // Any change to the content makes it a different recipe.
+    let mut changed = input(false);
+    changed.instructions[0].time_next = 5;
+    assert_eq!(cmd.import(changed, "john", None).await?, id);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_import_force_creates_copy() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = Module::new(state.clone());
+
+    let id = cmd.import(input(false), "john", None).await?;
+    run_recipe_query(&state).await?;
+
+    let copy = cmd.import(input(true), "john", None).await?;
+    assert_ne!(copy, id);
+    run_recipe_query(&state).await?;
+
+    assert!(cmd.user(&id).await?.is_some());
+    assert!(cmd.user(&copy).await?.is_some());
+
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use evento::Sqlite;
+use imkitchen_core::State;
+use imkitchen_core::recipe::ImportInput;
+use imkitchen_types::recipe::{
+    DietaryRestriction, Ingredient, IngredientUnit, Instruction, RecipeType,
+};
+use temp_dir::TempDir;
+
+async fn run_recipe_query(state: &State<Sqlite>) -> anyhow::Result<()> {
+    imkitchen_core::recipe::query::user::create_projection()
+        .data((state.read_db.clone(), state.write_db.clone()))
+        .subscription("recipe-query")
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    Ok(())
+}
+
+fn input(name: &str, recipe_type: RecipeType) -> ImportInput {
+    ImportInput {
+        recipe_type,
+        name: name.to_owned(),
+        origin: Some(format!("https://example.com/{name}")),
+        description: "my description".to_owned(),
+        household_size: 4,
+        prep_time: 10,
+        cook_time: 25,
+        ingredients: vec![Ingredient {
+            name: "ingredient 1".to_owned(),
+            quantity: 200,
+            unit: Some(IngredientUnit::G),
+            category: None,
+        }],
+        instructions: vec![Instruction {
+            time_next: 15,
+            description: "My first instruction".to_owned(),
+        }],
+        accepts_accompaniment: true,
+        dietary_restrictions: vec![DietaryRestriction::Vegetarian],
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_export_round_trip() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    let recipes = vec![
+        input("Ratatouille", RecipeType::MainCourse),
+        input("Tarte Tatin", RecipeType::Dessert),
+    ];
+    for recipe in recipes.iter() {
+        cmd.import(recipe.clone(), "john", None).await?;
+    }
+    // Drafts can't be imported back.
+    cmd.create("john", "john".to_owned(), RecipeType::MainCourse)
+        .await?;
+    run_recipe_query(&state).await?;
+
+    let exported = cmd.export_page("john", None).await?;
+    assert_eq!(exported.len(), 2);
+    assert!(
+        cmd.export_page("john", Some(exported[1].0.to_owned()))
+            .await?
+            .is_empty()
+    );
+
+    for (_, recipe) in exported.iter() {
+        cmd.import(recipe.clone(), "albert", None).await?;
+    }
+    run_recipe_query(&state).await?;
+
+    let mut exported = exported
+        .into_iter()
+        .map(|(_, recipe)| recipe)
+        .collect::<Vec<_>>();
+    let mut reimported = cmd
+        .export_page("albert", None)
+        .await?
+        .into_iter()
+        .map(|(_, recipe)| recipe)
+        .collect::<Vec<_>>();
+    exported.sort_by(|a, b| a.name.cmp(&b.name));
+    reimported.sort_by(|a, b| a.name.cmp(&b.name));
+
+    assert_eq!(exported, recipes);
+    assert_eq!(reimported, recipes);
+
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use evento::{AggregateEvent, EventFilter, Executor, Sqlite, cursor::Args};
+use imkitchen_core::State;
+use imkitchen_types::favorite::{Saved, Unsaved};
+use imkitchen_types::recipe::RecipeType;
+use temp_dir::TempDir;
+
+async fn run_user_stat_subscription(state: &State<Sqlite>) -> anyhow::Result<()> {
+    imkitchen_core::recipe::query::user_stat::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    Ok(())
+}
+
+async fn count_events<T: AggregateEvent>(state: &State<Sqlite>) -> anyhow::Result<usize> {
+    let result = state
+        .executor
+        .read(
+            Some(vec![EventFilter::by_event(
+                T::aggregate_type(),
+                T::event_name(),
+            )]),
+            None,
+            Args::forward(100, None),
+        )
+        .await?;
+
+    Ok(result.edges.len())
+}
+
+#[tokio::test]
+async fn test_double_save_is_noop() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::recipe::Module::new(state.clone());
+    let id = cmd
+        .create("albert", "albert".to_owned(), RecipeType::MainCourse)
+        .await?;
+
+    cmd.favorite.save(&id, "albert", "john", 10).await?;
+    cmd.favorite.save(&id, "albert", "john", 10).await?;
+
+    assert_eq!(count_events::<Saved>(&state).await?, 1);
+    assert!(cmd.favorite.load(&id, "john").await?.saved);
+
+    assert!(!cmd.favorite.toggle(&id, "albert", "john", 10).await?);
+    cmd.favorite.unsave(&id, "john").await?;
+    assert_eq!(count_events::<Unsaved>(&state).await?, 1);
+    assert!(!cmd.favorite.load(&id, "john").await?.saved);
+
+    assert!(cmd.favorite.toggle(&id, "albert", "john", 10).await?);
+    assert_eq!(count_events::<Saved>(&state).await?, 2);
+    assert!(cmd.favorite.load(&id, "john").await?.saved);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_max_favorites() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    let mut ids = vec![];
+    for _ in 0..3 {
+        ids.push(
+            cmd.create("albert", "albert".to_owned(), RecipeType::MainCourse)
+                .await?,
+        );
+    }
+
+    for id in &ids[..2] {
+        cmd.favorite.save(id, "albert", "john", 2).await?;
+        run_user_stat_subscription(&state).await?;
+    }
+
+    let err = cmd
+        .favorite
+        .save(&ids[2], "albert", "john", 2)
+        .await
+        .unwrap_err();
+    assert_eq!(err.to_string(), "You can save up to 2 favorite recipes");
+
+    // Saving an already saved recipe is still a no-op at the cap.
+    cmd.favorite.save(&ids[0], "albert", "john", 2).await?;
+
+    cmd.favorite.unsave(&ids[0], "john").await?;
+    run_user_stat_subscription(&state).await?;
+
+    cmd.favorite.save(&ids[2], "albert", "john", 2).await?;
+    run_user_stat_subscription(&state).await?;
+
+    let stat = cmd.find_user_stat("john").await?.expect("stat");
+    assert_eq!(stat.favorite, 2);
+
+    Ok(())
+}
@@ -1,6 +1,7 @@
 use evento::Sqlite;
 use evento::migrator::{Migrate, Plan};
 use imkitchen_core::State;
+use imkitchen_types::recipe::{Ingredient, Instruction};
 use sqlx::{SqlitePool, sqlite::SqliteConnectOptions};
 use std::{path::PathBuf, str::FromStr};
 
@@ -19,3 +20,21 @@ pub async fn setup_test_state(path: PathBuf) -> anyhow::Result<State<Sqlite>> {
         write_db: pool,
     })
 }
+
+/// A single ingredient, the least a recipe needs to be valid.
+pub fn ingredients() -> Vec<Ingredient> {
+    vec![Ingredient {
+        name: "salt".to_owned(),
+        quantity: 1,
+        unit: None,
+        category: None,
+    }]
+}
+
+/// A single step, the least a recipe needs to be valid.
+pub fn instructions() -> Vec<Instruction> {
+    vec![Instruction {
+        description: "Cook".to_owned(),
+        time_next: 0,
+    }]
+}
@@ -0,0 +1,98 @@
+use imkitchen_core::recipe::parse_recipe_json;
+use imkitchen_types::recipe::{CuisineType, IngredientCategory, IngredientUnit, RecipeType};
+use temp_dir::TempDir;
+
+const CHILI: &str = r#"{
+    "name": "Chili",
+    "description": "Slow cooked beef and beans",
+    "recipe_type": "MainCourse",
+    "cuisine_type": "Mexican",
+    "household_size": 6,
+    "prep_time": 15,
+    "cook_time": 90,
+    "ingredients": [
+        { "name": "beef", "quantity": 500, "unit": "g", "category": "Butcher" },
+        { "name": "salt" }
+    ],
+    "instructions": [
+        { "description": "Brown the beef", "time_next": 10 },
+        { "description": "Simmer", "time_next": 80 }
+    ],
+    "advance_prep": "Soak the beans overnight",
+    "dietary_restrictions": ["GlutenFree"]
+}"#;
+
+#[tokio::test]
+async fn test_import_json() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::recipe::Module::new(state);
+
+    let id = cmd
+        .import_json(CHILI, "john", "john_doe".to_owned())
+        .await?;
+
+    let chili = cmd.user(&id).await?.unwrap();
+    assert_eq!(chili.name, "Chili");
+    assert_eq!(chili.recipe_type.0, RecipeType::MainCourse);
+    assert_eq!(chili.household_size, 6);
+    assert_eq!(chili.prep_time, 15);
+    assert_eq!(chili.cook_time, 90);
+    assert_eq!(chili.advance_prep, "Soak the beans overnight");
+    assert_eq!(chili.ingredients.0[0].quantity, 500);
+    assert_eq!(chili.ingredients.0[0].unit, Some(IngredientUnit::G));
+    assert_eq!(
+        chili.ingredients.0[0].category,
+        Some(IngredientCategory::Butcher)
+    );
+    assert_eq!(chili.ingredients.0[1].unit, None);
+    assert_eq!(chili.instructions.0[1].time_next, 80);
+
+    let recipe = cmd.load(&id).await?.unwrap();
+    assert_eq!(recipe.cuisine_type, Some(CuisineType::Mexican));
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_recipe_json_errors() {
+    let error = |json: &str| parse_recipe_json(json).unwrap_err().to_string();
+
+    assert_eq!(
+        error(r#"{ "name": "Chili", "ingredients": [{ "name": "beef", "unit": "kg" }] }"#),
+        "Unknown unit for beef: kg"
+    );
+    assert_eq!(
+        error(r#"{ "name": "Chili", "ingredients": [{ "name": "beef", "category": "Meat" }] }"#),
+        "Unknown category for beef: Meat"
+    );
+    assert_eq!(
+        error(r#"{ "name": "Chili", "prep_time": -5 }"#),
+        "Invalid prep time: -5"
+    );
+    assert_eq!(
+        error(
+            r#"{ "name": "Chili", "instructions": [{ "description": "Stir", "time_next": -1 }] }"#
+        ),
+        "Invalid time for step 1: -1"
+    );
+    assert_eq!(error(r#"{ "name": "  " }"#), "Name is required");
+    assert_eq!(error(r#"{ "description": "No name" }"#), "Name is required");
+    assert_eq!(
+        error(r#"{ "name": "Chili", "recipe_type": "Brunch" }"#),
+        "Unknown recipe type: Brunch"
+    );
+    assert!(error("not json").starts_with("Invalid recipe JSON"));
+}
+
+#[test]
+fn test_parse_recipe_json_force() -> anyhow::Result<()> {
+    let (input, _) = parse_recipe_json(r#"{ "name": "Chili" }"#)?;
+    assert!(!input.force);
+
+    let (input, _) = parse_recipe_json(r#"{ "name": "Chili", "force": true }"#)?;
+    assert!(input.force);
+
+    Ok(())
+}
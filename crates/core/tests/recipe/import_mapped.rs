@@ -0,0 +1,80 @@
+use imkitchen_core::recipe::ColumnMapping;
+use imkitchen_types::recipe::{IngredientUnit, RecipeType};
+use temp_dir::TempDir;
+
+const CSV: &str = "\
+Title;Kind;Serves;Minutes;Shopping;Steps
+Pancakes;Dessert;2;15;\"250 g flour, 2 eggs, 500 ml milk\";\"Mix, Cook\"
+Tomato soup;Appetizer;4;30;\"1000 g tomatoes, salt\";Blend
+Lemonade;Beverage;oops;5;3 lemons;Squeeze
+";
+
+#[tokio::test]
+async fn test_import_mapped_csv() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::recipe::Module::new(state);
+
+    let mapping = ColumnMapping {
+        delimiter: ';',
+        list_delimiter: ',',
+        name: "Title".to_owned(),
+        recipe_type: Some("Kind".to_owned()),
+        household_size: Some("Serves".to_owned()),
+        cook_time: Some("Minutes".to_owned()),
+        ingredients: Some("Shopping".to_owned()),
+        instructions: Some("Steps".to_owned()),
+        ..Default::default()
+    };
+
+    let imported = cmd
+        .import_mapped(CSV, &mapping, "john", "john_doe".to_owned())
+        .await?;
+
+    assert_eq!(imported.ids.len(), 2);
+    assert_eq!(imported.errors.len(), 1);
+    assert_eq!(imported.errors[0].row, 3);
+    assert_eq!(imported.errors[0].name, "Lemonade");
+    assert_eq!(
+        imported.errors[0].error.to_string(),
+        "Invalid household size: oops"
+    );
+
+    let pancakes = cmd.user(&imported.ids[0]).await?.unwrap();
+    assert_eq!(pancakes.name, "Pancakes");
+    assert_eq!(pancakes.recipe_type.0, RecipeType::Dessert);
+    assert_eq!(pancakes.household_size, 2);
+    assert_eq!(pancakes.cook_time, 15);
+    assert_eq!(pancakes.ingredients.0.len(), 3);
+    assert_eq!(pancakes.ingredients.0[0].name, "flour");
+    assert_eq!(pancakes.ingredients.0[0].quantity, 250);
+    assert_eq!(pancakes.ingredients.0[0].unit, Some(IngredientUnit::G));
+    assert_eq!(pancakes.ingredients.0[2].unit, Some(IngredientUnit::ML));
+    assert_eq!(pancakes.instructions.0.len(), 2);
+    assert_eq!(pancakes.instructions.0[1].description, "Cook");
+
+    let soup = cmd.user(&imported.ids[1]).await?.unwrap();
+    assert_eq!(soup.name, "Tomato soup");
+    assert_eq!(soup.recipe_type.0, RecipeType::Appetizer);
+    assert_eq!(soup.ingredients.0[1].name, "salt");
+    assert_eq!(soup.ingredients.0[1].quantity, 0);
+
+    let resp = cmd
+        .import_mapped(
+            CSV,
+            &ColumnMapping {
+                name: "Recipe".to_owned(),
+                ..mapping
+            },
+            "john",
+            None,
+        )
+        .await;
+    assert_eq!(
+        resp.err().map(|e| e.to_string()),
+        Some("Column \"Recipe\" not found".to_owned())
+    );
+
+    Ok(())
+}
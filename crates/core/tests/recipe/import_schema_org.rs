@@ -0,0 +1,147 @@
+use imkitchen_core::recipe::parse_schema_org;
+use imkitchen_types::recipe::IngredientUnit;
+use temp_dir::TempDir;
+
+/// Trimmed down from a recipe blog page: the recipe sits in a `@graph`
+/// next to the page metadata, with steps grouped in sections.
+const PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<title>Classic Banana Bread</title>
+<script type="application/ld+json">{"@context":"https://schema.org","@type":"Organization","name":"Bake Blog"}</script>
+<script type="application/ld+json">
+{
+  "@context": "https://schema.org",
+  "@graph": [
+    { "@type": "WebPage", "name": "Classic Banana Bread" },
+    {
+      "@type": ["Recipe"],
+      "name": "Classic Banana Bread",
+      "description": "Moist banana bread with a crackly top.",
+      "url": "https://bakeblog.example.com/banana-bread",
+      "prepTime": "PT15M",
+      "cookTime": "PT1H5M",
+      "totalTime": "PT1H20M",
+      "recipeYield": ["8", "8 slices"],
+      "recipeIngredient": [
+        "3 ripe bananas",
+        "2 cups all-purpose flour",
+        "1/2 cup butter, melted",
+        "1 1/2 tsp baking soda",
+        "½ tsp salt",
+        "200 g sugar",
+        "a handful of walnuts"
+      ],
+      "recipeInstructions": [
+        {
+          "@type": "HowToSection",
+          "name": "Batter",
+          "itemListElement": [
+            { "@type": "HowToStep", "text": "Mash the bananas." },
+            { "@type": "HowToStep", "text": "Stir in the butter, then the dry ingredients." }
+          ]
+        },
+        { "@type": "HowToStep", "text": "Bake at 175°C for 65 minutes." }
+      ]
+    }
+  ]
+}
+</script>
+</head>
+<body></body>
+</html>"#;
+
+#[test]
+fn test_parse_schema_org_page() {
+    let clipped = parse_schema_org(PAGE).unwrap();
+    let input = clipped.input;
+
+    assert_eq!(input.name, "Classic Banana Bread");
+    assert_eq!(
+        input.origin.as_deref(),
+        Some("https://bakeblog.example.com/banana-bread")
+    );
+    assert_eq!(input.prep_time, 15);
+    assert_eq!(input.cook_time, 65);
+    assert_eq!(input.household_size, 8);
+    assert_eq!(input.ingredients.len(), 7);
+    assert_eq!(input.instructions.len(), 3);
+    assert_eq!(
+        input.instructions[2].description,
+        "Bake at 175°C for 65 minutes."
+    );
+
+    let ingredient = |pos: usize| {
+        let ingredient = &input.ingredients[pos];
+        (
+            ingredient.quantity,
+            ingredient.unit.clone(),
+            ingredient.name.as_str(),
+        )
+    };
+    assert_eq!(ingredient(0), (3, None, "ripe bananas"));
+    assert_eq!(
+        ingredient(1),
+        (2, Some(IngredientUnit::Cup), "all-purpose flour")
+    );
+    // Half a cup, a tsp and a half: converted to ml.
+    assert_eq!(
+        ingredient(2),
+        (120, Some(IngredientUnit::ML), "butter, melted")
+    );
+    assert_eq!(ingredient(3), (8, Some(IngredientUnit::ML), "baking soda"));
+    assert_eq!(ingredient(4), (3, Some(IngredientUnit::ML), "salt"));
+    assert_eq!(ingredient(5), (200, Some(IngredientUnit::G), "sugar"));
+    assert_eq!(ingredient(6), (0, None, "a handful of walnuts"));
+
+    assert_eq!(
+        clipped.warnings,
+        vec![
+            "Rounded quantity: 1 1/2 tsp baking soda",
+            "Rounded quantity: ½ tsp salt",
+            "Couldn't read a quantity: a handful of walnuts",
+        ]
+    );
+}
+
+#[test]
+fn test_parse_schema_org_json_ld() {
+    let clipped = parse_schema_org(
+        r#"{ "@type": "Recipe", "name": "Toast", "prepTime": "PT1H30", "recipeIngredient": "1 slice bread", "recipeInstructions": "Toast it.\nButter it." }"#,
+    )
+    .unwrap();
+
+    assert_eq!(clipped.input.description, "Toast");
+    assert_eq!(clipped.input.prep_time, 0);
+    assert_eq!(clipped.input.household_size, 4);
+    assert_eq!(clipped.input.ingredients[0].name, "slice bread");
+    assert_eq!(clipped.input.instructions.len(), 2);
+    assert_eq!(clipped.warnings, vec!["Couldn't read prepTime: PT1H30"]);
+
+    assert_eq!(
+        parse_schema_org("<html><body>No recipe</body></html>")
+            .unwrap_err()
+            .to_string(),
+        "No schema.org Recipe found"
+    );
+}
+
+#[tokio::test]
+async fn test_import_schema_org() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::recipe::Module::new(state);
+
+    let (id, warnings) = cmd
+        .import_schema_org(PAGE, "john", "john_doe".to_owned())
+        .await?;
+    assert_eq!(warnings.len(), 3);
+
+    let recipe = cmd.user(&id).await?.unwrap();
+    assert_eq!(recipe.name, "Classic Banana Bread");
+    assert_eq!(recipe.ingredients.0.len(), 7);
+    assert_eq!(recipe.instructions.0.len(), 3);
+
+    Ok(())
+}
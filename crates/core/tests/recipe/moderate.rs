@@ -0,0 +1,163 @@
+use imkitchen_core::recipe::ModerateInput;
+use imkitchen_types::comment::FlagReason;
+use imkitchen_types::recipe::{ModerationAction, RecipeType};
+use temp_dir::TempDir;
+
+#[tokio::test]
+async fn test_admin_force_unshare() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    let shared = cmd
+        .create("john", "john_doe".to_owned(), RecipeType::MainCourse)
+        .await?;
+    cmd.share_to_community(&shared, "john", "john_doe").await?;
+    let private = cmd
+        .create("john", "john_doe".to_owned(), RecipeType::MainCourse)
+        .await?;
+
+    let changed = cmd
+        .moderate(
+            ModerateInput {
+                ids: vec![shared.to_owned(), private.to_owned(), "unknown".to_owned()],
+                action: ModerationAction::MadePrivate,
+            },
+            "admin",
+        )
+        .await?;
+    assert_eq!(changed, 1);
+
+    let recipe = cmd.load(&shared).await?.expect("recipe");
+    assert!(!recipe.is_shared);
+    assert_eq!(recipe.owner_id, "john");
+    assert_eq!(recipe.moderated_by.as_deref(), Some("admin"));
+
+    let recipe = cmd.load(&private).await?.expect("recipe");
+    assert_eq!(recipe.moderated_by, None);
+
+    // The unshare is still counted against the owner, not the admin.
+    imkitchen_core::recipe::query::user_stat::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let stat = cmd.find_user_stat("john").await?.expect("stat");
+    assert_eq!(stat.shared, 0);
+    assert_eq!(stat.total, 2);
+    assert!(cmd.find_user_stat("admin").await?.is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_admin_delete() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::recipe::Module::new(state);
+
+    let recipe_id = cmd
+        .create("john", "john_doe".to_owned(), RecipeType::MainCourse)
+        .await?;
+    let changed = cmd
+        .moderate(
+            ModerateInput {
+                ids: vec![recipe_id.to_owned()],
+                action: ModerationAction::Deleted,
+            },
+            "admin",
+        )
+        .await?;
+    assert_eq!(changed, 1);
+    assert!(cmd.load(&recipe_id).await?.is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_admin_hide() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::recipe::Module::new(state);
+
+    let recipe_id = cmd
+        .create("john", "john_doe".to_owned(), RecipeType::MainCourse)
+        .await?;
+    cmd.share_to_community(&recipe_id, "john", "john_doe")
+        .await?;
+
+    let input = || ModerateInput {
+        ids: vec![recipe_id.to_owned()],
+        action: ModerationAction::Hidden,
+    };
+    assert_eq!(cmd.moderate(input(), "admin").await?, 1);
+    assert_eq!(cmd.moderate(input(), "admin").await?, 0);
+
+    let recipe = cmd.load(&recipe_id).await?.expect("recipe");
+    assert!(recipe.is_hidden);
+    assert!(!recipe.is_shared);
+    assert_eq!(recipe.moderated_by.as_deref(), Some("admin"));
+
+    let resp = cmd.share_to_community(&recipe_id, "john", "john_doe").await;
+    assert_eq!(
+        resp.err().map(|e| e.to_string()),
+        Some("forbidden recipe hidden by an admin".to_owned())
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_flagged_recipes_leave_queue_once_private() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::recipe::Module::new(state.clone());
+
+    let flagged = cmd
+        .create("john", "john_doe".to_owned(), RecipeType::MainCourse)
+        .await?;
+    cmd.share_to_community(&flagged, "john", "john_doe").await?;
+    let clean = cmd
+        .create("john", "john_doe".to_owned(), RecipeType::MainCourse)
+        .await?;
+    cmd.share_to_community(&clean, "john", "john_doe").await?;
+
+    let spam = cmd.comment.add(&flagged, "alice", "Buy cheap pans").await?;
+    cmd.comment.add(&clean, "alice", "Lovely").await?;
+    cmd.comment.flag(&spam, "bob", FlagReason::Spam).await?;
+
+    imkitchen_core::recipe::query::comment::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+    // Loading the user view writes its row.
+    cmd.user(&flagged).await?;
+    cmd.user(&clean).await?;
+
+    let recipes = cmd.filter_flagged_recipes(10).await?;
+    let recipes = recipes
+        .iter()
+        .map(|recipe| (recipe.id.as_str(), recipe.flag_count))
+        .collect::<Vec<_>>();
+    assert_eq!(recipes, vec![(flagged.as_str(), 1)]);
+
+    cmd.moderate(
+        ModerateInput {
+            ids: vec![flagged.to_owned()],
+            action: ModerationAction::MadePrivate,
+        },
+        "admin",
+    )
+    .await?;
+    cmd.user(&flagged).await?;
+
+    assert!(cmd.filter_flagged_recipes(10).await?.is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,128 @@
+use evento::Sqlite;
+use imkitchen_core::mealplan::{ChangeSlotRecipeStatus, Generate};
+use imkitchen_core::recipe::ImportInput;
+use imkitchen_types::mealplan::DaySlotStatus;
+use imkitchen_types::recipe::RecipeType;
+use temp_dir::TempDir;
+use time::{Duration, OffsetDateTime};
+
+async fn import_recipe(
+    cmd: &imkitchen_core::recipe::Module<Sqlite>,
+    db: &sqlx::SqlitePool,
+    name: &str,
+) -> anyhow::Result<String> {
+    let id = cmd
+        .import(
+            ImportInput {
+                name: name.to_owned(),
+                description: "my description".to_owned(),
+                ingredients: crate::helpers::ingredients(),
+                instructions: crate::helpers::instructions(),
+                household_size: 4,
+                cook_time: 25,
+                prep_time: 10,
+                recipe_type: RecipeType::MainCourse,
+                ..Default::default()
+            },
+            "john",
+            None,
+        )
+        .await?;
+
+    // Mirror what the `recipe-query` projection writes at runtime.
+    sqlx::query(
+        "INSERT INTO recipe_user \
+         (id, cursor, owner_id, recipe_type, slug, name, description, ingredients, \
+          instructions, dietary_restrictions, is_shared, created_at, difficulty_score) \
+         VALUES (?, ?, 'john', 'MainCourse', ?, ?, '', X'', X'', '[]', 0, 0, 0)",
+    )
+    .bind(&id)
+    .bind(&id)
+    .bind(&id)
+    .bind(name)
+    .execute(db)
+    .await?;
+
+    Ok(id)
+}
+
+async fn change_status(
+    cmd: &imkitchen_core::mealplan::Module<Sqlite>,
+    day: OffsetDateTime,
+    recipe_id: &str,
+    status: DaySlotStatus,
+) -> anyhow::Result<()> {
+    cmd.change_slot_recipe_status(ChangeSlotRecipeStatus {
+        user_id: "john".to_owned(),
+        date: imkitchen_core::mealplan::date_to_u64(day),
+        recipe_id: recipe_id.to_owned(),
+        status,
+    })
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_most_cooked() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+    let mealplan_cmd = imkitchen_core::mealplan::Module::new(state.clone());
+
+    let curry = import_recipe(&recipe_cmd, &state.write_db, "Curry").await?;
+    let pasta = import_recipe(&recipe_cmd, &state.write_db, "Pasta").await?;
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let week_1 = OffsetDateTime::now_utc();
+    let week_2 = week_1 + Duration::days(7);
+
+    for start in [week_1, week_2] {
+        mealplan_cmd
+            .generate(Generate {
+                user_id: "john".to_owned(),
+                start: start.unix_timestamp() as u64,
+                days: 7,
+                household_size: 4,
+                ..Default::default()
+            })
+            .await?;
+    }
+
+    // Curry is completed in both weeks; completing the same slot twice counts once.
+    change_status(&mealplan_cmd, week_1, &curry, DaySlotStatus::Cooking(1)).await?;
+    change_status(&mealplan_cmd, week_1, &curry, DaySlotStatus::Completed).await?;
+    change_status(&mealplan_cmd, week_2, &curry, DaySlotStatus::Completed).await?;
+    change_status(&mealplan_cmd, week_2, &curry, DaySlotStatus::Completed).await?;
+
+    // Pasta completed once, and once started but never finished.
+    let day_2 = week_1 + Duration::days(1);
+    change_status(&mealplan_cmd, day_2, &pasta, DaySlotStatus::Completed).await?;
+    let day_3 = week_1 + Duration::days(2);
+    change_status(&mealplan_cmd, day_3, &pasta, DaySlotStatus::Cooking(2)).await?;
+
+    imkitchen_core::recipe::query::cook_count::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let most_cooked = recipe_cmd.most_cooked("john", 10).await?;
+    assert_eq!(
+        most_cooked
+            .iter()
+            .map(|r| (r.id.to_owned(), r.cook_count))
+            .collect::<Vec<_>>(),
+        vec![(curry, 2), (pasta, 1)]
+    );
+
+    assert!(recipe_cmd.most_cooked("albert", 10).await?.is_empty());
+
+    Ok(())
+}
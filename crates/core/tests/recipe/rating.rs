@@ -0,0 +1,140 @@
+use evento::Sqlite;
+use evento::cursor::{Args, Value};
+use imkitchen_core::recipe::query::rating::RatingStat;
+use imkitchen_core::recipe::query::user::{RecipesQuery, SortBy};
+use imkitchen_core::recipe::{ImportInput, Module};
+use imkitchen_types::recipe::RecipeType;
+use temp_dir::TempDir;
+
+/// Every user counts once, rating again replaces their stars, and a deleted
+/// recipe loses its aggregate.
+#[tokio::test]
+async fn test_rating_stat() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = Module::new(state.clone());
+
+    let curry = import_recipe(&cmd, "Curry").await?;
+
+    for (user_id, stars) in [("alice", 5), ("bob", 4), ("carol", 3)] {
+        cmd.rating.rate(&curry, user_id, stars).await?;
+    }
+    run_subscriptions(&state).await?;
+
+    assert_eq!(
+        cmd.find_rating_stat(&curry).await?,
+        RatingStat {
+            rating_count: 3,
+            average_rating: 4.0,
+        }
+    );
+
+    cmd.rating.rate(&curry, "carol", 5).await?;
+    cmd.rating.rate(&curry, "dave", 1).await?;
+    run_subscriptions(&state).await?;
+
+    assert_eq!(
+        cmd.find_rating_stat(&curry).await?,
+        RatingStat {
+            rating_count: 4,
+            average_rating: 3.75,
+        }
+    );
+
+    cmd.delete(&curry, "john").await?;
+    run_subscriptions(&state).await?;
+
+    assert_eq!(cmd.find_rating_stat(&curry).await?, RatingStat::default());
+
+    Ok(())
+}
+
+/// Best average first, unrated recipes last, one recipe per page.
+#[tokio::test]
+async fn test_sort_by_top_rated() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = Module::new(state.clone());
+
+    let curry = import_recipe(&cmd, "Curry").await?;
+    let pasta = import_recipe(&cmd, "Pasta").await?;
+    let soup = import_recipe(&cmd, "Soup").await?;
+
+    cmd.rating.rate(&curry, "alice", 3).await?;
+    cmd.rating.rate(&curry, "bob", 4).await?;
+    cmd.rating.rate(&pasta, "alice", 5).await?;
+    cmd.rating.rate(&pasta, "bob", 4).await?;
+    run_subscriptions(&state).await?;
+
+    let expected = [
+        (pasta, 2, Some("4.5".to_owned())),
+        (curry, 2, Some("3.5".to_owned())),
+        (soup, 0, None),
+    ];
+    let mut after: Option<Value> = None;
+
+    for (i, (id, rating_count, stars)) in expected.iter().enumerate() {
+        let result = cmd
+            .filter_user(RecipesQuery {
+                exclude_ids: None,
+                user_id: None,
+                recipe_type: None,
+                is_shared: None,
+                has_thumbnail: None,
+                dietary_restrictions: vec![],
+                dietary_where_any: false,
+                tags: vec![],
+                in_meal_plan: None,
+                sort_by: SortBy::TopRated,
+                search: None,
+                args: Args::forward(1, after.clone()),
+            })
+            .await?;
+
+        assert_eq!(result.edges.len(), 1, "page {i}");
+        let node = &result.edges[0].node;
+        assert_eq!(&node.id, id, "page {i}");
+        assert_eq!(node.rating_count, *rating_count, "page {i}");
+        assert_eq!(&node.average_stars(), stars, "page {i}");
+        assert_eq!(result.page_info.has_next_page, i < expected.len() - 1);
+
+        after = result.page_info.end_cursor.clone();
+    }
+
+    Ok(())
+}
+
+async fn run_subscriptions(state: &imkitchen_core::State<Sqlite>) -> anyhow::Result<()> {
+    imkitchen_core::recipe::query::user::create_projection()
+        .data((state.read_db.clone(), state.write_db.clone()))
+        .subscription("recipe-query")
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    imkitchen_core::recipe::query::rating::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    Ok(())
+}
+
+async fn import_recipe(cmd: &Module<Sqlite>, name: &str) -> anyhow::Result<String> {
+    let input = ImportInput {
+        name: name.to_owned(),
+        description: "my description".to_owned(),
+        ingredients: crate::helpers::ingredients(),
+        instructions: crate::helpers::instructions(),
+        household_size: 4,
+        cook_time: 25,
+        prep_time: 10,
+        recipe_type: RecipeType::MainCourse,
+        ..Default::default()
+    };
+
+    Ok(cmd.import(input, "john", None).await?)
+}
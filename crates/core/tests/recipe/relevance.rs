@@ -57,6 +57,7 @@ fn query(sort_by: SortBy, search: Option<&str>, first: u16, after: Option<Value>
         has_thumbnail: None,
         dietary_restrictions: vec![],
         dietary_where_any: false,
+        tags: vec![],
         in_meal_plan: None,
         sort_by,
         search: search.map(str::to_owned),
@@ -0,0 +1,125 @@
+use evento::Sqlite;
+use evento::cursor::Args;
+use imkitchen_core::recipe::query::user::{RecipesQuery, SortBy};
+use imkitchen_core::recipe::{ImportInput, Module};
+use imkitchen_types::recipe::{Ingredient, RecipeType};
+use temp_dir::TempDir;
+
+/// Singular and plural find each other, whichever one the recipe uses.
+#[tokio::test]
+async fn test_search_matches_word_forms() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = Module::new(state.clone());
+
+    let hummus = import_recipe(&cmd, "Hummus", "", &["chickpea", "tahini"]).await?;
+    let curry = import_recipe(&cmd, "Chickpeas curry", "", &["rice"]).await?;
+    import_recipe(&cmd, "Lentil soup", "", &["lentils"]).await?;
+    run_subscriptions(&state).await?;
+
+    for term in ["chickpea", "chickpeas", "Chickpeas"] {
+        let mut found = search(&cmd, term).await?;
+        found.sort();
+        let mut expected = vec![hummus.to_owned(), curry.to_owned()];
+        expected.sort();
+        assert_eq!(found, expected, "{term}");
+    }
+
+    assert_eq!(search(&cmd, "lentil").await?.len(), 1);
+    assert!(search(&cmd, "falafel").await?.is_empty());
+
+    Ok(())
+}
+
+/// Name, description and ingredients are all searched, the recipe mentioning
+/// the term the most comes first.
+#[tokio::test]
+async fn test_search_ranks_by_relevance() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = Module::new(state.clone());
+
+    let side = import_recipe(
+        &cmd,
+        "Roasted vegetables",
+        "Goes well with a tomato sauce and many other things on the side",
+        &["carrots", "onions"],
+    )
+    .await?;
+    let soup = import_recipe(&cmd, "Tomato soup", "", &["tomatoes", "basil"]).await?;
+    run_subscriptions(&state).await?;
+
+    assert_eq!(search(&cmd, "tomato").await?, vec![soup, side]);
+
+    Ok(())
+}
+
+async fn run_subscriptions(state: &imkitchen_core::State<Sqlite>) -> anyhow::Result<()> {
+    imkitchen_core::recipe::query::user::create_projection()
+        .data((state.read_db.clone(), state.write_db.clone()))
+        .subscription("recipe-query")
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    imkitchen_core::recipe::query::user_fts::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    Ok(())
+}
+
+/// Ids matching `term`, best match first.
+async fn search(cmd: &Module<Sqlite>, term: &str) -> anyhow::Result<Vec<String>> {
+    let result = cmd
+        .filter_user(RecipesQuery {
+            exclude_ids: None,
+            user_id: None,
+            recipe_type: None,
+            is_shared: None,
+            has_thumbnail: None,
+            dietary_restrictions: vec![],
+            dietary_where_any: false,
+            tags: vec![],
+            in_meal_plan: None,
+            sort_by: SortBy::RecentlyAdded,
+            search: Some(term.to_owned()),
+            args: Args::forward(20, None),
+        })
+        .await?;
+
+    Ok(result.edges.into_iter().map(|edge| edge.node.id).collect())
+}
+
+async fn import_recipe(
+    cmd: &Module<Sqlite>,
+    name: &str,
+    description: &str,
+    ingredients: &[&str],
+) -> anyhow::Result<String> {
+    let input = ImportInput {
+        name: name.to_owned(),
+        description: description.to_owned(),
+        instructions: crate::helpers::instructions(),
+        ingredients: ingredients
+            .iter()
+            .map(|name| Ingredient {
+                name: name.to_string(),
+                quantity: 100,
+                unit: None,
+                category: None,
+            })
+            .collect(),
+        household_size: 4,
+        cook_time: 25,
+        prep_time: 10,
+        recipe_type: RecipeType::MainCourse,
+        ..Default::default()
+    };
+
+    Ok(cmd.import(input, "john", None).await?)
+}
@@ -0,0 +1,161 @@
+use evento::Sqlite;
+use imkitchen_core::State;
+use imkitchen_core::recipe::{CuisineTypeInput, ImportInput, TagsInput};
+use imkitchen_types::recipe::{CuisineType, DietaryRestriction, Ingredient, RecipeType};
+use temp_dir::TempDir;
+
+async fn run_recipe_query(state: &State<Sqlite>) -> anyhow::Result<()> {
+    imkitchen_core::recipe::query::user::create_projection()
+        .data((state.read_db.clone(), state.write_db.clone()))
+        .subscription("recipe-query")
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    Ok(())
+}
+
+async fn import(
+    cmd: &imkitchen_core::recipe::Module<Sqlite>,
+    owner: &str,
+    name: &str,
+    recipe_type: RecipeType,
+    dietary_restrictions: Vec<DietaryRestriction>,
+    ingredients: &[&str],
+) -> anyhow::Result<String> {
+    let input = ImportInput {
+        recipe_type,
+        name: name.to_owned(),
+        description: "my description".to_owned(),
+        instructions: crate::helpers::instructions(),
+        household_size: 4,
+        prep_time: 10,
+        cook_time: 25,
+        ingredients: ingredients
+            .iter()
+            .map(|name| Ingredient {
+                name: name.to_string(),
+                quantity: 1,
+                unit: None,
+                category: None,
+            })
+            .collect(),
+        dietary_restrictions,
+        ..Default::default()
+    };
+
+    Ok(cmd.import(input, owner, None).await?)
+}
+
+async fn describe(
+    cmd: &imkitchen_core::recipe::Module<Sqlite>,
+    owner: &str,
+    id: &str,
+    cuisine_type: CuisineType,
+    tags: &[&str],
+) -> anyhow::Result<()> {
+    cmd.set_cuisine_type(
+        CuisineTypeInput {
+            id: id.to_owned(),
+            cuisine_type,
+        },
+        owner,
+    )
+    .await?;
+    cmd.set_tags(
+        TagsInput {
+            id: id.to_owned(),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+        },
+        owner,
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_similar_ranks_same_cuisine_and_shared_tags_first() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::recipe::Module::new(state.clone());
+    let veggie = vec![DietaryRestriction::Vegetarian, DietaryRestriction::Vegan];
+
+    let ratatouille = import(
+        &cmd,
+        "john",
+        "Ratatouille",
+        RecipeType::MainCourse,
+        veggie.to_vec(),
+        &["Zucchini", "Eggplant", "Tomato"],
+    )
+    .await?;
+    describe(&cmd, "john", &ratatouille, CuisineType::French, &["summer"]).await?;
+    let steak = import(
+        &cmd,
+        "john",
+        "Steak",
+        RecipeType::MainCourse,
+        vec![],
+        &["Beef"],
+    )
+    .await?;
+    // Same ingredients and diet, but another cuisine and no shared tag.
+    let curry = import(
+        &cmd,
+        "john",
+        "Vegetable curry",
+        RecipeType::MainCourse,
+        veggie.to_vec(),
+        &["Zucchini", "Eggplant", "Tomato"],
+    )
+    .await?;
+    describe(&cmd, "john", &curry, CuisineType::Indian, &[]).await?;
+    let tian = import(
+        &cmd,
+        "john",
+        "Vegetable tian",
+        RecipeType::MainCourse,
+        veggie.to_vec(),
+        &["zucchini", "tomato", "onion"],
+    )
+    .await?;
+    describe(&cmd, "john", &tian, CuisineType::French, &["summer"]).await?;
+    // Another course, however alike.
+    let lemonade = import(
+        &cmd,
+        "john",
+        "Lemonade",
+        RecipeType::Beverage,
+        veggie.to_vec(),
+        &["Lemon"],
+    )
+    .await?;
+    describe(&cmd, "john", &lemonade, CuisineType::French, &["summer"]).await?;
+    // Private to someone else, however close it is.
+    import(
+        &cmd,
+        "paul",
+        "Ratatouille niçoise",
+        RecipeType::MainCourse,
+        veggie.to_vec(),
+        &["Zucchini", "Eggplant", "Tomato"],
+    )
+    .await?;
+    run_recipe_query(&state).await?;
+
+    let similar = cmd
+        .similar(&ratatouille, "john", 10)
+        .await?
+        .into_iter()
+        .map(|recipe| recipe.id)
+        .collect::<Vec<_>>();
+    assert_eq!(similar, vec![tian.to_owned(), curry, steak]);
+
+    let similar = cmd.similar(&ratatouille, "john", 1).await?;
+    assert_eq!(similar.len(), 1);
+    assert_eq!(similar[0].id, tian);
+
+    Ok(())
+}
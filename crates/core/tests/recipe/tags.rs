@@ -0,0 +1,125 @@
+use evento::Sqlite;
+use evento::cursor::Args;
+use imkitchen_core::recipe::query::user::{RecipesQuery, SortBy};
+use imkitchen_core::recipe::{ImportInput, Module, TagsInput};
+use imkitchen_types::recipe::RecipeType;
+use std::collections::HashSet;
+use temp_dir::TempDir;
+
+#[tokio::test]
+async fn test_tags_are_normalized() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = Module::new(state.clone());
+
+    let id = import_recipe(&cmd, "Chili").await?;
+    set_tags(&cmd, &id, &[" Quick", "one-pot", "quick ", "", "ONE-POT"]).await?;
+
+    assert_eq!(cmd.load(&id).await?.unwrap().tags, vec!["quick", "one-pot"]);
+
+    assert!(
+        cmd.set_tags(
+            TagsInput {
+                id: id.to_owned(),
+                tags: vec!["spicy".to_owned()],
+            },
+            "albert",
+        )
+        .await
+        .is_err()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_filter_by_tags() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = Module::new(state.clone());
+
+    let chili = import_recipe(&cmd, "Chili").await?;
+    set_tags(&cmd, &chili, &["quick", "one-pot"]).await?;
+    let pasta = import_recipe(&cmd, "Pasta").await?;
+    set_tags(&cmd, &pasta, &["quick", "kid-friendly"]).await?;
+    let stew = import_recipe(&cmd, "Stew").await?;
+    set_tags(&cmd, &stew, &["one-pot"]).await?;
+    import_recipe(&cmd, "Salad").await?;
+
+    imkitchen_core::recipe::query::user::create_projection()
+        .data((state.read_db.clone(), state.write_db.clone()))
+        .subscription("recipe-query")
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    assert_eq!(
+        filter(&cmd, &["quick"]).await?,
+        HashSet::from([chili.to_owned(), pasta])
+    );
+    assert_eq!(
+        filter(&cmd, &["One-Pot"]).await?,
+        HashSet::from([chili.to_owned(), stew])
+    );
+    assert_eq!(
+        filter(&cmd, &["quick", "one-pot"]).await?,
+        HashSet::from([chili])
+    );
+    assert!(filter(&cmd, &["kid-friendly", "one-pot"]).await?.is_empty());
+    assert_eq!(filter(&cmd, &[]).await?.len(), 4);
+
+    Ok(())
+}
+
+/// Ids of the recipes carrying every one of `tags`.
+async fn filter(cmd: &Module<Sqlite>, tags: &[&str]) -> anyhow::Result<HashSet<String>> {
+    let result = cmd
+        .filter_user(RecipesQuery {
+            exclude_ids: None,
+            user_id: None,
+            recipe_type: None,
+            is_shared: None,
+            has_thumbnail: None,
+            dietary_restrictions: vec![],
+            dietary_where_any: false,
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            in_meal_plan: None,
+            sort_by: SortBy::RecentlyAdded,
+            search: None,
+            args: Args::forward(20, None),
+        })
+        .await?;
+
+    Ok(result.edges.into_iter().map(|edge| edge.node.id).collect())
+}
+
+async fn set_tags(cmd: &Module<Sqlite>, id: &str, tags: &[&str]) -> anyhow::Result<()> {
+    cmd.set_tags(
+        TagsInput {
+            id: id.to_owned(),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+        },
+        "john",
+    )
+    .await?;
+
+    Ok(())
+}
+
+async fn import_recipe(cmd: &Module<Sqlite>, name: &str) -> anyhow::Result<String> {
+    let input = ImportInput {
+        name: name.to_owned(),
+        description: "my description".to_owned(),
+        ingredients: crate::helpers::ingredients(),
+        instructions: crate::helpers::instructions(),
+        household_size: 4,
+        cook_time: 25,
+        prep_time: 10,
+        recipe_type: RecipeType::MainCourse,
+        ..Default::default()
+    };
+
+    Ok(cmd.import(input, "john", None).await?)
+}
@@ -11,7 +11,9 @@ async fn test_update_no_fields() -> anyhow::Result<()> {
     let state = crate::helpers::setup_test_state(path).await?;
     let cmd = imkitchen_core::recipe::Module::new(state);
 
-    let recipe_id = cmd.create("john", "john_doe".to_owned()).await?;
+    let recipe_id = cmd
+        .create("john", "john_doe".to_owned(), RecipeType::MainCourse)
+        .await?;
 
     let input = UpdateInput {
         name: "My first Recipe".to_owned(),
@@ -63,7 +65,9 @@ async fn test_update_only_recipe_type() -> anyhow::Result<()> {
     let state = crate::helpers::setup_test_state(path).await?;
     let cmd = imkitchen_core::recipe::Module::new(state);
 
-    let recipe_id = cmd.create("john", "john_doe".to_owned()).await?;
+    let recipe_id = cmd
+        .create("john", "john_doe".to_owned(), RecipeType::MainCourse)
+        .await?;
 
     let mut input = UpdateInput {
         name: "My first Recipe".to_owned(),
@@ -112,7 +116,9 @@ async fn test_update_only_accepts_accompaniment() -> anyhow::Result<()> {
     let state = crate::helpers::setup_test_state(path).await?;
     let cmd = imkitchen_core::recipe::Module::new(state);
 
-    let recipe_id = cmd.create("john", "john_doe".to_owned()).await?;
+    let recipe_id = cmd
+        .create("john", "john_doe".to_owned(), RecipeType::MainCourse)
+        .await?;
 
     let mut input = UpdateInput {
         name: "My first Recipe".to_owned(),
@@ -156,3 +162,113 @@ async fn test_update_only_accepts_accompaniment() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_update_ingredient_hash_covers_every_unit() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::recipe::Module::new(state);
+
+    let recipe_id = cmd
+        .create("john", "john_doe".to_owned(), RecipeType::MainCourse)
+        .await?;
+
+    let mut input = UpdateInput {
+        name: "Pancakes".to_owned(),
+        origin: None,
+        description: "my description".to_owned(),
+        advance_prep: "".to_owned(),
+        dietary_restrictions: vec![],
+        accepts_accompaniment: false,
+        ingredients: vec![],
+        instructions: crate::helpers::instructions(),
+        household_size: 4,
+        cook_time: 25,
+        prep_time: 10,
+        recipe_type: RecipeType::MainCourse,
+        id: recipe_id.to_owned(),
+    };
+
+    let mut hashes = vec![];
+    for unit in [
+        IngredientUnit::Tsp,
+        IngredientUnit::Tbsp,
+        IngredientUnit::Cup,
+        IngredientUnit::Piece,
+        IngredientUnit::Pinch,
+    ] {
+        input.ingredients = vec![Ingredient {
+            name: "sugar".to_owned(),
+            quantity: 2,
+            unit: Some(unit),
+            category: None,
+        }];
+
+        cmd.update(input.clone(), "john").await?;
+        let hash = cmd.load(&recipe_id).await?.unwrap().ingredients_hash;
+
+        // Same input, same hash: no new event.
+        cmd.update(input.clone(), "john").await?;
+        assert_eq!(cmd.load(&recipe_id).await?.unwrap().ingredients_hash, hash);
+
+        assert!(!hashes.contains(&hash));
+        hashes.push(hash);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_update_instructions_resets_overlaps() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::recipe::Module::new(state);
+
+    let recipe_id = cmd
+        .create("john", "john_doe".to_owned(), RecipeType::MainCourse)
+        .await?;
+
+    let step = |description: &str| Instruction {
+        time_next: 10,
+        description: description.to_owned(),
+    };
+    let mut input = UpdateInput {
+        name: "My first Recipe".to_owned(),
+        origin: None,
+        description: "My first description".to_owned(),
+        advance_prep: String::new(),
+        dietary_restrictions: vec![],
+        accepts_accompaniment: false,
+        ingredients: vec![Ingredient {
+            name: "ingredient 1".to_owned(),
+            quantity: 1,
+            unit: Some(IngredientUnit::G),
+            category: None,
+        }],
+        instructions: vec![step("Simmer the sauce"), step("Chop vegetables")],
+        household_size: 4,
+        cook_time: 25,
+        prep_time: 10,
+        recipe_type: RecipeType::MainCourse,
+        id: recipe_id.to_owned(),
+    };
+
+    cmd.update(input.clone(), "john").await?;
+    cmd.set_instruction_overlaps(&recipe_id, vec![false, true], "john")
+        .await?;
+
+    // Saving the same steps keeps the flags.
+    cmd.update(input.clone(), "john").await?;
+    let recipe = cmd.load(&recipe_id).await?.unwrap();
+    assert_eq!(recipe.can_overlap, vec![false, true]);
+
+    // New steps don't inherit the old positions' flags.
+    input.instructions.insert(0, step("Boil water"));
+    cmd.update(input.clone(), "john").await?;
+    let recipe = cmd.load(&recipe_id).await?.unwrap();
+    assert!(recipe.can_overlap.is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use imkitchen_core::recipe::{ImportInput, MAX_INSTRUCTIONS, MAX_QUANTITY};
+use imkitchen_types::recipe::{Ingredient, Instruction, RecipeType};
+use temp_dir::TempDir;
+
+#[tokio::test]
+async fn test_import_reports_all_violations() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::recipe::Module::new(state);
+
+    let input = ImportInput {
+        name: "".to_owned(),
+        description: "ok description".to_owned(),
+        ingredients: vec![Ingredient {
+            name: "flour".to_owned(),
+            quantity: MAX_QUANTITY + 1,
+            unit: None,
+            category: None,
+        }],
+        instructions: (0..=MAX_INSTRUCTIONS)
+            .map(|index| Instruction {
+                description: format!("Stir for {index} minutes"),
+                time_next: 0,
+            })
+            .collect(),
+        household_size: 0,
+        cook_time: 25,
+        prep_time: 10,
+        recipe_type: RecipeType::MainCourse,
+        ..Default::default()
+    };
+
+    let Err(imkitchen_core::Error::Validate(errors)) = cmd.import(input, "john", None).await else {
+        anyhow::bail!("expected validation errors");
+    };
+
+    let mut fields = errors.field_errors().into_keys().collect::<Vec<_>>();
+    fields.sort();
+    assert_eq!(
+        fields,
+        vec!["household_size", "ingredients", "instructions", "name"]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_import_requires_ingredients_and_instructions() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = crate::helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_core::recipe::Module::new(state);
+
+    let input = ImportInput {
+        name: "Pancakes".to_owned(),
+        description: "ok description".to_owned(),
+        // Blank steps are dropped before validating, leaving none.
+        instructions: vec![Instruction {
+            description: "  ".to_owned(),
+            time_next: 0,
+        }],
+        household_size: 4,
+        cook_time: 25,
+        prep_time: 10,
+        recipe_type: RecipeType::MainCourse,
+        ..Default::default()
+    };
+
+    let Err(imkitchen_core::Error::Validate(errors)) = cmd.import(input, "john", None).await else {
+        anyhow::bail!("expected validation errors");
+    };
+
+    let errors = errors.field_errors();
+    let message = |field: &str| errors[field][0].message.as_deref().map(str::to_owned);
+    assert_eq!(
+        message("ingredients").as_deref(),
+        Some("at least one ingredient")
+    );
+    assert_eq!(
+        message("instructions").as_deref(),
+        Some("at least one step")
+    );
+
+    Ok(())
+}
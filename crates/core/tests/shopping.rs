@@ -1,8 +1,38 @@
 #[path = "shopping/add_recipe.rs"]
 mod add_recipe;
+#[path = "shopping/aisle_order.rs"]
+mod aisle_order;
+#[path = "shopping/expired.rs"]
+mod expired;
+#[path = "shopping/export.rs"]
+mod export;
+#[path = "shopping/guests.rs"]
+mod guests;
 #[path = "shopping/helpers/mod.rs"]
 mod helpers;
+#[path = "shopping/history.rs"]
+mod history;
+#[path = "shopping/leftovers.rs"]
+mod leftovers;
+#[path = "shopping/manual_item.rs"]
+mod manual_item;
+#[path = "shopping/outdated.rs"]
+mod outdated;
+#[path = "shopping/owned.rs"]
+mod owned;
+#[path = "shopping/package_size.rs"]
+mod package_size;
+#[path = "shopping/recipe_snapshot.rs"]
+mod recipe_snapshot;
 #[path = "shopping/regenerate.rs"]
 mod regenerate;
 #[path = "shopping/remove_recipe.rs"]
 mod remove_recipe;
+#[path = "shopping/repair.rs"]
+mod repair;
+#[path = "shopping/slot_servings.rs"]
+mod slot_servings;
+#[path = "shopping/sources.rs"]
+mod sources;
+#[path = "shopping/weeks.rs"]
+mod weeks;
@@ -0,0 +1,141 @@
+use crate::helpers;
+use imkitchen_core::recipe::ImportInput;
+use imkitchen_core::shopping::{Generate, Shopping};
+use imkitchen_types::recipe::{Ingredient, IngredientCategory, IngredientUnit, RecipeType};
+use temp_dir::TempDir;
+
+fn ingredient(name: &str, category: IngredientCategory) -> Ingredient {
+    Ingredient {
+        name: name.to_owned(),
+        quantity: 100,
+        unit: Some(IngredientUnit::G),
+        category: Some(category),
+    }
+}
+
+#[tokio::test]
+async fn test_aisles_follow_the_user_order() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = helpers::setup_test_state(path).await?;
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+    let shopping = imkitchen_core::shopping::Module::new(state.clone());
+
+    let id = recipe_cmd
+        .import(
+            ImportInput {
+                name: "Smoothie".to_owned(),
+                description: "desc".to_owned(),
+                instructions: crate::helpers::instructions(),
+                ingredients: vec![
+                    ingredient("banana", IngredientCategory::FruitsAndVegetables),
+                    ingredient("berries", IngredientCategory::Frozen),
+                    ingredient("oats", IngredientCategory::Grocery),
+                ],
+                household_size: 4,
+                cook_time: 5,
+                prep_time: 5,
+                recipe_type: RecipeType::MainCourse,
+                ..Default::default()
+            },
+            "john",
+            None,
+        )
+        .await?;
+    helpers::run_shopping_subscription(&state).await?;
+
+    sqlx::query("INSERT INTO shopping_slot (user_id, date, recipe_ids) VALUES (?, ?, ?)")
+        .bind("john")
+        .bind(20260101_i64)
+        .bind(bitcode::encode(&vec![id]))
+        .execute(&state.write_db)
+        .await?;
+    shopping
+        .generate(
+            Generate {
+                date: 20260101,
+                days: 7,
+                household_size: 4,
+            },
+            "john",
+        )
+        .await?;
+
+    let aisles = |current: &imkitchen_core::shopping::ShoppingState| {
+        current
+            .aisles()
+            .into_iter()
+            .filter_map(|(category, _)| category)
+            .collect::<Vec<_>>()
+    };
+
+    let current = shopping.state("john", 4).await?;
+    assert_eq!(current.aisle_order, IngredientCategory::WALK_ORDER.to_vec());
+    assert_eq!(
+        aisles(&current),
+        [
+            IngredientCategory::FruitsAndVegetables,
+            IngredientCategory::Frozen,
+            IngredientCategory::Grocery,
+        ]
+    );
+
+    shopping
+        .set_aisle_order(
+            vec![IngredientCategory::Grocery, IngredientCategory::Frozen],
+            "john",
+        )
+        .await?;
+
+    let current = shopping.state("john", 4).await?;
+    assert_eq!(
+        aisles(&current),
+        [
+            IngredientCategory::Grocery,
+            IngredientCategory::Frozen,
+            IngredientCategory::FruitsAndVegetables,
+        ]
+    );
+    let csv = current.to_csv();
+    let categories = csv
+        .split_terminator("\r\n")
+        .skip(1)
+        .map(|row| row.split(',').next().unwrap_or_default())
+        .collect::<Vec<_>>();
+    assert_eq!(categories, ["Grocery", "Frozen", "FruitsAndVegetables"]);
+
+    shopping.set_aisle_order(vec![], "john").await?;
+    let current = shopping.state("john", 4).await?;
+    assert_eq!(current.aisle_order, IngredientCategory::WALK_ORDER.to_vec());
+
+    Ok(())
+}
+
+#[test]
+fn test_unknown_stored_categories_are_ignored() {
+    let shopping = Shopping {
+        user_id: "john".to_owned(),
+        checked: Default::default(),
+        ingredients: Default::default(),
+        recipes: Default::default(),
+        cursor: Default::default(),
+        from_date: 0,
+        days: 0,
+        generated_at: 0,
+        household_size: 0,
+        package_sizes: Default::default(),
+        rounding_strategy: Default::default(),
+        manual_items: Default::default(),
+        aisle_order: vec!["Deli".to_owned(), "Bakery".to_owned()],
+        owned: Default::default(),
+    };
+
+    let order = shopping.aisle_order();
+    assert_eq!(order.len(), IngredientCategory::WALK_ORDER.len());
+    assert_eq!(order[0], IngredientCategory::Bakery);
+    let rest = IngredientCategory::WALK_ORDER
+        .into_iter()
+        .filter(|c| c != &IngredientCategory::Bakery)
+        .collect::<Vec<_>>();
+    assert_eq!(order[1..], rest);
+}
@@ -0,0 +1,56 @@
+use crate::helpers;
+use imkitchen_core::shopping::Generate;
+use temp_dir::TempDir;
+use time::OffsetDateTime;
+
+/// A list generated for a week that has passed reports expired, one covering
+/// today stays valid.
+#[tokio::test]
+async fn test_expired_after_week_passed() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = helpers::setup_test_state(path).await?;
+    let shopping = imkitchen_core::shopping::Module::new(state.clone());
+    let today = imkitchen_core::mealplan::date_to_u64(OffsetDateTime::now_utc());
+
+    shopping
+        .generate(
+            Generate {
+                date: 20200101,
+                days: 7,
+                household_size: 4,
+            },
+            "john",
+        )
+        .await?;
+    helpers::run_shopping_list_subscription(&state).await?;
+
+    let current = shopping.state("john", 4).await?;
+    assert!(current.generated_at > 0);
+    assert_eq!(current.valid_until(), Some(20200107));
+    assert!(current.is_expired(today));
+
+    let row = shopping.find("john").await?.expect("shopping list row");
+    assert_eq!(row.valid_until(), Some(20200107));
+    assert!(row.is_expired(today));
+
+    shopping
+        .generate(
+            Generate {
+                date: today,
+                days: 7,
+                household_size: 4,
+            },
+            "john",
+        )
+        .await?;
+    helpers::run_shopping_list_subscription(&state).await?;
+
+    let current = shopping.state("john", 4).await?;
+    assert!(!current.is_expired(today));
+
+    let row = shopping.find("john").await?.expect("shopping list row");
+    assert!(!row.is_expired(today));
+
+    Ok(())
+}
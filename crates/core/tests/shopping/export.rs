@@ -0,0 +1,149 @@
+use crate::helpers;
+use imkitchen_core::shopping::{AddManualItemInput, Generate, ToggleInput, manual_item_key};
+use temp_dir::TempDir;
+
+#[tokio::test]
+async fn test_csv_has_one_row_per_item_with_checked_state() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = helpers::setup_test_state(path).await?;
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+    let shopping = imkitchen_core::shopping::Module::new(state.clone());
+
+    let bread = helpers::import_recipe(&recipe_cmd, "Bread", "flour", 500, 4, "john").await?;
+    let rice = helpers::import_recipe(&recipe_cmd, "Pilaf", "rice", 300, 4, "john").await?;
+    helpers::run_shopping_subscription(&state).await?;
+
+    let recipe_ids = bitcode::encode(&vec![bread, rice]);
+    sqlx::query("INSERT INTO shopping_slot (user_id, date, recipe_ids) VALUES (?, ?, ?)")
+        .bind("john")
+        .bind(20260101_i64)
+        .bind(recipe_ids)
+        .execute(&state.write_db)
+        .await?;
+
+    shopping
+        .generate(
+            Generate {
+                date: 20260101,
+                days: 7,
+                household_size: 4,
+            },
+            "john",
+        )
+        .await?;
+    shopping
+        .add_manual_item(
+            AddManualItemInput {
+                name: "Paper towels, large".to_owned(),
+            },
+            "john",
+        )
+        .await?;
+
+    let current = shopping.state("john", 4).await?;
+    let rice = current
+        .ingredients
+        .iter()
+        .find(|i| i.name == "rice")
+        .expect("rice on the list")
+        .key();
+    for name in [rice, manual_item_key("Paper towels, large")] {
+        shopping.toggle(ToggleInput { name }, "john").await?;
+    }
+
+    let current = shopping.state("john", 4).await?;
+    let csv = current.to_csv();
+    let rows = csv.split_terminator("\r\n").collect::<Vec<_>>();
+
+    assert_eq!(rows[0], "category,item,quantity,unit,checked");
+    assert_eq!(
+        rows.len() - 1,
+        current.ingredients.len() + current.manual_items.len()
+    );
+    assert_eq!(
+        rows[1..],
+        [
+            "Grocery,flour,500,G,false",
+            "Grocery,rice,300,G,true",
+            ",\"Paper towels, large\",,,true",
+        ]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_csv_neutralizes_formulas_and_skips_owned() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = helpers::setup_test_state(path).await?;
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+    let shopping = imkitchen_core::shopping::Module::new(state.clone());
+
+    let bread = helpers::import_recipe(&recipe_cmd, "Bread", "flour", 500, 4, "john").await?;
+    let trap = helpers::import_recipe(
+        &recipe_cmd,
+        "Trap",
+        "=HYPERLINK(\"http://evil\")",
+        300,
+        4,
+        "john",
+    )
+    .await?;
+    let pilaf = helpers::import_recipe(&recipe_cmd, "Pilaf", "salt", 5, 4, "john").await?;
+    helpers::run_shopping_subscription(&state).await?;
+
+    let recipe_ids = bitcode::encode(&vec![bread, trap, pilaf]);
+    sqlx::query("INSERT INTO shopping_slot (user_id, date, recipe_ids) VALUES (?, ?, ?)")
+        .bind("john")
+        .bind(20260101_i64)
+        .bind(recipe_ids)
+        .execute(&state.write_db)
+        .await?;
+
+    shopping
+        .generate(
+            Generate {
+                date: 20260101,
+                days: 7,
+                household_size: 4,
+            },
+            "john",
+        )
+        .await?;
+    shopping
+        .add_manual_item(
+            AddManualItemInput {
+                name: "@SUM(A1)".to_owned(),
+            },
+            "john",
+        )
+        .await?;
+
+    let current = shopping.state("john", 4).await?;
+    let salt = current
+        .ingredients
+        .iter()
+        .find(|i| i.name == "salt")
+        .expect("salt on the list")
+        .key();
+    shopping
+        .toggle_owned(ToggleInput { name: salt }, "john")
+        .await?;
+
+    let current = shopping.state("john", 4).await?;
+    let csv = current.to_csv();
+    let rows = csv.split_terminator("\r\n").collect::<Vec<_>>();
+
+    assert_eq!(
+        rows[1..],
+        [
+            "Grocery,\"'=HYPERLINK(\"\"http://evil\"\")\",300,G,false",
+            "Grocery,flour,500,G,false",
+            ",'@SUM(A1),,,false",
+        ]
+    );
+
+    Ok(())
+}
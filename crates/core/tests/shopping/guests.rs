@@ -0,0 +1,98 @@
+use crate::helpers;
+use imkitchen_core::shopping::Generate;
+use std::collections::HashMap;
+use temp_dir::TempDir;
+use time::{Duration, OffsetDateTime, Weekday};
+
+/// Saturday is planned for 8 people: only its recipe's ingredients are
+/// scaled up, the other days stay at the household size.
+#[tokio::test]
+async fn test_guests_scale_only_their_day() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = helpers::setup_test_state(path).await?;
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+    let mealplan = imkitchen_core::mealplan::Module::new(state.clone());
+    let shopping = imkitchen_core::shopping::Module::new(state.clone());
+
+    for index in 0..7 {
+        let name = format!("Main {index}");
+        let ingredient = format!("ingredient {index}");
+        helpers::import_recipe(&recipe_cmd, &name, &ingredient, 100, 2, "john").await?;
+    }
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let today = OffsetDateTime::now_utc();
+    let saturday = (0..7)
+        .map(|offset| today + Duration::days(offset))
+        .find(|day| day.weekday() == Weekday::Saturday)
+        .expect("saturday");
+    let saturday_date = imkitchen_core::mealplan::date_to_u64(saturday);
+
+    mealplan
+        .generate(imkitchen_core::mealplan::Generate {
+            user_id: "john".to_owned(),
+            start: today.unix_timestamp() as u64,
+            days: 7,
+            household_size: 2,
+            guests: HashMap::from([(saturday_date, 8)]),
+            ..Default::default()
+        })
+        .await?;
+
+    imkitchen_core::mealplan::slot::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+    helpers::run_shopping_subscription(&state).await?;
+
+    let slots = mealplan
+        .range("john", today, today + Duration::days(6))
+        .await?;
+    let is_saturday = |day: u64| {
+        OffsetDateTime::from_unix_timestamp(day as i64)
+            .is_ok_and(|day| imkitchen_core::mealplan::date_to_u64(day) == saturday_date)
+    };
+    let saturday_slot = slots
+        .iter()
+        .find(|slot| is_saturday(slot.day))
+        .expect("saturday slot");
+    assert_eq!(saturday_slot.household_size, 8);
+    assert!(
+        slots
+            .iter()
+            .filter(|slot| !is_saturday(slot.day))
+            .all(|slot| slot.household_size == 2)
+    );
+    let saturday_ingredient = saturday_slot.main_course.name.replace("Main", "ingredient");
+
+    shopping
+        .generate(
+            Generate {
+                date: imkitchen_core::mealplan::date_to_u64(today),
+                days: 7,
+                household_size: 2,
+            },
+            "john",
+        )
+        .await?;
+
+    let current = shopping.state("john", 2).await?;
+    assert_eq!(current.ingredients.len(), 7);
+    for ingredient in current.ingredients {
+        let expected = if ingredient.name == saturday_ingredient {
+            400
+        } else {
+            100
+        };
+        assert_eq!(ingredient.quantity, expected, "{}", ingredient.name);
+    }
+
+    Ok(())
+}
@@ -4,7 +4,9 @@ use evento::{
 };
 use imkitchen_core::State;
 use imkitchen_core::recipe::ImportInput;
-use imkitchen_types::recipe::{Ingredient, IngredientCategory, IngredientUnit, RecipeType};
+use imkitchen_types::recipe::{
+    Ingredient, IngredientCategory, IngredientUnit, Instruction, RecipeType,
+};
 use sqlx::{SqlitePool, sqlite::SqliteConnectOptions};
 use std::{path::PathBuf, str::FromStr};
 
@@ -35,22 +37,19 @@ pub async fn import_recipe(
 ) -> anyhow::Result<String> {
     let input = ImportInput {
         name: name.to_owned(),
-        origin: None,
         description: "desc".to_owned(),
-        advance_prep: "".to_owned(),
+        instructions: crate::helpers::instructions(),
         ingredients: vec![Ingredient {
             name: ingredient_name.to_owned(),
             quantity,
             unit: Some(IngredientUnit::G),
             category: Some(IngredientCategory::Grocery),
         }],
-        instructions: vec![],
         household_size,
         cook_time: 25,
         prep_time: 10,
         recipe_type: RecipeType::MainCourse,
-        accepts_accompaniment: false,
-        dietary_restrictions: vec![],
+        ..Default::default()
     };
 
     cmd.import(input, user_id, None).await.map_err(Into::into)
@@ -75,3 +74,11 @@ pub async fn run_shopping_list_subscription(state: &State<Sqlite>) -> anyhow::Re
         .await?;
     Ok(())
 }
+
+/// A single step, the least a recipe needs to be valid.
+pub fn instructions() -> Vec<Instruction> {
+    vec![Instruction {
+        description: "Cook".to_owned(),
+        time_next: 0,
+    }]
+}
@@ -0,0 +1,277 @@
+use crate::helpers;
+use evento::cursor::Args;
+use imkitchen_core::shopping::{
+    AddManualItemInput, Generate, ToggleInput, history::RETENTION_WEEKS, manual_item_key,
+};
+use temp_dir::TempDir;
+
+async fn run_history_subscription(
+    state: &imkitchen_core::State<evento::Sqlite>,
+) -> anyhow::Result<()> {
+    imkitchen_core::shopping::history::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    Ok(())
+}
+
+/// Each generated week shows up in the history, newest first, with the share
+/// of items checked off while it was the current list.
+#[tokio::test]
+async fn test_history_newest_first_with_completion() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = helpers::setup_test_state(path).await?;
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+    let shopping = imkitchen_core::shopping::Module::new(state.clone());
+
+    let bread = helpers::import_recipe(&recipe_cmd, "Bread", "flour", 500, 4, "john").await?;
+    let cake = helpers::import_recipe(&recipe_cmd, "Cake", "sugar", 200, 4, "john").await?;
+    helpers::run_shopping_subscription(&state).await?;
+
+    let weeks = [20260105_u64, 20260112, 20260119];
+    let recipe_ids = bitcode::encode(&vec![bread, cake]);
+    for date in weeks {
+        sqlx::query("INSERT INTO shopping_slot (user_id, date, recipe_ids) VALUES (?, ?, ?)")
+            .bind("john")
+            .bind(date as i64)
+            .bind(&recipe_ids)
+            .execute(&state.write_db)
+            .await?;
+    }
+
+    // Week 1: everything bought. Week 2: half. Week 3: nothing yet.
+    for (date, checks) in weeks.into_iter().zip([2, 1, 0]) {
+        shopping
+            .generate(
+                Generate {
+                    date,
+                    days: 7,
+                    household_size: 4,
+                },
+                "john",
+            )
+            .await?;
+
+        let current = shopping.state("john", 4).await?;
+        assert_eq!(current.ingredients.len(), 2);
+        for ingredient in current.ingredients.iter().take(checks) {
+            shopping
+                .toggle(
+                    ToggleInput {
+                        name: ingredient.key(),
+                    },
+                    "john",
+                )
+                .await?;
+        }
+    }
+
+    run_history_subscription(&state).await?;
+
+    let history = shopping.history("john", Args::forward(10, None)).await?;
+    let rows = history
+        .edges
+        .iter()
+        .map(|e| {
+            (
+                e.node.from_date,
+                e.node.total,
+                e.node.checked,
+                e.node.completion_pct(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        rows,
+        vec![
+            (20260119, 2, 0, 0),
+            (20260112, 2, 1, 50),
+            (20260105, 2, 2, 100),
+        ]
+    );
+
+    let other = shopping.history("jane", Args::forward(10, None)).await?;
+    assert!(other.edges.is_empty());
+
+    Ok(())
+}
+
+/// Only the most recent weeks are kept once the retention window is full.
+#[tokio::test]
+async fn test_history_retention() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = helpers::setup_test_state(path).await?;
+    let shopping = imkitchen_core::shopping::Module::new(state.clone());
+
+    let start = time::Date::from_calendar_date(2026, time::Month::January, 5)?;
+    let weeks = (0..RETENTION_WEEKS + 2)
+        .map(|week| {
+            imkitchen_core::mealplan::date_to_u64(
+                (start + time::Duration::weeks(week as i64))
+                    .midnight()
+                    .assume_utc(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    for date in &weeks {
+        shopping
+            .generate(
+                Generate {
+                    date: *date,
+                    days: 7,
+                    household_size: 4,
+                },
+                "john",
+            )
+            .await?;
+    }
+
+    run_history_subscription(&state).await?;
+
+    let history = shopping.history("john", Args::forward(20, None)).await?;
+    let dates = history
+        .edges
+        .iter()
+        .map(|e| e.node.from_date)
+        .collect::<Vec<_>>();
+    let expected = weeks
+        .iter()
+        .rev()
+        .take(RETENTION_WEEKS as usize)
+        .copied()
+        .collect::<Vec<_>>();
+    assert_eq!(dates, expected);
+
+    Ok(())
+}
+
+/// Checks go to the latest week when two lists share the same generation
+/// timestamp.
+#[tokio::test]
+async fn test_history_checks_latest_week_on_equal_timestamps() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = helpers::setup_test_state(path).await?;
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+    let shopping = imkitchen_core::shopping::Module::new(state.clone());
+
+    let bread = helpers::import_recipe(&recipe_cmd, "Bread", "flour", 500, 4, "john").await?;
+    helpers::run_shopping_subscription(&state).await?;
+
+    let weeks = [20260105_u64, 20260112];
+    let recipe_ids = bitcode::encode(&vec![bread]);
+    for date in weeks {
+        sqlx::query("INSERT INTO shopping_slot (user_id, date, recipe_ids) VALUES (?, ?, ?)")
+            .bind("john")
+            .bind(date as i64)
+            .bind(&recipe_ids)
+            .execute(&state.write_db)
+            .await?;
+
+        shopping
+            .generate(
+                Generate {
+                    date,
+                    days: 7,
+                    household_size: 4,
+                },
+                "john",
+            )
+            .await?;
+    }
+
+    run_history_subscription(&state).await?;
+
+    sqlx::query("UPDATE shopping_history SET generated_at = 1000 WHERE user_id = ?")
+        .bind("john")
+        .execute(&state.write_db)
+        .await?;
+
+    let current = shopping.state("john", 4).await?;
+    shopping
+        .toggle(
+            ToggleInput {
+                name: current.ingredients[0].key(),
+            },
+            "john",
+        )
+        .await?;
+
+    run_history_subscription(&state).await?;
+
+    let history = shopping.history("john", Args::forward(10, None)).await?;
+    let rows = history
+        .edges
+        .iter()
+        .map(|e| (e.node.from_date, e.node.checked))
+        .collect::<Vec<_>>();
+    assert_eq!(rows, vec![(20260112, 1), (20260105, 0)]);
+
+    Ok(())
+}
+
+/// Manual items don't count towards completion and resetting the checklist
+/// empties it.
+#[tokio::test]
+async fn test_history_ignores_manual_items_and_resets() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = helpers::setup_test_state(path).await?;
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+    let shopping = imkitchen_core::shopping::Module::new(state.clone());
+
+    let bread = helpers::import_recipe(&recipe_cmd, "Bread", "flour", 500, 4, "john").await?;
+    let cake = helpers::import_recipe(&recipe_cmd, "Cake", "sugar", 200, 4, "john").await?;
+    helpers::run_shopping_subscription(&state).await?;
+
+    sqlx::query("INSERT INTO shopping_slot (user_id, date, recipe_ids) VALUES (?, ?, ?)")
+        .bind("john")
+        .bind(20260105_i64)
+        .bind(bitcode::encode(&vec![bread, cake]))
+        .execute(&state.write_db)
+        .await?;
+
+    shopping
+        .generate(
+            Generate {
+                date: 20260105,
+                days: 7,
+                household_size: 4,
+            },
+            "john",
+        )
+        .await?;
+    shopping
+        .add_manual_item(
+            AddManualItemInput {
+                name: "Trash bags".to_owned(),
+            },
+            "john",
+        )
+        .await?;
+
+    let current = shopping.state("john", 4).await?;
+    for name in [current.ingredients[0].key(), manual_item_key("Trash bags")] {
+        shopping.toggle(ToggleInput { name }, "john").await?;
+    }
+
+    run_history_subscription(&state).await?;
+
+    let history = shopping.history("john", Args::forward(10, None)).await?;
+    let row = &history.edges[0].node;
+    assert_eq!((row.checked, row.completion_pct()), (1, 50));
+
+    shopping.reset_checklist("john").await?;
+    run_history_subscription(&state).await?;
+
+    let history = shopping.history("john", Args::forward(10, None)).await?;
+    let row = &history.edges[0].node;
+    assert_eq!((row.checked, row.completion_pct()), (0, 0));
+
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use crate::helpers;
+use imkitchen_core::mealplan::date_to_u64;
+use imkitchen_core::recipe::ServingsYieldInput;
+use imkitchen_core::shopping::Generate;
+use temp_dir::TempDir;
+use time::{Duration, OffsetDateTime};
+
+/// A stew making 4 servings for a household of 2 is cooked once and eaten
+/// again the next day, which buys nothing more.
+#[tokio::test]
+async fn test_big_batch_leaves_leftovers_for_the_next_day() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = helpers::setup_test_state(path).await?;
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+    let mealplan = imkitchen_core::mealplan::Module::new(state.clone());
+    let shopping = imkitchen_core::shopping::Module::new(state.clone());
+
+    let stew = helpers::import_recipe(&recipe_cmd, "Stew", "beef", 800, 4, "john").await?;
+    recipe_cmd
+        .set_servings_yield(
+            ServingsYieldInput {
+                id: stew.to_owned(),
+                servings_yield: 4,
+            },
+            "john",
+        )
+        .await?;
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let today = OffsetDateTime::now_utc();
+    mealplan
+        .generate(imkitchen_core::mealplan::Generate {
+            user_id: "john".to_owned(),
+            start: today.unix_timestamp() as u64,
+            days: 2,
+            household_size: 2,
+            allow_leftovers: true,
+            ..Default::default()
+        })
+        .await?;
+
+    imkitchen_core::mealplan::slot::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+    helpers::run_shopping_subscription(&state).await?;
+
+    let slots = mealplan
+        .range("john", today, today + Duration::days(1))
+        .await?;
+    assert_eq!(slots.len(), 2);
+    assert!(slots.iter().all(|slot| slot.main_course.id == stew));
+    assert_eq!(slots[0].leftover_of, None);
+    assert_eq!(slots[1].leftover_of, Some(date_to_u64(today)));
+
+    shopping
+        .generate(
+            Generate {
+                date: date_to_u64(today),
+                days: 2,
+                household_size: 2,
+            },
+            "john",
+        )
+        .await?;
+
+    let current = shopping.state("john", 2).await?;
+    assert_eq!(current.ingredients.len(), 1);
+    assert_eq!(current.ingredients[0].quantity, 800);
+
+    shopping
+        .generate(
+            Generate {
+                date: date_to_u64(today + Duration::days(1)),
+                days: 1,
+                household_size: 2,
+            },
+            "john",
+        )
+        .await?;
+
+    assert!(shopping.state("john", 2).await?.ingredients.is_empty());
+
+    Ok(())
+}
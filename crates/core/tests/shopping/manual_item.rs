@@ -0,0 +1,100 @@
+use crate::helpers;
+use imkitchen_core::shopping::{AddManualItemInput, Generate, ToggleInput, manual_item_key};
+use temp_dir::TempDir;
+
+#[tokio::test]
+async fn test_manual_item_survives_checklist_reset() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = helpers::setup_test_state(path).await?;
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+    let shopping = imkitchen_core::shopping::Module::new(state.clone());
+
+    let bread = helpers::import_recipe(&recipe_cmd, "Bread", "flour", 500, 4, "john").await?;
+    helpers::run_shopping_subscription(&state).await?;
+
+    let recipe_ids = bitcode::encode(&vec![bread]);
+    sqlx::query("INSERT INTO shopping_slot (user_id, date, recipe_ids) VALUES (?, ?, ?)")
+        .bind("john")
+        .bind(20260101_i64)
+        .bind(recipe_ids)
+        .execute(&state.write_db)
+        .await?;
+
+    shopping
+        .generate(
+            Generate {
+                date: 20260101,
+                days: 7,
+                household_size: 4,
+            },
+            "john",
+        )
+        .await?;
+
+    for name in ["Trash bags", " trash BAGS "] {
+        shopping
+            .add_manual_item(
+                AddManualItemInput {
+                    name: name.to_owned(),
+                },
+                "john",
+            )
+            .await?;
+    }
+
+    let err = shopping
+        .add_manual_item(
+            AddManualItemInput {
+                name: "  ".to_owned(),
+            },
+            "john",
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err.to_string(), "item name is required");
+
+    let current = shopping.state("john", 4).await?;
+    assert_eq!(current.manual_items, vec!["Trash bags".to_owned()]);
+    let flour = current.ingredients[0].key();
+    let trash_bags = manual_item_key("Trash bags");
+
+    for name in [&flour, &trash_bags] {
+        shopping
+            .toggle(
+                ToggleInput {
+                    name: name.to_owned(),
+                },
+                "john",
+            )
+            .await?;
+    }
+
+    let current = shopping.state("john", 4).await?;
+    assert!(current.checked.contains(&flour));
+    assert!(current.checked.contains(&trash_bags));
+
+    shopping.reset_checklist("john").await?;
+
+    let current = shopping.state("john", 4).await?;
+    assert!(current.checked.is_empty());
+    assert_eq!(current.manual_items, vec!["Trash bags".to_owned()]);
+    assert_eq!(current.ingredients.len(), 1);
+
+    // A new week's list starts without them.
+    shopping
+        .generate(
+            Generate {
+                date: 20260101,
+                days: 7,
+                household_size: 4,
+            },
+            "john",
+        )
+        .await?;
+
+    let current = shopping.state("john", 4).await?;
+    assert!(current.manual_items.is_empty());
+
+    Ok(())
+}
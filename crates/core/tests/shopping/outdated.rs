@@ -0,0 +1,48 @@
+use crate::helpers;
+use imkitchen_core::shopping::Generate;
+use temp_dir::TempDir;
+
+/// The list remembers the household size it was generated for, so a later
+/// preference change flags it as possibly outdated.
+#[tokio::test]
+async fn test_outdated_after_household_size_change() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = helpers::setup_test_state(path).await?;
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+    let shopping = imkitchen_core::shopping::Module::new(state.clone());
+
+    let planned = helpers::import_recipe(&recipe_cmd, "Cake", "sugar", 200, 4, "john").await?;
+    helpers::run_shopping_subscription(&state).await?;
+
+    let recipe_ids = bitcode::encode(&vec![planned]);
+    sqlx::query("INSERT INTO shopping_slot (user_id, date, recipe_ids) VALUES (?, ?, ?)")
+        .bind("john")
+        .bind(20260101_i64)
+        .bind(recipe_ids)
+        .execute(&state.write_db)
+        .await?;
+
+    shopping
+        .generate(
+            Generate {
+                date: 20260101,
+                days: 7,
+                household_size: 4,
+            },
+            "john",
+        )
+        .await?;
+
+    let loaded = shopping.load("john").await?.expect("shopping aggregate");
+    assert_eq!(loaded.household_size, 4);
+
+    let current = shopping.state("john", 4).await?;
+    assert_eq!(current.outdated_household_size, None);
+
+    // Household grew from 4 to 6 after the list was generated.
+    let current = shopping.state("john", 6).await?;
+    assert_eq!(current.outdated_household_size, Some(4));
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use crate::helpers;
+use imkitchen_core::shopping::{Generate, ToggleInput};
+use temp_dir::TempDir;
+
+#[tokio::test]
+async fn test_owned_flag_survives_regeneration() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = helpers::setup_test_state(path).await?;
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+    let shopping = imkitchen_core::shopping::Module::new(state.clone());
+
+    let bread = helpers::import_recipe(&recipe_cmd, "Bread", "flour", 500, 4, "john").await?;
+    let pilaf = helpers::import_recipe(&recipe_cmd, "Pilaf", "salt", 5, 4, "john").await?;
+    helpers::run_shopping_subscription(&state).await?;
+
+    let recipe_ids = bitcode::encode(&vec![bread, pilaf]);
+    for date in [20260101_i64, 20260108] {
+        sqlx::query("INSERT INTO shopping_slot (user_id, date, recipe_ids) VALUES (?, ?, ?)")
+            .bind("john")
+            .bind(date)
+            .bind(&recipe_ids)
+            .execute(&state.write_db)
+            .await?;
+    }
+
+    let generate = |date| Generate {
+        date,
+        days: 7,
+        household_size: 4,
+    };
+    shopping.generate(generate(20260101), "john").await?;
+
+    let current = shopping.state("john", 4).await?;
+    let key = |name: &str| {
+        current
+            .ingredients
+            .iter()
+            .find(|i| i.name == name)
+            .map(|i| i.key())
+            .expect("ingredient on the list")
+    };
+    let salt = key("salt");
+    let flour = key("flour");
+
+    assert!(
+        shopping
+            .toggle_owned(
+                ToggleInput {
+                    name: "unknown".to_owned()
+                },
+                "john"
+            )
+            .await
+            .is_err()
+    );
+
+    shopping
+        .toggle_owned(ToggleInput { name: salt.clone() }, "john")
+        .await?;
+    shopping
+        .toggle(
+            ToggleInput {
+                name: flour.clone(),
+            },
+            "john",
+        )
+        .await?;
+
+    let current = shopping.state("john", 4).await?;
+    assert!(current.owned.contains(&salt));
+    assert!(!current.checked.contains(&salt));
+    assert!(current.checked.contains(&flour));
+
+    shopping.generate(generate(20260108), "john").await?;
+
+    let current = shopping.state("john", 4).await?;
+    assert!(current.checked.is_empty());
+    assert!(current.owned.contains(&salt));
+    assert!(current.ingredients.iter().any(|i| i.key() == salt));
+
+    shopping
+        .toggle_owned(ToggleInput { name: salt.clone() }, "john")
+        .await?;
+    let current = shopping.state("john", 4).await?;
+    assert!(current.owned.is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use crate::helpers;
+use imkitchen_core::recipe::ImportInput;
+use imkitchen_core::shopping::{Generate, PackageSizeInput};
+use imkitchen_types::recipe::{Ingredient, IngredientCategory, RecipeType};
+use temp_dir::TempDir;
+
+/// Merged quantities are rounded up to whole packages once the user records
+/// the size an ingredient is sold in.
+#[tokio::test]
+async fn test_round_to_package_size() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = helpers::setup_test_state(path).await?;
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+    let shopping = imkitchen_core::shopping::Module::new(state.clone());
+
+    let bread = helpers::import_recipe(&recipe_cmd, "Bread", "flour", 1100, 4, "john").await?;
+    let omelette = recipe_cmd
+        .import(
+            ImportInput {
+                name: "Omelette".to_owned(),
+                description: "desc".to_owned(),
+                instructions: crate::helpers::instructions(),
+                ingredients: vec![Ingredient {
+                    name: "eggs".to_owned(),
+                    quantity: 3,
+                    unit: None,
+                    category: Some(IngredientCategory::Refrigerated),
+                }],
+                household_size: 4,
+                cook_time: 10,
+                prep_time: 5,
+                recipe_type: RecipeType::MainCourse,
+                ..Default::default()
+            },
+            "john",
+            None,
+        )
+        .await?;
+    helpers::run_shopping_subscription(&state).await?;
+
+    let recipe_ids = bitcode::encode(&vec![bread, omelette]);
+    sqlx::query("INSERT INTO shopping_slot (user_id, date, recipe_ids) VALUES (?, ?, ?)")
+        .bind("john")
+        .bind(20260101_i64)
+        .bind(recipe_ids)
+        .execute(&state.write_db)
+        .await?;
+
+    shopping
+        .generate(
+            Generate {
+                date: 20260101,
+                days: 7,
+                household_size: 4,
+            },
+            "john",
+        )
+        .await?;
+
+    let current = shopping.state("john", 4).await?;
+    let find = |name: &str| {
+        current
+            .ingredients
+            .iter()
+            .find(|i| i.name == name)
+            .cloned()
+            .expect("ingredient")
+    };
+    let eggs = find("eggs");
+    let flour = find("flour");
+    assert_eq!(current.to_buy(&eggs), None);
+    assert_eq!(current.to_buy(&flour), None);
+
+    for (ingredient, package_size) in [(&eggs, 6), (&flour, 1000)] {
+        shopping
+            .set_package_size(
+                PackageSizeInput {
+                    name: ingredient.key(),
+                    package_size: Some(package_size),
+                },
+                "john",
+            )
+            .await?;
+    }
+
+    let current = shopping.state("john", 4).await?;
+    assert_eq!(current.to_buy(&eggs), Some(6));
+    assert_eq!(current.to_buy(&flour), Some(2000));
+
+    shopping
+        .set_package_size(
+            PackageSizeInput {
+                name: eggs.key(),
+                package_size: None,
+            },
+            "john",
+        )
+        .await?;
+
+    let current = shopping.state("john", 4).await?;
+    assert_eq!(current.to_buy(&eggs), None);
+
+    Ok(())
+}
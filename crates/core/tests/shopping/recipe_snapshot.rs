@@ -0,0 +1,111 @@
+use crate::helpers;
+use evento::Sqlite;
+use imkitchen_core::recipe::UpdateInput;
+use imkitchen_core::shopping::Generate;
+use imkitchen_types::recipe::{
+    Ingredient, IngredientCategory, IngredientUnit, Instruction, RecipeType,
+};
+use temp_dir::TempDir;
+use time::OffsetDateTime;
+
+/// Plans a single day around "Bread" (500 g of flour), then bumps the recipe
+/// to 900 g and returns the flour on the list generated afterwards.
+async fn flour_after_edit(
+    state: &imkitchen_core::State<Sqlite>,
+    snapshot: bool,
+) -> anyhow::Result<u32> {
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+    let mealplan = imkitchen_core::mealplan::Module::new(state.clone());
+    let shopping = imkitchen_core::shopping::Module::new(state.clone());
+
+    let id = helpers::import_recipe(&recipe_cmd, "Bread", "flour", 500, 4, "john").await?;
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+    helpers::run_shopping_subscription(state).await?;
+
+    let today = OffsetDateTime::now_utc();
+    mealplan
+        .generate(imkitchen_core::mealplan::Generate {
+            user_id: "john".to_owned(),
+            start: today.unix_timestamp() as u64,
+            days: 1,
+            household_size: 4,
+            snapshot_recipes: snapshot,
+            ..Default::default()
+        })
+        .await?;
+    helpers::run_shopping_subscription(state).await?;
+
+    recipe_cmd
+        .update(
+            UpdateInput {
+                id,
+                recipe_type: RecipeType::MainCourse,
+                name: "Bread".to_owned(),
+                origin: None,
+                description: "desc".to_owned(),
+                household_size: 4,
+                prep_time: 10,
+                cook_time: 25,
+                ingredients: vec![Ingredient {
+                    name: "flour".to_owned(),
+                    quantity: 900,
+                    unit: Some(IngredientUnit::G),
+                    category: Some(IngredientCategory::Grocery),
+                }],
+                instructions: vec![Instruction {
+                    time_next: 0,
+                    description: "Knead".to_owned(),
+                }],
+                dietary_restrictions: vec![],
+                accepts_accompaniment: false,
+                advance_prep: "".to_owned(),
+            },
+            "john",
+        )
+        .await?;
+    helpers::run_shopping_subscription(state).await?;
+
+    shopping
+        .generate(
+            Generate {
+                date: imkitchen_core::mealplan::date_to_u64(today),
+                days: 1,
+                household_size: 4,
+            },
+            "john",
+        )
+        .await?;
+
+    let current = shopping.state("john", 4).await?;
+    let flour = current
+        .ingredients
+        .iter()
+        .find(|i| i.name == "flour")
+        .expect("flour on the list");
+
+    Ok(flour.quantity)
+}
+
+#[tokio::test]
+async fn test_snapshotted_plan_ignores_recipe_edits() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let state = helpers::setup_test_state(dir.child("db.sqlite3")).await?;
+
+    assert_eq!(flour_after_edit(&state, true).await?, 500);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_live_plan_follows_recipe_edits() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let state = helpers::setup_test_state(dir.child("db.sqlite3")).await?;
+
+    assert_eq!(flour_after_edit(&state, false).await?, 900);
+
+    Ok(())
+}
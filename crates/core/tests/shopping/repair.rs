@@ -0,0 +1,109 @@
+use crate::helpers;
+use imkitchen_core::shopping::{AddManualItemInput, Generate, ToggleInput, manual_item_key};
+use std::collections::HashSet;
+use temp_dir::TempDir;
+
+#[tokio::test]
+async fn test_repair_rebuilds_from_plan() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = helpers::setup_test_state(path).await?;
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+    let shopping = imkitchen_core::shopping::Module::new(state.clone());
+
+    let bread = helpers::import_recipe(&recipe_cmd, "Bread", "flour", 500, 4, "john").await?;
+    let cake = helpers::import_recipe(&recipe_cmd, "Cake", "sugar", 200, 4, "john").await?;
+    let soup = helpers::import_recipe(&recipe_cmd, "Soup", "salt", 5, 4, "john").await?;
+    helpers::run_shopping_subscription(&state).await?;
+
+    let recipe_ids = bitcode::encode(&vec![bread.clone(), cake.clone()]);
+    sqlx::query("INSERT INTO shopping_slot (user_id, date, recipe_ids) VALUES (?, ?, ?)")
+        .bind("john")
+        .bind(20260101_i64)
+        .bind(recipe_ids)
+        .execute(&state.write_db)
+        .await?;
+
+    shopping
+        .generate(
+            Generate {
+                date: 20260101,
+                days: 7,
+                household_size: 4,
+            },
+            "john",
+        )
+        .await?;
+
+    let keys = |state: &imkitchen_core::shopping::ShoppingState| {
+        state
+            .ingredients
+            .iter()
+            .map(|i| (i.name.to_owned(), i.key()))
+            .collect::<std::collections::HashMap<_, _>>()
+    };
+    let planned = keys(&shopping.state("john", 4).await?);
+
+    shopping
+        .add_manual_item(
+            AddManualItemInput {
+                name: "Trash bags".to_owned(),
+            },
+            "john",
+        )
+        .await?;
+    for name in [planned["flour"].to_owned(), manual_item_key("Trash bags")] {
+        shopping.toggle(ToggleInput { name }, "john").await?;
+    }
+
+    // Drift away from the plan: bread dropped, an unplanned soup added and
+    // its salt checked.
+    shopping.remove_recipe(&bread, 4, "john").await?;
+    shopping.add_recipe(&soup, 4, "john").await?;
+    let drifted = keys(&shopping.state("john", 4).await?);
+    shopping
+        .toggle(
+            ToggleInput {
+                name: drifted["salt"].to_owned(),
+            },
+            "john",
+        )
+        .await?;
+
+    shopping.repair(4, "john").await?;
+
+    let repaired = shopping.state("john", 4).await?;
+    assert_eq!(
+        repaired.recipe_ids.iter().cloned().collect::<HashSet<_>>(),
+        HashSet::from([bread, cake])
+    );
+    assert_eq!(keys(&repaired), planned);
+    assert_eq!(repaired.manual_items, vec!["Trash bags".to_owned()]);
+    assert_eq!(
+        repaired.checked,
+        HashSet::from([planned["flour"].to_owned(), manual_item_key("Trash bags")])
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_repair_requires_generated_list() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = helpers::setup_test_state(path).await?;
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+    let shopping = imkitchen_core::shopping::Module::new(state.clone());
+
+    let bread = helpers::import_recipe(&recipe_cmd, "Bread", "flour", 500, 4, "john").await?;
+    helpers::run_shopping_subscription(&state).await?;
+    shopping.add_recipe(&bread, 4, "john").await?;
+
+    let err = shopping.repair(4, "john").await.unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "This shopping list wasn't generated from a meal plan"
+    );
+
+    Ok(())
+}
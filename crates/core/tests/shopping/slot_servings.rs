@@ -0,0 +1,154 @@
+use crate::helpers;
+use imkitchen_core::mealplan::ChangeSlotServings;
+use imkitchen_core::shopping::Generate;
+use imkitchen_types::recipe::RecipeType;
+use temp_dir::TempDir;
+use time::OffsetDateTime;
+
+/// Cooking the first day's main course twice over doubles only that
+/// recipe's ingredients, the other days stay at the household size.
+#[tokio::test]
+async fn test_slot_servings_scale_only_their_slot() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = helpers::setup_test_state(path).await?;
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+    let mealplan = imkitchen_core::mealplan::Module::new(state.clone());
+    let shopping = imkitchen_core::shopping::Module::new(state.clone());
+
+    for index in 0..7 {
+        let name = format!("Main {index}");
+        let ingredient = format!("ingredient {index}");
+        helpers::import_recipe(&recipe_cmd, &name, &ingredient, 100, 2, "john").await?;
+    }
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let today = OffsetDateTime::now_utc();
+    let today_date = imkitchen_core::mealplan::date_to_u64(today);
+
+    mealplan
+        .generate(imkitchen_core::mealplan::Generate {
+            user_id: "john".to_owned(),
+            start: today.unix_timestamp() as u64,
+            days: 7,
+            household_size: 2,
+            ..Default::default()
+        })
+        .await?;
+
+    imkitchen_core::mealplan::slot::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+    helpers::run_shopping_subscription(&state).await?;
+
+    for multiplier in [0.1, 12.0] {
+        assert!(
+            mealplan
+                .change_slot_servings(ChangeSlotServings {
+                    user_id: "john".to_owned(),
+                    date: today_date,
+                    recipe_type: RecipeType::MainCourse,
+                    multiplier,
+                })
+                .await
+                .is_err()
+        );
+    }
+
+    mealplan
+        .change_slot_servings(ChangeSlotServings {
+            user_id: "john".to_owned(),
+            date: today_date,
+            recipe_type: RecipeType::MainCourse,
+            multiplier: 2.0,
+        })
+        .await?;
+    helpers::run_shopping_subscription(&state).await?;
+
+    let doubled = mealplan
+        .slot_recipe("john", today_date, &RecipeType::MainCourse)
+        .await?
+        .expect("first day main course")
+        .name
+        .replace("Main", "ingredient");
+
+    shopping
+        .generate(
+            Generate {
+                date: today_date,
+                days: 7,
+                household_size: 2,
+            },
+            "john",
+        )
+        .await?;
+
+    let current = shopping.state("john", 2).await?;
+    assert_eq!(current.ingredients.len(), 7);
+    for ingredient in current.ingredients {
+        let expected = if ingredient.name == doubled { 200 } else { 100 };
+        assert_eq!(ingredient.quantity, expected, "{}", ingredient.name);
+    }
+
+    Ok(())
+}
+
+/// A recipe planned on two days is bought once; only the day cooked for
+/// more people adds to it.
+#[tokio::test]
+async fn test_slot_servings_apply_per_slot() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = helpers::setup_test_state(path).await?;
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+    let shopping = imkitchen_core::shopping::Module::new(state.clone());
+
+    let curry = helpers::import_recipe(&recipe_cmd, "Curry", "rice", 100, 2, "john").await?;
+    helpers::run_shopping_subscription(&state).await?;
+
+    let plan = async |servings: [Option<f32>; 2]| {
+        sqlx::query("DELETE FROM shopping_slot")
+            .execute(&state.write_db)
+            .await?;
+
+        for (date, multiplier) in [20260106_i64, 20260110].into_iter().zip(servings) {
+            sqlx::query(
+                "INSERT INTO shopping_slot (user_id, date, recipe_ids, servings) VALUES (?, ?, ?, ?)",
+            )
+            .bind("john")
+            .bind(date)
+            .bind(bitcode::encode(&vec![curry.to_owned()]))
+            .bind(multiplier.map(|multiplier| bitcode::encode(&vec![(curry.to_owned(), multiplier)])))
+            .execute(&state.write_db)
+            .await?;
+        }
+
+        shopping
+            .generate(
+                Generate {
+                    date: 20260105,
+                    days: 7,
+                    household_size: 2,
+                },
+                "john",
+            )
+            .await?;
+
+        let current = shopping.state("john", 2).await?;
+        anyhow::Ok(current.ingredients[0].quantity)
+    };
+
+    assert_eq!(plan([None, None]).await?, 100);
+    assert_eq!(plan([None, Some(2.0)]).await?, 200);
+    assert_eq!(plan([Some(2.0), Some(2.0)]).await?, 300);
+    assert_eq!(plan([Some(0.5), Some(0.5)]).await?, 50);
+
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use crate::helpers;
+use imkitchen_core::shopping::IngredientSource;
+use temp_dir::TempDir;
+
+/// Onions are bought for the soup and the curry: both show up as sources
+/// of the merged line, each with its own share, and the shares add up.
+#[tokio::test]
+async fn test_sources_break_down_merged_ingredients() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = helpers::setup_test_state(path).await?;
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+    let shopping = imkitchen_core::shopping::Module::new(state.clone());
+
+    let soup = helpers::import_recipe(&recipe_cmd, "Soup", "onion", 300, 4, "john").await?;
+    let curry = helpers::import_recipe(&recipe_cmd, "Curry", "onion", 50, 2, "john").await?;
+    let salad = helpers::import_recipe(&recipe_cmd, "Salad", "lettuce", 200, 4, "john").await?;
+    helpers::run_shopping_subscription(&state).await?;
+
+    for id in [&soup, &curry, &salad] {
+        shopping.add_recipe(id, 4, "john").await?;
+    }
+
+    let current = shopping.state("john", 4).await?;
+    let onion = current
+        .ingredients
+        .iter()
+        .find(|ingredient| ingredient.name == "onion")
+        .expect("onion is listed");
+    assert_eq!(onion.quantity, 400);
+
+    let mut sources = current.sources[&onion.key()].clone();
+    sources.sort_by_key(|source| source.quantity);
+    assert_eq!(
+        sources,
+        vec![
+            IngredientSource {
+                recipe_id: curry,
+                quantity: 100,
+            },
+            IngredientSource {
+                recipe_id: soup,
+                quantity: 300,
+            },
+        ]
+    );
+    assert_eq!(
+        sources.iter().map(|source| source.quantity).sum::<u32>(),
+        onion.quantity
+    );
+
+    let lettuce = current
+        .ingredients
+        .iter()
+        .find(|ingredient| ingredient.name == "lettuce")
+        .expect("lettuce is listed");
+    assert_eq!(
+        current.sources[&lettuce.key()],
+        vec![IngredientSource {
+            recipe_id: salad,
+            quantity: 200,
+        }]
+    );
+
+    Ok(())
+}
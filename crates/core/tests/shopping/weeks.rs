@@ -0,0 +1,77 @@
+use crate::helpers;
+use imkitchen_core::shopping::WeekQuantity;
+use temp_dir::TempDir;
+use time::{Duration, OffsetDateTime};
+
+/// Two planned weeks of recipes that all use rice: the combined list has a
+/// single rice line worth both weeks, split per week.
+#[tokio::test]
+async fn test_combined_weeks_sum_per_week() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = helpers::setup_test_state(path).await?;
+    let recipe_cmd = imkitchen_core::recipe::Module::new(state.clone());
+    let mealplan = imkitchen_core::mealplan::Module::new(state.clone());
+    let shopping = imkitchen_core::shopping::Module::new(state.clone());
+
+    for index in 0..7 {
+        let name = format!("Main {index}");
+        helpers::import_recipe(&recipe_cmd, &name, "rice", 100, 2, "john").await?;
+    }
+    helpers::import_recipe(&recipe_cmd, "Solo", "lentils", 50, 2, "albert").await?;
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let today = OffsetDateTime::now_utc();
+    mealplan
+        .generate(imkitchen_core::mealplan::Generate {
+            user_id: "john".to_owned(),
+            start: today.unix_timestamp() as u64,
+            days: 14,
+            household_size: 2,
+            ..Default::default()
+        })
+        .await?;
+
+    imkitchen_core::mealplan::slot::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+    helpers::run_shopping_subscription(&state).await?;
+
+    let week_1 = imkitchen_core::mealplan::date_to_u64(today);
+    let week_2 = imkitchen_core::mealplan::date_to_u64(today + Duration::days(7));
+
+    let items = shopping.combined_weeks("john", week_1, 2, 4).await?;
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].ingredient.name, "rice");
+    // 7 recipes for 2, cooked for 4, each week.
+    assert_eq!(items[0].ingredient.quantity, 2800);
+    assert_eq!(
+        items[0].weeks,
+        vec![
+            WeekQuantity {
+                from_date: week_1,
+                quantity: 1400,
+            },
+            WeekQuantity {
+                from_date: week_2,
+                quantity: 1400,
+            },
+        ]
+    );
+
+    // Only the first week is planned from there on.
+    let items = shopping.combined_weeks("john", week_2, 2, 4).await?;
+    assert_eq!(items[0].ingredient.quantity, 1400);
+    assert_eq!(items[0].weeks.len(), 1);
+
+    assert!(shopping.combined_weeks("john", week_1, 0, 4).await.is_err());
+
+    Ok(())
+}
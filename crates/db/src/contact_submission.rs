@@ -0,0 +1,178 @@
+use sea_query::Iden;
+
+#[derive(Iden, Clone)]
+pub enum ContactSubmission {
+    Table,
+    Id,
+    Email,
+    Ip,
+    Acknowledged,
+    CreatedAt,
+}
+
+pub(crate) mod m0042 {
+    use sea_query::{
+        ColumnDef, Index, IndexCreateStatement, IndexDropStatement, Table, TableCreateStatement,
+        TableDropStatement,
+    };
+
+    use super::ContactSubmission;
+
+    pub struct CreateTable;
+
+    fn create_table() -> TableCreateStatement {
+        Table::create()
+            .table(ContactSubmission::Table)
+            .col(
+                ColumnDef::new(ContactSubmission::Id)
+                    .string()
+                    .not_null()
+                    .string_len(26)
+                    .primary_key(),
+            )
+            .col(
+                ColumnDef::new(ContactSubmission::Email)
+                    .string()
+                    .not_null()
+                    .string_len(320),
+            )
+            .col(
+                ColumnDef::new(ContactSubmission::Ip)
+                    .string()
+                    .string_len(45),
+            )
+            .col(
+                ColumnDef::new(ContactSubmission::Acknowledged)
+                    .boolean()
+                    .not_null()
+                    .default(false),
+            )
+            .col(
+                ColumnDef::new(ContactSubmission::CreatedAt)
+                    .big_integer()
+                    .not_null(),
+            )
+            .to_owned()
+    }
+
+    fn drop_table() -> TableDropStatement {
+        Table::drop().table(ContactSubmission::Table).to_owned()
+    }
+
+    #[async_trait::async_trait]
+    impl sqlx_migrator::Operation<sqlx::Sqlite> for CreateTable {
+        async fn up(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = create_table().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+
+        async fn down(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = drop_table().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+    }
+
+    pub struct CreateIdx1;
+
+    fn create_idx_1() -> IndexCreateStatement {
+        Index::create()
+            .name("idx_contact_submission_Rk3nVb")
+            .table(ContactSubmission::Table)
+            .col(ContactSubmission::Email)
+            .col(ContactSubmission::CreatedAt)
+            .to_owned()
+    }
+
+    fn drop_idx_1() -> IndexDropStatement {
+        Index::drop()
+            .name("idx_contact_submission_Rk3nVb")
+            .table(ContactSubmission::Table)
+            .to_owned()
+    }
+
+    #[async_trait::async_trait]
+    impl sqlx_migrator::Operation<sqlx::Sqlite> for CreateIdx1 {
+        async fn up(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = create_idx_1().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+
+        async fn down(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = drop_idx_1().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+    }
+
+    pub struct CreateIdx2;
+
+    fn create_idx_2() -> IndexCreateStatement {
+        Index::create()
+            .name("idx_contact_submission_Wd7pLq")
+            .table(ContactSubmission::Table)
+            .col(ContactSubmission::Ip)
+            .col(ContactSubmission::CreatedAt)
+            .to_owned()
+    }
+
+    fn drop_idx_2() -> IndexDropStatement {
+        Index::drop()
+            .name("idx_contact_submission_Wd7pLq")
+            .table(ContactSubmission::Table)
+            .to_owned()
+    }
+
+    #[async_trait::async_trait]
+    impl sqlx_migrator::Operation<sqlx::Sqlite> for CreateIdx2 {
+        async fn up(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = create_idx_2().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+
+        async fn down(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = drop_idx_2().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+    }
+}
@@ -11,26 +11,72 @@ pub(crate) mod m0008;
 pub(crate) mod m0009;
 pub(crate) mod m0010;
 pub(crate) mod m0011;
+pub(crate) mod m0012;
+pub(crate) mod m0013;
+pub(crate) mod m0014;
+pub(crate) mod m0015;
+pub(crate) mod m0016;
+pub(crate) mod m0017;
+pub(crate) mod m0018;
+pub(crate) mod m0019;
+pub(crate) mod m0020;
+pub(crate) mod m0021;
+pub(crate) mod m0022;
+pub(crate) mod m0023;
+pub(crate) mod m0024;
+pub(crate) mod m0025;
+pub(crate) mod m0026;
+pub(crate) mod m0027;
+pub(crate) mod m0028;
+pub(crate) mod m0029;
+pub(crate) mod m0030;
+pub(crate) mod m0031;
+pub(crate) mod m0032;
+pub(crate) mod m0033;
+pub(crate) mod m0034;
+pub(crate) mod m0035;
+pub(crate) mod m0036;
+pub(crate) mod m0037;
+pub(crate) mod m0038;
+pub(crate) mod m0039;
+pub(crate) mod m0040;
+pub(crate) mod m0041;
+pub(crate) mod m0042;
+pub(crate) mod m0043;
+pub(crate) mod m0044;
 
 pub mod contact_admin;
 pub mod contact_global_stat;
+pub mod contact_submission;
 pub mod fts;
 pub mod mealplan_recipe;
+pub mod mealplan_skipped_week;
 pub mod mealplan_slot;
+pub mod mealplan_snapshot;
+pub mod notification_delivery;
+pub mod notification_push_subscription;
 pub mod notification_recipient;
 pub mod origin_framing;
+pub mod passkey_challenge;
+pub mod recipe_comment;
+pub mod recipe_cooked;
 pub mod recipe_owner;
+pub mod recipe_rating;
+pub mod recipe_rating_stat;
 pub mod recipe_thumbnail;
 pub mod recipe_user;
 pub mod recipe_user_stat;
+pub mod shopping_history;
 pub mod shopping_list;
 pub mod shopping_recipe;
+pub mod shopping_recipe_snapshot;
 pub mod shopping_slot;
 pub mod user;
 pub mod user_admin;
 pub mod user_global_stat;
 pub mod user_invoice_user;
 pub mod user_login;
+pub mod user_login_history;
 pub mod user_subscription;
 
 pub fn migrator<DB: sqlx::Database>() -> Result<Migrator<DB>, sqlx_migrator::Error>
@@ -51,6 +97,39 @@ where
     m0009::Migration: sqlx_migrator::Migration<DB>,
     m0010::Migration: sqlx_migrator::Migration<DB>,
     m0011::Migration: sqlx_migrator::Migration<DB>,
+    m0012::Migration: sqlx_migrator::Migration<DB>,
+    m0013::Migration: sqlx_migrator::Migration<DB>,
+    m0014::Migration: sqlx_migrator::Migration<DB>,
+    m0015::Migration: sqlx_migrator::Migration<DB>,
+    m0016::Migration: sqlx_migrator::Migration<DB>,
+    m0017::Migration: sqlx_migrator::Migration<DB>,
+    m0018::Migration: sqlx_migrator::Migration<DB>,
+    m0019::Migration: sqlx_migrator::Migration<DB>,
+    m0020::Migration: sqlx_migrator::Migration<DB>,
+    m0021::Migration: sqlx_migrator::Migration<DB>,
+    m0022::Migration: sqlx_migrator::Migration<DB>,
+    m0023::Migration: sqlx_migrator::Migration<DB>,
+    m0024::Migration: sqlx_migrator::Migration<DB>,
+    m0025::Migration: sqlx_migrator::Migration<DB>,
+    m0026::Migration: sqlx_migrator::Migration<DB>,
+    m0027::Migration: sqlx_migrator::Migration<DB>,
+    m0028::Migration: sqlx_migrator::Migration<DB>,
+    m0029::Migration: sqlx_migrator::Migration<DB>,
+    m0030::Migration: sqlx_migrator::Migration<DB>,
+    m0031::Migration: sqlx_migrator::Migration<DB>,
+    m0032::Migration: sqlx_migrator::Migration<DB>,
+    m0033::Migration: sqlx_migrator::Migration<DB>,
+    m0034::Migration: sqlx_migrator::Migration<DB>,
+    m0035::Migration: sqlx_migrator::Migration<DB>,
+    m0036::Migration: sqlx_migrator::Migration<DB>,
+    m0037::Migration: sqlx_migrator::Migration<DB>,
+    m0038::Migration: sqlx_migrator::Migration<DB>,
+    m0039::Migration: sqlx_migrator::Migration<DB>,
+    m0040::Migration: sqlx_migrator::Migration<DB>,
+    m0041::Migration: sqlx_migrator::Migration<DB>,
+    m0042::Migration: sqlx_migrator::Migration<DB>,
+    m0043::Migration: sqlx_migrator::Migration<DB>,
+    m0044::Migration: sqlx_migrator::Migration<DB>,
 {
     let mut migrator = evento::sql_migrator::new::<DB>()?;
     migrator.add_migrations(vec![
@@ -65,6 +144,39 @@ where
         Box::new(m0009::Migration),
         Box::new(m0010::Migration),
         Box::new(m0011::Migration),
+        Box::new(m0012::Migration),
+        Box::new(m0013::Migration),
+        Box::new(m0014::Migration),
+        Box::new(m0015::Migration),
+        Box::new(m0016::Migration),
+        Box::new(m0017::Migration),
+        Box::new(m0018::Migration),
+        Box::new(m0019::Migration),
+        Box::new(m0020::Migration),
+        Box::new(m0021::Migration),
+        Box::new(m0022::Migration),
+        Box::new(m0023::Migration),
+        Box::new(m0024::Migration),
+        Box::new(m0025::Migration),
+        Box::new(m0026::Migration),
+        Box::new(m0027::Migration),
+        Box::new(m0028::Migration),
+        Box::new(m0029::Migration),
+        Box::new(m0030::Migration),
+        Box::new(m0031::Migration),
+        Box::new(m0032::Migration),
+        Box::new(m0033::Migration),
+        Box::new(m0034::Migration),
+        Box::new(m0035::Migration),
+        Box::new(m0036::Migration),
+        Box::new(m0037::Migration),
+        Box::new(m0038::Migration),
+        Box::new(m0039::Migration),
+        Box::new(m0040::Migration),
+        Box::new(m0041::Migration),
+        Box::new(m0042::Migration),
+        Box::new(m0043::Migration),
+        Box::new(m0044::Migration),
     ])?;
 
     Ok(migrator)
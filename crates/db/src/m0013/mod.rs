@@ -0,0 +1,11 @@
+use sqlx_migrator::vec_box;
+
+pub struct Migration;
+
+sqlx_migrator::sqlite_migration!(
+    Migration,
+    "imkitchen",
+    "m0013",
+    vec_box![super::m0012::Migration],
+    vec_box![crate::mealplan_slot::m0013::RebuildForAdvancePrepDone]
+);
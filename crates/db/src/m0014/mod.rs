@@ -0,0 +1,14 @@
+use sqlx_migrator::vec_box;
+
+pub struct Migration;
+
+sqlx_migrator::sqlite_migration!(
+    Migration,
+    "imkitchen",
+    "m0014",
+    vec_box![super::m0013::Migration],
+    vec_box![
+        crate::shopping_history::m0014::CreateTable,
+        crate::shopping_history::m0014::CreateIdx1,
+    ]
+);
@@ -0,0 +1,11 @@
+use sqlx_migrator::vec_box;
+
+pub struct Migration;
+
+sqlx_migrator::sqlite_migration!(
+    Migration,
+    "imkitchen",
+    "m0016",
+    vec_box![super::m0015::Migration],
+    vec_box![crate::recipe_user_stat::m0016::RebuildForFavoriteCount]
+);
@@ -0,0 +1,14 @@
+use sqlx_migrator::vec_box;
+
+pub struct Migration;
+
+sqlx_migrator::sqlite_migration!(
+    Migration,
+    "imkitchen",
+    "m0017",
+    vec_box![super::m0016::Migration],
+    vec_box![
+        crate::recipe_rating::m0017::CreateTable,
+        crate::recipe_rating::m0017::CreateIdx1,
+    ]
+);
@@ -0,0 +1,11 @@
+use sqlx_migrator::vec_box;
+
+pub struct Migration;
+
+sqlx_migrator::sqlite_migration!(
+    Migration,
+    "imkitchen",
+    "m0019",
+    vec_box![super::m0018::Migration],
+    vec_box![crate::mealplan_snapshot::m0019::CreateTable]
+);
@@ -0,0 +1,11 @@
+use sqlx_migrator::vec_box;
+
+pub struct Migration;
+
+sqlx_migrator::sqlite_migration!(
+    Migration,
+    "imkitchen",
+    "m0020",
+    vec_box![super::m0019::Migration],
+    vec_box![crate::notification_delivery::m0020::CreateTable]
+);
@@ -0,0 +1,11 @@
+use sqlx_migrator::vec_box;
+
+pub struct Migration;
+
+sqlx_migrator::sqlite_migration!(
+    Migration,
+    "imkitchen",
+    "m0021",
+    vec_box![super::m0020::Migration],
+    vec_box![crate::shopping_recipe_snapshot::m0021::CreateTable]
+);
@@ -0,0 +1,14 @@
+use sqlx_migrator::vec_box;
+
+pub struct Migration;
+
+sqlx_migrator::sqlite_migration!(
+    Migration,
+    "imkitchen",
+    "m0022",
+    vec_box![super::m0021::Migration],
+    vec_box![
+        crate::user_login_history::m0022::CreateTable,
+        crate::user_login_history::m0022::CreateIdx1,
+    ]
+);
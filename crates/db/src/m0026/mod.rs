@@ -0,0 +1,14 @@
+use sqlx_migrator::vec_box;
+
+pub struct Migration;
+
+sqlx_migrator::sqlite_migration!(
+    Migration,
+    "imkitchen",
+    "m0026",
+    vec_box![super::m0025::Migration],
+    vec_box![
+        crate::mealplan_recipe::m0026::AddServingsYield,
+        crate::mealplan_slot::m0026::AddLeftoverOf,
+    ]
+);
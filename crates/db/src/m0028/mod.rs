@@ -0,0 +1,11 @@
+use sqlx_migrator::vec_box;
+
+pub struct Migration;
+
+sqlx_migrator::sqlite_migration!(
+    Migration,
+    "imkitchen",
+    "m0028",
+    vec_box![super::m0027::Migration],
+    vec_box![crate::mealplan_recipe::m0028::AddComplexity]
+);
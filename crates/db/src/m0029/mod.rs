@@ -0,0 +1,11 @@
+use sqlx_migrator::vec_box;
+
+pub struct Migration;
+
+sqlx_migrator::sqlite_migration!(
+    Migration,
+    "imkitchen",
+    "m0029",
+    vec_box![super::m0028::Migration],
+    vec_box![crate::recipe_user::m0029::AddTags]
+);
@@ -0,0 +1,11 @@
+use sqlx_migrator::vec_box;
+
+pub struct Migration;
+
+sqlx_migrator::sqlite_migration!(
+    Migration,
+    "imkitchen",
+    "m0035",
+    vec_box![super::m0034::Migration],
+    vec_box![crate::mealplan_skipped_week::m0035::CreateTable]
+);
@@ -0,0 +1,11 @@
+use sqlx_migrator::vec_box;
+
+pub struct Migration;
+
+sqlx_migrator::sqlite_migration!(
+    Migration,
+    "imkitchen",
+    "m0036",
+    vec_box![super::m0035::Migration],
+    vec_box![crate::shopping_slot::m0036::AddServings]
+);
@@ -0,0 +1,11 @@
+use sqlx_migrator::vec_box;
+
+pub struct Migration;
+
+sqlx_migrator::sqlite_migration!(
+    Migration,
+    "imkitchen",
+    "m0037",
+    vec_box![super::m0036::Migration],
+    vec_box![crate::recipe_rating_stat::m0037::CreateTable]
+);
@@ -0,0 +1,14 @@
+use sqlx_migrator::vec_box;
+
+pub struct Migration;
+
+sqlx_migrator::sqlite_migration!(
+    Migration,
+    "imkitchen",
+    "m0039",
+    vec_box![super::m0038::Migration],
+    vec_box![
+        crate::recipe_comment::m0039::CreateTable,
+        crate::recipe_comment::m0039::CreateIdx1,
+    ]
+);
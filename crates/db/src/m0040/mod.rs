@@ -0,0 +1,15 @@
+use sqlx_migrator::vec_box;
+
+pub struct Migration;
+
+sqlx_migrator::sqlite_migration!(
+    Migration,
+    "imkitchen",
+    "m0040",
+    vec_box![super::m0039::Migration],
+    vec_box![
+        crate::recipe_comment::m0040::AddFlagCount,
+        crate::recipe_comment::m0040::AddIsHidden,
+        crate::recipe_comment::m0040::CreateFlagTable,
+    ]
+);
@@ -0,0 +1,14 @@
+use sqlx_migrator::vec_box;
+
+pub struct Migration;
+
+sqlx_migrator::sqlite_migration!(
+    Migration,
+    "imkitchen",
+    "m0041",
+    vec_box![super::m0040::Migration],
+    vec_box![
+        crate::notification_push_subscription::m0041::CreateTable,
+        crate::notification_push_subscription::m0041::CreateIdx1,
+    ]
+);
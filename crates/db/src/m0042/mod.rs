@@ -0,0 +1,15 @@
+use sqlx_migrator::vec_box;
+
+pub struct Migration;
+
+sqlx_migrator::sqlite_migration!(
+    Migration,
+    "imkitchen",
+    "m0042",
+    vec_box![super::m0041::Migration],
+    vec_box![
+        crate::contact_submission::m0042::CreateTable,
+        crate::contact_submission::m0042::CreateIdx1,
+        crate::contact_submission::m0042::CreateIdx2,
+    ]
+);
@@ -0,0 +1,14 @@
+use sqlx_migrator::vec_box;
+
+pub struct Migration;
+
+sqlx_migrator::sqlite_migration!(
+    Migration,
+    "imkitchen",
+    "m0043",
+    vec_box![super::m0042::Migration],
+    vec_box![
+        crate::passkey_challenge::m0043::CreateTable,
+        crate::passkey_challenge::m0043::CreateIdx1,
+    ]
+);
@@ -0,0 +1,11 @@
+use sqlx_migrator::vec_box;
+
+pub struct Migration;
+
+sqlx_migrator::sqlite_migration!(
+    Migration,
+    "imkitchen",
+    "m0044",
+    vec_box![super::m0043::Migration],
+    vec_box![crate::recipe_user::m0044::AddCuisineType]
+);
@@ -12,6 +12,20 @@ pub enum MealPlanRecipe {
     CookTime,
     AcceptsAccompaniment,
     DietaryRestrictions,
+    AccompanimentType,
+    PreferredAccompanimentTypes,
+    DefaultAccompanimentIds,
+    Equipment,
+    MinHouseholdSize,
+    ServingsYield,
+    HouseholdSize,
+    Nutrition,
+    Complexity,
+    IngredientCount,
+    InstructionCount,
+    CuisineType,
+    AdvancePrepHours,
+    Perishability,
 }
 
 pub(crate) mod m0001 {
@@ -253,3 +267,632 @@ pub(crate) mod m0001 {
         }
     }
 }
+
+pub(crate) mod m0015 {
+    use sea_query::{ColumnDef, Table, TableAlterStatement};
+
+    use super::MealPlanRecipe;
+
+    pub struct AddAccompanimentTypes;
+
+    fn add_accompaniment_type() -> TableAlterStatement {
+        Table::alter()
+            .table(MealPlanRecipe::Table)
+            .add_column(
+                ColumnDef::new(MealPlanRecipe::AccompanimentType)
+                    .string()
+                    .string_len(25),
+            )
+            .to_owned()
+    }
+
+    fn add_preferred_accompaniment_types() -> TableAlterStatement {
+        Table::alter()
+            .table(MealPlanRecipe::Table)
+            .add_column(
+                ColumnDef::new(MealPlanRecipe::PreferredAccompanimentTypes)
+                    .json_binary()
+                    .not_null()
+                    .default("[]"),
+            )
+            .to_owned()
+    }
+
+    fn drop_accompaniment_type() -> TableAlterStatement {
+        Table::alter()
+            .table(MealPlanRecipe::Table)
+            .drop_column(MealPlanRecipe::AccompanimentType)
+            .to_owned()
+    }
+
+    fn drop_preferred_accompaniment_types() -> TableAlterStatement {
+        Table::alter()
+            .table(MealPlanRecipe::Table)
+            .drop_column(MealPlanRecipe::PreferredAccompanimentTypes)
+            .to_owned()
+    }
+
+    #[async_trait::async_trait]
+    impl sqlx_migrator::Operation<sqlx::Sqlite> for AddAccompanimentTypes {
+        async fn up(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            // SQLite only adds one column per ALTER TABLE.
+            for statement in [
+                add_accompaniment_type(),
+                add_preferred_accompaniment_types(),
+            ] {
+                let statement = statement.to_string(sea_query::SqliteQueryBuilder);
+                sqlx::query(sqlx::AssertSqlSafe(statement))
+                    .execute(&mut *connection)
+                    .await?;
+            }
+
+            Ok(())
+        }
+
+        async fn down(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            for statement in [
+                drop_preferred_accompaniment_types(),
+                drop_accompaniment_type(),
+            ] {
+                let statement = statement.to_string(sea_query::SqliteQueryBuilder);
+                sqlx::query(sqlx::AssertSqlSafe(statement))
+                    .execute(&mut *connection)
+                    .await?;
+            }
+
+            Ok(())
+        }
+    }
+}
+
+pub(crate) mod m0023 {
+    use sea_query::{ColumnDef, Table, TableAlterStatement};
+
+    use super::MealPlanRecipe;
+
+    pub struct AddDefaultAccompanimentIds;
+
+    fn add_column() -> TableAlterStatement {
+        Table::alter()
+            .table(MealPlanRecipe::Table)
+            .add_column(
+                ColumnDef::new(MealPlanRecipe::DefaultAccompanimentIds)
+                    .json_binary()
+                    .not_null()
+                    .default("[]"),
+            )
+            .to_owned()
+    }
+
+    fn drop_column() -> TableAlterStatement {
+        Table::alter()
+            .table(MealPlanRecipe::Table)
+            .drop_column(MealPlanRecipe::DefaultAccompanimentIds)
+            .to_owned()
+    }
+
+    #[async_trait::async_trait]
+    impl sqlx_migrator::Operation<sqlx::Sqlite> for AddDefaultAccompanimentIds {
+        async fn up(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = add_column().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+
+        async fn down(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = drop_column().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+    }
+}
+
+pub(crate) mod m0024 {
+    use sea_query::{ColumnDef, Table, TableAlterStatement};
+
+    use super::MealPlanRecipe;
+
+    pub struct AddEquipment;
+
+    fn add_column() -> TableAlterStatement {
+        Table::alter()
+            .table(MealPlanRecipe::Table)
+            .add_column(
+                ColumnDef::new(MealPlanRecipe::Equipment)
+                    .json_binary()
+                    .not_null()
+                    .default("[]"),
+            )
+            .to_owned()
+    }
+
+    fn drop_column() -> TableAlterStatement {
+        Table::alter()
+            .table(MealPlanRecipe::Table)
+            .drop_column(MealPlanRecipe::Equipment)
+            .to_owned()
+    }
+
+    #[async_trait::async_trait]
+    impl sqlx_migrator::Operation<sqlx::Sqlite> for AddEquipment {
+        async fn up(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = add_column().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+
+        async fn down(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = drop_column().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+    }
+}
+
+pub(crate) mod m0025 {
+    use sea_query::{ColumnDef, Table, TableAlterStatement};
+
+    use super::MealPlanRecipe;
+
+    pub struct AddMinHouseholdSize;
+
+    fn add_column() -> TableAlterStatement {
+        Table::alter()
+            .table(MealPlanRecipe::Table)
+            .add_column(
+                ColumnDef::new(MealPlanRecipe::MinHouseholdSize)
+                    .integer()
+                    .not_null()
+                    .default(0),
+            )
+            .to_owned()
+    }
+
+    fn drop_column() -> TableAlterStatement {
+        Table::alter()
+            .table(MealPlanRecipe::Table)
+            .drop_column(MealPlanRecipe::MinHouseholdSize)
+            .to_owned()
+    }
+
+    #[async_trait::async_trait]
+    impl sqlx_migrator::Operation<sqlx::Sqlite> for AddMinHouseholdSize {
+        async fn up(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = add_column().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+
+        async fn down(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = drop_column().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+    }
+}
+
+pub(crate) mod m0026 {
+    use sea_query::{ColumnDef, Table, TableAlterStatement};
+
+    use super::MealPlanRecipe;
+
+    pub struct AddServingsYield;
+
+    fn add_column() -> TableAlterStatement {
+        Table::alter()
+            .table(MealPlanRecipe::Table)
+            .add_column(
+                ColumnDef::new(MealPlanRecipe::ServingsYield)
+                    .integer()
+                    .not_null()
+                    .default(0),
+            )
+            .to_owned()
+    }
+
+    fn drop_column() -> TableAlterStatement {
+        Table::alter()
+            .table(MealPlanRecipe::Table)
+            .drop_column(MealPlanRecipe::ServingsYield)
+            .to_owned()
+    }
+
+    #[async_trait::async_trait]
+    impl sqlx_migrator::Operation<sqlx::Sqlite> for AddServingsYield {
+        async fn up(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = add_column().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+
+        async fn down(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = drop_column().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+    }
+}
+
+pub(crate) mod m0027 {
+    use sea_query::{ColumnDef, Table, TableAlterStatement};
+
+    use super::MealPlanRecipe;
+
+    pub struct AddNutrition;
+
+    fn add_household_size() -> TableAlterStatement {
+        Table::alter()
+            .table(MealPlanRecipe::Table)
+            .add_column(
+                ColumnDef::new(MealPlanRecipe::HouseholdSize)
+                    .integer()
+                    .not_null()
+                    .default(0),
+            )
+            .to_owned()
+    }
+
+    fn add_nutrition() -> TableAlterStatement {
+        Table::alter()
+            .table(MealPlanRecipe::Table)
+            .add_column(ColumnDef::new(MealPlanRecipe::Nutrition).json_binary())
+            .to_owned()
+    }
+
+    fn drop_household_size() -> TableAlterStatement {
+        Table::alter()
+            .table(MealPlanRecipe::Table)
+            .drop_column(MealPlanRecipe::HouseholdSize)
+            .to_owned()
+    }
+
+    fn drop_nutrition() -> TableAlterStatement {
+        Table::alter()
+            .table(MealPlanRecipe::Table)
+            .drop_column(MealPlanRecipe::Nutrition)
+            .to_owned()
+    }
+
+    #[async_trait::async_trait]
+    impl sqlx_migrator::Operation<sqlx::Sqlite> for AddNutrition {
+        async fn up(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            for statement in [add_household_size(), add_nutrition()] {
+                let statement = statement.to_string(sea_query::SqliteQueryBuilder);
+                sqlx::query(sqlx::AssertSqlSafe(statement))
+                    .execute(&mut *connection)
+                    .await?;
+            }
+
+            Ok(())
+        }
+
+        async fn down(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            for statement in [drop_nutrition(), drop_household_size()] {
+                let statement = statement.to_string(sea_query::SqliteQueryBuilder);
+                sqlx::query(sqlx::AssertSqlSafe(statement))
+                    .execute(&mut *connection)
+                    .await?;
+            }
+
+            Ok(())
+        }
+    }
+}
+
+pub(crate) mod m0028 {
+    use sea_query::{ColumnDef, Table, TableAlterStatement};
+
+    use super::MealPlanRecipe;
+
+    pub struct AddComplexity;
+
+    fn add_complexity() -> TableAlterStatement {
+        Table::alter()
+            .table(MealPlanRecipe::Table)
+            .add_column(ColumnDef::new(MealPlanRecipe::Complexity).string())
+            .to_owned()
+    }
+
+    fn add_ingredient_count() -> TableAlterStatement {
+        Table::alter()
+            .table(MealPlanRecipe::Table)
+            .add_column(
+                ColumnDef::new(MealPlanRecipe::IngredientCount)
+                    .integer()
+                    .not_null()
+                    .default(0),
+            )
+            .to_owned()
+    }
+
+    fn add_instruction_count() -> TableAlterStatement {
+        Table::alter()
+            .table(MealPlanRecipe::Table)
+            .add_column(
+                ColumnDef::new(MealPlanRecipe::InstructionCount)
+                    .integer()
+                    .not_null()
+                    .default(0),
+            )
+            .to_owned()
+    }
+
+    fn drop_column(column: MealPlanRecipe) -> TableAlterStatement {
+        Table::alter()
+            .table(MealPlanRecipe::Table)
+            .drop_column(column)
+            .to_owned()
+    }
+
+    /// The counts come from the recipe events, so existing rows are dropped
+    /// and the `mealplan-command` subscription replays them; its inserts
+    /// don't expect the rows to be there.
+    #[async_trait::async_trait]
+    impl sqlx_migrator::Operation<sqlx::Sqlite> for AddComplexity {
+        async fn up(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            for statement in [
+                add_complexity(),
+                add_ingredient_count(),
+                add_instruction_count(),
+            ] {
+                let statement = statement.to_string(sea_query::SqliteQueryBuilder);
+                sqlx::query(sqlx::AssertSqlSafe(statement))
+                    .execute(&mut *connection)
+                    .await?;
+            }
+
+            sqlx::query("DELETE FROM meal_plan_recipe")
+                .execute(&mut *connection)
+                .await?;
+
+            sqlx::query("UPDATE subscriber SET cursor = NULL WHERE key = 'mealplan-command'")
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+
+        async fn down(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            for statement in [
+                drop_column(MealPlanRecipe::InstructionCount),
+                drop_column(MealPlanRecipe::IngredientCount),
+                drop_column(MealPlanRecipe::Complexity),
+            ] {
+                let statement = statement.to_string(sea_query::SqliteQueryBuilder);
+                sqlx::query(sqlx::AssertSqlSafe(statement))
+                    .execute(&mut *connection)
+                    .await?;
+            }
+
+            Ok(())
+        }
+    }
+}
+
+pub(crate) mod m0031 {
+    use sea_query::{ColumnDef, Table, TableAlterStatement};
+
+    use super::MealPlanRecipe;
+
+    pub struct AddCuisineType;
+
+    fn add_column() -> TableAlterStatement {
+        Table::alter()
+            .table(MealPlanRecipe::Table)
+            .add_column(ColumnDef::new(MealPlanRecipe::CuisineType).string())
+            .to_owned()
+    }
+
+    fn drop_column() -> TableAlterStatement {
+        Table::alter()
+            .table(MealPlanRecipe::Table)
+            .drop_column(MealPlanRecipe::CuisineType)
+            .to_owned()
+    }
+
+    #[async_trait::async_trait]
+    impl sqlx_migrator::Operation<sqlx::Sqlite> for AddCuisineType {
+        async fn up(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = add_column().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+
+        async fn down(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = drop_column().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+    }
+}
+
+pub(crate) mod m0032 {
+    use sea_query::{ColumnDef, Table, TableAlterStatement};
+
+    use super::MealPlanRecipe;
+
+    pub struct AddAdvancePrepHours;
+
+    fn add_column() -> TableAlterStatement {
+        Table::alter()
+            .table(MealPlanRecipe::Table)
+            .add_column(ColumnDef::new(MealPlanRecipe::AdvancePrepHours).integer())
+            .to_owned()
+    }
+
+    fn drop_column() -> TableAlterStatement {
+        Table::alter()
+            .table(MealPlanRecipe::Table)
+            .drop_column(MealPlanRecipe::AdvancePrepHours)
+            .to_owned()
+    }
+
+    #[async_trait::async_trait]
+    impl sqlx_migrator::Operation<sqlx::Sqlite> for AddAdvancePrepHours {
+        async fn up(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = add_column().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+
+        async fn down(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = drop_column().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+    }
+}
+
+pub(crate) mod m0034 {
+    use sea_query::{ColumnDef, Table, TableAlterStatement};
+
+    use super::MealPlanRecipe;
+
+    pub struct AddPerishability;
+
+    fn add_column() -> TableAlterStatement {
+        Table::alter()
+            .table(MealPlanRecipe::Table)
+            .add_column(
+                ColumnDef::new(MealPlanRecipe::Perishability)
+                    .integer()
+                    .not_null()
+                    .default(0),
+            )
+            .to_owned()
+    }
+
+    fn drop_column() -> TableAlterStatement {
+        Table::alter()
+            .table(MealPlanRecipe::Table)
+            .drop_column(MealPlanRecipe::Perishability)
+            .to_owned()
+    }
+
+    /// Perishability comes from the recipe ingredients, so existing rows are
+    /// replayed by the `mealplan-command` subscription like in m0028.
+    #[async_trait::async_trait]
+    impl sqlx_migrator::Operation<sqlx::Sqlite> for AddPerishability {
+        async fn up(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = add_column().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(&mut *connection)
+                .await?;
+
+            sqlx::query("DELETE FROM meal_plan_recipe")
+                .execute(&mut *connection)
+                .await?;
+
+            sqlx::query("UPDATE subscriber SET cursor = NULL WHERE key = 'mealplan-command'")
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+
+        async fn down(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = drop_column().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use sea_query::Iden;
+
+#[derive(Iden, Clone)]
+pub enum MealPlanSkippedWeek {
+    Table,
+    UserId,
+    Week,
+}
+
+pub(crate) mod m0035 {
+    use sea_query::{ColumnDef, Index, Table, TableCreateStatement, TableDropStatement};
+
+    use super::MealPlanSkippedWeek;
+
+    pub struct CreateTable;
+
+    fn create_table() -> TableCreateStatement {
+        Table::create()
+            .table(MealPlanSkippedWeek::Table)
+            .col(
+                ColumnDef::new(MealPlanSkippedWeek::UserId)
+                    .string()
+                    .not_null()
+                    .string_len(26),
+            )
+            .col(
+                ColumnDef::new(MealPlanSkippedWeek::Week)
+                    .big_integer()
+                    .not_null(),
+            )
+            .primary_key(
+                Index::create()
+                    .col(MealPlanSkippedWeek::UserId)
+                    .col(MealPlanSkippedWeek::Week),
+            )
+            .to_owned()
+    }
+
+    fn drop_table() -> TableDropStatement {
+        Table::drop().table(MealPlanSkippedWeek::Table).to_owned()
+    }
+
+    #[async_trait::async_trait]
+    impl sqlx_migrator::Operation<sqlx::Sqlite> for CreateTable {
+        async fn up(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = create_table().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+
+        async fn down(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = drop_table().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+    }
+}
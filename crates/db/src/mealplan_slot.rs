@@ -14,6 +14,8 @@ pub enum MealPlanSlot {
     Beverage,
     Condiment,
     GeneratedAt,
+    LeftoverOf,
+    Suggested,
 }
 
 pub(crate) mod m0001 {
@@ -127,3 +129,142 @@ pub(crate) mod m0004 {
         }
     }
 }
+
+pub(crate) mod m0013 {
+    pub struct RebuildForAdvancePrepDone;
+
+    /// Slot courses are bitcode blobs; `DaySlotRecipe` gained
+    /// `advance_prep_done`, so existing rows no longer decode. Drop them and
+    /// replay the `mealplan-slot` subscription to rewrite them.
+    #[async_trait::async_trait]
+    impl sqlx_migrator::Operation<sqlx::Sqlite> for RebuildForAdvancePrepDone {
+        async fn up(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            sqlx::query("DELETE FROM meal_plan_slot")
+                .execute(&mut *connection)
+                .await?;
+
+            sqlx::query("UPDATE subscriber SET cursor = NULL WHERE key = 'mealplan-slot'")
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+
+        async fn down(
+            &self,
+            _connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            Ok(())
+        }
+    }
+}
+
+pub(crate) mod m0026 {
+    use sea_query::{ColumnDef, Table, TableAlterStatement};
+
+    use super::MealPlanSlot;
+
+    pub struct AddLeftoverOf;
+
+    fn add_column() -> TableAlterStatement {
+        Table::alter()
+            .table(MealPlanSlot::Table)
+            .add_column(
+                ColumnDef::new(MealPlanSlot::LeftoverOf)
+                    .big_integer()
+                    .null(),
+            )
+            .to_owned()
+    }
+
+    fn drop_column() -> TableAlterStatement {
+        Table::alter()
+            .table(MealPlanSlot::Table)
+            .drop_column(MealPlanSlot::LeftoverOf)
+            .to_owned()
+    }
+
+    #[async_trait::async_trait]
+    impl sqlx_migrator::Operation<sqlx::Sqlite> for AddLeftoverOf {
+        async fn up(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = add_column().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+
+        async fn down(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = drop_column().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+    }
+}
+
+pub(crate) mod m0038 {
+    use sea_query::{ColumnDef, Table, TableAlterStatement};
+
+    use super::MealPlanSlot;
+
+    pub struct AddSuggested;
+
+    fn add_column() -> TableAlterStatement {
+        Table::alter()
+            .table(MealPlanSlot::Table)
+            .add_column(
+                ColumnDef::new(MealPlanSlot::Suggested)
+                    .boolean()
+                    .not_null()
+                    .default(false),
+            )
+            .to_owned()
+    }
+
+    fn drop_column() -> TableAlterStatement {
+        Table::alter()
+            .table(MealPlanSlot::Table)
+            .drop_column(MealPlanSlot::Suggested)
+            .to_owned()
+    }
+
+    #[async_trait::async_trait]
+    impl sqlx_migrator::Operation<sqlx::Sqlite> for AddSuggested {
+        async fn up(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = add_column().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+
+        async fn down(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = drop_column().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+    }
+}
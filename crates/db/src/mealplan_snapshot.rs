@@ -0,0 +1,72 @@
+use sea_query::Iden;
+
+#[derive(Iden, Clone)]
+pub enum MealPlanSnapshot {
+    Table,
+    UserId,
+    Cursor,
+    GeneratedAt,
+}
+
+pub(crate) mod m0019 {
+    use sea_query::{ColumnDef, Table, TableCreateStatement, TableDropStatement};
+
+    use super::MealPlanSnapshot;
+
+    pub struct CreateTable;
+
+    fn create_table() -> TableCreateStatement {
+        Table::create()
+            .table(MealPlanSnapshot::Table)
+            .col(
+                ColumnDef::new(MealPlanSnapshot::UserId)
+                    .string()
+                    .not_null()
+                    .string_len(26)
+                    .primary_key(),
+            )
+            .col(
+                ColumnDef::new(MealPlanSnapshot::Cursor)
+                    .string()
+                    .not_null()
+                    .string_len(26),
+            )
+            .col(
+                ColumnDef::new(MealPlanSnapshot::GeneratedAt)
+                    .big_integer()
+                    .not_null(),
+            )
+            .to_owned()
+    }
+
+    fn drop_table() -> TableDropStatement {
+        Table::drop().table(MealPlanSnapshot::Table).to_owned()
+    }
+
+    #[async_trait::async_trait]
+    impl sqlx_migrator::Operation<sqlx::Sqlite> for CreateTable {
+        async fn up(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = create_table().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+
+        async fn down(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = drop_table().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use sea_query::Iden;
+
+#[derive(Iden, Clone)]
+pub enum NotificationDelivery {
+    Table,
+    EffectType,
+    AggregateId,
+    EventId,
+    CreatedAt,
+}
+
+pub(crate) mod m0020 {
+    use sea_query::{ColumnDef, Index, Table, TableCreateStatement, TableDropStatement};
+
+    use super::NotificationDelivery;
+
+    pub struct CreateTable;
+
+    fn create_table() -> TableCreateStatement {
+        Table::create()
+            .table(NotificationDelivery::Table)
+            .col(
+                ColumnDef::new(NotificationDelivery::EffectType)
+                    .string()
+                    .not_null()
+                    .string_len(50),
+            )
+            .col(
+                ColumnDef::new(NotificationDelivery::AggregateId)
+                    .string()
+                    .not_null()
+                    .string_len(26),
+            )
+            .col(
+                ColumnDef::new(NotificationDelivery::EventId)
+                    .string()
+                    .not_null()
+                    .string_len(26),
+            )
+            .col(
+                ColumnDef::new(NotificationDelivery::CreatedAt)
+                    .big_integer()
+                    .not_null(),
+            )
+            .primary_key(
+                Index::create()
+                    .col(NotificationDelivery::EffectType)
+                    .col(NotificationDelivery::AggregateId)
+                    .col(NotificationDelivery::EventId),
+            )
+            .to_owned()
+    }
+
+    fn drop_table() -> TableDropStatement {
+        Table::drop().table(NotificationDelivery::Table).to_owned()
+    }
+
+    #[async_trait::async_trait]
+    impl sqlx_migrator::Operation<sqlx::Sqlite> for CreateTable {
+        async fn up(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = create_table().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+
+        async fn down(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = drop_table().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+    }
+}
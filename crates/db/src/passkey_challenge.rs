@@ -0,0 +1,115 @@
+use sea_query::Iden;
+
+#[derive(Iden, Clone)]
+pub enum PasskeyChallenge {
+    Table,
+    Id,
+    Ip,
+    CreatedAt,
+}
+
+pub(crate) mod m0043 {
+    use sea_query::{
+        ColumnDef, Index, IndexCreateStatement, IndexDropStatement, Table, TableCreateStatement,
+        TableDropStatement,
+    };
+
+    use super::PasskeyChallenge;
+
+    pub struct CreateTable;
+
+    fn create_table() -> TableCreateStatement {
+        Table::create()
+            .table(PasskeyChallenge::Table)
+            .col(
+                ColumnDef::new(PasskeyChallenge::Id)
+                    .string()
+                    .not_null()
+                    .string_len(26)
+                    .primary_key(),
+            )
+            .col(ColumnDef::new(PasskeyChallenge::Ip).string().string_len(45))
+            .col(
+                ColumnDef::new(PasskeyChallenge::CreatedAt)
+                    .big_integer()
+                    .not_null(),
+            )
+            .to_owned()
+    }
+
+    fn drop_table() -> TableDropStatement {
+        Table::drop().table(PasskeyChallenge::Table).to_owned()
+    }
+
+    #[async_trait::async_trait]
+    impl sqlx_migrator::Operation<sqlx::Sqlite> for CreateTable {
+        async fn up(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = create_table().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+
+        async fn down(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = drop_table().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+    }
+
+    pub struct CreateIdx1;
+
+    fn create_idx_1() -> IndexCreateStatement {
+        Index::create()
+            .name("idx_passkey_challenge_Tq5mXw")
+            .table(PasskeyChallenge::Table)
+            .col(PasskeyChallenge::Ip)
+            .col(PasskeyChallenge::CreatedAt)
+            .to_owned()
+    }
+
+    fn drop_idx_1() -> IndexDropStatement {
+        Index::drop()
+            .name("idx_passkey_challenge_Tq5mXw")
+            .table(PasskeyChallenge::Table)
+            .to_owned()
+    }
+
+    #[async_trait::async_trait]
+    impl sqlx_migrator::Operation<sqlx::Sqlite> for CreateIdx1 {
+        async fn up(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = create_idx_1().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+
+        async fn down(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = drop_idx_1().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+    }
+}
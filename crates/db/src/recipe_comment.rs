@@ -0,0 +1,333 @@
+use sea_query::Iden;
+
+/// Comments on recipes, one level of replies deep. `ParentId` is the top
+/// level comment of the thread a reply belongs to, `NULL` on top level
+/// comments.
+#[derive(Iden, Clone)]
+pub enum RecipeComment {
+    Table,
+    Id,
+    RecipeId,
+    ParentId,
+    UserId,
+    Body,
+    /// Order the comments were written in; `CreatedAt` is only to the
+    /// second.
+    Position,
+    CreatedAt,
+    /// Distinct users that reported the comment.
+    FlagCount,
+    /// Left out of public threads once enough users flagged it.
+    IsHidden,
+}
+
+/// One row per user that reported a comment.
+#[derive(Iden, Clone)]
+pub enum RecipeCommentFlag {
+    Table,
+    CommentId,
+    UserId,
+    Reason,
+    CreatedAt,
+}
+
+pub(crate) mod m0039 {
+    use sea_query::{
+        ColumnDef, Index, IndexCreateStatement, IndexDropStatement, Table, TableCreateStatement,
+        TableDropStatement,
+    };
+
+    use super::RecipeComment;
+
+    pub struct CreateTable;
+
+    fn create_table() -> TableCreateStatement {
+        Table::create()
+            .table(RecipeComment::Table)
+            .col(
+                ColumnDef::new(RecipeComment::Id)
+                    .string()
+                    .not_null()
+                    .string_len(26)
+                    .primary_key(),
+            )
+            .col(
+                ColumnDef::new(RecipeComment::RecipeId)
+                    .string()
+                    .not_null()
+                    .string_len(26),
+            )
+            .col(
+                ColumnDef::new(RecipeComment::ParentId)
+                    .string()
+                    .null()
+                    .string_len(26),
+            )
+            .col(
+                ColumnDef::new(RecipeComment::UserId)
+                    .string()
+                    .not_null()
+                    .string_len(26),
+            )
+            .col(ColumnDef::new(RecipeComment::Body).text().not_null())
+            .col(
+                ColumnDef::new(RecipeComment::Position)
+                    .big_integer()
+                    .not_null(),
+            )
+            .col(
+                ColumnDef::new(RecipeComment::CreatedAt)
+                    .big_integer()
+                    .not_null(),
+            )
+            .to_owned()
+    }
+
+    fn drop_table() -> TableDropStatement {
+        Table::drop().table(RecipeComment::Table).to_owned()
+    }
+
+    #[async_trait::async_trait]
+    impl sqlx_migrator::Operation<sqlx::Sqlite> for CreateTable {
+        async fn up(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = create_table().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+
+        async fn down(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = drop_table().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+    }
+
+    pub struct CreateIdx1;
+
+    fn create_idx_1() -> IndexCreateStatement {
+        Index::create()
+            .name("idx_recipe_comment_Qm3xTd")
+            .table(RecipeComment::Table)
+            .col(RecipeComment::RecipeId)
+            .col(RecipeComment::ParentId)
+            .col(RecipeComment::Position)
+            .to_owned()
+    }
+
+    fn drop_idx_1() -> IndexDropStatement {
+        Index::drop()
+            .name("idx_recipe_comment_Qm3xTd")
+            .table(RecipeComment::Table)
+            .to_owned()
+    }
+
+    #[async_trait::async_trait]
+    impl sqlx_migrator::Operation<sqlx::Sqlite> for CreateIdx1 {
+        async fn up(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = create_idx_1().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+
+        async fn down(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = drop_idx_1().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+    }
+}
+
+pub(crate) mod m0040 {
+    use sea_query::{
+        ColumnDef, Index, Table, TableAlterStatement, TableCreateStatement, TableDropStatement,
+    };
+
+    use super::{RecipeComment, RecipeCommentFlag};
+
+    pub struct AddFlagCount;
+
+    fn add_flag_count() -> TableAlterStatement {
+        Table::alter()
+            .table(RecipeComment::Table)
+            .add_column(
+                ColumnDef::new(RecipeComment::FlagCount)
+                    .integer()
+                    .not_null()
+                    .default(0),
+            )
+            .to_owned()
+    }
+
+    fn drop_flag_count() -> TableAlterStatement {
+        Table::alter()
+            .table(RecipeComment::Table)
+            .drop_column(RecipeComment::FlagCount)
+            .to_owned()
+    }
+
+    #[async_trait::async_trait]
+    impl sqlx_migrator::Operation<sqlx::Sqlite> for AddFlagCount {
+        async fn up(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = add_flag_count().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+
+        async fn down(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = drop_flag_count().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+    }
+
+    pub struct AddIsHidden;
+
+    fn add_is_hidden() -> TableAlterStatement {
+        Table::alter()
+            .table(RecipeComment::Table)
+            .add_column(
+                ColumnDef::new(RecipeComment::IsHidden)
+                    .boolean()
+                    .not_null()
+                    .default(false),
+            )
+            .to_owned()
+    }
+
+    fn drop_is_hidden() -> TableAlterStatement {
+        Table::alter()
+            .table(RecipeComment::Table)
+            .drop_column(RecipeComment::IsHidden)
+            .to_owned()
+    }
+
+    #[async_trait::async_trait]
+    impl sqlx_migrator::Operation<sqlx::Sqlite> for AddIsHidden {
+        async fn up(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = add_is_hidden().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+
+        async fn down(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = drop_is_hidden().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+    }
+
+    pub struct CreateFlagTable;
+
+    fn create_flag_table() -> TableCreateStatement {
+        Table::create()
+            .table(RecipeCommentFlag::Table)
+            .col(
+                ColumnDef::new(RecipeCommentFlag::CommentId)
+                    .string()
+                    .not_null()
+                    .string_len(26),
+            )
+            .col(
+                ColumnDef::new(RecipeCommentFlag::UserId)
+                    .string()
+                    .not_null()
+                    .string_len(26),
+            )
+            .col(
+                ColumnDef::new(RecipeCommentFlag::Reason)
+                    .string()
+                    .not_null()
+                    .string_len(15),
+            )
+            .col(
+                ColumnDef::new(RecipeCommentFlag::CreatedAt)
+                    .big_integer()
+                    .not_null(),
+            )
+            .primary_key(
+                Index::create()
+                    .col(RecipeCommentFlag::CommentId)
+                    .col(RecipeCommentFlag::UserId),
+            )
+            .to_owned()
+    }
+
+    fn drop_flag_table() -> TableDropStatement {
+        Table::drop().table(RecipeCommentFlag::Table).to_owned()
+    }
+
+    #[async_trait::async_trait]
+    impl sqlx_migrator::Operation<sqlx::Sqlite> for CreateFlagTable {
+        async fn up(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = create_flag_table().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+
+        async fn down(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = drop_flag_table().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+    }
+}
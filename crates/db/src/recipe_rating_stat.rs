@@ -0,0 +1,74 @@
+use sea_query::Iden;
+
+/// Star ratings of a recipe, summed up. `Id` is the recipe id.
+#[derive(Iden, Clone)]
+pub enum RecipeRatingStat {
+    Table,
+    Id,
+    RatingCount,
+    AverageRating,
+}
+
+pub(crate) mod m0037 {
+    use sea_query::{ColumnDef, Table, TableCreateStatement, TableDropStatement};
+
+    use super::RecipeRatingStat;
+
+    pub struct CreateTable;
+
+    fn create_table() -> TableCreateStatement {
+        Table::create()
+            .table(RecipeRatingStat::Table)
+            .col(
+                ColumnDef::new(RecipeRatingStat::Id)
+                    .string()
+                    .not_null()
+                    .string_len(26)
+                    .primary_key(),
+            )
+            .col(
+                ColumnDef::new(RecipeRatingStat::RatingCount)
+                    .integer()
+                    .not_null()
+                    .default(0),
+            )
+            .col(
+                ColumnDef::new(RecipeRatingStat::AverageRating)
+                    .float()
+                    .not_null()
+                    .default(0.0),
+            )
+            .to_owned()
+    }
+
+    fn drop_table() -> TableDropStatement {
+        Table::drop().table(RecipeRatingStat::Table).to_owned()
+    }
+
+    #[async_trait::async_trait]
+    impl sqlx_migrator::Operation<sqlx::Sqlite> for CreateTable {
+        async fn up(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = create_table().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+
+        async fn down(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = drop_table().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+    }
+}
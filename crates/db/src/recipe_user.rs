@@ -27,6 +27,8 @@ pub enum RecipeUser {
     ThumbnailVersion,
     DifficultyScore,
     BlurPlaceholder,
+    Tags,
+    ContentHash,
 }
 
 #[derive(Iden, Clone)]
@@ -742,3 +744,167 @@ pub(crate) mod m0007 {
         }
     }
 }
+
+pub(crate) mod m0029 {
+    pub struct AddTags;
+
+    #[async_trait::async_trait]
+    impl sqlx_migrator::Operation<sqlx::Sqlite> for AddTags {
+        async fn up(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            // No recipe has tags yet, so the default covers existing rows
+            // without replaying the subscription.
+            sqlx::query("ALTER TABLE recipe_user ADD COLUMN tags TEXT NOT NULL DEFAULT '[]'")
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+
+        async fn down(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            sqlx::query("ALTER TABLE recipe_user DROP COLUMN tags")
+                .execute(connection)
+                .await
+                .ok();
+
+            Ok(())
+        }
+    }
+}
+
+pub(crate) mod m0030 {
+    pub struct StemFts;
+
+    async fn recreate(
+        connection: &mut sqlx::SqliteConnection,
+        create: &'static str,
+    ) -> Result<(), sqlx_migrator::Error> {
+        sqlx::query("DROP TABLE IF EXISTS recipe_user_fts")
+            .execute(&mut *connection)
+            .await?;
+        sqlx::query(create).execute(&mut *connection).await?;
+
+        // The table starts empty, replay every recipe event into it.
+        sqlx::query("UPDATE subscriber SET cursor = NULL WHERE key = 'recipe-user-fts-query'")
+            .execute(connection)
+            .await?;
+
+        Ok(())
+    }
+
+    #[async_trait::async_trait]
+    impl sqlx_migrator::Operation<sqlx::Sqlite> for StemFts {
+        async fn up(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            // The tokenizer can't be changed in place. Stemming lets "chickpeas"
+            // find a recipe listing "chickpea", which prefix matching alone
+            // only does the other way around.
+            recreate(
+                connection,
+                r#"
+CREATE VIRTUAL TABLE recipe_user_fts USING fts5(id, name, description, ingredients, tokenize = 'porter unicode61');
+            "#,
+            )
+            .await
+        }
+
+        async fn down(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            recreate(
+                connection,
+                r#"
+CREATE VIRTUAL TABLE recipe_user_fts USING fts5(id, name, description, ingredients);
+            "#,
+            )
+            .await
+        }
+    }
+}
+
+pub(crate) mod m0033 {
+    pub struct AddContentHash;
+
+    #[async_trait::async_trait]
+    impl sqlx_migrator::Operation<sqlx::Sqlite> for AddContentHash {
+        async fn up(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            sqlx::query("ALTER TABLE recipe_user ADD COLUMN content_hash BLOB")
+                .execute(&mut *connection)
+                .await?;
+
+            sqlx::query(
+                "CREATE INDEX idx_recipe_user_Zr4cHs ON recipe_user (owner_id, content_hash)",
+            )
+            .execute(&mut *connection)
+            .await?;
+
+            // Hashes are computed from the projection, replay it to fill them in.
+            sqlx::query("UPDATE subscriber SET cursor = NULL WHERE key = 'recipe-query'")
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+
+        async fn down(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            sqlx::query("DROP INDEX IF EXISTS idx_recipe_user_Zr4cHs")
+                .execute(&mut *connection)
+                .await?;
+
+            sqlx::query("ALTER TABLE recipe_user DROP COLUMN content_hash")
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+    }
+}
+
+pub(crate) mod m0044 {
+    pub struct AddCuisineType;
+
+    #[async_trait::async_trait]
+    impl sqlx_migrator::Operation<sqlx::Sqlite> for AddCuisineType {
+        async fn up(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            sqlx::query("ALTER TABLE recipe_user ADD COLUMN cuisine_type TEXT")
+                .execute(&mut *connection)
+                .await?;
+
+            // Related recipes are ranked by cuisine, replay the projection to
+            // fill it in for existing recipes.
+            sqlx::query("UPDATE subscriber SET cursor = NULL WHERE key = 'recipe-query'")
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+
+        async fn down(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            sqlx::query("ALTER TABLE recipe_user DROP COLUMN cuisine_type")
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+    }
+}
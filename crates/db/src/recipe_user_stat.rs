@@ -121,3 +121,34 @@ pub(crate) mod m0005 {
         }
     }
 }
+
+pub(crate) mod m0016 {
+    pub struct RebuildForFavoriteCount;
+
+    /// The `favorite` counter was never maintained; the view now counts
+    /// favorite saves, so rebuild it from the start of the event log.
+    #[async_trait::async_trait]
+    impl sqlx_migrator::Operation<sqlx::Sqlite> for RebuildForFavoriteCount {
+        async fn up(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            sqlx::query("DELETE FROM recipe_user_stat")
+                .execute(&mut *connection)
+                .await?;
+
+            sqlx::query("UPDATE subscriber SET cursor = NULL WHERE key = 'recipe-user-stat-view'")
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+
+        async fn down(
+            &self,
+            _connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use sea_query::Iden;
+
+#[derive(Iden, Clone)]
+pub enum ShoppingRecipeSnapshot {
+    Table,
+    UserId,
+    RecipeId,
+    Ingredients,
+    HouseholdSize,
+}
+
+pub(crate) mod m0021 {
+    use sea_query::{ColumnDef, Index, Table, TableCreateStatement, TableDropStatement};
+
+    use super::ShoppingRecipeSnapshot;
+
+    pub struct CreateTable;
+
+    fn create_table() -> TableCreateStatement {
+        Table::create()
+            .table(ShoppingRecipeSnapshot::Table)
+            .col(
+                ColumnDef::new(ShoppingRecipeSnapshot::UserId)
+                    .string()
+                    .not_null()
+                    .string_len(26),
+            )
+            .col(
+                ColumnDef::new(ShoppingRecipeSnapshot::RecipeId)
+                    .string()
+                    .not_null()
+                    .string_len(26),
+            )
+            .col(
+                ColumnDef::new(ShoppingRecipeSnapshot::Ingredients)
+                    .blob()
+                    .not_null(),
+            )
+            .col(
+                ColumnDef::new(ShoppingRecipeSnapshot::HouseholdSize)
+                    .integer()
+                    .not_null(),
+            )
+            .primary_key(
+                Index::create()
+                    .col(ShoppingRecipeSnapshot::UserId)
+                    .col(ShoppingRecipeSnapshot::RecipeId),
+            )
+            .to_owned()
+    }
+
+    fn drop_table() -> TableDropStatement {
+        Table::drop()
+            .table(ShoppingRecipeSnapshot::Table)
+            .to_owned()
+    }
+
+    #[async_trait::async_trait]
+    impl sqlx_migrator::Operation<sqlx::Sqlite> for CreateTable {
+        async fn up(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = create_table().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+
+        async fn down(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = drop_table().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+    }
+}
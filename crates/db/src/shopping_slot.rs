@@ -6,6 +6,8 @@ pub enum ShoppingSlot {
     UserId,
     Date,
     RecipeIds,
+    Guests,
+    Servings,
 }
 
 pub(crate) mod m0001 {
@@ -65,3 +67,101 @@ pub(crate) mod m0001 {
         }
     }
 }
+
+pub(crate) mod m0018 {
+    use sea_query::{ColumnDef, Table, TableAlterStatement};
+
+    use super::ShoppingSlot;
+
+    pub struct AddGuests;
+
+    fn add_guests() -> TableAlterStatement {
+        Table::alter()
+            .table(ShoppingSlot::Table)
+            .add_column(ColumnDef::new(ShoppingSlot::Guests).integer().null())
+            .to_owned()
+    }
+
+    fn drop_guests() -> TableAlterStatement {
+        Table::alter()
+            .table(ShoppingSlot::Table)
+            .drop_column(ShoppingSlot::Guests)
+            .to_owned()
+    }
+
+    #[async_trait::async_trait]
+    impl sqlx_migrator::Operation<sqlx::Sqlite> for AddGuests {
+        async fn up(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = add_guests().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+
+        async fn down(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = drop_guests().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+    }
+}
+
+pub(crate) mod m0036 {
+    use sea_query::{ColumnDef, Table, TableAlterStatement};
+
+    use super::ShoppingSlot;
+
+    pub struct AddServings;
+
+    fn add_servings() -> TableAlterStatement {
+        Table::alter()
+            .table(ShoppingSlot::Table)
+            .add_column(ColumnDef::new(ShoppingSlot::Servings).blob().null())
+            .to_owned()
+    }
+
+    fn drop_servings() -> TableAlterStatement {
+        Table::alter()
+            .table(ShoppingSlot::Table)
+            .drop_column(ShoppingSlot::Servings)
+            .to_owned()
+    }
+
+    #[async_trait::async_trait]
+    impl sqlx_migrator::Operation<sqlx::Sqlite> for AddServings {
+        async fn up(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = add_servings().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+
+        async fn down(
+            &self,
+            connection: &mut sqlx::SqliteConnection,
+        ) -> Result<(), sqlx_migrator::Error> {
+            let statement = drop_servings().to_string(sea_query::SqliteQueryBuilder);
+            sqlx::query(sqlx::AssertSqlSafe(statement))
+                .execute(connection)
+                .await?;
+
+            Ok(())
+        }
+    }
+}
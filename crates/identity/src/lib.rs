@@ -1,5 +1,8 @@
 pub mod meal_preferences;
+pub mod notification_preferences;
+pub mod passkey;
 pub mod password;
+pub mod push_subscription;
 pub mod types;
 pub mod user_profile;
 
@@ -7,5 +10,5 @@ pub(crate) mod query;
 pub(crate) mod repository;
 mod root;
 
-pub use query::{admin, global_stat, login};
+pub use query::{admin, global_stat, login, login_history};
 pub use root::*;
@@ -0,0 +1,36 @@
+use evento::{Executor, ProjectionAggregate};
+use imkitchen_types::meal_preferences::CommunitySuggestionsChanged;
+
+/// Most community main courses a week can be set to take, one a day.
+pub const MAX_COMMUNITY_SUGGESTIONS: u8 = 7;
+
+impl<E: Executor> super::Module<E> {
+    /// Sets how many main courses a week are picked among the best rated
+    /// community recipes the user hasn't saved. 0 turns them off.
+    pub async fn set_community_suggestions(
+        &self,
+        id: impl Into<String>,
+        count: u8,
+    ) -> imkitchen_core::Result<()> {
+        if count > MAX_COMMUNITY_SUGGESTIONS {
+            imkitchen_core::user!(
+                "Community suggestions must be at most {MAX_COMMUNITY_SUGGESTIONS} a week"
+            );
+        }
+
+        let id = id.into();
+        let preferences = self.load(&id).await?;
+        if preferences.community_suggestions == count {
+            return Ok(());
+        }
+
+        preferences
+            .write()?
+            .event(&CommunitySuggestionsChanged { count })
+            .requested_by(id)
+            .commit(&self.executor)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+use evento::{Executor, ProjectionAggregate};
+use imkitchen_types::meal_preferences::{ConstraintsChanged, UserConstraints};
+use imkitchen_types::recipe::RecipeType;
+
+use super::{
+    MAX_COMMUNITY_SUGGESTIONS, MAX_EQUIPMENT_CAPACITY, MAX_MIN_DAYS_SINCE_USE, MAX_RATING_WEIGHT,
+    MAX_TIME_BUDGET,
+};
+
+fn validate(constraints: &UserConstraints) -> imkitchen_core::Result<()> {
+    if constraints.household_size == 0 {
+        imkitchen_core::user!("Household size must be at least 1");
+    }
+
+    if !(0.1..=1.0).contains(&constraints.cuisine_variety_weight) {
+        imkitchen_core::user!("Cuisine variety must be between 0.1 and 1");
+    }
+
+    if !(0.0..=MAX_RATING_WEIGHT).contains(&constraints.rating_weight) {
+        imkitchen_core::user!("Rating weight must be between 0 and {MAX_RATING_WEIGHT}");
+    }
+
+    if !(0.0..=1.0).contains(&constraints.randomness) {
+        imkitchen_core::user!("Randomness must be between 0 and 1");
+    }
+
+    if constraints
+        .equipment_capacity
+        .values()
+        .any(|count| *count > MAX_EQUIPMENT_CAPACITY)
+    {
+        imkitchen_core::user!("Equipment capacity must be at most {MAX_EQUIPMENT_CAPACITY}");
+    }
+
+    if constraints
+        .time_budget
+        .iter()
+        .flatten()
+        .any(|minutes| *minutes == 0 || *minutes > MAX_TIME_BUDGET)
+    {
+        imkitchen_core::user!("Time budget must be between 1 and {MAX_TIME_BUDGET} minutes");
+    }
+
+    if constraints
+        .skipped_courses
+        .contains(&RecipeType::MainCourse)
+    {
+        imkitchen_core::user!("The main course can't be skipped");
+    }
+
+    if constraints.community_suggestions > MAX_COMMUNITY_SUGGESTIONS {
+        imkitchen_core::user!(
+            "Community suggestions must be at most {MAX_COMMUNITY_SUGGESTIONS} a week"
+        );
+    }
+
+    if constraints.min_days_since_use > MAX_MIN_DAYS_SINCE_USE {
+        imkitchen_core::user!("Days between repeats must be at most {MAX_MIN_DAYS_SINCE_USE}");
+    }
+
+    Ok(())
+}
+
+impl<E: Executor> super::Module<E> {
+    /// Stores the constraint set meal plan generation reads, replacing the
+    /// one the user had.
+    pub async fn set_constraints(
+        &self,
+        id: impl Into<String>,
+        constraints: UserConstraints,
+    ) -> imkitchen_core::Result<()> {
+        self.change_constraints(id, |current| *current = constraints)
+            .await
+    }
+
+    /// Applies `change` to the user's stored constraints and commits the
+    /// result, unless it is invalid or changes nothing.
+    pub(crate) async fn change_constraints(
+        &self,
+        id: impl Into<String>,
+        change: impl FnOnce(&mut UserConstraints),
+    ) -> imkitchen_core::Result<()> {
+        let id = id.into();
+        let preferences = self.load(&id).await?;
+
+        let current = preferences.constraints()?;
+        let mut constraints = current.clone();
+        change(&mut constraints);
+        validate(&constraints)?;
+
+        if constraints == current {
+            return Ok(());
+        }
+
+        preferences
+            .write()?
+            .event(&ConstraintsChanged {
+                constraints: constraints.to_json()?,
+            })
+            .requested_by(id)
+            .commit(&self.executor)
+            .await?;
+
+        Ok(())
+    }
+}
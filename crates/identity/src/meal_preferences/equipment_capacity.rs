@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+
+use evento::{Executor, ProjectionAggregate};
+use imkitchen_types::meal_preferences::EquipmentCapacityChanged;
+use imkitchen_types::recipe::Equipment;
+use strum::VariantArray;
+
+/// Most recipes one appliance can be set to run at once.
+pub const MAX_EQUIPMENT_CAPACITY: u8 = 8;
+
+impl<E: Executor> super::Module<E> {
+    /// Sets how many recipes the user's kitchen can run on each appliance at
+    /// once. Appliances missing from `capacity`, or set to their default,
+    /// go back to [`Equipment::default_capacity`]. 0 means the kitchen
+    /// doesn't have it.
+    pub async fn set_equipment_capacity(
+        &self,
+        id: impl Into<String>,
+        capacity: HashMap<Equipment, u8>,
+    ) -> imkitchen_core::Result<()> {
+        if capacity
+            .values()
+            .any(|count| *count > MAX_EQUIPMENT_CAPACITY)
+        {
+            imkitchen_core::user!("Equipment capacity must be at most {MAX_EQUIPMENT_CAPACITY}");
+        }
+
+        // In declaration order so the same capacity always reads the same.
+        let capacity = Equipment::VARIANTS
+            .iter()
+            .filter_map(|item| {
+                capacity
+                    .get(item)
+                    .filter(|count| **count != item.default_capacity())
+                    .map(|count| (*item, *count))
+            })
+            .collect::<Vec<_>>();
+
+        let id = id.into();
+        let preferences = self.load(&id).await?;
+        if preferences.equipment_capacity == capacity {
+            return Ok(());
+        }
+
+        preferences
+            .write()?
+            .event(&EquipmentCapacityChanged { capacity })
+            .requested_by(id)
+            .commit(&self.executor)
+            .await?;
+
+        Ok(())
+    }
+}
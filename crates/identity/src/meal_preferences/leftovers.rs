@@ -0,0 +1,14 @@
+use evento::Executor;
+
+impl<E: Executor> super::Module<E> {
+    /// Turns leftover planning on or off: big batches are eaten over the
+    /// following days instead of cooking every day.
+    pub async fn set_allow_leftovers(
+        &self,
+        id: impl Into<String>,
+        allow: bool,
+    ) -> imkitchen_core::Result<()> {
+        self.change_constraints(id, |constraints| constraints.allow_leftovers = allow)
+            .await
+    }
+}
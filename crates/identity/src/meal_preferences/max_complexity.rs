@@ -0,0 +1,20 @@
+use evento::Executor;
+use imkitchen_types::recipe::Complexity;
+
+impl<E: Executor> super::Module<E> {
+    /// Sets the hardest main course planned Monday to Friday and on the
+    /// weekend. Generation still goes above them when nothing easier is
+    /// left.
+    pub async fn set_max_complexity(
+        &self,
+        id: impl Into<String>,
+        weekday: Complexity,
+        weekend: Complexity,
+    ) -> imkitchen_core::Result<()> {
+        self.change_constraints(id, |constraints| {
+            constraints.max_weekday_complexity = weekday;
+            constraints.max_weekend_complexity = weekend;
+        })
+        .await
+    }
+}
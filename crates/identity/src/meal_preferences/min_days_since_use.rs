@@ -0,0 +1,17 @@
+use evento::Executor;
+
+/// Widest gap a user can ask between two days planning the same recipe.
+pub const MAX_MIN_DAYS_SINCE_USE: u16 = 60;
+
+impl<E: Executor> super::Module<E> {
+    /// Sets how many days a recipe should be from its other planned days
+    /// before replacing a meal brings it back.
+    pub async fn set_min_days_since_use(
+        &self,
+        id: impl Into<String>,
+        days: u16,
+    ) -> imkitchen_core::Result<()> {
+        self.change_constraints(id, |constraints| constraints.min_days_since_use = days)
+            .await
+    }
+}
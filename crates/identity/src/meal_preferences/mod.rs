@@ -1,12 +1,32 @@
+mod community_suggestions;
+mod constraints;
+mod equipment_capacity;
+mod leftovers;
+mod max_complexity;
+mod min_days_since_use;
+mod randomness;
+mod rating_weight;
+mod scale_down;
+mod skipped_courses;
+mod time_budget;
 mod update;
 
 use bitcode::{Decode, Encode};
+pub use community_suggestions::MAX_COMMUNITY_SUGGESTIONS;
+pub use equipment_capacity::MAX_EQUIPMENT_CAPACITY;
+pub use min_days_since_use::MAX_MIN_DAYS_SINCE_USE;
+pub use rating_weight::MAX_RATING_WEIGHT;
 use std::ops::Deref;
+pub use time_budget::MAX_TIME_BUDGET;
 pub use update::*;
 
 use evento::{Executor, Projection, metadata::Event};
-use imkitchen_types::meal_preferences::{self, Changed};
-use imkitchen_types::recipe::DietaryRestriction;
+use imkitchen_types::meal_preferences::{
+    self, Changed, CommunitySuggestionsChanged, ConstraintsChanged, EquipmentCapacityChanged,
+    TimeBudgetChanged, UserConstraints,
+};
+use imkitchen_types::recipe::{DietaryRestriction, Equipment};
+use strum::VariantArray;
 
 #[derive(Clone)]
 pub struct Module<E: Executor>(pub(crate) imkitchen_core::State<E>);
@@ -28,12 +48,20 @@ impl<E: Executor> Module<E> {
             .execute(&self.executor)
             .await
             .map(|r| {
-                r.unwrap_or_else(|| MealPreferences {
-                    id,
-                    household_size: 4,
-                    dietary_restrictions: vec![],
-                    cuisine_variety_weight: 1.0,
-                    cursor: Default::default(),
+                r.unwrap_or_else(|| {
+                    let defaults = UserConstraints::default();
+
+                    MealPreferences {
+                        id,
+                        household_size: defaults.household_size,
+                        dietary_restrictions: defaults.dietary_restrictions,
+                        cuisine_variety_weight: defaults.cuisine_variety_weight,
+                        equipment_capacity: vec![],
+                        time_budget: defaults.time_budget,
+                        community_suggestions: defaults.community_suggestions,
+                        constraints: None,
+                        cursor: Default::default(),
+                    }
                 })
             })
     }
@@ -45,11 +73,44 @@ pub struct MealPreferences {
     pub household_size: u16,
     pub dietary_restrictions: Vec<DietaryRestriction>,
     pub cuisine_variety_weight: f32,
+    /// Only the appliances the user changed from their default capacity.
+    pub equipment_capacity: Vec<(Equipment, u8)>,
+    pub time_budget: [Option<u16>; 7],
+    pub community_suggestions: u8,
+    /// The stored [`UserConstraints`] as JSON, `None` until the user changes
+    /// a preference.
+    pub constraints: Option<String>,
+}
+
+impl MealPreferences {
+    /// The user's full constraint set, as consumed by meal plan generation.
+    pub fn constraints(&self) -> anyhow::Result<UserConstraints> {
+        match self.constraints.as_deref() {
+            Some(json) => Ok(UserConstraints::from_json(json)?),
+            None => Ok(UserConstraints::default()),
+        }
+    }
+
+    fn change_constraints(
+        &mut self,
+        change: impl FnOnce(&mut UserConstraints),
+    ) -> anyhow::Result<()> {
+        let mut constraints = self.constraints()?;
+        change(&mut constraints);
+        self.constraints = Some(constraints.to_json()?);
+
+        Ok(())
+    }
 }
 
 fn create_projection<E: Executor>() -> Projection<E, MealPreferences> {
     Projection::new::<meal_preferences::MealPreferences>()
         .handler(handle_updated())
+        .handler(handle_equipment_capacity_changed())
+        .handler(handle_time_budget_changed())
+        .handler(handle_community_suggestions_changed())
+        .handler(handle_constraints_changed())
+        .revision(4)
         .strict()
 }
 
@@ -61,6 +122,11 @@ impl evento::ProjectionAggregate for MealPreferences {
 
 #[evento::handler]
 async fn handle_updated(event: Event<Changed>, data: &mut MealPreferences) -> anyhow::Result<()> {
+    data.change_constraints(|constraints| {
+        constraints.household_size = event.data.household_size;
+        constraints.dietary_restrictions = event.data.dietary_restrictions.to_vec();
+        constraints.cuisine_variety_weight = event.data.cuisine_variety_weight;
+    })?;
     data.id = event.aggregate_id.to_owned();
     data.household_size = event.data.household_size;
     data.dietary_restrictions = event.data.dietary_restrictions;
@@ -68,3 +134,69 @@ async fn handle_updated(event: Event<Changed>, data: &mut MealPreferences) -> an
 
     Ok(())
 }
+
+#[evento::handler]
+async fn handle_equipment_capacity_changed(
+    event: Event<EquipmentCapacityChanged>,
+    data: &mut MealPreferences,
+) -> anyhow::Result<()> {
+    data.id = event.aggregate_id.to_owned();
+    data.change_constraints(|constraints| {
+        constraints.equipment_capacity = event.data.capacity.iter().copied().collect();
+    })?;
+    data.equipment_capacity = event.data.capacity;
+
+    Ok(())
+}
+
+#[evento::handler]
+async fn handle_time_budget_changed(
+    event: Event<TimeBudgetChanged>,
+    data: &mut MealPreferences,
+) -> anyhow::Result<()> {
+    data.id = event.aggregate_id.to_owned();
+    data.time_budget = event.data.minutes;
+    data.change_constraints(|constraints| constraints.time_budget = event.data.minutes)?;
+
+    Ok(())
+}
+
+#[evento::handler]
+async fn handle_community_suggestions_changed(
+    event: Event<CommunitySuggestionsChanged>,
+    data: &mut MealPreferences,
+) -> anyhow::Result<()> {
+    data.id = event.aggregate_id.to_owned();
+    data.community_suggestions = event.data.count;
+    data.change_constraints(|constraints| constraints.community_suggestions = event.data.count)?;
+
+    Ok(())
+}
+
+#[evento::handler]
+async fn handle_constraints_changed(
+    event: Event<ConstraintsChanged>,
+    data: &mut MealPreferences,
+) -> anyhow::Result<()> {
+    let constraints = UserConstraints::from_json(&event.data.constraints)?;
+
+    data.id = event.aggregate_id.to_owned();
+    data.household_size = constraints.household_size;
+    data.dietary_restrictions = constraints.dietary_restrictions.to_vec();
+    data.cuisine_variety_weight = constraints.cuisine_variety_weight;
+    data.equipment_capacity = Equipment::VARIANTS
+        .iter()
+        .filter_map(|item| {
+            constraints
+                .equipment_capacity
+                .get(item)
+                .filter(|count| **count != item.default_capacity())
+                .map(|count| (*item, *count))
+        })
+        .collect();
+    data.time_budget = constraints.time_budget;
+    data.community_suggestions = constraints.community_suggestions;
+    data.constraints = Some(event.data.constraints);
+
+    Ok(())
+}
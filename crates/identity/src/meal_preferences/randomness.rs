@@ -0,0 +1,14 @@
+use evento::Executor;
+
+impl<E: Executor> super::Module<E> {
+    /// Sets how mixed up generated weeks are, from 0, always the best scored
+    /// recipes, to 1, the most variety.
+    pub async fn set_randomness(
+        &self,
+        id: impl Into<String>,
+        randomness: f32,
+    ) -> imkitchen_core::Result<()> {
+        self.change_constraints(id, |constraints| constraints.randomness = randomness)
+            .await
+    }
+}
@@ -0,0 +1,17 @@
+use evento::Executor;
+
+/// Strongest pull towards well rated recipes a user can set.
+pub const MAX_RATING_WEIGHT: f32 = 2.0;
+
+impl<E: Executor> super::Module<E> {
+    /// Sets how much generation favors recipes with a higher average
+    /// rating. 0 leaves ratings out.
+    pub async fn set_rating_weight(
+        &self,
+        id: impl Into<String>,
+        weight: f32,
+    ) -> imkitchen_core::Result<()> {
+        self.change_constraints(id, |constraints| constraints.rating_weight = weight)
+            .await
+    }
+}
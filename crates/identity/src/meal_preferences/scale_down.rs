@@ -0,0 +1,14 @@
+use evento::Executor;
+
+impl<E: Executor> super::Module<E> {
+    /// Lets generation plan recipes meant for a bigger household, scaled
+    /// down to the user's.
+    pub async fn set_scale_down(
+        &self,
+        id: impl Into<String>,
+        scale_down: bool,
+    ) -> imkitchen_core::Result<()> {
+        self.change_constraints(id, |constraints| constraints.scale_down = scale_down)
+            .await
+    }
+}
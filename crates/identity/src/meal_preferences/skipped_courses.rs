@@ -0,0 +1,23 @@
+use evento::Executor;
+use imkitchen_types::recipe::RecipeType;
+use strum::VariantArray;
+
+impl<E: Executor> super::Module<E> {
+    /// Sets the courses generation leaves out of every day. The main course
+    /// can't be skipped.
+    pub async fn set_skipped_courses(
+        &self,
+        id: impl Into<String>,
+        courses: Vec<RecipeType>,
+    ) -> imkitchen_core::Result<()> {
+        // In declaration order so the same courses always read the same.
+        let courses = RecipeType::VARIANTS
+            .iter()
+            .filter(|course| courses.contains(course))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        self.change_constraints(id, |constraints| constraints.skipped_courses = courses)
+            .await
+    }
+}
@@ -0,0 +1,38 @@
+use evento::{Executor, ProjectionAggregate};
+use imkitchen_types::meal_preferences::TimeBudgetChanged;
+
+/// Longest daily time budget, in minutes. Anything above is no limit at all.
+pub const MAX_TIME_BUDGET: u16 = 24 * 60;
+
+impl<E: Executor> super::Module<E> {
+    /// Sets the minutes the user has to cook each day, Monday first. `None`
+    /// leaves the day unlimited.
+    pub async fn set_time_budget(
+        &self,
+        id: impl Into<String>,
+        minutes: [Option<u16>; 7],
+    ) -> imkitchen_core::Result<()> {
+        if minutes
+            .iter()
+            .flatten()
+            .any(|minutes| *minutes == 0 || *minutes > MAX_TIME_BUDGET)
+        {
+            imkitchen_core::user!("Time budget must be between 1 and {MAX_TIME_BUDGET} minutes");
+        }
+
+        let id = id.into();
+        let preferences = self.load(&id).await?;
+        if preferences.time_budget == minutes {
+            return Ok(());
+        }
+
+        preferences
+            .write()?
+            .event(&TimeBudgetChanged { minutes })
+            .requested_by(id)
+            .commit(&self.executor)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,111 @@
+mod quiet_hours;
+mod send_test;
+mod update;
+
+use bitcode::{Decode, Encode};
+pub use quiet_hours::*;
+use std::ops::Deref;
+pub use update::*;
+
+use evento::{Executor, Projection, metadata::Event};
+use imkitchen_types::notification_preferences::{
+    self, Changed, NotificationKind, QuietHours, QuietHoursChanged, TestRequested,
+};
+use strum::VariantArray;
+
+#[derive(Clone)]
+pub struct Module<E: Executor>(pub(crate) imkitchen_core::State<E>);
+
+impl<E: Executor> Deref for Module<E> {
+    type Target = imkitchen_core::State<E>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<E: Executor> Module<E> {
+    pub async fn load(&self, id: impl Into<String>) -> anyhow::Result<NotificationPreferences> {
+        let id = id.into();
+
+        create_projection::<E>()
+            .load(&id)
+            .execute(&self.executor)
+            .await
+            .map(|r| {
+                r.unwrap_or_else(|| NotificationPreferences {
+                    id,
+                    enabled: NotificationKind::VARIANTS.to_vec(),
+                    last_test_kinds: vec![],
+                    last_tested_at: 0,
+                    quiet_hours: None,
+                    cursor: Default::default(),
+                })
+            })
+    }
+}
+
+#[evento::projection(Encode, Decode)]
+pub struct NotificationPreferences {
+    pub id: String,
+    /// Every kind is enabled until the user changes their preferences.
+    pub enabled: Vec<NotificationKind>,
+    pub last_test_kinds: Vec<NotificationKind>,
+    pub last_tested_at: u64,
+    pub quiet_hours: Option<QuietHours>,
+}
+
+impl NotificationPreferences {
+    pub fn is_enabled(&self, kind: NotificationKind) -> bool {
+        self.enabled.contains(&kind)
+    }
+}
+
+fn create_projection<E: Executor>() -> Projection<E, NotificationPreferences> {
+    Projection::new::<notification_preferences::NotificationPreferences>()
+        .handler(handle_changed())
+        .handler(handle_test_requested())
+        .handler(handle_quiet_hours_changed())
+        .revision(1)
+        .strict()
+}
+
+impl evento::ProjectionAggregate for NotificationPreferences {
+    fn aggregate_id(&self) -> String {
+        self.id.to_owned()
+    }
+}
+
+#[evento::handler]
+async fn handle_changed(
+    event: Event<Changed>,
+    data: &mut NotificationPreferences,
+) -> anyhow::Result<()> {
+    data.id = event.aggregate_id.to_owned();
+    data.enabled = event.data.enabled;
+
+    Ok(())
+}
+
+#[evento::handler]
+async fn handle_test_requested(
+    event: Event<TestRequested>,
+    data: &mut NotificationPreferences,
+) -> anyhow::Result<()> {
+    data.id = event.aggregate_id.to_owned();
+    data.last_test_kinds = event.data.kinds;
+    data.last_tested_at = event.timestamp;
+
+    Ok(())
+}
+
+#[evento::handler]
+async fn handle_quiet_hours_changed(
+    event: Event<QuietHoursChanged>,
+    data: &mut NotificationPreferences,
+) -> anyhow::Result<()> {
+    data.id = event.aggregate_id.to_owned();
+    data.quiet_hours = event.data.quiet_hours;
+
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use evento::{Executor, ProjectionAggregate};
+use imkitchen_types::notification_preferences::{QuietHours, QuietHoursChanged};
+use time::{Duration, OffsetDateTime};
+
+impl<E: Executor> super::Module<E> {
+    /// Sets the user's quiet hours, `None` turns them off.
+    pub async fn set_quiet_hours(
+        &self,
+        id: impl Into<String>,
+        quiet_hours: Option<QuietHours>,
+    ) -> imkitchen_core::Result<()> {
+        let id = id.into();
+
+        if let Some(quiet_hours) = quiet_hours {
+            if quiet_hours.start >= QuietHours::MINUTES_PER_DAY
+                || quiet_hours.end >= QuietHours::MINUTES_PER_DAY
+            {
+                imkitchen_core::user!("Quiet hours must be a time of day");
+            }
+
+            if quiet_hours.start == quiet_hours.end {
+                imkitchen_core::user!("Quiet hours must start and end at different times");
+            }
+        }
+
+        let preferences = self.load(&id).await?;
+        if preferences.quiet_hours == quiet_hours {
+            return Ok(());
+        }
+
+        preferences
+            .write()?
+            .event(&QuietHoursChanged { quiet_hours })
+            .requested_by(id)
+            .commit(&self.executor)
+            .await?;
+
+        Ok(())
+    }
+}
+
+impl super::NotificationPreferences {
+    /// When a notification meant for `at` may go out, given the user's quiet
+    /// hours in `timezone`. Inside quiet hours it waits for them to end,
+    /// unless `deadline` comes first: it then goes out as they begin, so a
+    /// reminder is never delivered too late to be useful.
+    pub fn next_allowed(
+        &self,
+        at: OffsetDateTime,
+        timezone: &str,
+        deadline: Option<OffsetDateTime>,
+    ) -> OffsetDateTime {
+        let Some(quiet_hours) = self.quiet_hours else {
+            return at;
+        };
+
+        let local = imkitchen_core::mealplan::to_timezone(at, timezone);
+        let minute = local.hour() as u16 * 60 + local.minute() as u16;
+        if !quiet_hours.contains(minute) {
+            return at;
+        }
+
+        let at = at
+            .replace_second(0)
+            .unwrap_or(at)
+            .replace_nanosecond(0)
+            .unwrap_or(at);
+        let end = at + Duration::minutes(quiet_hours.minutes_until_end(minute).into());
+
+        match deadline {
+            Some(deadline) if deadline < end => {
+                at - Duration::minutes(quiet_hours.minutes_since_start(minute).into())
+            }
+            _ => end,
+        }
+    }
+}
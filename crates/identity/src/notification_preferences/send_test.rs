@@ -0,0 +1,35 @@
+use evento::{Executor, ProjectionAggregate};
+use imkitchen_types::notification_preferences::TestRequested;
+use time::OffsetDateTime;
+
+/// Minimum delay between two test sends for the same user.
+const TEST_COOLDOWN_SECS: u64 = 5 * 60;
+
+impl<E: Executor> super::Module<E> {
+    /// Queue a sample of every enabled notification kind for the user, right
+    /// away instead of waiting for the scheduler. Disabled kinds are skipped.
+    pub async fn send_test(&self, id: impl Into<String>) -> imkitchen_core::Result<()> {
+        let id = id.into();
+        let preferences = self.load(&id).await?;
+
+        let now = OffsetDateTime::now_utc().unix_timestamp() as u64;
+        if preferences.last_tested_at + TEST_COOLDOWN_SECS > now {
+            imkitchen_core::user!("Test notifications were sent recently. Please try again later.");
+        }
+
+        if preferences.enabled.is_empty() {
+            imkitchen_core::user!("All notifications are disabled");
+        }
+
+        let kinds = preferences.enabled.to_vec();
+
+        preferences
+            .write()?
+            .event(&TestRequested { kinds })
+            .requested_by(id)
+            .commit(&self.executor)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,33 @@
+use evento::{Executor, ProjectionAggregate};
+use imkitchen_types::notification_preferences::{Changed, NotificationKind};
+use strum::VariantArray;
+
+pub struct UpdateInput {
+    pub enabled: Vec<NotificationKind>,
+}
+
+impl<E: Executor> super::Module<E> {
+    pub async fn update(
+        &self,
+        id: impl Into<String>,
+        input: UpdateInput,
+    ) -> imkitchen_core::Result<()> {
+        let id = id.into();
+        let preferences = self.load(&id).await?;
+
+        let enabled = NotificationKind::VARIANTS
+            .iter()
+            .filter(|kind| input.enabled.contains(kind))
+            .copied()
+            .collect();
+
+        preferences
+            .write()?
+            .event(&Changed { enabled })
+            .requested_by(id)
+            .commit(&self.executor)
+            .await?;
+
+        Ok(())
+    }
+}
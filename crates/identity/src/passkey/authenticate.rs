@@ -0,0 +1,100 @@
+use crate::types::{
+    passkey::{Ceremony, ChallengeConsumed},
+    user::{LoggedIn, State},
+};
+use evento::{Executor, ProjectionAggregate};
+use ring::{
+    digest::{SHA256, digest},
+    signature::{ECDSA_P256_SHA256_ASN1, UnparsedPublicKey},
+};
+use ulid::Ulid;
+use validator::Validate;
+
+#[derive(Validate)]
+pub struct AuthenticateInput {
+    pub challenge_id: String,
+    #[validate(length(min = 1))]
+    pub credential_id: String,
+    /// The `userHandle` returned by the authenticator, which is the user id.
+    #[validate(length(min = 1))]
+    pub user_handle: String,
+    pub client_data_json: Vec<u8>,
+    pub authenticator_data: Vec<u8>,
+    pub signature: Vec<u8>,
+    pub origin: String,
+    pub rp_id: String,
+    pub lang: String,
+    pub timezone: String,
+    pub user_agent: String,
+}
+
+impl<E: Executor> super::Module<E> {
+    pub async fn authenticate(
+        &self,
+        input: AuthenticateInput,
+    ) -> imkitchen_core::Result<(String, String)> {
+        input.validate()?;
+
+        let challenge = self
+            .load_valid(&input.challenge_id, Ceremony::Authentication)
+            .await?;
+
+        super::verify_client_data(&input.client_data_json, &challenge, &input.origin)?;
+        super::verify_authenticator_data(&input.authenticator_data, &input.rp_id)?;
+
+        let Some(user) = crate::root::create_projection()
+            .load(&input.user_handle)
+            .execute(&self.executor)
+            .await?
+        else {
+            imkitchen_core::user!("Invalid passkey. Please try again.");
+        };
+
+        let Some(passkey) = user
+            .passkeys
+            .iter()
+            .find(|p| p.credential_id == input.credential_id)
+        else {
+            imkitchen_core::user!("Invalid passkey. Please try again.");
+        };
+
+        let client_data_hash = digest(&SHA256, &input.client_data_json);
+        let message = [
+            input.authenticator_data.as_slice(),
+            client_data_hash.as_ref(),
+        ]
+        .concat();
+
+        if UnparsedPublicKey::new(&ECDSA_P256_SHA256_ASN1, &passkey.public_key)
+            .verify(&message, &input.signature)
+            .is_err()
+        {
+            imkitchen_core::user!("Invalid passkey. Please try again.");
+        }
+
+        if user.state == State::Suspended {
+            imkitchen_core::user!("Account suspended");
+        }
+
+        challenge
+            .write()?
+            .event(&ChallengeConsumed)
+            .requested_by(&user.id)
+            .commit(&self.executor)
+            .await?;
+
+        let access_id = Ulid::new().to_string();
+
+        user.write()?
+            .event(&LoggedIn {
+                lang: input.lang,
+                timezone: input.timezone,
+                user_agent: input.user_agent,
+                access_id: access_id.to_owned(),
+            })
+            .commit(&self.executor)
+            .await?;
+
+        Ok((user.id, access_id))
+    }
+}
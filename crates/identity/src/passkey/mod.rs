@@ -0,0 +1,199 @@
+mod authenticate;
+mod register;
+mod start;
+
+use std::ops::Deref;
+
+pub use authenticate::*;
+pub use register::*;
+pub use start::*;
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use bitcode::{Decode, Encode};
+use evento::{Executor, Projection, metadata::Event};
+use ring::digest::{SHA256, digest};
+use serde::Deserialize;
+use time::OffsetDateTime;
+
+use crate::types::passkey::{self, Ceremony, ChallengeConsumed, ChallengeIssued};
+
+/// DER prefix of a SubjectPublicKeyInfo holding an uncompressed P-256 point
+/// (`id-ecPublicKey` + `prime256v1`), as returned by `getPublicKey()`.
+const P256_SPKI_PREFIX: [u8; 26] = [
+    0x30, 0x59, 0x30, 0x13, 0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01, 0x06, 0x08, 0x2a,
+    0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07, 0x03, 0x42, 0x00,
+];
+
+const FLAG_USER_PRESENT: u8 = 0x01;
+
+#[derive(Clone)]
+pub struct Module<E: Executor>(pub(crate) imkitchen_core::State<E>);
+
+impl<E: Executor> Deref for Module<E> {
+    type Target = imkitchen_core::State<E>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<E: Executor> Module<E> {
+    pub async fn load(&self, id: impl Into<String>) -> anyhow::Result<Option<Challenge>> {
+        create_projection().load(id).execute(&self.executor).await
+    }
+
+    /// Loads a challenge and checks it is still usable for the given ceremony.
+    /// Challenges are single use and expire after 5 minutes.
+    pub(crate) async fn load_valid(
+        &self,
+        id: impl Into<String>,
+        ceremony: Ceremony,
+    ) -> imkitchen_core::Result<Challenge> {
+        let Some(challenge) = self.load(id).await? else {
+            imkitchen_core::not_found!("challenge");
+        };
+
+        let now: u64 = OffsetDateTime::now_utc().unix_timestamp().try_into()?;
+
+        if now > challenge.expire_at {
+            imkitchen_core::user!("challenge expired");
+        }
+
+        if challenge.consumed {
+            imkitchen_core::user!("challenge already used");
+        }
+
+        if challenge.ceremony != ceremony {
+            imkitchen_core::user!("invalid challenge");
+        }
+
+        Ok(challenge)
+    }
+}
+
+#[evento::projection(Encode, Decode)]
+pub struct Challenge {
+    pub id: String,
+    pub user_id: Option<String>,
+    pub challenge: String,
+    pub ceremony: Ceremony,
+    pub consumed: bool,
+    pub expire_at: u64,
+}
+
+fn create_projection<E: Executor>() -> Projection<E, Challenge> {
+    Projection::new::<passkey::Passkey>()
+        .handler(handle_challenge_issued())
+        .handler(handle_challenge_consumed())
+        .strict()
+}
+
+impl evento::ProjectionAggregate for Challenge {
+    fn aggregate_id(&self) -> String {
+        self.id.to_owned()
+    }
+}
+
+#[evento::handler]
+async fn handle_challenge_issued(
+    event: Event<ChallengeIssued>,
+    data: &mut Challenge,
+) -> anyhow::Result<()> {
+    data.id = event.aggregate_id.to_owned();
+    data.user_id = event.data.user_id.to_owned();
+    data.challenge = event.data.challenge.to_owned();
+    data.ceremony = event.data.ceremony.to_owned();
+    data.consumed = false;
+    data.expire_at = (OffsetDateTime::from_unix_timestamp(event.timestamp.try_into()?)?
+        + time::Duration::minutes(5))
+    .unix_timestamp()
+    .try_into()?;
+
+    Ok(())
+}
+
+#[evento::handler]
+async fn handle_challenge_consumed(
+    _event: Event<ChallengeConsumed>,
+    data: &mut Challenge,
+) -> anyhow::Result<()> {
+    data.consumed = true;
+
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct ClientData {
+    #[serde(rename = "type")]
+    kind: String,
+    challenge: String,
+    origin: String,
+}
+
+/// Checks the `clientDataJSON` produced by the browser against the challenge
+/// we issued and the origin we expect.
+fn verify_client_data(
+    client_data_json: &[u8],
+    challenge: &Challenge,
+    origin: &str,
+) -> imkitchen_core::Result<()> {
+    let Ok(client_data) = serde_json::from_slice::<ClientData>(client_data_json) else {
+        imkitchen_core::user!("invalid client data");
+    };
+
+    if client_data.kind != challenge.ceremony.client_data_type() {
+        imkitchen_core::user!("invalid client data");
+    }
+
+    if client_data.challenge != challenge.challenge {
+        imkitchen_core::user!("invalid challenge");
+    }
+
+    if client_data.origin != origin {
+        imkitchen_core::user!("invalid origin");
+    }
+
+    Ok(())
+}
+
+/// Checks the relying party id hash and the user presence flag of the
+/// authenticator data.
+fn verify_authenticator_data(authenticator_data: &[u8], rp_id: &str) -> imkitchen_core::Result<()> {
+    if authenticator_data.len() < 37 {
+        imkitchen_core::user!("invalid authenticator data");
+    }
+
+    if authenticator_data[..32] != *digest(&SHA256, rp_id.as_bytes()).as_ref() {
+        imkitchen_core::user!("invalid relying party");
+    }
+
+    if authenticator_data[32] & FLAG_USER_PRESENT == 0 {
+        imkitchen_core::user!("user presence required");
+    }
+
+    Ok(())
+}
+
+/// Extracts the raw P-256 point from a DER encoded SubjectPublicKeyInfo.
+fn p256_public_key(spki: &[u8]) -> imkitchen_core::Result<Vec<u8>> {
+    let Some(point) = spki.strip_prefix(&P256_SPKI_PREFIX) else {
+        imkitchen_core::user!("unsupported passkey algorithm");
+    };
+
+    if point.len() != 65 || point[0] != 0x04 {
+        imkitchen_core::user!("unsupported passkey algorithm");
+    }
+
+    Ok(point.to_vec())
+}
+
+pub(crate) fn generate_challenge() -> imkitchen_core::Result<String> {
+    use ring::rand::{SecureRandom, SystemRandom};
+
+    let mut bytes = [0u8; 32];
+    if SystemRandom::new().fill(&mut bytes).is_err() {
+        imkitchen_core::server!("failed to generate passkey challenge");
+    }
+
+    Ok(URL_SAFE_NO_PAD.encode(bytes))
+}
@@ -0,0 +1,76 @@
+use crate::types::{
+    passkey::{Ceremony, ChallengeConsumed},
+    user::PasskeyRegistered,
+};
+use evento::{Executor, ProjectionAggregate};
+use validator::Validate;
+
+#[derive(Validate)]
+pub struct RegisterInput {
+    pub challenge_id: String,
+    pub user_id: String,
+    #[validate(length(min = 1))]
+    pub credential_id: String,
+    #[validate(length(min = 1, max = 50))]
+    pub name: String,
+    /// DER encoded SubjectPublicKeyInfo from `response.getPublicKey()`.
+    pub public_key: Vec<u8>,
+    pub client_data_json: Vec<u8>,
+    /// Raw bytes from `response.getAuthenticatorData()`.
+    pub authenticator_data: Vec<u8>,
+    pub origin: String,
+    pub rp_id: String,
+}
+
+impl<E: Executor> super::Module<E> {
+    pub async fn register(&self, input: RegisterInput) -> imkitchen_core::Result<()> {
+        input.validate()?;
+
+        let challenge = self
+            .load_valid(&input.challenge_id, Ceremony::Registration)
+            .await?;
+
+        if challenge.user_id.as_deref() != Some(input.user_id.as_str()) {
+            imkitchen_core::forbidden!("passkey challenge");
+        }
+
+        super::verify_client_data(&input.client_data_json, &challenge, &input.origin)?;
+        super::verify_authenticator_data(&input.authenticator_data, &input.rp_id)?;
+        let public_key = super::p256_public_key(&input.public_key)?;
+
+        let Some(user) = crate::root::create_projection()
+            .load(&input.user_id)
+            .execute(&self.executor)
+            .await?
+        else {
+            imkitchen_core::not_found!("user in passkey register");
+        };
+
+        if user
+            .passkeys
+            .iter()
+            .any(|p| p.credential_id == input.credential_id)
+        {
+            imkitchen_core::user!("Passkey already registered");
+        }
+
+        challenge
+            .write()?
+            .event(&ChallengeConsumed)
+            .requested_by(&input.user_id)
+            .commit(&self.executor)
+            .await?;
+
+        user.write()?
+            .event(&PasskeyRegistered {
+                credential_id: input.credential_id,
+                public_key,
+                name: input.name,
+            })
+            .requested_by(&input.user_id)
+            .commit(&self.executor)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,114 @@
+use crate::types::passkey::{Ceremony, ChallengeIssued};
+use evento::Executor;
+use imkitchen_db::passkey_challenge::PasskeyChallenge;
+use sea_query::{Expr, ExprTrait, Func, Query, SqliteQueryBuilder};
+use sea_query_sqlx::SqlxBinder;
+use time::OffsetDateTime;
+
+/// Login challenges issued to the same IP within [`CHALLENGE_WINDOW_SECS`],
+/// matching how long a challenge stays valid. Requests without a known IP
+/// share one allowance.
+pub const CHALLENGE_LIMIT_PER_IP: i64 = 20;
+pub const CHALLENGE_WINDOW_SECS: i64 = 5 * 60;
+
+/// What the browser needs to start a WebAuthn ceremony. `challenge` is
+/// base64url encoded, `id` must be sent back to finish the ceremony.
+#[derive(Debug, Clone)]
+pub struct ChallengeOptions {
+    pub id: String,
+    pub challenge: String,
+}
+
+impl<E: Executor> super::Module<E> {
+    pub async fn start_registration(
+        &self,
+        user_id: impl Into<String>,
+    ) -> imkitchen_core::Result<ChallengeOptions> {
+        self.issue(Some(user_id.into()), Ceremony::Registration)
+            .await
+    }
+
+    /// Anyone can start a login, so challenges are rate limited per `ip`.
+    pub async fn start_authentication(
+        &self,
+        ip: Option<String>,
+    ) -> imkitchen_core::Result<ChallengeOptions> {
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        let filter = match ip.as_deref() {
+            Some(ip) => Expr::col(PasskeyChallenge::Ip).eq(ip),
+            None => Expr::col(PasskeyChallenge::Ip).is_null(),
+        };
+        let (sql, values) = Query::select()
+            .expr(Func::count(Expr::col(PasskeyChallenge::Id)))
+            .from(PasskeyChallenge::Table)
+            .and_where(filter)
+            .and_where(Expr::col(PasskeyChallenge::CreatedAt).gte(now - CHALLENGE_WINDOW_SECS))
+            .build_sqlx(SqliteQueryBuilder);
+
+        let (count,) = sqlx::query_as_with::<_, (i64,), _>(sqlx::AssertSqlSafe(sql), values)
+            .fetch_one(&self.read_db)
+            .await?;
+
+        if count >= CHALLENGE_LIMIT_PER_IP {
+            imkitchen_core::user!("Too many login attempts, please try again later");
+        }
+
+        let options = self.issue(None, Ceremony::Authentication).await?;
+        self.record_challenge(&options.id, ip, now).await?;
+
+        Ok(options)
+    }
+
+    async fn issue(
+        &self,
+        user_id: Option<String>,
+        ceremony: Ceremony,
+    ) -> imkitchen_core::Result<ChallengeOptions> {
+        let challenge = super::generate_challenge()?;
+
+        let id = evento::create()
+            .event(&ChallengeIssued {
+                user_id,
+                challenge: challenge.to_owned(),
+                ceremony,
+            })
+            .commit(&self.executor)
+            .await?;
+
+        Ok(ChallengeOptions { id, challenge })
+    }
+
+    /// Kept in `passkey_challenge` for the limit above. Rows of expired
+    /// challenges are dropped.
+    async fn record_challenge(
+        &self,
+        id: &str,
+        ip: Option<String>,
+        now: i64,
+    ) -> imkitchen_core::Result<()> {
+        let (sql, values) = Query::insert()
+            .into_table(PasskeyChallenge::Table)
+            .columns([
+                PasskeyChallenge::Id,
+                PasskeyChallenge::Ip,
+                PasskeyChallenge::CreatedAt,
+            ])
+            .values_panic([id.into(), ip.into(), now.into()])
+            .build_sqlx(SqliteQueryBuilder);
+
+        sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+            .execute(&self.write_db)
+            .await?;
+
+        let (sql, values) = Query::delete()
+            .from_table(PasskeyChallenge::Table)
+            .and_where(Expr::col(PasskeyChallenge::CreatedAt).lt(now - CHALLENGE_WINDOW_SECS))
+            .build_sqlx(SqliteQueryBuilder);
+
+        sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+            .execute(&self.write_db)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -12,7 +12,10 @@ use evento::{Executor, Projection, metadata::Event};
 use time::OffsetDateTime;
 
 #[derive(Clone)]
-pub struct Module<E: Executor>(pub(crate) imkitchen_core::State<E>);
+pub struct Module<E: Executor>(
+    pub(crate) imkitchen_core::State<E>,
+    pub(crate) crate::PasswordHashing,
+);
 
 impl<E: Executor> Deref for Module<E> {
     type Target = imkitchen_core::State<E>;
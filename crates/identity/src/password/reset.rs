@@ -1,8 +1,4 @@
 use crate::types::password::ResetCompleted;
-use argon2::{
-    Argon2, PasswordHasher,
-    password_hash::{SaltString, rand_core::OsRng},
-};
 use evento::{Executor, ProjectionAggregate};
 use time::OffsetDateTime;
 use validator::Validate;
@@ -33,11 +29,7 @@ impl<E: Executor> super::Module<E> {
             imkitchen_core::user!("has already been reset");
         }
 
-        let salt = SaltString::generate(&mut OsRng);
-        let argon2 = Argon2::default();
-        let password_hash = argon2
-            .hash_password(input.password.as_bytes(), &salt)?
-            .to_string();
+        let password_hash = self.1.hash(&input.password)?;
 
         repository::update(
             &self.write_db,
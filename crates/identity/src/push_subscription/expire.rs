@@ -0,0 +1,25 @@
+use evento::{Executor, ProjectionAggregate};
+
+use crate::types::push_subscription::Expired;
+
+impl<E: Executor> super::Module<E> {
+    /// Marks the subscription as gone after the push service rejected it
+    /// with `status`.
+    pub async fn expire(&self, id: impl Into<String>, status: u16) -> imkitchen_core::Result<()> {
+        let Some(subscription) = self.load(id).await? else {
+            imkitchen_core::not_found!("push subscription in expire");
+        };
+
+        if subscription.expired {
+            return Ok(());
+        }
+
+        subscription
+            .write()?
+            .event(&Expired { status })
+            .commit(&self.executor)
+            .await?;
+
+        Ok(())
+    }
+}
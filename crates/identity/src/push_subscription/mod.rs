@@ -0,0 +1,85 @@
+mod expire;
+mod subscribe;
+mod unsubscribe;
+
+use std::ops::Deref;
+
+pub use subscribe::*;
+
+use bitcode::{Decode, Encode};
+use evento::{Executor, Projection, metadata::Event};
+
+use crate::types::push_subscription::{self, Expired, Subscribed, Unsubscribed};
+
+#[derive(Clone)]
+pub struct Module<E: Executor>(pub(crate) imkitchen_core::State<E>);
+
+impl<E: Executor> Deref for Module<E> {
+    type Target = imkitchen_core::State<E>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<E: Executor> Module<E> {
+    pub async fn load(&self, id: impl Into<String>) -> anyhow::Result<Option<PushSubscription>> {
+        create_projection().load(id).execute(&self.executor).await
+    }
+}
+
+#[evento::projection(Encode, Decode)]
+pub struct PushSubscription {
+    pub id: String,
+    pub user_id: String,
+    pub endpoint: String,
+    pub p256dh: String,
+    pub auth: String,
+    pub expired: bool,
+}
+
+fn create_projection<E: Executor>() -> Projection<E, PushSubscription> {
+    Projection::new::<push_subscription::PushSubscription>()
+        .handler(handle_subscribed())
+        .handler(handle_expired())
+        .handler(handle_unsubscribed())
+        .strict()
+}
+
+impl evento::ProjectionAggregate for PushSubscription {
+    fn aggregate_id(&self) -> String {
+        self.id.to_owned()
+    }
+}
+
+#[evento::handler]
+async fn handle_subscribed(
+    event: Event<Subscribed>,
+    data: &mut PushSubscription,
+) -> anyhow::Result<()> {
+    data.id = event.aggregate_id.to_owned();
+    data.user_id = event.data.user_id;
+    data.endpoint = event.data.endpoint;
+    data.p256dh = event.data.p256dh;
+    data.auth = event.data.auth;
+    data.expired = false;
+
+    Ok(())
+}
+
+#[evento::handler]
+async fn handle_expired(_event: Event<Expired>, data: &mut PushSubscription) -> anyhow::Result<()> {
+    data.expired = true;
+
+    Ok(())
+}
+
+#[evento::handler]
+async fn handle_unsubscribed(
+    _event: Event<Unsubscribed>,
+    data: &mut PushSubscription,
+) -> anyhow::Result<()> {
+    data.expired = true;
+
+    Ok(())
+}
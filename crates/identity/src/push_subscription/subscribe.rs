@@ -0,0 +1,39 @@
+use evento::Executor;
+use validator::Validate;
+
+use crate::types::push_subscription::Subscribed;
+
+/// Fields of the browser's `PushSubscription.toJSON()`.
+#[derive(Validate)]
+pub struct SubscribeInput {
+    #[validate(url)]
+    pub endpoint: String,
+    #[validate(length(min = 1))]
+    pub p256dh: String,
+    #[validate(length(min = 1))]
+    pub auth: String,
+}
+
+impl<E: Executor> super::Module<E> {
+    pub async fn subscribe(
+        &self,
+        user_id: impl Into<String>,
+        input: SubscribeInput,
+    ) -> imkitchen_core::Result<String> {
+        input.validate()?;
+
+        let user_id = user_id.into();
+        let id = evento::create()
+            .event(&Subscribed {
+                user_id: user_id.to_owned(),
+                endpoint: input.endpoint,
+                p256dh: input.p256dh,
+                auth: input.auth,
+            })
+            .requested_by(user_id)
+            .commit(&self.executor)
+            .await?;
+
+        Ok(id)
+    }
+}
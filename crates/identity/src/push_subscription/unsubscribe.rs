@@ -0,0 +1,34 @@
+use evento::{Executor, ProjectionAggregate};
+
+use crate::types::push_subscription::Unsubscribed;
+
+impl<E: Executor> super::Module<E> {
+    /// Stops pushing to the subscription at the user's request.
+    pub async fn unsubscribe(
+        &self,
+        id: impl Into<String>,
+        user_id: impl Into<String>,
+    ) -> imkitchen_core::Result<()> {
+        let user_id = user_id.into();
+        let Some(subscription) = self.load(id).await? else {
+            imkitchen_core::not_found!("push subscription in unsubscribe");
+        };
+
+        if subscription.user_id != user_id {
+            imkitchen_core::forbidden!("push subscription in unsubscribe");
+        }
+
+        if subscription.expired {
+            return Ok(());
+        }
+
+        subscription
+            .write()?
+            .event(&Unsubscribed)
+            .requested_by(user_id)
+            .commit(&self.executor)
+            .await?;
+
+        Ok(())
+    }
+}
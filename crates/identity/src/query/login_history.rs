@@ -0,0 +1,147 @@
+use evento::{
+    Executor,
+    metadata::Event,
+    subscription::{Context, SubscriptionBuilder},
+};
+use imkitchen_db::user_login_history::UserLoginHistory;
+use sea_query::{Expr, ExprTrait, OnConflict, Query, SqliteQueryBuilder};
+use sea_query_sqlx::SqlxBinder;
+use sqlx::{SqlitePool, prelude::FromRow};
+
+use crate::types::password::ResetCompleted;
+use crate::types::user::{LoggedIn, Logout};
+
+/// Most logins returned by [`crate::Module::login_history`].
+pub const MAX_LOGIN_HISTORY: u64 = 50;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct LoginHistoryView {
+    pub access_id: String,
+    pub user_agent: String,
+    pub logged_in_at: u64,
+    /// When the session ended, by logout or password reset.
+    pub logged_out_at: Option<u64>,
+}
+
+impl LoginHistoryView {
+    pub fn is_active(&self) -> bool {
+        self.logged_out_at.is_none()
+    }
+}
+
+impl<E: Executor> crate::Module<E> {
+    /// The user's most recent logins, newest first.
+    pub async fn login_history(
+        &self,
+        user_id: impl Into<String>,
+        limit: u64,
+    ) -> anyhow::Result<Vec<LoginHistoryView>> {
+        let statement = Query::select()
+            .columns([
+                UserLoginHistory::AccessId,
+                UserLoginHistory::UserAgent,
+                UserLoginHistory::LoggedInAt,
+                UserLoginHistory::LoggedOutAt,
+            ])
+            .from(UserLoginHistory::Table)
+            .and_where(Expr::col(UserLoginHistory::UserId).eq(user_id.into()))
+            .order_by(UserLoginHistory::LoggedInAt, sea_query::Order::Desc)
+            .order_by(UserLoginHistory::AccessId, sea_query::Order::Desc)
+            .limit(limit.min(MAX_LOGIN_HISTORY))
+            .to_owned();
+
+        let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+
+        Ok(
+            sqlx::query_as_with::<_, LoginHistoryView, _>(sqlx::AssertSqlSafe(sql), values)
+                .fetch_all(&self.read_db)
+                .await?,
+        )
+    }
+}
+
+pub fn subscription<E: Executor>() -> SubscriptionBuilder<E> {
+    SubscriptionBuilder::new("user-login-history")
+        .handler(handle_logged_in())
+        .handler(handle_logout())
+        .handler(handle_reset_completed())
+}
+
+#[evento::subscription]
+async fn handle_logged_in<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<LoggedIn>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<SqlitePool>();
+    let statement = Query::insert()
+        .into_table(UserLoginHistory::Table)
+        .columns([
+            UserLoginHistory::AccessId,
+            UserLoginHistory::UserId,
+            UserLoginHistory::UserAgent,
+            UserLoginHistory::LoggedInAt,
+        ])
+        .values_panic([
+            event.data.access_id.to_owned().into(),
+            event.aggregate_id.to_owned().into(),
+            event.data.user_agent.to_owned().into(),
+            event.timestamp.into(),
+        ])
+        .on_conflict(
+            OnConflict::column(UserLoginHistory::AccessId)
+                .do_nothing()
+                .to_owned(),
+        )
+        .to_owned();
+
+    let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+    sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+        .execute(&pool)
+        .await?;
+
+    Ok(())
+}
+
+#[evento::subscription]
+async fn handle_logout<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<Logout>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<SqlitePool>();
+    let statement = Query::update()
+        .table(UserLoginHistory::Table)
+        .value(UserLoginHistory::LoggedOutAt, event.timestamp)
+        .and_where(Expr::col(UserLoginHistory::AccessId).eq(&event.data.access_id))
+        .and_where(Expr::col(UserLoginHistory::UserId).eq(&event.aggregate_id))
+        .and_where(Expr::col(UserLoginHistory::LoggedOutAt).is_null())
+        .to_owned();
+
+    let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+    sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+        .execute(&pool)
+        .await?;
+
+    Ok(())
+}
+
+/// A password reset signs every session out (see the login projection).
+#[evento::subscription]
+async fn handle_reset_completed<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<ResetCompleted>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<SqlitePool>();
+    let statement = Query::update()
+        .table(UserLoginHistory::Table)
+        .value(UserLoginHistory::LoggedOutAt, event.timestamp)
+        .and_where(Expr::col(UserLoginHistory::UserId).eq(event.metadata.requested_by()?))
+        .and_where(Expr::col(UserLoginHistory::LoggedOutAt).is_null())
+        .to_owned();
+
+    let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+    sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+        .execute(&pool)
+        .await?;
+
+    Ok(())
+}
@@ -1,3 +1,4 @@
 pub mod admin;
 pub mod global_stat;
 pub mod login;
+pub mod login_history;
@@ -1,5 +1,4 @@
 use crate::types::user::{LoggedIn, Logout, State};
-use argon2::{Argon2, PasswordHash, PasswordVerifier};
 use evento::{Executor, ProjectionAggregate};
 use ulid::Ulid;
 use validator::Validate;
@@ -31,13 +30,7 @@ impl<E: Executor> super::Module<E> {
             imkitchen_core::server!("User not found in login");
         };
 
-        let parsed_hash = PasswordHash::new(&user_row.password)?;
-        let argon2 = Argon2::default();
-
-        if argon2
-            .verify_password(input.password.as_bytes(), &parsed_hash)
-            .is_err()
-        {
+        if !super::PasswordHashing::verify(&user_row.password, &input.password)? {
             imkitchen_core::user!("Invalid email or password. Please try again.");
         }
 
@@ -1,6 +1,6 @@
 use crate::types::user::{
-    self, Activated, EmailChanged, LoggedIn, Logout, MadeAdmin, Registered, Role, RoleChanged,
-    State, Suspended, UsernameChanged,
+    self, Activated, EmailChanged, LoggedIn, Logout, MadeAdmin, PasskeyRegistered, Registered,
+    Role, RoleChanged, State, Suspended, UsernameChanged,
 };
 use bitcode::{Decode, Encode};
 use evento::{Executor, Projection, ProjectionAggregate, metadata::Event};
@@ -13,11 +13,13 @@ mod change_email;
 mod change_role;
 mod login;
 mod made_admin;
+mod password_hashing;
 mod register;
 mod set_username;
 mod suspend;
 
 pub use login::LoginInput;
+pub use password_hashing::PasswordHashing;
 pub use register::RegisterInput;
 pub use set_username::SetUsernameInput;
 
@@ -25,8 +27,12 @@ pub use set_username::SetUsernameInput;
 pub struct Module<E: Executor> {
     state: imkitchen_core::State<E>,
     pub meal_preferences: crate::meal_preferences::Module<E>,
+    pub notification_preferences: crate::notification_preferences::Module<E>,
+    pub passkey: crate::passkey::Module<E>,
     pub password: crate::password::Module<E>,
+    pub push_subscription: crate::push_subscription::Module<E>,
     pub user_profile: crate::user_profile::Module<E>,
+    password_hashing: PasswordHashing,
 }
 
 impl<E: Executor> Deref for Module<E> {
@@ -44,11 +50,23 @@ impl<E: Executor> Module<E> {
     {
         Self {
             meal_preferences: crate::meal_preferences::Module(state.clone()),
-            password: crate::password::Module(state.clone()),
+            notification_preferences: crate::notification_preferences::Module(state.clone()),
+            passkey: crate::passkey::Module(state.clone()),
+            password: crate::password::Module(state.clone(), PasswordHashing::default()),
+            push_subscription: crate::push_subscription::Module(state.clone()),
             user_profile: crate::user_profile::Module(state.clone()),
+            password_hashing: PasswordHashing::default(),
             state,
         }
     }
+
+    /// Cost parameters used for passwords hashed from now on.
+    pub fn with_password_hashing(mut self, password_hashing: PasswordHashing) -> Self {
+        self.password_hashing = password_hashing;
+        self.password.1 = password_hashing;
+        self
+    }
+
     pub async fn load(&self, id: impl Into<String>) -> anyhow::Result<Option<User>> {
         create_projection().load(id).execute(&self.executor).await
     }
@@ -102,6 +120,16 @@ pub struct User {
     pub id: String,
     pub role: Role,
     pub state: State,
+    pub passkeys: Vec<PasskeyCredential>,
+}
+
+/// A WebAuthn credential registered by the user. `public_key` is the raw
+/// uncompressed P-256 point (ES256 is the only algorithm accepted).
+#[derive(Encode, Decode, Clone, Debug, Default, PartialEq)]
+pub struct PasskeyCredential {
+    pub credential_id: String,
+    pub public_key: Vec<u8>,
+    pub name: String,
 }
 
 pub fn create_projection<E: Executor>() -> Projection<E, User> {
@@ -111,10 +139,12 @@ pub fn create_projection<E: Executor>() -> Projection<E, User> {
         .handler(handle_susended())
         .handler(handle_made_admin())
         .handler(handle_role_changed())
+        .handler(handle_passkey_registered())
         .skip::<LoggedIn>()
         .skip::<Logout>()
         .skip::<UsernameChanged>()
         .skip::<EmailChanged>()
+        .revision(1)
         .strict()
 }
 
@@ -160,3 +190,19 @@ async fn handle_susended(_event: Event<Suspended>, data: &mut User) -> anyhow::R
 
     Ok(())
 }
+
+#[evento::handler]
+async fn handle_passkey_registered(
+    event: Event<PasskeyRegistered>,
+    data: &mut User,
+) -> anyhow::Result<()> {
+    data.passkeys
+        .retain(|p| p.credential_id != event.data.credential_id);
+    data.passkeys.push(PasskeyCredential {
+        credential_id: event.data.credential_id.to_owned(),
+        public_key: event.data.public_key.to_owned(),
+        name: event.data.name.to_owned(),
+    });
+
+    Ok(())
+}
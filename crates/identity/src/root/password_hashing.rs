@@ -0,0 +1,48 @@
+use argon2::{
+    Algorithm, Argon2, Params, PasswordHash, PasswordHasher, PasswordVerifier, Version,
+    password_hash::{SaltString, rand_core::OsRng},
+};
+
+/// Argon2id cost parameters for new password hashes. Each hash records the
+/// parameters it was made with, so changing them leaves existing passwords
+/// verifiable.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PasswordHashing {
+    /// Memory cost in KiB.
+    pub memory_kib: u32,
+    /// Number of passes over the memory.
+    pub iterations: u32,
+    /// Degree of parallelism.
+    pub parallelism: u32,
+}
+
+impl Default for PasswordHashing {
+    fn default() -> Self {
+        Self {
+            memory_kib: Params::DEFAULT_M_COST,
+            iterations: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+impl PasswordHashing {
+    pub fn hash(&self, password: &str) -> Result<String, argon2::password_hash::Error> {
+        let params = Params::new(self.memory_kib, self.iterations, self.parallelism, None)?;
+        let salt = SaltString::generate(&mut OsRng);
+
+        Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password(password.as_bytes(), &salt)?
+            .to_string())
+    }
+
+    /// Checks `password` against `hash` using the parameters stored in the
+    /// hash, whatever the current ones are.
+    pub fn verify(hash: &str, password: &str) -> Result<bool, argon2::password_hash::Error> {
+        let parsed_hash = PasswordHash::new(hash)?;
+
+        Ok(Argon2::default()
+            .verify_password(password.as_bytes(), &parsed_hash)
+            .is_ok())
+    }
+}
@@ -1,2 +1,4 @@
+pub mod passkey;
 pub mod password;
+pub mod push_subscription;
 pub mod user;
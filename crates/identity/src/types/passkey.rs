@@ -0,0 +1,32 @@
+use bitcode::{Decode, Encode};
+use serde::Deserialize;
+use strum::{AsRefStr, Display, EnumString};
+
+#[derive(
+    Encode, Decode, EnumString, Display, AsRefStr, Clone, Debug, Default, PartialEq, Deserialize,
+)]
+pub enum Ceremony {
+    #[default]
+    Registration,
+    Authentication,
+}
+
+impl Ceremony {
+    /// The `type` a browser writes in `clientDataJSON` for this ceremony.
+    pub fn client_data_type(&self) -> &'static str {
+        match self {
+            Ceremony::Registration => "webauthn.create",
+            Ceremony::Authentication => "webauthn.get",
+        }
+    }
+}
+
+#[evento::aggregate]
+pub enum Passkey {
+    ChallengeIssued {
+        user_id: Option<String>,
+        challenge: String,
+        ceremony: Ceremony,
+    },
+    ChallengeConsumed,
+}
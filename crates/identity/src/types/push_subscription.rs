@@ -0,0 +1,14 @@
+#[evento::aggregate]
+pub enum PushSubscription {
+    Subscribed {
+        user_id: String,
+        endpoint: String,
+        p256dh: String,
+        auth: String,
+    },
+    /// The push service answered with `status` (404 or 410): the browser
+    /// dropped the subscription and it must not be used again.
+    Expired { status: u16 },
+    /// The user turned push notifications off on this device.
+    Unsubscribed,
+}
@@ -69,4 +69,9 @@ pub enum User {
     },
     Suspended,
     Activated,
+    PasskeyRegistered {
+        credential_id: String,
+        public_key: Vec<u8>,
+        name: String,
+    },
 }
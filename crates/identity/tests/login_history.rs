@@ -0,0 +1,52 @@
+use imkitchen_identity::LoginInput;
+use temp_dir::TempDir;
+
+mod helpers;
+
+#[tokio::test]
+async fn test_login_history() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_identity::Module::new(state.clone());
+    let user = helpers::create_user(&cmd, "john.doe").await?;
+
+    let mut access_ids = vec![];
+    for user_agent in ["Firefox", "Safari"] {
+        let (_, access_id) = cmd
+            .login(LoginInput {
+                email: "john.doe@imkitchen.localhost".to_owned(),
+                password: "my_password".to_owned(),
+                lang: "en".to_owned(),
+                timezone: "UTC".to_owned(),
+                user_agent: user_agent.to_owned(),
+            })
+            .await?;
+        access_ids.push(access_id);
+    }
+
+    cmd.logout(&user, access_ids[0].to_owned()).await?;
+
+    imkitchen_identity::login_history::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let history = cmd.login_history(&user, 10).await?;
+    assert_eq!(history.len(), 2);
+
+    let firefox = history.iter().find(|h| h.user_agent == "Firefox").unwrap();
+    assert_eq!(firefox.access_id, access_ids[0]);
+    assert!(!firefox.is_active());
+    assert!(firefox.logged_out_at.unwrap() >= firefox.logged_in_at);
+
+    let safari = history.iter().find(|h| h.user_agent == "Safari").unwrap();
+    assert_eq!(safari.access_id, access_ids[1]);
+    assert!(safari.is_active());
+
+    let history = cmd.login_history(&user, 1).await?;
+    assert_eq!(history.len(), 1);
+
+    Ok(())
+}
@@ -0,0 +1,126 @@
+use imkitchen_identity::notification_preferences::UpdateInput;
+use imkitchen_types::notification_preferences::{NotificationKind, QuietHours};
+use temp_dir::TempDir;
+use time::macros::datetime;
+
+mod helpers;
+
+#[tokio::test]
+async fn test_send_test_notifications() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_identity::Module::new(state);
+    let users = helpers::create_users(&cmd, vec!["john", "albert", "paul"]).await?;
+    let (john, albert, paul) = (&users[0], &users[1], &users[2]);
+
+    // Every kind is enabled by default.
+    cmd.notification_preferences.send_test(john).await?;
+    let preferences = cmd.notification_preferences.load(john).await?;
+    assert_eq!(
+        preferences.last_test_kinds,
+        vec![
+            NotificationKind::WeeklySummary,
+            NotificationKind::ShoppingReminder
+        ]
+    );
+
+    let resp = cmd.notification_preferences.send_test(john).await;
+    assert_eq!(
+        resp.unwrap_err().to_string(),
+        "Test notifications were sent recently. Please try again later."
+    );
+
+    cmd.notification_preferences
+        .update(
+            albert,
+            UpdateInput {
+                enabled: vec![NotificationKind::WeeklySummary],
+            },
+        )
+        .await?;
+    cmd.notification_preferences.send_test(albert).await?;
+    let preferences = cmd.notification_preferences.load(albert).await?;
+    assert_eq!(
+        preferences.last_test_kinds,
+        vec![NotificationKind::WeeklySummary]
+    );
+
+    cmd.notification_preferences
+        .update(paul, UpdateInput { enabled: vec![] })
+        .await?;
+    let resp = cmd.notification_preferences.send_test(paul).await;
+    assert_eq!(
+        resp.unwrap_err().to_string(),
+        "All notifications are disabled"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_quiet_hours_across_midnight() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_identity::Module::new(state);
+    let users = helpers::create_users(&cmd, vec!["john"]).await?;
+    let john = &users[0];
+
+    let resp = cmd
+        .notification_preferences
+        .set_quiet_hours(
+            john,
+            Some(QuietHours {
+                start: 600,
+                end: 600,
+            }),
+        )
+        .await;
+    assert_eq!(
+        resp.unwrap_err().to_string(),
+        "Quiet hours must start and end at different times"
+    );
+
+    // 22:00 to 07:00.
+    let quiet_hours = QuietHours {
+        start: 22 * 60,
+        end: 7 * 60,
+    };
+    assert!(quiet_hours.contains(23 * 60));
+    assert!(quiet_hours.contains(30));
+    assert!(!quiet_hours.contains(7 * 60));
+    assert!(!quiet_hours.contains(12 * 60));
+
+    cmd.notification_preferences
+        .set_quiet_hours(john, Some(quiet_hours))
+        .await?;
+    let preferences = cmd.notification_preferences.load(john).await?;
+    assert_eq!(preferences.quiet_hours, Some(quiet_hours));
+
+    // Paris is one hour ahead of UTC in winter: 23:30 UTC is 00:30 there.
+    let tz = "Europe/Paris";
+    let at = datetime!(2025-01-10 23:30:00 UTC);
+    assert_eq!(
+        preferences.next_allowed(at, tz, None),
+        datetime!(2025-01-11 06:00:00 UTC)
+    );
+
+    // The reminder would be too late by 07:00, it goes out at 22:00 instead.
+    assert_eq!(
+        preferences.next_allowed(at, tz, Some(datetime!(2025-01-11 03:00:00 UTC))),
+        datetime!(2025-01-10 21:00:00 UTC)
+    );
+
+    let at = datetime!(2025-01-10 12:00:00 UTC);
+    assert_eq!(preferences.next_allowed(at, tz, None), at);
+
+    cmd.notification_preferences
+        .set_quiet_hours(john, None)
+        .await?;
+    let preferences = cmd.notification_preferences.load(john).await?;
+    let at = datetime!(2025-01-10 23:30:00 UTC);
+    assert_eq!(preferences.next_allowed(at, tz, None), at);
+
+    Ok(())
+}
@@ -0,0 +1,207 @@
+use imkitchen_identity::passkey::{AuthenticateInput, CHALLENGE_LIMIT_PER_IP, RegisterInput};
+use ring::{
+    digest::{SHA256, digest},
+    rand::SystemRandom,
+    signature::{ECDSA_P256_SHA256_ASN1_SIGNING, EcdsaKeyPair, KeyPair},
+};
+use temp_dir::TempDir;
+
+mod helpers;
+
+const ORIGIN: &str = "https://imkitchen.localhost";
+const RP_ID: &str = "imkitchen.localhost";
+
+const P256_SPKI_PREFIX: [u8; 26] = [
+    0x30, 0x59, 0x30, 0x13, 0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01, 0x06, 0x08, 0x2a,
+    0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07, 0x03, 0x42, 0x00,
+];
+
+fn key_pair(rng: &SystemRandom) -> anyhow::Result<EcdsaKeyPair> {
+    let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, rng)
+        .map_err(|_| anyhow::anyhow!("generate_pkcs8"))?;
+
+    EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, pkcs8.as_ref(), rng)
+        .map_err(|_| anyhow::anyhow!("from_pkcs8"))
+}
+
+fn client_data(kind: &str, challenge: &str) -> Vec<u8> {
+    format!(r#"{{"type":"{kind}","challenge":"{challenge}","origin":"{ORIGIN}"}}"#).into_bytes()
+}
+
+fn authenticator_data() -> Vec<u8> {
+    let mut data = digest(&SHA256, RP_ID.as_bytes()).as_ref().to_vec();
+    data.push(0x05);
+    data.extend_from_slice(&[0, 0, 0, 1]);
+    data
+}
+
+fn sign(
+    key: &EcdsaKeyPair,
+    rng: &SystemRandom,
+    authenticator_data: &[u8],
+    client_data_json: &[u8],
+) -> anyhow::Result<Vec<u8>> {
+    let message = [
+        authenticator_data,
+        digest(&SHA256, client_data_json).as_ref(),
+    ]
+    .concat();
+
+    Ok(key
+        .sign(rng, &message)
+        .map_err(|_| anyhow::anyhow!("sign"))?
+        .as_ref()
+        .to_vec())
+}
+
+#[tokio::test]
+async fn test_passkey_register_and_authenticate() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_identity::Module::new(state);
+    let user_id = helpers::create_user(&cmd, "john.doe").await?;
+    let rng = SystemRandom::new();
+    let key = key_pair(&rng)?;
+
+    let options = cmd.passkey.start_registration(&user_id).await?;
+    let register_input = || RegisterInput {
+        challenge_id: options.id.to_owned(),
+        user_id: user_id.to_owned(),
+        credential_id: "credential-1".to_owned(),
+        name: "Laptop".to_owned(),
+        public_key: [&P256_SPKI_PREFIX[..], key.public_key().as_ref()].concat(),
+        client_data_json: client_data("webauthn.create", &options.challenge),
+        authenticator_data: authenticator_data(),
+        origin: ORIGIN.to_owned(),
+        rp_id: RP_ID.to_owned(),
+    };
+
+    cmd.passkey.register(register_input()).await?;
+
+    let user = cmd.load(&user_id).await?.unwrap();
+    assert_eq!(user.passkeys.len(), 1);
+    assert_eq!(user.passkeys[0].credential_id, "credential-1");
+    assert_eq!(user.passkeys[0].public_key, key.public_key().as_ref());
+
+    let resp = cmd.passkey.register(register_input()).await;
+    assert_eq!(resp.unwrap_err().to_string(), "challenge already used");
+
+    let options = cmd.passkey.start_authentication(None).await?;
+    let client_data_json = client_data("webauthn.get", &options.challenge);
+    let authenticator_data = authenticator_data();
+    let signature = sign(&key, &rng, &authenticator_data, &client_data_json)?;
+
+    let (id, access_id) = cmd
+        .passkey
+        .authenticate(AuthenticateInput {
+            challenge_id: options.id.to_owned(),
+            credential_id: "credential-1".to_owned(),
+            user_handle: user_id.to_owned(),
+            client_data_json,
+            authenticator_data,
+            signature,
+            origin: ORIGIN.to_owned(),
+            rp_id: RP_ID.to_owned(),
+            lang: "en".to_owned(),
+            timezone: "UTC".to_owned(),
+            user_agent: "".to_owned(),
+        })
+        .await?;
+
+    assert_eq!(id, user_id);
+    assert!(!access_id.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_passkey_authenticate_rejects_invalid_assertion() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_identity::Module::new(state);
+    let user_id = helpers::create_user(&cmd, "john.doe").await?;
+    let rng = SystemRandom::new();
+    let key = key_pair(&rng)?;
+    let other_key = key_pair(&rng)?;
+
+    let options = cmd.passkey.start_registration(&user_id).await?;
+    cmd.passkey
+        .register(RegisterInput {
+            challenge_id: options.id.to_owned(),
+            user_id: user_id.to_owned(),
+            credential_id: "credential-1".to_owned(),
+            name: "Laptop".to_owned(),
+            public_key: [&P256_SPKI_PREFIX[..], key.public_key().as_ref()].concat(),
+            client_data_json: client_data("webauthn.create", &options.challenge),
+            authenticator_data: authenticator_data(),
+            origin: ORIGIN.to_owned(),
+            rp_id: RP_ID.to_owned(),
+        })
+        .await?;
+
+    let options = cmd.passkey.start_authentication(None).await?;
+    let authenticate_input = |challenge: &str, key: &EcdsaKeyPair| -> anyhow::Result<_> {
+        let client_data_json = client_data("webauthn.get", challenge);
+        let signature = sign(key, &rng, &authenticator_data(), &client_data_json)?;
+
+        Ok(AuthenticateInput {
+            challenge_id: options.id.to_owned(),
+            credential_id: "credential-1".to_owned(),
+            user_handle: user_id.to_owned(),
+            client_data_json,
+            authenticator_data: authenticator_data(),
+            signature,
+            origin: ORIGIN.to_owned(),
+            rp_id: RP_ID.to_owned(),
+            lang: "en".to_owned(),
+            timezone: "UTC".to_owned(),
+            user_agent: "".to_owned(),
+        })
+    };
+
+    let resp = cmd
+        .passkey
+        .authenticate(authenticate_input(&options.challenge, &other_key)?)
+        .await;
+    assert_eq!(
+        resp.unwrap_err().to_string(),
+        "Invalid passkey. Please try again."
+    );
+
+    let resp = cmd
+        .passkey
+        .authenticate(authenticate_input("forged-challenge", &key)?)
+        .await;
+    assert_eq!(resp.unwrap_err().to_string(), "invalid challenge");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_passkey_login_challenges_are_rate_limited_per_ip() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_identity::Module::new(state);
+    let ip = || Some("203.0.113.7".to_owned());
+
+    for _ in 0..CHALLENGE_LIMIT_PER_IP {
+        cmd.passkey.start_authentication(ip()).await?;
+    }
+
+    let resp = cmd.passkey.start_authentication(ip()).await;
+    assert_eq!(
+        resp.unwrap_err().to_string(),
+        "Too many login attempts, please try again later"
+    );
+
+    // Other clients can still log in.
+    cmd.passkey
+        .start_authentication(Some("198.51.100.1".to_owned()))
+        .await?;
+    cmd.passkey.start_authentication(None).await?;
+
+    Ok(())
+}
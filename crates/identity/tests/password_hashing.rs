@@ -0,0 +1,68 @@
+use imkitchen_identity::{LoginInput, PasswordHashing, RegisterInput};
+use temp_dir::TempDir;
+
+mod helpers;
+
+#[test]
+fn test_hash_and_verify() -> anyhow::Result<()> {
+    let light = PasswordHashing {
+        memory_kib: 8 * 1024,
+        iterations: 1,
+        parallelism: 1,
+    };
+
+    for params in [light, PasswordHashing::default()] {
+        let hash = params.hash("my_password")?;
+        assert!(hash.starts_with("$argon2id$"));
+        assert!(hash.contains(&format!(
+            "m={},t={},p={}",
+            params.memory_kib, params.iterations, params.parallelism
+        )));
+        assert!(PasswordHashing::verify(&hash, "my_password")?);
+        assert!(!PasswordHashing::verify(&hash, "my_password2")?);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_login_after_params_change() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = helpers::setup_test_state(path).await?;
+    let cmd =
+        imkitchen_identity::Module::new(state.clone()).with_password_hashing(PasswordHashing {
+            memory_kib: 8 * 1024,
+            iterations: 1,
+            parallelism: 1,
+        });
+
+    let user = cmd
+        .register(RegisterInput {
+            email: "john.doe@imkitchen.localhost".to_owned(),
+            password: "my_password".to_owned(),
+            lang: "en".to_owned(),
+            timezone: "UTC".to_owned(),
+        })
+        .await?;
+
+    // Stronger parameters only apply to new hashes.
+    let cmd = imkitchen_identity::Module::new(state).with_password_hashing(PasswordHashing {
+        memory_kib: 32 * 1024,
+        iterations: 3,
+        parallelism: 2,
+    });
+
+    let (id, _) = cmd
+        .login(LoginInput {
+            email: "john.doe@imkitchen.localhost".to_owned(),
+            password: "my_password".to_owned(),
+            lang: "en".to_owned(),
+            timezone: "UTC".to_owned(),
+            user_agent: "".to_owned(),
+        })
+        .await?;
+    assert_eq!(id, user);
+
+    Ok(())
+}
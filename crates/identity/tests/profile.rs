@@ -1,5 +1,7 @@
 use imkitchen_identity::meal_preferences::UpdateInput;
-use imkitchen_types::recipe::DietaryRestriction;
+use imkitchen_types::meal_preferences::UserConstraints;
+use imkitchen_types::recipe::{Complexity, DietaryRestriction, Equipment, RecipeType};
+use std::collections::HashMap;
 use temp_dir::TempDir;
 
 mod helpers;
@@ -56,5 +58,338 @@ async fn test_update_meal_preferences() -> anyhow::Result<()> {
         vec![DietaryRestriction::GlutenFree, DietaryRestriction::Vegan,]
     );
 
+    let constraints = preferences.constraints()?;
+    assert_eq!(constraints.household_size, 4);
+    assert_eq!(
+        UserConstraints::from_json(&constraints.to_json()?)?,
+        constraints
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_equipment_capacity() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_identity::Module::new(state);
+    let users = helpers::create_users(&cmd, vec!["john"]).await?;
+    let john = users.first().unwrap();
+
+    cmd.meal_preferences
+        .set_equipment_capacity(
+            john,
+            HashMap::from([
+                (Equipment::Oven, 2),
+                (Equipment::Grill, 0),
+                (Equipment::Stovetop, 4),
+            ]),
+        )
+        .await?;
+
+    // The stovetop is left at its default.
+    let constraints = cmd.meal_preferences.load(john).await?.constraints()?;
+    assert_eq!(
+        constraints.equipment_capacity,
+        HashMap::from([(Equipment::Oven, 2), (Equipment::Grill, 0)])
+    );
+
+    let resp = cmd
+        .meal_preferences
+        .set_equipment_capacity(john, HashMap::from([(Equipment::Oven, 20)]))
+        .await;
+    assert_eq!(
+        resp.err().map(|e| e.to_string()),
+        Some("Equipment capacity must be at most 8".to_owned())
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_time_budget() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_identity::Module::new(state);
+    let users = helpers::create_users(&cmd, vec!["john"]).await?;
+    let john = users.first().unwrap();
+
+    let minutes = [None, Some(20), None, None, None, None, None];
+    cmd.meal_preferences.set_time_budget(john, minutes).await?;
+
+    let constraints = cmd.meal_preferences.load(john).await?.constraints()?;
+    assert_eq!(constraints.time_budget, minutes);
+
+    let resp = cmd
+        .meal_preferences
+        .set_time_budget(john, [Some(0), None, None, None, None, None, None])
+        .await;
+    assert_eq!(
+        resp.err().map(|e| e.to_string()),
+        Some("Time budget must be between 1 and 1440 minutes".to_owned())
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_community_suggestions() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_identity::Module::new(state);
+    let users = helpers::create_users(&cmd, vec!["john"]).await?;
+    let john = users.first().unwrap();
+
+    let constraints = cmd.meal_preferences.load(john).await?.constraints()?;
+    assert_eq!(constraints.community_suggestions, 0);
+
+    cmd.meal_preferences
+        .set_community_suggestions(john, 2)
+        .await?;
+
+    let constraints = cmd.meal_preferences.load(john).await?.constraints()?;
+    assert_eq!(constraints.community_suggestions, 2);
+
+    let resp = cmd
+        .meal_preferences
+        .set_community_suggestions(john, 8)
+        .await;
+    assert_eq!(
+        resp.err().map(|e| e.to_string()),
+        Some("Community suggestions must be at most 7 a week".to_owned())
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_min_days_since_use() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_identity::Module::new(state);
+    let users = helpers::create_users(&cmd, vec!["john"]).await?;
+    let john = users.first().unwrap();
+
+    let constraints = cmd.meal_preferences.load(john).await?.constraints()?;
+    assert_eq!(constraints.min_days_since_use, 14);
+
+    cmd.meal_preferences.set_min_days_since_use(john, 7).await?;
+
+    let constraints = cmd.meal_preferences.load(john).await?.constraints()?;
+    assert_eq!(constraints.min_days_since_use, 7);
+
+    let resp = cmd.meal_preferences.set_min_days_since_use(john, 61).await;
+    assert_eq!(
+        resp.err().map(|e| e.to_string()),
+        Some("Days between repeats must be at most 60".to_owned())
+    );
+
+    Ok(())
+}
+
+/// The stored set is what generation reads back, including the fields no
+/// other preference event carries, and later per-field changes keep it.
+#[tokio::test]
+async fn test_set_constraints() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_identity::Module::new(state);
+    let users = helpers::create_users(&cmd, vec!["john"]).await?;
+    let john = users.first().unwrap();
+
+    let constraints = UserConstraints {
+        household_size: 3,
+        rating_weight: 1.5,
+        scale_down: true,
+        randomness: 0.25,
+        allow_leftovers: true,
+        max_weekday_complexity: Complexity::Simple,
+        skipped_courses: vec![RecipeType::Dessert],
+        equipment_capacity: HashMap::from([(Equipment::Oven, 2)]),
+        ..Default::default()
+    };
+    cmd.meal_preferences
+        .set_constraints(john, constraints.clone())
+        .await?;
+
+    let preferences = cmd.meal_preferences.load(john).await?;
+    assert_eq!(preferences.constraints()?, constraints);
+    assert_eq!(preferences.household_size, 3);
+    assert_eq!(preferences.equipment_capacity, vec![(Equipment::Oven, 2)]);
+
+    cmd.meal_preferences
+        .set_community_suggestions(john, 2)
+        .await?;
+
+    let stored = cmd.meal_preferences.load(john).await?.constraints()?;
+    assert_eq!(
+        stored,
+        UserConstraints {
+            community_suggestions: 2,
+            ..constraints
+        }
+    );
+
+    let resp = cmd
+        .meal_preferences
+        .set_constraints(
+            john,
+            UserConstraints {
+                household_size: 0,
+                ..Default::default()
+            },
+        )
+        .await;
+    assert_eq!(
+        resp.err().map(|e| e.to_string()),
+        Some("Household size must be at least 1".to_owned())
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_max_complexity() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_identity::Module::new(state);
+    let users = helpers::create_users(&cmd, vec!["john"]).await?;
+    let john = users.first().unwrap();
+
+    cmd.meal_preferences
+        .set_max_complexity(john, Complexity::Simple, Complexity::Moderate)
+        .await?;
+
+    let constraints = cmd.meal_preferences.load(john).await?.constraints()?;
+    assert_eq!(constraints.max_weekday_complexity, Complexity::Simple);
+    assert_eq!(constraints.max_weekend_complexity, Complexity::Moderate);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_skipped_courses() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_identity::Module::new(state);
+    let users = helpers::create_users(&cmd, vec!["john"]).await?;
+    let john = users.first().unwrap();
+
+    cmd.meal_preferences
+        .set_skipped_courses(
+            john,
+            vec![
+                RecipeType::Dessert,
+                RecipeType::Appetizer,
+                RecipeType::Dessert,
+            ],
+        )
+        .await?;
+
+    let constraints = cmd.meal_preferences.load(john).await?.constraints()?;
+    assert_eq!(
+        constraints.skipped_courses,
+        vec![RecipeType::Appetizer, RecipeType::Dessert]
+    );
+
+    let resp = cmd
+        .meal_preferences
+        .set_skipped_courses(john, vec![RecipeType::MainCourse])
+        .await;
+    assert_eq!(
+        resp.err().map(|e| e.to_string()),
+        Some("The main course can't be skipped".to_owned())
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_allow_leftovers() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_identity::Module::new(state);
+    let users = helpers::create_users(&cmd, vec!["john"]).await?;
+    let john = users.first().unwrap();
+
+    let constraints = cmd.meal_preferences.load(john).await?.constraints()?;
+    assert!(!constraints.allow_leftovers);
+
+    cmd.meal_preferences.set_allow_leftovers(john, true).await?;
+
+    let constraints = cmd.meal_preferences.load(john).await?.constraints()?;
+    assert!(constraints.allow_leftovers);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_rating_weight() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_identity::Module::new(state);
+    let users = helpers::create_users(&cmd, vec!["john"]).await?;
+    let john = users.first().unwrap();
+
+    cmd.meal_preferences.set_rating_weight(john, 1.5).await?;
+
+    let constraints = cmd.meal_preferences.load(john).await?.constraints()?;
+    assert_eq!(constraints.rating_weight, 1.5);
+
+    let resp = cmd.meal_preferences.set_rating_weight(john, -1.0).await;
+    assert_eq!(
+        resp.err().map(|e| e.to_string()),
+        Some("Rating weight must be between 0 and 2".to_owned())
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_randomness() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_identity::Module::new(state);
+    let users = helpers::create_users(&cmd, vec!["john"]).await?;
+    let john = users.first().unwrap();
+
+    cmd.meal_preferences.set_randomness(john, 0.25).await?;
+
+    let constraints = cmd.meal_preferences.load(john).await?.constraints()?;
+    assert_eq!(constraints.randomness, 0.25);
+
+    let resp = cmd.meal_preferences.set_randomness(john, 1.5).await;
+    assert_eq!(
+        resp.err().map(|e| e.to_string()),
+        Some("Randomness must be between 0 and 1".to_owned())
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_scale_down() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.child("db.sqlite3");
+    let state = helpers::setup_test_state(path).await?;
+    let cmd = imkitchen_identity::Module::new(state);
+    let users = helpers::create_users(&cmd, vec!["john"]).await?;
+    let john = users.first().unwrap();
+
+    cmd.meal_preferences.set_scale_down(john, true).await?;
+
+    let constraints = cmd.meal_preferences.load(john).await?.constraints()?;
+    assert!(constraints.scale_down);
+
     Ok(())
 }
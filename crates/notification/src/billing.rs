@@ -9,7 +9,7 @@ use sqlx::SqlitePool;
 use time::OffsetDateTime;
 
 use crate::{
-    EmailService, recipient,
+    EmailService, delivery, recipient,
     template::{Template, filters},
 };
 
@@ -103,7 +103,16 @@ async fn handle_invoice_created<E: Executor>(
         invoice_url,
         year,
     });
-    if let Err(err) = service.send(&email, subject, html, plain).await {
+    let delivered = delivery::once(
+        &write_db,
+        "email.invoice_created",
+        &event.aggregate_id,
+        &event.id.to_string(),
+        || service.send(&email, subject, html, plain),
+    )
+    .await;
+
+    if let Err(err) = delivered {
         tracing::warn!(error = ?err, "handle_invoice_created.send");
     }
 
@@ -143,7 +152,16 @@ async fn handle_subscription_cancelled<E: Executor>(
     });
 
     let subject = rust_i18n::t!("Subscription Cancelled", locale = &recipient.lang).to_string();
-    if let Err(err) = service.send(&recipient.email, subject, html, plain).await {
+    let delivered = delivery::once(
+        &write_db,
+        "email.subscription_cancelled",
+        &event.aggregate_id,
+        &event.id.to_string(),
+        || service.send(&recipient.email, subject, html, plain),
+    )
+    .await;
+
+    if let Err(err) = delivered {
         tracing::warn!(error = ?err, "handle_subscription_cancelled.send");
     }
 
@@ -3,12 +3,41 @@ use evento::{
     metadata::Event,
     subscription::{Context, SubscriptionBuilder},
 };
-use imkitchen_types::contact::FormSubmitted;
+use imkitchen_types::contact::{AcknowledgementRequested, FormSubmitted, Status};
+use sqlx::SqlitePool;
+use time::OffsetDateTime;
 
-use crate::EmailService;
+use crate::{
+    EmailService, delivery,
+    template::{Template, filters},
+};
 
 pub fn subscription<E: Executor>() -> SubscriptionBuilder<E> {
-    SubscriptionBuilder::new("notification-contact").handler(handle_form_submitted())
+    SubscriptionBuilder::new("notification-contact")
+        .handler(handle_form_submitted())
+        .handler(handle_acknowledgement_requested())
+}
+
+#[derive(askama::Template)]
+#[template(path = "contact-acknowledgement.html")]
+pub struct ContactAcknowledgementHtmlTemplate {
+    pub email: String,
+    pub name: String,
+    pub subject: String,
+    pub summary: String,
+    pub year: i32,
+    pub lang: String,
+}
+
+#[derive(askama::Template)]
+#[template(path = "contact-acknowledgement.txt")]
+pub struct ContactAcknowledgementPlainTemplate {
+    pub email: String,
+    pub name: String,
+    pub subject: String,
+    pub summary: String,
+    pub year: i32,
+    pub lang: String,
 }
 
 #[evento::subscription]
@@ -16,21 +45,86 @@ async fn handle_form_submitted<E: Executor>(
     context: &Context<'_, E>,
     event: Event<FormSubmitted>,
 ) -> anyhow::Result<()> {
+    // FlaggedAsSpam is committed with the submission, the contact is
+    // already flagged when this runs.
+    let contact = imkitchen_core::contact::create_projection()
+        .load(&event.aggregate_id)
+        .execute(context.executor)
+        .await?;
+    if contact.is_some_and(|contact| contact.status == Status::Flagged) {
+        return Ok(());
+    }
+
     let service = context.extract::<EmailService>();
-    service
-        .send_plain(
-            &event.data.to,
-            event.data.subject.to_string(),
-            format!(
-                r#"
+    let (_, write_db) = context.extract::<(SqlitePool, SqlitePool)>();
+    delivery::once(
+        &write_db,
+        "email.contact_form",
+        &event.aggregate_id,
+        &event.id.to_string(),
+        || {
+            service.send_plain(
+                &event.data.to,
+                event.data.subject.to_string(),
+                format!(
+                    r#"
 {} <{}>,
 
 {}
             "#,
-                event.data.name, event.data.email, event.data.message
-            ),
-        )
-        .await?;
+                    event.data.name, event.data.email, event.data.message
+                ),
+            )
+        },
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[evento::subscription]
+async fn handle_acknowledgement_requested<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<AcknowledgementRequested>,
+) -> anyhow::Result<()> {
+    let service = context.extract::<EmailService>();
+    let lang = event.data.lang.to_owned();
+    let template = Template::new(&lang);
+    let year = OffsetDateTime::from_unix_timestamp(event.timestamp.try_into()?)?.year();
+    let subject = rust_i18n::t!(event.data.subject.to_string(), locale = lang).to_string();
+
+    let html = template.to_string(ContactAcknowledgementHtmlTemplate {
+        email: event.data.email.to_owned(),
+        name: event.data.name.to_owned(),
+        subject: subject.to_owned(),
+        summary: event.data.summary.to_owned(),
+        year,
+        lang: lang.to_owned(),
+    });
+
+    let plain = template.to_string(ContactAcknowledgementPlainTemplate {
+        email: event.data.email.to_owned(),
+        name: event.data.name.to_owned(),
+        subject,
+        summary: event.data.summary.to_owned(),
+        year,
+        lang: lang.to_owned(),
+    });
+
+    let title = rust_i18n::t!("We received your message", locale = lang).to_string();
+    let (_, write_db) = context.extract::<(SqlitePool, SqlitePool)>();
+    let delivered = delivery::once(
+        &write_db,
+        "email.contact_acknowledgement",
+        &event.aggregate_id,
+        &event.id.to_string(),
+        || service.send(event.data.email, title, html, plain),
+    )
+    .await;
+
+    if let Err(err) = delivered {
+        tracing::warn!(error = ?err, "handle_acknowledgement_requested.send");
+    }
 
     Ok(())
 }
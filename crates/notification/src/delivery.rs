@@ -0,0 +1,84 @@
+//! Idempotency store for external effects (emails) triggered by events.
+//!
+//! Subscriptions may see the same event again after a crash or a cursor
+//! reset. Each delivery is claimed in `notification_delivery` first, keyed by
+//! effect type, aggregate id and event id, so a replay finds the claim and
+//! skips the effect.
+
+use std::future::Future;
+
+use imkitchen_db::notification_delivery::NotificationDelivery;
+use sea_query::{Expr, ExprTrait, OnConflict, Query, SqliteQueryBuilder};
+use sea_query_sqlx::SqlxBinder;
+use sqlx::SqlitePool;
+use time::OffsetDateTime;
+
+/// Runs `deliver` unless this effect was already delivered for the event.
+/// A failed delivery releases its claim so a retry can send it again.
+/// Returns whether `deliver` ran.
+pub async fn once<F, Fut>(
+    pool: &SqlitePool,
+    effect_type: &str,
+    aggregate_id: &str,
+    event_id: &str,
+    deliver: F,
+) -> anyhow::Result<bool>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = anyhow::Result<()>>,
+{
+    let statement = Query::insert()
+        .into_table(NotificationDelivery::Table)
+        .columns([
+            NotificationDelivery::EffectType,
+            NotificationDelivery::AggregateId,
+            NotificationDelivery::EventId,
+            NotificationDelivery::CreatedAt,
+        ])
+        .values_panic([
+            effect_type.into(),
+            aggregate_id.into(),
+            event_id.into(),
+            OffsetDateTime::now_utc().unix_timestamp().into(),
+        ])
+        .on_conflict(
+            OnConflict::columns([
+                NotificationDelivery::EffectType,
+                NotificationDelivery::AggregateId,
+                NotificationDelivery::EventId,
+            ])
+            .do_nothing()
+            .to_owned(),
+        )
+        .to_owned();
+
+    let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+    let claimed = sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+        .execute(pool)
+        .await?
+        .rows_affected()
+        == 1;
+
+    if !claimed {
+        tracing::info!(effect_type, aggregate_id, event_id, "already delivered");
+        return Ok(false);
+    }
+
+    if let Err(err) = deliver().await {
+        let statement = Query::delete()
+            .from_table(NotificationDelivery::Table)
+            .and_where(Expr::col(NotificationDelivery::EffectType).eq(effect_type))
+            .and_where(Expr::col(NotificationDelivery::AggregateId).eq(aggregate_id))
+            .and_where(Expr::col(NotificationDelivery::EventId).eq(event_id))
+            .to_owned();
+
+        let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+        sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+            .execute(pool)
+            .await?;
+
+        return Err(err);
+    }
+
+    Ok(true)
+}
@@ -1,9 +1,14 @@
 pub mod billing;
 pub mod contact;
+pub mod delivery;
+pub mod preferences;
+pub mod push;
 pub mod recipient;
+pub mod reminder;
 mod service;
 pub(crate) mod template;
 pub mod user;
+pub mod web_push;
 
 pub use service::*;
 
@@ -0,0 +1,156 @@
+use evento::{
+    Executor,
+    metadata::Event,
+    subscription::{Context, SubscriptionBuilder},
+};
+use imkitchen_types::notification_preferences::{NotificationKind, TestRequested};
+use imkitchen_types::recipe::IngredientUnitFormat;
+use sqlx::SqlitePool;
+use time::{Duration, OffsetDateTime};
+
+use crate::recipient::{self, Recipient};
+use crate::{EmailService, delivery};
+
+pub fn subscription<E: Executor + Clone>() -> SubscriptionBuilder<E> {
+    SubscriptionBuilder::new("notification-preferences").handler(handle_test_requested())
+}
+
+/// Subject and plain text of the test email for `kind`, filled with what the
+/// real notification would send today: the coming week's meals or what is
+/// left on the shopping list.
+pub async fn sample<E: Executor + Clone>(
+    state: &imkitchen_core::State<E>,
+    kind: &NotificationKind,
+    recipient: &Recipient,
+    app_url: &str,
+) -> anyhow::Result<(String, String)> {
+    let lang = recipient.lang.as_str();
+    let mut lines = vec![];
+
+    match kind {
+        NotificationKind::WeeklySummary => {
+            let today = imkitchen_core::mealplan::now(&recipient.timezone);
+            let slots = imkitchen_core::mealplan::Module::new(state.clone())
+                .range(&recipient.id, today, today + Duration::days(6))
+                .await?;
+
+            for slot in slots {
+                let date = imkitchen_core::mealplan::to_timezone(
+                    OffsetDateTime::from_unix_timestamp(slot.day as i64)?,
+                    &recipient.timezone,
+                );
+                lines.push(format!(
+                    "- {} {}: {}",
+                    rust_i18n::t!(date.weekday().to_string(), locale = lang),
+                    date.day(),
+                    slot.main_course.name
+                ));
+            }
+
+            let intro = if lines.is_empty() {
+                rust_i18n::t!("Nothing is planned for the coming week yet.", locale = lang)
+            } else {
+                rust_i18n::t!(
+                    "Here is what is planned for the coming week:",
+                    locale = lang
+                )
+            };
+
+            Ok((
+                rust_i18n::t!("Your week in the kitchen (test)", locale = lang).to_string(),
+                plain(&intro, &lines, &format!("{app_url}/menu")),
+            ))
+        }
+        NotificationKind::ShoppingReminder => {
+            let household_size = imkitchen_identity::Module::new(state.clone())
+                .meal_preferences
+                .load(&recipient.id)
+                .await?
+                .household_size;
+            let shopping = imkitchen_core::shopping::Module::new(state.clone())
+                .state(&recipient.id, household_size)
+                .await?;
+
+            for ingredient in shopping.ingredients.iter() {
+                let key = ingredient.key();
+                if shopping.checked.contains(&key) || shopping.owned.contains(&key) {
+                    continue;
+                }
+
+                let quantity = shopping.to_buy(ingredient).unwrap_or(ingredient.quantity);
+                lines.push(format!(
+                    "- {}: {}",
+                    ingredient.name,
+                    ingredient.unit.format(quantity)
+                ));
+            }
+
+            for name in shopping.manual_items.iter() {
+                if !shopping
+                    .checked
+                    .contains(&imkitchen_core::shopping::manual_item_key(name))
+                {
+                    lines.push(format!("- {name}"));
+                }
+            }
+
+            let intro = if lines.is_empty() {
+                rust_i18n::t!("Your shopping list is empty.", locale = lang)
+            } else {
+                rust_i18n::t!("Here is what is left on your shopping list:", locale = lang)
+            };
+
+            Ok((
+                rust_i18n::t!("Shopping reminder (test)", locale = lang).to_string(),
+                plain(&intro, &lines, &format!("{app_url}/groceries")),
+            ))
+        }
+    }
+}
+
+fn plain(intro: &str, lines: &[String], url: &str) -> String {
+    if lines.is_empty() {
+        return format!("{intro}\n\n{url}");
+    }
+
+    format!("{intro}\n\n{}\n\n{url}", lines.join("\n"))
+}
+
+#[evento::subscription]
+async fn handle_test_requested<E: Executor + Clone>(
+    context: &Context<'_, E>,
+    event: Event<TestRequested>,
+) -> anyhow::Result<()> {
+    let service = context.extract::<EmailService>();
+    let user_id = event.aggregate_id.to_owned();
+    let (read_db, write_db) = context.extract::<(SqlitePool, SqlitePool)>();
+    let Some(recipient) = recipient::load(context.executor, &read_db, &write_db, &user_id).await?
+    else {
+        tracing::warn!(user_id = %user_id, "handle_test_requested: recipient not found");
+        return Ok(());
+    };
+
+    let state = imkitchen_core::State {
+        executor: context.executor.clone(),
+        read_db,
+        write_db: write_db.clone(),
+    };
+
+    for kind in &event.data.kinds {
+        let (subject, plain) = sample(&state, kind, &recipient, &service.app_url).await?;
+        let delivered = delivery::once(
+            &write_db,
+            &format!("email.test.{kind}"),
+            &user_id,
+            &event.id.to_string(),
+            || service.send_plain(&recipient.email, subject, plain),
+        )
+        .await;
+
+        if let Err(err) = delivered {
+            tracing::warn!(error = ?err, kind = %kind, "handle_test_requested.send");
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,318 @@
+//! Web push delivery to many subscriptions at once.
+//!
+//! Encrypting and posting the payload is left to a [`PushSender`], this
+//! module decides what to do with the status the push service answers:
+//! 2xx is delivered, 404/410 expires the subscription, 429/5xx and transport
+//! errors are retried with exponential backoff, anything else is a failure.
+//! [`dispatch`] sends to every device of a user at most once per
+//! notification, outside their quiet hours.
+
+use std::future::Future;
+use std::time::Duration;
+
+use evento::{
+    Executor,
+    metadata::Event,
+    subscription::{Context, SubscriptionBuilder},
+};
+use futures::StreamExt;
+use imkitchen_db::notification_push_subscription::NotificationPushSubscription;
+use imkitchen_identity::push_subscription::{self, PushSubscription};
+use imkitchen_identity::types::push_subscription::{Expired, Subscribed, Unsubscribed};
+use sea_query::{Expr, ExprTrait, OnConflict, Query, SqliteQueryBuilder};
+use sea_query_sqlx::SqlxBinder;
+use sqlx::SqlitePool;
+use time::OffsetDateTime;
+
+use crate::{delivery, recipient};
+
+pub trait PushSender {
+    /// Posts the payload to the subscription endpoint and returns the HTTP
+    /// status of the push service. `Err` is a transport failure.
+    fn send(
+        &self,
+        subscription: &PushSubscription,
+        payload: &[u8],
+    ) -> impl Future<Output = anyhow::Result<u16>> + Send;
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct BatchOptions {
+    /// Sends in flight at the same time.
+    pub concurrency: usize,
+    pub max_attempts: u8,
+    /// Wait before the first retry, doubled for each next one.
+    pub backoff: Duration,
+}
+
+impl Default for BatchOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: 16,
+            max_attempts: 3,
+            backoff: Duration::from_millis(500),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PushOutcome {
+    Delivered,
+    /// Removed after a 404 or 410, or already expired before the batch.
+    Expired,
+    Failed(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PushResult {
+    pub subscription_id: String,
+    pub outcome: PushOutcome,
+    pub attempts: u8,
+}
+
+/// Sends the payload to every subscription, at most
+/// `options.concurrency` at a time. Results come back in the order of
+/// `subscriptions`.
+pub async fn send_batch<E: Executor, S: PushSender>(
+    module: &push_subscription::Module<E>,
+    sender: &S,
+    subscriptions: &[PushSubscription],
+    payload: &[u8],
+    options: BatchOptions,
+) -> Vec<PushResult> {
+    futures::stream::iter(subscriptions)
+        .map(|subscription| send_one(module, sender, subscription, payload, options))
+        .buffered(options.concurrency.max(1))
+        .collect()
+        .await
+}
+
+async fn send_one<E: Executor, S: PushSender>(
+    module: &push_subscription::Module<E>,
+    sender: &S,
+    subscription: &PushSubscription,
+    payload: &[u8],
+    options: BatchOptions,
+) -> PushResult {
+    let mut result = PushResult {
+        subscription_id: subscription.id.to_owned(),
+        outcome: PushOutcome::Expired,
+        attempts: 0,
+    };
+
+    if subscription.expired {
+        return result;
+    }
+
+    loop {
+        result.attempts += 1;
+
+        let error = match sender.send(subscription, payload).await {
+            Ok(status) if (200..300).contains(&status) => {
+                result.outcome = PushOutcome::Delivered;
+                return result;
+            }
+            Ok(status @ (404 | 410)) => {
+                result.outcome = match module.expire(&subscription.id, status).await {
+                    Ok(_) => PushOutcome::Expired,
+                    Err(err) => PushOutcome::Failed(err.to_string()),
+                };
+                return result;
+            }
+            Ok(status) if status == 429 || status >= 500 => format!("status {status}"),
+            Ok(status) => {
+                result.outcome = PushOutcome::Failed(format!("status {status}"));
+                return result;
+            }
+            Err(err) => err.to_string(),
+        };
+
+        if result.attempts >= options.max_attempts {
+            tracing::warn!(subscription_id = %subscription.id, error = %error, "push.send_batch");
+            result.outcome = PushOutcome::Failed(error);
+            return result;
+        }
+
+        tokio::time::sleep(options.backoff * 2u32.pow(u32::from(result.attempts) - 1)).await;
+    }
+}
+
+/// A notification for every device of a user.
+#[derive(Debug, Clone, Copy)]
+pub struct Push<'a> {
+    pub user_id: &'a str,
+    /// The notification is sent once per effect type and key.
+    pub effect_type: &'a str,
+    pub key: &'a str,
+    pub payload: &'a [u8],
+    /// When the notification stops being useful. Inside quiet hours that
+    /// end after it, it goes out anyway.
+    pub deadline: Option<OffsetDateTime>,
+}
+
+/// Sends the notification to every device of the user, once per key: a
+/// notification dispatched again with the same key is skipped. Inside the
+/// user's quiet hours nothing is sent, unless the deadline comes before
+/// they end. When no device got it and some failed, the claim is released
+/// so a next dispatch retries. Returns whether it was sent.
+pub async fn dispatch<E: Executor, S: PushSender>(
+    identity: &imkitchen_identity::Module<E>,
+    sender: &S,
+    push: Push<'_>,
+    now: OffsetDateTime,
+    options: BatchOptions,
+) -> anyhow::Result<bool> {
+    let timezone = recipient::load(
+        &identity.executor,
+        &identity.read_db,
+        &identity.write_db,
+        push.user_id,
+    )
+    .await?
+    .map(|recipient| recipient.timezone)
+    .unwrap_or_else(|| "UTC".to_owned());
+
+    let preferences = identity.notification_preferences.load(push.user_id).await?;
+    if preferences.next_allowed(now, &timezone, push.deadline) > now {
+        return Ok(false);
+    }
+
+    let module = &identity.push_subscription;
+    let subscriptions = subscriptions(module, push.user_id).await?;
+    if subscriptions.is_empty() {
+        return Ok(false);
+    }
+
+    delivery::once(
+        &module.write_db,
+        push.effect_type,
+        push.user_id,
+        push.key,
+        || async {
+            let results = send_batch(module, sender, &subscriptions, push.payload, options).await;
+            if results
+                .iter()
+                .any(|result| result.outcome == PushOutcome::Delivered)
+            {
+                return Ok(());
+            }
+
+            match results.into_iter().find_map(|result| match result.outcome {
+                PushOutcome::Failed(err) => Some(err),
+                _ => None,
+            }) {
+                Some(err) => anyhow::bail!(err),
+                None => Ok(()),
+            }
+        },
+    )
+    .await
+}
+
+/// Subscriptions of the user that haven't expired.
+pub async fn subscriptions<E: Executor>(
+    module: &push_subscription::Module<E>,
+    user_id: &str,
+) -> anyhow::Result<Vec<PushSubscription>> {
+    let statement = Query::select()
+        .column(NotificationPushSubscription::Id)
+        .from(NotificationPushSubscription::Table)
+        .and_where(Expr::col(NotificationPushSubscription::UserId).eq(user_id))
+        .to_owned();
+
+    let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+    let ids = sqlx::query_as_with::<_, (String,), _>(sqlx::AssertSqlSafe(sql), values)
+        .fetch_all(&module.read_db)
+        .await?;
+
+    let mut subscriptions = vec![];
+    for (id,) in ids {
+        let Some(subscription) = module.load(id).await? else {
+            continue;
+        };
+
+        if !subscription.expired {
+            subscriptions.push(subscription);
+        }
+    }
+
+    Ok(subscriptions)
+}
+
+/// Keeps the subscriptions of each user in `notification_push_subscription`
+/// for [`subscriptions`].
+pub fn subscription<E: Executor>() -> SubscriptionBuilder<E> {
+    SubscriptionBuilder::new("notification-push")
+        .handler(handle_subscribed())
+        .handler(handle_expired())
+        .handler(handle_unsubscribed())
+}
+
+#[evento::subscription]
+async fn handle_subscribed<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<Subscribed>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<SqlitePool>();
+    let statement = Query::insert()
+        .into_table(NotificationPushSubscription::Table)
+        .columns([
+            NotificationPushSubscription::Id,
+            NotificationPushSubscription::UserId,
+        ])
+        .values_panic([
+            event.aggregate_id.to_owned().into(),
+            event.data.user_id.into(),
+        ])
+        .on_conflict(
+            OnConflict::column(NotificationPushSubscription::Id)
+                .do_nothing()
+                .to_owned(),
+        )
+        .to_owned();
+
+    let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+    sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+        .execute(&pool)
+        .await?;
+
+    Ok(())
+}
+
+#[evento::subscription]
+async fn handle_expired<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<Expired>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<SqlitePool>();
+    let statement = Query::delete()
+        .from_table(NotificationPushSubscription::Table)
+        .and_where(Expr::col(NotificationPushSubscription::Id).eq(&event.aggregate_id))
+        .to_owned();
+
+    let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+    sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+        .execute(&pool)
+        .await?;
+
+    Ok(())
+}
+
+#[evento::subscription]
+async fn handle_unsubscribed<E: Executor>(
+    context: &Context<'_, E>,
+    event: Event<Unsubscribed>,
+) -> anyhow::Result<()> {
+    let pool = context.extract::<SqlitePool>();
+    let statement = Query::delete()
+        .from_table(NotificationPushSubscription::Table)
+        .and_where(Expr::col(NotificationPushSubscription::Id).eq(&event.aggregate_id))
+        .to_owned();
+
+    let (sql, values) = statement.build_sqlx(SqliteQueryBuilder);
+    sqlx::query_with(sqlx::AssertSqlSafe(sql), values)
+        .execute(&pool)
+        .await?;
+
+    Ok(())
+}
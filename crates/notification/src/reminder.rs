@@ -0,0 +1,167 @@
+//! Advance prep reminders pushed to the user's devices ahead of the meal.
+
+use evento::Executor;
+use imkitchen_core::mealplan::slot::PrepReminder;
+use sqlx::SqlitePool;
+use time::OffsetDateTime;
+use tokio_cron_scheduler::{Job, JobScheduler, JobSchedulerError};
+
+use crate::push::{self, BatchOptions, Push, PushSender};
+use crate::recipient;
+
+/// Reminders are looked up this far back, so one held back by quiet hours
+/// or skipped by a missed run still goes out. [`push::dispatch`] sends each
+/// only once.
+const LOOKBACK_SECS: u64 = 24 * 3600;
+
+/// Characters of the prep instructions shown in the notification.
+const MAX_SUMMARY: usize = 120;
+
+pub async fn scheduler<E, S>(
+    evento: &E,
+    r_pool: &SqlitePool,
+    w_pool: &SqlitePool,
+    sender: &S,
+) -> Result<JobScheduler, JobSchedulerError>
+where
+    E: Executor + Clone,
+    S: PushSender + Clone + Send + Sync + 'static,
+{
+    let sched = JobScheduler::new().await?;
+    let sender = sender.clone();
+
+    let state = imkitchen_core::State {
+        executor: evento.clone(),
+        read_db: r_pool.clone(),
+        write_db: w_pool.clone(),
+    };
+
+    // Send advance prep reminders
+    sched
+        .add(Job::new_async("0 * * * * *", move |uuid, mut l| {
+            let sender = sender.clone();
+            let state = state.clone();
+
+            Box::pin(async move {
+                let now = OffsetDateTime::now_utc().unix_timestamp() as u64;
+                if let Err(err) = send_prep_reminders(&state, &sender, now).await {
+                    tracing::error!(err = %err, "failed to send advance prep reminders");
+                }
+
+                if let Err(err) = l.next_tick_for_job(uuid).await {
+                    tracing::error!(err = %err, "failed to get next tick for advance prep reminders");
+                }
+            })
+        })?)
+        .await?;
+
+    Ok(sched)
+}
+
+/// Pushes the advance prep reminders due at `now` (unix time) and returns
+/// how many were sent. A reminder due inside the user's quiet hours waits
+/// for them to end, or goes out as they begin when the meal's day starts
+/// first.
+pub async fn send_prep_reminders<E: Executor + Clone, S: PushSender>(
+    state: &imkitchen_core::State<E>,
+    sender: &S,
+    now: u64,
+) -> anyhow::Result<usize> {
+    let mealplan = imkitchen_core::mealplan::Module::new(state.clone());
+    let identity = imkitchen_identity::Module::new(state.clone());
+    let reminders = mealplan
+        .prep_reminders_between(now.saturating_sub(LOOKBACK_SECS), now + 1)
+        .await?;
+    let now = OffsetDateTime::from_unix_timestamp(now as i64)?;
+
+    let mut sent = 0;
+    for reminder in reminders {
+        let deadline = OffsetDateTime::from_unix_timestamp(reminder.day as i64)?;
+        if deadline <= now {
+            continue;
+        }
+
+        let recipient = recipient::load(
+            &state.executor,
+            &state.read_db,
+            &state.write_db,
+            &reminder.user_id,
+        )
+        .await?;
+        let (lang, timezone) = recipient
+            .map(|r| (r.lang, r.timezone))
+            .unwrap_or_else(|| ("en".to_owned(), "UTC".to_owned()));
+
+        let remind_at = OffsetDateTime::from_unix_timestamp(reminder.remind_at as i64)?;
+        let preferences = identity
+            .notification_preferences
+            .load(&reminder.user_id)
+            .await?;
+        if preferences.next_allowed(remind_at, &timezone, Some(deadline)) > now {
+            continue;
+        }
+
+        let payload = prep_payload(&reminder, &lang)?;
+        let delivered = push::dispatch(
+            &identity,
+            sender,
+            Push {
+                user_id: &reminder.user_id,
+                effect_type: &format!("push.prep.{}", reminder.date),
+                key: &reminder.recipe.id,
+                payload: &payload,
+                deadline: Some(deadline),
+            },
+            now,
+            BatchOptions::default(),
+        )
+        .await;
+
+        match delivered {
+            Ok(true) => sent += 1,
+            Ok(false) => {}
+            Err(err) => {
+                tracing::warn!(error = ?err, user_id = %reminder.user_id, "send_prep_reminders.dispatch");
+            }
+        }
+    }
+
+    Ok(sent)
+}
+
+/// Notification naming the recipe and summarizing its prep, in the format
+/// the service worker shows.
+pub fn prep_payload(reminder: &PrepReminder, lang: &str) -> anyhow::Result<Vec<u8>> {
+    let date = reminder.date;
+    let title = rust_i18n::t!(
+        "Prep for %{name}",
+        locale = lang,
+        name = reminder.recipe.name
+    );
+
+    Ok(serde_json::to_vec(&serde_json::json!({
+        "title": title,
+        "body": summary(&reminder.recipe.advance_prep),
+        "data": {
+            "url": format!("/kitchen/{}-{:02}-{:02}", date / 10000, date / 100 % 100, date % 100),
+        },
+    }))?)
+}
+
+/// First line of the prep instructions, cut to fit a notification.
+fn summary(advance_prep: &str) -> String {
+    let line = advance_prep
+        .trim()
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .trim();
+    if line.chars().count() <= MAX_SUMMARY {
+        return line.to_owned();
+    }
+
+    let mut summary = line.chars().take(MAX_SUMMARY - 1).collect::<String>();
+    summary.push('…');
+
+    summary
+}
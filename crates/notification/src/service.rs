@@ -1,11 +1,13 @@
 //! Email notification service using lettre
 
+use imkitchen_types::contact::Subject;
 use lettre::{
     Message, SmtpTransport, Transport,
     message::{MultiPart, header},
     transport::smtp::authentication::Credentials,
 };
 use serde::Deserialize;
+use std::collections::HashMap;
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct EmailConfig {
@@ -15,6 +17,22 @@ pub struct EmailConfig {
     pub smtp_password: String,
     pub from_address: String,
     pub contact_address: String,
+    /// Admin address per contact subject, e.g. `billing_question =
+    /// "billing@imkitchen.app"`. Subjects not listed go to `contact_address`.
+    #[serde(default)]
+    pub contact_routes: HashMap<String, String>,
+}
+
+impl EmailConfig {
+    /// Where submissions about `subject` are sent. Route keys may be written
+    /// in snake_case or as the variant name.
+    pub fn contact_address_for(&self, subject: &Subject) -> &str {
+        self.contact_routes
+            .iter()
+            .find(|(key, _)| key.replace('_', "").eq_ignore_ascii_case(subject.as_ref()))
+            .map(|(_, address)| address.as_str())
+            .unwrap_or(&self.contact_address)
+    }
 }
 
 /// Email service for sending notifications
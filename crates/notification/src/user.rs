@@ -4,10 +4,11 @@ use evento::{
     subscription::{Context, SubscriptionBuilder},
 };
 use imkitchen_identity::types::password::ResetRequested;
+use sqlx::SqlitePool;
 use time::OffsetDateTime;
 
 use crate::{
-    EmailService,
+    EmailService, delivery,
     template::{Template, filters},
 };
 
@@ -62,7 +63,17 @@ async fn handle_reset_requested<E: Executor>(
     });
 
     let subject = rust_i18n::t!("Reset Your Pasuword", locales = event.data.lang).to_string();
-    if let Err(err) = service.send(event.data.email, subject, html, plain).await {
+    let (_, write_db) = context.extract::<(SqlitePool, SqlitePool)>();
+    let delivered = delivery::once(
+        &write_db,
+        "email.reset_password",
+        &event.aggregate_id,
+        &event.id.to_string(),
+        || service.send(event.data.email, subject, html, plain),
+    )
+    .await;
+
+    if let Err(err) = delivered {
         tracing::warn!(error = ?err, "handle_reset_requested.send");
     }
 
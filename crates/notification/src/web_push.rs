@@ -0,0 +1,222 @@
+//! [`PushSender`] posting to browser push services: the payload is
+//! encrypted for the subscription (RFC 8291, `aes128gcm`) and the request is
+//! signed with the server's VAPID key (RFC 8292).
+
+use std::sync::Arc;
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use imkitchen_identity::push_subscription::PushSubscription;
+use ring::{aead, agreement, hkdf, rand, signature};
+use serde::Deserialize;
+use time::OffsetDateTime;
+
+use crate::push::PushSender;
+
+/// Record size announced in the content coding header.
+const RECORD_SIZE: u32 = 4096;
+
+/// Largest payload that fits the 4096 bytes push services accept once
+/// encrypted: 86 bytes of header, a delimiter and the 16 bytes tag.
+pub const MAX_PAYLOAD: usize = 4096 - 86 - 1 - 16;
+
+/// How long the push service keeps a message for an offline browser.
+const TTL_SECS: u32 = 24 * 3600;
+
+/// Lifetime of the VAPID token, push services refuse more than 24 hours.
+const VAPID_EXPIRATION_SECS: i64 = 12 * 3600;
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct PushConfig {
+    /// Contact given to push services, a `mailto:` or `https:` URL.
+    #[serde(default)]
+    pub subject: String,
+    /// VAPID key pair in base64url: the uncompressed P-256 public key and
+    /// its private scalar. Push is disabled while they are empty.
+    #[serde(default)]
+    pub public_key: String,
+    #[serde(default)]
+    pub private_key: String,
+}
+
+impl PushConfig {
+    pub fn is_enabled(&self) -> bool {
+        !self.public_key.is_empty() && !self.private_key.is_empty()
+    }
+}
+
+#[derive(Clone)]
+pub struct WebPushSender {
+    client: reqwest::Client,
+    subject: String,
+    public_key: String,
+    key_pair: Arc<signature::EcdsaKeyPair>,
+    rng: rand::SystemRandom,
+}
+
+impl WebPushSender {
+    pub fn new(config: &PushConfig) -> anyhow::Result<Self> {
+        let rng = rand::SystemRandom::new();
+        let key_pair = signature::EcdsaKeyPair::from_private_key_and_public_key(
+            &signature::ECDSA_P256_SHA256_FIXED_SIGNING,
+            &decode(&config.private_key)?,
+            &decode(&config.public_key)?,
+            &rng,
+        )
+        .map_err(|err| anyhow::anyhow!("invalid VAPID key pair: {err}"))?;
+
+        Ok(Self {
+            client: reqwest::Client::new(),
+            subject: config.subject.to_owned(),
+            public_key: config.public_key.trim_end_matches('=').to_owned(),
+            key_pair: Arc::new(key_pair),
+            rng,
+        })
+    }
+
+    /// `Authorization` header value for a push service endpoint.
+    fn authorization(&self, endpoint: &str) -> anyhow::Result<String> {
+        let audience = url::Url::parse(endpoint)?.origin().ascii_serialization();
+        let expires_at = OffsetDateTime::now_utc().unix_timestamp() + VAPID_EXPIRATION_SECS;
+
+        let header = URL_SAFE_NO_PAD.encode(r#"{"typ":"JWT","alg":"ES256"}"#);
+        let claims = URL_SAFE_NO_PAD.encode(
+            serde_json::json!({
+                "aud": audience,
+                "exp": expires_at,
+                "sub": self.subject,
+            })
+            .to_string(),
+        );
+        let message = format!("{header}.{claims}");
+        let signature = self
+            .key_pair
+            .sign(&self.rng, message.as_bytes())
+            .map_err(|_| anyhow::anyhow!("failed to sign VAPID token"))?;
+
+        Ok(format!(
+            "vapid t={message}.{}, k={}",
+            URL_SAFE_NO_PAD.encode(signature.as_ref()),
+            self.public_key
+        ))
+    }
+}
+
+impl PushSender for WebPushSender {
+    async fn send(&self, subscription: &PushSubscription, payload: &[u8]) -> anyhow::Result<u16> {
+        let body = encrypt(
+            &decode(&subscription.p256dh)?,
+            &decode(&subscription.auth)?,
+            payload,
+        )?;
+
+        let response = self
+            .client
+            .post(&subscription.endpoint)
+            .header("TTL", TTL_SECS.to_string())
+            .header("Content-Encoding", "aes128gcm")
+            .header("Content-Type", "application/octet-stream")
+            .header("Authorization", self.authorization(&subscription.endpoint)?)
+            .body(body)
+            .send()
+            .await?;
+
+        Ok(response.status().as_u16())
+    }
+}
+
+/// Encrypts the payload for the browser holding the `p256dh` key and `auth`
+/// secret of its subscription, as a single `aes128gcm` record.
+pub fn encrypt(p256dh: &[u8], auth: &[u8], payload: &[u8]) -> anyhow::Result<Vec<u8>> {
+    if payload.len() > MAX_PAYLOAD {
+        anyhow::bail!("push payload is {} bytes, max {MAX_PAYLOAD}", payload.len());
+    }
+
+    let rng = rand::SystemRandom::new();
+    let private_key = agreement::EphemeralPrivateKey::generate(&agreement::ECDH_P256, &rng)
+        .map_err(|_| anyhow::anyhow!("failed to generate push key"))?;
+    let public_key = private_key
+        .compute_public_key()
+        .map_err(|_| anyhow::anyhow!("failed to compute push key"))?;
+    let public_key = public_key.as_ref();
+
+    let shared_secret = agreement::agree_ephemeral(
+        private_key,
+        &agreement::UnparsedPublicKey::new(&agreement::ECDH_P256, p256dh),
+        |secret| secret.to_vec(),
+    )
+    .map_err(|_| anyhow::anyhow!("invalid p256dh key"))?;
+
+    let mut salt = [0u8; 16];
+    rand::SecureRandom::fill(&rng, &mut salt)
+        .map_err(|_| anyhow::anyhow!("failed to generate push salt"))?;
+
+    let (key, nonce) = content_key(&shared_secret, auth, p256dh, public_key, &salt)?;
+
+    let mut record = payload.to_vec();
+    // Delimiter of the last, and only, record.
+    record.push(2);
+    key.seal_in_place_append_tag(
+        aead::Nonce::assume_unique_for_key(nonce),
+        aead::Aad::empty(),
+        &mut record,
+    )
+    .map_err(|_| anyhow::anyhow!("failed to encrypt push payload"))?;
+
+    let mut body = Vec::with_capacity(86 + record.len());
+    body.extend_from_slice(&salt);
+    body.extend_from_slice(&RECORD_SIZE.to_be_bytes());
+    body.push(public_key.len() as u8);
+    body.extend_from_slice(public_key);
+    body.extend_from_slice(&record);
+
+    Ok(body)
+}
+
+/// Content encryption key and nonce derived from the ECDH secret between the
+/// browser key (`ua_public`) and the server's one-time key (`as_public`).
+pub fn content_key(
+    shared_secret: &[u8],
+    auth: &[u8],
+    ua_public: &[u8],
+    as_public: &[u8],
+    salt: &[u8],
+) -> anyhow::Result<(aead::LessSafeKey, [u8; 12])> {
+    let key_info = [b"WebPush: info\0".as_slice(), ua_public, as_public].concat();
+    let mut ikm = [0u8; 32];
+    expand(auth, shared_secret, &key_info, &mut ikm)?;
+
+    let mut cek = [0u8; 16];
+    expand(salt, &ikm, b"Content-Encoding: aes128gcm\0", &mut cek)?;
+
+    let mut nonce = [0u8; 12];
+    expand(salt, &ikm, b"Content-Encoding: nonce\0", &mut nonce)?;
+
+    let key = aead::UnboundKey::new(&aead::AES_128_GCM, &cek)
+        .map_err(|_| anyhow::anyhow!("invalid push content key"))?;
+
+    Ok((aead::LessSafeKey::new(key), nonce))
+}
+
+struct Len(usize);
+
+impl hkdf::KeyType for Len {
+    fn len(&self) -> usize {
+        self.0
+    }
+}
+
+fn expand(salt: &[u8], ikm: &[u8], info: &[u8], out: &mut [u8]) -> anyhow::Result<()> {
+    let info = [info];
+
+    hkdf::Salt::new(hkdf::HKDF_SHA256, salt)
+        .extract(ikm)
+        .expand(&info, Len(out.len()))
+        .and_then(|okm| okm.fill(out))
+        .map_err(|_| anyhow::anyhow!("failed to derive push key"))
+}
+
+/// Browsers give keys in base64url, with or without padding.
+fn decode(value: &str) -> anyhow::Result<Vec<u8>> {
+    Ok(URL_SAFE_NO_PAD.decode(value.trim_end_matches('='))?)
+}
@@ -0,0 +1,28 @@
+use imkitchen_notification::EmailConfig;
+use imkitchen_types::contact::Subject;
+
+#[test]
+fn test_contact_subject_routes_to_configured_address() {
+    let config = EmailConfig {
+        smtp_host: "localhost".to_owned(),
+        smtp_port: 1025,
+        smtp_username: "".to_owned(),
+        smtp_password: "".to_owned(),
+        from_address: "no-reply@imkitchen.localhost".to_owned(),
+        contact_address: "contact@imkitchen.localhost".to_owned(),
+        contact_routes: [(
+            "billing_question".to_owned(),
+            "billing@imkitchen.localhost".to_owned(),
+        )]
+        .into(),
+    };
+
+    assert_eq!(
+        config.contact_address_for(&Subject::BillingQuestion),
+        "billing@imkitchen.localhost"
+    );
+    assert_eq!(
+        config.contact_address_for(&Subject::Other),
+        "contact@imkitchen.localhost"
+    );
+}
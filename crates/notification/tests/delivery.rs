@@ -0,0 +1,61 @@
+use std::{cell::Cell, str::FromStr};
+
+use evento::migrator::{Migrate, Plan};
+use imkitchen_notification::delivery;
+use sqlx::{SqlitePool, sqlite::SqliteConnectOptions};
+use temp_dir::TempDir;
+
+async fn setup_pool(dir: &TempDir) -> anyhow::Result<SqlitePool> {
+    let path = dir.child("db.sqlite3");
+    let opts = SqliteConnectOptions::from_str(&format!("sqlite:{}", path.to_str().unwrap()))?
+        .create_if_missing(true);
+    let pool = SqlitePool::connect_with(opts).await?;
+    let mut conn = pool.acquire().await?;
+    imkitchen_db::migrator::<sqlx::Sqlite>()?
+        .run(&mut conn, &Plan::apply_all())
+        .await?;
+
+    Ok(pool)
+}
+
+#[tokio::test]
+async fn test_replayed_event_delivers_once() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let pool = setup_pool(&dir).await?;
+    let sent = Cell::new(0);
+    let send = || async {
+        sent.set(sent.get() + 1);
+        anyhow::Ok(())
+    };
+
+    assert!(delivery::once(&pool, "email.reset_password", "john", "event1", send).await?);
+    assert!(!delivery::once(&pool, "email.reset_password", "john", "event1", send).await?);
+    assert_eq!(sent.get(), 1);
+
+    // Another event, or another effect of the same event, is delivered.
+    assert!(delivery::once(&pool, "email.reset_password", "john", "event2", send).await?);
+    assert!(delivery::once(&pool, "email.contact_form", "john", "event1", send).await?);
+    assert_eq!(sent.get(), 3);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_failed_delivery_can_retry() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let pool = setup_pool(&dir).await?;
+
+    let resp = delivery::once(&pool, "email.contact_form", "john", "event1", || async {
+        anyhow::bail!("smtp down")
+    })
+    .await;
+    assert_eq!(resp.unwrap_err().to_string(), "smtp down");
+
+    let sent = delivery::once(&pool, "email.contact_form", "john", "event1", || async {
+        anyhow::Ok(())
+    })
+    .await?;
+    assert!(sent);
+
+    Ok(())
+}
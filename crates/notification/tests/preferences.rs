@@ -0,0 +1,213 @@
+use std::str::FromStr;
+
+use evento::migrator::{Migrate, Plan};
+use imkitchen_core::mealplan::Generate;
+use imkitchen_core::recipe::ImportInput;
+use imkitchen_core::shopping::AddManualItemInput;
+use imkitchen_notification::preferences;
+use imkitchen_notification::recipient::Recipient;
+use imkitchen_types::notification_preferences::NotificationKind;
+use imkitchen_types::recipe::{
+    Ingredient, IngredientCategory, IngredientUnit, Instruction, RecipeType,
+};
+use sqlx::{SqlitePool, sqlite::SqliteConnectOptions};
+use temp_dir::TempDir;
+
+const APP_URL: &str = "https://imkitchen.app";
+
+async fn setup_state(dir: &TempDir) -> anyhow::Result<imkitchen_core::State<evento::Sqlite>> {
+    let path = dir.child("db.sqlite3");
+    let opts = SqliteConnectOptions::from_str(&format!("sqlite:{}", path.to_str().unwrap()))?
+        .create_if_missing(true);
+    let pool = SqlitePool::connect_with(opts).await?;
+    let mut conn = pool.acquire().await?;
+    imkitchen_db::migrator::<sqlx::Sqlite>()?
+        .run(&mut conn, &Plan::apply_all())
+        .await?;
+
+    Ok(imkitchen_core::State {
+        executor: pool.clone().into(),
+        read_db: pool.clone(),
+        write_db: pool,
+    })
+}
+
+fn recipient(lang: &str) -> Recipient {
+    Recipient {
+        id: "john".to_owned(),
+        email: "john@imkitchen.app".to_owned(),
+        lang: lang.to_owned(),
+        timezone: "UTC".to_owned(),
+    }
+}
+
+async fn import(
+    state: &imkitchen_core::State<evento::Sqlite>,
+    name: &str,
+    ingredient: &str,
+) -> anyhow::Result<String> {
+    let id = imkitchen_core::recipe::Module::new(state.clone())
+        .import(
+            ImportInput {
+                name: name.to_owned(),
+                description: "my description".to_owned(),
+                ingredients: vec![Ingredient {
+                    name: ingredient.to_owned(),
+                    quantity: 500,
+                    unit: Some(IngredientUnit::G),
+                    category: Some(IngredientCategory::Grocery),
+                }],
+                instructions: vec![Instruction {
+                    description: "Bake".to_owned(),
+                    time_next: 0,
+                }],
+                household_size: 4,
+                cook_time: 25,
+                prep_time: 10,
+                recipe_type: RecipeType::MainCourse,
+                ..Default::default()
+            },
+            "john",
+            None,
+        )
+        .await?;
+
+    Ok(id)
+}
+
+#[tokio::test]
+async fn test_weekly_summary_sample_lists_the_coming_week() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let state = setup_state(&dir).await?;
+
+    let (subject, plain) = preferences::sample(
+        &state,
+        &NotificationKind::WeeklySummary,
+        &recipient("en"),
+        APP_URL,
+    )
+    .await?;
+    assert_eq!(subject, "Your week in the kitchen (test)");
+    assert_eq!(
+        plain,
+        "Nothing is planned for the coming week yet.\n\nhttps://imkitchen.app/menu"
+    );
+
+    import(&state, "Pizza", "flour").await?;
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let today = imkitchen_core::mealplan::now("UTC");
+    imkitchen_core::mealplan::Module::new(state.clone())
+        .generate(Generate {
+            user_id: "john".to_owned(),
+            start: today.unix_timestamp() as u64,
+            days: 1,
+            household_size: 4,
+            ..Default::default()
+        })
+        .await?;
+    imkitchen_core::mealplan::slot::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    let (_, plain) = preferences::sample(
+        &state,
+        &NotificationKind::WeeklySummary,
+        &recipient("en"),
+        APP_URL,
+    )
+    .await?;
+    assert_eq!(
+        plain,
+        format!(
+            "Here is what is planned for the coming week:\n\n- {} {}: Pizza\n\nhttps://imkitchen.app/menu",
+            today.weekday(),
+            today.day()
+        )
+    );
+
+    let (subject, plain) = preferences::sample(
+        &state,
+        &NotificationKind::WeeklySummary,
+        &recipient("fr"),
+        APP_URL,
+    )
+    .await?;
+    assert_eq!(subject, "Votre semaine en cuisine (test)");
+    assert!(plain.starts_with("Voici ce qui est prévu pour la semaine à venir :"));
+    assert!(plain.contains(": Pizza"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_shopping_reminder_sample_lists_what_is_left_to_buy() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let state = setup_state(&dir).await?;
+    let shopping = imkitchen_core::shopping::Module::new(state.clone());
+
+    let (subject, plain) = preferences::sample(
+        &state,
+        &NotificationKind::ShoppingReminder,
+        &recipient("en"),
+        APP_URL,
+    )
+    .await?;
+    assert_eq!(subject, "Shopping reminder (test)");
+    assert_eq!(
+        plain,
+        "Your shopping list is empty.\n\nhttps://imkitchen.app/groceries"
+    );
+
+    let bread = import(&state, "Bread", "flour").await?;
+    imkitchen_core::shopping::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    sqlx::query("INSERT INTO shopping_slot (user_id, date, recipe_ids) VALUES (?, ?, ?)")
+        .bind("john")
+        .bind(20260101_i64)
+        .bind(bitcode::encode(&vec![bread]))
+        .execute(&state.write_db)
+        .await?;
+    shopping
+        .generate(
+            imkitchen_core::shopping::Generate {
+                date: 20260101,
+                days: 7,
+                household_size: 4,
+            },
+            "john",
+        )
+        .await?;
+    shopping
+        .add_manual_item(
+            AddManualItemInput {
+                name: "Paper towels".to_owned(),
+            },
+            "john",
+        )
+        .await?;
+
+    let (_, plain) = preferences::sample(
+        &state,
+        &NotificationKind::ShoppingReminder,
+        &recipient("en"),
+        APP_URL,
+    )
+    .await?;
+    assert_eq!(
+        plain,
+        "Here is what is left on your shopping list:\n\n- flour: 500 g\n- Paper towels\n\nhttps://imkitchen.app/groceries"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,381 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    str::FromStr,
+    sync::Mutex,
+    time::Duration,
+};
+
+use evento::migrator::{Migrate, Plan};
+use imkitchen_identity::push_subscription::{PushSubscription, SubscribeInput};
+use imkitchen_notification::push::{self, BatchOptions, Push, PushOutcome, PushSender};
+use imkitchen_notification::web_push;
+use imkitchen_types::notification_preferences::QuietHours;
+use ring::{aead, agreement, rand};
+use sqlx::{SqlitePool, sqlite::SqliteConnectOptions};
+use temp_dir::TempDir;
+use time::OffsetDateTime;
+
+/// Answers each endpoint with its queued statuses, `None` being a transport
+/// error, and counts the calls.
+#[derive(Default)]
+struct MockSender {
+    responses: Mutex<HashMap<String, VecDeque<Option<u16>>>>,
+    calls: Mutex<HashMap<String, usize>>,
+}
+
+impl MockSender {
+    fn respond(&self, endpoint: &str, statuses: impl IntoIterator<Item = Option<u16>>) {
+        self.responses
+            .lock()
+            .unwrap()
+            .insert(endpoint.to_owned(), statuses.into_iter().collect());
+    }
+
+    fn calls(&self, endpoint: &str) -> usize {
+        self.calls
+            .lock()
+            .unwrap()
+            .get(endpoint)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+impl PushSender for MockSender {
+    async fn send(&self, subscription: &PushSubscription, _payload: &[u8]) -> anyhow::Result<u16> {
+        *self
+            .calls
+            .lock()
+            .unwrap()
+            .entry(subscription.endpoint.to_owned())
+            .or_default() += 1;
+
+        let status = self
+            .responses
+            .lock()
+            .unwrap()
+            .get_mut(&subscription.endpoint)
+            .and_then(|statuses| statuses.pop_front())
+            .unwrap_or(Some(201));
+
+        status.ok_or_else(|| anyhow::anyhow!("connection reset"))
+    }
+}
+
+async fn setup_module(
+    dir: &TempDir,
+) -> anyhow::Result<imkitchen_identity::push_subscription::Module<evento::Sqlite>> {
+    Ok(setup_identity(dir).await?.push_subscription)
+}
+
+async fn setup_identity(
+    dir: &TempDir,
+) -> anyhow::Result<imkitchen_identity::Module<evento::Sqlite>> {
+    let path = dir.child("db.sqlite3");
+    let opts = SqliteConnectOptions::from_str(&format!("sqlite:{}", path.to_str().unwrap()))?
+        .create_if_missing(true);
+    let pool = SqlitePool::connect_with(opts).await?;
+    let mut conn = pool.acquire().await?;
+    imkitchen_db::migrator::<sqlx::Sqlite>()?
+        .run(&mut conn, &Plan::apply_all())
+        .await?;
+
+    Ok(imkitchen_identity::Module::new(imkitchen_core::State {
+        executor: pool.clone().into(),
+        read_db: pool.clone(),
+        write_db: pool,
+    }))
+}
+
+async fn subscribe(
+    module: &imkitchen_identity::push_subscription::Module<evento::Sqlite>,
+    endpoint: &str,
+) -> anyhow::Result<PushSubscription> {
+    subscribe_user(module, "john", endpoint).await
+}
+
+async fn subscribe_user(
+    module: &imkitchen_identity::push_subscription::Module<evento::Sqlite>,
+    user_id: &str,
+    endpoint: &str,
+) -> anyhow::Result<PushSubscription> {
+    let id = module
+        .subscribe(
+            user_id,
+            SubscribeInput {
+                endpoint: endpoint.to_owned(),
+                p256dh: "p256dh".to_owned(),
+                auth: "auth".to_owned(),
+            },
+        )
+        .await?;
+
+    Ok(module.load(id).await?.unwrap())
+}
+
+#[tokio::test]
+async fn test_send_batch_retries_and_cleans_up() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let module = setup_module(&dir).await?;
+    let ok = "https://push.example.com/ok";
+    let flaky = "https://push.example.com/flaky";
+    let gone = "https://push.example.com/gone";
+    let down = "https://push.example.com/down";
+
+    let subscriptions = vec![
+        subscribe(&module, ok).await?,
+        subscribe(&module, flaky).await?,
+        subscribe(&module, gone).await?,
+        subscribe(&module, down).await?,
+    ];
+
+    let sender = MockSender::default();
+    sender.respond(flaky, [Some(503), None, Some(201)]);
+    sender.respond(gone, [Some(410)]);
+    sender.respond(down, [Some(500), Some(500), Some(500)]);
+
+    let options = BatchOptions {
+        concurrency: 2,
+        max_attempts: 3,
+        backoff: Duration::from_millis(1),
+    };
+    let results = push::send_batch(&module, &sender, &subscriptions, b"hello", options).await;
+
+    let outcomes = results
+        .iter()
+        .map(|result| (result.outcome.to_owned(), result.attempts))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        outcomes,
+        vec![
+            (PushOutcome::Delivered, 1),
+            (PushOutcome::Delivered, 3),
+            (PushOutcome::Expired, 1),
+            (PushOutcome::Failed("status 500".to_owned()), 3),
+        ]
+    );
+    assert_eq!(results[2].subscription_id, subscriptions[2].id);
+    assert_eq!(sender.calls(flaky), 3);
+
+    assert!(module.load(&subscriptions[2].id).await?.unwrap().expired);
+    assert!(!module.load(&subscriptions[3].id).await?.unwrap().expired);
+
+    // The expired subscription is never sent to again.
+    let subscription = module.load(&subscriptions[2].id).await?.unwrap();
+    let results = push::send_batch(&module, &sender, &[subscription], b"hello", options).await;
+    assert_eq!(results[0].outcome, PushOutcome::Expired);
+    assert_eq!(results[0].attempts, 0);
+    assert_eq!(sender.calls(gone), 1);
+
+    Ok(())
+}
+
+async fn run_push_subscription(
+    identity: &imkitchen_identity::Module<evento::Sqlite>,
+) -> anyhow::Result<()> {
+    push::subscription()
+        .data(identity.write_db.clone())
+        .no_retry()
+        .run_once(&identity.executor)
+        .await?;
+
+    Ok(())
+}
+
+fn notification(key: &str) -> Push<'_> {
+    Push {
+        user_id: "john",
+        effect_type: "push.test",
+        key,
+        payload: b"hi",
+        deadline: None,
+    }
+}
+
+fn fast() -> BatchOptions {
+    BatchOptions {
+        backoff: Duration::from_millis(1),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_dispatch_sends_once_to_each_device() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let identity = setup_identity(&dir).await?;
+    let module = &identity.push_subscription;
+    let phone = "https://push.example.com/phone";
+    let laptop = "https://push.example.com/laptop";
+    let gone = "https://push.example.com/gone";
+    let other = "https://push.example.com/other";
+
+    subscribe(module, phone).await?;
+    subscribe(module, laptop).await?;
+    let expired = subscribe(module, gone).await?;
+    subscribe_user(module, "albert", other).await?;
+    module.expire(&expired.id, 410).await?;
+    run_push_subscription(&identity).await?;
+
+    let sender = MockSender::default();
+    let now = OffsetDateTime::now_utc();
+
+    assert!(push::dispatch(&identity, &sender, notification("event1"), now, fast()).await?);
+    assert_eq!(sender.calls(phone), 1);
+    assert_eq!(sender.calls(laptop), 1);
+    assert_eq!(sender.calls(gone), 0);
+    assert_eq!(sender.calls(other), 0);
+
+    // Dispatching the same notification again sends nothing.
+    assert!(!push::dispatch(&identity, &sender, notification("event1"), now, fast()).await?);
+    assert_eq!(sender.calls(phone), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_dispatch_retries_when_every_device_failed() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let identity = setup_identity(&dir).await?;
+    let phone = "https://push.example.com/phone";
+
+    subscribe(&identity.push_subscription, phone).await?;
+    run_push_subscription(&identity).await?;
+
+    let sender = MockSender::default();
+    sender.respond(phone, [Some(500)]);
+    let options = BatchOptions {
+        max_attempts: 1,
+        ..Default::default()
+    };
+    let now = OffsetDateTime::now_utc();
+
+    let resp = push::dispatch(&identity, &sender, notification("event1"), now, options).await;
+    assert_eq!(resp.unwrap_err().to_string(), "status 500");
+
+    assert!(push::dispatch(&identity, &sender, notification("event1"), now, options).await?);
+    assert_eq!(sender.calls(phone), 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_dispatch_waits_for_quiet_hours_to_end() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let identity = setup_identity(&dir).await?;
+    let phone = "https://push.example.com/phone";
+
+    subscribe(&identity.push_subscription, phone).await?;
+    run_push_subscription(&identity).await?;
+
+    // 22:00 to 07:00, crossing midnight. John has no timezone, UTC is used.
+    identity
+        .notification_preferences
+        .set_quiet_hours(
+            "john",
+            Some(QuietHours {
+                start: 22 * 60,
+                end: 7 * 60,
+            }),
+        )
+        .await?;
+
+    let sender = MockSender::default();
+    let night = OffsetDateTime::now_utc().replace_time(time::macros::time!(23:30));
+
+    assert!(!push::dispatch(&identity, &sender, notification("event1"), night, fast()).await?);
+    assert_eq!(sender.calls(phone), 0);
+
+    // A deadline before the quiet hours end sends it anyway.
+    let urgent = Push {
+        deadline: Some(night + time::Duration::hours(1)),
+        ..notification("event1")
+    };
+    assert!(push::dispatch(&identity, &sender, urgent, night, fast()).await?);
+    assert_eq!(sender.calls(phone), 1);
+
+    // Once they end, it goes out.
+    let morning = night + time::Duration::hours(8);
+    assert!(push::dispatch(&identity, &sender, notification("event2"), morning, fast()).await?);
+    assert_eq!(sender.calls(phone), 2);
+
+    Ok(())
+}
+
+#[test]
+fn test_encrypted_payload_decrypts_with_browser_keys() -> anyhow::Result<()> {
+    let rng = rand::SystemRandom::new();
+    let ua_private = agreement::EphemeralPrivateKey::generate(&agreement::ECDH_P256, &rng)
+        .map_err(|_| anyhow::anyhow!("generate"))?;
+    let ua_public = ua_private
+        .compute_public_key()
+        .map_err(|_| anyhow::anyhow!("public key"))?;
+    let auth = b"0123456789abcdef";
+
+    let body = web_push::encrypt(ua_public.as_ref(), auth, b"Start your dough")?;
+
+    let (salt, rest) = body.split_at(16);
+    let (record_size, rest) = rest.split_at(4);
+    let (key_len, rest) = rest.split_at(1);
+    let (as_public, record) = rest.split_at(key_len[0] as usize);
+    assert_eq!(record_size, 4096u32.to_be_bytes());
+    assert_eq!(as_public.len(), 65);
+
+    let shared_secret = agreement::agree_ephemeral(
+        ua_private,
+        &agreement::UnparsedPublicKey::new(&agreement::ECDH_P256, as_public),
+        |secret| secret.to_vec(),
+    )
+    .map_err(|_| anyhow::anyhow!("agree"))?;
+    let (key, nonce) =
+        web_push::content_key(&shared_secret, auth, ua_public.as_ref(), as_public, salt)?;
+
+    let mut record = record.to_vec();
+    let plaintext = key
+        .open_in_place(
+            aead::Nonce::assume_unique_for_key(nonce),
+            aead::Aad::empty(),
+            &mut record,
+        )
+        .map_err(|_| anyhow::anyhow!("decrypt"))?;
+    assert_eq!(plaintext, b"Start your dough\x02");
+
+    Ok(())
+}
+
+#[test]
+fn test_encrypt_rejects_oversized_payload() {
+    let payload = vec![0; web_push::MAX_PAYLOAD + 1];
+    assert!(web_push::encrypt(&[4; 65], b"0123456789abcdef", &payload).is_err());
+}
+
+#[tokio::test]
+async fn test_dispatch_skips_unsubscribed_devices() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let identity = setup_identity(&dir).await?;
+    let module = &identity.push_subscription;
+    let phone = "https://push.example.com/phone";
+    let laptop = "https://push.example.com/laptop";
+
+    subscribe(module, phone).await?;
+    let laptop_subscription = subscribe(module, laptop).await?;
+    run_push_subscription(&identity).await?;
+
+    let resp = module.unsubscribe(&laptop_subscription.id, "albert").await;
+    assert!(matches!(resp, Err(imkitchen_core::Error::Forbidden(_))));
+
+    module.unsubscribe(&laptop_subscription.id, "john").await?;
+    run_push_subscription(&identity).await?;
+
+    let subscriptions = push::subscriptions(module, "john").await?;
+    assert_eq!(subscriptions.len(), 1);
+    assert_eq!(subscriptions[0].endpoint, phone);
+
+    let sender = MockSender::default();
+    let now = OffsetDateTime::now_utc();
+
+    assert!(push::dispatch(&identity, &sender, notification("event1"), now, fast()).await?);
+    assert_eq!(sender.calls(phone), 1);
+    assert_eq!(sender.calls(laptop), 0);
+
+    Ok(())
+}
@@ -0,0 +1,295 @@
+use std::{str::FromStr, sync::Mutex};
+
+use evento::migrator::{Migrate, Plan};
+use imkitchen_core::mealplan::Generate;
+use imkitchen_core::recipe::{AdvancePrepHoursInput, ImportInput};
+use imkitchen_identity::RegisterInput;
+use imkitchen_identity::push_subscription::{PushSubscription, SubscribeInput};
+use imkitchen_notification::{push, push::PushSender, reminder};
+use imkitchen_types::notification_preferences::QuietHours;
+use imkitchen_types::recipe::{Ingredient, Instruction, RecipeType};
+use sqlx::{SqlitePool, sqlite::SqliteConnectOptions};
+use temp_dir::TempDir;
+use time::{Duration, OffsetDateTime};
+
+/// Accepts every push and keeps what was sent to each endpoint.
+#[derive(Default)]
+struct MockSender {
+    sent: Mutex<Vec<(String, Vec<u8>)>>,
+}
+
+impl PushSender for MockSender {
+    async fn send(&self, subscription: &PushSubscription, payload: &[u8]) -> anyhow::Result<u16> {
+        self.sent
+            .lock()
+            .unwrap()
+            .push((subscription.endpoint.to_owned(), payload.to_vec()));
+
+        Ok(201)
+    }
+}
+
+async fn setup_state(dir: &TempDir) -> anyhow::Result<imkitchen_core::State<evento::Sqlite>> {
+    let path = dir.child("db.sqlite3");
+    let opts = SqliteConnectOptions::from_str(&format!("sqlite:{}", path.to_str().unwrap()))?
+        .create_if_missing(true);
+    let pool = SqlitePool::connect_with(opts).await?;
+    let mut conn = pool.acquire().await?;
+    imkitchen_db::migrator::<sqlx::Sqlite>()?
+        .run(&mut conn, &Plan::apply_all())
+        .await?;
+
+    Ok(imkitchen_core::State {
+        executor: pool.clone().into(),
+        read_db: pool.clone(),
+        write_db: pool,
+    })
+}
+
+/// Plans a recipe with 24 hours of advance prep for `user_id` on `day`.
+async fn plan_prep(
+    state: &imkitchen_core::State<evento::Sqlite>,
+    user_id: &str,
+    day: u64,
+) -> anyhow::Result<()> {
+    let recipe = imkitchen_core::recipe::Module::new(state.clone());
+    let id = recipe
+        .import(
+            ImportInput {
+                name: "Pizza".to_owned(),
+                description: "my description".to_owned(),
+                ingredients: vec![Ingredient {
+                    name: "flour".to_owned(),
+                    quantity: 500,
+                    unit: None,
+                    category: None,
+                }],
+                instructions: vec![Instruction {
+                    description: "Bake".to_owned(),
+                    time_next: 0,
+                }],
+                advance_prep: "Start the dough and let it rise in the fridge\nPunch it down"
+                    .to_owned(),
+                household_size: 4,
+                cook_time: 25,
+                prep_time: 10,
+                recipe_type: RecipeType::MainCourse,
+                ..Default::default()
+            },
+            user_id,
+            None,
+        )
+        .await?;
+    recipe
+        .set_advance_prep_hours(
+            AdvancePrepHoursInput {
+                id,
+                advance_prep_hours: Some(24),
+            },
+            user_id,
+        )
+        .await?;
+
+    imkitchen_core::mealplan::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    imkitchen_core::mealplan::Module::new(state.clone())
+        .generate(Generate {
+            user_id: user_id.to_owned(),
+            start: day,
+            days: 1,
+            household_size: 4,
+            ..Default::default()
+        })
+        .await?;
+
+    imkitchen_core::mealplan::slot::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    Ok(())
+}
+
+async fn subscribe(
+    state: &imkitchen_core::State<evento::Sqlite>,
+    user_id: &str,
+    endpoint: &str,
+) -> anyhow::Result<()> {
+    imkitchen_identity::Module::new(state.clone())
+        .push_subscription
+        .subscribe(
+            user_id,
+            SubscribeInput {
+                endpoint: endpoint.to_owned(),
+                p256dh: "p256dh".to_owned(),
+                auth: "auth".to_owned(),
+            },
+        )
+        .await?;
+
+    push::subscription()
+        .data(state.write_db.clone())
+        .no_retry()
+        .run_once(&state.executor)
+        .await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_prep_reminder_is_pushed_24_hours_before_the_slot() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let state = setup_state(&dir).await?;
+    let phone = "https://push.example.com/phone";
+
+    let day = (OffsetDateTime::now_utc() + Duration::days(3)).unix_timestamp() as u64;
+    plan_prep(&state, "john", day).await?;
+    subscribe(&state, "john", phone).await?;
+
+    let sender = MockSender::default();
+    let remind_at = day - 24 * 3600;
+
+    // Nothing is due a minute before.
+    assert_eq!(
+        reminder::send_prep_reminders(&state, &sender, remind_at - 60).await?,
+        0
+    );
+    assert!(sender.sent.lock().unwrap().is_empty());
+
+    assert_eq!(
+        reminder::send_prep_reminders(&state, &sender, remind_at).await?,
+        1
+    );
+
+    let sent = sender.sent.lock().unwrap().to_vec();
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0].0, phone);
+
+    let date =
+        imkitchen_core::mealplan::date_to_u64(OffsetDateTime::from_unix_timestamp(day as i64)?);
+    let payload: serde_json::Value = serde_json::from_slice(&sent[0].1)?;
+    assert_eq!(payload["title"], "Prep for Pizza");
+    assert_eq!(
+        payload["body"],
+        "Start the dough and let it rise in the fridge"
+    );
+    assert_eq!(
+        payload["data"]["url"],
+        format!(
+            "/kitchen/{}-{:02}-{:02}",
+            date / 10000,
+            date / 100 % 100,
+            date % 100
+        )
+    );
+
+    // The next runs don't send it again.
+    assert_eq!(
+        reminder::send_prep_reminders(&state, &sender, remind_at + 60).await?,
+        0
+    );
+    assert_eq!(sender.sent.lock().unwrap().len(), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_prep_reminder_waits_for_quiet_hours_to_end() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let state = setup_state(&dir).await?;
+    let phone = "https://push.example.com/phone";
+
+    let day = (OffsetDateTime::now_utc() + Duration::days(3)).unix_timestamp() as u64;
+    plan_prep(&state, "john", day).await?;
+    subscribe(&state, "john", phone).await?;
+
+    // Quiet from half an hour before the reminder to an hour after, in UTC
+    // as john has no timezone.
+    let remind_at = day - 24 * 3600;
+    let minute = (remind_at % 86400 / 60) as u16;
+    imkitchen_identity::Module::new(state.clone())
+        .notification_preferences
+        .set_quiet_hours(
+            "john",
+            Some(QuietHours {
+                start: (minute + 1440 - 30) % 1440,
+                end: (minute + 60) % 1440,
+            }),
+        )
+        .await?;
+
+    let sender = MockSender::default();
+    assert_eq!(
+        reminder::send_prep_reminders(&state, &sender, remind_at).await?,
+        0
+    );
+    assert_eq!(
+        reminder::send_prep_reminders(&state, &sender, remind_at + 59 * 60).await?,
+        0
+    );
+
+    assert_eq!(
+        reminder::send_prep_reminders(&state, &sender, remind_at + 61 * 60).await?,
+        1
+    );
+    assert_eq!(sender.sent.lock().unwrap().len(), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_prep_reminder_quiet_hours_follow_the_user_timezone() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let state = setup_state(&dir).await?;
+    let phone = "https://push.example.com/phone";
+
+    // Tokyo is 9 hours ahead of UTC all year long.
+    let identity = imkitchen_identity::Module::new(state.clone());
+    let user_id = identity
+        .register(RegisterInput {
+            email: "john.doe@imkitchen.localhost".to_owned(),
+            password: "my_password".to_owned(),
+            lang: "en".to_owned(),
+            timezone: "Asia/Tokyo".to_owned(),
+        })
+        .await?;
+
+    let day = (OffsetDateTime::now_utc() + Duration::days(3)).unix_timestamp() as u64;
+    plan_prep(&state, &user_id, day).await?;
+    subscribe(&state, &user_id, phone).await?;
+
+    // Quiet from half an hour before the reminder to an hour after, in
+    // Tokyo time. Read as UTC, the window would be 9 hours off.
+    let remind_at = day - 24 * 3600;
+    let minute = ((remind_at % 86400 / 60) as u16 + 9 * 60) % 1440;
+    identity
+        .notification_preferences
+        .set_quiet_hours(
+            &user_id,
+            Some(QuietHours {
+                start: (minute + 1440 - 30) % 1440,
+                end: (minute + 60) % 1440,
+            }),
+        )
+        .await?;
+
+    let sender = MockSender::default();
+    assert_eq!(
+        reminder::send_prep_reminders(&state, &sender, remind_at).await?,
+        0
+    );
+    assert!(sender.sent.lock().unwrap().is_empty());
+
+    assert_eq!(
+        reminder::send_prep_reminders(&state, &sender, remind_at + 61 * 60).await?,
+        1
+    );
+    assert_eq!(sender.sent.lock().unwrap().len(), 1);
+
+    Ok(())
+}
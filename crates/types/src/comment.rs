@@ -0,0 +1,78 @@
+use bitcode::{Decode, Encode};
+use serde::Deserialize;
+use strum::{AsRefStr, Display, EnumString, VariantArray};
+
+/// Why a user reported a comment.
+#[derive(
+    Encode,
+    Decode,
+    EnumString,
+    Display,
+    VariantArray,
+    Default,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Deserialize,
+    AsRefStr,
+)]
+pub enum FlagReason {
+    #[default]
+    Spam,
+    Offensive,
+    OffTopic,
+    Other,
+}
+
+/// How an admin settled the reports on a comment.
+#[derive(
+    Encode,
+    Decode,
+    EnumString,
+    Display,
+    VariantArray,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Deserialize,
+    AsRefStr,
+)]
+pub enum ReportResolution {
+    /// The reports were unfounded, the comment shows again.
+    Dismissed,
+    /// The reports were right, the comment stays hidden.
+    Removed,
+}
+
+/// A comment on a recipe. Threads are one level deep: a reply always
+/// belongs to the top level comment it was written under.
+#[evento::aggregate]
+pub enum Comment {
+    Added {
+        recipe_id: String,
+        body: String,
+    },
+    Replied {
+        recipe_id: String,
+        /// Top level comment of the thread.
+        parent_id: String,
+        body: String,
+    },
+
+    /// Reported by the user the event is requested by.
+    Flagged {
+        reason: FlagReason,
+    },
+    /// Committed with the flag that reached the threshold; the comment is
+    /// kept for admins but left out of public threads.
+    Hidden {
+        flag_count: u16,
+    },
+    /// An admin went through the reports, requested by that admin. Clears
+    /// the flags so the comment leaves the review queue.
+    ReportsResolved {
+        resolution: ReportResolution,
+    },
+}
@@ -44,6 +44,9 @@ pub enum Status {
     Unread,
     Read,
     Resolved,
+    /// Looked like spam when submitted; kept out of the inbox until an admin
+    /// reads it.
+    Flagged,
 }
 
 #[evento::aggregate]
@@ -58,4 +61,20 @@ pub enum Contact {
     MarkedReadAndReply,
     Resolved,
     Reopened,
+    /// Sent alongside `FormSubmitted` unless the sender looks like a spammer,
+    /// so the submitter gets a confirmation in their own language.
+    AcknowledgementRequested {
+        email: String,
+        name: String,
+        subject: Subject,
+        summary: String,
+        lang: String,
+    },
+    /// Sent alongside `FormSubmitted` when the sender's IP is known, so
+    /// submissions can be rate limited per IP.
+    SenderIpRecorded {
+        ip: String,
+    },
+    /// Sent alongside `FormSubmitted` when the message looks like spam.
+    FlaggedAsSpam,
 }
@@ -1,7 +1,10 @@
+pub mod comment;
 pub mod contact;
 pub mod favorite;
 pub mod meal_preferences;
 pub mod mealplan;
+pub mod notification_preferences;
+pub mod rating;
 pub mod recipe;
 pub mod recipe_share;
 pub mod shopping;
@@ -1,4 +1,7 @@
-use crate::recipe::DietaryRestriction;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::recipe::{Complexity, DietaryRestriction, Equipment, RecipeType};
 
 #[evento::aggregate]
 pub enum MealPreferences {
@@ -7,4 +10,135 @@ pub enum MealPreferences {
         dietary_restrictions: Vec<DietaryRestriction>,
         cuisine_variety_weight: f32,
     },
+    /// How many recipes the kitchen can run on each appliance at once.
+    /// Appliances left out have their [`Equipment::default_capacity`].
+    EquipmentCapacityChanged { capacity: Vec<(Equipment, u8)> },
+    /// Minutes available to cook each day, Monday first. `None` is no limit.
+    TimeBudgetChanged { minutes: [Option<u16>; 7] },
+    /// Main courses a week may take from the community instead of the
+    /// user's recipes.
+    CommunitySuggestionsChanged { count: u8 },
+    /// The whole [`UserConstraints`] set, as written by
+    /// [`UserConstraints::to_json`] so later fields don't break older events.
+    ConstraintsChanged { constraints: String },
+}
+
+/// Everything meal plan generation needs to know about a user, serialized as
+/// a single document. Missing fields fall back to their defaults so documents
+/// written before a field existed still load.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct UserConstraints {
+    pub household_size: u16,
+    pub dietary_restrictions: Vec<DietaryRestriction>,
+    pub cuisine_variety_weight: f32,
+    /// Boosts recipes with a higher average rating; 0 leaves ratings out.
+    pub rating_weight: f32,
+    /// Steers away from recipes cooked in the last few weeks; 0 leaves
+    /// cooking history out.
+    pub recency_weight: f32,
+    /// Plans recipes meant for a bigger household too, scaled down.
+    pub scale_down: bool,
+    /// 0 always plans the best scored recipes, 1 mixes them up the most.
+    pub randomness: f32,
+    /// Eats big batches over the next days instead of cooking every day.
+    pub allow_leftovers: bool,
+    /// Hardest main course planned Monday to Friday while easier ones are
+    /// left.
+    pub max_weekday_complexity: Complexity,
+    /// Same for Saturday and Sunday.
+    pub max_weekend_complexity: Complexity,
+    /// Courses left out of every day, e.g. all but the main course for
+    /// households that only plan dinner. The main course is always planned.
+    pub skipped_courses: Vec<RecipeType>,
+    /// Overrides [`Equipment::default_capacity`] for the household's
+    /// kitchen. Planning avoids courses of a day needing an appliance more
+    /// times than this, and never plans recipes needing one set to 0.
+    pub equipment_capacity: HashMap<Equipment, u8>,
+    /// Minutes available for a main course's prep and cook time, Monday
+    /// first. Days set to `None` have no limit.
+    pub time_budget: [Option<u16>; 7],
+    /// Main courses per week picked among the best rated community recipes
+    /// the user hasn't saved, still within their dietary restrictions. 0
+    /// plans the user's recipes only.
+    pub community_suggestions: u8,
+    /// Days a recipe should be from its other planned days before replacing
+    /// a meal brings it back. The least recently planned one comes back when
+    /// none is that far.
+    pub min_days_since_use: u16,
+}
+
+impl Default for UserConstraints {
+    fn default() -> Self {
+        Self {
+            household_size: 4,
+            dietary_restrictions: vec![],
+            cuisine_variety_weight: 1.0,
+            rating_weight: 0.0,
+            recency_weight: 1.0,
+            scale_down: false,
+            randomness: 1.0,
+            allow_leftovers: false,
+            max_weekday_complexity: Complexity::Moderate,
+            max_weekend_complexity: Complexity::Advanced,
+            skipped_courses: vec![],
+            equipment_capacity: HashMap::new(),
+            time_budget: [None; 7],
+            community_suggestions: 0,
+            min_days_since_use: 14,
+        }
+    }
+}
+
+impl UserConstraints {
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    pub fn from_json(value: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UserConstraints;
+    use crate::recipe::{Complexity, DietaryRestriction, Equipment, RecipeType};
+    use std::collections::HashMap;
+
+    #[test]
+    fn user_constraints_round_trip() {
+        let constraints = UserConstraints {
+            household_size: 6,
+            dietary_restrictions: vec![DietaryRestriction::Vegan, DietaryRestriction::GlutenFree],
+            cuisine_variety_weight: 0.5,
+            rating_weight: 1.5,
+            recency_weight: 0.5,
+            scale_down: true,
+            randomness: 0.25,
+            allow_leftovers: true,
+            max_weekday_complexity: Complexity::Simple,
+            max_weekend_complexity: Complexity::Moderate,
+            skipped_courses: vec![RecipeType::Appetizer, RecipeType::Dessert],
+            equipment_capacity: HashMap::from([(Equipment::Oven, 2), (Equipment::Grill, 0)]),
+            time_budget: [None, Some(20), None, None, Some(45), None, None],
+            community_suggestions: 2,
+            min_days_since_use: 7,
+        };
+
+        let json = constraints.to_json().unwrap();
+        assert_eq!(UserConstraints::from_json(&json).unwrap(), constraints);
+    }
+
+    #[test]
+    fn user_constraints_missing_fields_use_defaults() {
+        let constraints = UserConstraints::from_json(r#"{"household_size":2}"#).unwrap();
+        assert_eq!(
+            constraints,
+            UserConstraints {
+                household_size: 2,
+                ..Default::default()
+            }
+        );
+    }
 }
@@ -2,6 +2,8 @@ use bitcode::{Decode, Encode};
 use serde::Deserialize;
 use strum::{AsRefStr, Display, EnumString, VariantArray};
 
+use crate::recipe::{Ingredient, RecipeType};
+
 #[derive(Encode, Decode, Clone, Debug, PartialEq)]
 pub struct SlotRecipe {
     pub id: String,
@@ -21,6 +23,29 @@ pub struct Slot {
     pub condiment: Option<SlotRecipe>,
 }
 
+/// A planned recipe's ingredients as they were when the plan was generated.
+#[derive(Encode, Decode, Clone, PartialEq, Debug)]
+pub struct RecipeSnapshot {
+    pub id: String,
+    pub household_size: u16,
+    pub ingredients: Vec<Ingredient>,
+}
+
+/// A day whose main course is what's left of an earlier day's batch.
+#[derive(Encode, Decode, Clone, PartialEq, Debug)]
+pub struct Leftover {
+    pub date: u64,
+    pub recipe_id: String,
+    pub cooked_on: u64,
+}
+
+/// A day whose main course is a community recipe the user hasn't saved.
+#[derive(Encode, Decode, Clone, PartialEq, Debug)]
+pub struct Suggestion {
+    pub date: u64,
+    pub recipe_id: String,
+}
+
 #[derive(
     Encode, Decode, EnumString, Display, AsRefStr, Clone, Debug, Default, PartialEq, Deserialize,
 )]
@@ -31,6 +56,24 @@ pub enum DaySlotStatus {
     Completed,
 }
 
+impl DaySlotStatus {
+    /// Status after moving one step forward in a recipe of `steps`
+    /// instructions: idle starts at the first step, the last step completes
+    /// it. `None` once completed.
+    pub fn next(&self, steps: usize) -> Option<DaySlotStatus> {
+        let step = match self {
+            DaySlotStatus::Idle => 0,
+            DaySlotStatus::Cooking(step) => *step as usize + 1,
+            DaySlotStatus::Completed => return None,
+        };
+
+        match u8::try_from(step) {
+            Ok(step) if (step as usize) < steps => Some(DaySlotStatus::Cooking(step)),
+            _ => Some(DaySlotStatus::Completed),
+        }
+    }
+}
+
 #[derive(Encode, Decode, Default, Clone, PartialEq, Debug)]
 pub struct DaySlotRecipe {
     pub id: String,
@@ -39,6 +82,7 @@ pub struct DaySlotRecipe {
     pub cook_time: u16,
     pub advance_prep: String,
     pub status: DaySlotStatus,
+    pub advance_prep_done: bool,
 }
 
 impl DaySlotRecipe {
@@ -57,6 +101,11 @@ impl DaySlotRecipe {
     pub fn is_completed(&self) -> bool {
         matches!(self.status, DaySlotStatus::Completed)
     }
+
+    /// Has advance prep that hasn't been marked done yet.
+    pub fn needs_advance_prep(&self) -> bool {
+        !self.advance_prep.trim().is_empty() && !self.advance_prep_done
+    }
 }
 
 #[derive(
@@ -92,4 +141,58 @@ pub enum MealPlan {
         recipe_id: String,
         status: DaySlotStatus,
     },
+
+    MealReplaced {
+        date: u64,
+        recipe_type: RecipeType,
+        previous_recipe_id: String,
+        recipe: SlotRecipe,
+    },
+
+    AdvancePrepMarked {
+        date: u64,
+        recipe_id: String,
+        done: bool,
+    },
+
+    /// The recipes planned for one course on two days trade places.
+    MealsSwapped {
+        recipe_type: RecipeType,
+        date: u64,
+        recipe_id: String,
+        other_date: u64,
+        other_recipe_id: String,
+    },
+
+    /// Follows `DaysGenerated` when the plan keeps its recipes as planned, so
+    /// later edits don't change the week's shopping list.
+    RecipesSnapshotted { recipes: Vec<RecipeSnapshot> },
+
+    /// Follows `DaysGenerated` when some main courses are eaten again from
+    /// the batch cooked on an earlier day.
+    LeftoversPlanned { leftovers: Vec<Leftover> },
+
+    /// Follows `DaysGenerated` with the seed the recipes were drawn with, so
+    /// the same plan can be drawn again by passing it back.
+    Seeded { seed: u64 },
+
+    /// No plan for the week starting on `week` (a Monday, YYYYMMDD): its days
+    /// are cleared and later generations plan around it.
+    WeekSkipped { week: u64 },
+
+    /// The skipped week starting on `week` (YYYYMMDD) can be planned again.
+    WeekUnskipped { week: u64 },
+
+    /// One course of one day is cooked `multiplier` times its usual amount,
+    /// e.g. 2.0 to have Saturday's main course for twice the people.
+    SlotServingsChanged {
+        date: u64,
+        recipe_type: RecipeType,
+        recipe_id: String,
+        multiplier: f32,
+    },
+
+    /// Follows `DaysGenerated` when some main courses were picked among the
+    /// best rated community recipes rather than the user's own.
+    CommunityRecipesSuggested { suggestions: Vec<Suggestion> },
 }
@@ -0,0 +1,60 @@
+use bitcode::{Decode, Encode};
+use serde::Deserialize;
+use strum::{AsRefStr, Display, EnumString, VariantArray};
+
+#[derive(
+    Encode,
+    Decode,
+    EnumString,
+    VariantArray,
+    Display,
+    AsRefStr,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    Deserialize,
+)]
+pub enum NotificationKind {
+    WeeklySummary,
+    ShoppingReminder,
+}
+
+/// Time window, in minutes after local midnight, during which no
+/// notification is sent. `start` greater than `end` spans midnight.
+#[derive(Encode, Decode, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QuietHours {
+    pub start: u16,
+    pub end: u16,
+}
+
+impl QuietHours {
+    pub const MINUTES_PER_DAY: u16 = 24 * 60;
+
+    pub fn contains(&self, minute: u16) -> bool {
+        if self.start <= self.end {
+            (self.start..self.end).contains(&minute)
+        } else {
+            minute >= self.start || minute < self.end
+        }
+    }
+
+    /// Minutes elapsed since the window opened, for a minute inside it.
+    pub fn minutes_since_start(&self, minute: u16) -> u16 {
+        (minute + Self::MINUTES_PER_DAY - self.start) % Self::MINUTES_PER_DAY
+    }
+
+    /// Minutes left before the window closes, for a minute inside it.
+    pub fn minutes_until_end(&self, minute: u16) -> u16 {
+        (self.end + Self::MINUTES_PER_DAY - minute) % Self::MINUTES_PER_DAY
+    }
+}
+
+#[evento::aggregate]
+pub enum NotificationPreferences {
+    Changed { enabled: Vec<NotificationKind> },
+    TestRequested { kinds: Vec<NotificationKind> },
+    QuietHoursChanged { quiet_hours: Option<QuietHours> },
+}
@@ -0,0 +1,4 @@
+#[evento::aggregate]
+pub enum Rating {
+    Rated { recipe_id: String, stars: u8 },
+}
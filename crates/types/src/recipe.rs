@@ -1,5 +1,5 @@
 use bitcode::{Decode, Encode};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use strum::{AsRefStr, Display, EnumString, VariantArray};
 
 #[derive(
@@ -12,6 +12,9 @@ use strum::{AsRefStr, Display, EnumString, VariantArray};
     Clone,
     Debug,
     PartialEq,
+    Eq,
+    Hash,
+    Serialize,
     Deserialize,
     AsRefStr,
     sqlx::Type,
@@ -37,12 +40,32 @@ pub enum RecipeType {
     Debug,
     PartialEq,
     Deserialize,
+    Serialize,
     AsRefStr,
 )]
+#[strum(ascii_case_insensitive)]
 pub enum IngredientUnit {
     #[default]
     G,
     ML,
+    Tsp,
+    Tbsp,
+    Cup,
+    Piece,
+    Pinch,
+}
+
+impl IngredientUnit {
+    /// Unit a quantity is summed in and how many of it one `self` makes.
+    /// Spoons and cups are volumes; pieces and pinches don't convert.
+    pub fn base(&self) -> (IngredientUnit, u32) {
+        match self {
+            IngredientUnit::Tsp => (IngredientUnit::ML, 5),
+            IngredientUnit::Tbsp => (IngredientUnit::ML, 15),
+            IngredientUnit::Cup => (IngredientUnit::ML, 240),
+            unit => (unit.clone(), 1),
+        }
+    }
 }
 
 #[derive(
@@ -56,6 +79,7 @@ pub enum IngredientUnit {
     Debug,
     PartialEq,
     Deserialize,
+    Serialize,
     AsRefStr,
 )]
 pub enum IngredientCategory {
@@ -80,6 +104,76 @@ pub enum IngredientCategory {
     SnacksAndConfectionery,
 }
 
+impl IngredientCategory {
+    /// Built-in aisle order, following a typical walk through the store:
+    /// fresh produce first, chilled and frozen goods late so they stay cold,
+    /// then the shelf-stable aisles. The enum declaration order can't change
+    /// since it is part of the bitcode encoding.
+    pub const WALK_ORDER: [IngredientCategory; 9] = [
+        IngredientCategory::FruitsAndVegetables,
+        IngredientCategory::Bakery,
+        IngredientCategory::Butcher,
+        IngredientCategory::Seafood,
+        IngredientCategory::DairyAndEggs,
+        IngredientCategory::Refrigerated,
+        IngredientCategory::Frozen,
+        IngredientCategory::Grocery,
+        IngredientCategory::SnacksAndConfectionery,
+    ];
+
+    /// Full aisle order for a user: their own order first, then the remaining
+    /// categories in walk order. An empty `custom` gives [`Self::WALK_ORDER`].
+    pub fn aisle_order(custom: &[IngredientCategory]) -> Vec<IngredientCategory> {
+        let mut order: Vec<IngredientCategory> = vec![];
+        for category in custom.iter().chain(Self::WALK_ORDER.iter()) {
+            if !order.contains(category) {
+                order.push(category.clone());
+            }
+        }
+
+        order
+    }
+
+    pub const MAX_PERISHABILITY: u8 = 3;
+
+    /// How soon after shopping the category should be eaten, from 0 (keeps
+    /// the whole week) to [`Self::MAX_PERISHABILITY`] (fish and seafood,
+    /// best within a day or two).
+    pub fn perishability(&self) -> u8 {
+        match self {
+            IngredientCategory::Seafood => 3,
+            IngredientCategory::FruitsAndVegetables | IngredientCategory::Butcher => 2,
+            IngredientCategory::Refrigerated
+            | IngredientCategory::DairyAndEggs
+            | IngredientCategory::Bakery => 1,
+            IngredientCategory::Frozen
+            | IngredientCategory::Grocery
+            | IngredientCategory::SnacksAndConfectionery => 0,
+        }
+    }
+
+    /// Highest perishability of `ingredients`, see [`Self::perishability`].
+    /// Ingredients without a category don't count.
+    pub fn max_perishability(ingredients: &[Ingredient]) -> u8 {
+        ingredients
+            .iter()
+            .filter_map(|ingredient| ingredient.category.as_ref())
+            .map(IngredientCategory::perishability)
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Position of the category in `order`. Categories missing from it sort
+    /// last.
+    pub fn aisle_rank(&self, order: &[IngredientCategory]) -> usize {
+        order.iter().position(|c| c == self).unwrap_or(order.len())
+    }
+}
+
+fn plural(value: u32, one: &str, many: &str) -> String {
+    format!("{} {}", value, if value == 1 { one } else { many })
+}
+
 pub trait IngredientUnitFormat {
     fn format(&self, value: u32) -> String;
 }
@@ -111,12 +205,17 @@ impl IngredientUnitFormat for Option<IngredientUnit> {
                     format!("{} g", value)
                 }
             }
+            Some(IngredientUnit::Tsp) => format!("{} tsp", value),
+            Some(IngredientUnit::Tbsp) => format!("{} tbsp", value),
+            Some(IngredientUnit::Cup) => plural(value, "cup", "cups"),
+            Some(IngredientUnit::Piece) => plural(value, "piece", "pieces"),
+            Some(IngredientUnit::Pinch) => plural(value, "pinch", "pinches"),
             None => format!("{}", value),
         }
     }
 }
 
-#[derive(Encode, Decode, Clone, Deserialize, Debug, PartialEq)]
+#[derive(Encode, Decode, Clone, Deserialize, Serialize, Debug, PartialEq)]
 pub struct Ingredient {
     pub name: String,
     pub quantity: u32,
@@ -135,14 +234,154 @@ impl Ingredient {
         })
         .to_string()
     }
+
+    /// The same ingredient with its quantity in the unit's base unit, so that
+    /// compatible quantities share a [`Self::key`].
+    pub fn to_base_unit(self) -> Ingredient {
+        let Some((unit, factor)) = self.unit.as_ref().map(IngredientUnit::base) else {
+            return self;
+        };
+
+        Ingredient {
+            quantity: self.quantity * factor,
+            unit: Some(unit),
+            ..self
+        }
+    }
+
+    /// The ingredient for `target` people instead of the `authored` number
+    /// the recipe was written for. Rounds to the nearest whole quantity but
+    /// never down to nothing; "to taste" quantities (0) stay 0. An authored
+    /// size of 0 counts as 1.
+    pub fn scaled(&self, authored: u16, target: u16) -> Ingredient {
+        let scaled = self.quantity as f64 * target as f64 / authored.max(1) as f64;
+        let quantity = match scaled.round() as u32 {
+            0 if self.quantity > 0 && target > 0 => 1,
+            quantity => quantity,
+        };
+
+        Ingredient {
+            quantity,
+            ..self.clone()
+        }
+    }
+
+    /// Position of the ingredient's aisle in `order`, uncategorized last.
+    pub fn aisle_rank(&self, order: &[IngredientCategory]) -> usize {
+        self.category
+            .as_ref()
+            .map(|c| c.aisle_rank(order))
+            .unwrap_or(usize::MAX)
+    }
+
+    /// Shopping lists walk the store: by aisle, then by name. Recipes never
+    /// go through this, they keep the order the author wrote them in.
+    pub fn sort_for_shopping(ingredients: &mut [Ingredient], order: &[IngredientCategory]) {
+        ingredients.sort_by(|a, b| {
+            a.aisle_rank(order)
+                .cmp(&b.aisle_rank(order))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+    }
+
+    /// Sorts like [`Self::sort_for_shopping`] and splits the list into one
+    /// group per aisle, uncategorized ingredients last.
+    pub fn group_by_aisle(
+        ingredients: &[Ingredient],
+        order: &[IngredientCategory],
+    ) -> Vec<(Option<IngredientCategory>, Vec<Ingredient>)> {
+        let mut ingredients = ingredients.to_vec();
+        Self::sort_for_shopping(&mut ingredients, order);
+
+        let mut aisles: Vec<(Option<IngredientCategory>, Vec<Ingredient>)> = vec![];
+        for ingredient in ingredients {
+            match aisles.last_mut() {
+                Some((_, group)) if group[0].aisle_rank(order) == ingredient.aisle_rank(order) => {
+                    group.push(ingredient)
+                }
+                _ => aisles.push((ingredient.category.clone(), vec![ingredient])),
+            }
+        }
+
+        aisles
+    }
+}
+
+/// Energy (kcal) and macros (g) for an ingredient's whole quantity, or
+/// summed over several of them.
+#[derive(Encode, Decode, Clone, Copy, Default, Deserialize, Serialize, Debug, PartialEq)]
+pub struct Nutrition {
+    pub calories: u32,
+    pub protein: u32,
+    pub carbs: u32,
+    pub fat: u32,
+}
+
+impl Nutrition {
+    /// Sum over a recipe's ingredients. Ingredients without data count as
+    /// zero.
+    pub fn total<'a>(nutrition: impl IntoIterator<Item = &'a Option<Nutrition>>) -> Nutrition {
+        nutrition.into_iter().flatten().copied().sum()
+    }
+
+    /// One serving of a recipe written for `household_size` people.
+    pub fn per_serving(&self, household_size: u16) -> Nutrition {
+        let household_size = household_size.max(1) as f64;
+        let share = |value: u32| (value as f64 / household_size).round() as u32;
+
+        Nutrition {
+            calories: share(self.calories),
+            protein: share(self.protein),
+            carbs: share(self.carbs),
+            fat: share(self.fat),
+        }
+    }
 }
 
-#[derive(Encode, Decode, Clone, Deserialize, Debug, PartialEq)]
+impl std::ops::Add for Nutrition {
+    type Output = Nutrition;
+
+    fn add(self, rhs: Nutrition) -> Nutrition {
+        Nutrition {
+            calories: self.calories + rhs.calories,
+            protein: self.protein + rhs.protein,
+            carbs: self.carbs + rhs.carbs,
+            fat: self.fat + rhs.fat,
+        }
+    }
+}
+
+impl std::iter::Sum for Nutrition {
+    fn sum<I: Iterator<Item = Nutrition>>(iter: I) -> Nutrition {
+        iter.fold(Nutrition::default(), |acc, n| acc + n)
+    }
+}
+
+#[derive(Encode, Decode, Clone, Deserialize, Serialize, Debug, PartialEq)]
 pub struct Instruction {
     pub description: String,
     pub time_next: u16,
 }
 
+/// Minutes from the first step to the end of the last one on the cook-along
+/// timeline. A step waits for everything before it to finish, unless its
+/// `can_overlap` flag lets it start together with the previous step. Missing
+/// flags count as serial.
+pub fn timeline_minutes(instructions: &[Instruction], can_overlap: &[bool]) -> u32 {
+    let mut end = 0;
+    let mut previous_start = 0;
+
+    for (pos, instruction) in instructions.iter().enumerate() {
+        let overlaps = pos > 0 && can_overlap.get(pos).copied().unwrap_or_default();
+        let start = if overlaps { previous_start } else { end };
+
+        end = end.max(start + instruction.time_next as u32);
+        previous_start = start;
+    }
+
+    end
+}
+
 #[derive(
     Encode,
     Decode,
@@ -180,6 +419,7 @@ pub enum CuisineType {
     PartialEq,
     Clone,
     Debug,
+    Serialize,
     Deserialize,
     AsRefStr,
 )]
@@ -189,6 +429,9 @@ pub enum DietaryRestriction {
     GlutenFree,
     DairyFree,
     NutFree,
+    Pescatarian,
+    Kosher,
+    Halal,
 }
 
 impl DietaryRestriction {
@@ -198,6 +441,190 @@ impl DietaryRestriction {
     ) -> bool {
         iterator.into_iter().any(|d| d == self)
     }
+
+    /// Tags that make a recipe suit this restriction: a vegetarian dish is
+    /// fine for a pescatarian, a vegan one for a vegetarian or dairy-free diet.
+    pub fn satisfied_by(&self) -> &'static [DietaryRestriction] {
+        use DietaryRestriction::*;
+
+        match self {
+            Vegetarian => &[Vegetarian, Vegan],
+            Vegan => &[Vegan],
+            GlutenFree => &[GlutenFree],
+            DairyFree => &[DairyFree, Vegan],
+            NutFree => &[NutFree],
+            Pescatarian => &[Pescatarian, Vegetarian, Vegan],
+            Kosher => &[Kosher],
+            Halal => &[Halal],
+        }
+    }
+
+    /// Whether a recipe tagged with `tags` suits this restriction.
+    pub fn is_satisfied_by<'a>(
+        &self,
+        tags: impl IntoIterator<Item = &'a DietaryRestriction>,
+    ) -> bool {
+        tags.into_iter()
+            .any(|tag| self.satisfied_by().contains(tag))
+    }
+}
+
+#[derive(
+    Encode,
+    Decode,
+    EnumString,
+    VariantArray,
+    Display,
+    PartialEq,
+    Eq,
+    Hash,
+    Clone,
+    Copy,
+    Debug,
+    Serialize,
+    Deserialize,
+    AsRefStr,
+)]
+pub enum Equipment {
+    Oven,
+    Stovetop,
+    SlowCooker,
+    Grill,
+}
+
+impl Equipment {
+    /// How many recipes a typical kitchen can run on it at once: one oven
+    /// program, four burners.
+    pub fn default_capacity(&self) -> u8 {
+        match self {
+            Equipment::Stovetop => 4,
+            Equipment::Oven | Equipment::SlowCooker | Equipment::Grill => 1,
+        }
+    }
+}
+
+/// How demanding a recipe is to cook, from a quick weeknight dish to a
+/// weekend project. Variants are ordered from easiest to hardest.
+#[derive(
+    Encode,
+    Decode,
+    EnumString,
+    VariantArray,
+    Display,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Clone,
+    Copy,
+    Debug,
+    Serialize,
+    Deserialize,
+    AsRefStr,
+    sqlx::Type,
+)]
+pub enum Complexity {
+    Simple,
+    Moderate,
+    Advanced,
+}
+
+impl Complexity {
+    /// Complexity guessed from the size of the recipe, for recipes whose
+    /// author didn't rate it: any of many ingredients, many steps or a long
+    /// total time is enough to move it up a level.
+    pub fn estimate(ingredients: usize, instructions: usize, total_minutes: u32) -> Self {
+        if ingredients > 12 || instructions > 10 || total_minutes > 90 {
+            Complexity::Advanced
+        } else if ingredients > 7 || instructions > 5 || total_minutes > 45 {
+            Complexity::Moderate
+        } else {
+            Complexity::Simple
+        }
+    }
+
+    /// The author's rating when there is one, the estimate otherwise.
+    pub fn resolve(
+        explicit: Option<Complexity>,
+        ingredients: usize,
+        instructions: usize,
+        total_minutes: u32,
+    ) -> Self {
+        explicit.unwrap_or_else(|| Self::estimate(ingredients, instructions, total_minutes))
+    }
+}
+
+#[derive(
+    Encode,
+    Decode,
+    EnumString,
+    VariantArray,
+    Display,
+    PartialEq,
+    Eq,
+    Clone,
+    Copy,
+    Debug,
+    Serialize,
+    Deserialize,
+    AsRefStr,
+    sqlx::Type,
+)]
+pub enum AccompanimentType {
+    Rice,
+    Pasta,
+    Couscous,
+    Fries,
+    Potatoes,
+    Bread,
+    Salad,
+    Vegetables,
+}
+
+/// Broader grouping of accompaniment types, used when no recipe of the exact
+/// type a main course prefers is available.
+#[derive(Display, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum AccompanimentCategory {
+    Grain,
+    Potato,
+    Bread,
+    Vegetable,
+}
+
+impl AccompanimentType {
+    pub fn category(&self) -> AccompanimentCategory {
+        match self {
+            AccompanimentType::Rice | AccompanimentType::Pasta | AccompanimentType::Couscous => {
+                AccompanimentCategory::Grain
+            }
+            AccompanimentType::Fries | AccompanimentType::Potatoes => AccompanimentCategory::Potato,
+            AccompanimentType::Bread => AccompanimentCategory::Bread,
+            AccompanimentType::Salad | AccompanimentType::Vegetables => {
+                AccompanimentCategory::Vegetable
+            }
+        }
+    }
+}
+
+#[derive(
+    Encode,
+    Decode,
+    EnumString,
+    VariantArray,
+    Display,
+    PartialEq,
+    Clone,
+    Copy,
+    Debug,
+    Deserialize,
+    AsRefStr,
+)]
+pub enum ModerationAction {
+    MadePrivate,
+    /// Out of the community and other users' meal plans for good; the owner
+    /// keeps it.
+    Hidden,
+    Deleted,
 }
 
 #[evento::aggregate]
@@ -237,6 +664,7 @@ pub enum Recipe {
         cook_time: u16,
     },
 
+    /// The position of each ingredient is its display order.
     IngredientsChanged {
         ingredients: Vec<Ingredient>,
     },
@@ -245,6 +673,13 @@ pub enum Recipe {
         instructions: Vec<Instruction>,
     },
 
+    /// One flag per instruction, in order: `true` when the step can start
+    /// alongside the previous one instead of waiting for it. Kept out of
+    /// `Instruction` so recorded instruction events still decode.
+    InstructionOverlapsChanged {
+        can_overlap: Vec<bool>,
+    },
+
     DietaryRestrictionsChanged {
         dietary_restrictions: Vec<DietaryRestriction>,
     },
@@ -257,10 +692,65 @@ pub enum Recipe {
         accepts_accompaniment: bool,
     },
 
+    /// `accompaniment_type` describes an accompaniment recipe;
+    /// `preferred_accompaniment_types` lists what a main course goes with.
+    AccompanimentTypesChanged {
+        accompaniment_type: Option<AccompanimentType>,
+        preferred_accompaniment_types: Vec<AccompanimentType>,
+    },
+
+    /// Accompaniment recipes a main course is usually served with. Generation
+    /// tries them first, ahead of `preferred_accompaniment_types`.
+    DefaultAccompanimentsChanged {
+        accompaniment_ids: Vec<String>,
+    },
+
+    /// Appliances the recipe ties up while cooking, used to warn about
+    /// planned days that need more of them than the kitchen has.
+    EquipmentChanged {
+        equipment: Vec<Equipment>,
+    },
+
+    /// Smallest household the recipe makes sense for, e.g. a whole roast
+    /// that only works in bulk. 0 means any household.
+    MinHouseholdSizeChanged {
+        min_household_size: u16,
+    },
+
+    /// Servings one batch makes, when it's more than the household eats in
+    /// one sitting. 0 means the recipe's household size.
+    ServingsYieldChanged {
+        servings_yield: u16,
+    },
+
+    /// One entry per ingredient, in order, `None` where it has no data.
+    /// Kept out of `Ingredient` so recorded ingredient events still decode.
+    NutritionChanged {
+        nutrition: Vec<Option<Nutrition>>,
+    },
+
+    /// The author's own rating; `None` goes back to the estimate, see
+    /// [`Complexity::resolve`].
+    ComplexityChanged {
+        complexity: Option<Complexity>,
+    },
+
+    /// Free-form labels like "quick" or "one-pot", stored lowercased, trimmed
+    /// and without duplicates.
+    TagsChanged {
+        tags: Vec<String>,
+    },
+
     AdvancePrepChanged {
         advance_prep: String,
     },
 
+    /// How many hours before the meal its advance prep has to start, e.g. 24
+    /// to soak beans the day before. `None` leaves it to the planner.
+    AdvancePrepHoursChanged {
+        advance_prep_hours: Option<u16>,
+    },
+
     SharedToCommunity {
         owner_name: String,
     },
@@ -278,13 +768,236 @@ pub enum Recipe {
         device: String,
     },
 
+    /// Committed alongside the `MadePrivate` / `Deleted` event an admin forced
+    /// on someone else's recipe. All of them are requested by the admin;
+    /// per-owner views look the owner up from the recipe's history.
+    Moderated {
+        action: ModerationAction,
+        admin_id: String,
+    },
+    /// Forced out of the community by an admin. Unlike `MadePrivate` the
+    /// owner can't share it again, and users who saved it lose it.
+    Hidden {
+        /// The owner keeps the recipe; the event is requested by the admin.
+        owner_id: String,
+    },
+
     MadePrivate,
     Deleted,
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{ThumbnailResized, ThumbnailUploaded};
+    use super::{
+        Complexity, DietaryRestriction, Ingredient, IngredientCategory, IngredientUnit,
+        IngredientUnitFormat, Instruction, Nutrition, ThumbnailResized, ThumbnailUploaded,
+        timeline_minutes,
+    };
+    use strum::VariantArray;
+
+    fn step(time_next: u16) -> Instruction {
+        Instruction {
+            description: "step".to_owned(),
+            time_next,
+        }
+    }
+
+    #[test]
+    fn timeline_is_serial_by_default() {
+        let steps = [step(20), step(10), step(5)];
+
+        assert_eq!(timeline_minutes(&steps, &[]), 35);
+        assert_eq!(timeline_minutes(&steps, &[false, false, false]), 35);
+    }
+
+    #[test]
+    fn timeline_overlapping_steps_compress() {
+        // Chop while the sauce simmers, then combine once both are done.
+        let steps = [step(20), step(10), step(5)];
+
+        assert_eq!(timeline_minutes(&steps, &[false, true, false]), 25);
+        // A longer overlapping step pushes the next serial one back.
+        let steps = [step(10), step(15), step(5)];
+        assert_eq!(timeline_minutes(&steps, &[false, true, false]), 20);
+        // The first step has nothing to overlap with.
+        assert_eq!(timeline_minutes(&steps, &[true, false, false]), 30);
+    }
+
+    #[test]
+    fn default_aisle_order_follows_store_walk() {
+        use IngredientCategory::*;
+
+        assert_eq!(
+            IngredientCategory::aisle_order(&[]),
+            vec![
+                FruitsAndVegetables,
+                Bakery,
+                Butcher,
+                Seafood,
+                DairyAndEggs,
+                Refrigerated,
+                Frozen,
+                Grocery,
+                SnacksAndConfectionery,
+            ]
+        );
+        // Every category has a place in the walk.
+        assert!(
+            IngredientCategory::VARIANTS
+                .iter()
+                .all(|c| IngredientCategory::WALK_ORDER.contains(c))
+        );
+    }
+
+    #[test]
+    fn custom_aisle_order_goes_first() {
+        use IngredientCategory::*;
+
+        let order = IngredientCategory::aisle_order(&[Frozen, Bakery, Frozen]);
+        assert_eq!(order.len(), IngredientCategory::VARIANTS.len());
+        assert_eq!(&order[..3], &[Frozen, Bakery, FruitsAndVegetables]);
+        assert_eq!(Frozen.aisle_rank(&order), 0);
+        assert_eq!(Grocery.aisle_rank(&[Bakery]), 1);
+    }
+
+    #[test]
+    fn unit_format() {
+        use IngredientUnit::*;
+
+        assert_eq!(Some(G).format(1500), "1.5 kg");
+        assert_eq!(Some(Tbsp).format(2), "2 tbsp");
+        assert_eq!(Some(Tsp).format(1), "1 tsp");
+        assert_eq!(Some(Cup).format(1), "1 cup");
+        assert_eq!(Some(Piece).format(3), "3 pieces");
+        assert_eq!(Some(Pinch).format(2), "2 pinches");
+        assert_eq!(None.format(2), "2");
+    }
+
+    #[test]
+    fn unit_roundtrips_and_keys_stay_stable() {
+        let ingredient = |unit| Ingredient {
+            name: "flour".to_owned(),
+            quantity: 2,
+            unit,
+            category: None,
+        };
+
+        // Keys feed shopping list aggregation, existing ones must not move.
+        assert_eq!(ingredient(Some(IngredientUnit::G)).key(), "flour-0 g");
+        assert_eq!(ingredient(Some(IngredientUnit::ML)).key(), "flour-0 ml");
+        assert_eq!(ingredient(None).key(), "flour-0");
+
+        for unit in IngredientUnit::VARIANTS {
+            let ingredients = vec![ingredient(Some(unit.clone())), ingredient(None)];
+            let decoded: Vec<Ingredient> = bitcode::decode(&bitcode::encode(&ingredients)).unwrap();
+            assert_eq!(decoded, ingredients);
+            assert_eq!(
+                unit.to_string().parse::<IngredientUnit>().ok(),
+                Some(unit.clone())
+            );
+        }
+        assert_eq!(
+            "TBSP".parse::<IngredientUnit>().ok(),
+            Some(IngredientUnit::Tbsp)
+        );
+    }
+
+    #[test]
+    fn dietary_restrictions_keep_their_order() {
+        use DietaryRestriction::*;
+
+        // Stored events encode the variant index, new ones go last.
+        assert_eq!(
+            &DietaryRestriction::VARIANTS[..5],
+            &[Vegetarian, Vegan, GlutenFree, DairyFree, NutFree]
+        );
+
+        for restriction in DietaryRestriction::VARIANTS {
+            let decoded: DietaryRestriction =
+                bitcode::decode(&bitcode::encode(restriction)).unwrap();
+            assert_eq!(&decoded, restriction);
+            assert_eq!(
+                restriction.to_string().parse::<DietaryRestriction>().ok(),
+                Some(restriction.clone())
+            );
+            assert!(restriction.is_satisfied_by([restriction]));
+        }
+
+        assert!(Pescatarian.is_satisfied_by(&[Vegetarian]));
+        assert!(!Pescatarian.is_satisfied_by(&[GlutenFree]));
+        assert!(!Vegan.is_satisfied_by(&[Vegetarian]));
+    }
+
+    #[test]
+    fn nutrition_without_data_counts_as_zero() {
+        let rice = Nutrition {
+            calories: 700,
+            protein: 14,
+            carbs: 156,
+            fat: 2,
+        };
+
+        let total = Nutrition::total(&[Some(rice), None, Some(rice)]);
+        assert_eq!(total.calories, 1400);
+        assert_eq!(total.per_serving(4).carbs, 78);
+        assert_eq!(Nutrition::total(&[None]), Nutrition::default());
+        // A household of 0 reads as one person.
+        assert_eq!(rice.per_serving(0), rice);
+    }
+
+    #[test]
+    fn explicit_complexity_overrides_the_estimate() {
+        assert_eq!(Complexity::estimate(4, 3, 30), Complexity::Simple);
+        assert_eq!(Complexity::estimate(9, 3, 30), Complexity::Moderate);
+        assert_eq!(Complexity::estimate(15, 12, 120), Complexity::Advanced);
+        assert_eq!(
+            Complexity::resolve(Some(Complexity::Simple), 15, 12, 120),
+            Complexity::Simple
+        );
+        assert_eq!(Complexity::resolve(None, 15, 12, 120), Complexity::Advanced);
+    }
+
+    #[test]
+    fn scales_to_household() {
+        let ingredient = |quantity| Ingredient {
+            name: "rice".to_owned(),
+            quantity,
+            unit: Some(IngredientUnit::G),
+            category: None,
+        };
+
+        // Doubling and halving.
+        assert_eq!(ingredient(300).scaled(2, 4).quantity, 600);
+        assert_eq!(ingredient(300).scaled(4, 2).quantity, 150);
+        assert_eq!(ingredient(5).scaled(4, 3).quantity, 4);
+        // Never rounds a real quantity away, "to taste" stays as is.
+        assert_eq!(ingredient(1).scaled(4, 1).quantity, 1);
+        assert_eq!(ingredient(0).scaled(2, 8).quantity, 0);
+        // A recipe authored for 0 people is treated as written for 1.
+        assert_eq!(ingredient(100).scaled(0, 3).quantity, 300);
+    }
+
+    #[test]
+    fn shopping_order_groups_by_aisle_then_name() {
+        use IngredientCategory::*;
+
+        let ingredient = |name: &str, category| Ingredient {
+            name: name.to_owned(),
+            quantity: 1,
+            unit: None,
+            category,
+        };
+        let mut ingredients = vec![
+            ingredient("salt", None),
+            ingredient("zucchini", Some(FruitsAndVegetables)),
+            ingredient("butter", Some(DairyAndEggs)),
+            ingredient("apple", Some(FruitsAndVegetables)),
+        ];
+        Ingredient::sort_for_shopping(&mut ingredients, &IngredientCategory::aisle_order(&[]));
+
+        let names: Vec<_> = ingredients.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, ["apple", "zucchini", "butter", "salt"]);
+    }
 
     // The m0009 data migration strips image bytes out of existing thumbnail
     // event blobs with pure SQL, relying on the fact that the new byte-free
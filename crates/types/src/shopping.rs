@@ -1,5 +1,35 @@
+use bitcode::{Decode, Encode};
+use serde::Deserialize;
+use strum::{AsRefStr, Display, EnumString, VariantArray};
+
 use crate::recipe::Ingredient;
 
+/// How a quantity scaled to the household size is rounded.
+#[derive(
+    Encode,
+    Decode,
+    EnumString,
+    Display,
+    VariantArray,
+    Default,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Deserialize,
+    AsRefStr,
+)]
+pub enum RoundingStrategy {
+    /// Round to the closest whole unit.
+    Nearest,
+    /// Round up so the list never comes out short.
+    #[default]
+    Up,
+    /// Round to the closest step a quantity is usually measured in (5 g,
+    /// 25 g, 50 g, ...).
+    PracticalSteps,
+}
+
 #[evento::aggregate]
 pub enum Shopping {
     Checked {
@@ -8,11 +38,19 @@ pub enum Shopping {
     Unchecked {
         ingredient: String,
     },
+    // Original shape, kept so lists generated before the household size was
+    // recorded still decode. New lists are written as `GeneratedV2`.
     Generated {
         ingredients: Vec<Ingredient>,
         from_date: u64,
         days: u8,
     },
+    GeneratedV2 {
+        ingredients: Vec<Ingredient>,
+        from_date: u64,
+        days: u8,
+        household_size: u16,
+    },
     RecipeSetGenerated {
         recipe_ids: Vec<String>,
     },
@@ -26,4 +64,32 @@ pub enum Shopping {
         recipe_ids: Vec<String>,
         ingredients: Vec<Ingredient>,
     },
+    PackageSizeChanged {
+        ingredient: String,
+        package_size: Option<u32>,
+    },
+    RoundingStrategyChanged {
+        rounding_strategy: RoundingStrategy,
+    },
+    /// Non-recipe item (trash bags, snacks) added by hand to the current
+    /// week's list.
+    ManualItemAdded {
+        name: String,
+    },
+    /// Unchecks every item, keeping the list itself.
+    ChecklistReset,
+    /// Aisles in the order the user walks their store. Stored by
+    /// `IngredientCategory` name so a category removed later is skipped
+    /// instead of failing to decode.
+    AisleOrderChanged {
+        aisle_order: Vec<String>,
+    },
+    /// The user always has this ingredient at home. Unlike `Checked`, it
+    /// is kept when a new list is generated.
+    MarkedOwned {
+        ingredient: String,
+    },
+    UnmarkedOwned {
+        ingredient: String,
+    },
 }
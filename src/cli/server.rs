@@ -43,11 +43,19 @@ pub async fn serve(
 
     let sub_notification_contact = imkitchen_notification::contact::subscription()
         .data(email_service.clone())
+        .data((read_pool.clone(), write_pool.clone()))
         .start(&executor)
         .await?;
 
     let sub_notification_user = imkitchen_notification::user::subscription()
         .data(email_service.clone())
+        .data((read_pool.clone(), write_pool.clone()))
+        .start(&executor)
+        .await?;
+
+    let sub_notification_preferences = imkitchen_notification::preferences::subscription()
+        .data(email_service.clone())
+        .data((read_pool.clone(), write_pool.clone()))
         .start(&executor)
         .await?;
 
@@ -57,6 +65,11 @@ pub async fn serve(
         .start(&executor)
         .await?;
 
+    let sub_notification_push = imkitchen_notification::push::subscription()
+        .data(write_pool.clone())
+        .start(&executor)
+        .await?;
+
     let sub_user_query = imkitchen_identity::admin::create_projection()
         .data((read_pool.clone(), write_pool.clone()))
         .subscription("user-query")
@@ -75,6 +88,12 @@ pub async fn serve(
         .start(&executor)
         .await?;
 
+    let sub_user_login_history = imkitchen_identity::login_history::subscription()
+        .data(write_pool.clone())
+        .all()
+        .start(&executor)
+        .await?;
+
     let sub_user_invoice = imkitchen_billing::invoice::subscription()
         .data((read_pool.clone(), write_pool.clone()))
         .data(config.email.clone())
@@ -136,6 +155,24 @@ pub async fn serve(
         .start(&executor)
         .await?;
 
+    let sub_recipe_cook_count = imkitchen_core::recipe::query::cook_count::subscription()
+        .data(write_pool.clone())
+        .all()
+        .start(&executor)
+        .await?;
+
+    let sub_recipe_rating = imkitchen_core::recipe::query::rating::subscription()
+        .data(write_pool.clone())
+        .all()
+        .start(&executor)
+        .await?;
+
+    let sub_recipe_comment = imkitchen_core::recipe::query::comment::subscription()
+        .data(write_pool.clone())
+        .all()
+        .start(&executor)
+        .await?;
+
     let sub_mealplan_cmd = imkitchen_core::mealplan::subscription()
         .data(write_pool.clone())
         .start(&executor)
@@ -158,6 +195,12 @@ pub async fn serve(
         .start(&executor)
         .await?;
 
+    let sub_shopping_history = imkitchen_core::shopping::history::subscription()
+        .data(write_pool.clone())
+        .all()
+        .start(&executor)
+        .await?;
+
     let stripe = stripe::ClientBuilder::new(&config.stripe.secret_key)
         .request_strategy(stripe::RequestStrategy::ExponentialBackoff(4))
         .build()?;
@@ -166,19 +209,56 @@ pub async fn serve(
         imkitchen_billing::scheduler(&executor, &read_pool, &write_pool, &stripe).await?;
     sched_billing.start().await?;
 
+    let mut sched_reminder = if config.push.is_enabled() {
+        let sender = imkitchen_notification::web_push::WebPushSender::new(&config.push)?;
+        let mut sched = imkitchen_notification::reminder::scheduler(
+            &executor,
+            &read_pool,
+            &write_pool,
+            &sender,
+        )
+        .await?;
+        sched.start().await?;
+
+        Some(sched)
+    } else {
+        tracing::warn!("Web push is not configured, advance prep reminders are disabled");
+
+        None
+    };
+
     let state = imkitchen_core::State {
         executor: executor.clone(),
         read_db: read_pool.clone(),
         write_db: write_pool.clone(),
     };
 
+    let mut core = imkitchen_core::Core::new(state.clone());
+    core.mealplan = core
+        .mealplan
+        .with_snapshot_threshold(config.mealplan.snapshot_threshold);
+
+    let identity = imkitchen_identity::Module::new(state.clone()).with_password_hashing(
+        imkitchen_identity::PasswordHashing {
+            memory_kib: config.password.memory_kib,
+            iterations: config.password.iterations,
+            parallelism: config.password.parallelism,
+        },
+    );
+
+    let maintenance = imkitchen_web_shared::Maintenance::new(config.maintenance.enabled);
+    if maintenance.is_enabled() {
+        tracing::warn!("Starting in maintenance mode");
+    }
+
     let app_state = AppState {
         config,
         stripe,
-        identity: imkitchen_identity::Module::new(state.clone()),
+        identity,
         billing: imkitchen_billing::Billing::new(state.clone()),
-        core: imkitchen_core::Core::new(state.clone()),
+        core,
         import_jobs: Default::default(),
+        maintenance,
         inner: state,
     };
 
@@ -212,10 +292,14 @@ pub async fn serve(
             "/static",
             imkitchen_web_shared::assets::AssetsService::new(),
         )
-        .with_state(app_state)
+        .with_state(app_state.clone())
         .layer(DefaultBodyLimit::disable())
         .layer(tower_http::limit::RequestBodyLimitLayer::new(1024 * 1024)) // 1MB
         .merge(admin_upload)
+        .layer(axum::middleware::from_fn_with_state(
+            app_state,
+            imkitchen_web_shared::middleware::maintenance_middleware,
+        ))
         .layer(axum::middleware::from_fn(
             imkitchen_web_shared::middleware::cache_control_middleware,
         ))
@@ -269,9 +353,12 @@ pub async fn serve(
         sub_notification_contact.shutdown(),
         sub_notification_user.shutdown(),
         sub_notification_billing.shutdown(),
+        sub_notification_preferences.shutdown(),
+        sub_notification_push.shutdown(),
         sub_user_query.shutdown(),
         sub_user_shed.shutdown(),
         sub_user_global_stat.shutdown(),
+        sub_user_login_history.shutdown(),
         sub_user_invoice.shutdown(),
         sub_contact_query.shutdown(),
         sub_contact_global_stat.shutdown(),
@@ -282,10 +369,14 @@ pub async fn serve(
         sub_recipe_user_fts.shutdown(),
         sub_recipe_user_stat.shutdown(),
         sub_recipe_thumbnail.shutdown(),
+        sub_recipe_cook_count.shutdown(),
+        sub_recipe_rating.shutdown(),
+        sub_recipe_comment.shutdown(),
         sub_mealplan_cmd.shutdown(),
         sub_mealplan_slot.shutdown(),
         sub_shopping.shutdown(),
         sub_shopping_list.shutdown(),
+        sub_shopping_history.shutdown(),
     ])
     .await;
 
@@ -297,6 +388,10 @@ pub async fn serve(
 
     sched_billing.shutdown().await?;
 
+    if let Some(sched) = sched_reminder.as_mut() {
+        sched.shutdown().await?;
+    }
+
     tracing::info!("All projections shut down successfully");
 
     tracing::info!("Closing database pools...");
@@ -0,0 +1,196 @@
+// Passkey login and registration
+// Runs the WebAuthn ceremonies against the /passkey/* endpoints for buttons
+// marked with data-passkey="login" or data-passkey="register"
+
+(function() {
+  'use strict';
+
+  document.addEventListener('DOMContentLoaded', function() {
+    const buttons = document.querySelectorAll('[data-passkey]');
+
+    // Feature detection: keep the buttons hidden when passkeys aren't supported
+    if (!window.PublicKeyCredential) {
+      return;
+    }
+
+    buttons.forEach(function(button) {
+      button.classList.remove('hidden');
+      button.addEventListener('click', function() {
+        const ceremony = button.dataset.passkey === 'register' ? register : login;
+
+        button.disabled = true;
+        ceremony(button).finally(function() {
+          button.disabled = false;
+        });
+      });
+    });
+  });
+
+  /**
+   * Log in with a passkey, then go to the home page like the password form
+   */
+  async function login() {
+    const options = await fetchOptions('/passkey/login/options');
+    if (!options) return;
+
+    const credential = await ask(function() {
+      return navigator.credentials.get({
+        publicKey: {
+          challenge: decode(options.challenge),
+          rpId: options.rp_id,
+          userVerification: 'preferred'
+        }
+      });
+    });
+    if (!credential) return;
+
+    const response = credential.response;
+    const done = await post('/passkey/login', {
+      challenge_id: options.id,
+      credential_id: credential.id,
+      user_handle: encode(response.userHandle),
+      client_data_json: encode(response.clientDataJSON),
+      authenticator_data: encode(response.authenticatorData),
+      signature: encode(response.signature)
+    });
+
+    if (done) {
+      window.location.href = '/';
+    }
+  }
+
+  /**
+   * Register a passkey for the logged in user, named after this device
+   */
+  async function register(button) {
+    const options = await fetchOptions('/passkey/register/options');
+    if (!options) return;
+
+    const credential = await ask(function() {
+      return navigator.credentials.create({
+        publicKey: {
+          challenge: decode(options.challenge),
+          rp: { id: options.rp_id, name: 'imkitchen' },
+          user: {
+            id: new TextEncoder().encode(options.user_id),
+            name: button.dataset.passkeyUser,
+            displayName: button.dataset.passkeyUser
+          },
+          // Only P-256 (ES256) keys are accepted by the server
+          pubKeyCredParams: [{ type: 'public-key', alg: -7 }],
+          authenticatorSelection: {
+            residentKey: 'required',
+            userVerification: 'preferred'
+          }
+        }
+      });
+    });
+    if (!credential) return;
+
+    const response = credential.response;
+    const done = await post('/passkey/register', {
+      challenge_id: options.id,
+      credential_id: credential.id,
+      name: deviceName(),
+      public_key: encode(response.getPublicKey()),
+      client_data_json: encode(response.clientDataJSON),
+      authenticator_data: encode(response.getAuthenticatorData())
+    });
+
+    if (done) {
+      window.location.reload();
+    }
+  }
+
+  /**
+   * Start a ceremony, the server answers with the challenge to sign or an error toast
+   */
+  async function fetchOptions(url) {
+    const response = await fetch(url, { method: 'POST' });
+    if (!response.ok || !isJson(response)) {
+      await showToast(response);
+      return null;
+    }
+
+    return response.json();
+  }
+
+  /**
+   * Finish a ceremony, the server answers 204 on success or an error toast
+   */
+  async function post(url, body) {
+    const response = await fetch(url, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify(body)
+    });
+
+    if (response.status === 204) {
+      return true;
+    }
+
+    await showToast(response);
+    return false;
+  }
+
+  /**
+   * Prompt the user, a cancelled prompt is not an error
+   */
+  async function ask(prompt) {
+    try {
+      return await prompt();
+    } catch (error) {
+      if (error.name !== 'NotAllowedError' && error.name !== 'AbortError') {
+        console.error('Passkey ceremony failed:', error);
+      }
+
+      return null;
+    }
+  }
+
+  function isJson(response) {
+    return (response.headers.get('Content-Type') || '').startsWith('application/json');
+  }
+
+  /**
+   * Show the error toast rendered by the server
+   */
+  async function showToast(response) {
+    const container = document.getElementById('toast-container');
+    const html = await response.text();
+    if (!container || !html) return;
+
+    const template = document.createElement('template');
+    template.innerHTML = html;
+
+    Array.from(template.content.children).forEach(function(toast) {
+      container.appendChild(toast);
+      if (window.twinspark) {
+        window.twinspark.activate(toast);
+      }
+    });
+  }
+
+  function deviceName() {
+    const platform = (navigator.userAgentData && navigator.userAgentData.platform) || navigator.platform;
+
+    return (platform || 'Passkey').slice(0, 50);
+  }
+
+  function decode(value) {
+    const base64 = value.replace(/-/g, '+').replace(/_/g, '/');
+    const padded = base64 + '='.repeat((4 - base64.length % 4) % 4);
+
+    return Uint8Array.from(atob(padded), function(c) { return c.charCodeAt(0); });
+  }
+
+  function encode(buffer) {
+    const bytes = new Uint8Array(buffer);
+    let binary = '';
+    for (let i = 0; i < bytes.length; i++) {
+      binary += String.fromCharCode(bytes[i]);
+    }
+
+    return btoa(binary).replace(/\+/g, '-').replace(/\//g, '_').replace(/=+$/, '');
+  }
+})();
@@ -0,0 +1,128 @@
+// Push notifications
+// Subscribes this device through the service worker and registers the
+// subscription with the /profile/notifications/push endpoints for buttons
+// marked with data-push="subscribe" or data-push="unsubscribe"
+
+(function() {
+  'use strict';
+
+  document.addEventListener('DOMContentLoaded', async function() {
+    const subscribeButton = document.querySelector('[data-push="subscribe"]');
+    const unsubscribeButton = document.querySelector('[data-push="unsubscribe"]');
+
+    // Feature detection: keep the buttons hidden when push isn't supported
+    if (!subscribeButton || !unsubscribeButton) return;
+    if (!('serviceWorker' in navigator) || !('PushManager' in window) || !('Notification' in window)) {
+      return;
+    }
+
+    const registration = await navigator.serviceWorker.ready;
+
+    async function refresh() {
+      const subscription = await registration.pushManager.getSubscription();
+      subscribeButton.classList.toggle('hidden', !!subscription);
+      unsubscribeButton.classList.toggle('hidden', !subscription);
+    }
+
+    bind(subscribeButton, function() {
+      return subscribe(registration, subscribeButton.dataset.pushKey);
+    }, refresh);
+    bind(unsubscribeButton, function() {
+      return unsubscribe(registration);
+    }, refresh);
+
+    await refresh();
+  });
+
+  function bind(button, action, refresh) {
+    button.addEventListener('click', function() {
+      button.disabled = true;
+      action()
+        .catch(function(error) {
+          console.error('Push subscription failed:', error);
+        })
+        .then(refresh)
+        .finally(function() {
+          button.disabled = false;
+        });
+    });
+  }
+
+  /**
+   * Ask for permission, subscribe with the VAPID public key and send the
+   * subscription to the server
+   */
+  async function subscribe(registration, publicKey) {
+    const permission = await Notification.requestPermission();
+    if (permission !== 'granted') return;
+
+    const subscription = await registration.pushManager.subscribe({
+      userVisibleOnly: true,
+      applicationServerKey: decode(publicKey)
+    });
+
+    const done = await post('/profile/notifications/push', subscription.toJSON());
+    if (!done) {
+      await subscription.unsubscribe();
+    }
+  }
+
+  /**
+   * Forget the subscription on the server, then in the browser
+   */
+  async function unsubscribe(registration) {
+    const subscription = await registration.pushManager.getSubscription();
+    if (!subscription) return;
+
+    const done = await post('/profile/notifications/push/unsubscribe', {
+      endpoint: subscription.endpoint
+    });
+    if (done) {
+      await subscription.unsubscribe();
+    }
+  }
+
+  /**
+   * The server answers 204 on success or an error toast
+   */
+  async function post(url, body) {
+    const response = await fetch(url, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify(body)
+    });
+
+    if (response.status === 204) {
+      return true;
+    }
+
+    await showToast(response);
+    return false;
+  }
+
+  /**
+   * Show the error toast rendered by the server
+   */
+  async function showToast(response) {
+    const container = document.getElementById('toast-container');
+    const html = await response.text();
+    if (!container || !html) return;
+
+    const template = document.createElement('template');
+    template.innerHTML = html;
+
+    Array.from(template.content.children).forEach(function(toast) {
+      container.appendChild(toast);
+      if (window.twinspark) {
+        window.twinspark.activate(toast);
+      }
+    });
+  }
+
+  function decode(value) {
+    const base64 = value.replace(/-/g, '+').replace(/_/g, '/');
+    const padded = base64 + '='.repeat((4 - base64.length % 4) % 4);
+
+    return Uint8Array.from(atob(padded), function(c) { return c.charCodeAt(0); });
+  }
+})();
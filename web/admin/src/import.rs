@@ -110,6 +110,7 @@ fn parse_recipe(bytes: &[u8]) -> Result<imkitchen_core::recipe::ImportInput, Str
         advance_prep: recipe.advance_prep.unwrap_or_default(),
         accepts_accompaniment: recipe.accepts_accompaniment,
         dietary_restrictions: recipe.dietary_restrictions,
+        force: false,
     })
 }
 
@@ -342,6 +343,8 @@ pub async fn process_zip<E: Executor + Clone>(
                         });
                     }
                 }
+                // Imported unchanged by an earlier run of the same batch.
+                Err(imkitchen_core::Error::PossibleDuplicate(_)) => {}
                 Err(e) => {
                     progress.errors.push(AdminImportError {
                         scope: "recipe".into(),
@@ -9,6 +9,10 @@ pub fn routes() -> axum::Router<imkitchen_web_shared::AppState> {
             "/admin/recipes/import/{id}/status",
             get(routes::recipe_import::status),
         )
+        .route("/admin/recipes", get(routes::recipes::page))
+        .route("/admin/recipes/moderate", post(routes::recipes::moderate))
+        .route("/admin/comments", get(routes::comments::page))
+        .route("/admin/comments/resolve", post(routes::comments::resolve))
         .route("/admin/users", get(routes::users::page))
         .route("/admin/users/{id}/suspend", post(routes::users::suspend))
         .route("/admin/users/{id}/activate", post(routes::users::activate))
@@ -18,6 +22,7 @@ pub fn routes() -> axum::Router<imkitchen_web_shared::AppState> {
         )
         .route("/admin/users/{id}/edit", get(routes::users::edit_modal))
         .route("/admin/users/{id}", post(routes::users::update_user))
+        .route("/admin/maintenance", post(routes::maintenance::toggle))
         .route("/admin/invoices", get(routes::invoices::page))
         .route("/admin/invoices/{id}", get(routes::invoices::detail))
         .route("/admin/contact", get(routes::contact::page))
@@ -0,0 +1,97 @@
+use axum::{
+    extract::{Query, State},
+    response::{IntoResponse, Redirect},
+};
+use axum_extra::extract::Form;
+use evento::cursor::{Args, ReadResult, Value};
+use imkitchen_core::recipe::comment::ResolveReportsInput;
+use imkitchen_core::recipe::query::comment::CommentView;
+use imkitchen_types::comment::ReportResolution;
+use serde::Deserialize;
+
+use imkitchen_web_shared::{
+    AppState,
+    auth::AuthAdmin,
+    template::{Template, filters},
+};
+
+#[derive(askama::Template)]
+#[template(path = "admin-comments.html")]
+pub struct CommentsTemplate {
+    pub current_path: String,
+    pub comments: ReadResult<CommentView>,
+}
+
+impl Default for CommentsTemplate {
+    fn default() -> Self {
+        Self {
+            current_path: "comments".to_owned(),
+            comments: ReadResult::default(),
+        }
+    }
+}
+
+#[derive(Deserialize, Default, Clone)]
+pub struct PageQuery {
+    pub first: Option<u16>,
+    pub after: Option<Value>,
+    pub last: Option<u16>,
+    pub before: Option<Value>,
+}
+
+#[tracing::instrument(skip_all, fields(admin = admin.id))]
+pub async fn page(
+    template: Template,
+    Query(query): Query<PageQuery>,
+    State(app): State<AppState>,
+    admin: AuthAdmin,
+) -> impl IntoResponse {
+    let args = Args {
+        first: query.first,
+        after: query.after,
+        last: query.last,
+        before: query.before,
+    };
+
+    let comments = imkitchen_web_shared::try_page_response!(
+        app.core.recipe.filter_flagged_comments(args.limit(20)),
+        template
+    );
+
+    template
+        .render(CommentsTemplate {
+            comments,
+            ..Default::default()
+        })
+        .into_response()
+}
+
+#[derive(Deserialize)]
+pub struct ResolveForm {
+    pub resolution: ReportResolution,
+    #[serde(default)]
+    pub ids: Vec<String>,
+}
+
+#[tracing::instrument(skip_all, fields(admin = admin.id))]
+pub async fn resolve(
+    template: Template,
+    State(app): State<AppState>,
+    admin: AuthAdmin,
+    Form(input): Form<ResolveForm>,
+) -> impl IntoResponse {
+    let resolved = imkitchen_web_shared::try_response!(
+        app.core.recipe.comment.resolve_reports(
+            ResolveReportsInput {
+                ids: input.ids,
+                resolution: input.resolution,
+            },
+            &admin.id,
+        ),
+        template
+    );
+
+    tracing::info!(resolution = %input.resolution, resolved, "comment reports resolved");
+
+    Redirect::to("/admin/comments").into_response()
+}
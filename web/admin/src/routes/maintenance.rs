@@ -0,0 +1,26 @@
+use axum::{
+    extract::State,
+    response::{IntoResponse, Redirect},
+};
+use axum_extra::extract::Form;
+use serde::Deserialize;
+
+use imkitchen_web_shared::{AppState, auth::AuthAdmin};
+
+#[derive(Deserialize)]
+pub struct MaintenanceForm {
+    pub enabled: bool,
+}
+
+#[tracing::instrument(skip_all, fields(admin = admin.id))]
+pub async fn toggle(
+    State(app): State<AppState>,
+    admin: AuthAdmin,
+    Form(input): Form<MaintenanceForm>,
+) -> impl IntoResponse {
+    app.maintenance.set(input.enabled);
+
+    tracing::warn!(enabled = input.enabled, "maintenance mode changed");
+
+    Redirect::to("/admin/users").into_response()
+}
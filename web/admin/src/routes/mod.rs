@@ -1,4 +1,7 @@
+pub mod comments;
 pub mod contact;
 pub mod invoices;
+pub mod maintenance;
 pub mod recipe_import;
+pub mod recipes;
 pub mod users;
@@ -0,0 +1,80 @@
+use axum::{
+    extract::State,
+    response::{IntoResponse, Redirect},
+};
+use axum_extra::extract::Form;
+use imkitchen_core::recipe::ModerateInput;
+use imkitchen_core::recipe::query::comment::FlaggedRecipeView;
+use imkitchen_types::recipe::ModerationAction;
+use serde::Deserialize;
+
+use imkitchen_web_shared::{
+    AppState,
+    auth::AuthAdmin,
+    template::{Template, filters},
+};
+
+#[derive(askama::Template)]
+#[template(path = "admin-recipes.html")]
+pub struct RecipesTemplate {
+    pub current_path: String,
+    pub recipes: Vec<FlaggedRecipeView>,
+}
+
+impl Default for RecipesTemplate {
+    fn default() -> Self {
+        Self {
+            current_path: "flagged-recipes".to_owned(),
+            recipes: Vec::default(),
+        }
+    }
+}
+
+#[tracing::instrument(skip_all, fields(admin = admin.id))]
+pub async fn page(
+    template: Template,
+    State(app): State<AppState>,
+    admin: AuthAdmin,
+) -> impl IntoResponse {
+    let recipes = imkitchen_web_shared::try_page_response!(
+        app.core.recipe.filter_flagged_recipes(50),
+        template
+    );
+
+    template
+        .render(RecipesTemplate {
+            recipes,
+            ..Default::default()
+        })
+        .into_response()
+}
+
+#[derive(Deserialize)]
+pub struct ModerateForm {
+    pub action: ModerationAction,
+    #[serde(default)]
+    pub ids: Vec<String>,
+}
+
+#[tracing::instrument(skip_all, fields(admin = admin.id))]
+pub async fn moderate(
+    template: Template,
+    State(app): State<AppState>,
+    admin: AuthAdmin,
+    Form(input): Form<ModerateForm>,
+) -> impl IntoResponse {
+    let changed = imkitchen_web_shared::try_response!(
+        app.core.recipe.moderate(
+            ModerateInput {
+                ids: input.ids,
+                action: input.action,
+            },
+            &admin.id,
+        ),
+        template
+    );
+
+    tracing::info!(action = %input.action, changed, "recipes moderated");
+
+    Redirect::to("/admin/recipes").into_response()
+}
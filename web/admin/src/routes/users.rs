@@ -29,6 +29,7 @@ pub struct UsersTemplate {
     pub total_percent: i64,
     pub suspended_percent: i64,
     pub premium_percent: i64,
+    pub maintenance: bool,
 }
 
 impl Default for UsersTemplate {
@@ -41,6 +42,7 @@ impl Default for UsersTemplate {
             premium_percent: 0,
             suspended_percent: 0,
             total_percent: 0,
+            maintenance: false,
         }
     }
 }
@@ -122,6 +124,7 @@ pub async fn page(
             suspended_percent,
             total_percent,
             premium_percent,
+            maintenance: app.maintenance.is_enabled(),
             ..Default::default()
         })
         .into_response()
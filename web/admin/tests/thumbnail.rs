@@ -5,6 +5,7 @@ use evento::Sqlite;
 use evento::migrator::{Migrate, Plan};
 use image::{DynamicImage, ImageFormat, RgbImage};
 use imkitchen_core::State;
+use imkitchen_types::recipe::RecipeType;
 use sqlx::{SqlitePool, sqlite::SqliteConnectOptions};
 use temp_dir::TempDir;
 
@@ -42,7 +43,9 @@ async fn upload_thumbnail_stashes_original_and_emits_byte_free_event() -> anyhow
     let state = setup_test_state(dir.child("db.sqlite3")).await?;
     let recipe = imkitchen_core::recipe::Module::new(state.clone());
 
-    let id = recipe.create("chef-1", None).await?;
+    let id = recipe
+        .create("chef-1", None, RecipeType::MainCourse)
+        .await?;
 
     let png = png_bytes();
     recipe.upload_thumbnail(&id, png.clone(), "chef-1").await?;
@@ -117,7 +120,9 @@ async fn resize_pipeline_writes_variants_and_blur_without_event_bytes() -> anyho
     };
     let recipe = imkitchen_core::recipe::Module::new(state);
 
-    let id = recipe.create("chef-1", None).await?;
+    let id = recipe
+        .create("chef-1", None, RecipeType::MainCourse)
+        .await?;
     recipe.upload_thumbnail(&id, png_bytes(), "chef-1").await?;
 
     // Poll until the async resize subscription has produced the three variants.
@@ -16,7 +16,7 @@ use imkitchen_core::recipe::query::user::{SortBy, UserView, UserViewList};
 use imkitchen_core::recipe::query::user_stat::UserStatView;
 use imkitchen_types::mealplan::{DaySlotRecipe, DaySlotStatus};
 use imkitchen_types::recipe::{
-    Ingredient, IngredientCategory, IngredientUnit, Instruction, RecipeType,
+    Ingredient, IngredientCategory, IngredientUnit, Instruction, RecipeType, timeline_minutes,
 };
 use imkitchen_web_grocery::{AisleSection, GroceriesTemplate};
 use imkitchen_web_kitchen::{CookingTemplate, KitchenTemplate, KitchenWeekDay};
@@ -414,6 +414,7 @@ fn dsr(id: &str, status: DaySlotStatus) -> DaySlotRecipe {
         cook_time: r.cook_time,
         advance_prep: r.advance_prep,
         status,
+        advance_prep_done: false,
     }
 }
 
@@ -505,6 +506,8 @@ fn slot_for(day: OffsetDateTime) -> SlotRow {
         beverage: plan.4.map(|id| dsr(id, DaySlotStatus::Idle).into()),
         condiment: plan.5.map(|id| dsr(id, DaySlotStatus::Idle).into()),
         generated_at: 0,
+        leftover_of: None,
+        suggested: false,
     }
 }
 
@@ -551,7 +554,7 @@ pub fn kitchen() -> KitchenTemplate {
         .map(|d| KitchenWeekDay {
             date: ymd(*d),
             day_num: d.day(),
-            weekday: d.weekday().to_string().chars().take(3).collect(),
+            weekday: d.weekday(),
             is_today: mealplan::date_to_u64(*d) == today_u64,
             meal_types: meal_types_for(*d),
         })
@@ -616,6 +619,8 @@ pub fn cooking(recipe_id: &str) -> CookingTemplate {
         show_iframe: false,
         show_ingredients: false,
         ingredient_aisles: vec![],
+        is_completed: false,
+        next_step_at: None,
     }
 }
 
@@ -675,12 +680,14 @@ pub fn menu(date: Option<String>) -> MenuTemplate {
             let d_u64 = mealplan::date_to_u64(*d);
             MenuBoardDay {
                 date: ymd(*d),
-                weekday: d.weekday().to_string().chars().take(3).collect(),
+                weekday: d.weekday(),
                 day_num: d.day(),
                 is_today: d_u64 == today_u64,
                 is_past: d_u64 < today_u64,
                 is_in_month: in_month,
                 slot: in_month.then(|| slot_for(*d)),
+                conflicts: vec![],
+                is_skipped: false,
             }
         })
         .collect();
@@ -895,6 +902,8 @@ pub fn recipe_detail(id: &str) -> DetailTemplate<'static> {
     recipe.owner_id = "imkitchen-team".to_owned();
     recipe.owner_name = Some("imkitchen".to_owned());
     recipe.is_shared = true;
+    let timeline_minutes =
+        u16::try_from(timeline_minutes(&recipe.instructions, &[])).unwrap_or(u16::MAX);
 
     DetailTemplate {
         user: demo_user(),
@@ -906,6 +915,7 @@ pub fn recipe_detail(id: &str) -> DetailTemplate<'static> {
         },
         favorite: Favorite::default(),
         owner_description: "Home cook sharing tried-and-tested family recipes.".to_owned(),
+        timeline_minutes,
         ..Default::default()
     }
 }
@@ -1,11 +1,14 @@
 use axum::{
-    extract::{Json, Path, State},
+    extract::{Json, Path, Query, State},
+    http::header,
     response::{IntoResponse, Redirect},
 };
 use axum_extra::extract::Form;
 use imkitchen_core::recipe::query::user::RecipeCard;
-use imkitchen_core::shopping::{Generate, ToggleInput};
-use imkitchen_types::recipe::{Ingredient, IngredientUnitFormat, RecipeType};
+use imkitchen_core::shopping::{
+    AddManualItemInput, Generate, PackageSizeInput, ToggleInput, manual_item_key,
+};
+use imkitchen_types::recipe::{Ingredient, IngredientCategory, IngredientUnitFormat, RecipeType};
 use serde::Deserialize;
 use std::collections::{HashMap, HashSet};
 
@@ -20,12 +23,20 @@ pub fn routes() -> axum::Router<imkitchen_web_shared::AppState> {
     axum::Router::new()
         .route("/groceries", get(page))
         .route("/groceries/toggle", post(toggle_action))
+        .route("/groceries/owned", post(toggle_owned_action))
+        .route("/groceries/package", post(package_size_action))
+        .route("/groceries/manual", post(add_manual_item_action))
+        .route("/groceries/reset", post(reset_checklist_action))
+        .route("/groceries/repair", post(repair_action))
         .route(
             "/groceries/generate",
             get(generate_modal).post(generate_action),
         )
         .route("/groceries/generate/status", get(generate_status))
         .route("/groceries/recipe/{id}/remove", post(remove_recipe_action))
+        .route("/groceries/weeks.json", get(weeks_json))
+        .route("/groceries/export.txt", get(export_txt))
+        .route("/groceries/export.csv", get(export_csv))
 }
 
 pub struct AisleSection {
@@ -37,6 +48,18 @@ pub struct AisleSection {
     pub pct: usize,
 }
 
+/// An ad-hoc item added by hand, with the key it's checked under.
+pub struct ManualItem {
+    pub name: String,
+    pub key: String,
+}
+
+impl ManualItem {
+    pub fn json_key(&self) -> String {
+        serde_json::json!({ "name": self.key }).to_string()
+    }
+}
+
 #[derive(askama::Template)]
 #[template(path = "groceries.html")]
 pub struct GroceriesTemplate {
@@ -53,6 +76,24 @@ pub struct GroceriesTemplate {
     pub total_items: usize,
     pub checked_items: usize,
     pub progress_pct: usize,
+    /// Household size the list was generated for, when it differs from the
+    /// current preferences.
+    pub outdated_household_size: Option<u16>,
+    /// Ingredient key → quantity to buy in whole packages, for ingredients
+    /// sold in packages that the needed amount doesn't fill.
+    pub to_buy: HashMap<String, u32>,
+    /// Ingredient key → package size it is sold in, as set by the user.
+    pub package_sizes: HashMap<String, u32>,
+    /// The week the list was generated for has passed.
+    pub expired: bool,
+    /// Ad-hoc items added by hand, shown apart from the recipe aisles.
+    pub manual_items: Vec<ManualItem>,
+    /// Ingredients on several lines because their units don't add up.
+    pub mixed_units: Vec<String>,
+    /// Ingredients the user always has, listed apart and not counted.
+    pub owned: Vec<Ingredient>,
+    /// Ingredient key → recipe name and quantity it is bought for.
+    pub sources: HashMap<String, Vec<(String, u32)>>,
 }
 
 impl Default for GroceriesTemplate {
@@ -69,6 +110,14 @@ impl Default for GroceriesTemplate {
             total_items: 0,
             checked_items: 0,
             progress_pct: 0,
+            outdated_household_size: None,
+            to_buy: HashMap::new(),
+            package_sizes: HashMap::new(),
+            expired: false,
+            manual_items: vec![],
+            mixed_units: vec![],
+            owned: vec![],
+            sources: HashMap::new(),
         }
     }
 }
@@ -86,6 +135,11 @@ pub struct GroceriesBodyTemplate {
     pub total_items: usize,
     pub checked_items: usize,
     pub progress_pct: usize,
+    pub to_buy: HashMap<String, u32>,
+    pub package_sizes: HashMap<String, u32>,
+    pub manual_items: Vec<ManualItem>,
+    pub owned: Vec<Ingredient>,
+    pub sources: HashMap<String, Vec<(String, u32)>>,
 }
 
 /// Everything the groceries body needs, derived from the persisted list.
@@ -99,9 +153,18 @@ struct ShoppingView {
     total_items: usize,
     checked_items: usize,
     progress_pct: usize,
+    outdated_household_size: Option<u16>,
+    to_buy: HashMap<String, u32>,
+    package_sizes: HashMap<String, u32>,
+    expired: bool,
+    manual_items: Vec<ManualItem>,
+    mixed_units: Vec<String>,
+    owned: Vec<Ingredient>,
+    sources: HashMap<String, Vec<(String, u32)>>,
 }
 
-async fn build_view(app: &AppState, user_id: &str) -> anyhow::Result<ShoppingView> {
+async fn build_view(app: &AppState, user: &AuthUser) -> anyhow::Result<ShoppingView> {
+    let user_id = user.id.as_str();
     // Read straight from the aggregate (immediately consistent) rather than the
     // `shopping_list` read model, whose subscription lags a command by a beat —
     // otherwise a re-render right after add/remove shows the pre-change list.
@@ -113,8 +176,34 @@ async fn build_view(app: &AppState, user_id: &str) -> anyhow::Result<ShoppingVie
         .household_size;
     let state = app.core.shopping.state(user_id, household_size).await?;
 
-    let ingredients: Vec<(String, Vec<Ingredient>)> = to_categories(&state.ingredients);
+    let (owned, to_shop): (Vec<Ingredient>, Vec<Ingredient>) = state
+        .ingredients
+        .iter()
+        .cloned()
+        .partition(|i| state.owned.contains(&i.key()));
+    let ingredients: Vec<(String, Vec<Ingredient>)> = to_categories(&to_shop, &state.aisle_order);
+    let to_buy = state
+        .ingredients
+        .iter()
+        .filter_map(|i| Some((i.key(), state.to_buy(i)?)))
+        .collect();
+    let today = imkitchen_core::mealplan::date_to_u64(imkitchen_core::mealplan::now(&user.tz));
+    let expired = state.is_expired(today);
     let recipes = app.core.recipe.filter_by_ids(state.recipe_ids).await?;
+    let sources = state
+        .sources
+        .into_iter()
+        .map(|(key, sources)| {
+            let sources = sources
+                .into_iter()
+                .filter_map(|source| {
+                    let recipe = recipes.iter().find(|r| r.id == source.recipe_id)?;
+                    Some((recipe.name.to_owned(), source.quantity))
+                })
+                .collect();
+            (key, sources)
+        })
+        .collect();
 
     let (from_date, to_date) = Some((state.from_date, state.days))
         .filter(|(from, days)| *from > 0 && *days > 0)
@@ -126,9 +215,26 @@ async fn build_view(app: &AppState, user_id: &str) -> anyhow::Result<ShoppingVie
         .unwrap_or_default();
 
     let checked: HashSet<String> = state.checked;
+    let mixed_units = state.mixed_units;
 
-    let total_items: usize = ingredients.iter().map(|(_, items)| items.len()).sum();
-    let checked_items = checked.len();
+    let manual_items: Vec<ManualItem> = state
+        .manual_items
+        .into_iter()
+        .map(|name| ManualItem {
+            key: manual_item_key(&name),
+            name,
+        })
+        .collect();
+
+    let total_items: usize = ingredients
+        .iter()
+        .map(|(_, items)| items.len())
+        .sum::<usize>()
+        + manual_items.len();
+    let checked_items = checked
+        .iter()
+        .filter(|key| !state.owned.contains(*key))
+        .count();
     let progress_pct = (checked_items * 100).checked_div(total_items).unwrap_or(0);
 
     let aisles: Vec<AisleSection> = ingredients
@@ -160,6 +266,14 @@ async fn build_view(app: &AppState, user_id: &str) -> anyhow::Result<ShoppingVie
         total_items,
         checked_items,
         progress_pct,
+        outdated_household_size: state.outdated_household_size,
+        to_buy,
+        package_sizes: state.package_sizes,
+        expired,
+        manual_items,
+        mixed_units,
+        owned,
+        sources,
     })
 }
 
@@ -196,7 +310,7 @@ pub async fn page(
     user: AuthUser,
     State(app): State<AppState>,
 ) -> impl IntoResponse {
-    let view = imkitchen_web_shared::try_page_response!(build_view(&app, &user.id), template);
+    let view = imkitchen_web_shared::try_page_response!(build_view(&app, &user), template);
 
     template
         .render(GroceriesTemplate {
@@ -210,6 +324,14 @@ pub async fn page(
             total_items: view.total_items,
             checked_items: view.checked_items,
             progress_pct: view.progress_pct,
+            outdated_household_size: view.outdated_household_size,
+            to_buy: view.to_buy,
+            package_sizes: view.package_sizes,
+            expired: view.expired,
+            manual_items: view.manual_items,
+            mixed_units: view.mixed_units,
+            owned: view.owned,
+            sources: view.sources,
             ..Default::default()
         })
         .into_response()
@@ -233,7 +355,7 @@ pub async fn remove_recipe_action(
         template
     );
 
-    let view = imkitchen_web_shared::try_response!(anyhow: build_view(&app, &user.id), template);
+    let view = imkitchen_web_shared::try_response!(anyhow: build_view(&app, &user), template);
 
     template
         .render(GroceriesBodyTemplate {
@@ -244,6 +366,11 @@ pub async fn remove_recipe_action(
             total_items: view.total_items,
             checked_items: view.checked_items,
             progress_pct: view.progress_pct,
+            to_buy: view.to_buy,
+            package_sizes: view.package_sizes,
+            manual_items: view.manual_items,
+            owned: view.owned,
+            sources: view.sources,
         })
         .into_response()
 }
@@ -253,6 +380,66 @@ pub struct ToggleJson {
     pub name: String,
 }
 
+#[derive(Deserialize, Default, Clone)]
+pub struct PackageSizeForm {
+    pub name: String,
+    /// Blank clears the package size.
+    #[serde(default)]
+    pub package_size: String,
+}
+
+/// Sets the package size an ingredient is sold in, then re-renders the body
+/// so its quantity to buy follows.
+#[tracing::instrument(skip_all, fields(user = user.id))]
+pub async fn package_size_action(
+    template: Template,
+    user: AuthUser,
+    State(app): State<AppState>,
+    Form(input): Form<PackageSizeForm>,
+) -> impl IntoResponse {
+    let package_size = input.package_size.trim();
+    let package_size = if package_size.is_empty() {
+        None
+    } else {
+        Some(imkitchen_web_shared::try_response!(sync:
+            package_size.parse::<u32>().map_err(|_| imkitchen_core::Error::User(
+                "package size must be greater than 0".to_owned()
+            )),
+            template
+        ))
+    };
+
+    imkitchen_web_shared::try_response!(
+        app.core.shopping.set_package_size(
+            PackageSizeInput {
+                name: input.name,
+                package_size,
+            },
+            &user.id
+        ),
+        template
+    );
+
+    let view = imkitchen_web_shared::try_response!(anyhow: build_view(&app, &user), template);
+
+    template
+        .render(GroceriesBodyTemplate {
+            recipes: view.recipes,
+            checked: view.checked,
+            aisles: view.aisles,
+            split_at: view.split_at,
+            total_items: view.total_items,
+            checked_items: view.checked_items,
+            progress_pct: view.progress_pct,
+            to_buy: view.to_buy,
+            package_sizes: view.package_sizes,
+            manual_items: view.manual_items,
+            owned: view.owned,
+            sources: view.sources,
+        })
+        .into_response()
+}
+
 #[tracing::instrument(skip_all, fields(user = user.id))]
 pub async fn toggle_action(
     template: Template,
@@ -270,6 +457,96 @@ pub async fn toggle_action(
     "<div></div>".into_response()
 }
 
+/// Marks or unmarks an ingredient as always at home, then re-renders the body
+/// so it moves between its aisle and the owned section.
+#[tracing::instrument(skip_all, fields(user = user.id))]
+pub async fn toggle_owned_action(
+    template: Template,
+    user: AuthUser,
+    State(app): State<AppState>,
+    Json(input): Json<ToggleJson>,
+) -> impl IntoResponse {
+    imkitchen_web_shared::try_response!(
+        app.core
+            .shopping
+            .toggle_owned(ToggleInput { name: input.name }, &user.id),
+        template
+    );
+
+    let view = imkitchen_web_shared::try_response!(anyhow: build_view(&app, &user), template);
+
+    template
+        .render(GroceriesBodyTemplate {
+            recipes: view.recipes,
+            checked: view.checked,
+            aisles: view.aisles,
+            split_at: view.split_at,
+            total_items: view.total_items,
+            checked_items: view.checked_items,
+            progress_pct: view.progress_pct,
+            to_buy: view.to_buy,
+            package_sizes: view.package_sizes,
+            manual_items: view.manual_items,
+            owned: view.owned,
+            sources: view.sources,
+        })
+        .into_response()
+}
+
+#[derive(Deserialize, Default, Clone)]
+pub struct ManualItemInput {
+    pub name: String,
+}
+
+#[tracing::instrument(skip_all, fields(user = user.id))]
+pub async fn add_manual_item_action(
+    template: Template,
+    user: AuthUser,
+    State(app): State<AppState>,
+    Form(input): Form<ManualItemInput>,
+) -> impl IntoResponse {
+    imkitchen_web_shared::try_response!(
+        app.core
+            .shopping
+            .add_manual_item(AddManualItemInput { name: input.name }, &user.id),
+        template
+    );
+
+    Redirect::to("/groceries").into_response()
+}
+
+#[tracing::instrument(skip_all, fields(user = user.id))]
+pub async fn reset_checklist_action(
+    template: Template,
+    user: AuthUser,
+    State(app): State<AppState>,
+) -> impl IntoResponse {
+    imkitchen_web_shared::try_response!(app.core.shopping.reset_checklist(&user.id), template);
+
+    Redirect::to("/groceries").into_response()
+}
+
+/// Rebuilds a list that no longer matches the meal plan it came from.
+#[tracing::instrument(skip_all, fields(user = user.id))]
+pub async fn repair_action(
+    template: Template,
+    user: AuthUser,
+    State(app): State<AppState>,
+) -> impl IntoResponse {
+    let preferences = imkitchen_web_shared::try_response!(anyhow:
+        app.identity.meal_preferences.load(&user.id),
+        template
+    );
+    imkitchen_web_shared::try_response!(
+        app.core
+            .shopping
+            .repair(preferences.household_size, &user.id),
+        template
+    );
+
+    Redirect::to("/groceries").into_response()
+}
+
 fn u64_to_date(date: u64) -> Option<time::OffsetDateTime> {
     let year = (date / 10000) as i32;
     let month = ((date % 10000) / 100) as u8;
@@ -279,33 +556,121 @@ fn u64_to_date(date: u64) -> Option<time::OffsetDateTime> {
     Some(time::PrimitiveDateTime::new(d, time::Time::MIDNIGHT).assume_utc())
 }
 
-fn to_categories(ingredients: &[Ingredient]) -> Vec<(String, Vec<Ingredient>)> {
-    let mut categories = HashMap::new();
-    let mut ingredients = ingredients.to_vec();
-    ingredients.sort_by_key(|i| i.name.to_owned());
+#[derive(askama::Template)]
+#[template(path = "groceries-export.txt")]
+pub struct ExportTextTemplate {
+    /// `shopping_<Category>` key → (checked, "name — quantity") lines.
+    pub aisles: Vec<(String, Vec<(bool, String)>)>,
+    pub manual_items: Vec<(bool, String)>,
+}
 
-    for ingredient in ingredients.iter() {
-        match &ingredient.category {
-            Some(c) => {
-                let entry = categories.entry(format!("shopping_{c}")).or_insert(vec![]);
-                entry.push(ingredient.clone());
-            }
-            _ => {
-                let entry = categories
-                    .entry("shopping_Unknown".to_owned())
-                    .or_insert(vec![]);
-                entry.push(ingredient.clone());
-            }
-        };
-    }
+/// The list as plain text to paste into a notes app, one aisle per block.
+#[tracing::instrument(skip_all, fields(user = user.id))]
+pub async fn export_txt(
+    template: Template,
+    user: AuthUser,
+    State(app): State<AppState>,
+) -> impl IntoResponse {
+    let preferences = imkitchen_web_shared::try_page_response!(
+        app.identity.meal_preferences.load(&user.id),
+        template
+    );
+    let state = imkitchen_web_shared::try_page_response!(
+        app.core
+            .shopping
+            .state(&user.id, preferences.household_size),
+        template
+    );
 
-    let mut categories = categories
+    // Ingredients the user always has aren't to buy.
+    let aisles = to_categories(&state.ingredients, &state.aisle_order)
         .into_iter()
-        .collect::<Vec<(String, Vec<Ingredient>)>>();
+        .filter_map(|(name, items)| {
+            let lines = items
+                .iter()
+                .filter(|i| !state.owned.contains(&i.key()))
+                .map(|i| {
+                    let quantity = state.to_buy(i).unwrap_or(i.quantity);
+                    let line = format!("{} — {}", i.name, i.unit.format(quantity));
+                    (state.checked.contains(&i.key()), line)
+                })
+                .collect::<Vec<_>>();
+            (!lines.is_empty()).then_some((name, lines))
+        })
+        .collect();
+    let manual_items = state
+        .manual_items
+        .iter()
+        .map(|name| {
+            let checked = state.checked.contains(&manual_item_key(name));
+            (checked, name.to_owned())
+        })
+        .collect();
+
+    (
+        [
+            (header::CONTENT_TYPE, "text/plain; charset=utf-8"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"shopping-list.txt\"",
+            ),
+        ],
+        template.to_string(ExportTextTemplate {
+            aisles,
+            manual_items,
+        }),
+    )
+        .into_response()
+}
+
+/// The list as CSV for a spreadsheet. See
+/// [`imkitchen_core::shopping::ShoppingState::to_csv`].
+#[tracing::instrument(skip_all, fields(user = user.id))]
+pub async fn export_csv(
+    template: Template,
+    user: AuthUser,
+    State(app): State<AppState>,
+) -> impl IntoResponse {
+    let preferences = imkitchen_web_shared::try_page_response!(
+        app.identity.meal_preferences.load(&user.id),
+        template
+    );
+    let state = imkitchen_web_shared::try_page_response!(
+        app.core
+            .shopping
+            .state(&user.id, preferences.household_size),
+        template
+    );
 
-    categories.sort_by_key(|(k, _)| k.to_owned());
+    (
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"shopping-list.csv\"",
+            ),
+        ],
+        state.to_csv(),
+    )
+        .into_response()
+}
 
-    categories
+/// Groups ingredients into aisles, in the user's aisle order.
+/// Uncategorized ingredients come last.
+fn to_categories(
+    ingredients: &[Ingredient],
+    order: &[IngredientCategory],
+) -> Vec<(String, Vec<Ingredient>)> {
+    Ingredient::group_by_aisle(ingredients, order)
+        .into_iter()
+        .map(|(category, ingredients)| {
+            let name = match category {
+                Some(c) => format!("shopping_{c}"),
+                None => "shopping_Unknown".to_owned(),
+            };
+            (name, ingredients)
+        })
+        .collect()
 }
 
 #[derive(askama::Template)]
@@ -387,6 +752,42 @@ pub async fn generate_action(
         .into_response()
 }
 
+#[derive(Deserialize, Debug)]
+pub struct WeeksQuery {
+    pub from: String,
+    pub weeks: u8,
+}
+
+/// One list for several planned weeks, e.g. for a biweekly shop. Each item
+/// says how much of it every week needs.
+#[tracing::instrument(skip_all, fields(user = user.id))]
+pub async fn weeks_json(
+    template: Template,
+    State(app): State<AppState>,
+    RequirePremium(user): RequirePremium,
+    Query(input): Query<WeeksQuery>,
+) -> impl IntoResponse {
+    let preferences = imkitchen_web_shared::try_response!(anyhow:
+        app.identity.meal_preferences.load(&user.id),
+        template
+    );
+    let from_date: u64 = imkitchen_web_shared::try_response!(sync anyhow:
+        input.from.replace('-', "").parse().map_err(|e| anyhow::anyhow!("invalid from date: {e}")),
+        template
+    );
+    let items = imkitchen_web_shared::try_response!(
+        app.core.shopping.combined_weeks(
+            &user.id,
+            from_date,
+            input.weeks,
+            preferences.household_size
+        ),
+        template
+    );
+
+    Json(items).into_response()
+}
+
 #[tracing::instrument(skip_all, fields(user = user.id))]
 pub async fn generate_status(
     template: Template,
@@ -2,7 +2,9 @@ use axum::extract::{Path, State};
 use axum::response::{IntoResponse, Redirect};
 use axum_extra::extract::CookieJar;
 use imkitchen_core::mealplan::slot::SlotRow;
-use imkitchen_core::mealplan::{ChangeSlotRecipeStatus, Recipe};
+use imkitchen_core::mealplan::{
+    AdvanceCookingStep, ChangeSlotRecipeStatus, CompleteDay, PoolFilter, Recipe,
+};
 use imkitchen_types::mealplan::DaySlotStatus;
 use imkitchen_types::recipe::{IngredientUnitFormat, Instruction};
 use imkitchen_types::{mealplan::DaySlotRecipe, recipe::RecipeType};
@@ -39,11 +41,11 @@ pub struct OnboardingMenuTemplate {
     pub dessert_count: usize,
 }
 
-#[derive(Default, Clone)]
+#[derive(Clone)]
 pub struct KitchenWeekDay {
     pub date: String,
     pub day_num: u8,
-    pub weekday: String,
+    pub weekday: time::Weekday,
     pub is_today: bool,
     pub meal_types: Vec<RecipeType>,
 }
@@ -134,8 +136,8 @@ pub struct IngredientAisle {
     pub items: Vec<imkitchen_types::recipe::Ingredient>,
 }
 
-/// Scale a recipe's ingredient quantities to the meal-plan slot's household size
-/// and sort them by name. Shared by every kitchen screen that shows ingredients
+/// Scale a recipe's ingredient quantities to the meal-plan slot's household size,
+/// keeping the author's order. Shared by every kitchen screen that shows ingredients
 /// (dashboard, dish preview, and the cooking screen) so they stay consistent.
 fn scale_ingredients(
     recipe: &mut imkitchen_core::recipe::query::user::UserView,
@@ -145,43 +147,38 @@ fn scale_ingredients(
     // as the recipe's minimum: a recipe can't realistically be made for fewer
     // servings than it was written for (e.g. a whole chicken serves 4). So scale
     // to `max(recipe, slot)` — up for larger households, never below the recipe's
-    // own size. Guard the divisor since household size is an unvalidated field.
-    let recipe_household_size = recipe.household_size.max(1);
-    let serving_target = recipe_household_size.max(slot_household_size);
-    for ingredient in recipe.ingredients.iter_mut() {
-        ingredient.quantity = (ingredient.quantity as f64 * serving_target as f64
-            / recipe_household_size as f64)
-            .ceil() as u32;
-    }
-    recipe.ingredients.sort_by_key(|i| i.name.to_owned());
+    // own size.
+    let serving_target = recipe.household_size.max(1).max(slot_household_size);
+    recipe.ingredients.0 = recipe.scaled_ingredients(serving_target);
 }
 
-/// Group (already-scaled) ingredients into ordered aisle sections keyed by
-/// `shopping_<Category>`, mirroring the groceries page grouping.
+/// Group (already-scaled) ingredients into aisle sections keyed by
+/// `shopping_<Category>`, in the same store walk order as the groceries page.
 fn group_ingredients_by_aisle(
     ingredients: &[imkitchen_types::recipe::Ingredient],
 ) -> Vec<IngredientAisle> {
-    let mut categories: std::collections::HashMap<
-        String,
-        Vec<imkitchen_types::recipe::Ingredient>,
-    > = std::collections::HashMap::new();
+    use imkitchen_types::recipe::IngredientCategory;
+
+    let order = IngredientCategory::aisle_order(&[]);
+    let mut aisles: std::collections::BTreeMap<usize, IngredientAisle> =
+        std::collections::BTreeMap::new();
 
     for ingredient in ingredients.iter() {
-        let key = match &ingredient.category {
-            Some(c) => format!("shopping_{c}"),
-            None => "shopping_Unknown".to_owned(),
+        let (rank, name) = match &ingredient.category {
+            Some(c) => (c.aisle_rank(&order), format!("shopping_{c}")),
+            None => (usize::MAX, "shopping_Unknown".to_owned()),
         };
-        categories.entry(key).or_default().push(ingredient.clone());
+        aisles
+            .entry(rank)
+            .or_insert_with(|| IngredientAisle {
+                name,
+                items: vec![],
+            })
+            .items
+            .push(ingredient.clone());
     }
 
-    let mut aisles = categories
-        .into_iter()
-        .map(|(name, items)| IngredientAisle { name, items })
-        .collect::<Vec<_>>();
-
-    aisles.sort_by(|a, b| a.name.cmp(&b.name));
-
-    aisles
+    aisles.into_values().collect()
 }
 
 #[tracing::instrument(skip_all, fields(user = tracing::field::Empty))]
@@ -226,9 +223,11 @@ pub async fn page(
 
     if slot.is_none() {
         let main_courses = imkitchen_web_shared::try_page_response!(
-            app.core
-                .mealplan
-                .first_week_recipes(&user.id, RecipeType::MainCourse),
+            app.core.mealplan.first_week_recipes(
+                &user.id,
+                RecipeType::MainCourse,
+                &PoolFilter::default()
+            ),
             template
         );
 
@@ -244,21 +243,27 @@ pub async fn page(
         }
 
         let appetizers = imkitchen_web_shared::try_page_response!(
-            app.core
-                .mealplan
-                .first_week_recipes(&user.id, RecipeType::Appetizer),
+            app.core.mealplan.first_week_recipes(
+                &user.id,
+                RecipeType::Appetizer,
+                &PoolFilter::default()
+            ),
             template
         );
         let accompaniments = imkitchen_web_shared::try_page_response!(
-            app.core
-                .mealplan
-                .first_week_recipes(&user.id, RecipeType::Accompaniment),
+            app.core.mealplan.first_week_recipes(
+                &user.id,
+                RecipeType::Accompaniment,
+                &PoolFilter::default()
+            ),
             template
         );
         let desserts = imkitchen_web_shared::try_page_response!(
-            app.core
-                .mealplan
-                .first_week_recipes(&user.id, RecipeType::Dessert),
+            app.core.mealplan.first_week_recipes(
+                &user.id,
+                RecipeType::Dessert,
+                &PoolFilter::default()
+            ),
             template
         );
 
@@ -486,7 +491,7 @@ pub async fn page(
             KitchenWeekDay {
                 date: d.format(&fmt).unwrap_or_default(),
                 day_num: d.day(),
-                weekday: d.weekday().to_string().chars().take(3).collect(),
+                weekday: d.weekday(),
                 is_today: d_u64 == today_u64,
                 meal_types,
             }
@@ -540,6 +545,12 @@ pub struct CookingTemplate {
     /// as the first screen of the cooking flow instead of a step.
     pub show_ingredients: bool,
     pub ingredient_aisles: Vec<IngredientAisle>,
+    /// Every step is done: the last one is shown with a link back to the
+    /// kitchen instead of a button finishing it.
+    pub is_completed: bool,
+    /// When the current step's timer runs out (unix seconds), if it was
+    /// started by moving to that step.
+    pub next_step_at: Option<u64>,
 }
 
 // Fragment version of CookingTemplate — same fields, but renders only the
@@ -555,6 +566,8 @@ pub struct CookingScreenTemplate {
     pub show_iframe: bool,
     pub show_ingredients: bool,
     pub ingredient_aisles: Vec<IngredientAisle>,
+    pub is_completed: bool,
+    pub next_step_at: Option<u64>,
 }
 
 #[tracing::instrument(skip_all, fields(user = tracing::field::Empty))]
@@ -617,56 +630,43 @@ pub async fn update_slot_step_action(
     let mut slot_recipe = imkitchen_web_shared::try_page_response!(opt: app.core.recipe.find_user(&recipe_id), template);
     scale_ingredients(&mut slot_recipe, slot.household_size);
 
-    // `Idle` is the ingredients screen; `Cooking(0)` is the first instruction,
-    // `Cooking(len-2)` the second-to-last, and `Completed` the last.
-    let len = slot_recipe.instructions.len();
-    let slot_recipe_status = match (direction.as_str(), slot_recipe_status) {
-        ("prev", DaySlotStatus::Idle) => DaySlotStatus::Idle,
-        ("prev", DaySlotStatus::Cooking(pos)) => {
-            if *pos == 0 {
-                DaySlotStatus::Idle
-            } else {
-                DaySlotStatus::Cooking(pos - 1)
-            }
-        }
-        ("prev", DaySlotStatus::Completed) => {
-            if len <= 1 {
-                DaySlotStatus::Idle
-            } else {
-                DaySlotStatus::Cooking((len - 2) as u8)
-            }
-        }
-        ("next", DaySlotStatus::Idle) => {
-            if len <= 1 {
-                DaySlotStatus::Completed
-            } else {
-                DaySlotStatus::Cooking(0)
-            }
-        }
-        ("next", DaySlotStatus::Cooking(pos)) => {
-            if ((*pos + 1) as usize) < len - 1 {
-                DaySlotStatus::Cooking(pos + 1)
-            } else {
-                DaySlotStatus::Completed
-            }
-        }
-        ("next", DaySlotStatus::Completed) => DaySlotStatus::Completed,
-        _ => slot_recipe_status.clone(),
-    };
-
+    // `Idle` is the ingredients screen and `Cooking(pos)` instruction `pos`;
+    // finishing the last one completes the recipe.
     let bounds_date = imkitchen_core::mealplan::date_to_u64(bounds.date);
-
-    imkitchen_web_shared::try_response!(
-        app.core
-            .mealplan
-            .change_slot_recipe_status(ChangeSlotRecipeStatus {
+    let len = slot_recipe.instructions.len();
+    let (slot_recipe_status, next_step_at) = if direction == "next" {
+        let step = imkitchen_web_shared::try_response!(
+            app.core.mealplan.advance_cooking_step(AdvanceCookingStep {
                 user_id: user.id.to_owned(),
                 date: bounds_date,
                 recipe_id: recipe_id.clone(),
-                status: slot_recipe_status.clone()
             }),
-        template
-    );
+            template
+        );
+
+        (step.status, step.next_step_at)
+    } else {
+        let status = match slot_recipe_status {
+            DaySlotStatus::Cooking(0) => DaySlotStatus::Idle,
+            DaySlotStatus::Cooking(pos) => DaySlotStatus::Cooking(pos - 1),
+            DaySlotStatus::Completed if len > 0 => DaySlotStatus::Cooking((len - 1) as u8),
+            _ => DaySlotStatus::Idle,
+        };
+
+        imkitchen_web_shared::try_response!(
+            app.core
+                .mealplan
+                .change_slot_recipe_status(ChangeSlotRecipeStatus {
+                    user_id: user.id.to_owned(),
+                    date: bounds_date,
+                    recipe_id: recipe_id.clone(),
+                    status: status.clone()
+                }),
+            template
+        );
+
+        (status, None)
+    };
 
     // Compute view state from the NEW status in-memory — re-reading the projection
     // here would race with evento's async projection update and show stale state
@@ -744,6 +744,8 @@ pub async fn update_slot_step_action(
             show_iframe,
             show_ingredients,
             ingredient_aisles,
+            is_completed: matches!(slot_recipe_status, DaySlotStatus::Completed),
+            next_step_at,
         })
         .into_response()
 }
@@ -1029,10 +1031,35 @@ pub async fn cook_page(
             show_iframe,
             show_ingredients,
             ingredient_aisles,
+            is_completed: matches!(slot_recipe_status, DaySlotStatus::Completed),
+            next_step_at: None,
         })
         .into_response()
 }
 
+/// Marks every course of the day as cooked in one go.
+#[tracing::instrument(skip_all, fields(user = tracing::field::Empty))]
+pub async fn complete_day(
+    template: Template,
+    RequirePremium(user): RequirePremium,
+    State(app): State<AppState>,
+    Path((date,)): Path<(String,)>,
+) -> impl IntoResponse {
+    tracing::Span::current().record("user", &user.id);
+
+    let bounds = imkitchen_web_shared::try_page_response!(sync: imkitchen_core::mealplan::month_bounds_from_date(&date, &user.tz), template);
+
+    imkitchen_web_shared::try_response!(
+        app.core.mealplan.complete_day(CompleteDay {
+            user_id: user.id.to_owned(),
+            date: imkitchen_core::mealplan::date_to_u64(bounds.date),
+        }),
+        template
+    );
+
+    Redirect::to(&format!("/kitchen/{date}")).into_response()
+}
+
 pub fn routes() -> axum::Router<imkitchen_web_shared::AppState> {
     use axum::routing::{get, post};
     axum::Router::new()
@@ -1043,5 +1070,6 @@ pub fn routes() -> axum::Router<imkitchen_web_shared::AppState> {
         )
         .route("/kitchen/{date}/{recipe_id}/select-dish", post(select_dish))
         .route("/kitchen/{date}/{recipe_id}/cook", get(cook_page))
+        .route("/kitchen/{date}/complete", post(complete_day))
         .route("/kitchen/{date}", get(kitchen_page))
 }
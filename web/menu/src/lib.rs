@@ -1,13 +1,24 @@
 use axum::{
+    Json,
     extract::{Path, State},
+    http::header,
     response::{IntoResponse, Redirect},
 };
-use imkitchen_core::mealplan::{Generate, Randomize, slot::SlotRow};
-use time::OffsetDateTime;
+use axum_extra::extract::Form;
+use imkitchen_core::mealplan::{
+    ChangeSlotServings, Generate, MarkAdvancePrep, ReplaceMeal, SkipWeek, SwapMeals, UnskipWeek,
+    conflict::EquipmentConflict, nutrition::NutritionTotals, slot::SlotRow,
+};
+use imkitchen_types::meal_preferences::UserConstraints;
+use imkitchen_types::recipe::RecipeType;
+use serde::Deserialize;
+use std::collections::HashMap;
+use time::{Duration, OffsetDateTime};
 
 use imkitchen_web_shared::{
     AppState,
     auth::{AuthUser, RequirePremium},
+    config::{Config, LEFTOVER_PLANNING},
     template::{Status as TemplateStatus, Template, filters},
 };
 
@@ -16,21 +27,28 @@ pub struct MenuSlot {
     pub slot: Option<SlotRow>,
 }
 
-#[derive(Default, Clone)]
+#[derive(Clone)]
 pub struct MenuBoardDay {
     pub date: String,
-    pub weekday: String,
+    pub weekday: time::Weekday,
     pub day_num: u8,
     pub is_today: bool,
     pub is_past: bool,
     pub is_in_month: bool,
     pub slot: Option<SlotRow>,
+    /// Appliances the day's courses double-book.
+    pub conflicts: Vec<EquipmentConflict>,
+    /// Falls in a week set aside with `skip_week`.
+    pub is_skipped: bool,
 }
 
 #[derive(askama::Template)]
 #[template(path = "partials/menu-regenerate-modal.html")]
 pub struct GenerateModalTemplate {
     pub date: String,
+    /// Days already planned in the generated range whose main course can be
+    /// kept: (YYYY-MM-DD, main course name).
+    pub planned: Vec<(String, String)>,
 }
 
 #[derive(askama::Template)]
@@ -57,6 +75,18 @@ pub struct MenuTemplate {
     /// Recipe id → slug for every recipe shown, so course cards can link to the
     /// canonical `/r/{slug}` detail page. Missing ids fall back to the id.
     pub slugs: std::collections::HashMap<String, String>,
+    /// Planned recipe ids that have been deleted since the plan was generated.
+    pub removed: std::collections::HashSet<String>,
+    /// Per-person nutrition of the month, with how many meals it covers.
+    pub nutrition: NutritionTotals,
+    /// How many times the usual amount the selected day's main course is
+    /// cooked for.
+    pub main_course_servings: f32,
+    /// The selected day's week was set aside with `skip_week`.
+    pub is_week_skipped: bool,
+    /// Other upcoming days of the month the selected main course can swap
+    /// with: (YYYY-MM-DD, main course name).
+    pub swap_days: Vec<(String, String)>,
 }
 
 impl MenuTemplate {
@@ -65,6 +95,16 @@ impl MenuTemplate {
     pub fn dish_slug<'a>(&'a self, id: &'a str) -> &'a str {
         self.slugs.get(id).map(String::as_str).unwrap_or(id)
     }
+
+    /// Removed recipes render a placeholder with a replace button instead of
+    /// a link to their (now missing) detail page.
+    pub fn is_removed(&self, id: &str) -> bool {
+        self.removed.contains(id)
+    }
+
+    pub fn is_main_course_servings(&self, multiplier: &f32) -> bool {
+        self.main_course_servings == *multiplier
+    }
 }
 
 impl Default for MenuTemplate {
@@ -82,6 +122,11 @@ impl Default for MenuTemplate {
             next_month: "".to_owned(),
             board_weeks: vec![],
             slugs: std::collections::HashMap::new(),
+            removed: std::collections::HashSet::new(),
+            nutrition: NutritionTotals::default(),
+            main_course_servings: 1.0,
+            is_week_skipped: false,
+            swap_days: vec![],
         }
     }
 }
@@ -126,11 +171,46 @@ pub async fn page(
         template
     );
 
+    let preferences = imkitchen_web_shared::try_page_response!(
+        app.identity.meal_preferences.load(&user.id),
+        template
+    );
+    let constraints =
+        imkitchen_web_shared::try_page_response!(sync: preferences.constraints(), template);
+    let conflicts = imkitchen_web_shared::try_page_response!(
+        app.core.mealplan.conflicts(
+            &user.id,
+            bounds.first,
+            bounds.last,
+            &constraints.equipment_capacity
+        ),
+        template
+    );
+
+    let skipped_weeks = imkitchen_web_shared::try_page_response!(
+        app.core.mealplan.skipped_weeks(&user.id),
+        template
+    );
+
+    let nutrition = imkitchen_web_shared::try_page_response!(
+        app.core
+            .mealplan
+            .nutrition(&user.id, bounds.first, bounds.last),
+        template
+    );
+
+    let recipe_ids = slot_recipe_ids(&slots);
     let slugs = imkitchen_web_shared::try_page_response!(
-        app.core.recipe.slugs(slot_recipe_ids(&slots)),
+        app.core.recipe.slugs(recipe_ids.to_vec()),
         template
     );
 
+    // Deleted recipes lose their `recipe_user` row, hence their slug.
+    let removed = recipe_ids
+        .into_iter()
+        .filter(|id| !slugs.contains_key(id))
+        .collect();
+
     let mut menu_slots = imkitchen_core::mealplan::week_days_before(bounds.first)
         .iter()
         .map(|date| MenuSlot {
@@ -179,12 +259,46 @@ pub async fn page(
 
     let selected_day = selected_slot.as_ref().map(|s| s.day).unwrap_or(0);
 
+    let main_course_servings = match selected_slot.as_ref() {
+        Some(slot) => {
+            let date = imkitchen_web_shared::try_page_response!(sync:
+                OffsetDateTime::from_unix_timestamp(slot.day as i64),
+                template
+            );
+            let servings = imkitchen_web_shared::try_page_response!(
+                app.core
+                    .shopping
+                    .day_servings(&user.id, imkitchen_core::mealplan::date_to_u64(date)),
+                template
+            );
+
+            servings.get(&slot.main_course.id).copied().unwrap_or(1.0)
+        }
+        _ => 1.0,
+    };
+
     let today = imkitchen_core::mealplan::now(&user.tz);
     let today_u64 = imkitchen_core::mealplan::date_to_u64(today);
     let is_past = imkitchen_core::mealplan::date_to_u64(bounds.date) < today_u64;
 
     let fmt = time::macros::format_description!("[year]-[month]-[day]");
     let current_date = bounds.date.format(&fmt).unwrap_or_default();
+    let swap_days = slots
+        .iter()
+        .filter(|slot| Some(slot.day) != selected_slot.as_ref().map(|s| s.day))
+        .filter_map(|slot| {
+            let date = OffsetDateTime::from_unix_timestamp(slot.day as i64).ok()?;
+            (imkitchen_core::mealplan::date_to_u64(date) >= today_u64).then(|| {
+                (
+                    date.format(&fmt).unwrap_or_default(),
+                    slot.main_course.name.to_owned(),
+                )
+            })
+        })
+        .collect();
+    let is_week_skipped =
+        imkitchen_core::mealplan::week_dates(imkitchen_core::mealplan::date_to_u64(bounds.date))
+            .is_some_and(|week| skipped_weeks.contains(&week[0]));
 
     // ── Desktop week-board: walk the same Mon–Sun-padded date sequence the
     // calendar uses, but build rich cells (date string for URL, weekday label,
@@ -211,14 +325,27 @@ pub async fn page(
                         .unwrap_or(false)
                 })
                 .cloned();
+            let conflicts = slot
+                .as_ref()
+                .map(|slot| {
+                    conflicts
+                        .iter()
+                        .filter(|c| c.day == slot.day)
+                        .cloned()
+                        .collect()
+                })
+                .unwrap_or_default();
             MenuBoardDay {
                 date: d.format(&fmt).unwrap_or_default(),
-                weekday: d.weekday().to_string().chars().take(3).collect(),
+                weekday: d.weekday(),
                 day_num: d.day(),
                 is_today: d_u64 == today_u64,
                 is_past: d_u64 < today_u64,
                 is_in_month: d.month() == bounds_month,
                 slot,
+                conflicts,
+                is_skipped: imkitchen_core::mealplan::week_dates(d_u64)
+                    .is_some_and(|week| skipped_weeks.contains(&week[0])),
             }
         })
         .collect();
@@ -238,52 +365,139 @@ pub async fn page(
             selected_day,
             board_weeks,
             slugs,
+            removed,
+            nutrition,
+            main_course_servings,
+            is_week_skipped,
+            swap_days,
             ..Default::default()
         })
         .into_response()
 }
 
+/// What generating a month asks for, from the user's stored constraints.
+/// Leftovers are only planned while [`LEFTOVER_PLANNING`] is rolled out to
+/// the user. `no_repeats` is asked for in the generate form instead.
+pub fn generate_input(
+    config: &Config,
+    user_id: &str,
+    start: u64,
+    days: u8,
+    constraints: &UserConstraints,
+) -> Generate {
+    Generate {
+        start,
+        days,
+        user_id: user_id.to_owned(),
+        snapshot_recipes: true,
+        allow_leftovers: constraints.allow_leftovers
+            && config.is_feature_enabled(user_id, LEFTOVER_PLANNING),
+        ..Generate::from(constraints)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct GenerateInput {
+    /// Plans every main course once before any of them comes back.
+    #[serde(default)]
+    pub no_repeats: bool,
+    /// Days (YYYY-MM-DD) whose planned main course is pinned there instead
+    /// of being picked again.
+    #[serde(default)]
+    pub keep: Vec<String>,
+}
+
+/// First day and number of days generating from `date`'s month covers: the
+/// rest of the month, from today when it's the current one.
+fn generate_span(date: &str, tz: &str) -> anyhow::Result<(OffsetDateTime, u8)> {
+    let bounds = imkitchen_core::mealplan::month_bounds_from_date(date, tz)?;
+    let now_bounds = imkitchen_core::mealplan::month_bounds_from_now(tz)?;
+    let (target_local, last_day) = if now_bounds.date > bounds.date {
+        (now_bounds.date, now_bounds.last.day())
+    } else {
+        (bounds.date, bounds.last.day())
+    };
+    // Use user-tz noon as start so date_to_u64(from_unix_timestamp(start)) yields
+    // the user-tz date — from_unix_timestamp always returns UTC, so encoding start
+    // at user-tz midnight gives the wrong UTC day for any non-UTC user (e.g. in
+    // Martinique UTC-4 evening, midnight rolls into the next UTC day, infinite-polling
+    // on a slot stored under tomorrow's date).
+    let start = time::PrimitiveDateTime::new(target_local.date(), time::macros::time!(12:00))
+        .assume_offset(target_local.offset());
+
+    Ok((start, last_day - target_local.date().day() + 1))
+}
+
+/// Whether the slot's main course can be pinned when generating again.
+/// Community suggestions aren't the user's recipes, and a leftover day eats
+/// a batch cooked earlier.
+fn is_keepable(slot: &SlotRow) -> bool {
+    !slot.suggested && slot.leftover_of.is_none()
+}
+
+/// A slot's day as YYYY-MM-DD and YYYYMMDD.
+fn slot_day(slot: &SlotRow) -> Option<(String, u64)> {
+    let date = OffsetDateTime::from_unix_timestamp(slot.day as i64).ok()?;
+    let fmt = time::macros::format_description!("[year]-[month]-[day]");
+
+    Some((
+        date.format(&fmt).ok()?,
+        imkitchen_core::mealplan::date_to_u64(date),
+    ))
+}
+
 #[tracing::instrument(skip_all, fields(user = user.id))]
 pub async fn generate_action(
     template: Template,
     State(app): State<AppState>,
     RequirePremium(user): RequirePremium,
     Path((date,)): Path<(String,)>,
+    Form(input): Form<GenerateInput>,
 ) -> impl IntoResponse {
     let preferences = imkitchen_web_shared::try_response!(anyhow:
         app.identity.meal_preferences.load(&user.id),
         template
     );
 
-    let randomize = Some(Randomize {
-        cuisine_variety_weight: preferences.cuisine_variety_weight,
-        dietary_restrictions: preferences.dietary_restrictions.to_vec(),
-    });
+    let constraints =
+        imkitchen_web_shared::try_response!(sync anyhow: preferences.constraints(), template);
 
-    let bounds = imkitchen_web_shared::try_response!(sync anyhow: imkitchen_core::mealplan::month_bounds_from_date(&date, &user.tz), template);
-    let now_bounds = imkitchen_web_shared::try_response!(sync anyhow: imkitchen_core::mealplan::month_bounds_from_now(&user.tz), template);
-    let (target_local, last_day) = if now_bounds.date > bounds.date {
-        (now_bounds.date, now_bounds.last.day())
+    let (start, days) =
+        imkitchen_web_shared::try_response!(sync anyhow: generate_span(&date, &user.tz), template);
+    let pinned = if input.keep.is_empty() {
+        HashMap::new()
     } else {
-        (bounds.date, bounds.last.day())
+        let slots = imkitchen_web_shared::try_response!(anyhow:
+            app.core.mealplan.range(&user.id, start, start + Duration::days(days as i64 - 1)),
+            template
+        );
+
+        slots
+            .iter()
+            .filter(|slot| is_keepable(slot))
+            .filter_map(|slot| {
+                let (day, date) = slot_day(slot)?;
+                input.keep.contains(&day).then(|| {
+                    (
+                        (date, RecipeType::MainCourse),
+                        slot.main_course.id.to_owned(),
+                    )
+                })
+            })
+            .collect()
     };
-    // Use user-tz noon as start so date_to_u64(from_unix_timestamp(start)) yields
-    // the user-tz date — from_unix_timestamp always returns UTC, so encoding start
-    // at user-tz midnight gives the wrong UTC day for any non-UTC user (e.g. in
-    // Martinique UTC-4 evening, midnight rolls into the next UTC day, infinite-polling
-    // on a slot stored under tomorrow's date).
-    let start_noon = time::PrimitiveDateTime::new(target_local.date(), time::macros::time!(12:00))
-        .assume_offset(target_local.offset());
-    let start = start_noon.unix_timestamp();
-    let days = last_day - target_local.date().day() + 1;
 
     imkitchen_web_shared::try_response!(
         app.core.mealplan.generate(Generate {
-            start: start as u64,
-            days,
-            user_id: user.id.to_owned(),
-            randomize,
-            household_size: preferences.household_size,
+            no_repeats: input.no_repeats,
+            pinned,
+            ..generate_input(
+                &app.config,
+                &user.id,
+                start.unix_timestamp() as u64,
+                days,
+                &constraints
+            )
         }),
         template
     );
@@ -344,15 +558,252 @@ pub async fn generate_status(
         .into_response()
 }
 
+#[derive(Deserialize)]
+pub struct ReplaceInput {
+    /// Minutes available to cook tonight; 0 for no limit.
+    #[serde(default)]
+    pub available_minutes: u16,
+}
+
+#[tracing::instrument(skip_all, fields(user = user.id))]
+pub async fn replace_action(
+    template: Template,
+    State(app): State<AppState>,
+    RequirePremium(user): RequirePremium,
+    Path((date, recipe_type)): Path<(String, RecipeType)>,
+    Form(input): Form<ReplaceInput>,
+) -> impl IntoResponse {
+    let bounds = imkitchen_web_shared::try_response!(sync anyhow: imkitchen_core::mealplan::month_bounds_from_date(&date, &user.tz), template);
+
+    let preferences = imkitchen_web_shared::try_response!(anyhow:
+        app.identity.meal_preferences.load(&user.id),
+        template
+    );
+
+    let constraints =
+        imkitchen_web_shared::try_response!(sync anyhow: preferences.constraints(), template);
+
+    imkitchen_web_shared::try_response!(
+        app.core.mealplan.replace_meal(ReplaceMeal {
+            user_id: user.id.to_owned(),
+            date: imkitchen_core::mealplan::date_to_u64(bounds.date),
+            recipe_type,
+            available_minutes: (input.available_minutes > 0).then_some(input.available_minutes),
+            min_days_since_use: constraints.min_days_since_use,
+        }),
+        template
+    );
+
+    Redirect::to(&format!("/menu/{date}")).into_response()
+}
+
+#[derive(Deserialize)]
+pub struct ServingsInput {
+    /// 1 goes back to the household size.
+    pub multiplier: f32,
+}
+
+#[tracing::instrument(skip_all, fields(user = user.id))]
+pub async fn servings_action(
+    template: Template,
+    State(app): State<AppState>,
+    RequirePremium(user): RequirePremium,
+    Path((date, recipe_type)): Path<(String, RecipeType)>,
+    Form(input): Form<ServingsInput>,
+) -> impl IntoResponse {
+    let bounds = imkitchen_web_shared::try_response!(sync anyhow: imkitchen_core::mealplan::month_bounds_from_date(&date, &user.tz), template);
+
+    imkitchen_web_shared::try_response!(
+        app.core.mealplan.change_slot_servings(ChangeSlotServings {
+            user_id: user.id.to_owned(),
+            date: imkitchen_core::mealplan::date_to_u64(bounds.date),
+            recipe_type,
+            multiplier: input.multiplier,
+        }),
+        template
+    );
+
+    Redirect::to(&format!("/menu/{date}")).into_response()
+}
+
+#[derive(Deserialize)]
+pub struct SwapInput {
+    /// YYYY-MM-DD
+    pub other_date: String,
+}
+
+#[tracing::instrument(skip_all, fields(user = user.id))]
+pub async fn swap_action(
+    template: Template,
+    State(app): State<AppState>,
+    RequirePremium(user): RequirePremium,
+    Path((date, recipe_type)): Path<(String, RecipeType)>,
+    Form(input): Form<SwapInput>,
+) -> impl IntoResponse {
+    let bounds = imkitchen_web_shared::try_response!(sync anyhow: imkitchen_core::mealplan::month_bounds_from_date(&date, &user.tz), template);
+    let other_bounds = imkitchen_web_shared::try_response!(sync anyhow: imkitchen_core::mealplan::month_bounds_from_date(&input.other_date, &user.tz), template);
+
+    imkitchen_web_shared::try_response!(
+        app.core.mealplan.swap_meals(SwapMeals {
+            user_id: user.id.to_owned(),
+            date: imkitchen_core::mealplan::date_to_u64(bounds.date),
+            recipe_type,
+            other_date: imkitchen_core::mealplan::date_to_u64(other_bounds.date),
+        }),
+        template
+    );
+
+    Redirect::to(&format!("/menu/{date}")).into_response()
+}
+
+pub async fn advance_prep_action(
+    template: Template,
+    State(app): State<AppState>,
+    RequirePremium(user): RequirePremium,
+    Path((date, recipe_id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    let bounds = imkitchen_web_shared::try_response!(sync anyhow: imkitchen_core::mealplan::month_bounds_from_date(&date, &user.tz), template);
+
+    imkitchen_web_shared::try_response!(
+        app.core.mealplan.mark_advance_prep(MarkAdvancePrep {
+            user_id: user.id.to_owned(),
+            date: imkitchen_core::mealplan::date_to_u64(bounds.date),
+            recipe_id,
+            done: true,
+        }),
+        template
+    );
+
+    Redirect::to(&format!("/menu/{date}")).into_response()
+}
+
+#[tracing::instrument(skip_all, fields(user = user.id))]
+pub async fn skip_week_action(
+    template: Template,
+    State(app): State<AppState>,
+    user: AuthUser,
+    Path((date,)): Path<(String,)>,
+) -> impl IntoResponse {
+    let bounds = imkitchen_web_shared::try_response!(sync anyhow: imkitchen_core::mealplan::month_bounds_from_date(&date, &user.tz), template);
+
+    imkitchen_web_shared::try_response!(
+        app.core.mealplan.skip_week(SkipWeek {
+            user_id: user.id.to_owned(),
+            date: imkitchen_core::mealplan::date_to_u64(bounds.date),
+        }),
+        template
+    );
+
+    Redirect::to(&format!("/menu/{date}")).into_response()
+}
+
+#[tracing::instrument(skip_all, fields(user = user.id))]
+pub async fn unskip_week_action(
+    template: Template,
+    State(app): State<AppState>,
+    user: AuthUser,
+    Path((date,)): Path<(String,)>,
+) -> impl IntoResponse {
+    let bounds = imkitchen_web_shared::try_response!(sync anyhow: imkitchen_core::mealplan::month_bounds_from_date(&date, &user.tz), template);
+
+    imkitchen_web_shared::try_response!(
+        app.core.mealplan.unskip_week(UnskipWeek {
+            user_id: user.id.to_owned(),
+            date: imkitchen_core::mealplan::date_to_u64(bounds.date),
+        }),
+        template
+    );
+
+    Redirect::to(&format!("/menu/{date}")).into_response()
+}
+
+/// The week (Monday to Sunday) around `date` as an iCalendar file.
+#[tracing::instrument(skip_all, fields(user = user.id))]
+pub async fn week_ics(
+    template: Template,
+    user: AuthUser,
+    State(app): State<AppState>,
+    Path((date,)): Path<(String,)>,
+) -> impl IntoResponse {
+    let bounds = imkitchen_web_shared::try_page_response!(sync: imkitchen_core::mealplan::month_bounds_from_date(&date, &user.tz), template);
+    let monday = imkitchen_core::mealplan::week_days_before(bounds.date)
+        .first()
+        .copied()
+        .unwrap_or(bounds.date);
+    let sunday = imkitchen_core::mealplan::week_days_after(bounds.date)
+        .last()
+        .copied()
+        .unwrap_or(bounds.date);
+    let ics = imkitchen_web_shared::try_page_response!(
+        app.core.mealplan.ics(&user.id, monday, sunday),
+        template
+    );
+
+    (
+        [
+            (
+                header::CONTENT_TYPE,
+                imkitchen_core::mealplan::ics::ICS_CONTENT_TYPE.to_owned(),
+            ),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"imkitchen-{date}.ics\""),
+            ),
+        ],
+        ics,
+    )
+        .into_response()
+}
+
+/// The plan of the week `index` weeks away from the current one (0 for this
+/// week, -1 for the previous one) as JSON, for dashboards and scripts.
+#[tracing::instrument(skip_all, fields(user = user.id))]
+pub async fn week_json(
+    template: Template,
+    user: AuthUser,
+    State(app): State<AppState>,
+    Path((index,)): Path<(i32,)>,
+) -> impl IntoResponse {
+    let today = imkitchen_core::mealplan::now(&user.tz);
+    let monday = imkitchen_core::mealplan::week_days_before(today)
+        .first()
+        .copied()
+        .unwrap_or(today)
+        + time::Duration::weeks(index.into());
+    let week = imkitchen_web_shared::try_response!(anyhow:
+        app.core.mealplan.week(&user.id, monday),
+        template
+    );
+
+    Json(week).into_response()
+}
+
 pub async fn generate_modal(
     template: Template,
+    user: AuthUser,
+    State(app): State<AppState>,
     Path((date,)): Path<(String,)>,
 ) -> impl IntoResponse {
-    template.render(GenerateModalTemplate { date })
+    let (start, days) =
+        imkitchen_web_shared::try_response!(sync anyhow: generate_span(&date, &user.tz), template);
+    let slots = imkitchen_web_shared::try_response!(anyhow:
+        app.core.mealplan.range(&user.id, start, start + Duration::days(days as i64 - 1)),
+        template
+    );
+
+    let planned = slots
+        .iter()
+        .filter(|slot| is_keepable(slot))
+        .filter_map(|slot| Some((slot_day(slot)?.0, slot.main_course.name.to_owned())))
+        .collect();
+
+    template
+        .render(GenerateModalTemplate { date, planned })
+        .into_response()
 }
 
 pub fn routes() -> axum::Router<imkitchen_web_shared::AppState> {
-    use axum::routing::get;
+    use axum::routing::{get, post};
     axum::Router::new()
         .route("/menu", get(page))
         .route("/menu/{date}", get(page))
@@ -361,4 +812,15 @@ pub fn routes() -> axum::Router<imkitchen_web_shared::AppState> {
             get(generate_modal).post(generate_action),
         )
         .route("/menu/{date}/generate/status", get(generate_status))
+        .route("/menu/{date}/week.ics", get(week_ics))
+        .route("/api/calendar/week/{index}", get(week_json))
+        .route("/menu/{date}/replace/{recipe_type}", post(replace_action))
+        .route("/menu/{date}/servings/{recipe_type}", post(servings_action))
+        .route("/menu/{date}/swap/{recipe_type}", post(swap_action))
+        .route("/menu/{date}/skip-week", post(skip_week_action))
+        .route("/menu/{date}/unskip-week", post(unskip_week_action))
+        .route(
+            "/menu/{date}/advance-prep/{recipe_id}",
+            post(advance_prep_action),
+        )
 }
@@ -0,0 +1,32 @@
+use imkitchen_types::meal_preferences::UserConstraints;
+use imkitchen_web_menu::generate_input;
+use imkitchen_web_shared::config::Config;
+
+fn with_features(features: &str) -> anyhow::Result<Config> {
+    let mut config = Config::load(None)?;
+    config.features = serde_json::from_str(features)?;
+
+    Ok(config)
+}
+
+#[test]
+fn test_leftovers_follow_the_feature_flag() -> anyhow::Result<()> {
+    let constraints = UserConstraints {
+        allow_leftovers: true,
+        ..Default::default()
+    };
+
+    let config = with_features(r#"{"leftover_planning": ["john"]}"#)?;
+    assert!(generate_input(&config, "john", 0, 7, &constraints).allow_leftovers);
+    assert!(!generate_input(&config, "albert", 0, 7, &constraints).allow_leftovers);
+
+    let config = with_features("{}")?;
+    assert!(!generate_input(&config, "john", 0, 7, &constraints).allow_leftovers);
+
+    // The flag only lets users have leftovers, it doesn't turn them on.
+    let config = with_features(r#"{"leftover_planning": ["*"]}"#)?;
+    let input = generate_input(&config, "john", 0, 7, &UserConstraints::default());
+    assert!(!input.allow_leftovers);
+
+    Ok(())
+}
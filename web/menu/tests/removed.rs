@@ -0,0 +1,60 @@
+use std::any::Any;
+use std::collections::{HashMap, HashSet};
+
+use askama::Template;
+use imkitchen_core::mealplan::slot::SlotRow;
+use imkitchen_types::mealplan::DaySlotRecipe;
+use imkitchen_web_menu::MenuTemplate;
+use imkitchen_web_shared::config::Config;
+
+fn render(template: MenuTemplate) -> anyhow::Result<String> {
+    let mut values: HashMap<&str, Box<dyn Any>> = HashMap::new();
+    values.insert("preferred_language", Box::new("en".to_owned()));
+    values.insert("preferred_language_iso", Box::new("en".to_owned()));
+    values.insert("config", Box::new(Config::load(None)?));
+    values.insert("is_demo", Box::new(false));
+    values.insert("is_dev", Box::new(false));
+
+    Ok(template.render_with_values(&values)?)
+}
+
+#[test]
+fn test_removed_recipe_renders_replace_button() -> anyhow::Result<()> {
+    // 2026-03-02 at midnight UTC.
+    let day = 1772409600;
+    let slot = SlotRow {
+        day,
+        household_size: 4,
+        main_course: DaySlotRecipe {
+            id: "curry".to_owned(),
+            name: "Curry".to_owned(),
+            ..Default::default()
+        }
+        .into(),
+        ..Default::default()
+    };
+
+    let html = render(MenuTemplate {
+        selected_slot: Some(slot.clone()),
+        selected_day: day,
+        removed: HashSet::from(["curry".to_owned()]),
+        ..Default::default()
+    })?;
+
+    assert!(html.contains("Recipe removed"));
+    assert!(html.contains(r#"action="/menu/2026-03-02/replace/MainCourse""#));
+    assert!(!html.contains(r#"href="/r/curry""#));
+
+    let html = render(MenuTemplate {
+        selected_slot: Some(slot),
+        selected_day: day,
+        slugs: HashMap::from([("curry".to_owned(), "curry-abc".to_owned())]),
+        ..Default::default()
+    })?;
+
+    assert!(!html.contains("Recipe removed"));
+    assert!(!html.contains("/replace/MainCourse"));
+    assert!(html.contains(r#"href="/r/curry-abc""#));
+
+    Ok(())
+}
@@ -3,7 +3,7 @@ pub use imkitchen_web_shared::config;
 pub mod routes;
 
 pub fn routes() -> axum::Router<imkitchen_web_shared::AppState> {
-    use axum::routing::get;
+    use axum::routing::{get, post};
     axum::Router::new()
         .route(
             "/upgrade",
@@ -38,6 +38,16 @@ pub fn routes() -> axum::Router<imkitchen_web_shared::AppState> {
             "/reset-password/new/{id}",
             get(routes::reset_password::new_page).post(routes::reset_password::new_action),
         )
+        .route(
+            "/passkey/register/options",
+            post(routes::passkey::register_options),
+        )
+        .route("/passkey/register", post(routes::passkey::register_action))
+        .route(
+            "/passkey/login/options",
+            post(routes::passkey::login_options),
+        )
+        .route("/passkey/login", post(routes::passkey::login_action))
         .route("/logout", get(routes::login::logout))
         .route("/sw.js", get(routes::assets::service_worker))
         .route("/manifest.json", get(routes::assets::manifest))
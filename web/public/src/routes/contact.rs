@@ -2,6 +2,7 @@ use std::str::FromStr;
 
 use axum::{
     extract::{Form, State},
+    http::HeaderMap,
     response::IntoResponse,
 };
 use imkitchen_core::contact::SubmitFormInput;
@@ -33,6 +34,7 @@ pub struct ActionInput {
 pub async fn action(
     template: Template,
     State(app): State<AppState>,
+    headers: HeaderMap,
     Form(input): Form<ActionInput>,
 ) -> impl IntoResponse {
     let Ok(subject) = Subject::from_str(&input.subject) else {
@@ -40,16 +42,19 @@ pub async fn action(
             original: None,
             message: "invalid subject",
             description: None,
+            link: None,
         });
     };
 
     imkitchen_web_shared::try_response!(
         app.core.contact.submit_form(SubmitFormInput {
-            to: app.config.email.contact_address,
+            to: app.config.email.contact_address_for(&subject).to_owned(),
             name: input.name,
             email: input.email,
             subject,
             message: input.message,
+            lang: template.preferred_language.to_owned(),
+            ip: super::client_ip(&headers),
         },),
         template
     );
@@ -60,6 +60,7 @@ pub async fn action(
                     original: None,
                     message: SERVER_ERROR_MESSAGE,
                     description: None,
+                    link: None,
                 })
                 .into_response();
         }
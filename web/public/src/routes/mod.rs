@@ -1,3 +1,5 @@
+use axum::http::HeaderMap;
+
 pub mod about;
 pub mod assets;
 pub mod contact;
@@ -5,8 +7,22 @@ pub mod health;
 pub mod help;
 pub mod legal;
 pub mod login;
+pub mod passkey;
 pub mod policy;
 pub mod register;
 pub mod reset_password;
 pub mod terms;
 pub mod upgrade;
+
+/// The address our ingress appended to `X-Forwarded-For`. Entries before it
+/// come from the client and can't be trusted for rate limiting.
+pub(crate) fn client_ip(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get("x-forwarded-for")?
+        .to_str()
+        .ok()?
+        .rsplit(',')
+        .next()
+        .map(|ip| ip.trim().to_owned())
+        .filter(|ip| !ip.is_empty())
+}
@@ -0,0 +1,186 @@
+use axum::Json;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::IntoResponse;
+use axum_extra::TypedHeader;
+use axum_extra::extract::CookieJar;
+use axum_extra::headers::UserAgent;
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use imkitchen_identity::passkey::{AuthenticateInput, ChallengeOptions, RegisterInput};
+use serde::{Deserialize, Serialize};
+
+use imkitchen_web_shared::AppState;
+use imkitchen_web_shared::auth::{AuthUser, build_cookie};
+use imkitchen_web_shared::template::{SERVER_ERROR_MESSAGE, Template, ToastErrorTemplate};
+
+#[derive(Serialize)]
+pub struct OptionsJson {
+    pub id: String,
+    pub challenge: String,
+    pub rp_id: String,
+    pub user_id: Option<String>,
+}
+
+impl OptionsJson {
+    fn new(app: &AppState, options: ChallengeOptions, user_id: Option<String>) -> Self {
+        Self {
+            id: options.id,
+            challenge: options.challenge,
+            rp_id: app.config.server.rp_id().to_owned(),
+            user_id,
+        }
+    }
+}
+
+/// Attestation sent back by the browser after `navigator.credentials.create()`.
+/// Binary fields are base64url encoded.
+#[derive(Deserialize)]
+pub struct RegisterJson {
+    pub challenge_id: String,
+    pub credential_id: String,
+    pub name: String,
+    pub public_key: String,
+    pub client_data_json: String,
+    pub authenticator_data: String,
+}
+
+/// Assertion sent back by the browser after `navigator.credentials.get()`.
+/// Binary fields are base64url encoded.
+#[derive(Deserialize)]
+pub struct LoginJson {
+    pub challenge_id: String,
+    pub credential_id: String,
+    pub user_handle: String,
+    pub client_data_json: String,
+    pub authenticator_data: String,
+    pub signature: String,
+}
+
+/// Decodes the binary fields sent by the browser, failing on the first one
+/// that isn't base64url.
+fn decode<const N: usize>(values: [&str; N]) -> Result<[Vec<u8>; N], base64::DecodeError> {
+    let mut decoded = [const { Vec::new() }; N];
+    for (value, out) in values.into_iter().zip(decoded.iter_mut()) {
+        *out = URL_SAFE_NO_PAD.decode(value)?;
+    }
+
+    Ok(decoded)
+}
+
+#[tracing::instrument(skip_all, fields(user = user.id))]
+pub async fn register_options(
+    template: Template,
+    user: AuthUser,
+    State(app): State<AppState>,
+) -> impl IntoResponse {
+    let options = imkitchen_web_shared::try_response!(
+        app.identity.passkey.start_registration(&user.id),
+        template
+    );
+
+    Json(OptionsJson::new(&app, options, Some(user.id.to_owned()))).into_response()
+}
+
+#[tracing::instrument(skip_all, fields(user = user.id))]
+pub async fn register_action(
+    template: Template,
+    user: AuthUser,
+    State(app): State<AppState>,
+    Json(input): Json<RegisterJson>,
+) -> impl IntoResponse {
+    let Ok([public_key, client_data_json, authenticator_data]) = decode([
+        &input.public_key,
+        &input.client_data_json,
+        &input.authenticator_data,
+    ]) else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+
+    imkitchen_web_shared::try_response!(
+        app.identity.passkey.register(RegisterInput {
+            challenge_id: input.challenge_id,
+            user_id: user.id.to_owned(),
+            credential_id: input.credential_id,
+            name: input.name,
+            public_key,
+            client_data_json,
+            authenticator_data,
+            origin: app.config.server.origin().to_owned(),
+            rp_id: app.config.server.rp_id().to_owned(),
+        }),
+        template
+    );
+
+    StatusCode::NO_CONTENT.into_response()
+}
+
+pub async fn login_options(
+    template: Template,
+    State(app): State<AppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let options = imkitchen_web_shared::try_response!(
+        app.identity
+            .passkey
+            .start_authentication(super::client_ip(&headers)),
+        template
+    );
+
+    Json(OptionsJson::new(&app, options, None)).into_response()
+}
+
+pub async fn login_action(
+    template: Template,
+    State(app): State<AppState>,
+    jar: CookieJar,
+    TypedHeader(user_agent): TypedHeader<UserAgent>,
+    Json(input): Json<LoginJson>,
+) -> impl IntoResponse {
+    let Ok([user_handle, client_data_json, authenticator_data, signature]) = decode([
+        &input.user_handle,
+        &input.client_data_json,
+        &input.authenticator_data,
+        &input.signature,
+    ]) else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+
+    let Ok(user_handle) = String::from_utf8(user_handle) else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+
+    let (user_id, access_id) = imkitchen_web_shared::try_response!(
+        app.identity.passkey.authenticate(AuthenticateInput {
+            challenge_id: input.challenge_id,
+            credential_id: input.credential_id,
+            user_handle,
+            client_data_json,
+            authenticator_data,
+            signature,
+            origin: app.config.server.origin().to_owned(),
+            rp_id: app.config.server.rp_id().to_owned(),
+            lang: template.preferred_language_iso.to_owned(),
+            timezone: template.timezone.to_owned(),
+            user_agent: user_agent.to_string(),
+        }),
+        template
+    );
+
+    let auth_cookie = match build_cookie(app.config.jwt, user_id, access_id) {
+        Ok(cookie) => cookie,
+        Err(e) => {
+            tracing::error!("{e}");
+
+            return template
+                .render(ToastErrorTemplate {
+                    original: None,
+                    message: SERVER_ERROR_MESSAGE,
+                    description: None,
+                    link: None,
+                })
+                .into_response();
+        }
+    };
+
+    (jar.add(auth_cookie), StatusCode::NO_CONTENT).into_response()
+}
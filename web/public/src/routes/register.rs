@@ -43,6 +43,7 @@ pub async fn action(
                 original: None,
                 message: "Passwords don't match. Please make sure both fields are identical.",
                 description: None,
+                link: None,
             }),
         )
             .into_response();
@@ -80,6 +80,7 @@ pub async fn new_action(
                 original: None,
                 message: "Passwords don't match. Please make sure both fields are identical.",
                 description: None,
+                link: None,
             }),
         )
             .into_response();
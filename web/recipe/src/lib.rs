@@ -6,7 +6,11 @@ pub fn routes() -> axum::Router<imkitchen_web_shared::AppState> {
     use axum::routing::{get, post};
     axum::Router::new()
         .route("/recipes", get(routes::index::page))
-        .route("/recipes/create", post(routes::index::create))
+        .route(
+            "/recipes/create",
+            get(routes::index::create_modal).post(routes::index::create),
+        )
+        .route("/recipes/export.json", get(routes::export::json))
         .route("/recipes/share-all", post(routes::index::share_all))
         .route(
             "/recipes/make-all-private",
@@ -16,6 +20,12 @@ pub fn routes() -> axum::Router<imkitchen_web_shared::AppState> {
             "/recipes/import",
             get(routes::import::page).post(routes::import::action),
         )
+        .route("/recipes/import/clip", post(routes::import::clip_action))
+        .route("/recipes/import/force", post(routes::import::force_action))
+        .route(
+            "/recipes/import/mapped",
+            post(routes::import::mapped_action),
+        )
         .route("/recipes/import/{id}/status", get(routes::import::status))
         .route(
             "/recipes/{id}/make-private",
@@ -35,10 +45,27 @@ pub fn routes() -> axum::Router<imkitchen_web_shared::AppState> {
         )
         .route("/recipes/{id}/save", post(routes::detail::save))
         .route("/recipes/{id}/unsave", post(routes::detail::unsave))
+        .route(
+            "/recipes/{id}/plan-eligibility",
+            get(routes::detail::plan_eligibility),
+        )
         .route(
             "/recipes/{id}/add-to-shopping",
             post(routes::detail::add_to_shopping),
         )
+        .route(
+            "/recipes/{id}/comments",
+            get(routes::comment::page).post(routes::comment::add_action),
+        )
+        .route(
+            "/recipes/{id}/comments/{comment_id}/reply",
+            post(routes::comment::reply_action),
+        )
+        .route(
+            "/recipes/{id}/comments/{comment_id}/flag",
+            post(routes::comment::flag_action),
+        )
+        .route("/recipes/{id}/cook-sheet", get(routes::cook_sheet::page))
         .route(
             "/recipes/{id}/edit",
             get(routes::edit::page).post(routes::edit::action),
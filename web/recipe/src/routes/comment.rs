@@ -0,0 +1,162 @@
+use axum::{
+    extract::{Path, Query, State},
+    response::{IntoResponse, Redirect},
+};
+use axum_extra::extract::Form;
+use evento::cursor::{Args, ReadResult, Value};
+use imkitchen_core::recipe::comment::MAX_COMMENT_LENGTH;
+use imkitchen_core::recipe::query::comment::{
+    CommentThread, CommentThreadsQuery, DEFAULT_REPLY_LIMIT,
+};
+use imkitchen_types::comment::FlagReason;
+use serde::Deserialize;
+
+use imkitchen_web_shared::{
+    AppState,
+    auth::AuthUser,
+    template::{NotFoundTemplate, Template, filters},
+};
+
+/// Comment threads of a recipe, lazily loaded by the detail page via
+/// twinspark. The first page also carries the form to add a comment.
+#[derive(askama::Template)]
+#[template(path = "partials/recipes-comments.html")]
+pub struct CommentsTemplate {
+    pub user: AuthUser,
+    pub recipe_id: String,
+    pub threads: ReadResult<CommentThread>,
+    pub is_first_page: bool,
+    pub max_length: usize,
+}
+
+impl CommentsTemplate {
+    pub fn flag_reasons(&self) -> &'static [FlagReason] {
+        <FlagReason as strum::VariantArray>::VARIANTS
+    }
+}
+
+#[derive(Deserialize, Default, Clone)]
+pub struct PageQuery {
+    pub after: Option<Value>,
+}
+
+#[tracing::instrument(skip_all)]
+pub async fn page(
+    template: Template,
+    user: Option<AuthUser>,
+    Path((id,)): Path<(String,)>,
+    Query(query): Query<PageQuery>,
+    State(app): State<AppState>,
+) -> impl IntoResponse {
+    let recipe = imkitchen_web_shared::try_page_response!(opt: app.core.recipe.user(&id), template);
+
+    // Same visibility and demo handling as the detail page.
+    let is_anonymous = user.is_none();
+    let user = user.unwrap_or_else(AuthUser::demo);
+
+    if recipe.owner_id != user.id && !recipe.is_shared {
+        return template.render(NotFoundTemplate).into_response();
+    }
+
+    let template = if is_anonymous {
+        template.demo()
+    } else {
+        template
+    };
+
+    let is_first_page = query.after.is_none();
+    let threads = imkitchen_web_shared::try_page_response!(
+        app.core.recipe.comment_threads(CommentThreadsQuery {
+            recipe_id: recipe.id.to_owned(),
+            reply_limit: DEFAULT_REPLY_LIMIT,
+            args: Args::forward(20, query.after),
+        }),
+        template
+    );
+
+    template
+        .render(CommentsTemplate {
+            user,
+            recipe_id: recipe.id,
+            threads,
+            is_first_page,
+            max_length: MAX_COMMENT_LENGTH,
+        })
+        .into_response()
+}
+
+#[derive(Deserialize)]
+pub struct CommentInput {
+    pub body: String,
+}
+
+#[tracing::instrument(skip_all, fields(user = user.id))]
+pub async fn add_action(
+    template: Template,
+    user: AuthUser,
+    State(app): State<AppState>,
+    Path((id,)): Path<(String,)>,
+    Form(input): Form<CommentInput>,
+) -> impl IntoResponse {
+    let recipe =
+        imkitchen_web_shared::try_response!(anyhow_opt: app.core.recipe.user(&id), template);
+
+    imkitchen_web_shared::try_response!(
+        app.core
+            .recipe
+            .comment
+            .add(&recipe.id, &user.id, input.body),
+        template
+    );
+
+    Redirect::to(&format!("/r/{}#comments", recipe.slug)).into_response()
+}
+
+#[tracing::instrument(skip_all, fields(user = user.id))]
+pub async fn reply_action(
+    template: Template,
+    user: AuthUser,
+    State(app): State<AppState>,
+    Path((id, comment_id)): Path<(String, String)>,
+    Form(input): Form<CommentInput>,
+) -> impl IntoResponse {
+    let recipe =
+        imkitchen_web_shared::try_response!(anyhow_opt: app.core.recipe.user(&id), template);
+
+    imkitchen_web_shared::try_response!(
+        app.core
+            .recipe
+            .comment
+            .reply(&comment_id, &user.id, input.body),
+        template
+    );
+
+    Redirect::to(&format!("/r/{}#comments", recipe.slug)).into_response()
+}
+
+#[derive(Deserialize)]
+pub struct FlagInput {
+    pub reason: FlagReason,
+}
+
+#[tracing::instrument(skip_all, fields(user = user.id))]
+pub async fn flag_action(
+    template: Template,
+    user: AuthUser,
+    State(app): State<AppState>,
+    Path((id, comment_id)): Path<(String, String)>,
+    Form(input): Form<FlagInput>,
+) -> impl IntoResponse {
+    let recipe =
+        imkitchen_web_shared::try_response!(anyhow_opt: app.core.recipe.user(&id), template);
+
+    imkitchen_web_shared::try_response!(
+        app.core
+            .recipe
+            .comment
+            .flag(&comment_id, &user.id, input.reason),
+        template
+    );
+
+    Redirect::to(&format!("/r/{}#comments", recipe.slug)).into_response()
+}
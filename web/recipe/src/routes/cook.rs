@@ -51,6 +51,8 @@ pub struct PageQuery {
     pub before: Option<Value>,
     pub recipe_type: Option<String>,
     pub search: Option<String>,
+    /// Comma separated, only recipes carrying every tag are listed.
+    pub tags: Option<String>,
     pub sort_by: Option<SortBy>,
     pub view: Option<String>,
 }
@@ -115,6 +117,7 @@ pub async fn page(
             has_thumbnail: None,
             dietary_restrictions: vec![],
             dietary_where_any: false,
+            tags: super::split_tags(input.tags.as_deref().unwrap_or_default()),
             in_meal_plan: None,
             sort_by: input.sort_by.unwrap_or_default(),
             args: args.limit(20),
@@ -0,0 +1,74 @@
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+};
+use axum_extra::extract::Query;
+use imkitchen_core::recipe::query::user::UserView;
+use imkitchen_types::recipe::{Ingredient, IngredientUnitFormat};
+use serde::Deserialize;
+
+use imkitchen_web_shared::{
+    AppState,
+    auth::AuthUser,
+    template::{NotFoundTemplate, Template, filters},
+};
+
+#[derive(askama::Template)]
+#[template(path = "recipes-cook-sheet.html")]
+pub struct CookSheetTemplate {
+    pub current_path: String,
+    pub user: AuthUser,
+    pub recipe: UserView,
+    pub servings: u16,
+    /// Scaled ingredients per aisle, keyed by the `shopping_<Category>`
+    /// translation key the shopping list uses.
+    pub aisles: Vec<(String, Vec<Ingredient>)>,
+    pub steps: Vec<(usize, String)>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct CookSheetQuery {
+    pub servings: Option<u16>,
+}
+
+pub async fn page(
+    template: Template,
+    user: AuthUser,
+    Path((id,)): Path<(String,)>,
+    Query(input): Query<CookSheetQuery>,
+    State(app): State<AppState>,
+) -> impl IntoResponse {
+    let recipe = imkitchen_web_shared::try_page_response!(opt: app.core.recipe.user(&id), template);
+
+    if recipe.owner_id != user.id && !recipe.is_shared {
+        return template.render(NotFoundTemplate).into_response();
+    }
+
+    let servings = match input.servings {
+        Some(servings) if servings > 0 => servings,
+        _ => recipe.household_size,
+    };
+    let sheet = recipe.cook_sheet(servings);
+    let aisles = sheet
+        .aisles
+        .into_iter()
+        .map(|(category, ingredients)| {
+            let name = match category {
+                Some(c) => format!("shopping_{c}"),
+                None => "shopping_Unknown".to_owned(),
+            };
+            (name, ingredients)
+        })
+        .collect();
+
+    template
+        .render(CookSheetTemplate {
+            current_path: "recipes".to_owned(),
+            user,
+            recipe,
+            servings: sheet.servings,
+            aisles,
+            steps: sheet.steps,
+        })
+        .into_response()
+}
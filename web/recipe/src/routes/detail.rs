@@ -1,16 +1,21 @@
 use axum::{
+    Json,
     extract::{Path, State},
     response::{IntoResponse, Redirect},
 };
-use evento::cursor::{Args, ReadResult};
+use evento::cursor::{Edge, PageInfo, ReadResult, Value};
+use imkitchen_core::mealplan::Generate;
+use imkitchen_core::mealplan::eligibility::Exclusion;
 use imkitchen_core::recipe::{
     favorite,
     query::{
-        user::{RecipesQuery, SortBy, UserView, UserViewList},
+        user::{UserView, UserViewList},
         user_stat::UserStatView,
     },
 };
-use imkitchen_types::recipe::{DietaryRestriction, IngredientUnitFormat, RecipeType};
+use imkitchen_types::recipe::{
+    DietaryRestriction, IngredientUnitFormat, RecipeType, timeline_minutes,
+};
 use serde_json::json;
 
 use imkitchen_web_shared::{
@@ -59,6 +64,10 @@ pub struct DetailTemplate<'a> {
     /// Pre-serialized schema.org/Recipe JSON-LD for search-engine rich
     /// results. Empty string renders no `<script>` (e.g. in demo mode).
     pub json_ld: String,
+    /// Per step, whether it starts alongside the previous one.
+    pub can_overlap: Vec<bool>,
+    /// Length of the cook-along timeline, overlapping steps included.
+    pub timeline_minutes: u16,
 }
 
 /// Right-rail "Similar recipes" fragment, lazily loaded via twinspark
@@ -115,6 +124,9 @@ pub fn recipe_json_ld(recipe: &UserView, base_url: &str) -> String {
             DietaryRestriction::GlutenFree => Some("https://schema.org/GlutenFreeDiet"),
             DietaryRestriction::DairyFree => Some("https://schema.org/LowLactoseDiet"),
             DietaryRestriction::NutFree => None,
+            DietaryRestriction::Pescatarian => None,
+            DietaryRestriction::Kosher => Some("https://schema.org/KosherDiet"),
+            DietaryRestriction::Halal => Some("https://schema.org/HalalDiet"),
         })
         .collect();
 
@@ -169,6 +181,8 @@ impl<'a> Default for DetailTemplate<'a> {
             owner_description: String::new(),
             in_shopping: false,
             json_ld: String::new(),
+            can_overlap: vec![],
+            timeline_minutes: 0,
         }
     }
 }
@@ -247,6 +261,13 @@ pub async fn page(
             .unwrap_or(false)
     };
 
+    let can_overlap =
+        imkitchen_web_shared::try_page_response!(app.core.recipe.load(&recipe.id), template)
+            .map(|root| root.can_overlap)
+            .unwrap_or_default();
+    let timeline_minutes =
+        u16::try_from(timeline_minutes(&recipe.instructions, &can_overlap)).unwrap_or(u16::MAX);
+
     let username = user.username();
     // Structured data for search engines — only on the canonical public page
     // (signed-in or guest), not the demo tour.
@@ -262,6 +283,8 @@ pub async fn page(
             owner_description: owner_profile.description,
             in_shopping,
             json_ld,
+            can_overlap,
+            timeline_minutes,
             ..Default::default()
         })
         .into_response()
@@ -269,7 +292,7 @@ pub async fn page(
 
 /// Right-rail "Similar recipes" fragment, lazily loaded by the detail page via
 /// twinspark (`ts-trigger="load"`). Kept off the page's critical path because
-/// finding suggestions runs up to three fallback queries.
+/// ranking suggestions compares the recipe against every candidate.
 #[tracing::instrument(skip_all)]
 pub async fn similar(
     template: Template,
@@ -302,84 +325,21 @@ pub async fn similar(
         template
     };
 
-    let exclude_ids = vec![recipe.id.to_owned()];
-
-    let mut similar_recipes = imkitchen_web_shared::try_page_response!(
-        app.core.recipe.filter_user(RecipesQuery {
-            exclude_ids: Some(exclude_ids.to_vec()),
-            user_id: None,
-            recipe_type: Some(recipe.recipe_type.0.to_owned()),
-            is_shared: Some(true),
-            has_thumbnail: None,
-            dietary_restrictions: recipe.dietary_restrictions.0.to_vec(),
-            dietary_where_any: false,
-            in_meal_plan: None,
-            sort_by: SortBy::Random,
-            args: Args::forward(10, None),
-            search: None,
-        }),
+    let similar = imkitchen_web_shared::try_page_response!(
+        app.core.recipe.similar(&recipe.id, &user.id, 10),
         template
     );
 
-    if similar_recipes.edges.len() < 10 {
-        let mut similar_ids = similar_recipes
-            .edges
-            .iter()
-            .map(|n| n.node.id.to_owned())
-            .collect::<Vec<_>>();
-        similar_ids.extend(exclude_ids.to_vec());
-
-        let more_recipes = imkitchen_web_shared::try_page_response!(
-            app.core.recipe.filter_user(RecipesQuery {
-                exclude_ids: Some(similar_ids),
-                user_id: None,
-                recipe_type: Some(recipe.recipe_type.0.to_owned()),
-                is_shared: Some(true),
-                has_thumbnail: None,
-                dietary_restrictions: recipe.dietary_restrictions.0.to_vec(),
-                dietary_where_any: true,
-                in_meal_plan: None,
-                sort_by: SortBy::Random,
-                args: Args::forward(10, None),
-                search: None,
-            }),
-            template
-        );
-
-        similar_recipes.edges.extend(more_recipes.edges);
-    }
-
-    if similar_recipes.edges.len() < 10 {
-        let mut similar_ids = similar_recipes
-            .edges
-            .iter()
-            .map(|n| n.node.id.to_owned())
-            .collect::<Vec<_>>();
-        similar_ids.extend(exclude_ids);
-
-        let more_recipes = imkitchen_web_shared::try_page_response!(
-            app.core.recipe.filter_user(RecipesQuery {
-                exclude_ids: Some(similar_ids),
-                user_id: None,
-                recipe_type: Some(recipe.recipe_type.0.to_owned()),
-                is_shared: Some(true),
-                has_thumbnail: None,
-                dietary_restrictions: vec![],
-                dietary_where_any: false,
-                in_meal_plan: None,
-                sort_by: SortBy::Random,
-                args: Args::forward(10, None),
-                search: None,
-            }),
-            template
-        );
-
-        similar_recipes.edges.extend(more_recipes.edges);
-    }
-
-    // Fallback tiers can overshoot the target; keep at most 10 (exact matches,
-    // which are collected first, take precedence).
-    similar_recipes.edges.truncate(10);
+    let similar_recipes = ReadResult {
+        edges: similar
+            .into_iter()
+            .map(|node| Edge {
+                cursor: Value(node.id.to_owned()),
+                node,
+            })
+            .collect(),
+        page_info: PageInfo::default(),
+    };
 
     template
         .render(SimilarTemplate { similar_recipes })
@@ -500,10 +460,12 @@ pub async fn save(
     }
 
     imkitchen_web_shared::try_response!(
-        app.core
-            .recipe
-            .favorite
-            .save(&id, recipe.owner_id, &user.id),
+        app.core.recipe.favorite.save(
+            &id,
+            recipe.owner_id,
+            &user.id,
+            app.config.favorites.max_for(user.is_premium()),
+        ),
         template
     );
 
@@ -529,6 +491,68 @@ pub async fn unsave(
         .into_response()
 }
 
+/// Why meal plan generation would or wouldn't pick this recipe for the
+/// viewer, under their current meal preferences.
+#[tracing::instrument(skip_all, fields(user = user.id))]
+pub async fn plan_eligibility(
+    template: Template,
+    user: AuthUser,
+    State(app): State<AppState>,
+    Path((id,)): Path<(String,)>,
+) -> impl IntoResponse {
+    let preferences = imkitchen_web_shared::try_response!(anyhow:
+        app.identity.meal_preferences.load(&user.id),
+        template
+    );
+    let constraints =
+        imkitchen_web_shared::try_response!(sync anyhow: preferences.constraints(), template);
+    let eligibility = imkitchen_web_shared::try_response!(anyhow:
+        app.core.mealplan.plan_eligibility(
+            &id,
+            &Generate {
+                user_id: user.id.to_owned(),
+                ..Generate::from(&constraints)
+            }
+        ),
+        template
+    );
+
+    let exclusions = eligibility
+        .exclusions
+        .iter()
+        .map(|exclusion| match exclusion {
+            Exclusion::NotInPool => json!({ "reason": "not_in_pool" }),
+            Exclusion::Untitled => json!({ "reason": "untitled" }),
+            Exclusion::RecipeType(recipe_type) => {
+                json!({ "reason": "recipe_type", "value": recipe_type.to_string() })
+            }
+            Exclusion::DietaryRestriction(restriction) => {
+                json!({ "reason": "dietary_restriction", "value": restriction.to_string() })
+            }
+            Exclusion::HouseholdSize(household_size) => {
+                json!({ "reason": "household_size", "value": household_size })
+            }
+            Exclusion::Equipment(item) => {
+                json!({ "reason": "equipment", "value": item.to_string() })
+            }
+            Exclusion::Complexity(complexity) => {
+                json!({ "reason": "complexity", "value": complexity.to_string() })
+            }
+            Exclusion::TimeBudget(minutes) => {
+                json!({ "reason": "time_budget", "value": minutes })
+            }
+        })
+        .collect::<Vec<_>>();
+
+    Json(json!({
+        "id": id,
+        "eligible": eligibility.is_eligible(),
+        "recipe_type": eligibility.recipe_type.map(|t| t.to_string()),
+        "exclusions": exclusions,
+    }))
+    .into_response()
+}
+
 #[derive(askama::Template)]
 #[template(path = "partials/recipes-detail-add-to-shopping-button.html")]
 pub struct AddToShoppingButtonTemplate {
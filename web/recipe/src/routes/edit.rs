@@ -3,9 +3,17 @@ use axum::{
     response::IntoResponse,
 };
 use axum_extra::extract::Form;
-use imkitchen_core::recipe::UpdateInput;
+use evento::cursor::Args;
+use imkitchen_core::mealplan::slot::DEFAULT_ADVANCE_PREP_HOURS;
+use imkitchen_core::recipe::query::user::{RecipesQuery, SortBy};
+use imkitchen_core::recipe::{
+    AccompanimentTypesInput, AdvancePrepHoursInput, ComplexityInput, CuisineTypeInput,
+    DefaultAccompanimentsInput, EquipmentInput, MAX_ADVANCE_PREP_HOURS, MinHouseholdSizeInput,
+    NutritionInput, TagsInput, UpdateInput,
+};
 use imkitchen_types::recipe::{
-    DietaryRestriction, Ingredient, IngredientCategory, IngredientUnit, Instruction, RecipeType,
+    AccompanimentType, Complexity, CuisineType, DietaryRestriction, Equipment, Ingredient,
+    IngredientCategory, IngredientUnit, Instruction, Nutrition, RecipeType,
 };
 use serde::Deserialize;
 use std::str::FromStr;
@@ -36,17 +44,57 @@ pub struct EditForm {
     pub ingredients_name: Vec<String>,
     #[serde(default)]
     pub ingredients_category: Vec<String>,
+    /// Blank when the ingredient has no nutrition data.
+    #[serde(default)]
+    pub ingredients_calories: Vec<String>,
+    #[serde(default)]
+    pub ingredients_protein: Vec<String>,
+    #[serde(default)]
+    pub ingredients_carbs: Vec<String>,
+    #[serde(default)]
+    pub ingredients_fat: Vec<String>,
+    #[serde(skip)]
+    pub nutrition: Vec<Option<Nutrition>>,
     #[serde(default)]
     pub instructions: Vec<Instruction>,
     #[serde(default)]
     pub instructions_description: Vec<String>,
     #[serde(default)]
     pub instructions_time_next: Vec<u16>,
+    /// Per step, whether it starts alongside the previous one.
+    #[serde(default)]
+    pub instructions_can_overlap: Vec<bool>,
     #[serde(default)]
     pub dietary_restrictions: Vec<DietaryRestriction>,
     #[serde(default)]
     pub accepts_accompaniment: String,
+    /// Blank unless the recipe is served as an accompaniment.
+    #[serde(default)]
+    pub accompaniment_type: String,
+    /// Most preferred first, blanks are skipped.
+    #[serde(default)]
+    pub preferred_accompaniment_types: Vec<String>,
+    /// Accompaniment recipe ids, most preferred first.
+    #[serde(default)]
+    pub default_accompaniment_ids: Vec<String>,
     pub advance_prep: String,
+    /// Blank reminds the day before.
+    #[serde(default)]
+    pub advance_prep_hours: String,
+    /// 0 lets the recipe be planned for any household.
+    #[serde(default)]
+    pub min_household_size: u16,
+    /// Blank lets the planner estimate it.
+    #[serde(default)]
+    pub complexity: String,
+    /// Blank until a cuisine is picked, it can't be cleared afterwards.
+    #[serde(default)]
+    pub cuisine_type: String,
+    #[serde(default)]
+    pub equipment: Vec<Equipment>,
+    /// Comma separated.
+    #[serde(default)]
+    pub tags: String,
 }
 
 #[derive(askama::Template)]
@@ -64,6 +112,20 @@ pub struct EditTemplate {
     pub current_path: String,
     pub user: AuthUser,
     pub form: EditForm,
+    /// Id and name of the accompaniments offered as defaults, the current
+    /// defaults first.
+    pub accompaniments: Vec<(String, String)>,
+}
+
+impl EditTemplate {
+    /// The side picked at `rank` in the preferred accompaniments, blank when none.
+    fn preferred_accompaniment_type(&self, rank: usize) -> &str {
+        self.form
+            .preferred_accompaniment_types
+            .get(rank)
+            .map(String::as_str)
+            .unwrap_or_default()
+    }
 }
 
 impl Default for EditTemplate {
@@ -72,6 +134,7 @@ impl Default for EditTemplate {
             current_path: "recipes".to_owned(),
             user: AuthUser::default(),
             form: EditForm::default(),
+            accompaniments: vec![],
             id: "".to_owned(),
         }
     }
@@ -90,6 +153,41 @@ pub async fn page(
         return template.render(ForbiddenTemplate).into_response();
     }
 
+    let root = imkitchen_web_shared::try_page_response!(opt: app.core.recipe.load(&id), template);
+
+    let mut accompaniments = vec![];
+    for id in root.default_accompaniment_ids.iter() {
+        if let Some(accompaniment) =
+            imkitchen_web_shared::try_page_response!(app.core.recipe.user(id), template)
+        {
+            accompaniments.push((accompaniment.id, accompaniment.name));
+        }
+    }
+
+    let own_accompaniments = imkitchen_web_shared::try_page_response!(
+        app.core.recipe.filter_user(RecipesQuery {
+            exclude_ids: None,
+            user_id: Some(user.id.to_owned()),
+            recipe_type: Some(RecipeType::Accompaniment),
+            is_shared: None,
+            has_thumbnail: None,
+            dietary_restrictions: vec![],
+            dietary_where_any: false,
+            tags: vec![],
+            in_meal_plan: None,
+            sort_by: SortBy::default(),
+            args: Args::forward(50, None),
+            search: None,
+        }),
+        template
+    );
+
+    for edge in own_accompaniments.edges {
+        if !accompaniments.iter().any(|(id, _)| id == &edge.node.id) {
+            accompaniments.push((edge.node.id, edge.node.name));
+        }
+    }
+
     let accepts_accompaniment = if recipe.accepts_accompaniment {
         "on"
     } else {
@@ -111,14 +209,46 @@ pub async fn page(
                 instructions: recipe.instructions.0,
                 dietary_restrictions: recipe.dietary_restrictions.0,
                 accepts_accompaniment: accepts_accompaniment.to_owned(),
+                accompaniment_type: root
+                    .accompaniment_type
+                    .map(|accompaniment_type| accompaniment_type.to_string())
+                    .unwrap_or_default(),
+                preferred_accompaniment_types: root
+                    .preferred_accompaniment_types
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect(),
+                default_accompaniment_ids: root.default_accompaniment_ids,
                 advance_prep: recipe.advance_prep,
+                advance_prep_hours: root
+                    .advance_prep_hours
+                    .map(|hours| hours.to_string())
+                    .unwrap_or_default(),
                 ingredients_unit: vec![],
                 ingredients_name: vec![],
                 ingredients_quantity: vec![],
                 ingredients_category: vec![],
+                ingredients_calories: vec![],
+                ingredients_protein: vec![],
+                ingredients_carbs: vec![],
+                ingredients_fat: vec![],
+                nutrition: root.nutrition,
                 instructions_description: vec![],
                 instructions_time_next: vec![],
+                instructions_can_overlap: root.can_overlap,
+                min_household_size: root.min_household_size,
+                complexity: root
+                    .complexity
+                    .map(|complexity| complexity.to_string())
+                    .unwrap_or_default(),
+                cuisine_type: root
+                    .cuisine_type
+                    .map(|cuisine_type| cuisine_type.to_string())
+                    .unwrap_or_default(),
+                equipment: root.equipment,
+                tags: root.tags.join(", "),
             },
+            accompaniments,
             id,
             ..Default::default()
         })
@@ -136,20 +266,26 @@ pub async fn action(
     if input.ingredients_name.len() != input.ingredients_quantity.len()
         || input.ingredients_name.len() != input.ingredients_unit.len()
         || input.ingredients_name.len() != input.ingredients_category.len()
+        || input.ingredients_name.len() != input.ingredients_calories.len()
+        || input.ingredients_name.len() != input.ingredients_protein.len()
+        || input.ingredients_name.len() != input.ingredients_carbs.len()
+        || input.ingredients_name.len() != input.ingredients_fat.len()
     {
         imkitchen_web_shared::try_response!(sync:
             Err(imkitchen_core::Error::User(
-                "ingredients_name, ingredients_quantity, ingredients_unit and ingredients_category size not matched"
+                "ingredients_name, ingredients_quantity, ingredients_unit, ingredients_category and nutrition size not matched"
                     .to_owned()
             )),
             template
         );
     }
 
-    if input.instructions_description.len() != input.instructions_time_next.len() {
+    if input.instructions_description.len() != input.instructions_time_next.len()
+        || input.instructions_description.len() != input.instructions_can_overlap.len()
+    {
         imkitchen_web_shared::try_response!(sync:
             Err(imkitchen_core::Error::User(
-                "instructions_description and instructions_time_next size not matched"
+                "instructions_description, instructions_time_next and instructions_can_overlap size not matched"
                     .to_owned()
             )),
             template
@@ -157,6 +293,7 @@ pub async fn action(
     }
 
     let mut ingredients = vec![];
+    let mut nutrition = vec![];
     for (pos, name) in input.ingredients_name.iter().skip(2).enumerate() {
         ingredients.push(Ingredient {
             name: name.to_owned(),
@@ -164,6 +301,21 @@ pub async fn action(
             category: IngredientCategory::from_str(&input.ingredients_category[pos + 2]).ok(),
             quantity: input.ingredients_quantity[pos + 2].to_owned(),
         });
+
+        let values = [
+            &input.ingredients_calories[pos + 2],
+            &input.ingredients_protein[pos + 2],
+            &input.ingredients_carbs[pos + 2],
+            &input.ingredients_fat[pos + 2],
+        ]
+        .map(|value| value.trim().parse::<u32>().ok());
+
+        nutrition.push(values.iter().any(Option::is_some).then(|| Nutrition {
+            calories: values[0].unwrap_or_default(),
+            protein: values[1].unwrap_or_default(),
+            carbs: values[2].unwrap_or_default(),
+            fat: values[3].unwrap_or_default(),
+        }));
     }
 
     let mut instructions = vec![];
@@ -202,6 +354,121 @@ pub async fn action(
         template
     );
 
+    imkitchen_web_shared::try_response!(
+        app.core.recipe.set_nutrition(
+            NutritionInput {
+                id: id.to_owned(),
+                nutrition,
+            },
+            &user.id
+        ),
+        template
+    );
+
+    imkitchen_web_shared::try_response!(
+        app.core.recipe.set_instruction_overlaps(
+            &id,
+            input.instructions_can_overlap.into_iter().skip(2).collect(),
+            &user.id
+        ),
+        template
+    );
+
+    imkitchen_web_shared::try_response!(
+        app.core.recipe.set_min_household_size(
+            MinHouseholdSizeInput {
+                id: id.to_owned(),
+                min_household_size: input.min_household_size,
+            },
+            &user.id
+        ),
+        template
+    );
+
+    imkitchen_web_shared::try_response!(
+        app.core.recipe.set_complexity(
+            ComplexityInput {
+                id: id.to_owned(),
+                complexity: Complexity::from_str(&input.complexity).ok(),
+            },
+            &user.id
+        ),
+        template
+    );
+
+    if let Ok(cuisine_type) = CuisineType::from_str(&input.cuisine_type) {
+        imkitchen_web_shared::try_response!(
+            app.core.recipe.set_cuisine_type(
+                CuisineTypeInput {
+                    id: id.to_owned(),
+                    cuisine_type,
+                },
+                &user.id
+            ),
+            template
+        );
+    }
+
+    imkitchen_web_shared::try_response!(
+        app.core.recipe.set_equipment(
+            EquipmentInput {
+                id: id.to_owned(),
+                equipment: input.equipment,
+            },
+            &user.id
+        ),
+        template
+    );
+
+    imkitchen_web_shared::try_response!(
+        app.core.recipe.set_accompaniment_types(
+            AccompanimentTypesInput {
+                id: id.to_owned(),
+                accompaniment_type: AccompanimentType::from_str(&input.accompaniment_type).ok(),
+                preferred_accompaniment_types: input
+                    .preferred_accompaniment_types
+                    .iter()
+                    .filter_map(|value| AccompanimentType::from_str(value).ok())
+                    .collect(),
+            },
+            &user.id
+        ),
+        template
+    );
+
+    imkitchen_web_shared::try_response!(
+        app.core.recipe.set_default_accompaniments(
+            DefaultAccompanimentsInput {
+                id: id.to_owned(),
+                accompaniment_ids: input.default_accompaniment_ids,
+            },
+            &user.id
+        ),
+        template
+    );
+
+    imkitchen_web_shared::try_response!(
+        app.core.recipe.set_advance_prep_hours(
+            AdvancePrepHoursInput {
+                id: id.to_owned(),
+                advance_prep_hours: input.advance_prep_hours.trim().parse().ok(),
+            },
+            &user.id
+        ),
+        template
+    );
+
+    imkitchen_web_shared::try_response!(
+        app.core.recipe.set_tags(
+            TagsInput {
+                id: id.to_owned(),
+                tags: super::split_tags(&input.tags),
+            },
+            &user.id
+        ),
+        template
+    );
+
     template
         .render(ToastSuccessTemplate {
             original: None,
@@ -0,0 +1,78 @@
+use axum::{body::Body, extract::State, http::header, response::IntoResponse};
+use futures::StreamExt;
+use imkitchen_core::recipe::query::export::EXPORT_PAGE_SIZE;
+
+use imkitchen_web_shared::{AppState, auth::AuthUser};
+
+use super::import::ImportJson;
+
+struct ExportCursor {
+    after: Option<String>,
+    is_first: bool,
+}
+
+/// Streams the user's recipes as a JSON array `POST /recipes/import`
+/// accepts, one page at a time.
+#[tracing::instrument(skip_all, fields(user = user.id))]
+pub async fn json(State(app): State<AppState>, user: AuthUser) -> impl IntoResponse {
+    let recipe = app.core.recipe.clone();
+    let user_id = user.id.to_owned();
+
+    let cursor = ExportCursor {
+        after: None,
+        is_first: true,
+    };
+
+    let pages = futures::stream::unfold(Some(cursor), move |cursor| {
+        let recipe = recipe.clone();
+        let user_id = user_id.clone();
+
+        async move {
+            let mut cursor = cursor?;
+            let recipes = match recipe.export_page(&user_id, cursor.after.take()).await {
+                Ok(recipes) => recipes,
+                Err(err) => {
+                    tracing::error!(user = user_id, err = %err, "failed to export recipes");
+
+                    return Some((Err(std::io::Error::other(err.to_string())), None));
+                }
+            };
+
+            let is_last = recipes.len() < EXPORT_PAGE_SIZE as usize;
+            let mut chunk = String::new();
+            for (id, input) in recipes {
+                let json = match serde_json::to_string(&ImportJson::from(input)) {
+                    Ok(json) => json,
+                    Err(err) => return Some((Err(std::io::Error::other(err)), None)),
+                };
+
+                if !cursor.is_first {
+                    chunk.push(',');
+                }
+
+                chunk.push_str(&json);
+                cursor.is_first = false;
+                cursor.after = Some(id);
+            }
+
+            if is_last {
+                chunk.push(']');
+            }
+
+            Some((Ok(chunk), (!is_last).then_some(cursor)))
+        }
+    });
+
+    let body = futures::stream::once(async { Ok("[".to_owned()) }).chain(pages);
+
+    (
+        [
+            (header::CONTENT_TYPE, "application/json"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"recipes.json\"",
+            ),
+        ],
+        Body::from_stream(body),
+    )
+}
@@ -4,8 +4,9 @@ use axum::{
     http::StatusCode,
     response::IntoResponse,
 };
+use axum_extra::extract::Form;
 use imkitchen_types::recipe::{DietaryRestriction, Ingredient, Instruction, RecipeType};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use imkitchen_web_shared::{
     AppState,
@@ -17,9 +18,14 @@ use imkitchen_web_shared::{
 pub struct ErrorRecipe {
     pub name: String,
     pub error: String,
+    /// The user's recipe this one looks like, see
+    /// [`imkitchen_core::Error::PossibleDuplicate`].
+    pub duplicate_of: Option<String>,
+    /// The recipe JSON with `force` set, to import it anyway.
+    pub force_json: Option<String>,
 }
 
-#[derive(Deserialize, Default, Clone)]
+#[derive(Deserialize, Serialize, Default, Clone)]
 pub struct ImportJson {
     pub recipe_type: RecipeType,
     pub name: String,
@@ -35,6 +41,31 @@ pub struct ImportJson {
     pub accepts_accompaniment: bool,
 }
 
+impl From<imkitchen_core::recipe::ImportInput> for ImportJson {
+    fn from(value: imkitchen_core::recipe::ImportInput) -> Self {
+        Self {
+            recipe_type: value.recipe_type,
+            name: value.name,
+            origin: value.origin,
+            description: value.description,
+            household_size: value.household_size,
+            prep_time: value.prep_time,
+            cook_time: value.cook_time,
+            ingredients: value.ingredients,
+            instructions: value.instructions,
+            advance_prep: (!value.advance_prep.is_empty()).then_some(value.advance_prep),
+            dietary_restrictions: value.dietary_restrictions,
+            accepts_accompaniment: value.accepts_accompaniment,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct MappedImportJson {
+    pub csv: String,
+    pub mapping: imkitchen_core::recipe::ColumnMapping,
+}
+
 #[derive(askama::Template)]
 #[template(path = "partials/recipes-importing-status.html")]
 pub struct ImportingStatusTemplate {
@@ -46,6 +77,8 @@ pub struct ImportingStatusTemplate {
 pub struct ImportingTemplate {
     pub id: Option<String>,
     pub error_recipes: Vec<ErrorRecipe>,
+    /// What had to be guessed or left out of a clipped recipe.
+    pub warnings: Vec<String>,
 }
 
 #[derive(askama::Template)]
@@ -71,59 +104,211 @@ pub async fn page(template: Template, user: AuthUser) -> impl IntoResponse {
     })
 }
 
+/// Imports one recipe in the interchange format documented on
+/// [`imkitchen_core::recipe::parse_recipe_json`].
+async fn import_one(
+    app: &AppState,
+    user: &AuthUser,
+    recipe: serde_json::Value,
+) -> Result<String, ErrorRecipe> {
+    let name = recipe
+        .get("name")
+        .and_then(serde_json::Value::as_str)
+        .unwrap_or_default()
+        .to_owned();
+
+    match app
+        .core
+        .recipe
+        .import_json(&recipe.to_string(), &user.id, user.username.to_owned())
+        .await
+    {
+        Ok(recipe_id) => Ok(recipe_id),
+        Err(imkitchen_core::Error::PossibleDuplicate(existing_id)) => {
+            let mut force = recipe;
+            if let Some(object) = force.as_object_mut() {
+                object.insert("force".to_owned(), true.into());
+            }
+
+            Err(ErrorRecipe {
+                name,
+                error: imkitchen_core::Error::PossibleDuplicate(existing_id.to_owned()).to_string(),
+                duplicate_of: Some(existing_id),
+                force_json: Some(force.to_string()),
+            })
+        }
+        Err(imkitchen_core::Error::Server(err)) => {
+            tracing::error!(user = user.id, err = %err,"failed to import recipes");
+
+            Err(ErrorRecipe {
+                name,
+                error: SERVER_ERROR_MESSAGE.to_string(),
+                ..Default::default()
+            })
+        }
+        Err(error) => Err(ErrorRecipe {
+            name,
+            error: error.to_string(),
+            ..Default::default()
+        }),
+    }
+}
+
+/// Imports a batch of recipes. Each recipe is read on its own, so one bad
+/// field only rejects that recipe, naming the field.
 pub async fn action(
     template: Template,
     State(app): State<AppState>,
     RequirePremium(user): RequirePremium,
-    Json(recipes): Json<Vec<ImportJson>>,
+    Json(recipes): Json<Vec<serde_json::Value>>,
 ) -> impl IntoResponse {
     let mut id = None;
     let mut error_recipes = vec![];
 
     for recipe in recipes {
-        match app
-            .core
-            .recipe
-            .import(
-                imkitchen_core::recipe::ImportInput {
-                    recipe_type: recipe.recipe_type,
-                    name: recipe.name.to_owned(),
-                    origin: recipe.origin.to_owned(),
-                    description: recipe.description,
-                    household_size: recipe.household_size,
-                    prep_time: recipe.prep_time,
-                    cook_time: recipe.cook_time,
-                    ingredients: recipe.ingredients,
-                    instructions: recipe.instructions,
-                    advance_prep: recipe.advance_prep.unwrap_or_default(),
-                    accepts_accompaniment: recipe.accepts_accompaniment,
-                    dietary_restrictions: recipe.dietary_restrictions,
-                },
-                &user.id,
-                user.username.to_owned(),
-            )
-            .await
-        {
-            Ok(recipe_id) => {
-                id = Some(recipe_id);
-            }
-            Err(imkitchen_core::Error::Server(err)) => {
-                tracing::error!(user = user.id, err = %err,"failed to import recipes");
+        match import_one(&app, &user, recipe).await {
+            Ok(recipe_id) => id = Some(recipe_id),
+            Err(error) => error_recipes.push(error),
+        }
+    }
 
-                error_recipes.push(ErrorRecipe {
-                    name: recipe.name,
-                    error: SERVER_ERROR_MESSAGE.to_string(),
-                });
-            }
-            Err(error) => {
-                error_recipes.push(ErrorRecipe {
-                    name: recipe.name,
-                    error: error.to_string(),
-                });
+    template.render(ImportingTemplate {
+        id,
+        error_recipes,
+        warnings: vec![],
+    })
+}
+
+#[derive(Deserialize)]
+pub struct ForceInput {
+    /// See [`ErrorRecipe::force_json`].
+    pub recipe: String,
+}
+
+/// Imports a recipe flagged as a possible duplicate anyway.
+pub async fn force_action(
+    template: Template,
+    State(app): State<AppState>,
+    RequirePremium(user): RequirePremium,
+    Form(input): Form<ForceInput>,
+) -> impl IntoResponse {
+    let recipe = imkitchen_web_shared::try_response!(sync anyhow:
+        serde_json::from_str::<serde_json::Value>(&input.recipe),
+        template
+    );
+
+    let (id, error_recipes) = match import_one(&app, &user, recipe).await {
+        Ok(id) => (Some(id), vec![]),
+        Err(error) => (None, vec![error]),
+    };
+
+    template
+        .render(ImportingTemplate {
+            id,
+            error_recipes,
+            warnings: vec![],
+        })
+        .into_response()
+}
+
+#[derive(Deserialize)]
+pub struct ClipInput {
+    /// A recipe page's HTML or its schema.org JSON-LD.
+    pub source: String,
+}
+
+pub async fn clip_action(
+    template: Template,
+    State(app): State<AppState>,
+    RequirePremium(user): RequirePremium,
+    Form(input): Form<ClipInput>,
+) -> impl IntoResponse {
+    let (id, error_recipes, warnings) = match app
+        .core
+        .recipe
+        .import_schema_org(&input.source, &user.id, user.username.to_owned())
+        .await
+    {
+        Ok((id, warnings)) => (Some(id), vec![], warnings),
+        Err(imkitchen_core::Error::Server(err)) => {
+            tracing::error!(user = user.id, err = %err, "failed to clip recipe");
+
+            let error = ErrorRecipe {
+                error: SERVER_ERROR_MESSAGE.to_string(),
+                ..Default::default()
+            };
+
+            (None, vec![error], vec![])
+        }
+        Err(imkitchen_core::Error::PossibleDuplicate(existing_id)) => {
+            let error = ErrorRecipe {
+                error: imkitchen_core::Error::PossibleDuplicate(existing_id.to_owned()).to_string(),
+                duplicate_of: Some(existing_id),
+                ..Default::default()
+            };
+
+            (None, vec![error], vec![])
+        }
+        Err(error) => {
+            let error = ErrorRecipe {
+                error: error.to_string(),
+                ..Default::default()
+            };
+
+            (None, vec![error], vec![])
+        }
+    };
+
+    template.render(ImportingTemplate {
+        id,
+        error_recipes,
+        warnings,
+    })
+}
+
+pub async fn mapped_action(
+    template: Template,
+    State(app): State<AppState>,
+    RequirePremium(user): RequirePremium,
+    Json(input): Json<MappedImportJson>,
+) -> impl IntoResponse {
+    let imported = imkitchen_web_shared::try_response!(
+        app.core.recipe.import_mapped(
+            &input.csv,
+            &input.mapping,
+            &user.id,
+            user.username.to_owned()
+        ),
+        template
+    );
+
+    let error_recipes = imported
+        .errors
+        .into_iter()
+        .map(|row| {
+            let error = match row.error {
+                imkitchen_core::Error::Server(err) => {
+                    tracing::error!(user = user.id, row = row.row, err = %err, "failed to import mapped recipe");
+                    SERVER_ERROR_MESSAGE.to_string()
+                }
+                error => error.to_string(),
+            };
+
+            ErrorRecipe {
+                name: format!("#{} {}", row.row, row.name),
+                error,
+                ..Default::default()
             }
-        };
-    }
-    template.render(ImportingTemplate { id, error_recipes })
+        })
+        .collect();
+
+    template
+        .render(ImportingTemplate {
+            id: imported.ids.last().cloned(),
+            error_recipes,
+            warnings: vec![],
+        })
+        .into_response()
 }
 
 pub async fn status(
@@ -2,7 +2,7 @@ use axum::{
     extract::State,
     response::{IntoResponse, Redirect},
 };
-use axum_extra::extract::Query;
+use axum_extra::extract::{Form, Query};
 use evento::cursor::{Args, ReadResult, Value};
 use imkitchen_core::recipe::query::user::{RecipesQuery, SortBy, UserViewList};
 use imkitchen_types::recipe::RecipeType;
@@ -48,6 +48,8 @@ pub struct PageQuery {
     pub before: Option<Value>,
     pub recipe_type: Option<String>,
     pub search: Option<String>,
+    /// Comma separated, only recipes carrying every tag are listed.
+    pub tags: Option<String>,
     pub sort_by: Option<SortBy>,
     pub in_meal_plan: Option<bool>,
     pub mine: Option<bool>,
@@ -121,6 +123,7 @@ pub async fn page(
             has_thumbnail,
             dietary_restrictions,
             dietary_where_any: false,
+            tags: super::split_tags(input.tags.as_deref().unwrap_or_default()),
             in_meal_plan: in_meal_plan_filter,
             sort_by: input.sort_by.unwrap_or_default(),
             args: args.limit(20),
@@ -144,17 +147,33 @@ pub async fn page(
         .into_response()
 }
 
+#[derive(askama::Template)]
+#[template(path = "partials/recipes-create-modal.html")]
+pub struct CreateModalTemplate;
+
+pub async fn create_modal(template: Template) -> impl IntoResponse {
+    template.render(CreateModalTemplate)
+}
+
+#[derive(Deserialize)]
+pub struct CreateInput {
+    pub recipe_type: RecipeType,
+}
+
 #[tracing::instrument(skip_all, fields(user = user.id))]
 pub async fn create(
     template: Template,
     RequirePremium(user): RequirePremium,
     State(app): State<AppState>,
+    Form(input): Form<CreateInput>,
 ) -> impl IntoResponse {
     let id = match imkitchen_web_shared::try_response!(anyhow: app.core.recipe.find_user_draft(&user.id), template)
     {
         Some(id) => id,
         _ => imkitchen_web_shared::try_response!(
-            app.core.recipe.create(&user.id, user.username.to_owned()),
+            app.core
+                .recipe
+                .create(&user.id, user.username.to_owned(), input.recipe_type),
             template
         ),
     };
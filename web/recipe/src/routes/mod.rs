@@ -1,6 +1,17 @@
+pub mod comment;
 pub mod cook;
+pub mod cook_sheet;
 pub mod detail;
 pub mod edit;
+pub mod export;
 pub mod import;
 pub mod index;
 pub mod thumbnail;
+
+/// Tags typed as a comma separated list, in the form `set_tags` stores them.
+pub(crate) fn split_tags(tags: &str) -> Vec<String> {
+    tags.split(',')
+        .map(|tag| tag.trim().to_lowercase())
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
@@ -35,5 +35,30 @@ pub fn routes() -> axum::Router<imkitchen_web_shared::AppState> {
             "/settings/account",
             get(routes::account::page).post(routes::account::action),
         )
+        .route("/profile/security", get(routes::account::login_history))
+        .route(
+            "/profile/meal-preferences/preview",
+            post(routes::general::preview_action),
+        )
+        .route(
+            "/profile/notifications",
+            post(routes::general::update_notifications_action),
+        )
+        .route(
+            "/profile/notifications/test",
+            post(routes::general::send_test_notifications_action),
+        )
+        .route(
+            "/profile/notifications/quiet-hours",
+            post(routes::general::quiet_hours_action),
+        )
+        .route(
+            "/profile/notifications/push",
+            post(routes::push::subscribe_action),
+        )
+        .route(
+            "/profile/notifications/push/unsubscribe",
+            post(routes::push::unsubscribe_action),
+        )
         .route("/invoices/{id}", get(routes::invoices::detail::page))
 }
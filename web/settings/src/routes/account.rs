@@ -1,3 +1,4 @@
+use axum::Json;
 use axum::extract::State;
 use axum::response::IntoResponse;
 
@@ -7,6 +8,7 @@ use imkitchen_web_shared::auth::AuthUser;
 use imkitchen_web_shared::template::Template;
 use imkitchen_web_shared::template::ToastSuccessTemplate;
 use imkitchen_web_shared::template::filters;
+use serde::Serialize;
 
 #[derive(askama::Template)]
 #[template(path = "settings-account.html")]
@@ -46,3 +48,36 @@ pub async fn action(
         })
         .into_response()
 }
+
+#[derive(Serialize)]
+pub struct LoginHistoryJson {
+    pub user_agent: String,
+    pub logged_in_at: u64,
+    pub logged_out_at: Option<u64>,
+    pub active: bool,
+}
+
+/// Recent sign-ins for the security section, newest first.
+#[tracing::instrument(skip_all, fields(user = user.id))]
+pub async fn login_history(
+    template: Template,
+    State(app): State<AppState>,
+    user: AuthUser,
+) -> impl IntoResponse {
+    let rows = imkitchen_web_shared::try_response!(anyhow:
+        app.identity.login_history(&user.id, 20),
+        template
+    );
+
+    Json(
+        rows.into_iter()
+            .map(|row| LoginHistoryJson {
+                active: row.is_active(),
+                user_agent: row.user_agent,
+                logged_in_at: row.logged_in_at,
+                logged_out_at: row.logged_out_at,
+            })
+            .collect::<Vec<_>>(),
+    )
+    .into_response()
+}
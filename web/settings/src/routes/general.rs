@@ -1,10 +1,21 @@
+use std::collections::HashMap;
+
+use axum::Json;
 use axum::extract::State;
 use axum::response::IntoResponse;
 use axum_extra::extract::Form;
-use imkitchen_identity::meal_preferences::UpdateInput;
+use imkitchen_identity::meal_preferences::{
+    MAX_COMMUNITY_SUGGESTIONS, MAX_EQUIPMENT_CAPACITY, MAX_MIN_DAYS_SINCE_USE, MAX_TIME_BUDGET,
+    UpdateInput,
+};
 use imkitchen_identity::user_profile;
-use imkitchen_types::recipe::DietaryRestriction;
-use serde::Deserialize;
+use imkitchen_types::meal_preferences::UserConstraints;
+use imkitchen_types::notification_preferences::{NotificationKind, QuietHours};
+use imkitchen_types::recipe::{
+    Complexity, DietaryRestriction, Equipment, IngredientCategory, RecipeType,
+};
+use imkitchen_types::shopping::RoundingStrategy;
+use serde::{Deserialize, Serialize};
 use strum::VariantArray;
 
 use imkitchen_web_shared::AppState;
@@ -19,8 +30,13 @@ pub struct MealPreferencesTemplate {
     pub household_size: u16,
     pub dietary_restrictions: Vec<DietaryRestriction>,
     pub cuisine_variety_weight: f32,
+    pub rounding_strategy: RoundingStrategy,
+    pub aisle_order: Vec<IngredientCategory>,
+    pub constraints: UserConstraints,
     pub email: String,
     pub description: String,
+    pub notifications_enabled: Vec<NotificationKind>,
+    pub quiet_hours: Option<QuietHours>,
     pub user: AuthUser,
 }
 
@@ -32,13 +48,37 @@ impl Default for MealPreferencesTemplate {
             household_size: 4,
             dietary_restrictions: Vec::default(),
             cuisine_variety_weight: 1.0,
+            rounding_strategy: RoundingStrategy::default(),
+            aisle_order: IngredientCategory::aisle_order(&[]),
+            constraints: UserConstraints::default(),
             email: String::new(),
             description: String::new(),
+            notifications_enabled: NotificationKind::VARIANTS.to_vec(),
+            quiet_hours: None,
             user: AuthUser::default(),
         }
     }
 }
 
+impl MealPreferencesTemplate {
+    fn aisle_label(&self, category: &IngredientCategory) -> String {
+        format!("shopping_{category}")
+    }
+
+    fn equipment_capacity(&self, item: &Equipment) -> u8 {
+        self.constraints
+            .equipment_capacity
+            .get(item)
+            .copied()
+            .unwrap_or_else(|| item.default_capacity())
+    }
+
+    /// `HH:MM` of a minute after midnight, as a time input expects it.
+    fn time_of_day(&self, minute: u16) -> String {
+        format!("{:02}:{:02}", minute / 60, minute % 60)
+    }
+}
+
 #[tracing::instrument(skip_all, fields(user = user.id))]
 pub async fn page(
     template: Template,
@@ -50,20 +90,41 @@ pub async fn page(
         template
     );
 
+    let constraints =
+        imkitchen_web_shared::try_page_response!(sync: preferences.constraints(), template);
+
     let profile = imkitchen_web_shared::try_page_response!(
         app.identity.user_profile.load(&user.id),
         template
     );
 
+    let shopping =
+        imkitchen_web_shared::try_page_response!(app.core.shopping.load(&user.id), template);
+
     let email =
         imkitchen_web_shared::try_page_response!(app.identity.find_email(&user.id), template);
 
+    let notification_preferences = imkitchen_web_shared::try_page_response!(
+        app.identity.notification_preferences.load(&user.id),
+        template
+    );
+
     template.render(MealPreferencesTemplate {
         household_size: preferences.household_size,
         dietary_restrictions: preferences.dietary_restrictions.to_vec(),
         cuisine_variety_weight: preferences.cuisine_variety_weight,
+        rounding_strategy: shopping
+            .as_ref()
+            .map(|s| s.rounding_strategy)
+            .unwrap_or_default(),
+        aisle_order: shopping
+            .map(|s| s.aisle_order())
+            .unwrap_or_else(|| IngredientCategory::aisle_order(&[])),
+        constraints,
         email: email.unwrap_or_default(),
         description: profile.description,
+        notifications_enabled: notification_preferences.enabled,
+        quiet_hours: notification_preferences.quiet_hours,
         user,
         ..Default::default()
     })
@@ -75,6 +136,24 @@ pub struct ActionInput {
     #[serde(default)]
     pub dietary_restrictions: Vec<DietaryRestriction>,
     pub cuisine_variety_weight: f32,
+    #[serde(default)]
+    pub rounding_strategy: RoundingStrategy,
+    #[serde(default)]
+    pub aisle_order: Vec<IngredientCategory>,
+    pub max_weekday_complexity: Complexity,
+    pub max_weekend_complexity: Complexity,
+    #[serde(default)]
+    pub skipped_courses: Vec<RecipeType>,
+    #[serde(default)]
+    pub equipment: Vec<Equipment>,
+    #[serde(default)]
+    pub equipment_capacity: Vec<u8>,
+    /// Minutes per day, Monday first. Blank leaves the day unlimited.
+    #[serde(default)]
+    pub time_budget: Vec<String>,
+    #[serde(default)]
+    pub community_suggestions: u8,
+    pub min_days_since_use: u16,
 }
 
 #[tracing::instrument(skip_all, fields(user = user.id))]
@@ -96,6 +175,96 @@ pub async fn action(
         template
     );
 
+    imkitchen_web_shared::try_response!(
+        app.identity.meal_preferences.set_max_complexity(
+            &user.id,
+            input.max_weekday_complexity,
+            input.max_weekend_complexity
+        ),
+        template
+    );
+
+    imkitchen_web_shared::try_response!(
+        app.identity
+            .meal_preferences
+            .set_skipped_courses(&user.id, input.skipped_courses),
+        template
+    );
+
+    let equipment_capacity = input
+        .equipment
+        .into_iter()
+        .zip(input.equipment_capacity)
+        .collect::<HashMap<_, _>>();
+
+    imkitchen_web_shared::try_response!(
+        app.identity
+            .meal_preferences
+            .set_equipment_capacity(&user.id, equipment_capacity),
+        template
+    );
+
+    let mut time_budget = [None; 7];
+    if input.time_budget.len() != time_budget.len() {
+        imkitchen_web_shared::try_response!(sync:
+            Err(imkitchen_core::Error::User(
+                "time_budget must have one entry per day".to_owned()
+            )),
+            template
+        );
+    }
+
+    for (day, minutes) in input.time_budget.iter().enumerate() {
+        let minutes = minutes.trim();
+        if minutes.is_empty() {
+            continue;
+        }
+
+        time_budget[day] = Some(imkitchen_web_shared::try_response!(sync:
+            minutes
+                .parse::<u16>()
+                .map_err(|_| imkitchen_core::Error::User(format!(
+                    "Time budget must be between 1 and {MAX_TIME_BUDGET} minutes"
+                ))),
+            template
+        ));
+    }
+
+    imkitchen_web_shared::try_response!(
+        app.identity
+            .meal_preferences
+            .set_time_budget(&user.id, time_budget),
+        template
+    );
+
+    imkitchen_web_shared::try_response!(
+        app.identity
+            .meal_preferences
+            .set_community_suggestions(&user.id, input.community_suggestions),
+        template
+    );
+
+    imkitchen_web_shared::try_response!(
+        app.identity
+            .meal_preferences
+            .set_min_days_since_use(&user.id, input.min_days_since_use),
+        template
+    );
+
+    imkitchen_web_shared::try_response!(
+        app.core
+            .shopping
+            .set_rounding_strategy(input.rounding_strategy, &user.id),
+        template
+    );
+
+    imkitchen_web_shared::try_response!(
+        app.core
+            .shopping
+            .set_aisle_order(input.aisle_order, &user.id),
+        template
+    );
+
     template
         .render(ToastSuccessTemplate {
             original: None,
@@ -105,6 +274,47 @@ pub async fn action(
         .into_response()
 }
 
+#[derive(Deserialize, Debug)]
+pub struct PreviewInput {
+    #[serde(default)]
+    pub dietary_restrictions: Vec<DietaryRestriction>,
+}
+
+#[derive(Serialize)]
+pub struct PreviewJson {
+    pub recipe_type: String,
+    pub remaining: u32,
+    pub excluded: u32,
+}
+
+/// Counts, per course, the recipes that would stay eligible for meal plans
+/// under the submitted restrictions. Nothing is saved.
+#[tracing::instrument(skip_all, fields(user = user.id))]
+pub async fn preview_action(
+    template: Template,
+    State(app): State<AppState>,
+    user: AuthUser,
+    Form(input): Form<PreviewInput>,
+) -> impl IntoResponse {
+    let rows = imkitchen_web_shared::try_response!(anyhow:
+        app.core
+            .mealplan
+            .dietary_preview(&user.id, &input.dietary_restrictions),
+        template
+    );
+
+    Json(
+        rows.into_iter()
+            .map(|row| PreviewJson {
+                recipe_type: row.recipe_type.to_string(),
+                remaining: row.remaining,
+                excluded: row.excluded,
+            })
+            .collect::<Vec<_>>(),
+    )
+    .into_response()
+}
+
 #[derive(Deserialize)]
 pub struct SetUsernameActionInput {
     pub username: String,
@@ -123,6 +333,7 @@ pub async fn set_username_action(
                 original: None,
                 message: "Username has already been set.",
                 description: None,
+                link: None,
             }),
         )
             .into_response();
@@ -166,3 +377,110 @@ pub async fn update_profile_action(
         })
         .into_response()
 }
+
+#[derive(Deserialize)]
+pub struct NotificationsActionInput {
+    #[serde(default)]
+    pub enabled: Vec<NotificationKind>,
+}
+
+#[tracing::instrument(skip_all, fields(user = user.id))]
+pub async fn update_notifications_action(
+    template: Template,
+    State(app): State<AppState>,
+    user: AuthUser,
+    Form(input): Form<NotificationsActionInput>,
+) -> impl IntoResponse {
+    imkitchen_web_shared::try_response!(
+        app.identity.notification_preferences.update(
+            &user.id,
+            imkitchen_identity::notification_preferences::UpdateInput {
+                enabled: input.enabled
+            }
+        ),
+        template
+    );
+
+    template
+        .render(ToastSuccessTemplate {
+            original: None,
+            message: "Notification preferences updated",
+            description: None,
+        })
+        .into_response()
+}
+
+#[tracing::instrument(skip_all, fields(user = user.id))]
+pub async fn send_test_notifications_action(
+    template: Template,
+    State(app): State<AppState>,
+    user: AuthUser,
+) -> impl IntoResponse {
+    imkitchen_web_shared::try_response!(
+        app.identity.notification_preferences.send_test(&user.id),
+        template
+    );
+
+    template
+        .render(ToastSuccessTemplate {
+            original: None,
+            message: "Test notifications sent",
+            description: None,
+        })
+        .into_response()
+}
+
+#[derive(Deserialize)]
+pub struct QuietHoursActionInput {
+    /// `HH:MM`, blank with `end` turns quiet hours off.
+    #[serde(default)]
+    pub start: String,
+    #[serde(default)]
+    pub end: String,
+}
+
+#[tracing::instrument(skip_all, fields(user = user.id))]
+pub async fn quiet_hours_action(
+    template: Template,
+    State(app): State<AppState>,
+    user: AuthUser,
+    Form(input): Form<QuietHoursActionInput>,
+) -> impl IntoResponse {
+    let quiet_hours = if input.start.trim().is_empty() && input.end.trim().is_empty() {
+        None
+    } else {
+        Some(QuietHours {
+            start: imkitchen_web_shared::try_response!(sync: parse_time_of_day(&input.start), template),
+            end: imkitchen_web_shared::try_response!(sync: parse_time_of_day(&input.end), template),
+        })
+    };
+
+    imkitchen_web_shared::try_response!(
+        app.identity
+            .notification_preferences
+            .set_quiet_hours(&user.id, quiet_hours),
+        template
+    );
+
+    template
+        .render(ToastSuccessTemplate {
+            original: None,
+            message: "Quiet hours updated",
+            description: None,
+        })
+        .into_response()
+}
+
+/// Minutes after midnight of a `HH:MM` time.
+fn parse_time_of_day(value: &str) -> imkitchen_core::Result<u16> {
+    value
+        .trim()
+        .split_once(':')
+        .and_then(|(hours, minutes)| {
+            let hours = hours.parse::<u16>().ok()?;
+            let minutes = minutes.parse::<u16>().ok()?;
+
+            (hours < 24 && minutes < 60).then_some(hours * 60 + minutes)
+        })
+        .ok_or_else(|| imkitchen_core::Error::User("Quiet hours must be a time of day".to_owned()))
+}
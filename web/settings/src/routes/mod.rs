@@ -2,3 +2,4 @@ pub mod account;
 pub mod billing;
 pub mod general;
 pub mod invoices;
+pub mod push;
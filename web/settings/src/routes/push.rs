@@ -0,0 +1,91 @@
+use axum::Json;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use imkitchen_identity::push_subscription::SubscribeInput;
+use serde::Deserialize;
+
+use imkitchen_web_shared::AppState;
+use imkitchen_web_shared::auth::AuthUser;
+use imkitchen_web_shared::template::Template;
+
+/// Keys of the browser's `PushSubscription.toJSON()`.
+#[derive(Deserialize)]
+pub struct SubscriptionKeysJson {
+    pub p256dh: String,
+    pub auth: String,
+}
+
+/// Body of `PushSubscription.toJSON()` posted after `pushManager.subscribe()`.
+#[derive(Deserialize)]
+pub struct SubscribeJson {
+    pub endpoint: String,
+    pub keys: SubscriptionKeysJson,
+}
+
+#[derive(Deserialize)]
+pub struct UnsubscribeJson {
+    pub endpoint: String,
+}
+
+#[tracing::instrument(skip_all, fields(user = user.id))]
+pub async fn subscribe_action(
+    template: Template,
+    State(app): State<AppState>,
+    user: AuthUser,
+    Json(input): Json<SubscribeJson>,
+) -> impl IntoResponse {
+    let module = &app.identity.push_subscription;
+    let subscriptions = imkitchen_web_shared::try_response!(anyhow:
+        imkitchen_notification::push::subscriptions(module, &user.id),
+        template
+    );
+
+    // The browser hands out the same endpoint until it is unsubscribed.
+    if subscriptions
+        .iter()
+        .any(|subscription| subscription.endpoint == input.endpoint)
+    {
+        return StatusCode::NO_CONTENT.into_response();
+    }
+
+    imkitchen_web_shared::try_response!(
+        module.subscribe(
+            &user.id,
+            SubscribeInput {
+                endpoint: input.endpoint,
+                p256dh: input.keys.p256dh,
+                auth: input.keys.auth,
+            }
+        ),
+        template
+    );
+
+    StatusCode::NO_CONTENT.into_response()
+}
+
+#[tracing::instrument(skip_all, fields(user = user.id))]
+pub async fn unsubscribe_action(
+    template: Template,
+    State(app): State<AppState>,
+    user: AuthUser,
+    Json(input): Json<UnsubscribeJson>,
+) -> impl IntoResponse {
+    let module = &app.identity.push_subscription;
+    let subscriptions = imkitchen_web_shared::try_response!(anyhow:
+        imkitchen_notification::push::subscriptions(module, &user.id),
+        template
+    );
+
+    for subscription in subscriptions
+        .into_iter()
+        .filter(|subscription| subscription.endpoint == input.endpoint)
+    {
+        imkitchen_web_shared::try_response!(
+            module.unsubscribe(&subscription.id, &user.id),
+            template
+        );
+    }
+
+    StatusCode::NO_CONTENT.into_response()
+}
@@ -1,5 +1,8 @@
+use std::collections::HashMap;
+
 use config::{Config as ConfigBuilder, ConfigError, Environment, File};
 use imkitchen_notification::EmailConfig;
+use imkitchen_notification::web_push::PushConfig;
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize, Clone)]
@@ -9,9 +12,80 @@ pub struct Config {
     pub jwt: JwtConfig,
     pub root: RootConfig,
     pub email: EmailConfig,
+    #[serde(default)]
+    pub push: PushConfig,
     pub stripe: StripeConfig,
     pub premium: Option<PremiumConfig>,
     pub monitoring: MonitoringConfig,
+    #[serde(default)]
+    pub features: FeaturesConfig,
+    pub favorites: FavoritesConfig,
+    pub mealplan: MealPlanConfig,
+    pub password: PasswordConfig,
+    pub maintenance: MaintenanceConfig,
+}
+
+/// Upper bound on how many recipes a user can keep as favorites.
+#[derive(Debug, Deserialize, Clone)]
+pub struct FavoritesConfig {
+    pub max: u32,
+    pub max_premium: u32,
+}
+
+impl FavoritesConfig {
+    pub fn max_for(&self, is_premium: bool) -> u32 {
+        if is_premium {
+            self.max_premium
+        } else {
+            self.max
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct MealPlanConfig {
+    /// Events replayed on top of a meal plan's last snapshot before a new one
+    /// is stored.
+    pub snapshot_threshold: u32,
+}
+
+/// Argon2id cost of new password hashes. Raise it on hardware that can
+/// afford it; hashes made with older parameters keep verifying.
+#[derive(Debug, Deserialize, Clone)]
+pub struct PasswordConfig {
+    /// Memory cost in KiB.
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+/// Starts the server in maintenance mode: only admins get past the 503
+/// page. Admins can also toggle it at runtime.
+#[derive(Debug, Deserialize, Clone)]
+pub struct MaintenanceConfig {
+    pub enabled: bool,
+}
+
+/// Feature flags for gradual rollout, keyed by flag name. Each flag lists the
+/// user ids it is enabled for; `"*"` enables it for everyone.
+///
+/// ```toml
+/// [features]
+/// leftover_planning = ["01JCXYZ...", "01JCABC..."]
+/// ```
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(transparent)]
+pub struct FeaturesConfig(HashMap<String, Vec<String>>);
+
+/// Plans leftovers from big batches for users who turned it on.
+pub const LEFTOVER_PLANNING: &str = "leftover_planning";
+
+impl FeaturesConfig {
+    pub fn is_enabled(&self, user_id: &str, flag: &str) -> bool {
+        self.0
+            .get(flag)
+            .is_some_and(|users| users.iter().any(|u| u == "*" || u == user_id))
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -48,6 +122,23 @@ pub struct ServerConfig {
     pub region: Option<String>,
 }
 
+impl ServerConfig {
+    /// Origin the browser reports in WebAuthn client data, e.g. `https://imkitchen.app`.
+    pub fn origin(&self) -> &str {
+        self.url.trim_end_matches('/')
+    }
+
+    /// WebAuthn relying party id: the host part of the server url.
+    pub fn rp_id(&self) -> &str {
+        let host = self
+            .origin()
+            .split_once("://")
+            .map_or(self.origin(), |(_, h)| h);
+
+        host.split([':', '/']).next().unwrap_or(host)
+    }
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct DatabaseConfig {
     pub url: String,
@@ -75,6 +166,11 @@ pub struct StripeConfig {
 }
 
 impl Config {
+    /// Whether `flag` is rolled out to `user_id`. Unknown flags are disabled.
+    pub fn is_feature_enabled(&self, user_id: &str, flag: &str) -> bool {
+        self.features.is_enabled(user_id, flag)
+    }
+
     /// Load configuration from file and environment variables
     ///
     /// Priority (highest to lowest):
@@ -98,6 +194,13 @@ impl Config {
             .set_default("premium.monthly_price", 499)?
             .set_default("premium.annual_rate", 20)?
             .set_default("premium.tax", true)?
+            .set_default("favorites.max", 50)?
+            .set_default("favorites.max_premium", 500)?
+            .set_default("mealplan.snapshot_threshold", 20)?
+            .set_default("password.memory_kib", 19456)?
+            .set_default("password.iterations", 2)?
+            .set_default("password.parallelism", 1)?
+            .set_default("maintenance.enabled", false)?
             .set_default(
                 "monitoring.log_level",
                 "debug,sqlx=info,tower_http=info,stripe=debug,reqwest=debug,hyper_util=info",
@@ -119,3 +222,39 @@ impl Config {
             .try_deserialize()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::FeaturesConfig;
+    use config::{Config as ConfigBuilder, File, FileFormat};
+
+    fn features(toml: &str) -> FeaturesConfig {
+        ConfigBuilder::builder()
+            .add_source(File::from_str(toml, FileFormat::Toml))
+            .build()
+            .unwrap()
+            .try_deserialize()
+            .unwrap()
+    }
+
+    #[test]
+    fn enabled_only_for_listed_users() {
+        let features = features(r#"leftover_planning = ["john"]"#);
+
+        assert!(features.is_enabled("john", "leftover_planning"));
+        assert!(!features.is_enabled("albert", "leftover_planning"));
+        assert!(!features.is_enabled("john", "unknown"));
+    }
+
+    #[test]
+    fn wildcard_enables_for_everyone() {
+        let features = features(r#"leftover_planning = ["*"]"#);
+
+        assert!(features.is_enabled("albert", "leftover_planning"));
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        assert!(!FeaturesConfig::default().is_enabled("john", "leftover_planning"));
+    }
+}
@@ -0,0 +1,68 @@
+//! Date labels in the user's language. Weeks start on Monday whatever the
+//! language; only the names change.
+
+use time::{OffsetDateTime, Weekday};
+
+/// The days of a calendar week, in display order.
+pub const WEEK: [Weekday; 7] = [
+    Weekday::Monday,
+    Weekday::Tuesday,
+    Weekday::Wednesday,
+    Weekday::Thursday,
+    Weekday::Friday,
+    Weekday::Saturday,
+    Weekday::Sunday,
+];
+
+/// "Monday" / "Lundi".
+pub fn weekday(weekday: Weekday, lang: &str) -> String {
+    rust_i18n::t!(weekday.to_string(), locale = lang).to_string()
+}
+
+/// "Mon" / "Lun", for calendar headers and week strips.
+pub fn weekday_short(weekday: Weekday, lang: &str) -> String {
+    let key = weekday.to_string().chars().take(3).collect::<String>();
+
+    rust_i18n::t!(key, locale = lang).to_string()
+}
+
+/// "Monday, 3" / "Lundi 3".
+pub fn day(date: OffsetDateTime, lang: &str) -> String {
+    rust_i18n::t!(
+        "day_format",
+        locale = lang,
+        weekday = weekday(date.weekday(), lang),
+        day = date.day()
+    )
+    .to_string()
+}
+
+/// "Monday, March 3, 2025" / "Lundi 3 Mars 2025".
+pub fn date_year(date: OffsetDateTime, lang: &str) -> String {
+    rust_i18n::t!(
+        "date_year_format",
+        locale = lang,
+        month = month(date, lang),
+        weekday = weekday(date.weekday(), lang),
+        day = date.day(),
+        year = date.year()
+    )
+    .to_string()
+}
+
+/// "March 3, 2025" / "3 Mars 2025".
+pub fn day_month_year(date: OffsetDateTime, lang: &str) -> String {
+    rust_i18n::t!(
+        "day_month_year_format",
+        locale = lang,
+        month = month(date, lang),
+        day = date.day(),
+        year = date.year()
+    )
+    .to_string()
+}
+
+/// "March" / "Mars".
+pub fn month(date: OffsetDateTime, lang: &str) -> String {
+    rust_i18n::t!(format!("{}", date.month()), locale = lang).to_string()
+}
@@ -0,0 +1,96 @@
+use axum::{
+    body::Body,
+    extract::{FromRequestParts, State},
+    http::{Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::{
+    AppState,
+    auth::AuthUser,
+    template::{Template, filters},
+};
+
+#[derive(askama::Template)]
+#[template(path = "503.html")]
+pub struct MaintenanceTemplate;
+
+/// Reachable by anyone during maintenance: probes, assets, and what admins
+/// need to sign in and work.
+const ALWAYS_AVAILABLE: [&str; 9] = [
+    "/health",
+    "/ready",
+    "/static",
+    "/login",
+    "/logout",
+    "/passkey",
+    "/sw.js",
+    "/manifest.json",
+    "/admin",
+];
+
+/// Whether a request for `path` goes through while maintenance mode is on.
+pub fn is_allowed(path: &str, is_admin: bool) -> bool {
+    is_admin
+        || ALWAYS_AVAILABLE.iter().any(|prefix| {
+            path.strip_prefix(prefix)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        })
+}
+
+/// Answers 503 with the maintenance page to everyone but admins while
+/// [`crate::state::Maintenance`] is on.
+pub async fn maintenance_middleware(
+    State(app): State<AppState>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    if !app.maintenance.is_enabled() || is_allowed(req.uri().path(), false) {
+        return next.run(req).await;
+    }
+
+    let (mut parts, body) = req.into_parts();
+    let is_admin = AuthUser::from_request_parts(&mut parts, &app)
+        .await
+        .is_ok_and(|user| user.is_admin());
+
+    if is_allowed(parts.uri.path(), is_admin) {
+        return next.run(Request::from_parts(parts, body)).await;
+    }
+
+    let template = Template::from_request_parts(&mut parts, &app)
+        .await
+        .expect("Infallible");
+
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        template.render(MaintenanceTemplate),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_allowed;
+
+    #[test]
+    fn regular_users_are_locked_out() {
+        assert!(!is_allowed("/", false));
+        assert!(!is_allowed("/recipes", false));
+        assert!(!is_allowed("/menu/2026-10-16", false));
+        assert!(!is_allowed("/administrator", false));
+    }
+
+    #[test]
+    fn admins_and_probes_stay_available() {
+        assert!(is_allowed("/", true));
+        assert!(is_allowed("/recipes", true));
+        assert!(is_allowed("/admin/users", false));
+        assert!(is_allowed("/admin/maintenance", false));
+        assert!(is_allowed("/health", false));
+        assert!(is_allowed("/ready", false));
+        assert!(is_allowed("/login", false));
+        assert!(is_allowed("/static/css/app.css", false));
+    }
+}
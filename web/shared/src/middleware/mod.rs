@@ -1,5 +1,7 @@
 pub mod cache;
+pub mod maintenance;
 pub mod minify;
 
 pub use cache::cache_control_middleware;
+pub use maintenance::maintenance_middleware;
 pub use minify::minify_html_middleware;
@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::ops::Deref;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 
 use evento::Evento;
@@ -13,6 +14,7 @@ pub struct AppState {
     pub billing: imkitchen_billing::Billing<Evento>,
     pub core: imkitchen_core::Core<Evento>,
     pub import_jobs: AdminImportJobs,
+    pub maintenance: Maintenance,
 }
 
 impl Deref for AppState {
@@ -49,3 +51,22 @@ pub struct AdminImportProgress {
 
 /// In-memory registry of running/completed import jobs, keyed by job id.
 pub type AdminImportJobs = Arc<Mutex<HashMap<String, AdminImportProgress>>>;
+
+/// Runtime maintenance switch, seeded from `maintenance.enabled` and flipped
+/// by admins without a restart. Shared by every clone of the state.
+#[derive(Clone, Default)]
+pub struct Maintenance(Arc<AtomicBool>);
+
+impl Maintenance {
+    pub fn new(enabled: bool) -> Self {
+        Self(Arc::new(AtomicBool::new(enabled)))
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    pub fn set(&self, enabled: bool) {
+        self.0.store(enabled, Ordering::Relaxed);
+    }
+}
@@ -29,6 +29,20 @@ pub mod filters {
         Ok(rust_i18n::t!(value, locale = preferred_language).to_string())
     }
 
+    /// Translates a key holding a `[count]`, e.g.
+    /// `{{ "[count] days ago"|t_count(days) }}`.
+    #[askama::filter_fn]
+    pub fn t_count(
+        value: &str,
+        values: &dyn askama::Values,
+        count: &u16,
+    ) -> askama::Result<String> {
+        let preferred_language = askama::get_value::<String>(values, "preferred_language")
+            .expect("Unable to get preferred_language from askama::get_value");
+
+        Ok(rust_i18n::t!(value, locale = preferred_language, count = count).to_string())
+    }
+
     #[askama::filter_fn]
     pub fn initials(value: &str, _values: &dyn askama::Values) -> askama::Result<String> {
         let parts: Vec<&str> = value.split(['_', ' ']).collect();
@@ -104,15 +118,7 @@ pub mod filters {
         let date = OffsetDateTime::from_unix_timestamp(*value as i64)
             .map_err(|e| askama::Error::Custom(Box::new(e)))?;
 
-        let weekday = rust_i18n::t!(date.weekday().to_string(), locale = preferred_language);
-
-        Ok(rust_i18n::t!(
-            "day_format",
-            locale = preferred_language,
-            weekday = weekday,
-            day = date.day()
-        )
-        .to_string())
+        Ok(crate::date::day(date, preferred_language))
     }
 
     #[askama::filter_fn]
@@ -123,18 +129,7 @@ pub mod filters {
         let date = OffsetDateTime::from_unix_timestamp(*value as i64)
             .map_err(|e| askama::Error::Custom(Box::new(e)))?;
 
-        let month = rust_i18n::t!(format!("{}", date.month()), locale = preferred_language);
-        let weekday = rust_i18n::t!(date.weekday().to_string(), locale = preferred_language);
-
-        Ok(rust_i18n::t!(
-            "date_year_format",
-            locale = preferred_language,
-            month = month,
-            weekday = weekday,
-            day = date.day(),
-            year = date.year()
-        )
-        .to_string())
+        Ok(crate::date::date_year(date, preferred_language))
     }
 
     #[askama::filter_fn]
@@ -145,16 +140,18 @@ pub mod filters {
         let date = OffsetDateTime::from_unix_timestamp(*value as i64)
             .map_err(|e| askama::Error::Custom(Box::new(e)))?;
 
-        let month = rust_i18n::t!(format!("{}", date.month()), locale = preferred_language);
+        Ok(crate::date::day_month_year(date, preferred_language))
+    }
+
+    #[askama::filter_fn]
+    pub fn weekday_short(
+        value: &time::Weekday,
+        values: &dyn askama::Values,
+    ) -> askama::Result<String> {
+        let preferred_language = askama::get_value::<String>(values, "preferred_language")
+            .expect("Unable to get preferred_language from askama::get_value");
 
-        Ok(rust_i18n::t!(
-            "day_month_year_format",
-            locale = preferred_language,
-            month = month,
-            day = date.day(),
-            year = date.year()
-        )
-        .to_string())
+        Ok(crate::date::weekday_short(*value, preferred_language))
     }
 
     #[askama::filter_fn]
@@ -168,13 +165,13 @@ pub mod filters {
         let date_b = OffsetDateTime::from_unix_timestamp(*b as i64)
             .map_err(|e| askama::Error::Custom(Box::new(e)))?;
 
-        let month_a = rust_i18n::t!(format!("{}", date_a.month()), locale = preferred_language);
+        let month_a = crate::date::month(date_a, preferred_language);
 
         if date_a.month() == date_b.month() {
             return Ok(format!("{month_a} {}", date_a.year()));
         }
 
-        let month_b = rust_i18n::t!(format!("{}", date_b.month()), locale = preferred_language);
+        let month_b = crate::date::month(date_b, preferred_language);
 
         Ok(format!(
             "{month_a} {} - {month_b} {}",
@@ -517,12 +514,18 @@ pub struct ToastErrorTemplate<'a> {
     pub original: Option<&'a str>,
     pub message: &'a str,
     pub description: Option<&'a str>,
+    /// `(href, label)` of a link to where the error can be fixed.
+    pub link: Option<(&'a str, &'a str)>,
 }
 
 #[macro_export]
 macro_rules! try_response {
     // Internal helper for rendering error responses
     (@render $template:expr, $fallback:expr, $message:expr) => {
+        $crate::try_response!(@render $template, $fallback, $message, None)
+    };
+
+    (@render $template:expr, $fallback:expr, $message:expr, $link:expr) => {
         match $fallback {
             Some(t) => {
                 return $template
@@ -530,6 +533,7 @@ macro_rules! try_response {
                         original: Some(&$template.to_string(t)),
                         message: $message,
                         description: None,
+                        link: $link,
                     })
                     .into_response();
             }
@@ -540,6 +544,7 @@ macro_rules! try_response {
                         original: None,
                         message: $message,
                         description: None,
+                        link: $link,
                     }),
                 )
                     .into_response();
@@ -618,6 +623,9 @@ macro_rules! try_response {
             Err(imkitchen_core::Error::Forbidden(_)) => {
                 $crate::try_response!(@render $template, $fallback, $crate::template::FORBIDDEN)
             }
+            Err(err @ imkitchen_core::Error::NoFavorites) => {
+                $crate::try_response!(@render $template, $fallback, err.to_string().as_str(), Some(("/recipes", "Browse recipes")))
+            }
             Err(err) => {
                 $crate::try_response!(@render $template, $fallback, err.to_string().as_str())
             }
@@ -638,6 +646,9 @@ macro_rules! try_response {
             Err(imkitchen_core::Error::Forbidden(_)) => {
                 $crate::try_response!(@render $template, $fallback, $crate::template::FORBIDDEN)
             }
+            Err(err @ imkitchen_core::Error::NoFavorites) => {
+                $crate::try_response!(@render $template, $fallback, err.to_string().as_str(), Some(("/recipes", "Browse recipes")))
+            }
             Err(err) => {
                 $crate::try_response!(@render $template, $fallback, err.to_string().as_str())
             }
@@ -0,0 +1,33 @@
+use imkitchen_web_shared::date;
+use time::{OffsetDateTime, Weekday};
+
+// Monday 3 March 2025, 12:00 UTC.
+const MONDAY: i64 = 1_741_003_200;
+
+#[test]
+fn test_weekday_labels_follow_language() {
+    let date = OffsetDateTime::from_unix_timestamp(MONDAY).unwrap();
+    assert_eq!(date.weekday(), Weekday::Monday);
+
+    assert_eq!(date::weekday(date.weekday(), "en"), "Monday");
+    assert_eq!(date::weekday(date.weekday(), "fr"), "Lundi");
+    assert_eq!(date::weekday_short(date.weekday(), "en"), "Mon");
+    assert_eq!(date::weekday_short(date.weekday(), "fr"), "Lun");
+    assert_eq!(date::weekday_short(Weekday::Sunday, "fr"), "Dim");
+
+    assert!(date::day(date, "fr").contains("Lundi"));
+    assert!(date::date_year(date, "fr").contains("Mars"));
+    assert!(date::day_month_year(date, "en").contains("March"));
+}
+
+#[test]
+fn test_week_starts_on_monday() {
+    assert_eq!(date::WEEK[0], Weekday::Monday);
+    assert_eq!(date::WEEK[6], Weekday::Sunday);
+
+    let fr = date::WEEK
+        .iter()
+        .map(|d| date::weekday_short(*d, "fr"))
+        .collect::<Vec<_>>();
+    assert_eq!(fr, ["Lun", "Mar", "Mer", "Jeu", "Ven", "Sam", "Dim"]);
+}
@@ -0,0 +1,130 @@
+use std::str::FromStr;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header},
+    middleware::from_fn_with_state,
+    routing::get,
+};
+use evento::migrator::{Migrate, Plan};
+use imkitchen_identity::{LoginInput, RegisterInput};
+use imkitchen_web_shared::{
+    AppState, Maintenance, auth::build_cookie, config::Config,
+    middleware::maintenance::maintenance_middleware,
+};
+use sqlx::{SqlitePool, sqlite::SqliteConnectOptions};
+use temp_dir::TempDir;
+use tower::ServiceExt;
+
+const USER_AGENT: &str = "imkitchen-test";
+
+async fn setup_app_state(dir: &TempDir) -> anyhow::Result<AppState> {
+    let path = dir.child("db.sqlite3");
+    let opts = SqliteConnectOptions::from_str(&format!("sqlite:{}", path.to_str().unwrap()))?
+        .create_if_missing(true);
+    let pool = SqlitePool::connect_with(opts).await?;
+    let mut conn = pool.acquire().await?;
+    imkitchen_db::migrator::<sqlx::Sqlite>()?
+        .run(&mut conn, &Plan::apply_all())
+        .await?;
+
+    let rw: evento::sql::RwSqlite = (
+        evento::Sqlite::from(pool.clone()),
+        evento::Sqlite::from(pool.clone()),
+    )
+        .into();
+    let state = imkitchen_core::State {
+        executor: evento::Evento::new(rw),
+        read_db: pool.clone(),
+        write_db: pool,
+    };
+
+    Ok(AppState {
+        config: Config::load(None)?,
+        stripe: stripe::ClientBuilder::new("sk_test").build()?,
+        identity: imkitchen_identity::Module::new(state.clone()),
+        billing: imkitchen_billing::Billing::new(state.clone()),
+        core: imkitchen_core::Core::new(state.clone()),
+        import_jobs: Default::default(),
+        maintenance: Maintenance::new(true),
+        inner: state,
+    })
+}
+
+/// Registers and logs in `name`, returning the auth cookie to send.
+async fn login(app: &AppState, name: &str, admin: bool) -> anyhow::Result<String> {
+    let email = format!("{name}@imkitchen.localhost");
+    let id = app
+        .identity
+        .register(RegisterInput {
+            email: email.to_owned(),
+            password: "my_password".to_owned(),
+            lang: "en".to_owned(),
+            timezone: "UTC".to_owned(),
+        })
+        .await?;
+
+    if admin {
+        app.identity.made_admin(&id).await?;
+    }
+
+    let (user_id, access_id) = app
+        .identity
+        .login(LoginInput {
+            email,
+            password: "my_password".to_owned(),
+            lang: "en".to_owned(),
+            timezone: "UTC".to_owned(),
+            user_agent: USER_AGENT.to_owned(),
+        })
+        .await?;
+
+    let cookie = build_cookie(app.config.jwt.clone(), user_id, access_id)?;
+
+    Ok(cookie.stripped().to_string())
+}
+
+async fn status(app: &AppState, path: &str, cookie: Option<&str>) -> anyhow::Result<StatusCode> {
+    let router = Router::new()
+        .route("/", get(|| async { "ok" }))
+        .route("/admin/users", get(|| async { "ok" }))
+        .layer(from_fn_with_state(app.clone(), maintenance_middleware))
+        .with_state(app.clone());
+
+    let mut req = Request::get(path).header(header::USER_AGENT, USER_AGENT);
+    if let Some(cookie) = cookie {
+        req = req.header(header::COOKIE, cookie);
+    }
+
+    Ok(router.oneshot(req.body(Body::empty())?).await?.status())
+}
+
+#[tokio::test]
+async fn test_maintenance_locks_out_everyone_but_admins() -> anyhow::Result<()> {
+    let dir = TempDir::new()?;
+    let app = setup_app_state(&dir).await?;
+    let john = login(&app, "john", false).await?;
+    let admin = login(&app, "albert", true).await?;
+
+    assert_eq!(
+        status(&app, "/", None).await?,
+        StatusCode::SERVICE_UNAVAILABLE
+    );
+    assert_eq!(
+        status(&app, "/", Some(&john)).await?,
+        StatusCode::SERVICE_UNAVAILABLE
+    );
+    assert_eq!(status(&app, "/", Some(&admin)).await?, StatusCode::OK);
+
+    // Admin routes stay reachable so admins can sign in and turn it off.
+    assert_eq!(
+        status(&app, "/admin/users", Some(&admin)).await?,
+        StatusCode::OK
+    );
+
+    app.maintenance.set(false);
+    assert_eq!(status(&app, "/", Some(&john)).await?, StatusCode::OK);
+
+    Ok(())
+}